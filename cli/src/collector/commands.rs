@@ -54,6 +54,24 @@ pub(crate) enum CommandArgs {
     },
     /// Get systeminfo
     Systeminfo {},
+    /// Parse Chromium based browsers (Chrome, Edge, Brave, Chromium, Vivaldi, Opera)
+    Chromium {
+        /// Alternative profile directory to use
+        #[arg(long, default_value = None)]
+        alt_path: Option<String>,
+        /// Records to collect. Can provide comma separated list. Default is all
+        #[arg(long, default_value = None, value_delimiter = ',')]
+        records: Option<Vec<String>>,
+    },
+    /// Parse Firefox
+    Firefox {
+        /// Alternative profile directory to use
+        #[arg(long, default_value = None)]
+        alt_path: Option<String>,
+        /// Records to collect. Can provide comma separated list. Default is all
+        #[arg(long, default_value = None, value_delimiter = ',')]
+        records: Option<Vec<String>>,
+    },
 
     /// windows: Parse Prefetch
     Prefetch {
@@ -72,6 +90,9 @@ pub(crate) enum CommandArgs {
         /// Alternative full path to template file. Can create one using `--dump_templates`
         #[arg(long, default_value = None)]
         alt_template_file: Option<String>,
+        /// Mounted or acquired Windows image used to build templates. Combine with `--dump_templates` to create a template file
+        #[arg(long, default_value = None)]
+        alt_template_image: Option<String>,
         /// Attempt to include template strings in the output. Only works on Windows
         #[arg(long)]
         include_templates: bool,
@@ -81,6 +102,36 @@ pub(crate) enum CommandArgs {
         /// Only output EventLog templates. Only works on Windows
         #[arg(long)]
         only_templates: bool,
+        /// Directory containing Sigma rules to evaluate against EventLog records
+        #[arg(long, default_value = None)]
+        sigma_dir: Option<String>,
+        /// Only include Event IDs or Event ID ranges. Ex: 4624,4672,4720-4738
+        #[arg(long, default_value = None, value_delimiter = ',')]
+        event_ids: Option<Vec<String>>,
+        /// Only include records from these providers
+        #[arg(long, default_value = None, value_delimiter = ',')]
+        providers: Option<Vec<String>>,
+        /// Only include records from these channels. Ex: Security
+        #[arg(long, default_value = None, value_delimiter = ',')]
+        channels: Option<Vec<String>>,
+        /// Only include records with these levels. Ex: 1,2,3
+        #[arg(long, default_value = None, value_delimiter = ',')]
+        levels: Option<Vec<u8>>,
+        /// Only include records with a record ID greater than or equal to the start record
+        #[arg(long, default_value = None)]
+        start_record: Option<u64>,
+        /// Only include records with a record ID less than or equal to the end record
+        #[arg(long, default_value = None)]
+        end_record: Option<u64>,
+        /// Include records after the start date. Format needs to be ISO 8601. Ex: YYYY-MM-ddTHH:mm:ss.000Z
+        #[arg(long, default_value = None)]
+        start_date: Option<String>,
+        /// Include records before the end date. Format needs to be ISO 8601. Ex: YYYY-MM-ddTHH:mm:ss.000Z
+        #[arg(long, default_value = None)]
+        end_date: Option<String>,
+        /// Carve EventLog records from the alternative file or from unallocated space on the system drive
+        #[arg(long)]
+        carve: bool,
     },
     /// windows: Parse NTFS to get filelisting
     Rawfilelisting {
@@ -135,6 +186,12 @@ pub(crate) enum CommandArgs {
         /// Regex to only include entries that match path
         #[arg(long, default_value = None)]
         path_regex: Option<String>,
+        /// Recover deleted keys and values from unallocated cells
+        #[arg(long)]
+        recover_deleted: bool,
+        /// Include deleted values that cannot be associated with a key. Ignored if path_regex is set
+        #[arg(long)]
+        recover_orphans: bool,
     },
     /// windows: Parse Userassist
     Userassist {
@@ -183,6 +240,48 @@ pub(crate) enum CommandArgs {
         /// Alternative path to MFT
         #[arg(long, default_value = None)]
         alt_mft: Option<String>,
+        /// Carve UsnJrnl records from unallocated space and slack space
+        #[arg(long)]
+        carve: bool,
+    },
+    /// windows: Parse $LogFile
+    Logfile {
+        /// Alternative drive letter to use
+        #[arg(long, default_value = None)]
+        alt_drive: Option<char>,
+        /// Alternative path to $LogFile
+        #[arg(long, default_value = None)]
+        alt_file: Option<String>,
+        /// Alternative path to MFT
+        #[arg(long, default_value = None)]
+        alt_mft: Option<String>,
+    },
+    /// windows: Parse hiberfil.sys
+    Hiberfil {
+        /// Alternative path to hiberfil.sys
+        #[arg(long, default_value = None)]
+        alt_file: Option<String>,
+        /// Do not write the raw memory image to the output directory
+        #[arg(long)]
+        skip_raw_image: bool,
+    },
+    /// windows: Carve strings and structures from pagefile.sys and swapfile.sys
+    Pagefile {
+        /// Alternative path to pagefile.sys or swapfile.sys
+        #[arg(long, default_value = None)]
+        alt_file: Option<String>,
+        /// Extractors to run. Default is all. Supported: url, ip, path, command, evtx, lnk, pe
+        #[arg(long, value_delimiter = ',')]
+        extractors: Option<Vec<String>>,
+        /// Base64 encoded Yara rule to scan each page with
+        #[arg(long, default_value = None)]
+        yara_rule: Option<String>,
+    },
+    /// windows: Parse Windows Timeline ActivitiesCache.db files
+    Activitiescache {
+        /// Alternative path to ActivitiesCache.db
+        #[arg(long, default_value = None)]
+        alt_file: Option<String>,
     },
     /// windows: Parse BITS
     Bits {
@@ -198,6 +297,9 @@ pub(crate) enum CommandArgs {
         /// Alternative SRUM file path
         #[arg(long, default_value = None)]
         alt_file: Option<String>,
+        /// Recover deleted SRUM rows from free ESE page space
+        #[arg(long)]
+        recover_deleted: bool,
     },
     /// windows: Parse Users
     UsersWindows {
@@ -210,6 +312,9 @@ pub(crate) enum CommandArgs {
         /// Alternative Search file path
         #[arg(long, default_value = None)]
         alt_file: Option<String>,
+        /// Recover deleted Search rows from free ESE page space
+        #[arg(long)]
+        recover_deleted: bool,
     },
     /// windows: Parse Windows Tasks
     Tasks {
@@ -270,6 +375,9 @@ pub(crate) enum CommandArgs {
         /// Alternative Drive letter to used instead of SystemDrive. Windows only
         #[arg(long)]
         alt_drive: Option<char>,
+        /// Carve MFT FILE records from unallocated space and slack space
+        #[arg(long)]
+        carve: bool,
     },
     /// macos: Collect local users
     UsersMacos {
@@ -349,6 +457,12 @@ pub(crate) enum CommandArgs {
         #[arg(long, default_value = None)]
         alt_file: Option<String>,
     },
+    /// linux: Parse shell histories, editor history, and SSH files for all users
+    Useractivity {
+        /// Alternative root directory to use. Ex: a mounted disk image
+        #[arg(long, default_value = None)]
+        alt_dir: Option<String>,
+    },
     /// linux: Parse the raw ext4 filesystem
     RawfilelistingExt4 {
         /// Start path for listing
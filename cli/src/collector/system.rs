@@ -4,8 +4,11 @@ use forensics::{
     core::artemis_collection,
     structs::{
         artifacts::os::{
+            browsers::BrowserOptions,
             files::FileOptions,
-            linux::{Ext4Options, JournalOptions, LinuxSudoOptions, LogonOptions},
+            linux::{
+                Ext4Options, JournalOptions, LinuxSudoOptions, LogonOptions, UserActivityOptions,
+            },
             macos::{
                 EmondOptions, FseventsOptions, LaunchdOptions, LoginitemsOptions,
                 MacosGroupsOptions, MacosSudoOptions, MacosUsersOptions, SpotlightOptions,
@@ -13,11 +16,13 @@ use forensics::{
             },
             processes::ProcessOptions,
             windows::{
-                AmcacheOptions, BitsOptions, EventLogsOptions, JumplistsOptions, MftOptions,
-                OutlookOptions, PrefetchOptions, RawFilesOptions, RecycleBinOptions,
-                RegistryOptions, SearchOptions, ServicesOptions, ShellbagsOptions,
-                ShimcacheOptions, ShimdbOptions, ShortcutOptions, SrumOptions, TasksOptions,
-                UserAssistOptions, UsnJrnlOptions, WindowsUserOptions, WmiPersistOptions,
+                ActivitiesCacheOptions, AmcacheOptions, BitsOptions, EventLogsFilter,
+                EventLogsOptions, HiberfilOptions, JumplistsOptions, LogFileOptions, MftOptions,
+                OutlookOptions, PagefileOptions, PrefetchOptions, RawFilesOptions,
+                RecycleBinOptions, RegistryOptions, SearchOptions, ServicesOptions,
+                ShellbagsOptions, ShimcacheOptions, ShimdbOptions, ShortcutOptions, SrumOptions,
+                TasksOptions, UserAssistOptions, UsnJrnlOptions, WindowsUserOptions,
+                WmiPersistOptions,
             },
         },
         toml::{ArtemisToml, Artifacts, OutputConfig, OutputFormat},
@@ -235,6 +240,13 @@ fn setup_artifact(artifact: &CommandArgs) -> Artifacts {
             collect.logons = Some(options);
             collect.artifact_name = String::from("logons");
         }
+        CommandArgs::Useractivity { alt_dir } => {
+            let options = UserActivityOptions {
+                alt_dir: alt_dir.clone(),
+            };
+            collect.useractivity = Some(options);
+            collect.artifact_name = String::from("useractivity");
+        }
         CommandArgs::SudologsLinux { alt_dir } => {
             let options = LinuxSudoOptions {
                 alt_dir: alt_dir.clone(),
@@ -285,16 +297,42 @@ fn setup_artifact(artifact: &CommandArgs) -> Artifacts {
             include_templates,
             alt_dir,
             alt_template_file,
+            alt_template_image,
             dump_templates,
             only_templates,
+            sigma_dir,
+            event_ids,
+            providers,
+            channels,
+            levels,
+            start_record,
+            end_record,
+            start_date,
+            end_date,
+            carve,
         } => {
+            let filter = EventLogsFilter {
+                event_ids: event_ids.clone(),
+                providers: providers.clone(),
+                channels: channels.clone(),
+                levels: levels.clone(),
+                start_record: *start_record,
+                end_record: *end_record,
+                start_date: start_date.clone(),
+                end_date: end_date.clone(),
+            };
             let options = EventLogsOptions {
                 alt_file: alt_file.clone(),
                 alt_dir: alt_dir.clone(),
                 alt_template_file: alt_template_file.clone(),
+                alt_template_image: alt_template_image.clone(),
                 include_templates: *include_templates,
                 dump_templates: *dump_templates,
                 only_templates: *only_templates,
+                sigma_dir: sigma_dir.clone(),
+                sigma_rules: None,
+                filter: Some(filter),
+                carve: Some(*carve),
             };
             collect.eventlogs = Some(options);
             collect.artifact_name = String::from("eventlogs");
@@ -352,19 +390,27 @@ fn setup_artifact(artifact: &CommandArgs) -> Artifacts {
             system_hives,
             alt_file,
             path_regex,
+            recover_deleted,
+            recover_orphans,
         } => {
             let options = RegistryOptions {
                 user_hives: *user_hives,
                 system_hives: *system_hives,
                 alt_file: alt_file.clone(),
                 path_regex: path_regex.clone(),
+                recover_deleted: Some(*recover_deleted),
+                recover_orphans: Some(*recover_orphans),
             };
             collect.registry = Some(options);
             collect.artifact_name = String::from("registry");
         }
-        CommandArgs::Search { alt_file } => {
+        CommandArgs::Search {
+            alt_file,
+            recover_deleted,
+        } => {
             let options = SearchOptions {
                 alt_file: alt_file.clone(),
+                recover_deleted: Some(*recover_deleted),
             };
             collect.search = Some(options);
             collect.artifact_name = String::from("search");
@@ -406,9 +452,13 @@ fn setup_artifact(artifact: &CommandArgs) -> Artifacts {
             collect.shortcuts = Some(options);
             collect.artifact_name = String::from("shortcuts");
         }
-        CommandArgs::Srum { alt_file } => {
+        CommandArgs::Srum {
+            alt_file,
+            recover_deleted,
+        } => {
             let options = SrumOptions {
                 alt_file: alt_file.clone(),
+                recover_deleted: Some(*recover_deleted),
             };
             collect.srum = Some(options);
             collect.artifact_name = String::from("srum");
@@ -442,15 +492,61 @@ fn setup_artifact(artifact: &CommandArgs) -> Artifacts {
             alt_drive,
             alt_file,
             alt_mft,
+            carve,
         } => {
             let options = UsnJrnlOptions {
                 alt_drive: *alt_drive,
                 alt_file: alt_file.clone(),
                 alt_mft: alt_mft.clone(),
+                carve: Some(*carve),
             };
             collect.usnjrnl = Some(options);
             collect.artifact_name = String::from("usnjrnl");
         }
+        CommandArgs::Logfile {
+            alt_drive,
+            alt_file,
+            alt_mft,
+        } => {
+            let options = LogFileOptions {
+                alt_drive: *alt_drive,
+                alt_file: alt_file.clone(),
+                alt_mft: alt_mft.clone(),
+            };
+            collect.logfile = Some(options);
+            collect.artifact_name = String::from("logfile");
+        }
+        CommandArgs::Hiberfil {
+            alt_file,
+            skip_raw_image,
+        } => {
+            let options = HiberfilOptions {
+                alt_file: alt_file.clone(),
+                skip_raw_image: Some(*skip_raw_image),
+            };
+            collect.hiberfil = Some(options);
+            collect.artifact_name = String::from("hiberfil");
+        }
+        CommandArgs::Pagefile {
+            alt_file,
+            extractors,
+            yara_rule,
+        } => {
+            let options = PagefileOptions {
+                alt_file: alt_file.clone(),
+                extractors: extractors.clone(),
+                yara_rule: yara_rule.clone(),
+            };
+            collect.pagefile = Some(options);
+            collect.artifact_name = String::from("pagefile");
+        }
+        CommandArgs::Activitiescache { alt_file } => {
+            let options = ActivitiesCacheOptions {
+                alt_file: alt_file.clone(),
+            };
+            collect.activitiescache = Some(options);
+            collect.artifact_name = String::from("activitiescache");
+        }
         CommandArgs::Wmipersist { alt_dir } => {
             let options = WmiPersistOptions {
                 alt_dir: alt_dir.clone(),
@@ -481,14 +577,32 @@ fn setup_artifact(artifact: &CommandArgs) -> Artifacts {
         CommandArgs::Mft {
             alt_file,
             alt_drive,
+            carve,
         } => {
             let options = MftOptions {
                 alt_drive: *alt_drive,
                 alt_file: alt_file.clone(),
+                carve: Some(*carve),
             };
             collect.mft = Some(options);
             collect.artifact_name = String::from("mft");
         }
+        CommandArgs::Chromium { alt_path, records } => {
+            let options = BrowserOptions {
+                alt_path: alt_path.clone(),
+                records: records.clone(),
+            };
+            collect.chromium = Some(options);
+            collect.artifact_name = String::from("chromium");
+        }
+        CommandArgs::Firefox { alt_path, records } => {
+            let options = BrowserOptions {
+                alt_path: alt_path.clone(),
+                records: records.clone(),
+            };
+            collect.firefox = Some(options);
+            collect.artifact_name = String::from("firefox");
+        }
         CommandArgs::Connections {} => {
             collect.artifact_name = String::from("connections");
         }
@@ -746,6 +860,8 @@ mod tests {
                 system_hives: false,
                 alt_file: None,
                 path_regex: None,
+                recover_deleted: false,
+                recover_orphans: false,
             }),
             format: String::from("json"),
             compress: false,
@@ -767,7 +883,18 @@ mod tests {
                 dump_templates: false,
                 alt_dir: None,
                 alt_template_file: None,
+                alt_template_image: None,
                 only_templates: false,
+                sigma_dir: None,
+                event_ids: Some(vec![String::from("4624"), String::from("4720-4738")]),
+                providers: None,
+                channels: None,
+                levels: None,
+                start_record: None,
+                end_record: None,
+                start_date: None,
+                end_date: None,
+                carve: false,
             }),
             format: String::from("json"),
             output_dir: String::from("./tmp"),
@@ -903,7 +1030,10 @@ mod tests {
     #[test]
     fn test_run_collector_srum() {
         let command = Commands::Acquire {
-            artifact: Some(Srum { alt_file: None }),
+            artifact: Some(Srum {
+                alt_file: None,
+                recover_deleted: false,
+            }),
             format: String::from("json"),
             output_dir: String::from("./tmp"),
             compress: false,
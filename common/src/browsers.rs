@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+/// Normalized record for Chromium and Firefox based browsers
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BrowserRecord {
+    /**Browser that created the record. Ex: Chrome, Edge, Brave, Firefox */
+    pub browser: String,
+    pub record_type: BrowserRecordType,
+    pub url: String,
+    pub title: String,
+    pub host: String,
+    /**Primary timestamp for the record. Ex: Visit time, download start, cookie creation */
+    pub timestamp: String,
+    /**Description of the primary timestamp */
+    pub timestamp_desc: String,
+    pub last_accessed: String,
+    /**End of the activity. Ex: Download completed */
+    pub end_time: String,
+    pub expires: String,
+    pub visit_count: i64,
+    /**Path on disk associated with the record. Ex: Download target path or cache file */
+    pub path: String,
+    pub size: i64,
+    /**Name associated with the record. Ex: Cookie name */
+    pub name: String,
+    /**Additional record specific details. Ex: Transition type, download state */
+    pub details: String,
+    pub user: String,
+    pub profile: String,
+    pub evidence: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Default, Clone, Copy)]
+pub enum BrowserRecordType {
+    History,
+    Download,
+    Cookie,
+    Login,
+    TopSite,
+    Cache,
+    Session,
+    #[default]
+    Unknown,
+}
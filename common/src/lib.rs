@@ -1,3 +1,4 @@
+pub mod browsers;
 pub mod files;
 pub mod linux;
 pub mod macos;
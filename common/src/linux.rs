@@ -160,3 +160,38 @@ pub enum Status {
     Success,
     Failed,
 }
+
+/// User activity parsed from files in a Linux user's home directory
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserActivity {
+    pub username: String,
+    pub uid: u32,
+    pub gid: u32,
+    pub home: String,
+    pub shell: String,
+    pub activity_type: UserActivityType,
+    /**Command, search string, file path, host, or key comment depending on the activity type */
+    pub value: String,
+    /**Additional activity specific details. Ex: zsh command duration, SSH key type */
+    pub details: String,
+    /**Timestamp for the activity. Many files do not track timestamps */
+    pub timestamp: String,
+    /**Line number in the source file */
+    pub line: usize,
+    pub evidence: String,
+}
+
+#[derive(Debug, Serialize, PartialEq, Clone, Copy, Deserialize)]
+pub enum UserActivityType {
+    /**User entry from /etc/passwd */
+    User,
+    BashHistory,
+    ZshHistory,
+    PythonHistory,
+    LessHistory,
+    VimCommand,
+    VimSearch,
+    VimFile,
+    KnownHost,
+    AuthorizedKey,
+}
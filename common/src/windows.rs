@@ -367,6 +367,25 @@ pub struct EventLogRecord {
     pub timestamp: String,
     pub data: Value,
     pub evidence: String,
+    /**Record was carved from unallocated space or a binary blob */
+    #[serde(default)]
+    pub carved: bool,
+    /**Offset of a carved record in the source */
+    #[serde(default)]
+    pub offset: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SigmaHit {
+    pub rule_id: String,
+    pub title: String,
+    pub level: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    pub event_record_id: u64,
+    pub timestamp: String,
+    pub record: Value,
+    pub evidence: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -758,6 +777,8 @@ pub struct RegistryData {
     pub security_offset: i32,
     pub evidence: String,
     pub registry_file: String,
+    pub log_recovery: bool, // Dirty pages from the .LOG1/.LOG2 files were replayed before parsing
+    pub deleted: bool,      // Recovered from unallocated cells
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -913,6 +934,9 @@ pub struct ApplicationInfo {
     pub background_num_write_operations: i32,
     pub background_number_of_flushes: i32,
     pub evidence: String,
+    pub log_replay: bool,
+    pub log_generation: u32,
+    pub recovered: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -962,6 +986,9 @@ pub struct AppTimelineInfo {
     pub keyboard_input_s: i32,
     pub mouse_input_s: i32,
     pub evidence: String,
+    pub log_replay: bool,
+    pub log_generation: u32,
+    pub recovered: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -975,6 +1002,9 @@ pub struct AppVfu {
     pub end_time: String,
     pub usage: String,
     pub evidence: String,
+    pub log_replay: bool,
+    pub log_generation: u32,
+    pub recovered: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -985,6 +1015,9 @@ pub struct EnergyInfo {
     pub user_id: String,
     pub binary_data: String,
     pub evidence: String,
+    pub log_replay: bool,
+    pub log_generation: u32,
+    pub recovered: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -1001,6 +1034,9 @@ pub struct EnergyUsage {
     pub cycle_count: i32,
     pub configuration_hash: i64,
     pub evidence: String,
+    pub log_replay: bool,
+    pub log_generation: u32,
+    pub recovered: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -1015,6 +1051,9 @@ pub struct NetworkInfo {
     pub bytes_sent: i64,
     pub bytes_recvd: i64,
     pub evidence: String,
+    pub log_replay: bool,
+    pub log_generation: u32,
+    pub recovered: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -1029,6 +1068,9 @@ pub struct NetworkConnectivityInfo {
     pub connect_start_time: String,
     pub l2_profile_flags: i32,
     pub evidence: String,
+    pub log_replay: bool,
+    pub log_generation: u32,
+    pub recovered: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -1041,6 +1083,9 @@ pub struct NotificationInfo {
     pub payload_size: i32,
     pub network_type: i32,
     pub evidence: String,
+    pub log_replay: bool,
+    pub log_generation: u32,
+    pub recovered: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -1467,6 +1512,7 @@ pub struct UsnJrnlEntry {
     pub full_path: String,
     pub drive: String,
     pub evidence: String,
+    pub carved: bool,
 }
 
 #[derive(Debug, PartialEq, Serialize, Clone, Deserialize)]
@@ -1658,6 +1704,7 @@ pub struct MftEntry {
     pub deleted: bool,
     pub drive: String,
     pub evidence: String,
+    pub carved: bool,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Default)]
@@ -1669,3 +1716,174 @@ pub enum Namespace {
     #[default]
     Unknown,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LogFileEntry {
+    pub lsn: u64,
+    pub previous_lsn: u64,
+    pub undo_next_lsn: u64,
+    pub transaction_id: u32,
+    pub redo_operation: LogOperation,
+    pub undo_operation: LogOperation,
+    pub target_attribute: u16,
+    pub target_vcn: u64,
+    pub cluster_block_offset: u16,
+    pub record_offset: u16,
+    pub attribute_offset: u16,
+    pub redo_size: u16,
+    pub undo_size: u16,
+    pub mft_entry: u32,
+    pub mft_sequence: u16,
+    pub parent_mft_entry: u32,
+    pub parent_mft_sequence: u16,
+    pub filename: String,
+    pub extension: String,
+    pub full_path: String,
+    pub file_attributes: Vec<AttributeFlags>,
+    pub created: String,
+    pub modified: String,
+    pub changed: String,
+    pub accessed: String,
+    pub drive: String,
+    pub evidence: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Default)]
+pub enum LogOperation {
+    Noop,
+    CompensationLogRecord,
+    InitializeFileRecordSegment,
+    DeallocateFileRecordSegment,
+    WriteEndOfFileRecordSegment,
+    CreateAttribute,
+    DeleteAttribute,
+    UpdateResidentValue,
+    UpdateNonResidentValue,
+    UpdateMappingPairs,
+    DeleteDirtyClusters,
+    SetNewAttributeSizes,
+    AddIndexEntryRoot,
+    DeleteIndexEntryRoot,
+    AddIndexEntryAllocation,
+    DeleteIndexEntryAllocation,
+    WriteEndOfIndexBuffer,
+    SetIndexEntryVcnRoot,
+    SetIndexEntryVcnAllocation,
+    UpdateFileNameRoot,
+    UpdateFileNameAllocation,
+    SetBitsInNonResidentBitMap,
+    ClearBitsInNonResidentBitMap,
+    HotFix,
+    EndTopLevelAction,
+    PrepareTransaction,
+    CommitTransaction,
+    ForgetTransaction,
+    OpenNonResidentAttribute,
+    OpenAttributeTableDump,
+    AttributeNamesDump,
+    DirtyPageTableDump,
+    TransactionTableDump,
+    UpdateRecordDataRoot,
+    UpdateRecordDataAllocation,
+    UpdateRelativeDataInIndex,
+    UpdateRelativeDataInIndex2,
+    ZeroEndOfFileRecord,
+    #[default]
+    Unknown,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HiberfilProcess {
+    pub image_path: String,
+    pub command_line: String,
+    pub current_directory: String,
+    pub window_title: String,
+    /**Physical memory offset of the process parameters */
+    pub offset: u64,
+    pub hibernation_time: String,
+    pub raw_image: String,
+    pub evidence: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PagefileHit {
+    /**Extractor that found the data. Ex: url, ip, path, command, evtx, lnk, pe, yara */
+    pub extractor: String,
+    pub value: String,
+    pub offset: u64,
+    /**String or structure details surrounding the hit */
+    pub context: String,
+    pub evidence: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ActivityEntry {
+    pub id: String,
+    /**Table the entry was found in. Activity or ActivityOperation */
+    pub source_table: String,
+    /**Order of the operation. Only for ActivityOperation entries */
+    pub operation_order: i64,
+    pub activity_type: ActivityType,
+    /**ActivityStatus for Activity entries. OperationType for ActivityOperation entries */
+    pub status: ActivityStatus,
+    /**Application associated with the activity. Known folder GUIDs are resolved */
+    pub application: String,
+    pub app_ids: Vec<ActivityApp>,
+    /**Entries from the Activity_PackageId table */
+    pub package_ids: Vec<ActivityApp>,
+    pub app_activity_id: String,
+    pub parent_activity_id: String,
+    pub group: String,
+    pub tag: String,
+    pub match_id: String,
+    pub platform_device_id: String,
+    pub priority: i64,
+    pub is_local_only: bool,
+    pub display_text: String,
+    pub description: String,
+    pub app_display_name: String,
+    pub content_uri: String,
+    pub active_duration_seconds: i64,
+    pub user_timezone: String,
+    /**Decoded text copied to the clipboard */
+    pub clipboard_text: Vec<String>,
+    pub payload: Value,
+    pub start_time: String,
+    pub end_time: String,
+    pub last_modified: String,
+    pub last_modified_on_client: String,
+    pub expiration: String,
+    pub created_in_cloud: String,
+    /**Only for ActivityOperation entries */
+    pub created: String,
+    pub evidence: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct ActivityApp {
+    pub application: String,
+    pub platform: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Default)]
+pub enum ActivityType {
+    Notification,
+    MobileBackup,
+    OpenApp,
+    InFocus,
+    Clipboard,
+    System,
+    CopyPaste,
+    #[default]
+    Unknown,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Default)]
+pub enum ActivityStatus {
+    Active,
+    Updated,
+    Deleted,
+    Ignored,
+    #[default]
+    Unknown,
+}
@@ -1,10 +1,10 @@
-use super::error::CollectError;
-use crate::{enrollment::enroll::bad_request, start::DaemonConfig};
-use reqwest::{StatusCode, blocking::Client};
+use super::{error::CollectError, schedule::Schedule};
+use crate::{enrollment::enroll::bad_request, start::DaemonConfig, utils::client::http_client};
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use tracing::{error, info};
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub(crate) struct CollectResponse {
     /// Base64 toml endpoint collection
     pub(crate) collection: String,
@@ -14,6 +14,12 @@ pub(crate) struct CollectResponse {
     pub(crate) collection_id: u64,
     /// How long to allow collection to run before it is killed
     pub(crate) collection_timeout: u64,
+    /// Base64 detached Ed25519 signature of the decoded collection
+    #[serde(default)]
+    pub(crate) signature: String,
+    /// Run the collection on a recurring schedule instead of once
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) schedule: Option<Schedule>,
 }
 
 #[derive(Serialize, Debug)]
@@ -22,11 +28,15 @@ pub(crate) struct CollectRequest {
     endpoint_id: String,
 }
 
-#[derive(Serialize, Debug, Copy, Clone)]
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
 pub(crate) enum CollectionStatus {
     Complete,
     Error,
     Timeout,
+    /// Collection signature could not be verified
+    Rejected,
+    /// Collection schedule was saved and will run locally
+    Scheduled,
 }
 
 #[derive(Serialize, Debug)]
@@ -68,7 +78,10 @@ impl CollectEndpoint for DaemonConfig {
             endpoint_id: self.server.daemon.endpoint_id.clone(),
         };
 
-        let client = Client::new();
+        let client = match http_client(&self.server.server) {
+            Ok(result) => result,
+            Err(_err) => return Err(CollectError::FailedCollect),
+        };
         let mut builder = client.post(&url).json(&req);
         builder = builder.header("accept", "application/json");
 
@@ -132,7 +145,10 @@ impl CollectEndpoint for DaemonConfig {
             collection_id,
         };
 
-        let client = Client::new();
+        let client = match http_client(&self.server.server) {
+            Ok(result) => result,
+            Err(_err) => return Err(CollectError::FailedCollect),
+        };
         let mut builder = client.post(&url).json(&req);
         builder = builder.header("accept", "application/json");
         builder = builder.header(
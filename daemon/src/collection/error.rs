@@ -6,6 +6,9 @@ pub(crate) enum CollectError {
     BadCollect,
     CollectNotOk,
     NoCollection,
+    BadSignature,
+    BadSchedule,
+    ScheduleFile,
 }
 
 impl std::error::Error for CollectError {}
@@ -17,6 +20,9 @@ impl fmt::Display for CollectError {
             CollectError::BadCollect => write!(f, "Collection request data was bad"),
             CollectError::CollectNotOk => write!(f, "Server returned non-Ok response"),
             CollectError::NoCollection => write!(f, "Server does not have any collections for us"),
+            CollectError::BadSignature => write!(f, "Collection signature could not be verified"),
+            CollectError::BadSchedule => write!(f, "Collection schedule is not valid"),
+            CollectError::ScheduleFile => write!(f, "Could not read or write collection schedule"),
         }
     }
 }
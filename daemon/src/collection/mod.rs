@@ -1,2 +1,6 @@
 pub(crate) mod collect;
 pub(crate) mod error;
+pub(crate) mod schedule;
+pub(crate) mod scheduler;
+pub(crate) mod spool;
+pub(crate) mod verify;
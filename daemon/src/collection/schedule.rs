@@ -0,0 +1,373 @@
+use super::error::CollectError;
+use chrono::{DateTime, Datelike, Timelike};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+/// Schedule provided by the server for a recurring collection. All times are UTC
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub(crate) struct Schedule {
+    /// Cron expression: minute hour day-of-month month day-of-week
+    #[serde(default)]
+    pub(crate) cron: Option<String>,
+    /// Run the collection every `interval` seconds
+    #[serde(default)]
+    pub(crate) interval: Option<u64>,
+    /// Random delay in seconds added to each run
+    #[serde(default)]
+    pub(crate) jitter: u64,
+    /// Windows where collections must not start. Format is `HH:MM-HH:MM`
+    #[serde(default)]
+    pub(crate) blackout: Vec<String>,
+    /// Max runtime in seconds. Default is the collection timeout
+    #[serde(default)]
+    pub(crate) max_runtime: Option<u64>,
+    /// Stop running the schedule after this `UnixEpoch` time
+    #[serde(default)]
+    pub(crate) expires: Option<u64>,
+}
+
+impl Schedule {
+    /// Make sure the schedule can be used before we save it
+    pub(crate) fn validate(&self) -> Result<(), CollectError> {
+        match (&self.cron, self.interval) {
+            (Some(cron), None) => {
+                Cron::parse(cron)?;
+            }
+            (None, Some(interval)) if interval != 0 => {}
+            _ => {
+                error!("Schedule must have either a cron expression or a non-zero interval");
+                return Err(CollectError::BadSchedule);
+            }
+        }
+        for window in &self.blackout {
+            blackout_window(window)?;
+        }
+        Ok(())
+    }
+
+    /// Get the first time a new schedule should run. Interval schedules start right away
+    pub(crate) fn first_run(&self, now: u64) -> Result<u64, CollectError> {
+        if self.interval.is_some() {
+            let jitter = if self.jitter == 0 {
+                0
+            } else {
+                fastrand::u64(..=self.jitter)
+            };
+            return self.after_blackout(now + jitter);
+        }
+        self.next_run(now)
+    }
+
+    /// Get the next time the collection should run after `after`
+    pub(crate) fn next_run(&self, after: u64) -> Result<u64, CollectError> {
+        let next = if let Some(cron) = &self.cron {
+            Cron::parse(cron)?.next_after(after)?
+        } else {
+            after + self.interval.unwrap_or_default().max(1)
+        };
+
+        let jitter = if self.jitter == 0 {
+            0
+        } else {
+            fastrand::u64(..=self.jitter)
+        };
+        self.after_blackout(next + jitter)
+    }
+
+    /// Move `time` to the end of any blackout window it falls in
+    pub(crate) fn after_blackout(&self, time: u64) -> Result<u64, CollectError> {
+        let mut time = time;
+        // Windows can overlap. Check again after moving past a window
+        for _ in 0..=self.blackout.len() {
+            let mut moved = false;
+            for window in &self.blackout {
+                let (start, end) = blackout_window(window)?;
+                let day_start = time - time % DAY;
+                let minute = (time % DAY) / 60;
+
+                let end_time = if start <= end {
+                    (minute >= start && minute < end).then(|| day_start + end * 60)
+                } else if minute >= start {
+                    // Window wraps past midnight
+                    Some(day_start + DAY + end * 60)
+                } else {
+                    (minute < end).then(|| day_start + end * 60)
+                };
+                if let Some(end_time) = end_time {
+                    time = end_time;
+                    moved = true;
+                }
+            }
+            if !moved {
+                break;
+            }
+        }
+        Ok(time)
+    }
+}
+
+/// Seconds in a day
+const DAY: u64 = 24 * 60 * 60;
+
+/// Parse a blackout window into start and end minutes of the day
+fn blackout_window(window: &str) -> Result<(u64, u64), CollectError> {
+    let parse_time = |value: &str| -> Option<u64> {
+        let (hour, minute) = value.trim().split_once(':')?;
+        let hour = hour.parse::<u64>().ok()?;
+        let minute = minute.parse::<u64>().ok()?;
+        if hour > 23 || minute > 59 {
+            return None;
+        }
+        Some(hour * 60 + minute)
+    };
+
+    let times = window
+        .split_once('-')
+        .and_then(|(start, end)| Some((parse_time(start)?, parse_time(end)?)));
+    let Some(result) = times else {
+        error!("Invalid blackout window {window}. Format should be HH:MM-HH:MM");
+        return Err(CollectError::BadSchedule);
+    };
+    Ok(result)
+}
+
+/// Parsed cron expression. Each field is a bitmask of allowed values
+#[derive(Debug, PartialEq)]
+struct Cron {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Day of month did not start with `*`
+    days_restricted: bool,
+    /// Day of week did not start with `*`
+    weekdays_restricted: bool,
+}
+
+impl Cron {
+    /// Parse a 5 field cron expression. Supports `*`, lists, ranges, and steps
+    fn parse(expression: &str) -> Result<Cron, CollectError> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            error!("Cron expression {expression} must have 5 fields");
+            return Err(CollectError::BadSchedule);
+        }
+
+        let mut weekdays = cron_field(fields[4], 0, 7)?;
+        // Both 0 and 7 are Sunday
+        if weekdays & (1 << 7) != 0 {
+            weekdays |= 1;
+        }
+
+        Ok(Cron {
+            minutes: cron_field(fields[0], 0, 59)?,
+            hours: cron_field(fields[1], 0, 23)?,
+            days: cron_field(fields[2], 1, 31)?,
+            months: cron_field(fields[3], 1, 12)?,
+            weekdays,
+            // Same as Vixie cron. A field starting with `*` (such as `*/2`) is not a restriction
+            days_restricted: !fields[2].starts_with('*'),
+            weekdays_restricted: !fields[4].starts_with('*'),
+        })
+    }
+
+    /// Find the next matching minute after `after`. Searches up to a little over four years ahead
+    fn next_after(&self, after: u64) -> Result<u64, CollectError> {
+        let mut time = (after / 60 + 1) * 60;
+        let limit = time + 4 * 366 * DAY;
+
+        while time < limit {
+            let Some(date) = DateTime::from_timestamp(time as i64, 0) else {
+                break;
+            };
+            let day_match = match (self.days_restricted, self.weekdays_restricted) {
+                // Standard cron matches either field when both are restricted
+                (true, true) => {
+                    bit(self.days, date.day())
+                        || bit(self.weekdays, date.weekday().num_days_from_sunday())
+                }
+                _ => {
+                    bit(self.days, date.day())
+                        && bit(self.weekdays, date.weekday().num_days_from_sunday())
+                }
+            };
+
+            if !bit(self.months, date.month()) || !day_match {
+                time = time - time % DAY + DAY;
+                continue;
+            }
+            if !bit(self.hours, date.hour()) {
+                time = time - time % 3600 + 3600;
+                continue;
+            }
+            if !bit(self.minutes, date.minute()) {
+                time += 60;
+                continue;
+            }
+            return Ok(time);
+        }
+
+        error!("Cron expression never matches");
+        Err(CollectError::BadSchedule)
+    }
+}
+
+/// Check if `value` is set in the bitmask
+fn bit(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+/// Parse one cron field into a bitmask
+fn cron_field(field: &str, min: u64, max: u64) -> Result<u64, CollectError> {
+    let mut mask = 0;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => match step.parse::<u64>() {
+                Ok(value) if value != 0 => (range, value),
+                _ => {
+                    error!("Invalid cron step in {field}");
+                    return Err(CollectError::BadSchedule);
+                }
+            },
+            None => (item, 1),
+        };
+
+        let parse = |value: &str| -> Result<u64, CollectError> {
+            match value.parse::<u64>() {
+                Ok(result) if result >= min && result <= max => Ok(result),
+                _ => {
+                    error!("Invalid cron value {value} in {field}");
+                    Err(CollectError::BadSchedule)
+                }
+            }
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse(start)?, parse(end)?)
+        } else if step != 1 {
+            // "5/15" runs from 5 to the max value
+            (parse(range)?, max)
+        } else {
+            let value = parse(range)?;
+            (value, value)
+        };
+        if start > end {
+            error!("Invalid cron range in {field}");
+            return Err(CollectError::BadSchedule);
+        }
+
+        let mut value = start;
+        while value <= end {
+            mask |= 1 << value;
+            value += step;
+        }
+    }
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cron, Schedule, blackout_window, cron_field};
+    use crate::collection::error::CollectError;
+
+    #[test]
+    fn test_cron_field() {
+        assert_eq!(cron_field("*", 0, 3).unwrap(), 0b1111);
+        assert_eq!(cron_field("1,3", 0, 5).unwrap(), 0b1010);
+        assert_eq!(cron_field("2-4", 0, 5).unwrap(), 0b11100);
+        assert_eq!(cron_field("*/2", 0, 5).unwrap(), 0b10101);
+        assert_eq!(cron_field("1/2", 0, 5).unwrap(), 0b101010);
+        assert_eq!(cron_field("60", 0, 59), Err(CollectError::BadSchedule));
+        assert_eq!(cron_field("*/0", 0, 59), Err(CollectError::BadSchedule));
+        assert_eq!(cron_field("5-1", 0, 59), Err(CollectError::BadSchedule));
+    }
+
+    #[test]
+    fn test_cron_next_after() {
+        // 2024-01-01 00:00:00 UTC was a Monday
+        let start = 1704067200;
+        let cron = Cron::parse("30 2 * * *").unwrap();
+        assert_eq!(cron.next_after(start).unwrap(), start + 2 * 3600 + 1800);
+        assert_eq!(
+            cron.next_after(start + 2 * 3600 + 1800).unwrap(),
+            start + 86400 + 2 * 3600 + 1800
+        );
+
+        // Every Sunday at midnight
+        let cron = Cron::parse("0 0 * * 7").unwrap();
+        assert_eq!(cron.next_after(start).unwrap(), start + 6 * 86400);
+
+        let cron = Cron::parse("*/15 * * * *").unwrap();
+        assert_eq!(cron.next_after(start).unwrap(), start + 900);
+
+        // Odd days that are also a Monday. Next is 2024-01-15
+        let cron = Cron::parse("0 0 */2 * 1").unwrap();
+        assert_eq!(cron.next_after(start).unwrap(), start + 14 * 86400);
+
+        // Either the 15th or a Monday
+        let cron = Cron::parse("0 0 15 * 1").unwrap();
+        assert_eq!(cron.next_after(start).unwrap(), start + 7 * 86400);
+
+        let cron = Cron::parse("0 0 31 2 *").unwrap();
+        assert_eq!(cron.next_after(start), Err(CollectError::BadSchedule));
+        assert_eq!(Cron::parse("* * *"), Err(CollectError::BadSchedule));
+    }
+
+    #[test]
+    fn test_schedule_validate() {
+        let mut schedule = Schedule {
+            interval: Some(3600),
+            blackout: vec![String::from("22:00-06:00")],
+            ..Default::default()
+        };
+        schedule.validate().unwrap();
+
+        schedule.cron = Some(String::from("0 * * * *"));
+        assert_eq!(schedule.validate(), Err(CollectError::BadSchedule));
+
+        schedule.interval = None;
+        schedule.validate().unwrap();
+
+        schedule.blackout = vec![String::from("25:00-06:00")];
+        assert_eq!(schedule.validate(), Err(CollectError::BadSchedule));
+    }
+
+    #[test]
+    fn test_schedule_next_run() {
+        let start = 1704067200;
+        let schedule = Schedule {
+            interval: Some(3600),
+            jitter: 60,
+            ..Default::default()
+        };
+        let next = schedule.next_run(start).unwrap();
+        assert!(next >= start + 3600 && next <= start + 3660);
+    }
+
+    #[test]
+    fn test_schedule_blackout() {
+        let start = 1704067200;
+        let schedule = Schedule {
+            interval: Some(3600),
+            blackout: vec![String::from("22:00-06:00"), String::from("06:00-07:30")],
+            ..Default::default()
+        };
+        assert_eq!(blackout_window("22:00-06:00").unwrap(), (1320, 360));
+
+        // Midnight is in both windows. Next allowed time is 07:30
+        assert_eq!(
+            schedule.after_blackout(start).unwrap(),
+            start + 7 * 3600 + 1800
+        );
+        assert_eq!(
+            schedule.after_blackout(start - 3600).unwrap(),
+            start + 7 * 3600 + 1800
+        );
+        assert_eq!(
+            schedule.after_blackout(start + 12 * 3600).unwrap(),
+            start + 12 * 3600
+        );
+    }
+}
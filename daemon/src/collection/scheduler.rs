@@ -0,0 +1,532 @@
+use super::{
+    collect::{CollectEndpoint, CollectResponse, CollectionStatus},
+    error::CollectError,
+    spool::track_spool,
+};
+use crate::{
+    start::DaemonConfig,
+    utils::{
+        config::{create_directory, read_file, write_file},
+        setup::{setup_collection, verify_request},
+        time::time_now,
+    },
+};
+use forensics::{core::CancelToken, structs::toml::ResourcePolicy};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{
+    fs::{read_dir, remove_file},
+    path::{Path, PathBuf},
+    thread::{JoinHandle, spawn},
+    time::Duration,
+};
+use tracing::{error, info, warn};
+
+/// Recurring collection saved under the daemon `collection_path`
+#[derive(Deserialize, Serialize, Debug)]
+pub(crate) struct ScheduledCollection {
+    /// Collection provided by the server. Includes the schedule
+    pub(crate) collection: CollectResponse,
+    /// Last time the collection ran
+    pub(crate) last_run: u64,
+    /// Next time the collection should run
+    pub(crate) next_run: u64,
+}
+
+/// Status of a scheduled collection waiting to be sent to the server
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+pub(crate) struct ScheduledStatus {
+    /// ID for the collection
+    pub(crate) collection_id: u64,
+    /// Status of the collection
+    pub(crate) status: CollectionStatus,
+    /// Time the status was recorded
+    pub(crate) time: u64,
+}
+
+/// Save a collection schedule provided by the server
+pub(crate) fn add_schedule(
+    collection_path: &str,
+    collect: &CollectResponse,
+) -> Result<(), CollectError> {
+    let Some(schedule) = &collect.schedule else {
+        error!(
+            "Collection {} does not have a schedule",
+            collect.collection_id
+        );
+        return Err(CollectError::BadSchedule);
+    };
+    schedule.validate()?;
+
+    let path = schedule_directory(collection_path).join(format!("{}.json", collect.collection_id));
+    let now = time_now();
+    // If the server sends the schedule again, keep our last run so we do not run the collection twice
+    let (last_run, next_run) = match read_json::<ScheduledCollection>(&path) {
+        Ok(existing) if existing.last_run != 0 => {
+            (existing.last_run, schedule.next_run(existing.last_run)?)
+        }
+        _ => (0, schedule.first_run(now)?),
+    };
+
+    let scheduled = ScheduledCollection {
+        collection: collect.clone(),
+        last_run,
+        next_run,
+    };
+    info!(
+        "Scheduled collection {} to run next at {next_run}",
+        collect.collection_id
+    );
+    write_json(&path, &scheduled)
+}
+
+/// Save a collection schedule from the server if its signature can be verified. The status is sent to the server later
+pub(crate) fn register_schedule(
+    config: &DaemonConfig,
+    collect: &CollectResponse,
+) -> CollectionStatus {
+    let collection_path = &config.server.daemon.collection_path;
+    let status = match verify_request(
+        collect,
+        config.server.server.collection_key.as_deref(),
+        config.server.server.allow_unsigned,
+    ) {
+        Ok(_) => match add_schedule(collection_path, collect) {
+            Ok(()) => CollectionStatus::Scheduled,
+            Err(_err) => CollectionStatus::Error,
+        },
+        Err(status) => status,
+    };
+    let _ = save_status(
+        collection_path,
+        &ScheduledStatus {
+            collection_id: collect.collection_id,
+            status,
+            time: time_now(),
+        },
+    );
+    status
+}
+
+/// Run any due scheduled collections on a background thread so the daemon can keep polling the server
+///
+/// Nothing is started if scheduled collections from an earlier call are still running
+pub(crate) fn spawn_schedules(
+    config: &DaemonConfig,
+    running: &mut Option<JoinHandle<usize>>,
+) -> bool {
+    if running.as_ref().is_some_and(|handle| !handle.is_finished()) {
+        return false;
+    }
+    let collection_path = config.server.daemon.collection_path.clone();
+    let collection_key = config.server.server.collection_key.clone();
+    let allow_unsigned = config.server.server.allow_unsigned;
+    let resources = config.server.daemon.resources.clone();
+
+    *running = Some(spawn(move || {
+        run_schedules(
+            &collection_path,
+            collection_key.as_deref(),
+            allow_unsigned,
+            resources.as_ref(),
+        )
+    }));
+    true
+}
+
+/// Run any scheduled collections that are due. Runs without contacting the server
+///
+/// The run is saved before the collection starts so restarts do not run the same schedule twice
+pub(crate) fn run_schedules(
+    collection_path: &str,
+    collection_key: Option<&str>,
+    allow_unsigned: bool,
+    resources: Option<&ResourcePolicy>,
+) -> usize {
+    let directory = schedule_directory(collection_path);
+    let mut runs = 0;
+    for path in json_files(&directory) {
+        let mut scheduled = match read_json::<ScheduledCollection>(&path) {
+            Ok(result) => result,
+            Err(_err) => continue,
+        };
+        let Some(schedule) = scheduled.collection.schedule.clone() else {
+            continue;
+        };
+
+        let now = time_now();
+        if schedule.expires.is_some_and(|expires| now >= expires) {
+            info!(
+                "Schedule for collection {} has expired",
+                scheduled.collection.collection_id
+            );
+            let _ = remove_file(&path);
+            continue;
+        }
+        if scheduled.next_run > now {
+            continue;
+        }
+
+        // The clock may have moved into a blackout window since we last checked
+        let allowed = match schedule.after_blackout(now) {
+            Ok(result) => result,
+            Err(_err) => continue,
+        };
+        if allowed != now {
+            scheduled.next_run = allowed;
+            let _ = write_json(&path, &scheduled);
+            continue;
+        }
+
+        scheduled.last_run = now;
+        scheduled.next_run = match schedule.next_run(now) {
+            Ok(result) => result,
+            Err(_err) => continue,
+        };
+        if write_json(&path, &scheduled).is_err() {
+            // Do not run the collection if we cannot track the run
+            continue;
+        }
+
+        let status = run_scheduled(
+            &scheduled.collection,
+            collection_key,
+            allow_unsigned,
+            resources,
+        );
+        if status != CollectionStatus::Rejected {
+            let _ = track_spool(collection_path, &scheduled.collection);
+        }
+        let _ = save_status(
+            collection_path,
+            &ScheduledStatus {
+                collection_id: scheduled.collection.collection_id,
+                status,
+                time: now,
+            },
+        );
+        runs += 1;
+    }
+    runs
+}
+
+/// Run a scheduled collection. The collection is cancelled once the max runtime is reached
+fn run_scheduled(
+    collect: &CollectResponse,
+    collection_key: Option<&str>,
+    allow_unsigned: bool,
+    resources: Option<&ResourcePolicy>,
+) -> CollectionStatus {
+    let max_runtime = collect
+        .schedule
+        .as_ref()
+        .and_then(|schedule| schedule.max_runtime)
+        .unwrap_or(collect.collection_timeout);
+    let cancel = CancelToken::with_timeout(Duration::from_secs(max_runtime));
+    setup_collection(collect, collection_key, allow_unsigned, resources, &cancel)
+}
+
+/// Save a status to send to the server once it is reachable
+pub(crate) fn save_status(
+    collection_path: &str,
+    status: &ScheduledStatus,
+) -> Result<(), CollectError> {
+    let path = status_directory(collection_path).join(format!(
+        "{}_{}_{}.json",
+        status.time,
+        status.collection_id,
+        fastrand::u32(..)
+    ));
+    write_json(&path, status)
+}
+
+/// Send saved statuses using the `complete_collection` endpoint. Stops at the first failure
+pub(crate) fn report_schedules(config: &DaemonConfig) -> usize {
+    let mut reported = 0;
+    for path in json_files(&status_directory(&config.server.daemon.collection_path)) {
+        let status = match read_json::<ScheduledStatus>(&path) {
+            Ok(result) => result,
+            Err(_err) => continue,
+        };
+
+        match config.complete_collection(status.status, status.collection_id) {
+            Ok(response) if !response.endpoint_invalid => {
+                let _ = remove_file(&path);
+                reported += 1;
+            }
+            Ok(_) => {
+                warn!("Endpoint is invalid. Scheduled collection statuses will be sent later");
+                break;
+            }
+            Err(_err) => break,
+        }
+    }
+    reported
+}
+
+/// Directory containing our collection schedules
+fn schedule_directory(collection_path: &str) -> PathBuf {
+    Path::new(collection_path).join("schedules")
+}
+
+/// Directory containing statuses waiting to be sent to the server
+fn status_directory(collection_path: &str) -> PathBuf {
+    schedule_directory(collection_path).join("status")
+}
+
+/// Get all JSON files in a directory. Sorted by filename
+pub(super) fn json_files(directory: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = read_dir(directory) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Read and deserialize a JSON file
+pub(super) fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, CollectError> {
+    let bytes = match read_file(&path.display().to_string()) {
+        Ok(result) => result,
+        Err(_err) => return Err(CollectError::ScheduleFile),
+    };
+    match serde_json::from_slice(&bytes) {
+        Ok(result) => Ok(result),
+        Err(err) => {
+            error!("Could not parse schedule file {}: {err:?}", path.display());
+            Err(CollectError::ScheduleFile)
+        }
+    }
+}
+
+/// Serialize and write a JSON file
+pub(super) fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), CollectError> {
+    let bytes = match serde_json::to_vec(value) {
+        Ok(result) => result,
+        Err(err) => {
+            error!(
+                "Could not serialize schedule file {}: {err:?}",
+                path.display()
+            );
+            return Err(CollectError::ScheduleFile);
+        }
+    };
+    if let Some(parent) = path.parent()
+        && create_directory(&parent.display().to_string()).is_err()
+    {
+        return Err(CollectError::ScheduleFile);
+    }
+    if write_file(&bytes, &path.display().to_string()).is_err() {
+        return Err(CollectError::ScheduleFile);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ScheduledCollection, ScheduledStatus, add_schedule, json_files, read_json,
+        report_schedules, run_schedules, save_status, schedule_directory, spawn_schedules,
+        status_directory,
+    };
+    use crate::{
+        collection::{
+            collect::{CollectEndpoint, CollectResponse, CollectionStatus},
+            error::CollectError,
+            schedule::Schedule,
+        },
+        start::DaemonConfig,
+        utils::{config::server, encoding::base64_encode_standard, time::time_now},
+    };
+    use httpmock::{Method::POST, MockServer};
+    use serde_json::json;
+    use std::{
+        fs::remove_dir_all,
+        path::PathBuf,
+        time::{Duration, Instant},
+    };
+
+    fn test_collection(collection_id: u64, schedule: Schedule) -> CollectResponse {
+        CollectResponse {
+            // Not JSONL with compression. The collection fails quickly with an error
+            collection: base64_encode_standard(b"[output]\nformat = \"json\"\n"),
+            endpoint_invalid: false,
+            collection_id,
+            collection_timeout: 20,
+            signature: String::new(),
+            schedule: Some(schedule),
+        }
+    }
+
+    #[test]
+    fn test_add_schedule() {
+        let path = "./tmp/schedules_add";
+        let _ = remove_dir_all(path);
+        let schedule = Schedule {
+            cron: Some(String::from("0 3 * * *")),
+            ..Default::default()
+        };
+        add_schedule(path, &test_collection(5, schedule)).unwrap();
+
+        let scheduled: ScheduledCollection =
+            read_json(&schedule_directory(path).join("5.json")).unwrap();
+        assert_eq!(scheduled.last_run, 0);
+        assert!(scheduled.next_run > time_now());
+        assert_eq!(scheduled.next_run % 86400, 3 * 3600);
+
+        let mut collect = test_collection(6, Schedule::default());
+        assert_eq!(add_schedule(path, &collect), Err(CollectError::BadSchedule));
+        collect.schedule = None;
+        assert_eq!(add_schedule(path, &collect), Err(CollectError::BadSchedule));
+    }
+
+    #[test]
+    fn test_run_schedules() {
+        let path = "./tmp/schedules_run";
+        let _ = remove_dir_all(path);
+        let schedule = Schedule {
+            interval: Some(3600),
+            ..Default::default()
+        };
+        add_schedule(path, &test_collection(7, schedule)).unwrap();
+
+        assert_eq!(run_schedules(path, None, true, None), 1);
+        // Already ran. Next run is in an hour
+        assert_eq!(run_schedules(path, None, true, None), 0);
+
+        let scheduled: ScheduledCollection =
+            read_json(&schedule_directory(path).join("7.json")).unwrap();
+        assert!(scheduled.last_run != 0);
+        assert_eq!(scheduled.next_run, scheduled.last_run + 3600);
+
+        // Server sends the same schedule again. It should not reset the last run
+        let schedule = scheduled.collection.schedule.clone().unwrap();
+        add_schedule(path, &test_collection(7, schedule)).unwrap();
+        assert_eq!(run_schedules(path, None, true, None), 0);
+
+        let statuses = json_files(&status_directory(path));
+        assert_eq!(statuses.len(), 1);
+        let status: ScheduledStatus = read_json(&statuses[0]).unwrap();
+        assert_eq!(status.collection_id, 7);
+        assert_eq!(status.status, CollectionStatus::Error);
+    }
+
+    #[test]
+    fn test_run_schedules_expired() {
+        let path = "./tmp/schedules_expired";
+        let _ = remove_dir_all(path);
+        let schedule = Schedule {
+            interval: Some(60),
+            expires: Some(1),
+            ..Default::default()
+        };
+        add_schedule(path, &test_collection(8, schedule)).unwrap();
+
+        assert_eq!(run_schedules(path, None, true, None), 0);
+        assert!(json_files(&schedule_directory(path)).is_empty());
+    }
+
+    #[test]
+    fn test_report_schedules() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/configs/server.toml");
+        let path = "./tmp/schedules_report";
+        let _ = remove_dir_all(path);
+
+        let mock_server = MockServer::start();
+        let server_config = server(test_location.to_str().unwrap(), Some("./tmp/artemis")).unwrap();
+        let mut config = DaemonConfig {
+            server: server_config,
+        };
+        config.server.server.port = mock_server.port();
+        config.server.daemon.collection_path = path.to_string();
+
+        for collection_id in [1, 2] {
+            save_status(
+                path,
+                &ScheduledStatus {
+                    collection_id,
+                    status: CollectionStatus::Complete,
+                    time: collection_id,
+                },
+            )
+            .unwrap();
+        }
+
+        // Server is unreachable. Statuses are kept
+        let mut mock_me = mock_server.mock(|when, then| {
+            when.method(POST).path("/v1/endpoint/collections/status");
+            then.status(503);
+        });
+        assert_eq!(report_schedules(&config), 0);
+        mock_me.assert_calls(1);
+        assert_eq!(json_files(&status_directory(path)).len(), 2);
+
+        mock_me.delete();
+        let mock_me = mock_server.mock(|when, then| {
+            when.method(POST)
+                .path("/v1/endpoint/collections/status")
+                .body_includes("\"collection_status\":\"Complete\"");
+            then.status(200)
+                .json_body(json!({ "endpoint_invalid": false }));
+        });
+        assert_eq!(report_schedules(&config), 2);
+        mock_me.assert_calls(2);
+        assert!(json_files(&status_directory(path)).is_empty());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_spawn_schedules_long() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/configs/server.toml");
+        let path = "./tmp/schedules_long";
+        let _ = remove_dir_all(path);
+
+        let mock_server = MockServer::start();
+        let server_config = server(test_location.to_str().unwrap(), Some("./tmp/artemis")).unwrap();
+        let mut config = DaemonConfig {
+            server: server_config,
+        };
+        config.server.server.port = mock_server.port();
+        config.server.server.collection_key = None;
+        config.server.server.allow_unsigned = true;
+        config.server.daemon.collection_path = path.to_string();
+
+        // Hashing everything under /usr takes much longer than the max runtime
+        let schedule = Schedule {
+            interval: Some(3600),
+            max_runtime: Some(10),
+            ..Default::default()
+        };
+        let mut collect = test_collection(9, schedule);
+        collect.collection = base64_encode_standard(
+            b"[output]\nname = \"schedules_long\"\ndirectory = \"./tmp\"\nformat = \"jsonl\"\ncompress = true\nendpoint_id = \"abcd\"\ncollection_id = 9\ndestination = \"local\"\n\n[[artifacts]]\nartifact_name = \"files\"\n[artifacts.files]\nstart_path = \"/usr\"\ndepth = 99\nmd5 = true\nsha1 = true\nsha256 = true\n",
+        );
+        add_schedule(path, &collect).unwrap();
+
+        let mock_me = mock_server.mock(|when, then| {
+            when.method(POST).path("/v1/endpoint/collections");
+            then.status(204);
+        });
+
+        let start = Instant::now();
+        let mut running = None;
+        assert!(spawn_schedules(&config, &mut running));
+        // Polling the server is not blocked by the scheduled collection
+        assert!(matches!(
+            config.collect_request(),
+            Err(CollectError::NoCollection)
+        ));
+        mock_me.assert_calls(1);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(!running.as_ref().unwrap().is_finished());
+
+        // Schedules still running are not started again
+        assert!(!spawn_schedules(&config, &mut running));
+        assert_eq!(running.take().unwrap().join().unwrap(), 1);
+    }
+}
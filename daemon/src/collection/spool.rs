@@ -0,0 +1,108 @@
+use super::{
+    collect::CollectResponse,
+    error::CollectError,
+    scheduler::{json_files, read_json, write_json},
+};
+use crate::utils::encoding::base64_decode_standard;
+use forensics::core::flush_spool;
+use std::{
+    fs::remove_file,
+    path::{Path, PathBuf},
+};
+use tracing::{error, info};
+
+/// Track a collection that ran. Any uploads it left in the spool are retried by `flush_spools`
+pub(crate) fn track_spool(
+    collection_path: &str,
+    collect: &CollectResponse,
+) -> Result<(), CollectError> {
+    let path = spool_directory(collection_path).join(format!("{}.json", collect.collection_id));
+    write_json(&path, collect)
+}
+
+/// Retry uploads left in the spool by tracked collections. Returns the number of collections still waiting on uploads
+///
+/// Collections are no longer tracked once all of their uploads are sent
+pub(crate) fn flush_spools(collection_path: &str) -> usize {
+    let mut remaining = 0;
+    for path in json_files(&spool_directory(collection_path)) {
+        let collect = match read_json::<CollectResponse>(&path) {
+            Ok(result) => result,
+            Err(_err) => {
+                let _ = remove_file(&path);
+                continue;
+            }
+        };
+        let collection_bytes = match base64_decode_standard(&collect.collection) {
+            Ok(result) => result,
+            Err(err) => {
+                error!("Could not decode TOML collection {err:?}");
+                let _ = remove_file(&path);
+                continue;
+            }
+        };
+
+        match flush_spool(&collection_bytes) {
+            Ok(0) => {
+                let _ = remove_file(&path);
+            }
+            Ok(uploads) => {
+                info!(
+                    "Collection {} has {uploads} uploads still waiting in the spool",
+                    collect.collection_id
+                );
+                remaining += 1;
+            }
+            Err(err) => {
+                error!(
+                    "Could not flush spool for collection {}: {err}",
+                    collect.collection_id
+                );
+                let _ = remove_file(&path);
+            }
+        }
+    }
+    remaining
+}
+
+/// Directory containing collections that may have uploads waiting in the spool
+fn spool_directory(collection_path: &str) -> PathBuf {
+    Path::new(collection_path).join("spool")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{flush_spools, spool_directory, track_spool};
+    use crate::{
+        collection::{collect::CollectResponse, scheduler::json_files},
+        utils::encoding::base64_encode_standard,
+    };
+    use std::fs::remove_dir_all;
+
+    #[test]
+    fn test_flush_spools() {
+        let path = "./tmp/spool_flush";
+        let _ = remove_dir_all(path);
+        let local = CollectResponse {
+            collection: base64_encode_standard(
+                b"artifacts = []\n[output]\nname = \"spool\"\ndirectory = \"./tmp\"\nformat = \"jsonl\"\ncompress = true\nendpoint_id = \"abcd\"\ncollection_id = 1\ndestination = \"local\"\n",
+            ),
+            endpoint_invalid: false,
+            collection_id: 1,
+            collection_timeout: 20,
+            signature: String::new(),
+            schedule: None,
+        };
+        let mut bad = local.clone();
+        bad.collection_id = 2;
+        bad.collection = base64_encode_standard(b"not a collection");
+
+        track_spool(path, &local).unwrap();
+        track_spool(path, &bad).unwrap();
+        assert_eq!(json_files(&spool_directory(path)).len(), 2);
+
+        // Local output has nothing to upload and bad collections can never be flushed
+        assert_eq!(flush_spools(path), 0);
+        assert!(json_files(&spool_directory(path)).is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+use super::{error::CollectError, schedule::Schedule};
+use crate::utils::encoding::base64_decode_standard;
+use ring::signature::{ED25519, UnparsedPublicKey};
+use tracing::error;
+
+/// Get the bytes covered by the collection signature
+///
+/// Scheduled collections also sign the schedule. The schedule is appended to the collection TOML after a newline
+/// as compact JSON with every field in order: `cron`, `interval`, `jitter`, `blackout`, `max_runtime`, `expires`
+pub(crate) fn signed_collection(
+    collection: &[u8],
+    schedule: Option<&Schedule>,
+) -> Result<Vec<u8>, CollectError> {
+    let mut signed = collection.to_vec();
+    let Some(schedule) = schedule else {
+        return Ok(signed);
+    };
+    let schedule_bytes = match serde_json::to_vec(schedule) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Could not serialize collection schedule: {err:?}");
+            return Err(CollectError::BadSchedule);
+        }
+    };
+    signed.push(b'\n');
+    signed.extend_from_slice(&schedule_bytes);
+    Ok(signed)
+}
+
+/// Verify the detached Ed25519 signature of a collection TOML against our pinned public key
+///
+/// Both the signature and public key are base64 encoded
+pub(crate) fn verify_collection(
+    collection: &[u8],
+    signature: &str,
+    public_key: &str,
+) -> Result<(), CollectError> {
+    if signature.is_empty() {
+        error!("Collection is not signed");
+        return Err(CollectError::BadSignature);
+    }
+    let key = match base64_decode_standard(public_key) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Could not decode collection public key: {err:?}");
+            return Err(CollectError::BadSignature);
+        }
+    };
+    let sig = match base64_decode_standard(signature) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Could not decode collection signature: {err:?}");
+            return Err(CollectError::BadSignature);
+        }
+    };
+
+    if UnparsedPublicKey::new(&ED25519, &key)
+        .verify(collection, &sig)
+        .is_err()
+    {
+        error!("Collection signature is not valid");
+        return Err(CollectError::BadSignature);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{signed_collection, verify_collection};
+    use crate::{
+        collection::{error::CollectError, schedule::Schedule},
+        utils::encoding::base64_encode_standard,
+    };
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    fn test_key() -> Ed25519KeyPair {
+        Ed25519KeyPair::from_seed_unchecked(&[7; 32]).unwrap()
+    }
+
+    #[test]
+    fn test_verify_collection() {
+        let key = test_key();
+        let collection = b"[output]\nname = \"test\"";
+        let signature = base64_encode_standard(key.sign(collection).as_ref());
+        let public_key = base64_encode_standard(key.public_key().as_ref());
+
+        verify_collection(collection, &signature, &public_key).unwrap();
+    }
+
+    #[test]
+    fn test_verify_collection_tampered() {
+        let key = test_key();
+        let signature = base64_encode_standard(key.sign(b"[output]").as_ref());
+        let public_key = base64_encode_standard(key.public_key().as_ref());
+
+        assert_eq!(
+            verify_collection(b"[output]\nscript", &signature, &public_key),
+            Err(CollectError::BadSignature)
+        );
+    }
+
+    #[test]
+    fn test_signed_collection_schedule() {
+        let key = test_key();
+        let public_key = base64_encode_standard(key.public_key().as_ref());
+        let collection = b"[output]\nname = \"test\"";
+        let mut schedule = Schedule {
+            interval: Some(3600),
+            ..Default::default()
+        };
+
+        let signed = signed_collection(collection, Some(&schedule)).unwrap();
+        assert_eq!(
+            signed,
+            b"[output]\nname = \"test\"\n{\"cron\":null,\"interval\":3600,\"jitter\":0,\"blackout\":[],\"max_runtime\":null,\"expires\":null}"
+        );
+        let signature = base64_encode_standard(key.sign(&signed).as_ref());
+        verify_collection(&signed, &signature, &public_key).unwrap();
+
+        // Changing the schedule breaks the signature
+        schedule.interval = Some(60);
+        let tampered = signed_collection(collection, Some(&schedule)).unwrap();
+        assert_eq!(
+            verify_collection(&tampered, &signature, &public_key),
+            Err(CollectError::BadSignature)
+        );
+        assert_eq!(signed_collection(collection, None).unwrap(), collection);
+    }
+
+    #[test]
+    fn test_verify_collection_unsigned() {
+        let public_key = base64_encode_standard(test_key().public_key().as_ref());
+        assert_eq!(
+            verify_collection(b"[output]", "", &public_key),
+            Err(CollectError::BadSignature)
+        );
+        assert_eq!(
+            verify_collection(b"[output]", "!!", &public_key),
+            Err(CollectError::BadSignature)
+        );
+    }
+}
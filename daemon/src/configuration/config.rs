@@ -1,6 +1,6 @@
 use super::error::ConfigError;
-use crate::{enrollment::enroll::bad_request, start::DaemonConfig};
-use reqwest::{StatusCode, blocking::Client};
+use crate::{enrollment::enroll::bad_request, start::DaemonConfig, utils::client::http_client};
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use tracing::error;
 
@@ -37,7 +37,10 @@ impl ConfigEndpoint for DaemonConfig {
             endpoint_id: self.server.daemon.endpoint_id.clone(),
         };
 
-        let client = Client::new();
+        let client = match http_client(&self.server.server) {
+            Ok(result) => result,
+            Err(_err) => return Err(ConfigError::FailedConfig),
+        };
         let mut builder = client.post(&url).json(&config_req);
         builder = builder.header("accept", "application/json");
         let res = match builder.send() {
@@ -1,7 +1,10 @@
 use super::error::EnrollError;
-use crate::{start::DaemonConfig, utils::info::get_info};
+use crate::{
+    start::DaemonConfig,
+    utils::{client::http_client, info::get_info},
+};
 use common::system::SystemInfo;
-use reqwest::{StatusCode, blocking::Client};
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use tracing::error;
 use uuid::Uuid;
@@ -52,7 +55,10 @@ impl EnrollEndpoint for DaemonConfig {
             info,
         };
 
-        let client = Client::new();
+        let client = match http_client(&self.server.server) {
+            Ok(result) => result,
+            Err(_err) => return Err(EnrollError::FailedEnrollment),
+        };
         let mut builder = client.post(&url).json(&enroll);
         builder = builder.header("accept", "application/json");
 
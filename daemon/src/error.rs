@@ -9,6 +9,7 @@ pub enum DaemonError {
     BadToml,
     DaemonTomlWrite,
     NoPath,
+    TlsConfig,
 }
 
 impl std::error::Error for DaemonError {}
@@ -23,6 +24,7 @@ impl fmt::Display for DaemonError {
             DaemonError::BadToml => write!(f, "Failed to parse TOML data"),
             DaemonError::DaemonTomlWrite => write!(f, "Could not write daemon TOML file"),
             DaemonError::NoPath => write!(f, "Could not find suitable path for configs"),
+            DaemonError::TlsConfig => write!(f, "Could not setup TLS client configuration"),
         }
     }
 }
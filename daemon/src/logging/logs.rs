@@ -1,6 +1,6 @@
 use super::error::LoggingError;
-use crate::{enrollment::enroll::bad_request, start::DaemonConfig};
-use reqwest::{StatusCode, blocking::Client};
+use crate::{enrollment::enroll::bad_request, start::DaemonConfig, utils::client::http_client};
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use std::{
     fs::File,
@@ -37,7 +37,10 @@ impl LoggingEndpoint for DaemonConfig {
         let log_path = format!("{}/daemon.jsonl", self.server.log_path);
         let mut lines = read_log(&log_path)?;
 
-        let client = Client::new();
+        let client = match http_client(&self.server.server) {
+            Ok(result) => result,
+            Err(_err) => return Err(LoggingError::FailedUpload),
+        };
 
         let mut messages = Vec::new();
 
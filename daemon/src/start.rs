@@ -1,16 +1,17 @@
+use forensics::core::CancelToken;
 use std::{
-    sync::{
-        Arc,
-        atomic::{AtomicBool, Ordering},
-    },
-    thread::{sleep, spawn},
+    collections::VecDeque,
+    thread::{JoinHandle, sleep, spawn},
     time::Duration,
 };
+use tracing::info;
 
 use crate::{
     collection::{
-        collect::{CollectEndpoint, CollectionStatus},
+        collect::{CollectEndpoint, CollectResponse, CollectionStatus},
         error::CollectError,
+        scheduler::{register_schedule, report_schedules, spawn_schedules},
+        spool::{flush_spools, track_spool},
     },
     logging::{error::LoggingError, logs::LoggingEndpoint},
     utils::{
@@ -70,8 +71,20 @@ fn start(config: &mut DaemonConfig) {
     let pause = 8;
     let collection_poll = 60;
     let mut attempt = 1;
+    // Retry uploads left in the spool at startup and every 15 minutes
+    let spool_interval = 900;
+    let mut next_flush = 0;
+    let mut flush: Option<JoinHandle<usize>> = None;
+    let mut schedules: Option<JoinHandle<usize>> = None;
+    let mut queue = VecDeque::new();
 
     loop {
+        if time_now() >= next_flush && flush.as_ref().is_none_or(JoinHandle::is_finished) {
+            let collection_path = config.server.daemon.collection_path.clone();
+            flush = Some(spawn(move || flush_spools(&collection_path)));
+            next_flush = time_now() + spool_interval;
+        }
+
         let jitter = fastrand::usize(..11);
         let backoff = if attempt <= max_attempts {
             pause * attempt + jitter
@@ -80,6 +93,10 @@ fn start(config: &mut DaemonConfig) {
             300 + jitter
         };
 
+        // Scheduled collections run locally even if the server is unreachable
+        spawn_schedules(config, &mut schedules);
+        report_schedules(config);
+
         // Upload any logs accumulated
         match config.log_upload() {
             Ok(log_status) => {
@@ -102,36 +119,54 @@ fn start(config: &mut DaemonConfig) {
             }
         };
 
-        let collection = match config.collect_request() {
-            Ok(result) => result,
-            Err(_err) => {
-                attempt += 1;
-                sleep(Duration::from_secs(backoff as u64));
+        if queue.is_empty() {
+            let collection = match config.collect_request() {
+                Ok(result) => result,
+                Err(_err) => {
+                    attempt += 1;
+                    sleep(Duration::from_secs(backoff as u64));
+                    continue;
+                }
+            };
+            attempt = 0;
+
+            if collection.endpoint_invalid {
+                setup_enrollment(config);
                 continue;
             }
-        };
-        attempt = 0;
-        let collection_id = collection.collection_id;
+            accept_collection(config, &mut queue, collection);
+        }
 
-        if collection.endpoint_invalid {
-            setup_enrollment(config);
+        // Collections received while another collection was running are run in order
+        let Some(collection) = queue.pop_front() else {
             continue;
-        }
+        };
+        let collection_id = collection.collection_id;
 
-        let allow_thread = Arc::new(AtomicBool::new(true));
-        let thread_running = Arc::clone(&allow_thread);
         // Allow the collection to run for allocated timer. Default should be 300 seconds
+        // The token is also cancelled once the timeout elapses, even if we are waiting on the server
         let timeout = time_now() + collection.collection_timeout;
+        let cancel = CancelToken::with_timeout(Duration::from_secs(collection.collection_timeout));
+        let thread_cancel = cancel.clone();
+        let collection_key = config.server.server.collection_key.clone();
+        let allow_unsigned = config.server.server.allow_unsigned;
+        let resources = config.server.daemon.resources.clone();
+        let thread_collection = collection.clone();
 
-        let mut status = CollectionStatus::Complete;
         let handle = spawn(move || {
-            while thread_running.load(Ordering::SeqCst) {
-                setup_collection(&collection);
-            }
+            setup_collection(
+                &thread_collection,
+                collection_key.as_deref(),
+                allow_unsigned,
+                resources.as_ref(),
+                &thread_cancel,
+            )
         });
 
         // While thread is running continue to poll the server
         while !handle.is_finished() {
+            spawn_schedules(config, &mut schedules);
+
             let jitter = fastrand::usize(..11);
             let backoff = if attempt <= max_attempts {
                 pause * attempt + jitter
@@ -154,17 +189,23 @@ fn start(config: &mut DaemonConfig) {
                 setup_enrollment(config);
                 continue;
             }
-            // Next poll will be in 60 seconds
-            sleep(Duration::from_secs(collection_poll));
-            let now = time_now();
-            if now > timeout {
-                allow_thread.store(false, Ordering::SeqCst);
-                status = CollectionStatus::Timeout;
+            accept_collection(config, &mut queue, collection);
+
+            // Next poll will be in 60 seconds. Stop waiting early if the collection finishes
+            for _ in 0..collection_poll {
+                if handle.is_finished() {
+                    break;
+                }
+                sleep(Duration::from_secs(1));
+            }
+            if time_now() > timeout {
+                cancel.cancel();
             }
         }
 
-        if let Err(_err) = handle.join() {
-            status = CollectionStatus::Error;
+        let status = handle.join().unwrap_or(CollectionStatus::Error);
+        if status != CollectionStatus::Rejected {
+            let _ = track_spool(&config.server.daemon.collection_path, &collection);
         }
 
         // The final part of a remote forensic collection
@@ -199,3 +240,73 @@ fn start(config: &mut DaemonConfig) {
         sleep(Duration::from_secs(collection_poll));
     }
 }
+
+/// Queue a collection from the server. Recurring collections are saved and run by the daemon instead
+fn accept_collection(
+    config: &DaemonConfig,
+    queue: &mut VecDeque<CollectResponse>,
+    collection: CollectResponse,
+) {
+    // The server is told if the schedule was accepted once the status is reported
+    if collection.schedule.is_some() {
+        register_schedule(config, &collection);
+        return;
+    }
+    info!("Queued collection {}", collection.collection_id);
+    queue.push_back(collection);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DaemonConfig, accept_collection};
+    use crate::{
+        collection::{collect::CollectResponse, schedule::Schedule},
+        utils::{config::server, encoding::base64_encode_standard},
+    };
+    use std::{collections::VecDeque, fs::remove_dir_all, path::PathBuf};
+
+    #[test]
+    fn test_accept_collection() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/configs/server.toml");
+        let server_config = server(test_location.to_str().unwrap(), Some("./tmp/artemis")).unwrap();
+        let mut config = DaemonConfig {
+            server: server_config,
+        };
+        let path = "./tmp/start_accept";
+        let _ = remove_dir_all(path);
+        config.server.daemon.collection_path = path.to_string();
+        config.server.server.collection_key = None;
+        config.server.server.allow_unsigned = true;
+
+        let mut collection = CollectResponse {
+            collection: base64_encode_standard(b"[output]\nformat = \"json\"\n"),
+            endpoint_invalid: false,
+            collection_id: 1,
+            collection_timeout: 20,
+            signature: String::new(),
+            schedule: None,
+        };
+        let mut queue = VecDeque::new();
+        accept_collection(&config, &mut queue, collection.clone());
+        collection.collection_id = 2;
+        accept_collection(&config, &mut queue, collection.clone());
+
+        // Schedules are saved instead of queued
+        collection.collection_id = 3;
+        collection.schedule = Some(Schedule {
+            interval: Some(3600),
+            ..Default::default()
+        });
+        accept_collection(&config, &mut queue, collection);
+
+        assert_eq!(
+            queue
+                .iter()
+                .map(|collect| collect.collection_id)
+                .collect::<Vec<u64>>(),
+            vec![1, 2]
+        );
+        assert!(PathBuf::from(path).join("schedules/3.json").is_file());
+    }
+}
@@ -0,0 +1,113 @@
+use super::config::{Server, read_file};
+use crate::error::DaemonError;
+use reqwest::{
+    Certificate, Identity,
+    blocking::{Client, ClientBuilder},
+};
+use tracing::error;
+
+/// Create the HTTP client used for every request to our server
+///
+/// Supports mutual TLS with a client certificate and pinning the server certificate
+pub(crate) fn http_client(server: &Server) -> Result<Client, DaemonError> {
+    let mut builder = ClientBuilder::new()
+        .tls_danger_accept_invalid_certs(server.ignore_ssl && server.server_cert.is_none());
+
+    if let Some(path) = &server.client_cert {
+        // The PEM file must contain both the client certificate and private key
+        let pem = read_file(path)?;
+        let identity = match Identity::from_pem(&pem) {
+            Ok(result) => result,
+            Err(err) => {
+                error!("Failed to parse client certificate {path}: {err:?}");
+                return Err(DaemonError::TlsConfig);
+            }
+        };
+        builder = builder.identity(identity);
+    }
+
+    if let Some(path) = &server.server_cert {
+        let pem = read_file(path)?;
+        let certs = match Certificate::from_pem_bundle(&pem) {
+            Ok(result) if !result.is_empty() => result,
+            Ok(_) => {
+                error!("No certificates found in pinned server certificate {path}");
+                return Err(DaemonError::TlsConfig);
+            }
+            Err(err) => {
+                error!("Failed to parse pinned server certificate {path}: {err:?}");
+                return Err(DaemonError::TlsConfig);
+            }
+        };
+        // Only trust the pinned certificate. System root certificates are not used
+        builder = builder.tls_certs_only(certs);
+    }
+
+    match builder.build() {
+        Ok(result) => Ok(result),
+        Err(err) => {
+            error!("Failed to build HTTP client: {err:?}");
+            Err(DaemonError::TlsConfig)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::http_client;
+    use crate::utils::config::Server;
+    use std::path::PathBuf;
+
+    fn test_server() -> Server {
+        Server {
+            url: String::from("https://127.0.0.1"),
+            port: 8000,
+            ignore_ssl: false,
+            enrollment: String::new(),
+            collections: String::new(),
+            config: String::new(),
+            logging: String::new(),
+            version: 1,
+            key: String::new(),
+            collection_key: None,
+            client_cert: None,
+            server_cert: None,
+            allow_unsigned: false,
+        }
+    }
+
+    #[test]
+    fn test_http_client() {
+        let _ = http_client(&test_server()).unwrap();
+    }
+
+    #[test]
+    fn test_http_client_tls() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/certs");
+
+        let mut server = test_server();
+        server.client_cert = Some(test_location.join("client.pem").display().to_string());
+        server.server_cert = Some(test_location.join("server.crt").display().to_string());
+        let _ = http_client(&server).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "TlsConfig")]
+    fn test_http_client_bad_cert() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/configs/server.toml");
+
+        let mut server = test_server();
+        server.server_cert = Some(test_location.display().to_string());
+        let _ = http_client(&server).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "ReadFile")]
+    fn test_http_client_missing_cert() {
+        let mut server = test_server();
+        server.client_cert = Some(String::from("./tests/certs/missing.pem"));
+        let _ = http_client(&server).unwrap();
+    }
+}
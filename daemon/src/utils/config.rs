@@ -3,6 +3,7 @@ use super::{
     logging::setup_logging,
 };
 use crate::{error::DaemonError, utils::env::get_env_value};
+use forensics::structs::toml::ResourcePolicy;
 use serde::{Deserialize, Serialize};
 use std::{
     fs::{create_dir_all, read, write},
@@ -31,12 +32,27 @@ pub(crate) struct Server {
     pub(crate) logging: String,
     pub(crate) version: u8,
     pub(crate) key: String,
+    /// Base64 Ed25519 public key used to verify collection signatures
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) collection_key: Option<String>,
+    /// PEM file containing the client certificate and private key for mutual TLS
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) client_cert: Option<String>,
+    /// PEM file containing the pinned server certificate. Only this certificate is trusted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) server_cert: Option<String>,
+    /// Run collections without a signature if no `collection_key` is configured. Disabled by default
+    #[serde(default)]
+    pub(crate) allow_unsigned: bool,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
 pub(crate) struct Daemon {
     pub(crate) endpoint_id: String,
     pub(crate) collection_path: String,
+    /// Resource limits applied to every collection. Stricter limits in a collection are kept
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) resources: Option<ResourcePolicy>,
 }
 
 /// Parse the provided `Server` TOML config file
@@ -155,7 +171,7 @@ pub(crate) fn have_config() -> Option<String> {
 }
 
 /// Read the provided file
-fn read_file(path: &str) -> Result<Vec<u8>, DaemonError> {
+pub(crate) fn read_file(path: &str) -> Result<Vec<u8>, DaemonError> {
     let bytes = match read(path) {
         Ok(result) => result,
         Err(err) => {
@@ -168,7 +184,7 @@ fn read_file(path: &str) -> Result<Vec<u8>, DaemonError> {
 }
 
 /// Create directory and any parents
-fn create_directory(path: &str) -> Result<(), DaemonError> {
+pub(crate) fn create_directory(path: &str) -> Result<(), DaemonError> {
     match create_dir_all(path) {
         Ok(result) => Ok(result),
         Err(err) => {
@@ -179,7 +195,7 @@ fn create_directory(path: &str) -> Result<(), DaemonError> {
 }
 
 /// Write data to the provided path
-fn write_file(bytes: &[u8], path: &str) -> Result<(), DaemonError> {
+pub(crate) fn write_file(bytes: &[u8], path: &str) -> Result<(), DaemonError> {
     match write(path, bytes) {
         Ok(result) => Ok(result),
         Err(err) => {
@@ -258,6 +274,14 @@ mod tests {
         assert_eq!(result.server.enrollment, "endpoint/enroll");
         assert_eq!(result.server.port, 8000);
         assert_eq!(result.server.key, "my key");
+        assert_eq!(result.server.collection_key, None);
+        assert!(!result.server.allow_unsigned);
+
+        let resources = result.daemon.resources.unwrap();
+        assert_eq!(resources.max_cpu_percent, Some(50));
+        assert_eq!(resources.max_read_bytes_per_second, Some(52428800));
+        assert_eq!(resources.max_memory_bytes, None);
+        assert!(resources.low_priority);
     }
 
     #[test]
@@ -270,6 +294,7 @@ mod tests {
             daemon: Daemon {
                 endpoint_id: String::from("test"),
                 collection_path: String::from("./tmp/artemis/collections"),
+                resources: None,
             },
             server: Server {
                 url: String::new(),
@@ -281,6 +306,10 @@ mod tests {
                 logging: String::new(),
                 version: 1,
                 key: String::new(),
+                collection_key: None,
+                client_cert: None,
+                server_cert: None,
+                allow_unsigned: false,
             },
             log_path: String::new(),
             log_level: String::from("warn"),
@@ -1,3 +1,4 @@
+pub(crate) mod client;
 pub(crate) mod config;
 pub(crate) mod encoding;
 pub(crate) mod env;
@@ -1,15 +1,22 @@
 use super::{
-    config::{ServerToml, daemon},
+    config::{Server, ServerToml, daemon},
     encoding::base64_decode_standard,
 };
 use crate::{
-    collection::collect::{CollectResponse, CollectionStatus},
+    collection::{
+        collect::{CollectResponse, CollectionStatus},
+        verify::{signed_collection, verify_collection},
+    },
     configuration::config::ConfigEndpoint,
     enrollment::enroll::EnrollEndpoint,
     start::DaemonConfig,
 };
+use forensics::{
+    core::{CancelToken, TomlError},
+    structs::toml::ResourcePolicy,
+};
 use std::{str::from_utf8, thread::sleep, time::Duration};
-use tracing::error;
+use tracing::{error, warn};
 
 /// Enroll the endpoint to our server based on parsed Server.toml file
 pub(crate) fn setup_enrollment(config: &mut DaemonConfig) {
@@ -47,14 +54,20 @@ pub(crate) fn setup_enrollment(config: &mut DaemonConfig) {
     config.server.daemon.endpoint_id = enroll.endpoint_id;
 }
 
-/// Process our collection request
-pub(crate) fn setup_collection(collect: &CollectResponse) -> CollectionStatus {
-    let collection_bytes = match base64_decode_standard(&collect.collection) {
+/// Process our collection request. The collection stops early if `cancel` is cancelled
+///
+/// The collection must pass `verify_request` before it is run.
+/// If `resources` is provided the collection is throttled to stay under the daemon resource limits
+pub(crate) fn setup_collection(
+    collect: &CollectResponse,
+    collection_key: Option<&str>,
+    allow_unsigned: bool,
+    resources: Option<&ResourcePolicy>,
+    cancel: &CancelToken,
+) -> CollectionStatus {
+    let collection_bytes = match verify_request(collect, collection_key, allow_unsigned) {
         Ok(result) => result,
-        Err(err) => {
-            error!("Could not decode TOML collection {err:?}");
-            return CollectionStatus::Error;
-        }
+        Err(status) => return status,
     };
 
     // Validate the output is JSONL and compressed
@@ -65,14 +78,68 @@ pub(crate) fn setup_collection(collect: &CollectResponse) -> CollectionStatus {
         return CollectionStatus::Error;
     }
 
-    if let Err(err) = forensics::core::parse_toml_data(&collection_bytes) {
-        error!("Could not process TOML collection {err:?}");
-        return CollectionStatus::Error;
+    let result = match resources {
+        Some(policy) => {
+            forensics::core::parse_toml_data_with_policy(&collection_bytes, cancel, policy)
+        }
+        None => forensics::core::parse_toml_data_with_cancel(&collection_bytes, cancel),
+    };
+    match result {
+        Ok(_) => {}
+        Err(TomlError::Cancelled) => {
+            error!("TOML collection timed out");
+            return CollectionStatus::Timeout;
+        }
+        Err(err) => {
+            error!("Could not process TOML collection {err:?}");
+            return CollectionStatus::Error;
+        }
     }
 
     CollectionStatus::Complete
 }
 
+/// Decode a collection request and check its signature. Returns the collection TOML bytes
+///
+/// The collection and any schedule must have a valid signature for the configured `collection_key`.
+/// Unsigned collections are rejected unless `allow_unsigned` is enabled and no `collection_key` is configured.
+pub(crate) fn verify_request(
+    collect: &CollectResponse,
+    collection_key: Option<&str>,
+    allow_unsigned: bool,
+) -> Result<Vec<u8>, CollectionStatus> {
+    let collection_bytes = match base64_decode_standard(&collect.collection) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Could not decode TOML collection {err:?}");
+            return Err(CollectionStatus::Error);
+        }
+    };
+
+    if let Some(key) = collection_key {
+        let verified = signed_collection(&collection_bytes, collect.schedule.as_ref())
+            .and_then(|signed| verify_collection(&signed, &collect.signature, key));
+        if verified.is_err() {
+            error!(
+                "Rejecting collection {}. Signature could not be verified",
+                collect.collection_id
+            );
+            return Err(CollectionStatus::Rejected);
+        }
+    } else if allow_unsigned {
+        warn!(
+            "No collection_key configured and allow_unsigned is enabled. Collection signatures are not verified"
+        );
+    } else {
+        error!(
+            "Rejecting collection {}. No collection_key configured to verify the signature",
+            collect.collection_id
+        );
+        return Err(CollectionStatus::Rejected);
+    }
+    Ok(collection_bytes)
+}
+
 /// Get a daemon configuration from our server. If none is provided we will generate a default config
 pub(crate) fn setup_config(config: &mut DaemonConfig) {
     let daemon_config = match config.config_request() {
@@ -105,11 +172,21 @@ pub(crate) fn setup_config(config: &mut DaemonConfig) {
     if toml_config.daemon.endpoint_id.is_empty() {
         toml_config.daemon.endpoint_id = config.server.daemon.endpoint_id.clone();
     }
+    keep_local_trust(&config.server.server, &mut toml_config.server);
 
     config.server = toml_config;
     setup_daemon(config);
 }
 
+/// Keep the locally pinned trust settings. A server provided config cannot change how the daemon verifies the server or collections
+fn keep_local_trust(local: &Server, remote: &mut Server) {
+    remote.collection_key.clone_from(&local.collection_key);
+    remote.client_cert.clone_from(&local.client_cert);
+    remote.server_cert.clone_from(&local.server_cert);
+    remote.ignore_ssl = local.ignore_ssl;
+    remote.allow_unsigned = local.allow_unsigned;
+}
+
 /// Setup default config directories for the daemon
 fn setup_daemon(daemon_config: &mut DaemonConfig) {
     match daemon(&mut daemon_config.server, None) {
@@ -119,3 +196,164 @@ fn setup_daemon(daemon_config: &mut DaemonConfig) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{setup_collection, setup_config, verify_request};
+    use crate::{
+        collection::{
+            collect::{CollectResponse, CollectionStatus},
+            schedule::Schedule,
+            verify::signed_collection,
+        },
+        start::DaemonConfig,
+        utils::{config::server, encoding::base64_encode_standard},
+    };
+    use forensics::core::CancelToken;
+    use httpmock::{Method::POST, MockServer};
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    fn test_collection(signature: String) -> CollectResponse {
+        CollectResponse {
+            collection: base64_encode_standard(b"[output]\nformat = \"json\"\n"),
+            endpoint_invalid: false,
+            collection_id: 1,
+            collection_timeout: 20,
+            signature,
+            schedule: None,
+        }
+    }
+
+    #[test]
+    fn test_setup_collection_rejected() {
+        let key = Ed25519KeyPair::from_seed_unchecked(&[7; 32]).unwrap();
+        let public_key = base64_encode_standard(key.public_key().as_ref());
+        let signature = base64_encode_standard(key.sign(b"something else").as_ref());
+
+        let status = setup_collection(
+            &test_collection(signature),
+            Some(&public_key),
+            false,
+            None,
+            &CancelToken::new(),
+        );
+        assert!(matches!(status, CollectionStatus::Rejected));
+
+        let status = setup_collection(
+            &test_collection(String::new()),
+            Some(&public_key),
+            false,
+            None,
+            &CancelToken::new(),
+        );
+        assert!(matches!(status, CollectionStatus::Rejected));
+    }
+
+    #[test]
+    fn test_setup_collection_verified() {
+        let key = Ed25519KeyPair::from_seed_unchecked(&[7; 32]).unwrap();
+        let public_key = base64_encode_standard(key.public_key().as_ref());
+        let signature = base64_encode_standard(key.sign(b"[output]\nformat = \"json\"\n").as_ref());
+
+        // Signature is valid. But the collection is not JSONL with compression
+        let status = setup_collection(
+            &test_collection(signature),
+            Some(&public_key),
+            false,
+            None,
+            &CancelToken::new(),
+        );
+        assert!(matches!(status, CollectionStatus::Error));
+    }
+
+    #[test]
+    fn test_verify_request_schedule() {
+        let key = Ed25519KeyPair::from_seed_unchecked(&[7; 32]).unwrap();
+        let public_key = base64_encode_standard(key.public_key().as_ref());
+        let mut collect = test_collection(String::new());
+        collect.schedule = Some(Schedule {
+            interval: Some(86400),
+            ..Default::default()
+        });
+        let signed =
+            signed_collection(b"[output]\nformat = \"json\"\n", collect.schedule.as_ref()).unwrap();
+        collect.signature = base64_encode_standard(key.sign(&signed).as_ref());
+
+        let bytes = verify_request(&collect, Some(&public_key), false).unwrap();
+        assert_eq!(bytes, b"[output]\nformat = \"json\"\n");
+
+        // Schedule was changed after the collection was signed
+        collect.schedule = Some(Schedule {
+            interval: Some(60),
+            ..Default::default()
+        });
+        assert!(matches!(
+            verify_request(&collect, Some(&public_key), false),
+            Err(CollectionStatus::Rejected)
+        ));
+    }
+
+    #[test]
+    fn test_setup_collection_unsigned() {
+        // Unsigned collections are rejected by default
+        let status = setup_collection(
+            &test_collection(String::new()),
+            None,
+            false,
+            None,
+            &CancelToken::new(),
+        );
+        assert!(matches!(status, CollectionStatus::Rejected));
+
+        // Collection runs when unsigned collections are allowed. But the collection is not JSONL with compression
+        let status = setup_collection(
+            &test_collection(String::new()),
+            None,
+            true,
+            None,
+            &CancelToken::new(),
+        );
+        assert!(matches!(status, CollectionStatus::Error));
+    }
+
+    #[test]
+    fn test_setup_config_keeps_trust() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/configs/server.toml");
+
+        let mock_server = MockServer::start();
+        let port = mock_server.port();
+
+        // Server provided config tries to drop the collection_key and allow unsigned collections
+        let remote = format!(
+            "log_path = \"./tmp/artemis\"\nlog_level = \"info\"\n\n[server]\nurl = \"http://127.0.0.1\"\nport = {port}\nignore_ssl = true\nenrollment = \"endpoint/enroll\"\ncollections = \"endpoint/collections\"\nconfig = \"endpoint/config\"\nlogging = \"endpoint/logging\"\nversion = 1\nkey = \"my key\"\nallow_unsigned = true\n\n[daemon]\nendpoint_id = \"\"\ncollection_path = \"./tmp/artemis/collections\"\n"
+        );
+        let mock_me = mock_server.mock(|when, then| {
+            when.method(POST).path("/v1/endpoint/config");
+            then.status(200)
+                .header("content-type", "application/json")
+                .json_body(json!({ "config": base64_encode_standard(remote.as_bytes()), "endpoint_invalid": false }));
+        });
+
+        let server_config = server(test_location.to_str().unwrap(), Some("./tmp/artemis")).unwrap();
+        let mut config = DaemonConfig {
+            server: server_config,
+        };
+        config.server.server.port = port;
+        config.server.server.collection_key = Some(String::from("pinned key"));
+
+        setup_config(&mut config);
+        mock_me.assert();
+
+        assert_eq!(config.server.log_level, "info");
+        assert_eq!(config.server.daemon.endpoint_id, "my important key");
+        assert_eq!(
+            config.server.server.collection_key.as_deref(),
+            Some("pinned key")
+        );
+        assert!(!config.server.server.ignore_ssl);
+        assert!(!config.server.server.allow_unsigned);
+    }
+}
@@ -36,7 +36,37 @@ use tracing::info;
 /// `Source path` - None
 /// `Inner path` - `C:\\Users\\test.txt`
 ///
-/// Supported schemes are: `zip`, `ntfs`, `host`
+/// Example: `image:disk.dd!p2/Windows/System32/config/SYSTEM`
+///
+/// `Scheme` - `image`
+/// `Source path` - `disk.dd`
+/// `Inner path` - `p2/Windows/System32/config/SYSTEM`. Partitions are numbered from the partition table
+///
+/// Example: `qcow:vm.qcow2!p1/var/log/auth.log`
+///
+/// `Scheme` - `qcow`. Partitions are numbered the same as `image`
+/// `Source path` - `vm.qcow2`
+/// `Inner path` - `p1/var/log/auth.log`
+///
+/// Example: `ewf:case.E01!p2/Windows/System32/config/SYSTEM`
+///
+/// `Scheme` - `ewf`. Remaining segments (E02, E03, ...) are opened automatically
+/// `Source path` - `case.E01`
+/// `Inner path` - `p2/Windows/System32/config/SYSTEM`
+///
+/// Example: `vhdx:child.vhdx!p3/Windows/System32/config/SOFTWARE`
+///
+/// `Scheme` - `vhd`, `vhdx`, or `vmdk`. Parent disks and split extents are opened automatically
+/// `Source path` - `child.vhdx`
+/// `Inner path` - `p3/Windows/System32/config/SOFTWARE`
+///
+/// Example: `vss:C@3!/Windows/System32/config/SYSTEM`
+///
+/// `Scheme` - `vss`. Reads the volume as it was when the shadow copy was created
+/// `Source path` - `C@3`. Drive letter or path to a volume image, and the store number. The oldest snapshot is store 1
+/// `Inner path` - `/Windows/System32/config/SYSTEM`
+///
+/// Supported schemes are: `zip`, `ntfs`, `host`, `image`, `qcow`, `ewf`, `vhd`, `vhdx`, `vmdk`, `vss`
 pub(crate) struct Accessor {
     /// The configuration for the `Accessor`
     config: AccessorConfig,
@@ -85,6 +115,19 @@ impl Accessor {
         read_dir_on_source(&self.cache, &source_id, &loc.inner_path)
     }
 
+    /// List only the files in a directory. Returns a location string for each file
+    ///
+    /// Example: `vss:C@3!/Windows/Prefetch` -> `vss:C@3!/Windows/Prefetch/CMD.EXE-4A81B364.pf`
+    pub(crate) fn list_files(&mut self, location: &str) -> AccessorResult<Vec<String>> {
+        let directory = location.trim_end_matches(['/', '\\']);
+        Ok(self
+            .read_dir(location)?
+            .into_iter()
+            .filter(|entry| entry.is_file())
+            .map(|entry| format!("{directory}/{}", entry.name))
+            .collect())
+    }
+
     /// Read bytes for a `FileHandle` from glob/listing
     pub(crate) fn read_file_handle(&mut self, handle: &FileHandle) -> AccessorResult<Vec<u8>> {
         let source_id = source_id_from_file_locator(&handle.locator)?;
@@ -159,7 +202,7 @@ impl Accessor {
 
     /// Open a source for repeated reads
     ///
-    /// Examples: `host:`, `ntfs:C:`, `zip:/path/archive.zip`
+    /// Examples: `host:`, `ntfs:C:`, `zip:/path/archive.zip`, `image:/path/disk.dd`, `qcow:/path/vm.qcow2`, `ewf:/path/case.E01`, `vmdk:/path/vm.vmdk`
     pub(crate) fn open_source(&mut self, source: &str) -> AccessorResult<SourceHandle> {
         let loc = Location::parse_source(source)?;
         let source_id = build_source(&loc, &self.config, &mut self.cache)?;
@@ -416,6 +459,133 @@ mod tests {
         assert_eq!(bytes, 10);
     }
 
+    #[test]
+    fn test_image_accessor() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/raw/gpt_ext4.raw");
+
+        let mut access = Accessor::with_defaults();
+        let results = access
+            .globfs(&format!("image:{}!p2/etc/*", test_location.display()))
+            .unwrap();
+        assert_eq!(results.len(), 2);
+
+        for entry in results {
+            if entry.meta.display_path.ends_with("!p2/etc/hostname") {
+                let bytes = access
+                    .read_file_handle(entry.handle.as_file().unwrap())
+                    .unwrap();
+                assert_eq!(bytes, b"artemis-image\n");
+            }
+        }
+
+        let source = access
+            .open_source(&format!("image:{}", test_location.display()))
+            .unwrap();
+        let mut reader = access
+            .source_open_reader(&source, "p2/home/analyst/.bash_history")
+            .unwrap();
+        let mut buf = [0u8; 6];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"ls -la");
+    }
+
+    #[test]
+    fn test_qcow_accessor() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/qcow/gpt_ext4.qcow2");
+
+        let mut access = Accessor::with_defaults();
+        let bytes = access
+            .read_file(&format!("qcow:{}!p2/etc/hostname", test_location.display()))
+            .unwrap();
+        assert_eq!(bytes, b"artemis-image\n");
+
+        let results = access
+            .globfs(&format!(
+                "qcow:{}!**/.bash_history",
+                test_location.display()
+            ))
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        let bytes = access
+            .read_file_handle(results[0].handle.as_file().unwrap())
+            .unwrap();
+        assert_eq!(bytes, b"ls -la\ncat /etc/passwd\n");
+    }
+
+    #[test]
+    fn test_ewf_accessor() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/ewf/gpt_ext4.E01");
+
+        let mut access = Accessor::with_defaults();
+        let bytes = access
+            .read_file(&format!("ewf:{}!p2/etc/hostname", test_location.display()))
+            .unwrap();
+        assert_eq!(bytes, b"artemis-image\n");
+
+        let results = access
+            .globfs(&format!("ewf:{}!p2/etc/*", test_location.display()))
+            .unwrap();
+        assert_eq!(results.len(), 2);
+    }
+
+    #[test]
+    fn test_virtual_disk_accessor() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks");
+
+        let mut access = Accessor::with_defaults();
+        let tests = [
+            ("vhd", "vhd/gpt_ext4_fixed.vhd", "artemis-image"),
+            ("vhdx", "vhdx/gpt_ext4_child.vhdx", "artemis-child"),
+            ("vmdk", "vmdk/gpt_ext4.vmdk", "artemis-image"),
+        ];
+        for (scheme, path, hostname) in tests {
+            let disk = test_location.join(path);
+            let bytes = access
+                .read_file(&format!("{scheme}:{}!p2/etc/hostname", disk.display()))
+                .unwrap();
+            assert_eq!(bytes, format!("{hostname}\n").as_bytes());
+
+            let results = access
+                .globfs(&format!("{scheme}:{}!p2/etc/*", disk.display()))
+                .unwrap();
+            assert_eq!(results.len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_vss_accessor() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/vss/ext4_vss.raw");
+
+        let mut access = Accessor::with_defaults();
+        let bytes = access
+            .read_file(&format!("vss:{}@1!/etc/hostname", test_location.display()))
+            .unwrap();
+        assert_eq!(bytes, b"artemis-vss1\n");
+
+        let results = access
+            .globfs(&format!("vss:{}@2!/home/*.txt", test_location.display()))
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        let bytes = access
+            .read_file_handle(results[0].handle.as_file().unwrap())
+            .unwrap();
+        assert_eq!(bytes, b"snap notes\n");
+
+        let files = access
+            .list_files(&format!("vss:{}@2!/home/", test_location.display()))
+            .unwrap();
+        let notes = files
+            .iter()
+            .find(|file| file.ends_with("@2!/home/notes.txt"))
+            .unwrap();
+        assert_eq!(access.read_file(notes).unwrap(), b"snap notes\n");
+    }
+
     #[test]
     #[cfg(windows)]
     fn test_ntfs_accessor_read_zip() {
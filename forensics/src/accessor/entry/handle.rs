@@ -58,7 +58,10 @@ impl FileHandle {
     pub(crate) fn display_path(&self) -> String {
         match &self.locator {
             FileLocator::Host { path } => path.display().to_string(),
-            FileLocator::Ntfs { display_path, .. } => display_path.clone(),
+            FileLocator::Ntfs { display_path, .. }
+            | FileLocator::Ext4 { display_path, .. }
+            | FileLocator::Image { display_path, .. }
+            | FileLocator::Vss { display_path, .. } => display_path.clone(),
             FileLocator::Zip { archive, entry, .. } => {
                 format!("zip:{}!{entry}", archive.display())
             }
@@ -88,7 +91,10 @@ impl DirHandle {
     pub(crate) fn display_path(&self) -> String {
         match &self.locator {
             DirLocator::Host { path } => path.display().to_string(),
-            DirLocator::Ntfs { display_path, .. } => display_path.clone(),
+            DirLocator::Ntfs { display_path, .. }
+            | DirLocator::Ext4 { display_path, .. }
+            | DirLocator::Image { display_path, .. }
+            | DirLocator::Vss { display_path, .. } => display_path.clone(),
             DirLocator::Zip {
                 archive, prefix, ..
             } => {
@@ -10,6 +10,10 @@ pub(crate) enum SourceId {
     RawNtfs(char),
     /// A zip file
     Zip(PathBuf),
+    /// A disk image
+    Image(DiskImage),
+    /// A Volume Shadow Copy snapshot
+    Vss(ShadowCopy),
 }
 
 impl SourceId {
@@ -19,10 +23,97 @@ impl SourceId {
             SourceId::Host => String::from("host"),
             SourceId::RawNtfs(drive) => format!("ntfs:{drive}:"),
             SourceId::Zip(path) => format!("zip:{}", path.display()),
+            SourceId::Image(image) => image.display(),
+            SourceId::Vss(snapshot) => snapshot.display(),
         }
     }
 }
 
+/// Container formats supported for disk images
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum DiskFormat {
+    /// Raw disk image (dd, img, raw)
+    Raw,
+    /// QEMU QCOW2 disk image
+    Qcow,
+    /// Expert Witness (E01) evidence container
+    Ewf,
+    /// Virtual Hard Disk used by Virtual PC and Azure
+    Vhd,
+    /// Virtual Hard Disk v2 used by Hyper-V
+    Vhdx,
+    /// `VMware` Virtual Machine Disk
+    Vmdk,
+}
+
+impl DiskFormat {
+    /// Return the scheme used to access the `DiskFormat`
+    pub(crate) fn scheme(&self) -> &str {
+        match self {
+            DiskFormat::Raw => "image",
+            DiskFormat::Qcow => "qcow",
+            DiskFormat::Ewf => "ewf",
+            DiskFormat::Vhd => "vhd",
+            DiskFormat::Vhdx => "vhdx",
+            DiskFormat::Vmdk => "vmdk",
+        }
+    }
+}
+
+/// A disk image file and its container format
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct DiskImage {
+    /// Container format of the disk image
+    pub(crate) format: DiskFormat,
+    /// Path to the disk image
+    pub(crate) path: PathBuf,
+}
+
+impl DiskImage {
+    /// Create a new `DiskImage`
+    pub(crate) fn new(format: DiskFormat, path: PathBuf) -> Self {
+        Self { format, path }
+    }
+
+    /// Return the `DiskImage` as a source string. Example: `qcow:/vms/disk.qcow2`
+    pub(crate) fn display(&self) -> String {
+        format!("{}:{}", self.format.scheme(), self.path.display())
+    }
+}
+
+/// A Volume Shadow Copy snapshot of a volume
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ShadowCopy {
+    /// Drive letter of a live volume or path to a volume image. Example: `C` or `/cases/volume.raw`
+    pub(crate) volume: String,
+    /// Shadow copy store number. The oldest snapshot is store 1
+    pub(crate) store: u32,
+}
+
+impl ShadowCopy {
+    /// Create a new `ShadowCopy`
+    pub(crate) fn new(volume: &str, store: u32) -> Self {
+        Self {
+            volume: volume.to_string(),
+            store,
+        }
+    }
+
+    /// Return the drive letter if the snapshot is on a live volume
+    pub(crate) fn drive(&self) -> Option<char> {
+        let mut chars = self.volume.chars();
+        match (chars.next(), chars.next()) {
+            (Some(drive), None) if drive.is_ascii_alphabetic() => Some(drive),
+            _ => None,
+        }
+    }
+
+    /// Return the `ShadowCopy` as a source string. Example: `vss:C@3`
+    pub(crate) fn display(&self) -> String {
+        format!("vss:{}@{}", self.volume, self.store)
+    }
+}
+
 /// Raw file reference to a file/directory on NTFS
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) struct NtfsEntryRef {
@@ -68,6 +159,33 @@ pub(crate) enum FileLocator {
         /// Path to the file in the zip
         entry: String,
     },
+    /// Ext4 file access requires the inode and human readable path
+    Ext4 {
+        /// Inode of the file
+        inode: u32,
+        /// Human readable path
+        display_path: String,
+    },
+    /// Disk image file access requires the image path, partition number, and locator inside the partition
+    Image {
+        /// Disk image file and format
+        image: DiskImage,
+        /// Partition number in the disk image
+        partition: u32,
+        /// Locator for the file on the partition filesystem
+        inner: Box<FileLocator>,
+        /// Human readable path
+        display_path: String,
+    },
+    /// Shadow copy file access requires the snapshot and locator inside the snapshot volume
+    Vss {
+        /// Volume and shadow copy store
+        snapshot: ShadowCopy,
+        /// Locator for the file on the snapshot filesystem
+        inner: Box<FileLocator>,
+        /// Human readable path
+        display_path: String,
+    },
 }
 
 /// Requirements to locate a directory from a provided source
@@ -94,4 +212,28 @@ pub(crate) enum DirLocator {
         /// Path to the directory in the zip
         prefix: String,
     },
+    Ext4 {
+        /// Inode of the directory
+        inode: u32,
+        /// Human readable path
+        display_path: String,
+    },
+    Image {
+        /// Disk image file and format
+        image: DiskImage,
+        /// Partition number in the disk image
+        partition: u32,
+        /// Locator for the directory on the partition filesystem
+        inner: Box<DirLocator>,
+        /// Human readable path
+        display_path: String,
+    },
+    Vss {
+        /// Volume and shadow copy store
+        snapshot: ShadowCopy,
+        /// Locator for the directory on the snapshot filesystem
+        inner: Box<DirLocator>,
+        /// Human readable path
+        display_path: String,
+    },
 }
@@ -79,6 +79,13 @@ pub(crate) enum AccessorError {
         /// Human explanation of error
         reason: String,
     },
+    /// Ext4 parsing or raw read failure
+    Ext4 {
+        /// Path if known
+        path: Option<String>,
+        /// Human explanation of error
+        reason: String,
+    },
     /// Zip archive read failure
     Zip {
         /// Archive path if known
@@ -160,6 +167,13 @@ impl fmt::Display for AccessorError {
                     write!(f, "NTFS error: {reason}")
                 }
             }
+            AccessorError::Ext4 { path, reason } => {
+                if let Some(ext4_path) = path {
+                    write!(f, "ext4 error at {ext4_path}: {reason}")
+                } else {
+                    write!(f, "ext4 error: {reason}")
+                }
+            }
             AccessorError::Zip { archive, reason } => {
                 if let Some(archive_path) = archive {
                     write!(f, "zip error for {}: {reason}", archive_path.display())
@@ -0,0 +1,503 @@
+use crate::accessor::{
+    entry::{
+        handle::{DirEntry, DirHandle, EntryKind, EntryMeta, FileHandle, ItemHandle},
+        locator::{DirLocator, FileLocator},
+    },
+    error::{AccessorError, AccessorResult},
+    io::reader::AccessorReader,
+    location::path::InnerPath,
+};
+use ext4_fs::{
+    extfs::{Ext4Reader, Ext4ReaderAction},
+    structs::FileType,
+};
+use std::{
+    fmt,
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    mem,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+/// Inode of the ext4 root directory
+const ROOT_INODE: u32 = 2;
+
+/// A filesystem like accessor that can be used to read files from a raw ext4 filesystem
+pub(crate) struct Ext4Fs<T: Read + Seek + Send> {
+    /// Name of the filesystem used in errors
+    display_id: String,
+    /// Reader for the ext4 filesystem
+    reader: Arc<Mutex<Ext4Reader<T>>>,
+}
+
+impl<T: Read + Seek + Send + 'static> Ext4Fs<T> {
+    /// Create a new `Ext4Fs` instance. The reader must start at the ext4 superblock boot area
+    pub(crate) fn open(reader: T, display_id: impl Into<String>) -> AccessorResult<Self> {
+        let display_id = display_id.into();
+        let blocksize = 4096;
+        let ext4 = Ext4Reader::new(BufReader::new(reader), blocksize, 0)
+            .map_err(|err| ext4_err(&display_id, err))?;
+
+        Ok(Self {
+            display_id,
+            reader: Arc::new(Mutex::new(ext4)),
+        })
+    }
+
+    /// Return the `DirLocator` for the root directory
+    pub(crate) fn root_locator() -> DirLocator {
+        DirLocator::Ext4 {
+            inode: ROOT_INODE,
+            display_path: String::from("/"),
+        }
+    }
+
+    /// Read a file into memory
+    pub(crate) fn read_file(
+        &self,
+        inner: &InnerPath,
+        max_read_size: Option<u64>,
+    ) -> AccessorResult<Vec<u8>> {
+        let display_path = display_ext4_path(inner);
+        let (inode, kind) = self.resolve(inner)?;
+        if kind != EntryKind::File {
+            return Err(AccessorError::not_a_file(display_path));
+        }
+
+        self.read_inode(inode, &display_path, max_read_size)
+    }
+
+    /// Read a file into memory by its inode
+    pub(crate) fn read_handle(
+        &self,
+        handle: &FileHandle,
+        max_read_size: Option<u64>,
+    ) -> AccessorResult<Vec<u8>> {
+        match &handle.locator {
+            FileLocator::Ext4 {
+                inode,
+                display_path,
+            } => self.read_inode(*inode, display_path, max_read_size),
+            _ => Err(AccessorError::invalid_handle(format!(
+                "ext4 source cannot read handle for {}",
+                handle.display_path()
+            ))),
+        }
+    }
+
+    /// Create an `AccessorReader` to stream a file
+    pub(crate) fn reader(&self, inner: &InnerPath) -> AccessorResult<AccessorReader> {
+        let display_path = display_ext4_path(inner);
+        let (inode, kind) = self.resolve(inner)?;
+        if kind != EntryKind::File {
+            return Err(AccessorError::not_a_file(display_path));
+        }
+
+        self.stream_inode(inode, &display_path)
+    }
+
+    /// Create an `AccessorReader` to stream a file by its inode
+    pub(crate) fn reader_handle(&self, handle: &FileHandle) -> AccessorResult<AccessorReader> {
+        match &handle.locator {
+            FileLocator::Ext4 {
+                inode,
+                display_path,
+            } => self.stream_inode(*inode, display_path),
+            _ => Err(AccessorError::invalid_handle(format!(
+                "ext4 source cannot open reader handle for {}",
+                handle.display_path()
+            ))),
+        }
+    }
+
+    /// List files and directories in provided path
+    pub(crate) fn read_dir(&self, inner: &InnerPath) -> AccessorResult<Vec<DirEntry>> {
+        let display_path = display_ext4_path(inner);
+        let (inode, kind) = self.resolve(inner)?;
+        if kind != EntryKind::Directory {
+            return Err(AccessorError::not_a_directory(display_path));
+        }
+
+        self.list_children(inode, &display_path)
+    }
+
+    /// List files and directories from provided `DirHandle`
+    pub(crate) fn read_dir_handle(&self, handle: &DirHandle) -> AccessorResult<Vec<DirEntry>> {
+        match &handle.locator {
+            DirLocator::Ext4 {
+                inode,
+                display_path,
+            } => self.list_children(*inode, display_path),
+            _ => Err(AccessorError::invalid_handle(format!(
+                "ext4 source cannot list directory handle for {}",
+                handle.display_path()
+            ))),
+        }
+    }
+
+    /// List the children of a directory inode. Special files such as devices and links are skipped
+    pub(crate) fn list_children(
+        &self,
+        inode: u32,
+        display_path: &str,
+    ) -> AccessorResult<Vec<DirEntry>> {
+        let mut reader = self.lock_reader()?;
+        let info = reader
+            .read_dir(inode)
+            .map_err(|err| ext4_err(display_path, err))?;
+
+        let mut entries = Vec::with_capacity(info.children.len());
+        for child in info.children {
+            if child.name == "." || child.name == ".." {
+                continue;
+            }
+
+            let child_display = join_ext4_path(display_path, &child.name);
+            let (handle, size) = match entry_kind(child.file_type) {
+                EntryKind::File => {
+                    let stat = reader
+                        .stat(child.inode)
+                        .map_err(|err| ext4_err(&child_display, err))?;
+                    let handle = ItemHandle::File(FileHandle::new(FileLocator::Ext4 {
+                        inode: child.inode,
+                        display_path: child_display.clone(),
+                    }));
+                    (handle, stat.size)
+                }
+                EntryKind::Directory => {
+                    let handle = ItemHandle::Directory(DirHandle::new(DirLocator::Ext4 {
+                        inode: child.inode,
+                        display_path: child_display.clone(),
+                    }));
+                    (handle, 0)
+                }
+                EntryKind::Unsupported => continue,
+            };
+
+            let meta = EntryMeta::new(handle.kind(), size, child_display);
+            entries.push(DirEntry::new(child.name, handle, meta));
+        }
+
+        Ok(entries)
+    }
+
+    /// Walk the directory tree to find the inode for provided path
+    fn resolve(&self, inner: &InnerPath) -> AccessorResult<(u32, EntryKind)> {
+        let components = split_ext4_path(inner);
+        let mut reader = self.lock_reader()?;
+
+        let mut inode = ROOT_INODE;
+        let mut kind = EntryKind::Directory;
+        for (index, component) in components.iter().enumerate() {
+            let not_found =
+                || AccessorError::not_found(format!("/{}", components[..=index].join("/")));
+            if kind != EntryKind::Directory {
+                return Err(not_found());
+            }
+
+            let info = reader
+                .read_dir(inode)
+                .map_err(|err| ext4_err(&self.display_id, err))?;
+            let child = info
+                .children
+                .iter()
+                .find(|child| &child.name == component)
+                .ok_or_else(not_found)?;
+
+            inode = child.inode;
+            kind = entry_kind(child.file_type);
+        }
+
+        Ok((inode, kind))
+    }
+
+    /// Read the entire file into memory by its inode
+    fn read_inode(
+        &self,
+        inode: u32,
+        display_path: &str,
+        max_read_size: Option<u64>,
+    ) -> AccessorResult<Vec<u8>> {
+        let mut reader = self.lock_reader()?;
+        let stat = reader
+            .stat(inode)
+            .map_err(|err| ext4_err(display_path, err))?;
+        if let Some(limit) = max_read_size
+            && stat.size > limit
+        {
+            return Err(AccessorError::file_too_large(stat.size, limit));
+        }
+
+        reader
+            .read(inode)
+            .map_err(|err| ext4_err(display_path, err))
+    }
+
+    /// Open a file inode for streaming
+    fn stream_inode(&self, inode: u32, display_path: &str) -> AccessorResult<AccessorReader> {
+        let size = self
+            .lock_reader()?
+            .stat(inode)
+            .map_err(|err| ext4_err(display_path, err))?
+            .size;
+
+        Ok(AccessorReader::stream(Ext4StreamReader {
+            reader: Arc::clone(&self.reader),
+            inode,
+            size,
+            position: 0,
+            cache: Vec::new(),
+            cache_offset: 0,
+        }))
+    }
+
+    /// Ensure our ext4 reader is properly locked
+    fn lock_reader(&self) -> AccessorResult<MutexGuard<'_, Ext4Reader<T>>> {
+        self.reader.lock().map_err(|err| AccessorError::Ext4 {
+            path: Some(self.display_id.clone()),
+            reason: format!("ext4 reader lock poisoned: {err:?}"),
+        })
+    }
+}
+
+/// Create a reader to stream large files from the raw ext4 filesystem
+pub(crate) struct Ext4StreamReader<T: Read + Seek + Send> {
+    /// Shared ext4 reader
+    reader: Arc<Mutex<Ext4Reader<T>>>,
+    /// Target file inode
+    inode: u32,
+    /// Size of the file
+    size: u64,
+    /// Position of the reader
+    position: u64,
+    /// Small look ahead cache
+    cache: Vec<u8>,
+    /// Offset where our cache read to
+    cache_offset: u64,
+}
+
+/// How much cache to read in between file reads
+const READ_AHEAD: usize = 1024 * 1024;
+
+impl<T: Read + Seek + Send> Ext4StreamReader<T> {
+    /// Update our cache starting at the current position
+    fn refill_cache(&mut self) -> io::Result<()> {
+        let to_read = READ_AHEAD.min((self.size - self.position) as usize);
+        let mut buf = mem::take(&mut self.cache);
+        buf.resize(to_read, 0);
+
+        let mut ext4 = self
+            .reader
+            .lock()
+            .map_err(|err| io::Error::other(format!("ext4 reader lock poisoned: {err:?}")))?;
+        let mut file = ext4
+            .reader(self.inode)
+            .map_err(|err| io::Error::other(err.to_string()))?;
+        file.seek(SeekFrom::Start(self.position))?;
+
+        let mut total = 0;
+        while total < buf.len() {
+            let bytes = file.read(&mut buf[total..])?;
+            if bytes == 0 {
+                break;
+            }
+            total += bytes;
+        }
+
+        buf.truncate(total);
+        self.cache = buf;
+        self.cache_offset = self.position;
+        Ok(())
+    }
+}
+
+impl<T: Read + Seek + Send> Read for Ext4StreamReader<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() || self.position >= self.size {
+            return Ok(0);
+        }
+
+        let cache_end = self.cache_offset + self.cache.len() as u64;
+        if self.position < self.cache_offset || self.position >= cache_end {
+            self.refill_cache()?;
+            if self.cache.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!(
+                        "no data at offset {} (file size {})",
+                        self.position, self.size
+                    ),
+                ));
+            }
+        }
+
+        let offset = (self.position - self.cache_offset) as usize;
+        let bytes = (self.cache.len() - offset).min(buf.len());
+        buf[..bytes].copy_from_slice(&self.cache[offset..offset + bytes]);
+        self.position += bytes as u64;
+
+        Ok(bytes)
+    }
+}
+
+impl<T: Read + Seek + Send> Seek for Ext4StreamReader<T> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+        };
+
+        match new_pos {
+            Some(position) if position <= self.size => {
+                self.position = position;
+                Ok(self.position)
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid seek for file (size {})", self.size),
+            )),
+        }
+    }
+}
+
+impl<T: Read + Seek + Send> fmt::Debug for Ext4StreamReader<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ext4StreamReader")
+            .field("inode", &self.inode)
+            .field("size", &self.size)
+            .field("position", &self.position)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Map ext4 file types to `EntryKind`
+fn entry_kind(file_type: FileType) -> EntryKind {
+    match file_type {
+        FileType::File => EntryKind::File,
+        FileType::Directory => EntryKind::Directory,
+        _ => EntryKind::Unsupported,
+    }
+}
+
+/// Split an `InnerPath` into ext4 path components. Supports both forward and back slashes
+fn split_ext4_path(inner: &InnerPath) -> Vec<String> {
+    inner
+        .display()
+        .split(['\\', '/'])
+        .filter(|part| !part.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Convert to an absolute ext4 path
+pub(crate) fn display_ext4_path(inner: &InnerPath) -> String {
+    format!("/{}", split_ext4_path(inner).join("/"))
+}
+
+/// Append a child name to a parent ext4 path
+fn join_ext4_path(parent: &str, name: &str) -> String {
+    format!("{}/{name}", parent.trim_end_matches('/'))
+}
+
+/// Handle ext4 errors to `AccessorError`
+fn ext4_err(path: &str, err: impl fmt::Display) -> AccessorError {
+    AccessorError::Ext4 {
+        path: Some(path.to_string()),
+        reason: err.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::accessor::{
+        entry::handle::EntryKind,
+        error::AccessorError,
+        filesystem::{
+            ext4::data::Ext4Fs,
+            image::{
+                partitions::read_partitions,
+                volume::{PartitionReader, SharedDisk},
+            },
+        },
+        location::path::InnerPath,
+    };
+    use std::{
+        fs::File,
+        io::{Read, Seek, SeekFrom},
+        path::PathBuf,
+        sync::{Arc, Mutex},
+    };
+
+    fn test_fs() -> Ext4Fs<PartitionReader> {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/raw/gpt_ext4.raw");
+        let mut file = File::open(test_location).unwrap();
+        let partitions = read_partitions(&mut file).unwrap();
+        let disk: SharedDisk = Arc::new(Mutex::new(Box::new(file)));
+
+        Ext4Fs::open(PartitionReader::new(disk, &partitions[1]), "p2").unwrap()
+    }
+
+    #[test]
+    fn test_ext4_read_file() {
+        let fs = test_fs();
+        let bytes = fs
+            .read_file(&InnerPath::new(PathBuf::from("etc/hostname")), None)
+            .unwrap();
+        assert_eq!(bytes, b"artemis-image\n");
+
+        let err = fs
+            .read_file(&InnerPath::new(PathBuf::from("etc/hostname")), Some(4))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AccessorError::FileTooLarge { size: 14, limit: 4 }
+        ));
+    }
+
+    #[test]
+    fn test_ext4_read_dir() {
+        let fs = test_fs();
+        let entries = fs.read_dir(&InnerPath::new(PathBuf::from("etc"))).unwrap();
+        assert_eq!(entries.len(), 2);
+
+        for entry in entries {
+            assert_eq!(entry.meta.kind, EntryKind::File);
+            if entry.name == "passwd" {
+                assert_eq!(entry.meta.display_path, "/etc/passwd");
+                assert_eq!(entry.meta.size, 77);
+                let bytes = fs
+                    .read_handle(entry.handle.as_file().unwrap(), None)
+                    .unwrap();
+                assert!(bytes.starts_with(b"root:x:0:0"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_ext4_not_found() {
+        let fs = test_fs();
+        let err = fs
+            .read_file(&InnerPath::new(PathBuf::from("etc/shadow")), None)
+            .unwrap_err();
+        assert!(matches!(err, AccessorError::NotFound { path } if path == "/etc/shadow"));
+
+        let err = fs
+            .read_dir(&InnerPath::new(PathBuf::from("etc/passwd")))
+            .unwrap_err();
+        assert!(matches!(err, AccessorError::NotADirectory { .. }));
+    }
+
+    #[test]
+    fn test_ext4_reader() {
+        let fs = test_fs();
+        let mut reader = fs
+            .reader(&InnerPath::new(PathBuf::from("home/analyst/.bash_history")))
+            .unwrap();
+
+        reader.seek(SeekFrom::Start(7)).unwrap();
+        let mut buf = String::new();
+        reader.read_to_string(&mut buf).unwrap();
+        assert_eq!(buf, "cat /etc/passwd\n");
+    }
+}
@@ -0,0 +1,161 @@
+use crate::accessor::{
+    entry::{
+        handle::{DirEntry, EntryKind, GlobMatch},
+        locator::DirLocator,
+    },
+    error::{AccessorError, AccessorResult},
+    filesystem::{
+        ext4::data::Ext4Fs,
+        helper::glob::{
+            descend, glob_max_depth, is_recursive, join_relative, normalize_glob_pattern,
+            path_component_count,
+        },
+    },
+    location::path::InnerPath,
+};
+use glob::Pattern;
+use std::io::{Read, Seek};
+use tracing::warn;
+
+impl<T: Read + Seek + Send + 'static> Ext4Fs<T> {
+    /// Apply a glob pattern and return matches
+    pub(crate) fn globfs(
+        &self,
+        directory: &InnerPath,
+        pattern: &str,
+    ) -> AccessorResult<Vec<GlobMatch>> {
+        // Normalize all pattern separators to forward slash '/'
+        let normalized = normalize_glob_pattern(pattern);
+        let glob_pattern = Pattern::new(&normalized)
+            .map_err(|err| AccessorError::bad_glob(pattern, err.to_string()))?;
+
+        let entries = self.read_dir(directory)?;
+        let mut matches = Vec::new();
+
+        // Support nested and recursive glob patterns. Such as '/home/*/*/*.txt' or '/home/**/*.txt'
+        if normalized.contains('/') || is_recursive(&normalized) {
+            glob_path_pattern(
+                self,
+                entries,
+                &glob_pattern,
+                "",
+                glob_max_depth(&normalized),
+                &mut matches,
+            )?;
+
+            return Ok(matches);
+        }
+
+        for entry in entries {
+            if !glob_pattern.matches(&entry.name) {
+                continue;
+            }
+            matches.push(GlobMatch::new(entry.handle, entry.meta));
+        }
+
+        Ok(matches)
+    }
+}
+
+/// List child files and directories and check if they match our glob pattern
+fn glob_path_pattern<T: Read + Seek + Send + 'static>(
+    fs: &Ext4Fs<T>,
+    entries: Vec<DirEntry>,
+    pattern: &Pattern,
+    relative_prefix: &str,
+    max_depth: Option<usize>,
+    matches: &mut Vec<GlobMatch>,
+) -> AccessorResult<()> {
+    for entry in entries {
+        let relative = join_relative(relative_prefix, &entry.name);
+        let depth = path_component_count(&relative);
+
+        match entry.meta.kind {
+            EntryKind::File | EntryKind::Unsupported => {
+                if pattern.matches(&relative) {
+                    matches.push(GlobMatch::new(entry.handle, entry.meta));
+                }
+            }
+            EntryKind::Directory => {
+                if pattern.matches(&relative) {
+                    matches.push(GlobMatch::new(entry.handle.clone(), entry.meta.clone()));
+                }
+
+                if descend(depth, max_depth) {
+                    let Some(dir_handle) = entry.handle.as_directory() else {
+                        continue;
+                    };
+                    let DirLocator::Ext4 {
+                        inode,
+                        display_path,
+                    } = &dir_handle.locator
+                    else {
+                        return Err(AccessorError::invalid_handle(format!(
+                            "ext4 glob expected ext4 directory handle for {}",
+                            entry.meta.display_path
+                        )));
+                    };
+                    let children = match fs.list_children(*inode, display_path) {
+                        Ok(result) => result,
+                        Err(err) => {
+                            warn!("Could not glob '{pattern}' for: {display_path}: {err:?}");
+                            continue;
+                        }
+                    };
+                    glob_path_pattern(fs, children, pattern, &relative, max_depth, matches)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::accessor::{
+        filesystem::{
+            ext4::data::Ext4Fs,
+            image::{
+                partitions::read_partitions,
+                volume::{PartitionReader, SharedDisk},
+            },
+        },
+        location::path::InnerPath,
+    };
+    use std::{
+        fs::File,
+        path::PathBuf,
+        sync::{Arc, Mutex},
+    };
+
+    fn test_fs() -> Ext4Fs<PartitionReader> {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/raw/gpt_ext4.raw");
+        let mut file = File::open(test_location).unwrap();
+        let partitions = read_partitions(&mut file).unwrap();
+        let disk: SharedDisk = Arc::new(Mutex::new(Box::new(file)));
+
+        Ext4Fs::open(PartitionReader::new(disk, &partitions[1]), "p2").unwrap()
+    }
+
+    #[test]
+    fn test_ext4_globfs_nested_pattern() {
+        let fs = test_fs();
+        let matches = fs.globfs(&InnerPath::empty(), "home/*/.bash*").unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].meta.display_path, "/home/analyst/.bash_history");
+    }
+
+    #[test]
+    fn test_ext4_globfs_recursive() {
+        let fs = test_fs();
+        let matches = fs.globfs(&InnerPath::empty(), "**/passwd").unwrap();
+        assert_eq!(matches.len(), 1);
+
+        let matches = fs
+            .globfs(&InnerPath::new(PathBuf::from("etc")), "*")
+            .unwrap();
+        assert_eq!(matches.len(), 2);
+    }
+}
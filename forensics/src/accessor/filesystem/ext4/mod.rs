@@ -0,0 +1,2 @@
+pub(crate) mod data;
+mod glob;
@@ -0,0 +1,851 @@
+use crate::{
+    accessor::{
+        entry::{
+            handle::{
+                DirEntry, DirHandle, EntryKind, EntryMeta, FileHandle, GlobMatch, ItemHandle,
+            },
+            locator::{DirLocator, DiskFormat, DiskImage, FileLocator, NtfsEntryRef},
+        },
+        error::{AccessorError, AccessorResult},
+        filesystem::{
+            ext4::data::Ext4Fs,
+            helper::glob::normalize_glob_pattern,
+            image::{
+                partitions::{Partition, read_at, read_partitions},
+                qcow::open_qcow,
+                volume::{PartitionReader, SharedDisk},
+            },
+            ntfs::{data::NtfsFs, volume::NtfsVolume},
+        },
+        io::reader::{AccessorReader, ReadSeek},
+        location::path::InnerPath,
+    },
+    filesystem::disks::{
+        error::DiskError, ewf::EwfDisk, vhd::VhdDisk, vhdx::VhdxDisk, vmdk::VmdkDisk,
+    },
+};
+use glob::Pattern;
+use std::{
+    fs::File,
+    io::BufReader,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use tracing::{info, warn};
+
+/// Drive letter used for NTFS partitions inside a disk image. Stripped from display paths
+const IMAGE_NTFS_DRIVE: char = 'X';
+/// Drive letter alias for the Windows system partition. Example: `ewf:case.E01!C/Windows`
+const SYSTEM_DRIVE: char = 'C';
+/// NTFS file record number for the root directory
+const NTFS_ROOT_RECORD: u64 = 5;
+
+/// A filesystem like accessor that exposes each supported partition of a disk image
+///
+/// Paths start with the partition number or the `C` drive alias for the Windows system partition. Example: `p2/Windows/System32/config/SYSTEM`
+pub(crate) struct ImageFs {
+    /// Disk image file and format
+    pub(crate) image: DiskImage,
+    /// Partitions with a supported filesystem
+    partitions: Vec<ImagePartition>,
+}
+
+/// A partition and the filesystem found on it
+struct ImagePartition {
+    /// Partition number from the partition table
+    number: u32,
+    /// Filesystem reader for the partition
+    fs: PartitionFs,
+}
+
+/// Supported filesystems for disk image partitions
+pub(crate) enum PartitionFs {
+    Ntfs(NtfsFs<BufReader<PartitionReader>>),
+    Ext4(Ext4Fs<PartitionReader>),
+}
+
+impl PartitionFs {
+    /// Read a file into memory
+    pub(crate) fn read_file(
+        &self,
+        inner: &InnerPath,
+        max_read_size: Option<u64>,
+    ) -> AccessorResult<Vec<u8>> {
+        match self {
+            PartitionFs::Ntfs(fs) => fs.read_file(inner, max_read_size),
+            PartitionFs::Ext4(fs) => fs.read_file(inner, max_read_size),
+        }
+    }
+
+    /// Read a file into memory by its filesystem `FileHandle`
+    pub(crate) fn read_handle(
+        &self,
+        handle: &FileHandle,
+        max_read_size: Option<u64>,
+    ) -> AccessorResult<Vec<u8>> {
+        match self {
+            PartitionFs::Ntfs(fs) => fs.read_handle(handle, max_read_size),
+            PartitionFs::Ext4(fs) => fs.read_handle(handle, max_read_size),
+        }
+    }
+
+    /// Create an `AccessorReader` to stream a file
+    pub(crate) fn reader(&self, inner: &InnerPath) -> AccessorResult<AccessorReader> {
+        match self {
+            PartitionFs::Ntfs(fs) => fs.reader(inner),
+            PartitionFs::Ext4(fs) => fs.reader(inner),
+        }
+    }
+
+    /// Create an `AccessorReader` to stream a file by its filesystem `FileHandle`
+    pub(crate) fn reader_handle(&self, handle: &FileHandle) -> AccessorResult<AccessorReader> {
+        match self {
+            PartitionFs::Ntfs(fs) => fs.reader_handle(handle),
+            PartitionFs::Ext4(fs) => fs.reader_handle(handle),
+        }
+    }
+
+    /// List files and directories in provided path
+    pub(crate) fn read_dir(&self, inner: &InnerPath) -> AccessorResult<Vec<DirEntry>> {
+        match self {
+            PartitionFs::Ntfs(fs) => fs.read_dir(inner),
+            PartitionFs::Ext4(fs) => fs.read_dir(inner),
+        }
+    }
+
+    /// List files and directories from provided filesystem `DirHandle`
+    pub(crate) fn read_dir_handle(&self, handle: &DirHandle) -> AccessorResult<Vec<DirEntry>> {
+        match self {
+            PartitionFs::Ntfs(fs) => fs.read_dir_handle(handle),
+            PartitionFs::Ext4(fs) => fs.read_dir_handle(handle),
+        }
+    }
+
+    /// Apply a glob pattern and return matches
+    pub(crate) fn globfs(
+        &self,
+        directory: &InnerPath,
+        pattern: &str,
+    ) -> AccessorResult<Vec<GlobMatch>> {
+        match self {
+            PartitionFs::Ntfs(fs) => fs.globfs(directory, pattern),
+            PartitionFs::Ext4(fs) => fs.globfs(directory, pattern),
+        }
+    }
+
+    /// Locator for the root directory of the filesystem
+    pub(crate) fn root_locator(&self) -> DirLocator {
+        match self {
+            PartitionFs::Ntfs(_) => DirLocator::Ntfs {
+                drive: IMAGE_NTFS_DRIVE,
+                dir_ref: NtfsEntryRef {
+                    file_record_number: NTFS_ROOT_RECORD,
+                    sequence_number: NTFS_ROOT_RECORD as u16,
+                },
+                display_path: format!("{IMAGE_NTFS_DRIVE}:\\"),
+            },
+            PartitionFs::Ext4(_) => Ext4Fs::<PartitionReader>::root_locator(),
+        }
+    }
+}
+
+impl ImageFs {
+    /// Open a disk image file and discover its partitions
+    pub(crate) fn open(image: DiskImage) -> AccessorResult<Self> {
+        let (disk, table) = open_disk(&image)?;
+        Ok(Self::from_disk(image, disk, table))
+    }
+
+    /// Open the filesystems on each partition using a reader for the entire disk
+    pub(crate) fn from_disk(
+        image: DiskImage,
+        disk: Box<dyn ReadSeek + Send>,
+        table: Vec<Partition>,
+    ) -> Self {
+        let disk: SharedDisk = Arc::new(Mutex::new(disk));
+
+        let mut partitions = Vec::new();
+        for partition in table {
+            let display_id = format!("{}!p{}", image.display(), partition.number);
+            match open_partition(&disk, &partition, &display_id) {
+                Ok(Some(fs)) => partitions.push(ImagePartition {
+                    number: partition.number,
+                    fs,
+                }),
+                Ok(None) => info!("No supported filesystem found for {display_id}"),
+                Err(err) => warn!("Could not open filesystem for {display_id}: {err}"),
+            }
+        }
+
+        Self { image, partitions }
+    }
+
+    /// Read a file into memory
+    pub(crate) fn read_file(
+        &self,
+        inner: &InnerPath,
+        max_read_size: Option<u64>,
+    ) -> AccessorResult<Vec<u8>> {
+        let (partition, path) = self.split_partition(inner)?;
+        partition.fs.read_file(&path, max_read_size)
+    }
+
+    /// Read a file into memory by its file handle
+    pub(crate) fn read_handle(
+        &self,
+        handle: &FileHandle,
+        max_read_size: Option<u64>,
+    ) -> AccessorResult<Vec<u8>> {
+        let (partition, inner) = self.unwrap_file_handle(handle)?;
+        partition.fs.read_handle(&inner, max_read_size)
+    }
+
+    /// Create an `AccessorReader` to stream a file
+    pub(crate) fn reader(&self, inner: &InnerPath) -> AccessorResult<AccessorReader> {
+        let (partition, path) = self.split_partition(inner)?;
+        partition.fs.reader(&path)
+    }
+
+    /// Create an `AccessorReader` to stream a file by its file handle
+    pub(crate) fn reader_handle(&self, handle: &FileHandle) -> AccessorResult<AccessorReader> {
+        let (partition, inner) = self.unwrap_file_handle(handle)?;
+        partition.fs.reader_handle(&inner)
+    }
+
+    /// List files and directories in provided path. The image root lists the partitions
+    pub(crate) fn read_dir(&self, inner: &InnerPath) -> AccessorResult<Vec<DirEntry>> {
+        if inner.is_empty() {
+            return Ok(self
+                .partitions
+                .iter()
+                .map(|partition| self.partition_entry(partition))
+                .collect());
+        }
+
+        let (partition, path) = self.split_partition(inner)?;
+        let entries = partition.fs.read_dir(&path)?;
+
+        Ok(self.wrap_entries(partition.number, entries))
+    }
+
+    /// List files and directories from provided `DirHandle`
+    pub(crate) fn read_dir_handle(&self, handle: &DirHandle) -> AccessorResult<Vec<DirEntry>> {
+        let DirLocator::Image {
+            image,
+            partition,
+            inner,
+            ..
+        } = &handle.locator
+        else {
+            return Err(AccessorError::invalid_handle(format!(
+                "image source cannot list directory handle for {}",
+                handle.display_path()
+            )));
+        };
+
+        let partition = self.handle_partition(image, *partition, &handle.display_path())?;
+        let inner = DirHandle::new(inner.as_ref().clone());
+        let entries = partition.fs.read_dir_handle(&inner)?;
+
+        Ok(self.wrap_entries(partition.number, entries))
+    }
+
+    /// Apply a glob pattern and return matches
+    ///
+    /// At the image root the first pattern component is matched against the partition names
+    pub(crate) fn globfs(
+        &self,
+        directory: &InnerPath,
+        pattern: &str,
+    ) -> AccessorResult<Vec<GlobMatch>> {
+        if !directory.is_empty() {
+            let (partition, path) = self.split_partition(directory)?;
+            return self.glob_partition(partition, &path, pattern);
+        }
+
+        let normalized = normalize_glob_pattern(pattern);
+        let (first, rest) = match normalized.split_once('/') {
+            Some((first, rest)) => (first, Some(rest)),
+            None => (normalized.as_str(), None),
+        };
+        let partition_pattern =
+            Pattern::new(first).map_err(|err| AccessorError::bad_glob(pattern, err.to_string()))?;
+
+        let mut matches = Vec::new();
+        for partition in &self.partitions {
+            // Recursive patterns apply to every partition
+            if first == "**" {
+                matches.append(&mut self.glob_partition(
+                    partition,
+                    &InnerPath::empty(),
+                    &normalized,
+                )?);
+                continue;
+            }
+            if !partition_pattern.matches(&format!("p{}", partition.number)) {
+                continue;
+            }
+
+            match rest {
+                Some(rest) if !rest.is_empty() => {
+                    matches.append(&mut self.glob_partition(
+                        partition,
+                        &InnerPath::empty(),
+                        rest,
+                    )?);
+                }
+                _ => {
+                    let entry = self.partition_entry(partition);
+                    matches.push(GlobMatch::new(entry.handle, entry.meta));
+                }
+            }
+        }
+
+        Ok(matches)
+    }
+
+    /// Apply a glob pattern to a single partition
+    fn glob_partition(
+        &self,
+        partition: &ImagePartition,
+        directory: &InnerPath,
+        pattern: &str,
+    ) -> AccessorResult<Vec<GlobMatch>> {
+        let matches = partition.fs.globfs(directory, pattern)?;
+
+        Ok(matches
+            .into_iter()
+            .map(|entry| {
+                let (handle, meta) = self.wrap_handle(partition.number, entry.handle, entry.meta);
+                GlobMatch::new(handle, meta)
+            })
+            .collect())
+    }
+
+    /// Split the partition component from an `InnerPath`. Example: `p2/Windows` returns partition 2 and `Windows`
+    fn split_partition(&self, inner: &InnerPath) -> AccessorResult<(&ImagePartition, InnerPath)> {
+        let (selector, path) = split_partition_path(inner)?;
+        let partition = match selector {
+            PartitionSelector::Number(number) => self.partition(number).ok_or_else(|| {
+                AccessorError::not_found(format!("{}!p{number}", self.image.display()))
+            })?,
+            PartitionSelector::Drive(drive) => self.drive_partition(drive)?,
+        };
+
+        Ok((partition, path))
+    }
+
+    /// Return the NTFS partition for a drive letter alias. Only the system drive is supported
+    ///
+    /// The system drive is the first NTFS partition with a `Windows` directory, otherwise the first NTFS partition
+    fn drive_partition(&self, drive: char) -> AccessorResult<&ImagePartition> {
+        if !drive.eq_ignore_ascii_case(&SYSTEM_DRIVE) {
+            return Err(AccessorError::location(
+                format!("{drive}/"),
+                "only the C drive alias is supported. Use a partition such as p1/",
+            ));
+        }
+
+        let ntfs: Vec<&ImagePartition> = self
+            .partitions
+            .iter()
+            .filter(|partition| matches!(partition.fs, PartitionFs::Ntfs(_)))
+            .collect();
+        let windows = InnerPath::new(PathBuf::from("Windows"));
+
+        ntfs.iter()
+            .find(|partition| partition.fs.read_dir(&windows).is_ok())
+            .or_else(|| ntfs.first())
+            .copied()
+            .ok_or_else(|| {
+                AccessorError::not_found(format!(
+                    "{}!{drive} (no NTFS partition found)",
+                    self.image.display()
+                ))
+            })
+    }
+
+    /// Resolve a `PartitionSelector` to a partition number
+    pub(crate) fn partition_number(&self, selector: &PartitionSelector) -> AccessorResult<u32> {
+        match selector {
+            PartitionSelector::Number(number) => Ok(*number),
+            PartitionSelector::Drive(drive) => Ok(self.drive_partition(*drive)?.number),
+        }
+    }
+
+    /// Return the `ImagePartition` by partition number
+    fn partition(&self, number: u32) -> Option<&ImagePartition> {
+        self.partitions
+            .iter()
+            .find(|partition| partition.number == number)
+    }
+
+    /// Return the `ImagePartition` for a handle after ensuring it belongs to this image
+    fn handle_partition(
+        &self,
+        image: &DiskImage,
+        number: u32,
+        display_path: &str,
+    ) -> AccessorResult<&ImagePartition> {
+        if image != &self.image {
+            return Err(AccessorError::invalid_handle(format!(
+                "image source cannot read handle for {display_path}"
+            )));
+        }
+
+        self.partition(number)
+            .ok_or_else(|| AccessorError::not_found(display_path))
+    }
+
+    /// Return the partition and the filesystem `FileHandle` wrapped by an image `FileHandle`
+    fn unwrap_file_handle(
+        &self,
+        handle: &FileHandle,
+    ) -> AccessorResult<(&ImagePartition, FileHandle)> {
+        let FileLocator::Image {
+            image,
+            partition,
+            inner,
+            ..
+        } = &handle.locator
+        else {
+            return Err(AccessorError::invalid_handle(format!(
+                "image source cannot read handle for {}",
+                handle.display_path()
+            )));
+        };
+
+        let partition = self.handle_partition(image, *partition, &handle.display_path())?;
+        Ok((partition, FileHandle::new(inner.as_ref().clone())))
+    }
+
+    /// Directory entry for the root of a partition
+    fn partition_entry(&self, partition: &ImagePartition) -> DirEntry {
+        let display_path = image_display_path(&self.image, partition.number, "");
+        let inner = partition.fs.root_locator();
+
+        let handle = ItemHandle::Directory(DirHandle::new(DirLocator::Image {
+            image: self.image.clone(),
+            partition: partition.number,
+            inner: Box::new(inner),
+            display_path: display_path.clone(),
+        }));
+        let meta = EntryMeta::new(EntryKind::Directory, 0, display_path);
+
+        DirEntry::new(format!("p{}", partition.number), handle, meta)
+    }
+
+    /// Wrap the partition filesystem entries with image handles
+    fn wrap_entries(&self, partition: u32, entries: Vec<DirEntry>) -> Vec<DirEntry> {
+        entries
+            .into_iter()
+            .map(|entry| {
+                let (handle, meta) = self.wrap_handle(partition, entry.handle, entry.meta);
+                DirEntry::new(entry.name, handle, meta)
+            })
+            .collect()
+    }
+
+    /// Wrap a partition filesystem handle so it can be routed back to this image
+    fn wrap_handle(
+        &self,
+        partition: u32,
+        handle: ItemHandle,
+        meta: EntryMeta,
+    ) -> (ItemHandle, EntryMeta) {
+        let display_path = image_display_path(&self.image, partition, &meta.display_path);
+        let wrap_file = |file: FileHandle| {
+            FileHandle::new(FileLocator::Image {
+                image: self.image.clone(),
+                partition,
+                inner: Box::new(file.locator),
+                display_path: display_path.clone(),
+            })
+        };
+
+        let handle = match handle {
+            ItemHandle::File(file) => ItemHandle::File(wrap_file(file)),
+            ItemHandle::Unsupported(file) => ItemHandle::Unsupported(wrap_file(file)),
+            ItemHandle::Directory(dir) => {
+                ItemHandle::Directory(DirHandle::new(DirLocator::Image {
+                    image: self.image.clone(),
+                    partition,
+                    inner: Box::new(dir.locator),
+                    display_path: display_path.clone(),
+                }))
+            }
+        };
+
+        (handle, EntryMeta::new(meta.kind, meta.size, display_path))
+    }
+}
+
+/// Open the disk image reader and read the partition table
+pub(crate) fn open_disk(
+    image: &DiskImage,
+) -> AccessorResult<(Box<dyn ReadSeek + Send>, Vec<Partition>)> {
+    if !image.path.is_file() {
+        return Err(AccessorError::not_found(image.display()));
+    }
+
+    match image.format {
+        DiskFormat::Raw => {
+            let file =
+                File::open(&image.path).map_err(|err| AccessorError::io_path(&image.path, err))?;
+            let mut disk: Box<dyn ReadSeek + Send> = Box::new(BufReader::new(file));
+            let table = read_partitions(&mut disk)?;
+            Ok((disk, table))
+        }
+        DiskFormat::Qcow => {
+            let (disk, table) = open_qcow(&image.path)?;
+            Ok((Box::new(disk), table))
+        }
+        DiskFormat::Ewf => open_virtual_disk(EwfDisk::open(&image.path.to_string_lossy())),
+        DiskFormat::Vhd => open_virtual_disk(VhdDisk::open(&image.path.to_string_lossy())),
+        DiskFormat::Vhdx => open_virtual_disk(VhdxDisk::open(&image.path.to_string_lossy())),
+        DiskFormat::Vmdk => open_virtual_disk(VmdkDisk::open(&image.path.to_string_lossy())),
+    }
+}
+
+/// Box a disk reader from `filesystem::disks` and read the partition table of the guest disk
+fn open_virtual_disk<T: ReadSeek + Send + 'static>(
+    disk: Result<T, DiskError>,
+) -> AccessorResult<(Box<dyn ReadSeek + Send>, Vec<Partition>)> {
+    let disk = disk.map_err(|err| AccessorError::Volume {
+        reason: err.to_string(),
+    })?;
+    let mut disk: Box<dyn ReadSeek + Send> = Box::new(disk);
+    let table = read_partitions(&mut disk)?;
+    Ok((disk, table))
+}
+
+/// Open a `PartitionReader` for a single partition in a disk image
+pub(crate) fn open_partition_reader(
+    image: &DiskImage,
+    number: u32,
+) -> AccessorResult<PartitionReader> {
+    let (disk, table) = open_disk(image)?;
+    let partition = table
+        .iter()
+        .find(|partition| partition.number == number)
+        .ok_or_else(|| AccessorError::not_found(format!("{}!p{number}", image.display())))?;
+
+    Ok(PartitionReader::new(Arc::new(Mutex::new(disk)), partition))
+}
+
+/// The partition component of a disk image path
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PartitionSelector {
+    /// Partition number. Example: `p2`
+    Number(u32),
+    /// Drive letter alias for the Windows system partition. Example: `C`
+    Drive(char),
+}
+
+/// Split the partition component from an `InnerPath`. Example: `p2/Windows` returns partition 2 and `Windows`
+///
+/// A drive letter alias is also accepted. Example: `C/Windows` or `C:/Windows`
+pub(crate) fn split_partition_path(
+    inner: &InnerPath,
+) -> AccessorResult<(PartitionSelector, InnerPath)> {
+    let value = inner.display().replace('\\', "/");
+    let (first, rest) = value.split_once('/').unwrap_or((value.as_str(), ""));
+
+    let number = first
+        .strip_prefix(['p', 'P'])
+        .and_then(|number| number.parse::<u32>().ok());
+    let drive = first.strip_suffix(':').unwrap_or(first);
+
+    let selector = match number {
+        Some(number) => PartitionSelector::Number(number),
+        None if drive.len() == 1 && drive.as_bytes()[0].is_ascii_alphabetic() => {
+            PartitionSelector::Drive(drive.as_bytes()[0].to_ascii_uppercase() as char)
+        }
+        None => {
+            return Err(AccessorError::location(
+                value.as_str(),
+                "image paths must start with a partition such as p1/ or the C drive alias such as C/",
+            ));
+        }
+    };
+
+    let path = if rest.is_empty() {
+        InnerPath::empty()
+    } else {
+        InnerPath::new(PathBuf::from(rest))
+    };
+
+    Ok((selector, path))
+}
+
+/// Detect and open the filesystem on a partition. Returns `None` for unsupported filesystems
+pub(crate) fn open_partition(
+    disk: &SharedDisk,
+    partition: &Partition,
+    display_id: &str,
+) -> AccessorResult<Option<PartitionFs>> {
+    let mut reader = PartitionReader::new(Arc::clone(disk), partition);
+    let boot = read_at(&mut reader, 0, 2048)?;
+
+    if boot.get(3..11) == Some(b"NTFS    ") {
+        let volume = NtfsVolume::open(BufReader::new(reader), display_id)?;
+        return Ok(Some(PartitionFs::Ntfs(NtfsFs::new(
+            volume,
+            IMAGE_NTFS_DRIVE,
+        ))));
+    }
+
+    // ext2/3/4 superblock magic is at offset 1080
+    if boot.get(1080..1082) == Some(&[0x53, 0xef]) {
+        return Ok(Some(PartitionFs::Ext4(Ext4Fs::open(reader, display_id)?)));
+    }
+
+    Ok(None)
+}
+
+/// Build the display path for an entry in a disk image partition
+///
+/// Example: `X:\Windows\System32` becomes `image:disk.dd!p2/Windows/System32`
+pub(crate) fn image_display_path(image: &DiskImage, partition: u32, inner_display: &str) -> String {
+    container_display_path(&format!("{}!p{partition}", image.display()), inner_display)
+}
+
+/// Append the display path of a filesystem entry to a container root. Example: `vss:C@3!` and `X:\Windows`
+/// become `vss:C@3!/Windows`
+pub(crate) fn container_display_path(container: &str, inner_display: &str) -> String {
+    let mut path = inner_display;
+    // NTFS display paths include a drive letter
+    if path.len() >= 2 && path.as_bytes()[1] == b':' {
+        path = &path[2..];
+    }
+    let path = path.replace('\\', "/");
+    let path = path.trim_matches('/');
+
+    if path.is_empty() {
+        container.to_string()
+    } else {
+        format!("{container}/{path}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::accessor::{
+        entry::{
+            handle::EntryKind,
+            locator::{DiskFormat, DiskImage},
+        },
+        error::AccessorError,
+        filesystem::image::data::{
+            ImageFs, PartitionSelector, image_display_path, split_partition_path,
+        },
+        location::path::InnerPath,
+    };
+    use std::path::PathBuf;
+
+    fn test_fs() -> ImageFs {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/raw/gpt_ext4.raw");
+        ImageFs::open(DiskImage::new(DiskFormat::Raw, test_location)).unwrap()
+    }
+
+    #[test]
+    fn test_image_read_file() {
+        let fs = test_fs();
+        let bytes = fs
+            .read_file(&InnerPath::new(PathBuf::from("p2/etc/hostname")), None)
+            .unwrap();
+        assert_eq!(bytes, b"artemis-image\n");
+
+        // Partition 1 has no filesystem
+        let err = fs
+            .read_file(&InnerPath::new(PathBuf::from("p1/etc/hostname")), None)
+            .unwrap_err();
+        assert!(matches!(err, AccessorError::NotFound { .. }));
+    }
+
+    #[test]
+    fn test_image_read_dir() {
+        let fs = test_fs();
+        let partitions = fs.read_dir(&InnerPath::empty()).unwrap();
+        assert_eq!(partitions.len(), 1);
+        assert_eq!(partitions[0].name, "p2");
+
+        let entries = fs
+            .read_dir_handle(partitions[0].handle.as_directory().unwrap())
+            .unwrap();
+        let etc = entries.iter().find(|entry| entry.name == "etc").unwrap();
+        assert_eq!(etc.meta.kind, EntryKind::Directory);
+        assert!(etc.meta.display_path.ends_with("gpt_ext4.raw!p2/etc"));
+
+        let files = fs
+            .read_dir_handle(etc.handle.as_directory().unwrap())
+            .unwrap();
+        let passwd = files.iter().find(|entry| entry.name == "passwd").unwrap();
+        let bytes = fs
+            .read_handle(passwd.handle.as_file().unwrap(), None)
+            .unwrap();
+        assert_eq!(bytes.len(), 77);
+    }
+
+    #[test]
+    fn test_image_globfs() {
+        let fs = test_fs();
+        let matches = fs
+            .globfs(&InnerPath::empty(), "p*/home/*/.bash_history")
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+
+        let mut reader = fs
+            .reader_handle(matches[0].handle.as_file().unwrap())
+            .unwrap();
+        assert_eq!(reader.read_bytes(0, 6).unwrap(), b"ls -la");
+
+        let matches = fs.globfs(&InnerPath::empty(), "**/hostname").unwrap();
+        assert_eq!(matches.len(), 1);
+
+        let matches = fs
+            .globfs(&InnerPath::new(PathBuf::from("p2/etc")), "pass*")
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+    }
+
+    #[test]
+    fn test_image_bad_partition() {
+        let fs = test_fs();
+        let err = fs
+            .read_file(&InnerPath::new(PathBuf::from("etc/hostname")), None)
+            .unwrap_err();
+        assert!(matches!(err, AccessorError::Location { .. }));
+    }
+
+    #[test]
+    fn test_split_partition_path() {
+        let (selector, path) =
+            split_partition_path(&InnerPath::new(PathBuf::from("p2/Windows"))).unwrap();
+        assert_eq!(selector, PartitionSelector::Number(2));
+        assert_eq!(path.display(), "Windows");
+
+        let (selector, path) =
+            split_partition_path(&InnerPath::new(PathBuf::from("c:\\Windows\\System32"))).unwrap();
+        assert_eq!(selector, PartitionSelector::Drive('C'));
+        assert_eq!(path.display(), "Windows/System32");
+
+        let (selector, path) = split_partition_path(&InnerPath::new(PathBuf::from("C"))).unwrap();
+        assert_eq!(selector, PartitionSelector::Drive('C'));
+        assert!(path.is_empty());
+
+        let err = split_partition_path(&InnerPath::new(PathBuf::from("etc/hostname"))).unwrap_err();
+        assert!(matches!(err, AccessorError::Location { .. }));
+    }
+
+    #[test]
+    fn test_image_drive_alias() {
+        let fs = test_fs();
+        // The test image only has an ext4 partition
+        let err = fs
+            .read_file(&InnerPath::new(PathBuf::from("C/etc/hostname")), None)
+            .unwrap_err();
+        assert!(matches!(err, AccessorError::NotFound { .. }));
+
+        let err = fs
+            .read_file(&InnerPath::new(PathBuf::from("D/etc/hostname")), None)
+            .unwrap_err();
+        assert!(matches!(err, AccessorError::Location { .. }));
+    }
+
+    #[test]
+    fn test_image_display_path() {
+        let image = &DiskImage::new(DiskFormat::Raw, PathBuf::from("disk.dd"));
+        assert_eq!(
+            image_display_path(image, 2, "X:\\Windows\\System32"),
+            "image:disk.dd!p2/Windows/System32"
+        );
+        assert_eq!(
+            image_display_path(image, 1, "/etc/passwd"),
+            "image:disk.dd!p1/etc/passwd"
+        );
+        assert_eq!(image_display_path(image, 1, "X:\\"), "image:disk.dd!p1");
+
+        let image = &DiskImage::new(DiskFormat::Qcow, PathBuf::from("vm.qcow2"));
+        assert_eq!(
+            image_display_path(image, 3, "/var/log"),
+            "qcow:vm.qcow2!p3/var/log"
+        );
+    }
+
+    #[test]
+    fn test_image_ewf() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/ewf/gpt_ext4.E01");
+        let fs = ImageFs::open(DiskImage::new(DiskFormat::Ewf, test_location)).unwrap();
+        let bytes = fs
+            .read_file(&InnerPath::new(PathBuf::from("p2/etc/passwd")), None)
+            .unwrap();
+        assert_eq!(bytes.len(), 77);
+    }
+
+    #[test]
+    fn test_image_virtual_disks() {
+        let disks = [
+            (DiskFormat::Vhd, "vhd/gpt_ext4_dynamic.vhd"),
+            (DiskFormat::Vhdx, "vhdx/gpt_ext4.vhdx"),
+            (DiskFormat::Vmdk, "vmdk/gpt_ext4_split.vmdk"),
+            (DiskFormat::Vmdk, "vmdk/gpt_ext4_stream.vmdk"),
+        ];
+        for (format, path) in disks {
+            let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+            test_location.push("tests/test_data/disks");
+            test_location.push(path);
+            let fs = ImageFs::open(DiskImage::new(format, test_location)).unwrap();
+            let bytes = fs
+                .read_file(&InnerPath::new(PathBuf::from("p2/etc/hostname")), None)
+                .unwrap();
+            assert_eq!(bytes, b"artemis-image\n");
+        }
+
+        // Differencing disks read changed sectors from the child and the rest from the parent
+        let children = [
+            (DiskFormat::Vhd, "vhd/gpt_ext4_child.vhd"),
+            (DiskFormat::Vhdx, "vhdx/gpt_ext4_child.vhdx"),
+            (DiskFormat::Vmdk, "vmdk/gpt_ext4-000001.vmdk"),
+        ];
+        for (format, path) in children {
+            let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+            test_location.push("tests/test_data/disks");
+            test_location.push(path);
+            let fs = ImageFs::open(DiskImage::new(format, test_location)).unwrap();
+            let bytes = fs
+                .read_file(&InnerPath::new(PathBuf::from("p2/etc/hostname")), None)
+                .unwrap();
+            assert_eq!(bytes, b"artemis-child\n");
+            let bytes = fs
+                .read_file(&InnerPath::new(PathBuf::from("p2/etc/passwd")), None)
+                .unwrap();
+            assert_eq!(bytes.len(), 77);
+        }
+    }
+
+    #[test]
+    fn test_image_qcow() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/qcow/gpt_ext4.qcow2");
+        let fs = ImageFs::open(DiskImage::new(DiskFormat::Qcow, test_location)).unwrap();
+
+        let matches = fs.globfs(&InnerPath::empty(), "p2/etc/*").unwrap();
+        assert_eq!(matches.len(), 2);
+        assert!(
+            matches
+                .iter()
+                .all(|entry| entry.meta.display_path.starts_with("qcow:"))
+        );
+
+        let bytes = fs
+            .read_file(
+                &InnerPath::new(PathBuf::from("p2/home/analyst/.bash_history")),
+                None,
+            )
+            .unwrap();
+        assert_eq!(bytes, b"ls -la\ncat /etc/passwd\n");
+    }
+}
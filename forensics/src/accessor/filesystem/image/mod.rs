@@ -0,0 +1,4 @@
+pub(crate) mod data;
+pub(crate) mod partitions;
+pub(crate) mod qcow;
+pub(crate) mod volume;
@@ -0,0 +1,425 @@
+use crate::{
+    accessor::error::{AccessorError, AccessorResult},
+    utils::nom_helper::{
+        Endian, nom_unsigned_eight_bytes, nom_unsigned_four_bytes, nom_unsigned_one_byte,
+    },
+};
+use nom::bytes::complete::take;
+use std::io::{Read, Seek, SeekFrom};
+use tracing::warn;
+
+/// A partition discovered in a disk image
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Partition {
+    /// Partition number. Starts at 1. MBR logical partitions start at 5
+    pub(crate) number: u32,
+    /// Offset to the start of the partition in bytes
+    pub(crate) offset: u64,
+    /// Size of the partition in bytes
+    pub(crate) size: u64,
+}
+
+/// Sector size used by MBR and GPT addressing. 4K native disks are also checked for GPT
+const SECTOR_SIZE: u64 = 512;
+/// MBR partition type for a GPT protective MBR
+const GPT_PROTECTIVE: u8 = 0xee;
+/// Max number of extended boot records to follow. Protects against EBR loops
+const MAX_LOGICAL_PARTITIONS: u32 = 128;
+
+/// Read the partition table of a disk image
+///
+/// If the image is a single volume (no partition table) one partition covering the entire image is returned
+pub(crate) fn read_partitions<T: Read + Seek>(reader: &mut T) -> AccessorResult<Vec<Partition>> {
+    let disk_size = reader.seek(SeekFrom::End(0))?;
+    let boot_sector = read_at(reader, 0, SECTOR_SIZE as usize)?;
+
+    if is_volume(reader, &boot_sector)? {
+        return Ok(vec![Partition {
+            number: 1,
+            offset: 0,
+            size: disk_size,
+        }]);
+    }
+
+    let entries = parse_mbr(&boot_sector)?;
+    if entries.is_empty() {
+        return Err(AccessorError::Volume {
+            reason: String::from("disk image has no MBR or GPT partition table"),
+        });
+    }
+
+    if entries
+        .iter()
+        .any(|entry| entry.partition_type == GPT_PROTECTIVE)
+    {
+        for sector_size in [SECTOR_SIZE, 4096] {
+            if let Some(partitions) = read_gpt(reader, sector_size, disk_size)? {
+                return Ok(partitions);
+            }
+        }
+        warn!("Protective MBR found but no GPT header. Falling back to MBR partitions");
+    }
+
+    let mut partitions = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        if entry.sector_count == 0 || entry.partition_type == GPT_PROTECTIVE {
+            continue;
+        }
+        if is_extended(entry.partition_type) {
+            read_logical_partitions(reader, entry.start_sector as u64, &mut partitions)?;
+            continue;
+        }
+        partitions.push(Partition {
+            number: index as u32 + 1,
+            offset: entry.start_sector as u64 * SECTOR_SIZE,
+            size: entry.sector_count as u64 * SECTOR_SIZE,
+        });
+    }
+
+    Ok(partitions)
+}
+
+/// MBR partition table entry
+#[derive(Debug)]
+struct MbrEntry {
+    partition_type: u8,
+    start_sector: u32,
+    sector_count: u32,
+}
+
+/// Parse the four MBR partition entries. Empty slots are kept so partition numbers match the slot
+fn parse_mbr(sector: &[u8]) -> AccessorResult<Vec<MbrEntry>> {
+    let signature_offset = 510;
+    if sector.get(signature_offset..) != Some(&[0x55, 0xaa]) {
+        return Ok(Vec::new());
+    }
+
+    let table_offset = 446;
+    let entry_size = 16;
+    let mut entries = Vec::new();
+    for slot in 0..4 {
+        let start = table_offset + slot * entry_size;
+        let entry = &sector[start..start + entry_size];
+        let (_, entry) = mbr_entry(entry).map_err(|_err| AccessorError::Volume {
+            reason: String::from("could not parse MBR partition entry"),
+        })?;
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+/// Parse a single 16 byte MBR partition entry
+fn mbr_entry(data: &[u8]) -> nom::IResult<&[u8], MbrEntry> {
+    let (input, _status) = nom_unsigned_one_byte(data, Endian::Le)?;
+    let (input, _chs_start) = take(3_usize)(input)?;
+    let (input, partition_type) = nom_unsigned_one_byte(input, Endian::Le)?;
+    let (input, _chs_end) = take(3_usize)(input)?;
+    let (input, start_sector) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, sector_count) = nom_unsigned_four_bytes(input, Endian::Le)?;
+
+    Ok((
+        input,
+        MbrEntry {
+            partition_type,
+            start_sector,
+            sector_count,
+        },
+    ))
+}
+
+/// Check for CHS, LBA, and Linux extended partition types
+fn is_extended(partition_type: u8) -> bool {
+    matches!(partition_type, 0x05 | 0x0f | 0x85)
+}
+
+/// Walk the extended boot record chain. Logical partitions are numbered starting at 5
+fn read_logical_partitions<T: Read + Seek>(
+    reader: &mut T,
+    extended_start: u64,
+    partitions: &mut Vec<Partition>,
+) -> AccessorResult<()> {
+    let mut ebr_sector = extended_start;
+    let mut number = 5;
+
+    while number < MAX_LOGICAL_PARTITIONS + 5 {
+        let sector = read_at(reader, ebr_sector * SECTOR_SIZE, SECTOR_SIZE as usize)?;
+        let entries = parse_mbr(&sector)?;
+        let Some(logical) = entries.first() else {
+            warn!("Extended boot record at sector {ebr_sector} has no signature");
+            break;
+        };
+
+        if logical.sector_count != 0 {
+            partitions.push(Partition {
+                number,
+                offset: (ebr_sector + logical.start_sector as u64) * SECTOR_SIZE,
+                size: logical.sector_count as u64 * SECTOR_SIZE,
+            });
+            number += 1;
+        }
+
+        // Second entry points to the next EBR relative to the start of the extended partition
+        match entries.get(1) {
+            Some(next) if next.sector_count != 0 && is_extended(next.partition_type) => {
+                ebr_sector = extended_start + next.start_sector as u64;
+            }
+            _ => break,
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the GPT header and partition entries. Returns `None` if no GPT header is at LBA 1
+fn read_gpt<T: Read + Seek>(
+    reader: &mut T,
+    sector_size: u64,
+    disk_size: u64,
+) -> AccessorResult<Option<Vec<Partition>>> {
+    let header = read_at(reader, sector_size, 92)?;
+    if !header.starts_with(b"EFI PART") {
+        return Ok(None);
+    }
+
+    let (_, (entries_lba, entry_count, entry_size)) =
+        gpt_header(&header).map_err(|_err| AccessorError::Volume {
+            reason: String::from("could not parse GPT header"),
+        })?;
+
+    // Minimum entry size is 128 bytes. Typically there are 128 entries
+    let min_entry_size = 128;
+    let max_entry_size = 4096;
+    let max_entries = 1024;
+    if entry_size < min_entry_size || entry_size > max_entry_size || entry_count > max_entries {
+        return Err(AccessorError::Volume {
+            reason: format!("unexpected GPT entry layout: {entry_count} entries of {entry_size}"),
+        });
+    }
+
+    let table_offset = entries_lba.checked_mul(sector_size);
+    let table_size = (entry_count as u64).checked_mul(entry_size as u64);
+    let (Some(table_offset), Some(table_size)) = (table_offset, table_size) else {
+        return Err(AccessorError::Volume {
+            reason: format!("GPT partition entries at LBA {entries_lba} overflow"),
+        });
+    };
+    if table_offset
+        .checked_add(table_size)
+        .is_none_or(|end| end > disk_size)
+    {
+        return Err(AccessorError::Volume {
+            reason: format!("GPT partition entries at LBA {entries_lba} are outside the image"),
+        });
+    }
+
+    let table = read_at(reader, table_offset, table_size as usize)?;
+
+    let mut partitions = Vec::new();
+    for (index, entry) in table.chunks_exact(entry_size as usize).enumerate() {
+        let (_, (type_guid, first_lba, last_lba)) =
+            gpt_entry(entry).map_err(|_err| AccessorError::Volume {
+                reason: String::from("could not parse GPT partition entry"),
+            })?;
+
+        // Unused entries have an empty type GUID
+        if type_guid.iter().all(|value| *value == 0) || last_lba < first_lba {
+            continue;
+        }
+
+        let number = index as u32 + 1;
+        let offset = first_lba.checked_mul(sector_size);
+        let size = (last_lba - first_lba)
+            .checked_add(1)
+            .and_then(|sectors| sectors.checked_mul(sector_size));
+        let (Some(offset), Some(size)) = (offset, size) else {
+            warn!("GPT partition {number} overflows. Skipping it");
+            continue;
+        };
+        if offset.checked_add(size).is_none_or(|end| end > disk_size) {
+            warn!("GPT partition {number} is outside the image. Skipping it");
+            continue;
+        }
+
+        partitions.push(Partition {
+            number,
+            offset,
+            size,
+        });
+    }
+
+    Ok(Some(partitions))
+}
+
+/// Parse the GPT header. Returns the partition entries LBA, entry count, and entry size
+fn gpt_header(data: &[u8]) -> nom::IResult<&[u8], (u64, u32, u32)> {
+    let (input, _) = take(72_usize)(data)?;
+    let (input, entries_lba) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+    let (input, entry_count) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, entry_size) = nom_unsigned_four_bytes(input, Endian::Le)?;
+
+    Ok((input, (entries_lba, entry_count, entry_size)))
+}
+
+/// Parse a GPT partition entry. Returns the type GUID, first LBA, and last LBA
+fn gpt_entry(data: &[u8]) -> nom::IResult<&[u8], (&[u8], u64, u64)> {
+    let (input, type_guid) = take(16_usize)(data)?;
+    let (input, _partition_guid) = take(16_usize)(input)?;
+    let (input, first_lba) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+    let (input, last_lba) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+
+    Ok((input, (type_guid, first_lba, last_lba)))
+}
+
+/// Check if the image starts with a filesystem instead of a partition table
+///
+/// NTFS boot sectors also end with the MBR signature
+pub(crate) fn is_volume<T: Read + Seek>(
+    reader: &mut T,
+    boot_sector: &[u8],
+) -> AccessorResult<bool> {
+    if boot_sector.get(3..11) == Some(b"NTFS    ") {
+        return Ok(true);
+    }
+
+    // ext2/3/4 superblock magic is at offset 1080
+    let magic = read_at(reader, 1080, 2)?;
+    Ok(magic == [0x53, 0xef])
+}
+
+/// Read bytes at an offset. Short reads past the end of the image are padded with zeros
+pub(crate) fn read_at<T: Read + Seek>(
+    reader: &mut T,
+    offset: u64,
+    size: usize,
+) -> AccessorResult<Vec<u8>> {
+    reader.seek(SeekFrom::Start(offset))?;
+    let mut buf = Vec::with_capacity(size);
+    reader.take(size as u64).read_to_end(&mut buf)?;
+    buf.resize(size, 0);
+
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::accessor::filesystem::image::partitions::{Partition, read_partitions};
+    use std::{fs::File, io::Cursor, path::PathBuf};
+
+    fn mbr_entry(sector: &mut [u8], slot: usize, partition_type: u8, start: u32, count: u32) {
+        let offset = 446 + slot * 16;
+        sector[offset + 4] = partition_type;
+        sector[offset + 8..offset + 12].copy_from_slice(&start.to_le_bytes());
+        sector[offset + 12..offset + 16].copy_from_slice(&count.to_le_bytes());
+        sector[510] = 0x55;
+        sector[511] = 0xaa;
+    }
+
+    #[test]
+    fn test_read_partitions_mbr() {
+        let mut disk = vec![0u8; 512 * 64];
+        mbr_entry(&mut disk, 0, 0x07, 2, 10);
+        mbr_entry(&mut disk, 1, 0x05, 20, 40);
+        // First EBR at sector 20. Logical partition plus a link to the next EBR at sector 40
+        mbr_entry(&mut disk[20 * 512..21 * 512], 0, 0x83, 1, 8);
+        mbr_entry(&mut disk[20 * 512..21 * 512], 1, 0x05, 20, 20);
+        mbr_entry(&mut disk[40 * 512..41 * 512], 0, 0x83, 2, 4);
+
+        let partitions = read_partitions(&mut Cursor::new(disk)).unwrap();
+        assert_eq!(
+            partitions,
+            vec![
+                Partition {
+                    number: 1,
+                    offset: 1024,
+                    size: 5120
+                },
+                Partition {
+                    number: 5,
+                    offset: 10752,
+                    size: 4096
+                },
+                Partition {
+                    number: 6,
+                    offset: 21504,
+                    size: 2048
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_partitions_gpt() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/raw/gpt_ext4.raw");
+
+        let partitions = read_partitions(&mut File::open(test_location).unwrap()).unwrap();
+        assert_eq!(partitions.len(), 2);
+        assert_eq!(partitions[0].offset, 32768);
+        assert_eq!(partitions[1].number, 2);
+        assert_eq!(partitions[1].offset, 65536);
+        assert_eq!(partitions[1].size, 524288);
+    }
+
+    /// Protective MBR and a GPT header with one partition entry
+    fn gpt_disk(entries_lba: u64, first_lba: u64, last_lba: u64) -> Vec<u8> {
+        let mut disk = vec![0u8; 512 * 64];
+        mbr_entry(&mut disk, 0, 0xee, 1, 63);
+        disk[512..520].copy_from_slice(b"EFI PART");
+        disk[512 + 72..512 + 80].copy_from_slice(&entries_lba.to_le_bytes());
+        disk[512 + 80..512 + 84].copy_from_slice(&4u32.to_le_bytes());
+        disk[512 + 84..512 + 88].copy_from_slice(&128u32.to_le_bytes());
+
+        let entry = 1024;
+        disk[entry..entry + 16].copy_from_slice(&[1; 16]);
+        disk[entry + 32..entry + 40].copy_from_slice(&first_lba.to_le_bytes());
+        disk[entry + 40..entry + 48].copy_from_slice(&last_lba.to_le_bytes());
+        disk
+    }
+
+    #[test]
+    fn test_read_partitions_gpt_bounds() {
+        let partitions = read_partitions(&mut Cursor::new(gpt_disk(2, 34, 63))).unwrap();
+        assert_eq!(
+            partitions,
+            vec![Partition {
+                number: 1,
+                offset: 17408,
+                size: 15360
+            }]
+        );
+
+        // Partition entries that overflow or are past the end of the image
+        assert!(read_partitions(&mut Cursor::new(gpt_disk(u64::MAX / 2, 34, 63))).is_err());
+        assert!(read_partitions(&mut Cursor::new(gpt_disk(100, 34, 63))).is_err());
+
+        // Partitions that overflow or are past the end of the image are skipped
+        let partitions =
+            read_partitions(&mut Cursor::new(gpt_disk(2, u64::MAX / 4, u64::MAX / 2))).unwrap();
+        assert!(partitions.is_empty());
+        let partitions = read_partitions(&mut Cursor::new(gpt_disk(2, 34, 64))).unwrap();
+        assert!(partitions.is_empty());
+    }
+
+    #[test]
+    fn test_read_partitions_volume() {
+        let mut disk = vec![0u8; 4096];
+        disk[3..11].copy_from_slice(b"NTFS    ");
+
+        let partitions = read_partitions(&mut Cursor::new(disk)).unwrap();
+        assert_eq!(
+            partitions,
+            vec![Partition {
+                number: 1,
+                offset: 0,
+                size: 4096
+            }]
+        );
+    }
+
+    #[test]
+    fn test_read_partitions_no_table() {
+        let disk = vec![0u8; 4096];
+        assert!(read_partitions(&mut Cursor::new(disk)).is_err());
+    }
+}
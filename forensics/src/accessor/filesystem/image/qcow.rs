@@ -0,0 +1,85 @@
+use crate::{
+    accessor::{
+        error::{AccessorError, AccessorResult},
+        filesystem::image::partitions::{Partition, read_partitions},
+    },
+    filesystem::disks::{error::DiskError, qcow::QcowDisk},
+};
+use std::path::Path;
+
+/// Open a QCOW disk image and discover its partitions
+///
+/// The guest disk is parsed with the same partition table reader as the `image:` scheme, so partition numbers match across containers.
+/// A guest disk without a partition table is returned as a single partition
+pub(crate) fn open_qcow(path: &Path) -> AccessorResult<(QcowDisk, Vec<Partition>)> {
+    let mut disk = QcowDisk::open(&path.to_string_lossy()).map_err(qcow_err)?;
+
+    let partitions = read_partitions(&mut disk)?;
+    if partitions.is_empty() {
+        return Err(AccessorError::Volume {
+            reason: format!("no partitions found in QCOW image {}", path.display()),
+        });
+    }
+
+    Ok((disk, partitions))
+}
+
+/// Map `DiskError` to `AccessorError`
+fn qcow_err(err: DiskError) -> AccessorError {
+    AccessorError::Volume {
+        reason: err.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::accessor::filesystem::image::{partitions::Partition, qcow::open_qcow};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_open_qcow() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/qcow/gpt_ext4.qcow2");
+
+        let (_, partitions) = open_qcow(&test_location).unwrap();
+        assert_eq!(
+            partitions,
+            vec![
+                Partition {
+                    number: 1,
+                    offset: 64 * 512,
+                    size: 64 * 512,
+                },
+                Partition {
+                    number: 2,
+                    offset: 128 * 512,
+                    size: 1024 * 512,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_open_qcow_gpt_gap() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        // Second GPT entry is unused. The ext4 partition is in the third slot
+        test_location.push("tests/test_data/disks/qcow/gpt_ext4_gap.qcow2");
+
+        let (_, partitions) = open_qcow(&test_location).unwrap();
+        assert_eq!(
+            partitions,
+            vec![
+                Partition {
+                    number: 1,
+                    offset: 64 * 512,
+                    size: 64 * 512,
+                },
+                Partition {
+                    number: 3,
+                    offset: 128 * 512,
+                    size: 1024 * 512,
+                },
+            ]
+        );
+    }
+}
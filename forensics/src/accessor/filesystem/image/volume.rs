@@ -0,0 +1,119 @@
+use crate::accessor::{filesystem::image::partitions::Partition, io::reader::ReadSeek};
+use std::{
+    fmt,
+    io::{self, Read, Seek, SeekFrom},
+    sync::{Arc, Mutex},
+};
+
+/// Disk image reader shared between all partitions of an image
+pub(crate) type SharedDisk = Arc<Mutex<Box<dyn ReadSeek + Send>>>;
+
+/// A `Read` + `Seek` window into a single partition of a disk image
+///
+/// Offsets are relative to the start of the partition. Each read seeks the shared disk reader
+pub(crate) struct PartitionReader {
+    /// Reader for the entire disk image
+    disk: SharedDisk,
+    /// Start of the partition in bytes
+    offset: u64,
+    /// Size of the partition in bytes
+    size: u64,
+    /// Position of the reader relative to the partition start
+    position: u64,
+}
+
+impl PartitionReader {
+    /// Create a `PartitionReader` for the provided `Partition`
+    pub(crate) fn new(disk: SharedDisk, partition: &Partition) -> Self {
+        Self {
+            disk,
+            offset: partition.offset,
+            size: partition.size,
+            position: 0,
+        }
+    }
+}
+
+impl Read for PartitionReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() || self.position >= self.size {
+            return Ok(0);
+        }
+
+        let remaining = (self.size - self.position).min(buf.len() as u64) as usize;
+        let mut disk = self
+            .disk
+            .lock()
+            .map_err(|err| io::Error::other(format!("disk image reader lock poisoned: {err:?}")))?;
+        disk.seek(SeekFrom::Start(self.offset + self.position))?;
+        let bytes = disk.read(&mut buf[..remaining])?;
+
+        self.position += bytes as u64;
+        Ok(bytes)
+    }
+}
+
+impl Seek for PartitionReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+        };
+
+        match new_pos {
+            Some(position) => {
+                self.position = position;
+                Ok(self.position)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+impl fmt::Debug for PartitionReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PartitionReader")
+            .field("offset", &self.offset)
+            .field("size", &self.size)
+            .field("position", &self.position)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::accessor::filesystem::image::{
+        partitions::Partition,
+        volume::{PartitionReader, SharedDisk},
+    };
+    use std::{
+        io::{Cursor, Read, Seek, SeekFrom},
+        sync::{Arc, Mutex},
+    };
+
+    #[test]
+    fn test_partition_reader() {
+        let disk: SharedDisk = Arc::new(Mutex::new(Box::new(Cursor::new(
+            (0..=255u8).collect::<Vec<u8>>(),
+        ))));
+        let partition = Partition {
+            number: 1,
+            offset: 16,
+            size: 8,
+        };
+        let mut reader = PartitionReader::new(disk, &partition);
+
+        let mut buf = [0u8; 4];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, [16, 17, 18, 19]);
+
+        reader.seek(SeekFrom::End(-2)).unwrap();
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, [22, 23]);
+    }
+}
@@ -1,4 +1,7 @@
+pub(crate) mod ext4;
 mod helper;
 pub(crate) mod host;
+pub(crate) mod image;
 pub(crate) mod ntfs;
+pub(crate) mod vss;
 pub(crate) mod zip;
@@ -3,13 +3,14 @@
 use crate::accessor::error::{AccessorError, AccessorResult};
 use ntfs::Ntfs;
 use std::{
+    fmt,
     fs::File,
     io::{self, BufReader, Read, Seek, SeekFrom},
     path::PathBuf,
     sync::{Mutex, MutexGuard},
 };
 
-const VOLUME_SECTOR_SIZE: u16 = 4096;
+pub(crate) const VOLUME_SECTOR_SIZE: u16 = 4096;
 
 /// `SectorReader` encapsulates any reader and only performs read and seek operations on it
 /// on boundaries of the given sector size.
@@ -135,6 +136,18 @@ where
     }
 }
 
+impl<R> fmt::Debug for SectorReader<R>
+where
+    R: Read + Seek,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SectorReader")
+            .field("sector_size", &self.sector_size)
+            .field("stream_position", &self.stream_position)
+            .finish_non_exhaustive()
+    }
+}
+
 /// Parsed NTFS volume backed by any [`Read`] + [`Seek`] source
 ///
 /// Used for live raw drives (Windows), disk images (any OS), and future image formats
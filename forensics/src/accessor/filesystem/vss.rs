@@ -0,0 +1,317 @@
+use crate::{
+    accessor::{
+        entry::{
+            handle::{DirEntry, DirHandle, EntryMeta, FileHandle, GlobMatch, ItemHandle},
+            locator::{DirLocator, FileLocator, ShadowCopy},
+        },
+        error::{AccessorError, AccessorResult},
+        filesystem::{
+            image::{
+                data::{PartitionFs, container_display_path, open_partition},
+                partitions::Partition,
+                volume::SharedDisk,
+            },
+            ntfs::volume::{SectorReader, VOLUME_SECTOR_SIZE},
+        },
+        io::reader::{AccessorReader, ReadSeek},
+        location::path::InnerPath,
+    },
+    filesystem::disks::vss::{ShadowStore, VssSnapshot, read_shadow_stores},
+};
+use std::{
+    fs::File,
+    io::BufReader,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+/// A filesystem like accessor for a Volume Shadow Copy snapshot
+///
+/// Paths start at the root of the volume. Example: `Windows/System32/config/SYSTEM`
+pub(crate) struct VssFs {
+    /// Volume and shadow copy store
+    pub(crate) snapshot: ShadowCopy,
+    /// Filesystem on the snapshot volume
+    fs: PartitionFs,
+}
+
+impl VssFs {
+    /// Open the snapshot and the filesystem on the snapshot volume
+    pub(crate) fn open(snapshot: ShadowCopy) -> AccessorResult<Self> {
+        let reader = open_shadow_copy(&snapshot)?;
+        let partition = Partition {
+            number: snapshot.store,
+            offset: 0,
+            size: reader.size(),
+        };
+        let disk: SharedDisk = Arc::new(Mutex::new(Box::new(reader)));
+
+        let Some(fs) = open_partition(&disk, &partition, &snapshot.display())? else {
+            return Err(AccessorError::Filesystem {
+                reason: format!("no supported filesystem found in {}", snapshot.display()),
+            });
+        };
+
+        Ok(Self { snapshot, fs })
+    }
+
+    /// Read a file into memory
+    pub(crate) fn read_file(
+        &self,
+        inner: &InnerPath,
+        max_read_size: Option<u64>,
+    ) -> AccessorResult<Vec<u8>> {
+        self.fs.read_file(inner, max_read_size)
+    }
+
+    /// Read a file into memory by its file handle
+    pub(crate) fn read_handle(
+        &self,
+        handle: &FileHandle,
+        max_read_size: Option<u64>,
+    ) -> AccessorResult<Vec<u8>> {
+        self.fs
+            .read_handle(&self.unwrap_file_handle(handle)?, max_read_size)
+    }
+
+    /// Create an `AccessorReader` to stream a file
+    pub(crate) fn reader(&self, inner: &InnerPath) -> AccessorResult<AccessorReader> {
+        self.fs.reader(inner)
+    }
+
+    /// Create an `AccessorReader` to stream a file by its file handle
+    pub(crate) fn reader_handle(&self, handle: &FileHandle) -> AccessorResult<AccessorReader> {
+        self.fs.reader_handle(&self.unwrap_file_handle(handle)?)
+    }
+
+    /// List files and directories in provided path
+    pub(crate) fn read_dir(&self, inner: &InnerPath) -> AccessorResult<Vec<DirEntry>> {
+        let entries = self.fs.read_dir(inner)?;
+        Ok(self.wrap_entries(entries))
+    }
+
+    /// List files and directories from provided `DirHandle`
+    pub(crate) fn read_dir_handle(&self, handle: &DirHandle) -> AccessorResult<Vec<DirEntry>> {
+        let DirLocator::Vss {
+            snapshot, inner, ..
+        } = &handle.locator
+        else {
+            return Err(AccessorError::invalid_handle(format!(
+                "vss source cannot list directory handle for {}",
+                handle.display_path()
+            )));
+        };
+        self.check_snapshot(snapshot, &handle.display_path())?;
+
+        let entries = self
+            .fs
+            .read_dir_handle(&DirHandle::new(inner.as_ref().clone()))?;
+        Ok(self.wrap_entries(entries))
+    }
+
+    /// Apply a glob pattern and return matches
+    pub(crate) fn globfs(
+        &self,
+        directory: &InnerPath,
+        pattern: &str,
+    ) -> AccessorResult<Vec<GlobMatch>> {
+        Ok(self
+            .fs
+            .globfs(directory, pattern)?
+            .into_iter()
+            .map(|entry| {
+                let (handle, meta) = self.wrap_handle(entry.handle, entry.meta);
+                GlobMatch::new(handle, meta)
+            })
+            .collect())
+    }
+
+    /// Ensure a handle belongs to this snapshot
+    fn check_snapshot(&self, snapshot: &ShadowCopy, display_path: &str) -> AccessorResult<()> {
+        if snapshot != &self.snapshot {
+            return Err(AccessorError::invalid_handle(format!(
+                "vss source cannot read handle for {display_path}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Return the filesystem `FileHandle` wrapped by a snapshot `FileHandle`
+    fn unwrap_file_handle(&self, handle: &FileHandle) -> AccessorResult<FileHandle> {
+        let FileLocator::Vss {
+            snapshot, inner, ..
+        } = &handle.locator
+        else {
+            return Err(AccessorError::invalid_handle(format!(
+                "vss source cannot read handle for {}",
+                handle.display_path()
+            )));
+        };
+        self.check_snapshot(snapshot, &handle.display_path())?;
+
+        Ok(FileHandle::new(inner.as_ref().clone()))
+    }
+
+    /// Wrap the snapshot filesystem entries with snapshot handles
+    fn wrap_entries(&self, entries: Vec<DirEntry>) -> Vec<DirEntry> {
+        entries
+            .into_iter()
+            .map(|entry| {
+                let (handle, meta) = self.wrap_handle(entry.handle, entry.meta);
+                DirEntry::new(entry.name, handle, meta)
+            })
+            .collect()
+    }
+
+    /// Wrap a filesystem handle so it can be routed back to this snapshot
+    fn wrap_handle(&self, handle: ItemHandle, meta: EntryMeta) -> (ItemHandle, EntryMeta) {
+        let display_path =
+            container_display_path(&format!("{}!", self.snapshot.display()), &meta.display_path);
+        let wrap_file = |file: FileHandle| {
+            FileHandle::new(FileLocator::Vss {
+                snapshot: self.snapshot.clone(),
+                inner: Box::new(file.locator),
+                display_path: display_path.clone(),
+            })
+        };
+
+        let handle = match handle {
+            ItemHandle::File(file) => ItemHandle::File(wrap_file(file)),
+            ItemHandle::Unsupported(file) => ItemHandle::Unsupported(wrap_file(file)),
+            ItemHandle::Directory(dir) => ItemHandle::Directory(DirHandle::new(DirLocator::Vss {
+                snapshot: self.snapshot.clone(),
+                inner: Box::new(dir.locator),
+                display_path: display_path.clone(),
+            })),
+        };
+
+        (handle, EntryMeta::new(meta.kind, meta.size, display_path))
+    }
+}
+
+/// Open a reader for the volume as it was when the snapshot was created
+pub(crate) fn open_shadow_copy(
+    snapshot: &ShadowCopy,
+) -> AccessorResult<VssSnapshot<Box<dyn ReadSeek + Send>>> {
+    let volume = open_volume(snapshot)?;
+    VssSnapshot::open(volume, snapshot.store).map_err(|err| AccessorError::Volume {
+        reason: format!("{}: {err}", snapshot.display()),
+    })
+}
+
+/// Return the catalog information for the snapshot. Includes the snapshot creation time
+pub(crate) fn shadow_copy_store(snapshot: &ShadowCopy) -> AccessorResult<ShadowStore> {
+    let mut volume = open_volume(snapshot)?;
+    let stores = read_shadow_stores(&mut volume).map_err(|err| AccessorError::Volume {
+        reason: format!("{}: {err}", snapshot.display()),
+    })?;
+
+    stores
+        .into_iter()
+        .find(|store| store.number == snapshot.store)
+        .ok_or_else(|| AccessorError::not_found(snapshot.display()))
+}
+
+/// Open the live volume by drive letter or a volume image file
+fn open_volume(snapshot: &ShadowCopy) -> AccessorResult<Box<dyn ReadSeek + Send>> {
+    if let Some(drive) = snapshot.drive() {
+        // Raw volume reads on Windows must be sector aligned
+        let device_path = format!(r"\\.\{drive}:");
+        let file =
+            File::open(&device_path).map_err(|err| AccessorError::io_path(&device_path, err))?;
+        let reader = SectorReader::new(file, VOLUME_SECTOR_SIZE).map_err(AccessorError::from)?;
+        return Ok(Box::new(BufReader::new(reader)));
+    }
+
+    let path = Path::new(&snapshot.volume);
+    if !path.is_file() {
+        return Err(AccessorError::not_found(snapshot.display()));
+    }
+    let file = File::open(path).map_err(|err| AccessorError::io_path(path, err))?;
+    Ok(Box::new(BufReader::new(file)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::accessor::{
+        entry::{handle::EntryKind, locator::ShadowCopy},
+        error::AccessorError,
+        filesystem::vss::{VssFs, shadow_copy_store},
+        location::path::InnerPath,
+    };
+    use std::path::PathBuf;
+
+    fn test_snapshot(store: u32) -> ShadowCopy {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/vss/ext4_vss.raw");
+        ShadowCopy::new(test_location.to_str().unwrap(), store)
+    }
+
+    #[test]
+    fn test_vss_read_file() {
+        let fs = VssFs::open(test_snapshot(1)).unwrap();
+        let bytes = fs
+            .read_file(&InnerPath::new(PathBuf::from("etc/hostname")), None)
+            .unwrap();
+        assert_eq!(bytes, b"artemis-vss1\n");
+
+        let fs = VssFs::open(test_snapshot(2)).unwrap();
+        let bytes = fs
+            .read_file(&InnerPath::new(PathBuf::from("etc/hostname")), None)
+            .unwrap();
+        assert_eq!(bytes, b"artemis-vss2\n");
+    }
+
+    #[test]
+    fn test_vss_read_dir() {
+        let fs = VssFs::open(test_snapshot(1)).unwrap();
+        let entries = fs.read_dir(&InnerPath::empty()).unwrap();
+        let home = entries.iter().find(|entry| entry.name == "home").unwrap();
+        assert_eq!(home.meta.kind, EntryKind::Directory);
+        assert!(home.meta.display_path.ends_with("ext4_vss.raw@1!/home"));
+
+        let files = fs
+            .read_dir_handle(home.handle.as_directory().unwrap())
+            .unwrap();
+        let notes = files
+            .iter()
+            .find(|entry| entry.name == "notes.txt")
+            .unwrap();
+        let bytes = fs
+            .read_handle(notes.handle.as_file().unwrap(), None)
+            .unwrap();
+        assert_eq!(bytes, b"snap notes\n");
+    }
+
+    #[test]
+    fn test_vss_globfs() {
+        let fs = VssFs::open(test_snapshot(2)).unwrap();
+        let matches = fs.globfs(&InnerPath::empty(), "*/host*").unwrap();
+        assert_eq!(matches.len(), 1);
+
+        let mut reader = fs
+            .reader_handle(matches[0].handle.as_file().unwrap())
+            .unwrap();
+        assert_eq!(reader.read_bytes(0, 12).unwrap(), b"artemis-vss2");
+    }
+
+    #[test]
+    fn test_vss_handle_wrong_snapshot() {
+        let first = VssFs::open(test_snapshot(1)).unwrap();
+        let second = VssFs::open(test_snapshot(2)).unwrap();
+        let matches = first.globfs(&InnerPath::empty(), "etc/hostname").unwrap();
+        let err = second
+            .read_handle(matches[0].handle.as_file().unwrap(), None)
+            .unwrap_err();
+        assert!(matches!(err, AccessorError::InvalidHandle { .. }));
+    }
+
+    #[test]
+    fn test_shadow_copy_store() {
+        let store = shadow_copy_store(&test_snapshot(2)).unwrap();
+        assert_eq!(store.created, "2024-02-01T00:00:00.000Z");
+
+        let err = shadow_copy_store(&test_snapshot(5)).unwrap_err();
+        assert!(matches!(err, AccessorError::NotFound { .. }));
+    }
+}
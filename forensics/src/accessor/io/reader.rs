@@ -1,3 +1,4 @@
+use crate::utils::governor::throttle;
 use std::{
     fmt::Debug,
     fs::File,
@@ -21,11 +22,14 @@ pub(crate) enum AccessorReader {
 
 impl Read for AccessorReader {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        match self {
-            Self::Host(file) => file.read(buf),
-            Self::Memory(cursor) => cursor.read(buf),
-            Self::Stream(stream) => stream.read(buf),
-        }
+        let bytes = match self {
+            Self::Host(file) => file.read(buf)?,
+            // Memory reads do not touch the disk and are not throttled
+            Self::Memory(cursor) => return cursor.read(buf),
+            Self::Stream(stream) => stream.read(buf)?,
+        };
+        throttle(bytes);
+        Ok(bytes)
     }
 }
 
@@ -31,7 +31,7 @@ impl Location {
         }
 
         // Determine the scheme of the data
-        // Can be ntfs, host, zip, or others
+        // Can be ntfs, host, zip, image, or others
         if let Some((scheme, remainder)) = split_scheme_prefix(value) {
             return parse_schemed_location(&format!("{scheme}:{remainder}"), None);
         }
@@ -48,7 +48,7 @@ impl Location {
 
         Err(AccessorError::location(
             value,
-            "expected an absolute host path or a scheme prefix such as host:, ntfs:, or zip:",
+            "expected an absolute host path or a scheme prefix such as host:, ntfs:, zip:, image:, qcow:, ewf:, vhd:, vhdx:, vmdk:, or vss:",
         ))
     }
 
@@ -87,16 +87,23 @@ impl Location {
         if is_absolute_host_path(value) {
             return Err(AccessorError::location(
                 input,
-                "expected a source spec such as host:, ntfs:C:, or zip:/path/archive.zip",
+                "expected a source spec such as host:, ntfs:C:, zip:/path/archive.zip, image:/path/disk.dd, qcow:/path/disk.qcow2, ewf:/path/case.E01, vmdk:/path/vm.vmdk, or vss:C@1",
             ));
         }
 
         Err(AccessorError::location(
             input,
-            "expected a source spec such as host:, ntfs:C:, or zip:/path/archive.zip",
+            "expected a source spec such as host:, ntfs:C:, zip:/path/archive.zip, image:/path/disk.dd, qcow:/path/disk.qcow2, ewf:/path/case.E01, vmdk:/path/vm.vmdk, or vss:C@1",
         ))
     }
 
+    /// Check if the input uses a scheme other than `host`. These paths can only be read with the `Accessor`
+    ///
+    /// Example: `ewf:case.E01!p2/Windows/System32/config/SYSTEM`
+    pub(crate) fn is_container(input: &str) -> bool {
+        Self::parse(input).is_ok_and(|location| location.scheme != Scheme::Host)
+    }
+
     /// Split a glob or read call input into location prefix and trailing pattern
     ///
     /// Example: `/var/log/*.log` -> (`/var/log/`, `*.log`)
@@ -179,13 +186,17 @@ fn parse_schemed_location(source_part: &str, inner_part: Option<&str>) -> Access
     let (scheme, remainder) = split_scheme_prefix(source_part).ok_or_else(|| {
         AccessorError::location(
             source_part,
-            "expected a scheme prefix such as host:, ntfs:, or zip:",
+            "expected a scheme prefix such as host:, ntfs:, zip:, image:, qcow:, ewf:, vhd:, vhdx:, vmdk:, or vss:",
         )
     })?;
 
     let scheme = Scheme::parse(scheme)?;
     let source = parse_source_path(scheme, remainder)?;
     let inner_path = match inner_part {
+        // Snapshot paths start at the root of the volume. Ex: vss:C@3!/Windows
+        Some(value) if scheme == Scheme::Vss => {
+            InnerPath::normalize_container_path(value.trim_start_matches(['/', '\\']))?
+        }
         Some(value) => InnerPath::normalize_container_path(value)?,
         None => parse_inner_path(scheme, remainder)?,
     };
@@ -230,9 +241,63 @@ fn parse_source_path(scheme: Scheme, remainder: &str) -> AccessorResult<Option<S
             }
             Ok(Some(SourcePath::new(PathBuf::from(remainder))))
         }
+        Scheme::Image | Scheme::Qcow | Scheme::Ewf | Scheme::Vhd | Scheme::Vhdx | Scheme::Vmdk => {
+            if remainder.is_empty() {
+                return Err(AccessorError::location(
+                    remainder,
+                    format!("{} source requires a disk image path", scheme.as_str()),
+                ));
+            }
+            if !is_host_path(remainder) {
+                return Err(AccessorError::location(
+                    remainder,
+                    "disk image paths must be absolute or relative host paths",
+                ));
+            }
+            Ok(Some(SourcePath::new(PathBuf::from(remainder))))
+        }
+        Scheme::Vss => {
+            let (volume, store) = parse_shadow_copy(remainder)?;
+            Ok(Some(SourcePath::new(PathBuf::from(format!(
+                "{volume}@{store}"
+            )))))
+        }
     }
 }
 
+/// Parse a shadow copy source into the volume and store number. Example: `C@3` or `/cases/volume.raw@1`
+///
+/// The volume is either a drive letter or a path to a volume image
+pub(crate) fn parse_shadow_copy(source: &str) -> AccessorResult<(String, u32)> {
+    let (volume, store) = source.rsplit_once('@').ok_or_else(|| {
+        AccessorError::location(
+            source,
+            "vss source requires a volume and store number such as vss:C@1",
+        )
+    })?;
+    let store = store
+        .parse::<u32>()
+        .ok()
+        .filter(|store| *store > 0)
+        .ok_or_else(|| {
+            AccessorError::location(source, "vss store number must be a number starting at 1")
+        })?;
+
+    // Drive letters can be written as C or C:
+    let drive = volume.trim_end_matches(':');
+    if drive.len() == 1 && drive.chars().all(|value| value.is_ascii_alphabetic()) {
+        return Ok((drive.to_ascii_uppercase(), store));
+    }
+    if volume.is_empty() || !is_host_path(volume) {
+        return Err(AccessorError::location(
+            source,
+            "vss volume must be a drive letter or a path to a volume image",
+        ));
+    }
+
+    Ok((volume.to_string(), store))
+}
+
 /// Supported raw filesystem access
 #[derive(Debug, PartialEq)]
 enum RawFileSystem {
@@ -308,7 +373,14 @@ fn parse_inner_path(scheme: Scheme, remainder: &str) -> AccessorResult<InnerPath
             }
             Ok(InnerPath::new(PathBuf::from(remainder)))
         }
-        Scheme::Zip => Ok(InnerPath::empty()),
+        Scheme::Zip
+        | Scheme::Image
+        | Scheme::Qcow
+        | Scheme::Ewf
+        | Scheme::Vhd
+        | Scheme::Vhdx
+        | Scheme::Vmdk
+        | Scheme::Vss => Ok(InnerPath::empty()),
     }
 }
 
@@ -336,6 +408,82 @@ mod tests {
         assert_eq!(result.source.unwrap().display(), "data.zip");
     }
 
+    #[test]
+    fn test_location_image() {
+        let test = "image:disk.dd!p2/Windows/System32/config/SYSTEM";
+        let result = Location::parse(test).unwrap();
+        assert_eq!(result.scheme, Scheme::Image);
+        assert_eq!(
+            result.inner_path.display().replace('\\', "/"),
+            "p2/Windows/System32/config/SYSTEM"
+        );
+        assert_eq!(result.source.unwrap().display(), "disk.dd");
+
+        let (loc, pattern) = Location::split_glob_pattern("image:disk.dd!p1/home/*").unwrap();
+        assert_eq!(loc.scheme, Scheme::Image);
+        assert_eq!(loc.inner_path.display().replace('\\', "/"), "p1/home");
+        assert_eq!(pattern, "*");
+    }
+
+    #[test]
+    fn test_location_ewf() {
+        let test = "ewf:/cases/case.E01!p2/Windows/System32/config/SYSTEM";
+        let result = Location::parse(test).unwrap();
+        assert_eq!(result.scheme, Scheme::Ewf);
+        assert_eq!(
+            result.inner_path.display().replace('\\', "/"),
+            "p2/Windows/System32/config/SYSTEM"
+        );
+        assert_eq!(result.source.unwrap().display(), "/cases/case.E01");
+    }
+
+    #[test]
+    fn test_location_virtual_disks() {
+        let tests = [
+            ("vhd:/vms/azure.vhd!p1/etc/passwd", Scheme::Vhd),
+            ("vhdx:/vms/server.vhdx!p3/Windows", Scheme::Vhdx),
+            ("VMDK:/vms/vm.vmdk!p2/etc/passwd", Scheme::Vmdk),
+        ];
+        for (test, scheme) in tests {
+            let result = Location::parse(test).unwrap();
+            assert_eq!(result.scheme, scheme);
+            assert!(result.inner_path.display().starts_with('p'));
+            assert!(Location::is_container(test));
+        }
+    }
+
+    #[test]
+    fn test_location_vss() {
+        let test = "vss:C@3!/Windows/System32/config/SYSTEM";
+        let result = Location::parse(test).unwrap();
+        assert_eq!(result.scheme, Scheme::Vss);
+        assert_eq!(
+            result.inner_path.display().replace('\\', "/"),
+            "Windows/System32/config/SYSTEM"
+        );
+        assert_eq!(result.source.unwrap().display(), "C@3");
+        assert!(Location::is_container(test));
+
+        let result = Location::parse("vss:/cases/volume.raw@1!etc/hostname").unwrap();
+        assert_eq!(result.source.unwrap().display(), "/cases/volume.raw@1");
+
+        let result = Location::parse_source("vss:c:@2").unwrap();
+        assert_eq!(result.source.unwrap().display(), "C@2");
+
+        assert!(Location::parse("vss:C!/Windows").is_err());
+        assert!(Location::parse("vss:C@0!/Windows").is_err());
+        assert!(Location::parse("vss:@1!/Windows").is_err());
+    }
+
+    #[test]
+    fn test_location_is_container() {
+        assert!(Location::is_container("ewf:case.E01!p2/Windows"));
+        assert!(Location::is_container("zip:archive.zip!NTUSER.DAT"));
+        assert!(!Location::is_container("C:\\Users\\NTUSER.DAT"));
+        assert!(!Location::is_container("/home/user/.bash_history"));
+        assert!(!Location::is_container("host:/home/user/.bash_history"));
+    }
+
     #[test]
     fn test_location_raw() {
         let test = "ntfs:C:\\home\\test.txt";
@@ -9,6 +9,20 @@ pub(crate) enum Scheme {
     RawNtfs,
     /// Access the data inside a zip file
     Zip,
+    /// Access the data inside a raw disk image
+    Image,
+    /// Access the data inside a QCOW2 disk image
+    Qcow,
+    /// Access the data inside an Expert Witness (E01) evidence container
+    Ewf,
+    /// Access the data inside a VHD disk
+    Vhd,
+    /// Access the data inside a VHDX disk
+    Vhdx,
+    /// Access the data inside a VMDK disk
+    Vmdk,
+    /// Access the data inside a Volume Shadow Copy snapshot
+    Vss,
 }
 
 impl Scheme {
@@ -18,6 +32,13 @@ impl Scheme {
             Self::Host => "host",
             Self::RawNtfs => "ntfs",
             Self::Zip => "zip",
+            Self::Image => "image",
+            Self::Qcow => "qcow",
+            Self::Ewf => "ewf",
+            Self::Vhd => "vhd",
+            Self::Vhdx => "vhdx",
+            Self::Vmdk => "vmdk",
+            Self::Vss => "vss",
         }
     }
 
@@ -27,6 +48,13 @@ impl Scheme {
             "host" => Ok(Self::Host),
             "ntfs" => Ok(Self::RawNtfs),
             "zip" => Ok(Self::Zip),
+            "image" => Ok(Self::Image),
+            "qcow" | "qcow2" => Ok(Self::Qcow),
+            "ewf" | "e01" => Ok(Self::Ewf),
+            "vhd" => Ok(Self::Vhd),
+            "vhdx" => Ok(Self::Vhdx),
+            "vmdk" => Ok(Self::Vmdk),
+            "vss" => Ok(Self::Vss),
             _ => Err(AccessorError::unsupported_scheme(value)),
         }
     }
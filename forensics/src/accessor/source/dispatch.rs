@@ -3,7 +3,10 @@ use crate::accessor::{
     error::AccessorResult,
     io::reader::AccessorReader,
     location::path::InnerPath,
-    source::{backend::SourceBackend, host::HostSource, ntfs::NtfsSource, zip::ZipSource},
+    source::{
+        backend::SourceBackend, host::HostSource, image::ImageSource, ntfs::NtfsSource,
+        vss::VssSource, zip::ZipSource,
+    },
 };
 
 /// Supported sources that we support reading data from
@@ -11,6 +14,8 @@ use crate::accessor::{
 /// Host - Live system
 /// Zip - Zip file
 /// NTFS - raw disk access
+/// Image - raw disk image
+/// Vss - Volume Shadow Copy snapshot
 pub(crate) enum Source {
     /// Use the live system as the source
     Host(HostSource),
@@ -18,6 +23,10 @@ pub(crate) enum Source {
     Zip(ZipSource),
     /// Use raw NTFS Windows drive as the source
     RawNtfs(NtfsSource),
+    /// Use a raw disk image as the source
+    Image(ImageSource),
+    /// Use a Volume Shadow Copy snapshot as the source
+    Vss(VssSource),
 }
 
 impl Source {
@@ -27,6 +36,8 @@ impl Source {
             Self::Host(source) => source.read_file(inner),
             Self::Zip(source) => source.read_file(inner),
             Self::RawNtfs(source) => source.read_file(inner),
+            Self::Image(source) => source.read_file(inner),
+            Self::Vss(source) => source.read_file(inner),
         }
     }
 
@@ -36,6 +47,8 @@ impl Source {
             Self::Host(source) => source.read_dir(inner),
             Self::Zip(source) => source.read_dir(inner),
             Self::RawNtfs(source) => source.read_dir(inner),
+            Self::Image(source) => source.read_dir(inner),
+            Self::Vss(source) => source.read_dir(inner),
         }
     }
 
@@ -44,6 +57,8 @@ impl Source {
             Self::Host(source) => source.read_dir_handle(handle),
             Self::Zip(source) => source.read_dir_handle(handle),
             Self::RawNtfs(source) => source.read_dir_handle(handle),
+            Self::Image(source) => source.read_dir_handle(handle),
+            Self::Vss(source) => source.read_dir_handle(handle),
         }
     }
 
@@ -53,6 +68,8 @@ impl Source {
             Self::Host(source) => source.globfs(dir, pattern),
             Self::Zip(source) => source.globfs(dir, pattern),
             Self::RawNtfs(source) => source.globfs(dir, pattern),
+            Self::Image(source) => source.globfs(dir, pattern),
+            Self::Vss(source) => source.globfs(dir, pattern),
         }
     }
 
@@ -62,6 +79,8 @@ impl Source {
             Self::Host(source) => source.read_file_handle(handle),
             Self::Zip(source) => source.read_file_handle(handle),
             Self::RawNtfs(source) => source.read_file_handle(handle),
+            Self::Image(source) => source.read_file_handle(handle),
+            Self::Vss(source) => source.read_file_handle(handle),
         }
     }
 
@@ -71,6 +90,8 @@ impl Source {
             Self::Host(source) => source.open_reader_handle(handle),
             Self::Zip(source) => source.open_reader_handle(handle),
             Self::RawNtfs(source) => source.open_reader_handle(handle),
+            Self::Image(source) => source.open_reader_handle(handle),
+            Self::Vss(source) => source.open_reader_handle(handle),
         }
     }
 
@@ -80,6 +101,8 @@ impl Source {
             Self::Host(source) => source.open_reader(inner),
             Self::Zip(source) => source.open_reader(inner),
             Self::RawNtfs(source) => source.open_reader(inner),
+            Self::Image(source) => source.open_reader(inner),
+            Self::Vss(source) => source.open_reader(inner),
         }
     }
 }
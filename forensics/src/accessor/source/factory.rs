@@ -3,12 +3,19 @@ use crate::accessor::{
     config::AccessorConfig,
     entry::{
         handle::{DirEntry, DirHandle, FileHandle, GlobMatch},
-        locator::{DirLocator, FileLocator, SourceId},
+        locator::{DirLocator, DiskFormat, DiskImage, FileLocator, ShadowCopy, SourceId},
     },
     error::{AccessorError, AccessorResult},
     io::reader::AccessorReader,
-    location::{loc::Location, path::InnerPath, scheme::Scheme},
-    source::{dispatch::Source, host::HostSource, ntfs::NtfsSource, zip::ZipSource},
+    location::{
+        loc::{Location, parse_shadow_copy},
+        path::InnerPath,
+        scheme::Scheme,
+    },
+    source::{
+        dispatch::Source, host::HostSource, image::ImageSource, ntfs::NtfsSource, vss::VssSource,
+        zip::ZipSource,
+    },
 };
 use std::path::PathBuf;
 
@@ -41,6 +48,8 @@ pub(crate) fn ensure_source(
         SourceId::Host => Source::Host(HostSource::new(config)),
         SourceId::RawNtfs(drive) => Source::RawNtfs(NtfsSource::new(config, *drive)?),
         SourceId::Zip(path) => Source::Zip(ZipSource::new(config, path.clone())?),
+        SourceId::Image(image) => Source::Image(ImageSource::new(config, image.clone())?),
+        SourceId::Vss(snapshot) => Source::Vss(VssSource::new(config, snapshot.clone())?),
     };
 
     cache.insert(source_id.clone(), source);
@@ -52,6 +61,11 @@ pub(crate) fn ensure_source(
 /// - `Host` → [`SourceId::Host`]
 /// - `Zip` → [`SourceId::Zip`]
 /// - `RawNtfs` → [`SourceId::RawNtfs`]
+/// - `Image` → [`SourceId::Image`] with [`DiskFormat::Raw`]
+/// - `Qcow` → [`SourceId::Image`] with [`DiskFormat::Qcow`]
+/// - `Ewf` → [`SourceId::Image`] with [`DiskFormat::Ewf`]
+/// - `Vhd`, `Vhdx`, `Vmdk` → [`SourceId::Image`] with the matching [`DiskFormat`]
+/// - `Vss` → [`SourceId::Vss`]
 pub(crate) fn source_id_from_location(location: &Location) -> AccessorResult<SourceId> {
     match location.scheme {
         Scheme::Host => Ok(SourceId::Host),
@@ -73,6 +87,36 @@ pub(crate) fn source_id_from_location(location: &Location) -> AccessorResult<Sou
                 .ok_or_else(|| AccessorError::location("", "zip location missing archive path"))?;
             Ok(SourceId::Zip(source.as_path().to_path_buf()))
         }
+        Scheme::Image | Scheme::Qcow | Scheme::Ewf | Scheme::Vhd | Scheme::Vhdx | Scheme::Vmdk => {
+            let source = location.source.as_ref().ok_or_else(|| {
+                AccessorError::location(
+                    "",
+                    format!(
+                        "{} location missing disk image path",
+                        location.scheme.as_str()
+                    ),
+                )
+            })?;
+            let format = match location.scheme {
+                Scheme::Qcow => DiskFormat::Qcow,
+                Scheme::Ewf => DiskFormat::Ewf,
+                Scheme::Vhd => DiskFormat::Vhd,
+                Scheme::Vhdx => DiskFormat::Vhdx,
+                Scheme::Vmdk => DiskFormat::Vmdk,
+                _ => DiskFormat::Raw,
+            };
+            Ok(SourceId::Image(DiskImage::new(
+                format,
+                source.as_path().to_path_buf(),
+            )))
+        }
+        Scheme::Vss => {
+            let source = location.source.as_ref().ok_or_else(|| {
+                AccessorError::location("", "vss location missing volume and store number")
+            })?;
+            let (volume, store) = parse_shadow_copy(&source.display())?;
+            Ok(SourceId::Vss(ShadowCopy::new(&volume, store)))
+        }
     }
 }
 
@@ -85,6 +129,11 @@ pub(crate) fn source_id_from_file_locator(locator: &FileLocator) -> AccessorResu
         FileLocator::Host { .. } => Ok(SourceId::Host),
         FileLocator::Ntfs { drive, .. } => Ok(SourceId::RawNtfs(*drive)),
         FileLocator::Zip { archive, .. } => Ok(SourceId::Zip(archive.clone())),
+        FileLocator::Image { image, .. } => Ok(SourceId::Image(image.clone())),
+        FileLocator::Vss { snapshot, .. } => Ok(SourceId::Vss(snapshot.clone())),
+        FileLocator::Ext4 { display_path, .. } => Err(AccessorError::invalid_handle(format!(
+            "ext4 handle {display_path} must be read through an image source"
+        ))),
     }
 }
 
@@ -195,6 +244,20 @@ pub(crate) fn validate_file_handle_for_source(
                 ..
             },
         ) if archive == handle_archive => Ok(()),
+        (
+            SourceId::Image(image),
+            FileLocator::Image {
+                image: handle_image,
+                ..
+            },
+        ) if image == handle_image => Ok(()),
+        (
+            SourceId::Vss(snapshot),
+            FileLocator::Vss {
+                snapshot: handle_snapshot,
+                ..
+            },
+        ) if snapshot == handle_snapshot => Ok(()),
         _ => Err(AccessorError::invalid_handle(format!(
             "file handle does not belong to open source {}",
             source_id.display()
@@ -208,6 +271,11 @@ pub(crate) fn source_id_from_dir_locator(locator: &DirLocator) -> AccessorResult
         DirLocator::Host { .. } => Ok(SourceId::Host),
         DirLocator::Ntfs { drive, .. } => Ok(SourceId::RawNtfs(*drive)),
         DirLocator::Zip { archive, .. } => Ok(SourceId::Zip(archive.clone())),
+        DirLocator::Image { image, .. } => Ok(SourceId::Image(image.clone())),
+        DirLocator::Vss { snapshot, .. } => Ok(SourceId::Vss(snapshot.clone())),
+        DirLocator::Ext4 { display_path, .. } => Err(AccessorError::invalid_handle(format!(
+            "ext4 handle {display_path} must be read through an image source"
+        ))),
     }
 }
 
@@ -244,6 +312,20 @@ pub(crate) fn validate_dir_handle_for_source(
                 ..
             },
         ) if archive == handle_archive => Ok(()),
+        (
+            SourceId::Image(image),
+            DirLocator::Image {
+                image: handle_image,
+                ..
+            },
+        ) if image == handle_image => Ok(()),
+        (
+            SourceId::Vss(snapshot),
+            DirLocator::Vss {
+                snapshot: handle_snapshot,
+                ..
+            },
+        ) if snapshot == handle_snapshot => Ok(()),
         _ => Err(AccessorError::invalid_handle(format!(
             "directory handle does not belong to open source {}",
             source_id.display()
@@ -0,0 +1,157 @@
+use crate::accessor::{
+    config::AccessorConfig,
+    entry::{
+        handle::{DirEntry, DirHandle, FileHandle, GlobMatch},
+        locator::{DiskImage, SourceId},
+    },
+    error::AccessorResult,
+    filesystem::image::data::ImageFs,
+    io::reader::AccessorReader,
+    location::path::InnerPath,
+    source::backend::SourceBackend,
+};
+
+/// Use a disk image as our source for data access
+pub(crate) struct ImageSource {
+    /// Max file size to read
+    max_read_size: Option<u64>,
+    /// Reader for the partitions in the disk image
+    fs: ImageFs,
+}
+
+impl ImageSource {
+    /// Create a new `ImageSource` instance
+    pub(crate) fn new(config: &AccessorConfig, image: DiskImage) -> AccessorResult<Self> {
+        Ok(Self {
+            fs: ImageFs::open(image)?,
+            max_read_size: config.max_read_size,
+        })
+    }
+}
+
+impl SourceBackend for ImageSource {
+    fn source_id(&self) -> SourceId {
+        SourceId::Image(self.fs.image.clone())
+    }
+
+    fn read_file(&self, inner: &InnerPath) -> AccessorResult<Vec<u8>> {
+        self.fs.read_file(inner, self.max_read_size)
+    }
+
+    fn read_dir(&self, inner: &InnerPath) -> AccessorResult<Vec<DirEntry>> {
+        self.fs.read_dir(inner)
+    }
+
+    fn read_dir_handle(&self, handle: &DirHandle) -> AccessorResult<Vec<DirEntry>> {
+        self.fs.read_dir_handle(handle)
+    }
+
+    fn globfs(&self, directory: &InnerPath, pattern: &str) -> AccessorResult<Vec<GlobMatch>> {
+        self.fs.globfs(directory, pattern)
+    }
+
+    fn read_file_handle(&self, handle: &FileHandle) -> AccessorResult<Vec<u8>> {
+        self.fs.read_handle(handle, self.max_read_size)
+    }
+
+    fn open_reader(&self, inner: &InnerPath) -> AccessorResult<AccessorReader> {
+        self.fs.reader(inner)
+    }
+
+    fn open_reader_handle(&self, handle: &FileHandle) -> AccessorResult<AccessorReader> {
+        self.fs.reader_handle(handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::accessor::{
+        config::AccessorConfig,
+        entry::{
+            handle::FileHandle,
+            locator::{DiskFormat, DiskImage},
+        },
+        error::AccessorError,
+        location::path::InnerPath,
+        source::{backend::SourceBackend, image::ImageSource},
+    };
+    use std::path::PathBuf;
+
+    fn test_source(config: &AccessorConfig) -> ImageSource {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/raw/gpt_ext4.raw");
+        ImageSource::new(config, DiskImage::new(DiskFormat::Raw, test_location)).unwrap()
+    }
+
+    #[test]
+    fn test_image_source_read_file() {
+        let source = test_source(&AccessorConfig::default());
+        let bytes = source
+            .read_file(&InnerPath::new(PathBuf::from("p2/etc/passwd")))
+            .unwrap();
+        assert_eq!(bytes.len(), 77);
+    }
+
+    #[test]
+    fn test_image_source_enforces_max_read_size() {
+        let source = test_source(&AccessorConfig {
+            max_read_size: Some(16),
+            ..AccessorConfig::default()
+        });
+        let err = source
+            .read_file(&InnerPath::new(PathBuf::from("p2/etc/passwd")))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AccessorError::FileTooLarge {
+                size: 77,
+                limit: 16
+            }
+        ));
+    }
+
+    #[test]
+    fn test_image_source_rejects_host_handle() {
+        let source = test_source(&AccessorConfig::default());
+        let err = source
+            .read_file_handle(&FileHandle::host("/etc/passwd"))
+            .unwrap_err();
+        assert!(matches!(err, AccessorError::InvalidHandle { .. }));
+    }
+
+    #[test]
+    fn test_image_source_qcow() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/qcow/gpt_ext4.qcow2");
+        let source = ImageSource::new(
+            &AccessorConfig::default(),
+            DiskImage::new(DiskFormat::Qcow, test_location),
+        )
+        .unwrap();
+
+        let bytes = source
+            .read_file(&InnerPath::new(PathBuf::from("p2/etc/hostname")))
+            .unwrap();
+        assert_eq!(bytes, b"artemis-image\n");
+        assert!(source.source_id().display().starts_with("qcow:"));
+    }
+
+    #[test]
+    fn test_image_source_ewf() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/ewf/gpt_ext4.E01");
+        let source = ImageSource::new(
+            &AccessorConfig::default(),
+            DiskImage::new(DiskFormat::Ewf, test_location),
+        )
+        .unwrap();
+
+        let bytes = source
+            .read_file(&InnerPath::new(PathBuf::from(
+                "p2/home/analyst/.bash_history",
+            )))
+            .unwrap();
+        assert_eq!(bytes, b"ls -la\ncat /etc/passwd\n");
+        assert!(source.source_id().display().starts_with("ewf:"));
+    }
+}
@@ -3,5 +3,7 @@ pub(crate) mod dispatch;
 pub(crate) mod factory;
 pub(crate) mod handle;
 mod host;
+mod image;
 mod ntfs;
+mod vss;
 mod zip;
@@ -0,0 +1,111 @@
+use crate::accessor::{
+    config::AccessorConfig,
+    entry::{
+        handle::{DirEntry, DirHandle, FileHandle, GlobMatch},
+        locator::{ShadowCopy, SourceId},
+    },
+    error::AccessorResult,
+    filesystem::vss::VssFs,
+    io::reader::AccessorReader,
+    location::path::InnerPath,
+    source::backend::SourceBackend,
+};
+
+/// Use a Volume Shadow Copy snapshot as our source for data access
+pub(crate) struct VssSource {
+    /// Max file size to read
+    max_read_size: Option<u64>,
+    /// Reader for the filesystem on the snapshot volume
+    fs: VssFs,
+}
+
+impl VssSource {
+    /// Create a new `VssSource` instance
+    pub(crate) fn new(config: &AccessorConfig, snapshot: ShadowCopy) -> AccessorResult<Self> {
+        Ok(Self {
+            fs: VssFs::open(snapshot)?,
+            max_read_size: config.max_read_size,
+        })
+    }
+}
+
+impl SourceBackend for VssSource {
+    fn source_id(&self) -> SourceId {
+        SourceId::Vss(self.fs.snapshot.clone())
+    }
+
+    fn read_file(&self, inner: &InnerPath) -> AccessorResult<Vec<u8>> {
+        self.fs.read_file(inner, self.max_read_size)
+    }
+
+    fn read_dir(&self, inner: &InnerPath) -> AccessorResult<Vec<DirEntry>> {
+        self.fs.read_dir(inner)
+    }
+
+    fn read_dir_handle(&self, handle: &DirHandle) -> AccessorResult<Vec<DirEntry>> {
+        self.fs.read_dir_handle(handle)
+    }
+
+    fn globfs(&self, directory: &InnerPath, pattern: &str) -> AccessorResult<Vec<GlobMatch>> {
+        self.fs.globfs(directory, pattern)
+    }
+
+    fn read_file_handle(&self, handle: &FileHandle) -> AccessorResult<Vec<u8>> {
+        self.fs.read_handle(handle, self.max_read_size)
+    }
+
+    fn open_reader(&self, inner: &InnerPath) -> AccessorResult<AccessorReader> {
+        self.fs.reader(inner)
+    }
+
+    fn open_reader_handle(&self, handle: &FileHandle) -> AccessorResult<AccessorReader> {
+        self.fs.reader_handle(handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::accessor::{
+        config::AccessorConfig,
+        entry::locator::ShadowCopy,
+        error::AccessorError,
+        location::path::InnerPath,
+        source::{backend::SourceBackend, vss::VssSource},
+    };
+    use std::path::PathBuf;
+
+    fn test_source(config: &AccessorConfig, store: u32) -> VssSource {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/vss/ext4_vss.raw");
+        VssSource::new(
+            config,
+            ShadowCopy::new(test_location.to_str().unwrap(), store),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_vss_source_read_file() {
+        let source = test_source(&AccessorConfig::default(), 2);
+        let bytes = source
+            .read_file(&InnerPath::new(PathBuf::from("home/notes.txt")))
+            .unwrap();
+        assert_eq!(bytes, b"snap notes\n");
+        assert!(source.source_id().display().ends_with("ext4_vss.raw@2"));
+    }
+
+    #[test]
+    fn test_vss_source_enforces_max_read_size() {
+        let source = test_source(
+            &AccessorConfig {
+                max_read_size: Some(4),
+                ..AccessorConfig::default()
+            },
+            1,
+        );
+        let err = source
+            .read_file(&InnerPath::new(PathBuf::from("etc/hostname")))
+            .unwrap_err();
+        assert!(matches!(err, AccessorError::FileTooLarge { .. }));
+    }
+}
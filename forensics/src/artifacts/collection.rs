@@ -1,9 +1,10 @@
 use super::{
     error::CollectionError,
     os::{
+        browsers::artifact::{grab_chromium, grab_firefox},
         connections::artifact::list_connections,
         files::artifact::filelisting,
-        linux::artifacts::{ext4_filelist, journals, logons, sudo_logs_linux},
+        linux::artifacts::{ext4_filelist, journals, logons, sudo_logs_linux, user_activity},
         macos::artifacts::{
             emond, fseventsd, groups_macos, launchd, loginitems, spotlight, sudo_logs_macos,
             unifiedlogs, users_macos,
@@ -12,15 +13,17 @@ use super::{
         systeminfo::artifact::systeminfo,
         triage::artifact::triage,
         windows::artifacts::{
-            amcache, bits, eventlogs, jumplists, mft, outlook, prefetch, raw_filelist, recycle_bin,
-            registry, search, services, shellbags, shimcache, shimdb, shortcuts, srum, tasks,
-            userassist, users_windows, usnjrnl, wmi_persist,
+            activitiescache, amcache, bits, eventlogs, hiberfil, jumplists, logfile, mft, outlook,
+            pagefile, prefetch, raw_filelist, recycle_bin, registry, search, services, shellbags,
+            shimcache, shimdb, shortcuts, srum, tasks, userassist, users_windows, usnjrnl,
+            wmi_persist,
         },
     },
 };
 use crate::{
-    output::{manager::OutputManager, marker::MarkerTracker},
+    output::{context::clear_source, manager::OutputManager, marker::MarkerTracker},
     structs::toml::ArtemisToml,
+    utils::{cancel::CancelToken, governor::Governor},
 };
 use serde::Serialize;
 use tracing::{Level, error, info, span, warn};
@@ -28,8 +31,11 @@ use tracing::{Level, error, info, span, warn};
 #[cfg(feature = "boa")]
 use crate::runtime::run::execute_script;
 
-/// Parse the TOML collector and get artifacts
-pub(crate) fn collect(mut collector: ArtemisToml) -> Result<(), CollectionError> {
+/// Parse the TOML collector and get artifacts. Stops early if the `CancelToken` is cancelled
+pub(crate) fn collect(
+    mut collector: ArtemisToml,
+    cancel: &CancelToken,
+) -> Result<(), CollectionError> {
     let mut manager = match OutputManager::new(collector.output) {
         Ok(result) => result,
         Err(err) => {
@@ -37,14 +43,27 @@ pub(crate) fn collect(mut collector: ArtemisToml) -> Result<(), CollectionError>
             return Err(CollectionError::Output);
         }
     };
+    manager.cancel = match manager.config.resources.clone() {
+        Some(policy) => cancel.with_governor(Governor::new(policy)),
+        None => cancel.clone(),
+    };
+    // Reads on this thread are throttled until the collection is finalized
+    let governor_guard = Governor::install(&manager.cancel);
 
     // Loop through all supported artifacts
     for artifacts in &mut collector.artifacts {
         manager.filter = artifacts.filter.unwrap_or(false);
 
         let artifact = artifacts.artifact_name.as_str();
+        if cancel.is_cancelled() {
+            warn!("Collection cancelled. Skipping {artifact} and remaining artifacts");
+            manager.cut_short = true;
+            break;
+        }
         let span = span!(Level::WARN, "Parsing artifact", artifact);
         let _guard = span.enter();
+        // Snapshot metadata only applies to the artifact that opened the snapshot
+        clear_source();
 
         match artifact {
             "loginitems" if !skip(&artifacts.loginitems, &collector.marker, artifact) => {
@@ -103,6 +122,34 @@ pub(crate) fn collect(mut collector: ArtemisToml) -> Result<(), CollectionError>
                     }
                 }
             }
+            "chromium" if !skip(&artifacts.chromium, &collector.marker, artifact) => {
+                let options = match &artifacts.chromium {
+                    Some(result) => result,
+                    None => continue,
+                };
+                let results = grab_chromium(options, &mut manager);
+                match results {
+                    Ok(_) => info!("Collected Chromium"),
+                    Err(err) => {
+                        error!("Failed to parse Chromium: {err:?}");
+                        manager.write_failed_artifact(artifact, options);
+                    }
+                }
+            }
+            "firefox" if !skip(&artifacts.firefox, &collector.marker, artifact) => {
+                let options = match &artifacts.firefox {
+                    Some(result) => result,
+                    None => continue,
+                };
+                let results = grab_firefox(options, &mut manager);
+                match results {
+                    Ok(_) => info!("Collected Firefox"),
+                    Err(err) => {
+                        error!("Failed to parse Firefox: {err:?}");
+                        manager.write_failed_artifact(artifact, options);
+                    }
+                }
+            }
             "files" if !skip(&artifacts.files, &collector.marker, artifact) => {
                 let options = match &artifacts.files {
                     Some(result_data) => result_data,
@@ -250,6 +297,21 @@ pub(crate) fn collect(mut collector: ArtemisToml) -> Result<(), CollectionError>
                     }
                 }
             }
+            "useractivity" if !skip(&artifacts.useractivity, &collector.marker, artifact) => {
+                let options = match &artifacts.useractivity {
+                    Some(result_data) => result_data,
+                    _ => continue,
+                };
+
+                let results = user_activity(&mut manager, options);
+                match results {
+                    Ok(_) => info!("Collected user activity"),
+                    Err(err) => {
+                        error!("Failed to parse user activity: {err:?}");
+                        manager.write_failed_artifact(artifact, options);
+                    }
+                }
+            }
             "logons" if !skip(&artifacts.logons, &collector.marker, artifact) => {
                 let options = match &artifacts.logons {
                     Some(result_data) => result_data,
@@ -450,6 +512,62 @@ pub(crate) fn collect(mut collector: ArtemisToml) -> Result<(), CollectionError>
                     }
                 }
             }
+            "logfile" if !skip(&artifacts.logfile, &collector.marker, artifact) => {
+                let options = match &artifacts.logfile {
+                    Some(result) => result,
+                    None => continue,
+                };
+                let results = logfile(options, &mut manager);
+                match results {
+                    Ok(_) => info!("Collected logfile"),
+                    Err(err) => {
+                        error!("Failed to parse logfile: {err:?}");
+                        manager.write_failed_artifact(artifact, options);
+                    }
+                }
+            }
+            "hiberfil" if !skip(&artifacts.hiberfil, &collector.marker, artifact) => {
+                let options = match &artifacts.hiberfil {
+                    Some(result) => result,
+                    None => continue,
+                };
+                let results = hiberfil(options, &mut manager);
+                match results {
+                    Ok(_) => info!("Collected hiberfil"),
+                    Err(err) => {
+                        error!("Failed to parse hiberfil: {err:?}");
+                        manager.write_failed_artifact(artifact, options);
+                    }
+                }
+            }
+            "pagefile" if !skip(&artifacts.pagefile, &collector.marker, artifact) => {
+                let options = match &artifacts.pagefile {
+                    Some(result) => result,
+                    None => continue,
+                };
+                let results = pagefile(options, &mut manager);
+                match results {
+                    Ok(_) => info!("Collected pagefile"),
+                    Err(err) => {
+                        error!("Failed to parse pagefile: {err:?}");
+                        manager.write_failed_artifact(artifact, options);
+                    }
+                }
+            }
+            "activitiescache" if !skip(&artifacts.activitiescache, &collector.marker, artifact) => {
+                let options = match &artifacts.activitiescache {
+                    Some(result) => result,
+                    None => continue,
+                };
+                let results = activitiescache(options, &mut manager);
+                match results {
+                    Ok(_) => info!("Collected ActivitiesCache"),
+                    Err(err) => {
+                        error!("Failed to parse ActivitiesCache: {err:?}");
+                        manager.write_failed_artifact(artifact, options);
+                    }
+                }
+            }
             "bits" if !skip(&artifacts.bits, &collector.marker, artifact) => {
                 let options = match &artifacts.bits {
                     Some(result) => result,
@@ -642,9 +760,15 @@ pub(crate) fn collect(mut collector: ArtemisToml) -> Result<(), CollectionError>
         }
     }
 
+    // Restore the thread priority before the report is written
+    drop(governor_guard);
+    let cut_short = manager.was_cut_short();
     if let Err(err) = manager.finalize() {
         error!("Could not finalize collection: {err:?}");
     }
+    if cut_short {
+        return Err(CollectionError::Cancelled);
+    }
     Ok(())
 }
 
@@ -667,7 +791,10 @@ fn skip<T: Serialize>(
 #[cfg(test)]
 mod tests {
     use super::collect;
-    use crate::{filesystem::files::read_file, structs::toml::ArtemisToml};
+    use crate::{
+        artifacts::error::CollectionError, filesystem::files::read_file,
+        structs::toml::ArtemisToml, utils::cancel::CancelToken,
+    };
     use std::path::PathBuf;
 
     #[test]
@@ -677,7 +804,7 @@ mod tests {
 
         let buffer = read_file(&test_location.display().to_string()).unwrap();
         let collector = ArtemisToml::parse_artemis_toml(&buffer).unwrap();
-        collect(collector).unwrap();
+        collect(collector, &CancelToken::new()).unwrap();
     }
 
     #[test]
@@ -687,7 +814,7 @@ mod tests {
 
         let buffer = read_file(&test_location.display().to_string()).unwrap();
         let collector = ArtemisToml::parse_artemis_toml(&buffer).unwrap();
-        collect(collector).unwrap();
+        collect(collector, &CancelToken::new()).unwrap();
     }
 
     #[test]
@@ -697,7 +824,7 @@ mod tests {
 
         let buffer = read_file(&test_location.display().to_string()).unwrap();
         let collector = ArtemisToml::parse_artemis_toml(&buffer).unwrap();
-        collect(collector).unwrap();
+        collect(collector, &CancelToken::new()).unwrap();
     }
 
     #[test]
@@ -707,6 +834,19 @@ mod tests {
 
         let buffer = read_file(&test_location.display().to_string()).unwrap();
         let collector = ArtemisToml::parse_artemis_toml(&buffer).unwrap();
-        collect(collector).unwrap();
+        collect(collector, &CancelToken::new()).unwrap();
+    }
+
+    #[test]
+    fn test_collect_cancelled() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/linux/quick.toml");
+
+        let buffer = read_file(&test_location.display().to_string()).unwrap();
+        let collector = ArtemisToml::parse_artemis_toml(&buffer).unwrap();
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let result = collect(collector, &cancel);
+        assert!(matches!(result, Err(CollectionError::Cancelled)));
     }
 }
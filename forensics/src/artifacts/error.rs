@@ -3,6 +3,7 @@ use std::fmt;
 #[derive(Debug)]
 pub(crate) enum CollectionError {
     Output,
+    Cancelled,
 }
 
 impl std::error::Error for CollectionError {}
@@ -11,6 +12,7 @@ impl fmt::Display for CollectionError {
     fn fmt<'a>(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             CollectionError::Output => write!(f, "Failed to output data"),
+            CollectionError::Cancelled => write!(f, "Collection was cancelled"),
         }
     }
 }
@@ -1,3 +1,3 @@
 pub(crate) mod collection;
-mod error;
+pub(crate) mod error;
 pub(crate) mod os;
@@ -0,0 +1,289 @@
+/**
+ * Parse Chromium based browsers (Chrome, Edge, Brave, Chromium, Vivaldi, Opera) and Firefox profiles for all users
+ * Records are normalized into a single `BrowserRecord` format
+ *
+ * References:
+ * `https://www.forensicswiki.org/wiki/Google_Chrome`
+ * `https://www.forensicswiki.org/wiki/Mozilla_Firefox`
+ */
+use super::{
+    chromium::{
+        cache::{cache_directories, chromium_cache},
+        cookies::chromium_cookies,
+        history::{chromium_downloads, chromium_history},
+        logins::chromium_logins,
+        sessions::{chromium_sessions, session_files},
+        topsites::chromium_topsites,
+    },
+    error::BrowserError,
+    firefox::{
+        cookies::firefox_cookies,
+        logins::firefox_logins,
+        places::{firefox_downloads, firefox_history},
+    },
+    profiles::{BrowserProfile, alt_profile, chromium_profiles, firefox_profiles, join_path},
+    records::RecordTypes,
+};
+use crate::{
+    filesystem::files::is_file,
+    output::{manager::OutputManager, record::serialize_records_to_stream},
+    structs::artifacts::os::browsers::BrowserOptions,
+};
+use common::browsers::BrowserRecord;
+use tracing::{error, warn};
+
+/// Parse and output Chromium browser records based on options provided
+pub(crate) fn grab_chromium(
+    options: &BrowserOptions,
+    manager: &mut OutputManager,
+) -> Result<(), BrowserError> {
+    let record_types = RecordTypes::new(&options.records)?;
+    let profiles = match &options.alt_path {
+        Some(path) => vec![alt_profile(path, "Chromium")],
+        None => chromium_profiles()?,
+    };
+
+    for profile in profiles {
+        let records = chromium_records(&profile, &record_types);
+        output_browser(records, "chromium", manager, options)?;
+    }
+    Ok(())
+}
+
+/// Parse and output Firefox browser records based on options provided
+pub(crate) fn grab_firefox(
+    options: &BrowserOptions,
+    manager: &mut OutputManager,
+) -> Result<(), BrowserError> {
+    let record_types = RecordTypes::new(&options.records)?;
+    let profiles = match &options.alt_path {
+        Some(path) => vec![alt_profile(path, "Firefox")],
+        None => firefox_profiles()?,
+    };
+
+    for profile in profiles {
+        let records = firefox_records(&profile, &record_types);
+        output_browser(records, "firefox", manager, options)?;
+    }
+    Ok(())
+}
+
+/// Parse the enabled records in a Chromium profile
+fn chromium_records(profile: &BrowserProfile, record_types: &RecordTypes) -> Vec<BrowserRecord> {
+    let mut records = Vec::new();
+    let history = join_path(&profile.path, "History");
+    if record_types.history {
+        collect_file(&history, profile, chromium_history, &mut records);
+    }
+    if record_types.downloads {
+        collect_file(&history, profile, chromium_downloads, &mut records);
+    }
+    if record_types.cookies {
+        // Newer versions of Chromium moved the Cookies file to the Network directory
+        let mut cookies = join_path(&profile.path, "Network/Cookies");
+        if !is_file(&cookies) {
+            cookies = join_path(&profile.path, "Cookies");
+        }
+        collect_file(&cookies, profile, chromium_cookies, &mut records);
+    }
+    if record_types.logins {
+        let logins = join_path(&profile.path, "Login Data");
+        collect_file(&logins, profile, chromium_logins, &mut records);
+    }
+    if record_types.topsites {
+        let topsites = join_path(&profile.path, "Top Sites");
+        collect_file(&topsites, profile, chromium_topsites, &mut records);
+    }
+    if record_types.cache {
+        for dir in cache_directories(&profile.path) {
+            records.append(&mut chromium_cache(&dir, profile));
+        }
+    }
+    if record_types.sessions {
+        for file in session_files(&profile.path) {
+            collect_file(&file, profile, chromium_sessions, &mut records);
+        }
+    }
+    records
+}
+
+/// Parse the enabled records in a Firefox profile
+fn firefox_records(profile: &BrowserProfile, record_types: &RecordTypes) -> Vec<BrowserRecord> {
+    let mut records = Vec::new();
+    let places = join_path(&profile.path, "places.sqlite");
+    if record_types.history {
+        collect_file(&places, profile, firefox_history, &mut records);
+    }
+    if record_types.downloads {
+        collect_file(&places, profile, firefox_downloads, &mut records);
+    }
+    if record_types.cookies {
+        let cookies = join_path(&profile.path, "cookies.sqlite");
+        collect_file(&cookies, profile, firefox_cookies, &mut records);
+    }
+    if record_types.logins {
+        let logins = join_path(&profile.path, "logins.json");
+        collect_file(&logins, profile, firefox_logins, &mut records);
+    }
+    if record_types.topsites || record_types.cache || record_types.sessions {
+        warn!("[browsers] Firefox top sites, cache, and sessions are not supported");
+    }
+    records
+}
+
+/// Parse a browser file if it exists. Errors are logged and parsing continues with the next file
+fn collect_file<F>(path: &str, profile: &BrowserProfile, parse: F, records: &mut Vec<BrowserRecord>)
+where
+    F: Fn(&str, &BrowserProfile) -> Result<Vec<BrowserRecord>, BrowserError>,
+{
+    if !is_file(path) {
+        return;
+    }
+    match parse(path, profile) {
+        Ok(mut result) => records.append(&mut result),
+        Err(err) => error!("[browsers] Could not parse {path}: {err:?}"),
+    }
+}
+
+/// Output browser records based on `Output` structure
+fn output_browser(
+    records: Vec<BrowserRecord>,
+    artifact_name: &str,
+    manager: &mut OutputManager,
+    options: &BrowserOptions,
+) -> Result<(), BrowserError> {
+    if records.is_empty() {
+        return Ok(());
+    }
+
+    let mut serde_data = match serialize_records_to_stream(records) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[browsers] Failed to serialize {artifact_name} records: {err:?}");
+            return Err(BrowserError::Serialize);
+        }
+    };
+    if let Err(err) = manager.write_artifact(artifact_name, options, &mut serde_data) {
+        error!("[browsers] Could not output {artifact_name} records: {err:?}");
+        return Err(BrowserError::OutputData);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{chromium_records, firefox_records, grab_chromium, grab_firefox};
+    use crate::{
+        artifacts::os::browsers::{
+            error::BrowserError, profiles::alt_profile, records::RecordTypes,
+        },
+        output::manager::OutputManager,
+        structs::{
+            artifacts::os::browsers::BrowserOptions,
+            toml::{OutputConfig, OutputDestination, OutputFormat},
+        },
+    };
+    use common::browsers::BrowserRecordType;
+    use std::path::PathBuf;
+
+    fn output_options(name: &str, directory: &str, compress: bool) -> OutputManager {
+        let config = OutputConfig {
+            name: name.to_string(),
+            directory: PathBuf::from(directory),
+            format: OutputFormat::Jsonl,
+            compress,
+            endpoint_id: String::from("abcd"),
+            destination: OutputDestination::Local,
+            ..Default::default()
+        };
+        OutputManager::new(config).unwrap()
+    }
+
+    fn test_location(path: &str) -> String {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push(path);
+        test_location.display().to_string()
+    }
+
+    #[test]
+    fn test_chromium_records() {
+        let profile = alt_profile(
+            &test_location("tests/test_data/browser/chromium/Default"),
+            "Chrome",
+        );
+        let records = chromium_records(&profile, &RecordTypes::new(&None).unwrap());
+        let count = |record_type: BrowserRecordType| {
+            records
+                .iter()
+                .filter(|record| record.record_type == record_type)
+                .count()
+        };
+        assert_eq!(count(BrowserRecordType::History), 3);
+        assert_eq!(count(BrowserRecordType::Download), 1);
+        assert_eq!(count(BrowserRecordType::Cookie), 1);
+        assert_eq!(count(BrowserRecordType::Login), 1);
+        assert_eq!(count(BrowserRecordType::TopSite), 2);
+        assert_eq!(count(BrowserRecordType::Cache), 1);
+        assert_eq!(count(BrowserRecordType::Session), 2);
+    }
+
+    #[test]
+    fn test_firefox_records() {
+        let profile = alt_profile(
+            &test_location("tests/test_data/browser/firefox/abcd1234.default-release"),
+            "Firefox",
+        );
+        let record_types = RecordTypes::new(&Some(vec![String::from("history")])).unwrap();
+        let records = firefox_records(&profile, &record_types);
+        assert_eq!(records.len(), 3);
+
+        let records = firefox_records(&profile, &RecordTypes::new(&None).unwrap());
+        assert_eq!(records.len(), 6);
+    }
+
+    #[test]
+    fn test_grab_chromium() {
+        let options = BrowserOptions {
+            alt_path: Some(test_location("tests/test_data/browser/chromium/Default")),
+            records: None,
+        };
+        let mut output = output_options("chromium_temp", "./tmp", false);
+        grab_chromium(&options, &mut output).unwrap();
+    }
+
+    #[test]
+    fn test_grab_firefox() {
+        let options = BrowserOptions {
+            alt_path: Some(test_location(
+                "tests/test_data/browser/firefox/abcd1234.default-release",
+            )),
+            records: Some(vec![String::from("cookies"), String::from("logins")]),
+        };
+        let mut output = output_options("firefox_temp", "./tmp", false);
+        grab_firefox(&options, &mut output).unwrap();
+    }
+
+    #[test]
+    fn test_grab_bad_records() {
+        let options = BrowserOptions {
+            alt_path: None,
+            records: Some(vec![String::from("bookmarks")]),
+        };
+        let mut output = output_options("firefox_temp", "./tmp", false);
+        assert_eq!(
+            grab_firefox(&options, &mut output).err(),
+            Some(BrowserError::BadRecordType)
+        );
+    }
+
+    #[test]
+    fn test_grab_browsers() {
+        let options = BrowserOptions {
+            alt_path: None,
+            records: None,
+        };
+        let mut output = output_options("chromium_temp", "./tmp", false);
+        grab_chromium(&options, &mut output).unwrap();
+        grab_firefox(&options, &mut output).unwrap();
+    }
+}
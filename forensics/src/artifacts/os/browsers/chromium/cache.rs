@@ -0,0 +1,345 @@
+/**
+ * Chromium caches HTTP responses in either the Simple Cache format (each entry is a separate file) or the older Blockfile format (entries stored in `data_#` files)
+ * We only extract the cached URLs and entry metadata. The cached content is not collected
+ *
+ * References:
+ * `https://www.chromium.org/developers/design-documents/network-stack/disk-cache/`
+ * `https://www.chromium.org/developers/design-documents/network-stack/disk-cache/very-simple-backend/`
+ */
+use crate::{
+    artifacts::os::browsers::{
+        profiles::{BrowserProfile, join_path},
+        records::{new_record, url_host},
+    },
+    filesystem::{
+        directory::is_directory,
+        files::{get_file_size, get_filename, is_file, list_files, read_file_custom},
+    },
+    utils::{
+        nom_helper::{
+            Endian, nom_signed_four_bytes, nom_unsigned_eight_bytes, nom_unsigned_four_bytes,
+        },
+        time::webkit_time_to_iso,
+    },
+};
+use common::browsers::{BrowserRecord, BrowserRecordType};
+use tracing::warn;
+
+/// Get the cache directories for a Chromium profile. Linux and macOS store the cache outside of the profile directory
+pub(crate) fn cache_directories(profile: &str) -> Vec<String> {
+    let mut profiles = vec![profile.to_string()];
+    for (config, cache) in [
+        ("/.config/", "/.cache/"),
+        ("/Library/Application Support/", "/Library/Caches/"),
+    ] {
+        if profile.contains(config) {
+            profiles.push(profile.replacen(config, cache, 1));
+        }
+    }
+
+    let mut dirs = Vec::new();
+    for path in profiles {
+        for relative in ["Cache/Cache_Data", "Cache"] {
+            let dir = join_path(&path, relative);
+            if is_directory(&dir) && !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+    }
+    dirs
+}
+
+/// Parse the Simple Cache and Blockfile entries in the cache directory
+pub(crate) fn chromium_cache(dir: &str, profile: &BrowserProfile) -> Vec<BrowserRecord> {
+    let mut records = Vec::new();
+    let files = match list_files(dir) {
+        Ok(result) => result,
+        Err(err) => {
+            warn!("[browsers] Could not list cache files in {dir}: {err:?}");
+            return records;
+        }
+    };
+
+    for file in files {
+        let name = get_filename(&file);
+        // Simple Cache stores the key and headers in the `_0` file
+        if name.len() == 18
+            && name.ends_with("_0")
+            && let Some(record) = simple_entry(&file, profile)
+        {
+            records.push(record);
+        }
+    }
+
+    let blockfile = join_path(dir, "data_1");
+    if is_file(&blockfile) {
+        records.append(&mut blockfile_entries(&blockfile, profile));
+    }
+    records
+}
+
+const SIMPLE_MAGIC: u64 = 0xfcfb6d1ba7725c30;
+const SIMPLE_EOF_MAGIC: u64 = 0xf4fa6f45970d41d8;
+/// Max size of a cache file to parse. Larger entries store the content in a separate `_1` stream file
+const MAX_CACHE_READ: u64 = 0x1000000;
+
+/// Parse a Simple Cache entry file
+fn simple_entry(path: &str, profile: &BrowserProfile) -> Option<BrowserRecord> {
+    let size = get_file_size(path);
+    let data = read_file_custom(path, MAX_CACHE_READ).ok()?;
+    let (input, magic) = nom_unsigned_eight_bytes(&data, Endian::Le).ok()?;
+    if magic != SIMPLE_MAGIC {
+        return None;
+    }
+    let (input, _version) = nom_unsigned_four_bytes(input, Endian::Le).ok()?;
+    let (input, key_length) = nom_unsigned_four_bytes(input, Endian::Le).ok()?;
+    let (input, _key_hash) = nom_unsigned_four_bytes(input, Endian::Le).ok()?;
+    let (input, _padding) = nom_unsigned_four_bytes(input, Endian::Le).ok()?;
+    let key = input.get(..key_length as usize)?;
+
+    let mut record = new_record(profile, BrowserRecordType::Cache, path);
+    record.url = key_url(&String::from_utf8_lossy(key));
+    record.host = url_host(&record.url);
+    record.path = path.to_string();
+    record.size = size as i64;
+
+    // The HTTP response info (stream 0) is at the end of the file
+    if let Some((request, response, details)) = simple_response(&data) {
+        record.timestamp = webkit_time_to_iso(response);
+        record.timestamp_desc = String::from("Cache Response Received");
+        record.last_accessed = webkit_time_to_iso(request);
+        record.details = details;
+    }
+    Some(record)
+}
+
+/// Parse the HTTP response info at the end of a Simple Cache entry file. Returns the request time, response time, and header details
+fn simple_response(data: &[u8]) -> Option<(i64, i64, String)> {
+    let eof_size = 24;
+    let eof_start = data.len().checked_sub(eof_size)?;
+    let (input, magic) = nom_unsigned_eight_bytes(&data[eof_start..], Endian::Le).ok()?;
+    if magic != SIMPLE_EOF_MAGIC {
+        return None;
+    }
+    let (input, flags) = nom_unsigned_four_bytes(input, Endian::Le).ok()?;
+    let (input, _crc) = nom_unsigned_four_bytes(input, Endian::Le).ok()?;
+    let (_, stream_size) = nom_unsigned_four_bytes(input, Endian::Le).ok()?;
+
+    // The SHA256 of the key may be stored before the EOF record
+    let has_key_hash = 2;
+    let key_hash_size = if flags & has_key_hash != 0 { 32 } else { 0 };
+    let stream_end = eof_start.checked_sub(key_hash_size)?;
+    let stream_start = stream_end.checked_sub(stream_size as usize)?;
+    response_info(&data[stream_start..stream_end])
+}
+
+/// Parse the pickled `HttpResponseInfo`
+fn response_info(data: &[u8]) -> Option<(i64, i64, String)> {
+    let (input, _payload_size) = nom_unsigned_four_bytes(data, Endian::Le).ok()?;
+    let (input, _flags) = nom_unsigned_four_bytes(input, Endian::Le).ok()?;
+    let (input, request) = nom_unsigned_eight_bytes(input, Endian::Le).ok()?;
+    let (input, response) = nom_unsigned_eight_bytes(input, Endian::Le).ok()?;
+    let (input, header_size) = nom_signed_four_bytes(input, Endian::Le).ok()?;
+    let headers = input.get(..usize::try_from(header_size).ok()?)?;
+
+    // Headers are separated by null bytes. First value is the status line
+    let headers = String::from_utf8_lossy(headers);
+    let mut values = headers.split('\0');
+    let status = values.next().unwrap_or_default();
+    let content_type = values
+        .find(|value| value.to_lowercase().starts_with("content-type:"))
+        .and_then(|value| value.split_once(':'))
+        .map(|(_, value)| value.trim())
+        .unwrap_or_default();
+    Some((
+        request as i64,
+        response as i64,
+        format!("Status: {status}. Content type: {content_type}"),
+    ))
+}
+
+/// Extract the URL from the cache key. Newer keys include the network isolation key. Ex: `1/0/_dk_https://site.com https://site.com https://site.com/file.js`
+fn key_url(key: &str) -> String {
+    key.split_whitespace()
+        .last()
+        .unwrap_or_default()
+        .trim_start_matches("_dk_")
+        .to_string()
+}
+
+const BLOCK_MAGIC: u32 = 0xc104cac3;
+const BLOCK_HEADER_SIZE: usize = 8192;
+const ENTRY_SIZE: usize = 256;
+
+/// Parse the cache entries in the Blockfile `data_1` file
+fn blockfile_entries(path: &str, profile: &BrowserProfile) -> Vec<BrowserRecord> {
+    let mut records = Vec::new();
+    let max_size = 0x8000000;
+    let data = match read_file_custom(path, max_size) {
+        Ok(result) => result,
+        Err(err) => {
+            warn!("[browsers] Could not read blockfile {path}: {err:?}");
+            return records;
+        }
+    };
+    if data.len() < BLOCK_HEADER_SIZE {
+        return records;
+    }
+    let Ok((_, magic)) = nom_unsigned_four_bytes(&data, Endian::Le) else {
+        return records;
+    };
+    if magic != BLOCK_MAGIC {
+        warn!("[browsers] Unexpected blockfile magic in {path}");
+        return records;
+    }
+
+    for entry in data[BLOCK_HEADER_SIZE..].chunks_exact(ENTRY_SIZE) {
+        if let Some((url, created, body_size)) = block_entry(entry) {
+            let mut record = new_record(profile, BrowserRecordType::Cache, path);
+            record.host = url_host(&url);
+            record.url = url;
+            record.timestamp = webkit_time_to_iso(created);
+            record.timestamp_desc = String::from("Cache Entry Created");
+            record.size = body_size;
+            record.path = path.to_string();
+            records.push(record);
+        }
+    }
+    records
+}
+
+/// Parse a Blockfile `EntryStore`. Returns the URL, creation time, and body size
+fn block_entry(entry: &[u8]) -> Option<(String, i64, i64)> {
+    let state_offset = 20;
+    let (input, state) = nom_unsigned_four_bytes(&entry[state_offset..], Endian::Le).ok()?;
+    let (input, created) = nom_unsigned_eight_bytes(input, Endian::Le).ok()?;
+    let (input, key_length) = nom_unsigned_four_bytes(input, Endian::Le).ok()?;
+    let (input, long_key) = nom_unsigned_four_bytes(input, Endian::Le).ok()?;
+    let (_, _header_size) = nom_signed_four_bytes(input, Endian::Le).ok()?;
+    let body_offset = 44;
+    let (_, body_size) = nom_signed_four_bytes(&entry[body_offset..], Endian::Le).ok()?;
+
+    // Only normal entries with inline keys
+    let key_offset = 96;
+    let max_inline = ENTRY_SIZE - key_offset;
+    if state != 0 || created == 0 || long_key != 0 || key_length == 0 {
+        return None;
+    }
+    let key = entry.get(key_offset..key_offset + (key_length as usize).min(max_inline))?;
+    if !key
+        .iter()
+        .all(|value| value.is_ascii_graphic() || *value == b' ')
+    {
+        return None;
+    }
+    let url = key_url(&String::from_utf8_lossy(key));
+    if !url.contains("://") {
+        return None;
+    }
+    Some((url, created as i64, body_size as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BLOCK_HEADER_SIZE, BLOCK_MAGIC, block_entry, blockfile_entries, cache_directories,
+        chromium_cache, key_url, response_info,
+    };
+    use crate::artifacts::os::browsers::profiles::alt_profile;
+    use std::{io::Write, path::PathBuf};
+
+    fn test_profile() -> String {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/browser/chromium/Default");
+        test_location.display().to_string()
+    }
+
+    #[test]
+    fn test_cache_directories() {
+        let dirs = cache_directories(&test_profile());
+        assert_eq!(dirs.len(), 2);
+        assert!(dirs[0].ends_with("Cache_Data"));
+    }
+
+    #[test]
+    fn test_chromium_cache() {
+        let dirs = cache_directories(&test_profile());
+        let profile = alt_profile(&test_profile(), "Chrome");
+        let results = chromium_cache(&dirs[0], &profile);
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].url,
+            "https://github.com/puffyCid/artemis/raw/main/logo.png"
+        );
+        assert_eq!(results[0].host, "github.com");
+        assert_eq!(results[0].size, 138);
+    }
+
+    #[test]
+    fn test_key_url() {
+        assert_eq!(
+            key_url("1/0/_dk_https://a.com https://a.com https://a.com/app.js"),
+            "https://a.com/app.js"
+        );
+        assert_eq!(key_url("https://a.com/app.js"), "https://a.com/app.js");
+    }
+
+    #[test]
+    fn test_response_info() {
+        let headers = b"HTTP/1.1 200\0content-type: image/png\0\0";
+        let mut data = Vec::new();
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.extend_from_slice(&13345678901234567u64.to_le_bytes());
+        data.extend_from_slice(&13345678902234567u64.to_le_bytes());
+        data.extend_from_slice(&(headers.len() as i32).to_le_bytes());
+        data.extend_from_slice(headers);
+
+        let (request, response, details) = response_info(&data).unwrap();
+        assert_eq!(request, 13345678901234567);
+        assert_eq!(response, 13345678902234567);
+        assert_eq!(details, "Status: HTTP/1.1 200. Content type: image/png");
+    }
+
+    /// Build a Blockfile `EntryStore`
+    fn test_entry(key: &[u8]) -> Vec<u8> {
+        let mut entry = vec![0; 256];
+        entry[24..32].copy_from_slice(&13345678901234567u64.to_le_bytes());
+        entry[32..36].copy_from_slice(&(key.len() as u32).to_le_bytes());
+        entry[44..48].copy_from_slice(&2048i32.to_le_bytes());
+        entry[96..96 + key.len()].copy_from_slice(key);
+        entry
+    }
+
+    #[test]
+    fn test_block_entry() {
+        let entry = test_entry(b"https://github.com/favicon.ico");
+        let (url, created, size) = block_entry(&entry).unwrap();
+        assert_eq!(url, "https://github.com/favicon.ico");
+        assert_eq!(created, 13345678901234567);
+        assert_eq!(size, 2048);
+
+        assert!(block_entry(&[0; 256]).is_none());
+    }
+
+    #[test]
+    fn test_blockfile_entries() {
+        let mut data = vec![0; BLOCK_HEADER_SIZE];
+        data[0..4].copy_from_slice(&BLOCK_MAGIC.to_le_bytes());
+        data.append(&mut test_entry(b"https://github.com/favicon.ico"));
+        data.append(&mut vec![0; 256]);
+
+        let path = "./tmp/browser_blockfile_data_1";
+        std::fs::create_dir_all("./tmp").unwrap();
+        std::fs::File::create(path)
+            .unwrap()
+            .write_all(&data)
+            .unwrap();
+
+        let profile = alt_profile("Default", "Chrome");
+        let results = blockfile_entries(path, &profile);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].timestamp, "2023-11-28T21:01:41.234Z");
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{
+    artifacts::os::browsers::{
+        error::BrowserError,
+        profiles::BrowserProfile,
+        records::{new_record, query_records},
+    },
+    utils::{
+        sqlite::{column_int, column_text},
+        time::webkit_time_to_iso,
+    },
+};
+use common::browsers::{BrowserRecord, BrowserRecordType};
+
+/// Get cookie metadata from the Chromium `Cookies` file. Cookie values are not collected
+pub(crate) fn chromium_cookies(
+    path: &str,
+    profile: &BrowserProfile,
+) -> Result<Vec<BrowserRecord>, BrowserError> {
+    let query = "SELECT host_key, name, path, creation_utc, expires_utc, last_access_utc, is_secure, is_httponly, is_persistent FROM cookies ORDER BY creation_utc";
+    query_records(path, query, |row| {
+        let mut record = new_record(profile, BrowserRecordType::Cookie, path);
+        record.host = column_text(row, "host_key");
+        record.name = column_text(row, "name");
+        record.url = format!(
+            "{}{}",
+            record.host.trim_start_matches('.'),
+            column_text(row, "path")
+        );
+        record.timestamp = webkit_time_to_iso(column_int(row, "creation_utc"));
+        record.timestamp_desc = String::from("Cookie Created");
+        record.last_accessed = webkit_time_to_iso(column_int(row, "last_access_utc"));
+        record.expires = webkit_time_to_iso(column_int(row, "expires_utc"));
+        record.details = format!(
+            "Secure: {}. HttpOnly: {}. Persistent: {}",
+            column_int(row, "is_secure") != 0,
+            column_int(row, "is_httponly") != 0,
+            column_int(row, "is_persistent") != 0,
+        );
+        record
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::chromium_cookies;
+    use crate::artifacts::os::browsers::profiles::alt_profile;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_chromium_cookies() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/browser/chromium/Default/Network/Cookies");
+        let profile = alt_profile("Default", "Chrome");
+
+        let results = chromium_cookies(test_location.to_str().unwrap(), &profile).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].host, ".github.com");
+        assert_eq!(results[0].name, "_octo");
+        assert_eq!(results[0].url, "github.com/");
+        assert_eq!(results[0].timestamp, "2023-11-14T22:16:40.000Z");
+        assert_eq!(results[0].expires, "2024-11-13T22:16:40.000Z");
+        assert_eq!(
+            results[0].details,
+            "Secure: true. HttpOnly: false. Persistent: true"
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{
+    artifacts::os::browsers::{
+        error::BrowserError,
+        profiles::BrowserProfile,
+        records::{new_record, query_records, url_host},
+    },
+    utils::{
+        sqlite::{column_int, column_text},
+        time::webkit_time_to_iso,
+    },
+};
+use common::browsers::{BrowserRecord, BrowserRecordType};
+
+/// Get the URL visits from the Chromium `History` file
+pub(crate) fn chromium_history(
+    path: &str,
+    profile: &BrowserProfile,
+) -> Result<Vec<BrowserRecord>, BrowserError> {
+    let query = "SELECT urls.url AS url, urls.title AS title, urls.visit_count AS visit_count, urls.typed_count AS typed_count, urls.last_visit_time AS last_visit_time, visits.visit_time AS visit_time, visits.visit_duration AS visit_duration, visits.transition AS transition FROM visits JOIN urls ON visits.url = urls.id ORDER BY visits.visit_time";
+    query_records(path, query, |row| {
+        let mut record = new_record(profile, BrowserRecordType::History, path);
+        record.url = column_text(row, "url");
+        record.host = url_host(&record.url);
+        record.title = column_text(row, "title");
+        record.visit_count = column_int(row, "visit_count");
+        record.timestamp = webkit_time_to_iso(column_int(row, "visit_time"));
+        record.timestamp_desc = String::from("URL Visited");
+        record.last_accessed = webkit_time_to_iso(column_int(row, "last_visit_time"));
+
+        // Duration is in microseconds
+        let duration = column_int(row, "visit_duration") as f64 / 1000000.0;
+        record.details = format!(
+            "Transition: {}. Typed count: {}. Visit duration: {duration}s",
+            transition(column_int(row, "transition")),
+            column_int(row, "typed_count"),
+        );
+        record
+    })
+}
+
+/// Get the downloads from the Chromium `History` file
+pub(crate) fn chromium_downloads(
+    path: &str,
+    profile: &BrowserProfile,
+) -> Result<Vec<BrowserRecord>, BrowserError> {
+    // The final URL in the redirect chain is the actual download URL
+    let query = "SELECT target_path, start_time, end_time, last_access_time, received_bytes, total_bytes, state, danger_type, opened, referrer, tab_url, mime_type, (SELECT url FROM downloads_url_chains WHERE downloads_url_chains.id = downloads.id ORDER BY chain_index DESC LIMIT 1) AS url FROM downloads ORDER BY start_time";
+    query_records(path, query, |row| {
+        let mut record = new_record(profile, BrowserRecordType::Download, path);
+        record.url = column_text(row, "url");
+        record.host = url_host(&record.url);
+        record.path = column_text(row, "target_path");
+        record.size = column_int(row, "total_bytes");
+        record.timestamp = webkit_time_to_iso(column_int(row, "start_time"));
+        record.timestamp_desc = String::from("Download Started");
+        record.end_time = webkit_time_to_iso(column_int(row, "end_time"));
+        record.last_accessed = webkit_time_to_iso(column_int(row, "last_access_time"));
+        record.details = format!(
+            "State: {}. Received bytes: {}. Danger type: {}. Opened: {}. Mime type: {}. Referrer: {}. Tab URL: {}",
+            download_state(column_int(row, "state")),
+            column_int(row, "received_bytes"),
+            column_int(row, "danger_type"),
+            column_int(row, "opened") != 0,
+            column_text(row, "mime_type"),
+            column_text(row, "referrer"),
+            column_text(row, "tab_url"),
+        );
+        record
+    })
+}
+
+/// Determine the core page transition type. Upper bits are qualifiers
+fn transition(value: i64) -> &'static str {
+    let core_mask = 0xff;
+    match value & core_mask {
+        0 => "Link",
+        1 => "Typed",
+        2 => "AutoBookmark",
+        3 => "AutoSubframe",
+        4 => "ManualSubframe",
+        5 => "Generated",
+        6 => "AutoToplevel",
+        7 => "FormSubmit",
+        8 => "Reload",
+        9 => "Keyword",
+        10 => "KeywordGenerated",
+        _ => "Unknown",
+    }
+}
+
+/// Determine the download state
+fn download_state(value: i64) -> &'static str {
+    match value {
+        0 => "InProgress",
+        1 => "Complete",
+        2 => "Cancelled",
+        3 | 4 => "Interrupted",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{chromium_downloads, chromium_history, download_state, transition};
+    use crate::artifacts::os::browsers::profiles::alt_profile;
+    use common::browsers::BrowserRecordType;
+    use std::path::PathBuf;
+
+    fn test_history() -> String {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/browser/chromium/Default/History");
+        test_location.display().to_string()
+    }
+
+    #[test]
+    fn test_chromium_history() {
+        let profile = alt_profile("Default", "Chrome");
+        let results = chromium_history(&test_history(), &profile).unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].record_type, BrowserRecordType::History);
+        assert_eq!(results[0].url, "https://www.rust-lang.org/");
+        assert_eq!(results[0].host, "www.rust-lang.org");
+        assert_eq!(results[0].title, "Rust Programming Language");
+        assert_eq!(results[0].visit_count, 2);
+        assert_eq!(results[0].timestamp, "2023-11-14T22:13:20.000Z");
+        assert_eq!(
+            results[0].details,
+            "Transition: Typed. Typed count: 1. Visit duration: 5s"
+        );
+        assert!(results[1].details.starts_with("Transition: Reload"));
+        assert_eq!(results[2].host, "github.com");
+    }
+
+    #[test]
+    fn test_chromium_downloads() {
+        let profile = alt_profile("Default", "Chrome");
+        let results = chromium_downloads(&test_history(), &profile).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].url,
+            "https://objects.githubusercontent.com/artemis.zip"
+        );
+        assert_eq!(results[0].path, "C:\\Users\\bob\\Downloads\\artemis.zip");
+        assert_eq!(results[0].size, 1024);
+        assert_eq!(results[0].timestamp, "2023-11-14T22:18:20.000Z");
+        assert_eq!(results[0].end_time, "2023-11-14T22:18:25.000Z");
+        assert!(results[0].details.starts_with("State: Complete"));
+    }
+
+    #[test]
+    fn test_transition() {
+        assert_eq!(transition(0x30000001), "Typed");
+        assert_eq!(transition(0xfe), "Unknown");
+    }
+
+    #[test]
+    fn test_download_state() {
+        assert_eq!(download_state(2), "Cancelled");
+        assert_eq!(download_state(9), "Unknown");
+    }
+}
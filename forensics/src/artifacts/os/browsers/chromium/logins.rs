@@ -0,0 +1,58 @@
+use crate::{
+    artifacts::os::browsers::{
+        error::BrowserError,
+        profiles::BrowserProfile,
+        records::{new_record, query_records, url_host},
+    },
+    utils::{
+        sqlite::{column_int, column_text},
+        time::webkit_time_to_iso,
+    },
+};
+use common::browsers::{BrowserRecord, BrowserRecordType};
+
+/// Get the saved login origins from the Chromium `Login Data` file. Usernames and passwords are not collected
+pub(crate) fn chromium_logins(
+    path: &str,
+    profile: &BrowserProfile,
+) -> Result<Vec<BrowserRecord>, BrowserError> {
+    let query = "SELECT origin_url, action_url, signon_realm, date_created, date_last_used, times_used, blacklisted_by_user FROM logins ORDER BY date_created";
+    query_records(path, query, |row| {
+        let mut record = new_record(profile, BrowserRecordType::Login, path);
+        record.url = column_text(row, "origin_url");
+        record.host = url_host(&record.url);
+        record.timestamp = webkit_time_to_iso(column_int(row, "date_created"));
+        record.timestamp_desc = String::from("Login Created");
+        record.last_accessed = webkit_time_to_iso(column_int(row, "date_last_used"));
+        record.visit_count = column_int(row, "times_used");
+        record.details = format!(
+            "Action URL: {}. Signon realm: {}. Never saved: {}",
+            column_text(row, "action_url"),
+            column_text(row, "signon_realm"),
+            column_int(row, "blacklisted_by_user") != 0,
+        );
+        record
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::chromium_logins;
+    use crate::artifacts::os::browsers::profiles::alt_profile;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_chromium_logins() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/browser/chromium/Default/Login Data");
+        let profile = alt_profile("Default", "Chrome");
+
+        let results = chromium_logins(test_location.to_str().unwrap(), &profile).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "https://github.com/login");
+        assert_eq!(results[0].host, "github.com");
+        assert_eq!(results[0].visit_count, 3);
+        assert_eq!(results[0].last_accessed, "2023-11-14T22:23:20.000Z");
+        assert!(!results[0].details.contains("bob"));
+    }
+}
@@ -0,0 +1,6 @@
+pub(crate) mod cache;
+pub(crate) mod cookies;
+pub(crate) mod history;
+pub(crate) mod logins;
+pub(crate) mod sessions;
+pub(crate) mod topsites;
@@ -0,0 +1,247 @@
+/**
+ * Chromium tracks open tabs and recently closed tabs in SNSS files under the `Sessions` directory
+ * Each file contains a list of commands. We only parse the tab navigation commands
+ *
+ * References:
+ * `https://source.chromium.org/chromium/chromium/src/+/main:components/sessions/core/session_service_commands.cc`
+ * `https://source.chromium.org/chromium/chromium/src/+/main:components/sessions/core/serialized_navigation_entry.cc`
+ */
+use crate::{
+    artifacts::os::browsers::{
+        error::BrowserError,
+        profiles::{BrowserProfile, join_path},
+        records::{new_record, url_host},
+    },
+    filesystem::files::{get_filename, list_files, read_file},
+    utils::{
+        nom_helper::{
+            Endian, nom_data, nom_signed_eight_bytes, nom_signed_four_bytes,
+            nom_unsigned_four_bytes, nom_unsigned_one_byte, nom_unsigned_two_bytes,
+        },
+        strings::{extract_utf8_string, extract_utf16_string},
+        time::webkit_time_to_iso,
+    },
+};
+use common::browsers::{BrowserRecord, BrowserRecordType};
+use tracing::{error, warn};
+
+/// Get the session files in the profile `Sessions` directory
+pub(crate) fn session_files(profile: &str) -> Vec<String> {
+    let dir = join_path(profile, "Sessions");
+    let Ok(files) = list_files(&dir) else {
+        return Vec::new();
+    };
+    files
+        .into_iter()
+        .filter(|file| {
+            let name = get_filename(file);
+            name.starts_with("Session_") || name.starts_with("Tabs_")
+        })
+        .collect()
+}
+
+/// Parse the tab navigations in a Chromium SNSS session file
+pub(crate) fn chromium_sessions(
+    path: &str,
+    profile: &BrowserProfile,
+) -> Result<Vec<BrowserRecord>, BrowserError> {
+    let data = match read_file(path) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[browsers] Could not read session file {path}: {err:?}");
+            return Err(BrowserError::ReadFile);
+        }
+    };
+
+    // Session files and closed tab files use different command IDs for tab navigations
+    let navigation_id = if get_filename(path).starts_with("Tabs_") {
+        1
+    } else {
+        6
+    };
+
+    let commands = match session_commands(&data) {
+        Ok((_, result)) => result,
+        Err(_err) => {
+            error!("[browsers] Could not parse session file {path}");
+            return Err(BrowserError::ReadFile);
+        }
+    };
+
+    let mut records = Vec::new();
+    for (id, command) in commands {
+        if id != navigation_id {
+            continue;
+        }
+        let Ok((_, navigation)) = parse_navigation(command) else {
+            warn!("[browsers] Could not parse session navigation in {path}");
+            continue;
+        };
+        let mut record = new_record(profile, BrowserRecordType::Session, path);
+        record.host = url_host(&navigation.url);
+        record.url = navigation.url;
+        record.title = navigation.title;
+        record.timestamp = webkit_time_to_iso(navigation.timestamp);
+        record.timestamp_desc = String::from("Session Navigation");
+        record.details = format!(
+            "Tab: {}. Index: {}. Referrer: {}",
+            navigation.tab_id, navigation.index, navigation.referrer
+        );
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Split the SNSS file into commands. Returns the command ID and command data
+fn session_commands(data: &[u8]) -> nom::IResult<&[u8], Vec<(u8, &[u8])>> {
+    let (input, signature) = nom_unsigned_four_bytes(data, Endian::Le)?;
+    let snss = 0x53534e53;
+    if signature != snss {
+        return Err(nom::Err::Failure(nom::error::Error::new(
+            data,
+            nom::error::ErrorKind::Tag,
+        )));
+    }
+    let (mut input, _version) = nom_signed_four_bytes(input, Endian::Le)?;
+
+    let mut commands = Vec::new();
+    // Size includes the command ID
+    let min_size = 1;
+    while let Ok((remaining, size)) = nom_unsigned_two_bytes(input, Endian::Le) {
+        if size < min_size {
+            break;
+        }
+        let Ok((remaining, id)) = nom_unsigned_one_byte(remaining, Endian::Le) else {
+            break;
+        };
+        // Last command may be truncated if the browser is running
+        let Ok((remaining, command)) = nom_data(remaining, u64::from(size - min_size)) else {
+            break;
+        };
+        commands.push((id, command));
+        input = remaining;
+    }
+    Ok((input, commands))
+}
+
+struct Navigation {
+    tab_id: i32,
+    index: i32,
+    url: String,
+    title: String,
+    referrer: String,
+    timestamp: i64,
+}
+
+/// Parse the pickled tab navigation command
+fn parse_navigation(data: &[u8]) -> nom::IResult<&[u8], Navigation> {
+    let (input, _payload_size) = nom_unsigned_four_bytes(data, Endian::Le)?;
+    let (input, tab_id) = nom_signed_four_bytes(input, Endian::Le)?;
+    let (input, index) = nom_signed_four_bytes(input, Endian::Le)?;
+    let (input, url) = pickle_string(input)?;
+    let (input, title) = pickle_string16(input)?;
+    let (input, _state) = pickle_string(input)?;
+    let (input, _transition) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, _has_post) = nom_signed_four_bytes(input, Endian::Le)?;
+    let (input, referrer) = pickle_string(input)?;
+    let (input, _policy) = nom_signed_four_bytes(input, Endian::Le)?;
+    let (input, _original_url) = pickle_string(input)?;
+    let (input, _override_agent) = nom_signed_four_bytes(input, Endian::Le)?;
+    let (input, timestamp) = nom_signed_eight_bytes(input, Endian::Le)?;
+
+    let navigation = Navigation {
+        tab_id,
+        index,
+        url,
+        title,
+        referrer,
+        timestamp,
+    };
+    Ok((input, navigation))
+}
+
+/// Pickle data is aligned to 4 bytes
+fn pickle_padding(size: u64) -> u64 {
+    let alignment = 4;
+    (alignment - size % alignment) % alignment
+}
+
+/// Parse a pickled UTF8 string
+fn pickle_string(data: &[u8]) -> nom::IResult<&[u8], String> {
+    let (input, size) = nom_unsigned_four_bytes(data, Endian::Le)?;
+    let (input, string_data) = nom_data(input, u64::from(size))?;
+    let (input, _) = nom_data(input, pickle_padding(u64::from(size)))?;
+    Ok((input, extract_utf8_string(string_data)))
+}
+
+/// Parse a pickled UTF16 string. Size is the number of characters
+fn pickle_string16(data: &[u8]) -> nom::IResult<&[u8], String> {
+    let (input, chars) = nom_unsigned_four_bytes(data, Endian::Le)?;
+    let size = u64::from(chars) * 2;
+    let (input, string_data) = nom_data(input, size)?;
+    let (input, _) = nom_data(input, pickle_padding(size))?;
+    Ok((input, extract_utf16_string(string_data)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        chromium_sessions, parse_navigation, pickle_padding, pickle_string, pickle_string16,
+        session_commands, session_files,
+    };
+    use crate::artifacts::os::browsers::profiles::alt_profile;
+    use crate::filesystem::files::read_file;
+    use std::path::PathBuf;
+
+    fn test_profile() -> String {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/browser/chromium/Default");
+        test_location.display().to_string()
+    }
+
+    #[test]
+    fn test_chromium_sessions() {
+        let files = session_files(&test_profile());
+        assert_eq!(files.len(), 1);
+
+        let profile = alt_profile(&test_profile(), "Chrome");
+        let results = chromium_sessions(&files[0], &profile).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].url, "https://www.rust-lang.org/");
+        assert_eq!(results[0].title, "Rust Programming Language");
+        assert_eq!(results[0].timestamp, "2023-11-14T22:13:20.000Z");
+        assert_eq!(results[1].host, "github.com");
+        assert_eq!(results[1].title, "GitHub - puffyCid/artemis");
+        assert_eq!(results[1].timestamp, "2023-11-14T22:16:40.000Z");
+        assert_eq!(results[1].details, "Tab: 2. Index: 1. Referrer: ");
+    }
+
+    #[test]
+    fn test_session_commands() {
+        let data = read_file(&session_files(&test_profile())[0]).unwrap();
+        let (_, commands) = session_commands(&data).unwrap();
+        assert_eq!(commands.len(), 3);
+        assert_eq!(commands[0].0, 0);
+        assert_eq!(commands[1].0, 6);
+
+        let (_, navigation) = parse_navigation(commands[1].1).unwrap();
+        assert_eq!(navigation.tab_id, 2);
+        assert_eq!(navigation.index, 0);
+
+        assert!(session_commands(b"SNSX\x03\0\0\0").is_err());
+    }
+
+    #[test]
+    fn test_pickle_strings() {
+        assert_eq!(pickle_padding(5), 3);
+        assert_eq!(pickle_padding(8), 0);
+
+        let (remaining, value) = pickle_string(b"\x03\0\0\0abc\0next").unwrap();
+        assert_eq!(value, "abc");
+        assert_eq!(remaining, b"next");
+
+        let (remaining, value) = pickle_string16(b"\x01\0\0\0a\0\0\0").unwrap();
+        assert_eq!(value, "a");
+        assert!(remaining.is_empty());
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{
+    artifacts::os::browsers::{
+        error::BrowserError,
+        profiles::BrowserProfile,
+        records::{new_record, query_records, url_host},
+    },
+    utils::sqlite::{column_int, column_text},
+};
+use common::browsers::{BrowserRecord, BrowserRecordType};
+
+/// Get the most visited sites from the Chromium `Top Sites` file. The file has no timestamps
+pub(crate) fn chromium_topsites(
+    path: &str,
+    profile: &BrowserProfile,
+) -> Result<Vec<BrowserRecord>, BrowserError> {
+    let query = "SELECT url, url_rank, title FROM top_sites ORDER BY url_rank";
+    query_records(path, query, |row| {
+        let mut record = new_record(profile, BrowserRecordType::TopSite, path);
+        record.url = column_text(row, "url");
+        record.host = url_host(&record.url);
+        record.title = column_text(row, "title");
+        record.details = format!("Rank: {}", column_int(row, "url_rank"));
+        record
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::chromium_topsites;
+    use crate::artifacts::os::browsers::profiles::alt_profile;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_chromium_topsites() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/browser/chromium/Default/Top Sites");
+        let profile = alt_profile("Default", "Chrome");
+
+        let results = chromium_topsites(test_location.to_str().unwrap(), &profile).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1].title, "GitHub - puffyCid/artemis");
+        assert_eq!(results[1].details, "Rank: 1");
+    }
+}
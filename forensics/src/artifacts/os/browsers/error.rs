@@ -0,0 +1,28 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum BrowserError {
+    BadRecordType,
+    UserPaths,
+    SqliteParse,
+    BadSQL,
+    ReadFile,
+    Serialize,
+    OutputData,
+}
+
+impl std::error::Error for BrowserError {}
+
+impl fmt::Display for BrowserError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BrowserError::BadRecordType => write!(f, "Unknown browser record type"),
+            BrowserError::UserPaths => write!(f, "Failed to get user paths"),
+            BrowserError::SqliteParse => write!(f, "Failed to parse browser SQLITE file"),
+            BrowserError::BadSQL => write!(f, "Failed to compose browser SQL query"),
+            BrowserError::ReadFile => write!(f, "Failed to read browser file"),
+            BrowserError::Serialize => write!(f, "Failed to serialize browser data"),
+            BrowserError::OutputData => write!(f, "Failed to output browser data"),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{
+    artifacts::os::browsers::{
+        error::BrowserError,
+        profiles::BrowserProfile,
+        records::{new_record, query_records},
+    },
+    utils::{
+        sqlite::{column_int, column_text},
+        time::{unixepoch_microseconds_to_iso, unixepoch_to_iso},
+    },
+};
+use common::browsers::{BrowserRecord, BrowserRecordType};
+
+/// Get cookie metadata from the Firefox `cookies.sqlite` file. Cookie values are not collected
+pub(crate) fn firefox_cookies(
+    path: &str,
+    profile: &BrowserProfile,
+) -> Result<Vec<BrowserRecord>, BrowserError> {
+    let query = "SELECT host, name, path, expiry, lastAccessed, creationTime, isSecure, isHttpOnly, originAttributes FROM moz_cookies ORDER BY creationTime";
+    query_records(path, query, |row| {
+        let mut record = new_record(profile, BrowserRecordType::Cookie, path);
+        record.host = column_text(row, "host");
+        record.name = column_text(row, "name");
+        record.url = format!(
+            "{}{}",
+            record.host.trim_start_matches('.'),
+            column_text(row, "path")
+        );
+        record.timestamp = unixepoch_microseconds_to_iso(column_int(row, "creationTime"));
+        record.timestamp_desc = String::from("Cookie Created");
+        record.last_accessed = unixepoch_microseconds_to_iso(column_int(row, "lastAccessed"));
+        // Expiry is in seconds
+        record.expires = unixepoch_to_iso(column_int(row, "expiry"));
+        record.details = format!(
+            "Secure: {}. HttpOnly: {}. Origin attributes: {}",
+            column_int(row, "isSecure") != 0,
+            column_int(row, "isHttpOnly") != 0,
+            column_text(row, "originAttributes"),
+        );
+        record
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::firefox_cookies;
+    use crate::artifacts::os::browsers::profiles::alt_profile;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_firefox_cookies() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location
+            .push("tests/test_data/browser/firefox/abcd1234.default-release/cookies.sqlite");
+        let profile = alt_profile("abcd1234.default-release", "Firefox");
+        let results = firefox_cookies(test_location.to_str().unwrap(), &profile).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "_ga");
+        assert_eq!(results[0].url, "mozilla.org/");
+        assert_eq!(results[0].timestamp, "2023-11-14T22:15:00.000Z");
+        assert_eq!(results[0].expires, "2024-11-13T22:15:00.000Z");
+        assert_eq!(
+            results[0].details,
+            "Secure: false. HttpOnly: false. Origin attributes: "
+        );
+    }
+}
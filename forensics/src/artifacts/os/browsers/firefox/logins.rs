@@ -0,0 +1,100 @@
+use crate::{
+    artifacts::os::browsers::{
+        error::BrowserError,
+        profiles::BrowserProfile,
+        records::{new_record, url_host},
+    },
+    filesystem::files::read_text_file,
+    utils::time::unixepoch_microseconds_to_iso,
+};
+use common::browsers::{BrowserRecord, BrowserRecordType};
+use serde::Deserialize;
+use tracing::error;
+
+#[derive(Deserialize)]
+struct Logins {
+    logins: Vec<Login>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Login {
+    hostname: String,
+    #[serde(default)]
+    form_submit_url: Option<String>,
+    #[serde(default)]
+    time_created: i64,
+    #[serde(default)]
+    time_last_used: i64,
+    #[serde(default)]
+    time_password_changed: i64,
+    #[serde(default)]
+    times_used: i64,
+}
+
+/// Get the saved login origins from the Firefox `logins.json` file. Encrypted usernames and passwords are not collected
+pub(crate) fn firefox_logins(
+    path: &str,
+    profile: &BrowserProfile,
+) -> Result<Vec<BrowserRecord>, BrowserError> {
+    let text = match read_text_file(path) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[browsers] Could not read logins file {path}: {err:?}");
+            return Err(BrowserError::ReadFile);
+        }
+    };
+    let logins: Logins = match serde_json::from_str(&text) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[browsers] Could not parse logins file {path}: {err:?}");
+            return Err(BrowserError::ReadFile);
+        }
+    };
+
+    // Times are in milliseconds
+    let to_micro = 1000;
+    let mut records = Vec::new();
+    for login in logins.logins {
+        let mut record = new_record(profile, BrowserRecordType::Login, path);
+        record.host = url_host(&login.hostname);
+        record.url = login.hostname;
+        record.visit_count = login.times_used;
+        record.timestamp = unixepoch_microseconds_to_iso(login.time_created * to_micro);
+        record.timestamp_desc = String::from("Login Created");
+        record.last_accessed = unixepoch_microseconds_to_iso(login.time_last_used * to_micro);
+        record.details = format!(
+            "Form URL: {}. Password changed: {}",
+            login.form_submit_url.unwrap_or_default(),
+            unixepoch_microseconds_to_iso(login.time_password_changed * to_micro),
+        );
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::firefox_logins;
+    use crate::artifacts::os::browsers::{error::BrowserError, profiles::alt_profile};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_firefox_logins() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/browser/firefox/abcd1234.default-release/logins.json");
+        let profile = alt_profile("abcd1234.default-release", "Firefox");
+        let results = firefox_logins(test_location.to_str().unwrap(), &profile).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "https://github.com");
+        assert_eq!(results[0].host, "github.com");
+        assert_eq!(results[0].visit_count, 3);
+        assert_eq!(results[0].timestamp, "2023-11-14T22:21:40.000Z");
+        assert_eq!(results[0].last_accessed, "2023-11-14T22:23:20.000Z");
+
+        assert_eq!(
+            firefox_logins("./does/not/exist.json", &profile).err(),
+            Some(BrowserError::ReadFile)
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub(crate) mod cookies;
+pub(crate) mod logins;
+pub(crate) mod places;
@@ -0,0 +1,152 @@
+use crate::{
+    artifacts::os::browsers::{
+        error::BrowserError,
+        profiles::BrowserProfile,
+        records::{new_record, query_records, url_host},
+    },
+    utils::{
+        sqlite::{column_int, column_text},
+        time::unixepoch_microseconds_to_iso,
+    },
+};
+use common::browsers::{BrowserRecord, BrowserRecordType};
+use serde_json::Value;
+
+/// Get the URL visits from the Firefox `places.sqlite` file
+pub(crate) fn firefox_history(
+    path: &str,
+    profile: &BrowserProfile,
+) -> Result<Vec<BrowserRecord>, BrowserError> {
+    let query = "SELECT moz_places.url AS url, moz_places.title AS title, moz_places.visit_count AS visit_count, moz_places.typed AS typed, moz_places.last_visit_date AS last_visit_date, moz_historyvisits.visit_date AS visit_date, moz_historyvisits.visit_type AS visit_type FROM moz_historyvisits JOIN moz_places ON moz_historyvisits.place_id = moz_places.id ORDER BY moz_historyvisits.visit_date";
+    query_records(path, query, |row| {
+        let mut record = new_record(profile, BrowserRecordType::History, path);
+        record.url = column_text(row, "url");
+        record.host = url_host(&record.url);
+        record.title = column_text(row, "title");
+        record.visit_count = column_int(row, "visit_count");
+        record.timestamp = unixepoch_microseconds_to_iso(column_int(row, "visit_date"));
+        record.timestamp_desc = String::from("URL Visited");
+        record.last_accessed = unixepoch_microseconds_to_iso(column_int(row, "last_visit_date"));
+        record.details = format!(
+            "Visit type: {}. Typed: {}",
+            visit_type(column_int(row, "visit_type")),
+            column_int(row, "typed") != 0,
+        );
+        record
+    })
+}
+
+/// Get the downloads from the Firefox `places.sqlite` file. Downloads are stored as annotations on the download URL
+pub(crate) fn firefox_downloads(
+    path: &str,
+    profile: &BrowserProfile,
+) -> Result<Vec<BrowserRecord>, BrowserError> {
+    let query = "SELECT moz_places.url AS url, moz_places.title AS title, MAX(CASE WHEN moz_anno_attributes.name = 'downloads/destinationFileURI' THEN moz_annos.content END) AS destination, MAX(CASE WHEN moz_anno_attributes.name = 'downloads/metaData' THEN moz_annos.content END) AS metadata, MIN(moz_annos.dateAdded) AS date_added FROM moz_annos JOIN moz_places ON moz_annos.place_id = moz_places.id JOIN moz_anno_attributes ON moz_annos.anno_attribute_id = moz_anno_attributes.id WHERE moz_anno_attributes.name LIKE 'downloads/%' GROUP BY moz_annos.place_id ORDER BY date_added";
+    query_records(path, query, |row| {
+        let mut record = new_record(profile, BrowserRecordType::Download, path);
+        record.url = column_text(row, "url");
+        record.host = url_host(&record.url);
+        record.title = column_text(row, "title");
+        record.path = column_text(row, "destination");
+        record.timestamp = unixepoch_microseconds_to_iso(column_int(row, "date_added"));
+        record.timestamp_desc = String::from("Download Started");
+
+        // Metadata is JSON. Ex: {"state":1,"deleted":false,"endTime":1700000305000,"fileSize":1024}
+        let metadata: Value =
+            serde_json::from_str(&column_text(row, "metadata")).unwrap_or_default();
+        let to_micro = 1000;
+        if let Some(end) = metadata["endTime"].as_i64() {
+            record.end_time = unixepoch_microseconds_to_iso(end * to_micro);
+        }
+        record.size = metadata["fileSize"].as_i64().unwrap_or_default();
+        record.details = format!(
+            "State: {}. Deleted: {}",
+            download_state(metadata["state"].as_i64().unwrap_or(-1)),
+            metadata["deleted"].as_bool().unwrap_or_default(),
+        );
+        record
+    })
+}
+
+/// Determine how the URL was visited
+fn visit_type(value: i64) -> &'static str {
+    match value {
+        1 => "Link",
+        2 => "Typed",
+        3 => "Bookmark",
+        4 => "Embed",
+        5 => "RedirectPermanent",
+        6 => "RedirectTemporary",
+        7 => "Download",
+        8 => "FramedLink",
+        9 => "Reload",
+        _ => "Unknown",
+    }
+}
+
+/// Determine the download state
+fn download_state(value: i64) -> &'static str {
+    match value {
+        0 => "InProgress",
+        1 => "Complete",
+        2 => "Failed",
+        3 => "Cancelled",
+        4 => "Paused",
+        5 | 6 | 8 => "Blocked",
+        7 => "Dirty",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{download_state, firefox_downloads, firefox_history, visit_type};
+    use crate::artifacts::os::browsers::profiles::alt_profile;
+    use std::path::PathBuf;
+
+    fn test_places() -> String {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location
+            .push("tests/test_data/browser/firefox/abcd1234.default-release/places.sqlite");
+        test_location.display().to_string()
+    }
+
+    #[test]
+    fn test_firefox_history() {
+        let profile = alt_profile("abcd1234.default-release", "Firefox");
+        let results = firefox_history(&test_places(), &profile).unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].url, "https://www.mozilla.org/en-US/firefox/");
+        assert_eq!(results[0].host, "www.mozilla.org");
+        assert_eq!(results[0].visit_count, 2);
+        assert_eq!(results[0].timestamp, "2023-11-14T22:13:20.000Z");
+        assert_eq!(results[0].details, "Visit type: Typed. Typed: true");
+        assert_eq!(results[1].details, "Visit type: Link. Typed: true");
+        assert!(results[2].details.starts_with("Visit type: Download"));
+    }
+
+    #[test]
+    fn test_firefox_downloads() {
+        let profile = alt_profile("abcd1234.default-release", "Firefox");
+        let results = firefox_downloads(&test_places(), &profile).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].path, "file:///home/bob/Downloads/artemis.zip");
+        assert_eq!(results[0].host, "github.com");
+        assert_eq!(results[0].size, 1024);
+        assert_eq!(results[0].timestamp, "2023-11-14T22:18:20.000Z");
+        assert_eq!(results[0].end_time, "2023-11-14T22:18:25.000Z");
+        assert_eq!(results[0].details, "State: Complete. Deleted: false");
+    }
+
+    #[test]
+    fn test_visit_type() {
+        assert_eq!(visit_type(9), "Reload");
+        assert_eq!(visit_type(20), "Unknown");
+    }
+
+    #[test]
+    fn test_download_state() {
+        assert_eq!(download_state(3), "Cancelled");
+        assert_eq!(download_state(-1), "Unknown");
+    }
+}
@@ -0,0 +1,6 @@
+pub(crate) mod artifact;
+mod chromium;
+mod error;
+mod firefox;
+mod profiles;
+mod records;
@@ -0,0 +1,208 @@
+use super::error::BrowserError;
+use crate::{
+    artifacts::os::systeminfo::info::{PlatformType, get_platform_enum},
+    filesystem::{
+        directory::{get_user_paths, is_directory, list_directories},
+        files::{get_filename, is_file},
+    },
+};
+use std::path::Path;
+use tracing::error;
+
+/// Browser profile directory
+#[derive(Debug)]
+pub(crate) struct BrowserProfile {
+    pub(crate) browser: String,
+    pub(crate) user: String,
+    pub(crate) path: String,
+}
+
+/// Profile for a user provided directory
+pub(crate) fn alt_profile(path: &str, browser: &str) -> BrowserProfile {
+    BrowserProfile {
+        browser: browser.to_string(),
+        user: String::new(),
+        path: path.to_string(),
+    }
+}
+
+/// Discover Chromium based browser profiles for all users
+pub(crate) fn chromium_profiles() -> Result<Vec<BrowserProfile>, BrowserError> {
+    let browsers: &[(&str, &str)] = match get_platform_enum() {
+        PlatformType::Windows => &[
+            ("Chrome", "AppData/Local/Google/Chrome/User Data"),
+            ("Edge", "AppData/Local/Microsoft/Edge/User Data"),
+            (
+                "Brave",
+                "AppData/Local/BraveSoftware/Brave-Browser/User Data",
+            ),
+            ("Chromium", "AppData/Local/Chromium/User Data"),
+            ("Vivaldi", "AppData/Local/Vivaldi/User Data"),
+            ("Opera", "AppData/Roaming/Opera Software/Opera Stable"),
+        ],
+        PlatformType::Macos => &[
+            ("Chrome", "Library/Application Support/Google/Chrome"),
+            ("Edge", "Library/Application Support/Microsoft Edge"),
+            (
+                "Brave",
+                "Library/Application Support/BraveSoftware/Brave-Browser",
+            ),
+            ("Chromium", "Library/Application Support/Chromium"),
+            ("Vivaldi", "Library/Application Support/Vivaldi"),
+            (
+                "Opera",
+                "Library/Application Support/com.operasoftware.Opera",
+            ),
+        ],
+        PlatformType::Linux => &[
+            ("Chrome", ".config/google-chrome"),
+            ("Edge", ".config/microsoft-edge"),
+            ("Brave", ".config/BraveSoftware/Brave-Browser"),
+            ("Chromium", ".config/chromium"),
+            ("Chromium", "snap/chromium/common/chromium"),
+            ("Vivaldi", ".config/vivaldi"),
+            ("Opera", ".config/opera"),
+        ],
+        PlatformType::Unknown => &[],
+    };
+
+    // Opera stores the profile data directly in the user data directory
+    let markers = ["Preferences", "History"];
+    let mut profiles = Vec::new();
+    for user in users()? {
+        for (browser, relative) in browsers {
+            let user_data = join_path(&user, relative);
+            if !is_directory(&user_data) {
+                continue;
+            }
+            let mut dirs = profile_dirs(&user_data, &markers);
+            if has_marker(&user_data, &markers) {
+                dirs.push(user_data);
+            }
+            for path in dirs {
+                profiles.push(BrowserProfile {
+                    browser: (*browser).to_string(),
+                    user: get_filename(&user),
+                    path,
+                });
+            }
+        }
+    }
+    Ok(profiles)
+}
+
+/// Discover Firefox profiles for all users
+pub(crate) fn firefox_profiles() -> Result<Vec<BrowserProfile>, BrowserError> {
+    let locations: &[&str] = match get_platform_enum() {
+        PlatformType::Windows => &["AppData/Roaming/Mozilla/Firefox/Profiles"],
+        PlatformType::Macos => &["Library/Application Support/Firefox/Profiles"],
+        PlatformType::Linux => &[
+            ".mozilla/firefox",
+            "snap/firefox/common/.mozilla/firefox",
+            ".var/app/org.mozilla.firefox/.mozilla/firefox",
+        ],
+        PlatformType::Unknown => &[],
+    };
+
+    let markers = ["places.sqlite", "cookies.sqlite"];
+    let mut profiles = Vec::new();
+    for user in users()? {
+        for relative in locations {
+            let parent = join_path(&user, relative);
+            if !is_directory(&parent) {
+                continue;
+            }
+            for path in profile_dirs(&parent, &markers) {
+                profiles.push(BrowserProfile {
+                    browser: String::from("Firefox"),
+                    user: get_filename(&user),
+                    path,
+                });
+            }
+        }
+    }
+    Ok(profiles)
+}
+
+/// Get the home directories for all users
+fn users() -> Result<Vec<String>, BrowserError> {
+    match get_user_paths() {
+        Ok(result) => Ok(result),
+        Err(err) => {
+            error!("[browsers] Could not get user paths: {err:?}");
+            Err(BrowserError::UserPaths)
+        }
+    }
+}
+
+/// Get directories that contain at least one of the profile marker files
+fn profile_dirs(parent: &str, markers: &[&str]) -> Vec<String> {
+    let Ok(dirs) = list_directories(parent) else {
+        return Vec::new();
+    };
+    dirs.into_iter()
+        .filter(|dir| has_marker(dir, markers))
+        .collect()
+}
+
+/// Check if a directory contains one of the marker files
+fn has_marker(dir: &str, markers: &[&str]) -> bool {
+    markers
+        .iter()
+        .any(|marker| is_file(&join_path(dir, marker)))
+}
+
+/// Join a relative path (with forward slashes) to a base directory using the platform separator
+pub(crate) fn join_path(base: &str, relative: &str) -> String {
+    let mut path = Path::new(base).to_path_buf();
+    for component in relative.split('/') {
+        path.push(component);
+    }
+    path.display().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{alt_profile, chromium_profiles, firefox_profiles, join_path, profile_dirs};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_profile_dirs() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/browser/chromium");
+        let dirs = profile_dirs(test_location.to_str().unwrap(), &["Preferences"]);
+        assert_eq!(dirs.len(), 1);
+        assert!(dirs[0].ends_with("Default"));
+
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/browser/firefox");
+        let dirs = profile_dirs(test_location.to_str().unwrap(), &["places.sqlite"]);
+        assert_eq!(dirs.len(), 1);
+    }
+
+    #[test]
+    fn test_join_path() {
+        let path = join_path("home", "Library/Application Support/Firefox");
+        assert_eq!(
+            PathBuf::from(path),
+            PathBuf::from("home")
+                .join("Library")
+                .join("Application Support")
+                .join("Firefox")
+        );
+    }
+
+    #[test]
+    fn test_alt_profile() {
+        let profile = alt_profile("/tmp/Default", "Chromium");
+        assert_eq!(profile.browser, "Chromium");
+        assert_eq!(profile.path, "/tmp/Default");
+    }
+
+    #[test]
+    fn test_discover_profiles() {
+        // Profiles depend on the system. Just make sure discovery does not fail
+        let _ = chromium_profiles().unwrap();
+        let _ = firefox_profiles().unwrap();
+    }
+}
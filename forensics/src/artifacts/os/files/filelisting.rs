@@ -20,6 +20,7 @@ use crate::output::manager::OutputManager;
 use crate::output::record::serialize_records_to_stream;
 use crate::structs::artifacts::os::files::FileOptions;
 use crate::structs::toml::OutputFormat;
+use crate::utils::cancel::CancelToken;
 use crate::utils::regex_options::{create_regex, regex_check};
 use common::files::FileInfo;
 use common::files::Hashes;
@@ -88,6 +89,14 @@ pub(crate) fn get_filelist(
                 continue;
             }
         };
+        // Stop walking the filesystem if the collection has been cancelled
+        if manager.cancel.is_cancelled() {
+            warn!(
+                "[files] Collection cancelled. Stopping filelisting at {:?}",
+                entry.path()
+            );
+            break;
+        }
 
         // If Regex does not match then skip file info
         if options.path_regex.is_some()
@@ -121,7 +130,7 @@ pub(crate) fn get_filelist(
             }
         }
 
-        let file_entry_result = file_metadata(&entry, options, &platform);
+        let file_entry_result = file_metadata(&entry, options, &platform, &manager.cancel);
         let mut file_entry = match file_entry_result {
             Ok(result) => result,
             Err(err) => {
@@ -157,6 +166,7 @@ fn file_metadata(
     entry: &DirEntry,
     options: &FileOptions,
     plat: &PlatformType,
+    cancel: &CancelToken,
 ) -> Result<FileInfo, ioError> {
     let mut file_entry = FileInfo {
         full_path: entry.path().display().to_string(),
@@ -211,7 +221,7 @@ fn file_metadata(
 
     if (hashes.md5 || hashes.sha1 || hashes.sha256) && file_entry.is_file && !file_entry.is_symlink
     {
-        let (md5, sha1, sha256) = hash_file(&hashes, &file_entry.full_path);
+        let (md5, sha1, sha256) = hash_file(&hashes, &file_entry.full_path, cancel);
         file_entry.md5 = md5;
         file_entry.sha1 = sha1;
         file_entry.sha256 = sha256;
@@ -347,6 +357,7 @@ mod tests {
     use crate::output::manager::OutputManager;
     use crate::structs::artifacts::os::files::FileOptions;
     use crate::structs::toml::{OutputConfig, OutputDestination, OutputFormat};
+    use crate::utils::cancel::CancelToken;
     use common::files::FileInfo;
     use std::path::PathBuf;
     use walkdir::WalkDir;
@@ -482,7 +493,13 @@ mod tests {
         let mut results: Vec<FileInfo> = Vec::new();
         for entries in start_path {
             let entry_data = entries.unwrap();
-            let data = file_metadata(&entry_data, &options, &PlatformType::Windows).unwrap();
+            let data = file_metadata(
+                &entry_data,
+                &options,
+                &PlatformType::Windows,
+                &CancelToken::new(),
+            )
+            .unwrap();
             results.push(data);
         }
         assert!(results.len() > 3);
@@ -508,7 +525,13 @@ mod tests {
         };
         for entries in start_path {
             let entry_data = entries.unwrap();
-            let data = file_metadata(&entry_data, &options, &PlatformType::Linux).unwrap();
+            let data = file_metadata(
+                &entry_data,
+                &options,
+                &PlatformType::Linux,
+                &CancelToken::new(),
+            )
+            .unwrap();
             results.push(data);
         }
         assert!(results.len() > 3);
@@ -574,7 +597,13 @@ mod tests {
         let mut results: Vec<FileInfo> = Vec::new();
         for entries in start_path {
             let entry_data = entries.unwrap();
-            let data = file_metadata(&entry_data, &options, &PlatformType::Macos).unwrap();
+            let data = file_metadata(
+                &entry_data,
+                &options,
+                &PlatformType::Macos,
+                &CancelToken::new(),
+            )
+            .unwrap();
             results.push(data);
         }
         assert!(results.len() > 3);
@@ -3,11 +3,12 @@ use crate::artifacts::os::linux::ext4::parser::ext4_filelisting;
 use crate::output::manager::OutputManager;
 use crate::output::record::serialize_records_to_stream;
 use crate::structs::artifacts::os::linux::{
-    Ext4Options, JournalOptions, LinuxSudoOptions, LogonOptions,
+    Ext4Options, JournalOptions, LinuxSudoOptions, LogonOptions, UserActivityOptions,
 };
 use tracing::{error, warn};
 
 use super::sudo::logs::grab_sudo_logs;
+use super::useractivity::parser::grab_user_activity;
 use super::{journals::parser::grab_journal, logons::parser::grab_logons};
 
 /// Get Linux `Journals`
@@ -84,6 +85,39 @@ pub(crate) fn sudo_logs_linux(
     Ok(())
 }
 
+/// Parse shell histories and other user activity files on Linux
+pub(crate) fn user_activity(
+    manager: &mut OutputManager,
+    options: &UserActivityOptions,
+) -> Result<(), LinuxArtifactError> {
+    let entries = match grab_user_activity(options) {
+        Ok(results) => results,
+        Err(err) => {
+            error!("Failed to get user activity: {err:?}");
+            return Err(LinuxArtifactError::UserActivity);
+        }
+    };
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let mut records = match serialize_records_to_stream(entries) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Failed to serialize user activity: {err:?}");
+            return Err(LinuxArtifactError::Serialize);
+        }
+    };
+
+    let artifact_name = "useractivity";
+    if let Err(err) = manager.write_artifact(artifact_name, options, &mut records) {
+        error!("Failed to output useractivity: {err:?}");
+        return Err(LinuxArtifactError::Output);
+    }
+
+    Ok(())
+}
+
 /// Parse the ext4 filesystem
 pub(crate) fn ext4_filelist(
     manager: &mut OutputManager,
@@ -101,12 +135,12 @@ pub(crate) fn ext4_filelist(
 #[cfg(target_os = "linux")]
 mod tests {
     use crate::artifacts::os::linux::artifacts::{
-        ext4_filelist, journals, logons, sudo_logs_linux,
+        ext4_filelist, journals, logons, sudo_logs_linux, user_activity,
     };
     use crate::artifacts::os::systeminfo::info::get_info_metadata;
     use crate::output::manager::OutputManager;
     use crate::structs::artifacts::os::linux::{
-        Ext4Options, JournalOptions, LinuxSudoOptions, LogonOptions,
+        Ext4Options, JournalOptions, LinuxSudoOptions, LogonOptions, UserActivityOptions,
     };
     use crate::structs::toml::{OutputConfig, OutputDestination, OutputFormat};
     use std::path::PathBuf;
@@ -166,6 +200,13 @@ mod tests {
         assert_eq!(status, ());
     }
 
+    #[test]
+    fn test_user_activity() {
+        let mut output = output_options("useractivity_test", "./tmp", false);
+
+        user_activity(&mut output, &UserActivityOptions { alt_dir: None }).unwrap();
+    }
+
     #[test]
     fn test_ext4_filelist() {
         // Run test only in Github CI. Parsing the ext4 filesystem requires root
@@ -6,6 +6,7 @@ pub(crate) enum LinuxArtifactError {
     Journal,
     SudoLog,
     Ext4,
+    UserActivity,
     Output,
 }
 
@@ -18,6 +19,7 @@ impl fmt::Display for LinuxArtifactError {
             LinuxArtifactError::Journal => write!(f, "Failed to parse Journals"),
             LinuxArtifactError::SudoLog => write!(f, "Failed to parse sudo logs"),
             LinuxArtifactError::Ext4 => write!(f, "Failed to parse ext4 filesystem"),
+            LinuxArtifactError::UserActivity => write!(f, "Failed to parse user activity"),
             LinuxArtifactError::Output => write!(f, "Failed to write linux artifact"),
         }
     }
@@ -26,7 +26,7 @@ use crate::{
             locator::FileLocator,
         },
     },
-    output::manager::OutputManager,
+    output::{context::record_source, manager::OutputManager},
     structs::artifacts::os::linux::JournalOptions,
 };
 use common::linux::Journal;
@@ -45,6 +45,7 @@ pub(crate) fn grab_journal(
 
     let mut accessor = Accessor::with_defaults();
     for path in paths {
+        record_source(path);
         let journals = match accessor.globfs(path) {
             Ok(results) => results,
             Err(err) => {
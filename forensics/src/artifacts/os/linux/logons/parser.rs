@@ -17,6 +17,7 @@ use crate::{
         entry::handle::{EntryKind, FileHandle},
     },
     artifacts::os::linux::logons::logon::logon_reader,
+    output::context::record_source,
     structs::artifacts::os::linux::LogonOptions,
 };
 use common::linux::{Logon, Status};
@@ -42,6 +43,7 @@ pub(crate) fn grab_logons(options: &LogonOptions) -> Vec<Logon> {
 
 /// Parse the provided logon file
 pub(crate) fn logon_file_path(accessor: &mut Accessor, path: &str, logons: &mut Vec<Logon>) {
+    record_source(path);
     let files = match accessor.globfs(path) {
         Ok(result) => result,
         Err(err) => {
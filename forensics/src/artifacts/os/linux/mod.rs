@@ -5,3 +5,4 @@ pub(crate) mod ext4;
 pub(crate) mod journals;
 pub(crate) mod logons;
 pub(crate) mod sudo;
+pub(crate) mod useractivity;
@@ -1,6 +1,7 @@
 use crate::{
     accessor::{access::Accessor, entry::handle::EntryKind},
     artifacts::os::linux::journals::{error::JournalError, journal::parse_journal_file},
+    output::context::record_source,
     structs::artifacts::os::linux::LinuxSudoOptions,
 };
 use common::linux::Journal;
@@ -21,6 +22,7 @@ pub(crate) fn grab_sudo_logs(options: &LinuxSudoOptions) -> Result<Vec<Journal>,
     let mut accessor = Accessor::with_defaults();
 
     for path in paths {
+        record_source(&path);
         let journals = match accessor.globfs(&path) {
             Ok(results) => results,
             Err(err) => {
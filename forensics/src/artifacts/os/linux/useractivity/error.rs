@@ -0,0 +1,16 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum UserActivityError {
+    ReadPasswd,
+}
+
+impl std::error::Error for UserActivityError {}
+
+impl fmt::Display for UserActivityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UserActivityError::ReadPasswd => write!(f, "Failed to read passwd file"),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use super::user::{PasswdUser, new_activity};
+use common::linux::{UserActivity, UserActivityType};
+
+/// Parse `.lesshst`. Entries are grouped into sections (`.search`, `.shell`, `.mark`). Search and shell entries start with a `"`
+pub(crate) fn less_history(text: &str, user: &PasswdUser, path: &str) -> Vec<UserActivity> {
+    let mut entries = Vec::new();
+    let mut section = "";
+    for (index, line) in text.lines().enumerate() {
+        if line.starts_with(".less-history-file:") || line.trim().is_empty() {
+            continue;
+        }
+        if let Some(value) = line.strip_prefix('.') {
+            section = value;
+            continue;
+        }
+
+        let value = match line.strip_prefix('"') {
+            Some(value) => value,
+            // Marks are formatted as `m <mark> <screen line> <file position> <file>`
+            None if section == "mark" => line.splitn(5, ' ').nth(4).unwrap_or(line),
+            None => line,
+        };
+        let mut entry = new_activity(user, UserActivityType::LessHistory, value, index + 1, path);
+        entry.details = format!("Section: {section}");
+        entries.push(entry);
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::less_history;
+    use crate::artifacts::os::linux::useractivity::user::PasswdUser;
+
+    #[test]
+    fn test_less_history() {
+        let user = PasswdUser {
+            username: String::from("bob"),
+            uid: 1000,
+            gid: 1000,
+            gecos: String::new(),
+            home: String::from("/home/bob"),
+            shell: String::from("/bin/bash"),
+        };
+        let text = ".less-history-file:\n.search\n\"password\n\"error\n.shell\n\"cat /etc/shadow\n.mark\nm a 1 2048 /var/log/syslog\n";
+        let results = less_history(text, &user, ".lesshst");
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].value, "password");
+        assert_eq!(results[0].details, "Section: search");
+        assert_eq!(results[2].value, "cat /etc/shadow");
+        assert_eq!(results[2].details, "Section: shell");
+        assert_eq!(results[3].value, "/var/log/syslog");
+        assert_eq!(results[3].line, 8);
+    }
+}
@@ -0,0 +1,7 @@
+mod error;
+mod less;
+pub(crate) mod parser;
+mod shell;
+mod ssh;
+mod user;
+mod viminfo;
@@ -0,0 +1,183 @@
+/**
+ * Linux users leave activity in dotfiles in their home directory such as shell histories, editor state, and SSH files
+ * Users are enumerated from `/etc/passwd` and each home directory is checked for supported files
+ *
+ * References:
+ * `https://www.gnu.org/software/bash/manual/html_node/Bash-History-Facilities.html`
+ * `https://zsh.sourceforge.io/Doc/Release/Options.html#index-EXTENDEDHISTORY`
+ * `https://man.openbsd.org/sshd.8#AUTHORIZED_KEYS_FILE_FORMAT`
+ */
+use super::{
+    error::UserActivityError,
+    less::less_history,
+    shell::{bash_history, python_history, zsh_history},
+    ssh::{authorized_keys, known_hosts},
+    user::{PasswdUser, new_activity, parse_passwd},
+    viminfo::viminfo,
+};
+use crate::{
+    filesystem::{
+        directory::is_directory,
+        files::{is_file, read_file},
+    },
+    structs::artifacts::os::linux::UserActivityOptions,
+};
+use common::linux::{UserActivity, UserActivityType};
+use std::path::Path;
+use tracing::{error, warn};
+
+/// Grab user activity for all users in `/etc/passwd`
+pub(crate) fn grab_user_activity(
+    options: &UserActivityOptions,
+) -> Result<Vec<UserActivity>, UserActivityError> {
+    // Alternative root directory. Ex: a mounted disk image
+    let root = options.alt_dir.as_deref().unwrap_or("/");
+    let passwd = root_path(root, "/etc/passwd");
+    let data = match read_file(&passwd) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[useractivity] Could not read {passwd}: {err:?}");
+            return Err(UserActivityError::ReadPasswd);
+        }
+    };
+    let users = parse_passwd(&String::from_utf8_lossy(&data));
+
+    let mut entries = Vec::new();
+    let mut homes = Vec::new();
+    for user in users {
+        let mut entry = new_activity(&user, UserActivityType::User, &user.gecos, 0, &passwd);
+        entry.details = format!("Home: {}. Shell: {}", user.home, user.shell);
+        entries.push(entry);
+
+        // Multiple system accounts may share the same home directory
+        let home = root_path(root, &user.home);
+        if user.home.is_empty() || homes.contains(&home) || !is_directory(&home) {
+            continue;
+        }
+        entries.append(&mut home_activity(&user, &home));
+        homes.push(home);
+    }
+    Ok(entries)
+}
+
+/// Parse the supported files in a user's home directory
+fn home_activity(user: &PasswdUser, home: &str) -> Vec<UserActivity> {
+    let mut entries = Vec::new();
+    for name in [
+        ".bash_history",
+        ".zsh_history",
+        ".histfile",
+        ".python_history",
+        ".lesshst",
+        ".viminfo",
+        ".ssh/known_hosts",
+        ".ssh/authorized_keys",
+    ] {
+        let path = Path::new(home).join(name).display().to_string();
+        if !is_file(&path) {
+            continue;
+        }
+        let data = match read_file(&path) {
+            Ok(result) => result,
+            Err(err) => {
+                warn!("[useractivity] Could not read {path}: {err:?}");
+                continue;
+            }
+        };
+        let text = String::from_utf8_lossy(&data);
+        let mut results = match name {
+            ".bash_history" => bash_history(&text, user, &path),
+            ".zsh_history" | ".histfile" => zsh_history(&data, user, &path),
+            ".python_history" => python_history(&text, user, &path),
+            ".lesshst" => less_history(&text, user, &path),
+            ".viminfo" => viminfo(&text, user, &path),
+            ".ssh/known_hosts" => known_hosts(&text, user, &path),
+            _ => authorized_keys(&text, user, &path),
+        };
+        entries.append(&mut results);
+    }
+    entries
+}
+
+/// Join an absolute path from `/etc/passwd` to the root directory
+fn root_path(root: &str, path: &str) -> String {
+    Path::new(root)
+        .join(path.trim_start_matches('/'))
+        .display()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{grab_user_activity, root_path};
+    use crate::{
+        artifacts::os::linux::useractivity::error::UserActivityError,
+        structs::artifacts::os::linux::UserActivityOptions,
+    };
+    use common::linux::UserActivityType;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_grab_user_activity() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/linux/useractivity");
+        let options = UserActivityOptions {
+            alt_dir: Some(test_location.display().to_string()),
+        };
+        let results = grab_user_activity(&options).unwrap();
+        let count = |activity_type: UserActivityType| {
+            results
+                .iter()
+                .filter(|entry| entry.activity_type == activity_type)
+                .count()
+        };
+        assert_eq!(count(UserActivityType::User), 3);
+        assert_eq!(count(UserActivityType::BashHistory), 4);
+        assert_eq!(count(UserActivityType::ZshHistory), 2);
+        assert_eq!(count(UserActivityType::PythonHistory), 1);
+        assert_eq!(count(UserActivityType::LessHistory), 2);
+        assert_eq!(count(UserActivityType::VimCommand), 1);
+        assert_eq!(count(UserActivityType::VimFile), 1);
+        assert_eq!(count(UserActivityType::KnownHost), 1);
+        assert_eq!(count(UserActivityType::AuthorizedKey), 1);
+
+        let sudo = results
+            .iter()
+            .find(|entry| entry.value == "sudo cat /etc/shadow")
+            .unwrap();
+        assert_eq!(sudo.username, "bob");
+        assert_eq!(sudo.uid, 1000);
+        assert_eq!(sudo.timestamp, "2023-11-14T22:15:00.000Z");
+    }
+
+    #[test]
+    fn test_grab_user_activity_bad_root() {
+        let options = UserActivityOptions {
+            alt_dir: Some(String::from("./does/not/exist")),
+        };
+        assert_eq!(
+            grab_user_activity(&options).err(),
+            Some(UserActivityError::ReadPasswd)
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_grab_user_activity_live() {
+        let options = UserActivityOptions { alt_dir: None };
+        let results = grab_user_activity(&options).unwrap();
+        assert!(!results.is_empty());
+    }
+
+    #[test]
+    fn test_root_path() {
+        assert_eq!(
+            PathBuf::from(root_path("/mnt/image", "/home/bob")),
+            PathBuf::from("/mnt/image/home/bob")
+        );
+        assert_eq!(
+            PathBuf::from(root_path("/", "/etc/passwd")),
+            PathBuf::from("/etc/passwd")
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use super::user::{PasswdUser, new_activity};
+use crate::utils::time::unixepoch_to_iso;
+use common::linux::{UserActivity, UserActivityType};
+
+/// Parse `.bash_history`. If `HISTTIMEFORMAT` is set each command is preceded by a `#<epoch>` line
+pub(crate) fn bash_history(text: &str, user: &PasswdUser, path: &str) -> Vec<UserActivity> {
+    let mut entries = Vec::new();
+    let mut timestamp = None;
+    for (index, line) in text.lines().enumerate() {
+        if let Some(value) = line.strip_prefix('#')
+            && !value.is_empty()
+            && value.chars().all(|char| char.is_ascii_digit())
+        {
+            timestamp = value.parse::<i64>().ok();
+            continue;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut entry = new_activity(user, UserActivityType::BashHistory, line, index + 1, path);
+        if let Some(time) = timestamp.take() {
+            entry.timestamp = unixepoch_to_iso(time);
+        }
+        entries.push(entry);
+    }
+    entries
+}
+
+/// Parse `.zsh_history`. Supports the `EXTENDED_HISTORY` format `: <start>:<elapsed>;<command>` and multiline commands
+pub(crate) fn zsh_history(data: &[u8], user: &PasswdUser, path: &str) -> Vec<UserActivity> {
+    let text = unmetafy(data);
+    let mut entries = Vec::new();
+
+    let mut command = String::new();
+    let mut start_line = 0;
+    for (index, line) in text.lines().enumerate() {
+        if command.is_empty() {
+            start_line = index + 1;
+        } else {
+            command.push('\n');
+        }
+
+        // Multiline commands end each line with a backslash
+        if let Some(value) = line.strip_suffix('\\') {
+            command.push_str(value);
+            continue;
+        }
+        command.push_str(line);
+
+        if !command.trim().is_empty() {
+            entries.push(zsh_entry(&command, user, path, start_line));
+        }
+        command.clear();
+    }
+    if !command.trim().is_empty() {
+        entries.push(zsh_entry(&command, user, path, start_line));
+    }
+    entries
+}
+
+/// Create an entry from a zsh history command
+fn zsh_entry(command: &str, user: &PasswdUser, path: &str, line: usize) -> UserActivity {
+    let extended = command
+        .strip_prefix(": ")
+        .and_then(|value| value.split_once(';'))
+        .and_then(|(times, value)| {
+            let (start, elapsed) = times.split_once(':')?;
+            Some((
+                start.parse::<i64>().ok()?,
+                elapsed.parse::<i64>().ok()?,
+                value,
+            ))
+        });
+
+    let Some((start, elapsed, value)) = extended else {
+        return new_activity(user, UserActivityType::ZshHistory, command, line, path);
+    };
+    let mut entry = new_activity(user, UserActivityType::ZshHistory, value, line, path);
+    entry.timestamp = unixepoch_to_iso(start);
+    entry.details = format!("Duration: {elapsed}s");
+    entry
+}
+
+/// zsh stores some bytes with a `0x83` prefix followed by the byte XOR 32
+fn unmetafy(data: &[u8]) -> String {
+    let meta = 0x83;
+    let mut bytes = Vec::with_capacity(data.len());
+    let mut iter = data.iter();
+    while let Some(value) = iter.next() {
+        if *value == meta
+            && let Some(next) = iter.next()
+        {
+            bytes.push(next ^ 32);
+            continue;
+        }
+        bytes.push(*value);
+    }
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+/// Parse `.python_history`. libedit based histories have a header and encode spaces as `\040`
+pub(crate) fn python_history(text: &str, user: &PasswdUser, path: &str) -> Vec<UserActivity> {
+    let mut entries = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() || line == "_HiStOrY_V2_" {
+            continue;
+        }
+        entries.push(new_activity(
+            user,
+            UserActivityType::PythonHistory,
+            &decode_octal(line),
+            index + 1,
+            path,
+        ));
+    }
+    entries
+}
+
+/// Decode octal escapes. Ex: `\040` is a space
+pub(crate) fn decode_octal(value: &str) -> String {
+    let mut decoded = String::with_capacity(value.len());
+    let mut remaining = value;
+    while let Some(position) = remaining.find('\\') {
+        decoded.push_str(&remaining[..position]);
+        let escape = remaining
+            .get(position + 1..position + 4)
+            .unwrap_or_default();
+        let octal = 8;
+        match u8::from_str_radix(escape, octal) {
+            Ok(byte) if escape.len() == 3 && escape.bytes().all(|value| value.is_ascii_digit()) => {
+                decoded.push(char::from(byte));
+                remaining = &remaining[position + 4..];
+            }
+            _ => {
+                decoded.push('\\');
+                remaining = &remaining[position + 1..];
+            }
+        }
+    }
+    decoded.push_str(remaining);
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bash_history, decode_octal, python_history, unmetafy, zsh_history};
+    use crate::artifacts::os::linux::useractivity::user::PasswdUser;
+
+    fn test_user() -> PasswdUser {
+        PasswdUser {
+            username: String::from("bob"),
+            uid: 1000,
+            gid: 1000,
+            gecos: String::new(),
+            home: String::from("/home/bob"),
+            shell: String::from("/bin/bash"),
+        }
+    }
+
+    #[test]
+    fn test_bash_history() {
+        let text = "ls -la\n#1700000000\nsudo su\n\n# not a timestamp\n#1700000100\n";
+        let results = bash_history(text, &test_user(), ".bash_history");
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].value, "ls -la");
+        assert_eq!(results[0].timestamp, "1970-01-01T00:00:00.000Z");
+        assert_eq!(results[1].value, "sudo su");
+        assert_eq!(results[1].line, 3);
+        assert_eq!(results[1].timestamp, "2023-11-14T22:13:20.000Z");
+        assert_eq!(results[2].value, "# not a timestamp");
+    }
+
+    #[test]
+    fn test_zsh_history() {
+        let data = b": 1700000000:5;cargo build\n: 1700000100:0;for i in 1 2\\\ndo echo $i\\\ndone\nvim notes.txt\n";
+        let results = zsh_history(data, &test_user(), ".zsh_history");
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].value, "cargo build");
+        assert_eq!(results[0].details, "Duration: 5s");
+        assert_eq!(results[0].timestamp, "2023-11-14T22:13:20.000Z");
+        assert_eq!(results[1].value, "for i in 1 2\ndo echo $i\ndone");
+        assert_eq!(results[1].line, 2);
+        assert_eq!(results[2].value, "vim notes.txt");
+        assert_eq!(results[2].line, 5);
+    }
+
+    #[test]
+    fn test_unmetafy() {
+        assert_eq!(unmetafy(&[b'a', 0xc3, 0x83, 0xa3]), "aÃ");
+        assert_eq!(unmetafy(&[0xc3, 0x83, 0x89]), "é");
+    }
+
+    #[test]
+    fn test_python_history() {
+        let text = "_HiStOrY_V2_\nimport\\040os\nprint(1)\n";
+        let results = python_history(text, &test_user(), ".python_history");
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].value, "import os");
+        assert_eq!(results[0].line, 2);
+    }
+
+    #[test]
+    fn test_decode_octal() {
+        assert_eq!(decode_octal("a\\040b"), "a b");
+        assert_eq!(decode_octal("C:\\Users"), "C:\\Users");
+        assert_eq!(decode_octal("end\\"), "end\\");
+    }
+}
@@ -0,0 +1,166 @@
+use super::user::{PasswdUser, new_activity};
+use crate::utils::encoding::{base64_decode_standard, base64_encode_standard};
+use common::linux::{UserActivity, UserActivityType};
+use sha2::{Digest, Sha256};
+
+/// Parse `~/.ssh/known_hosts`. Format is `[marker] <hosts> <key type> <key> [comment]`
+pub(crate) fn known_hosts(text: &str, user: &PasswdUser, path: &str) -> Vec<UserActivity> {
+    let mut entries = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut values: Vec<&str> = line.split_whitespace().collect();
+        // Markers are `@cert-authority` or `@revoked`
+        let marker = if values.first().is_some_and(|value| value.starts_with('@')) {
+            values.remove(0)
+        } else {
+            ""
+        };
+        let min_values = 3;
+        if values.len() < min_values {
+            continue;
+        }
+
+        let mut entry = new_activity(
+            user,
+            UserActivityType::KnownHost,
+            values[0],
+            index + 1,
+            path,
+        );
+        entry.details = format!(
+            "Key type: {}. Fingerprint: {}. Hashed: {}. Marker: {marker}",
+            values[1],
+            fingerprint(values[2]),
+            values[0].starts_with("|1|"),
+        );
+        entries.push(entry);
+    }
+    entries
+}
+
+/// Parse `~/.ssh/authorized_keys`. Format is `[options] <key type> <key> [comment]`
+pub(crate) fn authorized_keys(text: &str, user: &PasswdUser, path: &str) -> Vec<UserActivity> {
+    let mut entries = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let values: Vec<&str> = line.split_whitespace().collect();
+        // Options may contain spaces. The key type is followed by a base64 key
+        let Some(key_index) = values.iter().enumerate().position(|(position, value)| {
+            is_key_type(value)
+                && values
+                    .get(position + 1)
+                    .is_some_and(|key| base64_decode_standard(key).is_ok())
+        }) else {
+            continue;
+        };
+
+        let options = values[..key_index].join(" ");
+        let comment = values[key_index + 2..].join(" ");
+        let mut entry = new_activity(
+            user,
+            UserActivityType::AuthorizedKey,
+            &comment,
+            index + 1,
+            path,
+        );
+        entry.details = format!(
+            "Key type: {}. Fingerprint: {}. Options: {options}",
+            values[key_index],
+            fingerprint(values[key_index + 1]),
+        );
+        entries.push(entry);
+    }
+    entries
+}
+
+/// Check for a SSH key type. Ex: `ssh-ed25519`, `ecdsa-sha2-nistp256`, `sk-ssh-ed25519@openssh.com`
+fn is_key_type(value: &str) -> bool {
+    value.starts_with("ssh-") || value.starts_with("ecdsa-") || value.starts_with("sk-")
+}
+
+/// Get the OpenSSH SHA256 fingerprint of a base64 key
+fn fingerprint(key: &str) -> String {
+    let Ok(data) = base64_decode_standard(key) else {
+        return String::new();
+    };
+    let hash = Sha256::digest(&data);
+    format!(
+        "SHA256:{}",
+        base64_encode_standard(&hash).trim_end_matches('=')
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{authorized_keys, fingerprint, is_key_type, known_hosts};
+    use crate::artifacts::os::linux::useractivity::user::PasswdUser;
+
+    fn test_user() -> PasswdUser {
+        PasswdUser {
+            username: String::from("bob"),
+            uid: 1000,
+            gid: 1000,
+            gecos: String::new(),
+            home: String::from("/home/bob"),
+            shell: String::from("/bin/bash"),
+        }
+    }
+
+    const KEY: &str = "AAAAC3NzaC1lZDI1NTE5AAAAIOMqqnkVzrm0SdG6UOoqKLsabgH5C9okWi0dh2l9GKJl";
+
+    #[test]
+    fn test_known_hosts() {
+        let text = format!(
+            "# comment\ngithub.com,140.82.112.3 ssh-ed25519 {KEY}\n|1|c2FsdA==|aGFzaA== ssh-ed25519 {KEY}\n@cert-authority *.example.com ssh-ed25519 {KEY}\nbad line\n"
+        );
+        let results = known_hosts(&text, &test_user(), "known_hosts");
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].value, "github.com,140.82.112.3");
+        assert_eq!(results[0].line, 2);
+        assert!(
+            results[0]
+                .details
+                .starts_with("Key type: ssh-ed25519. Fingerprint: SHA256:")
+        );
+        assert!(results[1].details.contains("Hashed: true"));
+        assert!(results[2].details.ends_with("Marker: @cert-authority"));
+    }
+
+    #[test]
+    fn test_authorized_keys() {
+        let text = format!(
+            "ssh-ed25519 {KEY} bob@laptop\nfrom=\"10.0.0.1\",command=\"echo hi\" ssh-ed25519 {KEY}\n"
+        );
+        let results = authorized_keys(&text, &test_user(), "authorized_keys");
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].value, "bob@laptop");
+        assert!(results[0].details.ends_with("Options: "));
+        assert_eq!(results[1].value, "");
+        assert!(
+            results[1]
+                .details
+                .ends_with("Options: from=\"10.0.0.1\",command=\"echo hi\"")
+        );
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(
+            fingerprint(KEY),
+            "SHA256:+DiY3wvvV6TuJJhbpZisF/zLDA0zPMSvHdkr4UvCOqU"
+        );
+        assert_eq!(fingerprint("!!"), "");
+    }
+
+    #[test]
+    fn test_is_key_type() {
+        assert!(is_key_type("ecdsa-sha2-nistp256"));
+        assert!(!is_key_type("command=\"ls\""));
+    }
+}
@@ -0,0 +1,99 @@
+use common::linux::{UserActivity, UserActivityType};
+
+/// User entry from `/etc/passwd`
+#[derive(Debug)]
+pub(crate) struct PasswdUser {
+    pub(crate) username: String,
+    pub(crate) uid: u32,
+    pub(crate) gid: u32,
+    pub(crate) gecos: String,
+    pub(crate) home: String,
+    pub(crate) shell: String,
+}
+
+/// Parse the `/etc/passwd` file. Format is `name:password:uid:gid:gecos:home:shell`
+pub(crate) fn parse_passwd(text: &str) -> Vec<PasswdUser> {
+    let mut users = Vec::new();
+    for line in text.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let values: Vec<&str> = line.split(':').collect();
+        let min_values = 7;
+        if values.len() < min_values {
+            continue;
+        }
+        users.push(PasswdUser {
+            username: values[0].to_string(),
+            uid: values[2].parse().unwrap_or_default(),
+            gid: values[3].parse().unwrap_or_default(),
+            gecos: values[4].to_string(),
+            home: values[5].to_string(),
+            shell: values[6].to_string(),
+        });
+    }
+    users
+}
+
+/// Create an activity entry for the user
+pub(crate) fn new_activity(
+    user: &PasswdUser,
+    activity_type: UserActivityType,
+    value: &str,
+    line: usize,
+    evidence: &str,
+) -> UserActivity {
+    UserActivity {
+        username: user.username.clone(),
+        uid: user.uid,
+        gid: user.gid,
+        home: user.home.clone(),
+        shell: user.shell.clone(),
+        activity_type,
+        value: value.to_string(),
+        details: String::new(),
+        timestamp: String::from("1970-01-01T00:00:00.000Z"),
+        line,
+        evidence: evidence.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PasswdUser, new_activity, parse_passwd};
+    use common::linux::UserActivityType;
+
+    #[test]
+    fn test_parse_passwd() {
+        let text = "root:x:0:0:root:/root:/bin/bash\n# comment\n\nbob:x:1000:1000:Bob,,,:/home/bob:/usr/bin/zsh\nbad:x:1";
+        let users = parse_passwd(text);
+        assert_eq!(users.len(), 2);
+        assert_eq!(users[1].username, "bob");
+        assert_eq!(users[1].uid, 1000);
+        assert_eq!(users[1].gecos, "Bob,,,");
+        assert_eq!(users[1].home, "/home/bob");
+        assert_eq!(users[1].shell, "/usr/bin/zsh");
+    }
+
+    #[test]
+    fn test_new_activity() {
+        let user = PasswdUser {
+            username: String::from("bob"),
+            uid: 1000,
+            gid: 1000,
+            gecos: String::new(),
+            home: String::from("/home/bob"),
+            shell: String::from("/bin/bash"),
+        };
+        let entry = new_activity(
+            &user,
+            UserActivityType::BashHistory,
+            "ls",
+            1,
+            "/home/bob/.bash_history",
+        );
+        assert_eq!(entry.username, "bob");
+        assert_eq!(entry.value, "ls");
+        assert_eq!(entry.timestamp, "1970-01-01T00:00:00.000Z");
+    }
+}
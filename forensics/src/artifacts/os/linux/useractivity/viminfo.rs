@@ -0,0 +1,153 @@
+/**
+ * Vim tracks command line history, search history, and recently edited files in `.viminfo`
+ * Newer versions of Vim follow each entry with a `|` line that contains a timestamp
+ *
+ * References:
+ * `https://vimhelp.org/starting.txt.html#viminfo-file`
+ */
+use super::user::{PasswdUser, new_activity};
+use crate::utils::time::unixepoch_to_iso;
+use common::linux::{UserActivity, UserActivityType};
+
+/// Parse the `.viminfo` file
+pub(crate) fn viminfo(text: &str, user: &PasswdUser, path: &str) -> Vec<UserActivity> {
+    let mut entries: Vec<UserActivity> = Vec::new();
+    let mut section = "";
+    // Timestamp lines only apply to the entry directly before them
+    let mut last_entry = false;
+    for (index, line) in text.lines().enumerate() {
+        if let Some(value) = line.strip_prefix("# ") {
+            section = value;
+            last_entry = false;
+            continue;
+        }
+
+        if let Some(value) = line.strip_prefix('|') {
+            if last_entry
+                && let Some(timestamp) = bar_timestamp(value)
+                && let Some(entry) = entries.last_mut()
+            {
+                entry.timestamp = unixepoch_to_iso(timestamp);
+            }
+            last_entry = false;
+            continue;
+        }
+
+        // Mark history lines are tab indented. Ex: `\t*\t1700000000\t0`
+        if let Some(value) = line.strip_prefix("\t*\t") {
+            if last_entry
+                && let Some(Ok(timestamp)) = value.split('\t').next().map(str::parse::<i64>)
+                && let Some(entry) = entries.last_mut()
+            {
+                entry.timestamp = unixepoch_to_iso(timestamp);
+            }
+            continue;
+        }
+
+        let entry = if section.starts_with("Command Line History") {
+            line.strip_prefix(':')
+                .map(|value| (UserActivityType::VimCommand, value.to_string()))
+        } else if section.starts_with("Search String History") {
+            // First character is the history type. Second is the search separator
+            line.strip_prefix('?').map(|value| {
+                (
+                    UserActivityType::VimSearch,
+                    value
+                        .strip_prefix(['/', '?', ' '])
+                        .unwrap_or(value)
+                        .to_string(),
+                )
+            })
+        } else if section.starts_with("File marks") {
+            line.strip_prefix('\'')
+                .map(|value| (UserActivityType::VimFile, mark_path(value)))
+        } else if section.starts_with("History of marks within files") {
+            line.strip_prefix("> ")
+                .map(|value| (UserActivityType::VimFile, value.to_string()))
+        } else {
+            None
+        };
+
+        last_entry = false;
+        if let Some((activity_type, value)) = entry {
+            let mut activity = new_activity(user, activity_type, &value, index + 1, path);
+            activity.details = format!("Section: {section}");
+            entries.push(activity);
+            last_entry = true;
+        }
+    }
+    entries
+}
+
+/// Get the file path from a file mark. Format is `<mark>  <line>  <column>  <path>`
+fn mark_path(value: &str) -> String {
+    let mut remaining = value;
+    let fields = 3;
+    for _ in 0..fields {
+        remaining = remaining
+            .trim_start()
+            .split_once(char::is_whitespace)
+            .map_or("", |(_, next)| next);
+    }
+    remaining.trim().to_string()
+}
+
+/// Get the timestamp from a `|` line. Ex: `2,0,1700000000,,"wq"` or `4,48,12,0,1700000000,"/etc/hosts"`
+fn bar_timestamp(value: &str) -> Option<i64> {
+    let fields: Vec<&str> = value.split(',').collect();
+    let index = match fields.first() {
+        Some(&"2") => 2,
+        Some(&"4") => 4,
+        _ => return None,
+    };
+    fields.get(index)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bar_timestamp, mark_path, viminfo};
+    use crate::artifacts::os::linux::useractivity::user::PasswdUser;
+    use common::linux::UserActivityType;
+
+    #[test]
+    fn test_viminfo() {
+        let user = PasswdUser {
+            username: String::from("bob"),
+            uid: 1000,
+            gid: 1000,
+            gecos: String::new(),
+            home: String::from("/home/bob"),
+            shell: String::from("/bin/bash"),
+        };
+        let text = "# This viminfo file was generated by Vim 9.0.\n\n# Command Line History (newest to oldest):\n:wq\n|2,0,1700000300,,\"wq\"\n:set number\n\n# Search String History (newest to oldest):\n?/password\n|2,1,1700000250,47,\"password\"\n\n# File marks:\n'0  12  0  /etc/my hosts\n|4,48,12,0,1700000300,\"/etc/my hosts\"\n\n# Jumplist (newest first):\n-'  12  0  /etc/hosts\n\n# History of marks within files (newest to oldest):\n\n> /etc/hosts\n\t*\t1700000300\t0\n\t\"\t12\t0\n";
+        let results = viminfo(text, &user, ".viminfo");
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0].activity_type, UserActivityType::VimCommand);
+        assert_eq!(results[0].value, "wq");
+        assert_eq!(results[0].timestamp, "2023-11-14T22:18:20.000Z");
+        assert_eq!(results[1].value, "set number");
+        assert_eq!(results[1].timestamp, "1970-01-01T00:00:00.000Z");
+        assert_eq!(results[2].activity_type, UserActivityType::VimSearch);
+        assert_eq!(results[2].value, "password");
+        assert_eq!(results[3].value, "/etc/my hosts");
+        assert_eq!(results[3].line, 13);
+        assert_eq!(results[4].value, "/etc/hosts");
+        assert_eq!(results[4].timestamp, "2023-11-14T22:18:20.000Z");
+    }
+
+    #[test]
+    fn test_mark_path() {
+        assert_eq!(mark_path("0  12  0  /etc/hosts"), "/etc/hosts");
+        assert_eq!(mark_path("0  12"), "");
+    }
+
+    #[test]
+    fn test_bar_timestamp() {
+        assert_eq!(bar_timestamp("2,0,1700000300,,\"wq\""), Some(1700000300));
+        assert_eq!(
+            bar_timestamp("4,48,12,0,1700000300,\"/etc/hosts\""),
+            Some(1700000300)
+        );
+        assert_eq!(bar_timestamp("1,4"), None);
+    }
+}
@@ -1,3 +1,4 @@
+pub(crate) mod browsers;
 pub(crate) mod connections;
 pub(crate) mod files;
 pub(crate) mod linux;
@@ -12,7 +12,7 @@ use crate::structs::artifacts::os::processes::ProcessOptions;
 use crate::{
     artifacts::os::systeminfo::info::PlatformType,
     filesystem::{directory::get_parent_directory, files::hash_file},
-    utils::time::unixepoch_to_iso,
+    utils::{cancel::CancelToken, time::unixepoch_to_iso},
 };
 use common::files::Hashes;
 use common::system::Processes;
@@ -46,7 +46,11 @@ pub(crate) fn proc_list(
     let plat = get_platform_enum();
 
     for process in proc.processes().values() {
-        let system_proc = proc_info(process, options, &plat);
+        if manager.cancel.is_cancelled() {
+            warn!("Collection cancelled. Stopping process listing");
+            break;
+        }
+        let system_proc = proc_info(process, options, &plat, &manager.cancel);
         processes_list.push(system_proc);
         if options.metadata && processes_list.len() == binary_proc_limit {
             if let Err(err) = output_process(processes_list, manager, options) {
@@ -79,8 +83,9 @@ pub(crate) fn proc_list_entries(options: &ProcessOptions) -> Result<Vec<Processe
         return Ok(Vec::new());
     }
     let plat = get_platform_enum();
+    let cancel = CancelToken::new();
     for process in proc.processes().values() {
-        let system_proc = proc_info(process, options, &plat);
+        let system_proc = proc_info(process, options, &plat, &cancel);
         processes_list.push(system_proc);
     }
 
@@ -88,7 +93,12 @@ pub(crate) fn proc_list_entries(options: &ProcessOptions) -> Result<Vec<Processe
 }
 
 // Get the process info data
-fn proc_info(process: &Process, options: &ProcessOptions, plat: &PlatformType) -> Processes {
+fn proc_info(
+    process: &Process,
+    options: &ProcessOptions,
+    plat: &PlatformType,
+    cancel: &CancelToken,
+) -> Processes {
     let uid_result = process.user_id();
     let uid = match uid_result {
         Some(result) => result.to_string(),
@@ -176,7 +186,7 @@ fn proc_info(process: &Process, options: &ProcessOptions, plat: &PlatformType) -
             sha256: options.sha256,
         };
         (system_proc.md5, system_proc.sha1, system_proc.sha256) =
-            hash_file(&hashes, &system_proc.full_path);
+            hash_file(&hashes, &system_proc.full_path, cancel);
     }
 
     system_proc
@@ -232,6 +242,7 @@ mod tests {
     use crate::output::manager::OutputManager;
     use crate::structs::artifacts::os::processes::ProcessOptions;
     use crate::structs::toml::{OutputConfig, OutputDestination, OutputFormat};
+    use crate::utils::cancel::CancelToken;
     use common::system::Processes;
     use std::path::PathBuf;
     use sysinfo::{ProcessesToUpdate, System};
@@ -278,7 +289,7 @@ mod tests {
 
         let plat = get_platform_enum();
         for process in proc.processes().values() {
-            let system_proc = proc_info(process, &options, &plat);
+            let system_proc = proc_info(process, &options, &plat, &CancelToken::new());
             processes_list.push(system_proc);
         }
         assert!(processes_list.len() > 10);
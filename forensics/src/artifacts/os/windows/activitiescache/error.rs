@@ -0,0 +1,30 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum ActivitiesCacheError {
+    Systemdrive,
+    GlobPath,
+    SqliteParse,
+    BadSQL,
+    Serialize,
+    OutputData,
+}
+
+impl std::error::Error for ActivitiesCacheError {}
+
+impl fmt::Display for ActivitiesCacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActivitiesCacheError::Systemdrive => {
+                write!(f, "Failed to systemdrive env variable value")
+            }
+            ActivitiesCacheError::GlobPath => write!(f, "Failed to glob ActivitiesCache paths"),
+            ActivitiesCacheError::SqliteParse => write!(f, "Failed to parse ActivitiesCache file"),
+            ActivitiesCacheError::BadSQL => write!(f, "Failed to compose ActivitiesCache query"),
+            ActivitiesCacheError::Serialize => {
+                write!(f, "Failed to serialize ActivitiesCache data")
+            }
+            ActivitiesCacheError::OutputData => write!(f, "Failed to output ActivitiesCache data"),
+        }
+    }
+}
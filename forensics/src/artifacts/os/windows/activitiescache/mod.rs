@@ -0,0 +1,4 @@
+mod error;
+pub(crate) mod parser;
+mod payload;
+mod sqlite;
@@ -0,0 +1,135 @@
+/**
+ * Windows Timeline tracks user activity such as opened files, applications in focus, and clipboard data
+ * The data is stored in a SQLITE database at `<drive>:\Users\*\AppData\Local\ConnectedDevicesPlatform\*\ActivitiesCache.db`
+ * Timeline was removed from Windows 11, but the database is still populated on many systems
+ *
+ * References:
+ * `https://kacos2000.github.io/WindowsTimeline/WindowsTimeline.pdf`
+ * `https://github.com/EricZimmerman/WxTCmd`
+ */
+use super::{error::ActivitiesCacheError, sqlite::parse_activities};
+use crate::{
+    filesystem::metadata::glob_paths,
+    output::{manager::OutputManager, record::serialize_records_to_stream},
+    structs::artifacts::os::windows::ActivitiesCacheOptions,
+    utils::environment::get_systemdrive,
+};
+use common::windows::ActivityEntry;
+use tracing::error;
+
+/// Parse and grab Windows Timeline activities based on options provided
+pub(crate) fn grab_activitiescache(
+    options: &ActivitiesCacheOptions,
+    manager: &mut OutputManager,
+) -> Result<(), ActivitiesCacheError> {
+    if let Some(file) = &options.alt_file {
+        let entries = parse_activities(file)?;
+        return output_activities(entries, manager, options);
+    }
+
+    let drive = match get_systemdrive() {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[activitiescache] Could not get systemdrive: {err:?}");
+            return Err(ActivitiesCacheError::Systemdrive);
+        }
+    };
+
+    let glob_path = format!(
+        "{drive}:\\Users\\*\\AppData\\Local\\ConnectedDevicesPlatform\\*\\ActivitiesCache.db"
+    );
+    let paths = match glob_paths(&glob_path) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[activitiescache] Failed to glob {glob_path}: {err:?}");
+            return Err(ActivitiesCacheError::GlobPath);
+        }
+    };
+
+    for path in paths {
+        let entries = match parse_activities(&path.full_path) {
+            Ok(result) => result,
+            Err(err) => {
+                error!(
+                    "[activitiescache] Could not parse {}: {err:?}",
+                    path.full_path
+                );
+                continue;
+            }
+        };
+        output_activities(entries, manager, options)?;
+    }
+
+    Ok(())
+}
+
+/// Output Windows Timeline activities based on `Output` structure
+fn output_activities(
+    entries: Vec<ActivityEntry>,
+    manager: &mut OutputManager,
+    options: &ActivitiesCacheOptions,
+) -> Result<(), ActivitiesCacheError> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let mut records = match serialize_records_to_stream(entries) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[activitiescache] Failed to serialize activities: {err:?}");
+            return Err(ActivitiesCacheError::Serialize);
+        }
+    };
+    let artifact_name = "activitiescache";
+    if let Err(err) = manager.write_artifact(artifact_name, options, &mut records) {
+        error!("[activitiescache] Could not output activities: {err:?}");
+        return Err(ActivitiesCacheError::OutputData);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::grab_activitiescache;
+    use crate::{
+        output::manager::OutputManager,
+        structs::{
+            artifacts::os::windows::ActivitiesCacheOptions,
+            toml::{OutputConfig, OutputDestination, OutputFormat},
+        },
+    };
+    use std::path::PathBuf;
+
+    fn output_options(name: &str, directory: &str, compress: bool) -> OutputManager {
+        let config = OutputConfig {
+            name: name.to_string(),
+            directory: PathBuf::from(directory),
+            format: OutputFormat::Jsonl,
+            compress,
+            endpoint_id: String::from("abcd"),
+            destination: OutputDestination::Local,
+            ..Default::default()
+        };
+        OutputManager::new(config).unwrap()
+    }
+
+    #[test]
+    fn test_grab_activitiescache_alt_file() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/activitiescache/ActivitiesCache.db");
+        let params = ActivitiesCacheOptions {
+            alt_file: Some(test_location.display().to_string()),
+        };
+        let mut output = output_options("activitiescache_temp", "./tmp", false);
+        grab_activitiescache(&params, &mut output).unwrap();
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn test_grab_activitiescache() {
+        let params = ActivitiesCacheOptions { alt_file: None };
+        let mut output = output_options("activitiescache_temp", "./tmp", false);
+        grab_activitiescache(&params, &mut output).unwrap();
+    }
+}
@@ -0,0 +1,259 @@
+use crate::utils::encoding::base64_decode_standard;
+use common::windows::{ActivityApp, ActivityEntry};
+use serde_json::Value;
+use tracing::warn;
+
+/// Parse the `AppId` JSON array. Each entry contains the application and platform
+pub(super) fn parse_app_id(data: &str) -> Vec<ActivityApp> {
+    let mut apps = Vec::new();
+    if data.is_empty() {
+        return apps;
+    }
+    let values: Vec<Value> = match serde_json::from_str(data) {
+        Ok(result) => result,
+        Err(err) => {
+            warn!("[activitiescache] Could not parse AppId {data}: {err:?}");
+            return apps;
+        }
+    };
+
+    for value in values {
+        let application = value["application"].as_str().unwrap_or_default();
+        if application.is_empty() {
+            continue;
+        }
+        apps.push(ActivityApp {
+            application: application.to_string(),
+            platform: value["platform"].as_str().unwrap_or_default().to_string(),
+        });
+    }
+    apps
+}
+
+/// Determine the application for the activity. Prefer Win32 paths over package names
+pub(super) fn primary_application(apps: &[ActivityApp]) -> String {
+    let platforms = [
+        "windows_win32",
+        "x_exe_path",
+        "windows_universal",
+        "packageid",
+    ];
+    for platform in platforms {
+        if let Some(app) = apps
+            .iter()
+            .find(|app| app.platform.eq_ignore_ascii_case(platform))
+        {
+            return resolve_known_folder(&app.application);
+        }
+    }
+
+    apps.first()
+        .map(|app| resolve_known_folder(&app.application))
+        .unwrap_or_default()
+}
+
+/// Replace the known folder GUID at the start of an application path with the folder. Ex: {1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\notepad.exe
+fn resolve_known_folder(application: &str) -> String {
+    let folders = [
+        (
+            "{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}",
+            "%windir%\\System32",
+        ),
+        (
+            "{D65231B0-B2F1-4857-A4CE-A8E7C6EA7D27}",
+            "%windir%\\SysWOW64",
+        ),
+        ("{F38BF404-1D43-42F2-9305-67DE0B28FC23}", "%windir%"),
+        ("{6D809377-6AF0-444B-8957-A3773F02200E}", "%ProgramFiles%"),
+        ("{905E63B6-C1BF-494E-B29C-65B732D3D21A}", "%ProgramFiles%"),
+        (
+            "{7C5A40EF-A0FB-4BFC-874A-C0F2E0B9FA8E}",
+            "%ProgramFiles(x86)%",
+        ),
+        (
+            "{F7F1ED05-9F6D-47A2-AAAE-29D317C6F066}",
+            "%CommonProgramFiles%",
+        ),
+        (
+            "{DE974D24-D9C6-4D3E-BF91-F4455120B917}",
+            "%CommonProgramFiles(x86)%",
+        ),
+        ("{62AB5D82-FDC1-4DC3-A9DD-070D1D495D97}", "%ProgramData%"),
+        ("{F1B32785-6FBA-4FCF-9D55-7B8E7F157091}", "%LOCALAPPDATA%"),
+        ("{3EB685DB-65F9-4CF6-A03A-E3EF65729F3D}", "%APPDATA%"),
+        (
+            "{B4BFCC3A-DB2C-424C-B029-7FE99A87C641}",
+            "%USERPROFILE%\\Desktop",
+        ),
+        (
+            "{FDD39AD0-238F-46AF-ADB4-6C85480369C7}",
+            "%USERPROFILE%\\Documents",
+        ),
+        (
+            "{374DE290-123F-4565-9164-39C4925E467B}",
+            "%USERPROFILE%\\Downloads",
+        ),
+    ];
+
+    for (guid, folder) in folders {
+        if let Some(prefix) = application.get(..guid.len())
+            && prefix.eq_ignore_ascii_case(guid)
+        {
+            return format!("{folder}{}", &application[guid.len()..]);
+        }
+    }
+    application.to_string()
+}
+
+/// Parse the JSON `Payload` and extract the common fields
+pub(super) fn parse_payload(data: &str, entry: &mut ActivityEntry) {
+    if data.is_empty() {
+        return;
+    }
+    let payload: Value = match serde_json::from_str(data) {
+        Ok(result) => result,
+        Err(err) => {
+            warn!(
+                "[activitiescache] Could not parse payload for {}: {err:?}",
+                entry.id
+            );
+            return;
+        }
+    };
+
+    let text = |key: &str| payload[key].as_str().unwrap_or_default().to_string();
+    entry.display_text = text("displayText");
+    entry.description = text("description");
+    entry.app_display_name = text("appDisplayName");
+    entry.content_uri = text("contentUri");
+    entry.user_timezone = text("userTimezone");
+    entry.active_duration_seconds = payload["activeDurationSeconds"]
+        .as_i64()
+        .unwrap_or_default();
+    entry.payload = payload;
+}
+
+/// Parse the JSON `ClipboardPayload` and decode any text that was copied
+pub(super) fn parse_clipboard(data: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    if data.is_empty() {
+        return values;
+    }
+    let clipboard: Vec<Value> = match serde_json::from_str(data) {
+        Ok(result) => result,
+        Err(err) => {
+            warn!("[activitiescache] Could not parse clipboard payload: {err:?}");
+            return values;
+        }
+    };
+
+    for entry in clipboard {
+        let format = entry["formatName"].as_str().unwrap_or_default();
+        let Some(content) = entry["content"].as_str() else {
+            continue;
+        };
+        if !format.eq_ignore_ascii_case("text") && !format.eq_ignore_ascii_case("unicode text") {
+            continue;
+        }
+        match base64_decode_standard(content) {
+            Ok(result) => values.push(String::from_utf8_lossy(&result).to_string()),
+            Err(err) => warn!("[activitiescache] Could not decode clipboard text: {err:?}"),
+        }
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse_app_id, parse_clipboard, parse_payload, primary_application, resolve_known_folder,
+    };
+    use common::windows::{ActivityApp, ActivityEntry, ActivityStatus, ActivityType};
+    use serde_json::Value;
+
+    #[test]
+    fn test_parse_app_id() {
+        let data = r#"[{"application":"Microsoft.Windows.Notepad","platform":"packageId"},{"application":"{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\\notepad.exe","platform":"windows_win32"},{"application":"","platform":"alternateId"}]"#;
+        let apps = parse_app_id(data);
+        assert_eq!(apps.len(), 2);
+        assert_eq!(apps[0].platform, "packageId");
+        assert_eq!(
+            primary_application(&apps),
+            "%windir%\\System32\\notepad.exe"
+        );
+
+        assert!(parse_app_id("not json").is_empty());
+    }
+
+    #[test]
+    fn test_primary_application() {
+        let apps = vec![ActivityApp {
+            application: String::from("Microsoft.Windows.Explorer"),
+            platform: String::from("unknown"),
+        }];
+        assert_eq!(primary_application(&apps), "Microsoft.Windows.Explorer");
+        assert_eq!(primary_application(&[]), "");
+    }
+
+    #[test]
+    fn test_resolve_known_folder() {
+        assert_eq!(
+            resolve_known_folder("{6d809377-6af0-444b-8957-a3773f02200e}\\App\\app.exe"),
+            "%ProgramFiles%\\App\\app.exe"
+        );
+        assert_eq!(resolve_known_folder("Microsoft.Edge"), "Microsoft.Edge");
+    }
+
+    #[test]
+    fn test_parse_payload() {
+        let mut entry = ActivityEntry {
+            id: String::new(),
+            source_table: String::new(),
+            operation_order: 0,
+            activity_type: ActivityType::InFocus,
+            status: ActivityStatus::Active,
+            application: String::new(),
+            app_ids: Vec::new(),
+            package_ids: Vec::new(),
+            app_activity_id: String::new(),
+            parent_activity_id: String::new(),
+            group: String::new(),
+            tag: String::new(),
+            match_id: String::new(),
+            platform_device_id: String::new(),
+            priority: 0,
+            is_local_only: false,
+            display_text: String::new(),
+            description: String::new(),
+            app_display_name: String::new(),
+            content_uri: String::new(),
+            active_duration_seconds: 0,
+            user_timezone: String::new(),
+            clipboard_text: Vec::new(),
+            payload: Value::Null,
+            start_time: String::new(),
+            end_time: String::new(),
+            last_modified: String::new(),
+            last_modified_on_client: String::new(),
+            expiration: String::new(),
+            created_in_cloud: String::new(),
+            created: String::new(),
+            evidence: String::new(),
+        };
+        parse_payload(
+            r#"{"type":"UserEngaged","activeDurationSeconds":42,"userTimezone":"America/New_York","displayText":"notes.txt"}"#,
+            &mut entry,
+        );
+        assert_eq!(entry.active_duration_seconds, 42);
+        assert_eq!(entry.user_timezone, "America/New_York");
+        assert_eq!(entry.display_text, "notes.txt");
+        assert_eq!(entry.payload["type"], "UserEngaged");
+    }
+
+    #[test]
+    fn test_parse_clipboard() {
+        let data = r#"[{"content":"aGVsbG8gd29ybGQ=","formatName":"Text"},{"content":"AAAA","formatName":"Bitmap"}]"#;
+        assert_eq!(parse_clipboard(data), vec![String::from("hello world")]);
+        assert!(parse_clipboard("").is_empty());
+    }
+}
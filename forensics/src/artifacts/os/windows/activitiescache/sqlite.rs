@@ -0,0 +1,243 @@
+use super::{
+    error::ActivitiesCacheError,
+    payload::{parse_app_id, parse_clipboard, parse_payload, primary_application},
+};
+use crate::utils::{
+    sqlite::{column_int, column_text, open_sqlite},
+    time::unixepoch_to_iso,
+    uuid::format_guid_le_bytes,
+};
+use common::windows::{ActivityApp, ActivityEntry, ActivityStatus, ActivityType};
+use rusqlite::{Connection, Row, types::ValueRef};
+use serde_json::Value;
+use std::collections::HashMap;
+use tracing::{error, warn};
+
+/// Parse the `Activity`, `ActivityOperation`, and `Activity_PackageId` tables in the `ActivitiesCache.db` file
+pub(crate) fn parse_activities(path: &str) -> Result<Vec<ActivityEntry>, ActivitiesCacheError> {
+    let conn = match open_sqlite(path) {
+        Ok(connect) => connect,
+        Err(err) => {
+            error!("[activitiescache] Failed to read ActivitiesCache file {path}: {err:?}");
+            return Err(ActivitiesCacheError::SqliteParse);
+        }
+    };
+
+    let packages = package_ids(&conn)?;
+    let mut entries = table_activities(&conn, "Activity", &packages, path)?;
+    // Older versions of the database may not have any pending operations
+    match table_activities(&conn, "ActivityOperation", &packages, path) {
+        Ok(mut result) => entries.append(&mut result),
+        Err(err) => warn!("[activitiescache] Could not parse ActivityOperation table: {err:?}"),
+    }
+
+    Ok(entries)
+}
+
+/// Get all of the `Activity_PackageId` entries. Grouped by the activity ID
+fn package_ids(
+    conn: &Connection,
+) -> Result<HashMap<String, Vec<ActivityApp>>, ActivitiesCacheError> {
+    let query = "SELECT Id, Platform, PackageName FROM Activity_PackageId";
+    let mut stmt = match conn.prepare(query) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[activitiescache] Failed to compose Activity_PackageId query: {err:?}");
+            return Err(ActivitiesCacheError::BadSQL);
+        }
+    };
+    let mut rows = match stmt.query([]) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[activitiescache] Failed to query Activity_PackageId: {err:?}");
+            return Err(ActivitiesCacheError::SqliteParse);
+        }
+    };
+
+    let mut packages: HashMap<String, Vec<ActivityApp>> = HashMap::new();
+    while let Ok(Some(row)) = rows.next() {
+        packages
+            .entry(column_guid(row, "Id"))
+            .or_default()
+            .push(ActivityApp {
+                application: column_text(row, "PackageName"),
+                platform: column_text(row, "Platform"),
+            });
+    }
+
+    Ok(packages)
+}
+
+/// Parse all rows in the `Activity` or `ActivityOperation` table
+fn table_activities(
+    conn: &Connection,
+    table: &str,
+    packages: &HashMap<String, Vec<ActivityApp>>,
+    path: &str,
+) -> Result<Vec<ActivityEntry>, ActivitiesCacheError> {
+    // Columns have been added over time. Select everything and skip any column that does not exist
+    let query = format!("SELECT * FROM {table}");
+    let mut stmt = match conn.prepare(&query) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[activitiescache] Failed to compose {table} query: {err:?}");
+            return Err(ActivitiesCacheError::BadSQL);
+        }
+    };
+    let mut rows = match stmt.query([]) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[activitiescache] Failed to query {table}: {err:?}");
+            return Err(ActivitiesCacheError::SqliteParse);
+        }
+    };
+
+    let is_operation = table == "ActivityOperation";
+    let mut entries = Vec::new();
+    while let Ok(Some(row)) = rows.next() {
+        let id = column_guid(row, "Id");
+        let app_ids = parse_app_id(&column_text(row, "AppId"));
+        let status = if is_operation {
+            column_int(row, "OperationType")
+        } else {
+            column_int(row, "ActivityStatus")
+        };
+
+        let mut entry = ActivityEntry {
+            source_table: table.to_string(),
+            operation_order: column_int(row, "OperationOrder"),
+            activity_type: activity_type(column_int(row, "ActivityType")),
+            status: activity_status(status),
+            application: primary_application(&app_ids),
+            app_ids,
+            package_ids: packages.get(&id).cloned().unwrap_or_default(),
+            id,
+            app_activity_id: column_text(row, "AppActivityId"),
+            parent_activity_id: column_guid(row, "ParentActivityId"),
+            group: column_text(row, "Group"),
+            tag: column_text(row, "Tag"),
+            match_id: column_text(row, "MatchId"),
+            platform_device_id: column_text(row, "PlatformDeviceId"),
+            priority: column_int(row, "Priority"),
+            is_local_only: column_int(row, "IsLocalOnly") != 0,
+            display_text: String::new(),
+            description: String::new(),
+            app_display_name: String::new(),
+            content_uri: String::new(),
+            active_duration_seconds: 0,
+            user_timezone: String::new(),
+            clipboard_text: parse_clipboard(&column_text(row, "ClipboardPayload")),
+            payload: Value::Null,
+            start_time: column_time(row, "StartTime"),
+            end_time: column_time(row, "EndTime"),
+            last_modified: column_time(row, "LastModifiedTime"),
+            last_modified_on_client: column_time(row, "LastModifiedOnClient"),
+            expiration: column_time(row, "ExpirationTime"),
+            created_in_cloud: column_time(row, "CreatedInCloud"),
+            created: column_time(row, "CreatedTime"),
+            evidence: path.to_string(),
+        };
+        parse_payload(&column_text(row, "Payload"), &mut entry);
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+/// Determine the `ActivityType`
+fn activity_type(value: i64) -> ActivityType {
+    match value {
+        2 => ActivityType::Notification,
+        3 => ActivityType::MobileBackup,
+        5 => ActivityType::OpenApp,
+        6 => ActivityType::InFocus,
+        10 => ActivityType::Clipboard,
+        11 | 12 | 15 => ActivityType::System,
+        16 => ActivityType::CopyPaste,
+        _ => ActivityType::Unknown,
+    }
+}
+
+/// Determine the `ActivityStatus` or `OperationType`
+fn activity_status(value: i64) -> ActivityStatus {
+    match value {
+        1 => ActivityStatus::Active,
+        2 => ActivityStatus::Updated,
+        3 => ActivityStatus::Deleted,
+        4 => ActivityStatus::Ignored,
+        _ => ActivityStatus::Unknown,
+    }
+}
+
+/// Get a GUID column. GUIDs are stored as 16 byte blobs
+fn column_guid(row: &Row<'_>, name: &str) -> String {
+    let guid_size = 16;
+    match row.get_ref(name) {
+        Ok(ValueRef::Blob(value)) if value.len() == guid_size => format_guid_le_bytes(value),
+        Ok(ValueRef::Text(value)) => String::from_utf8_lossy(value).to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Get a `UnixEpoch` seconds column as ISO8601
+fn column_time(row: &Row<'_>, name: &str) -> String {
+    unixepoch_to_iso(column_int(row, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{activity_status, activity_type, parse_activities};
+    use common::windows::{ActivityStatus, ActivityType};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_parse_activities() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/activitiescache/ActivitiesCache.db");
+        let results = parse_activities(test_location.to_str().unwrap()).unwrap();
+        assert_eq!(results.len(), 3);
+
+        assert_eq!(results[0].id, "8e2e3c5a-4a1b-4e0f-9c9d-1f3a2b4c5d6e");
+        assert_eq!(results[0].source_table, "Activity");
+        assert_eq!(results[0].activity_type, ActivityType::OpenApp);
+        assert_eq!(results[0].status, ActivityStatus::Active);
+        assert_eq!(results[0].application, "%windir%\\System32\\notepad.exe");
+        assert_eq!(results[0].app_ids.len(), 2);
+        assert_eq!(results[0].package_ids.len(), 2);
+        assert_eq!(results[0].package_ids[1].platform, "x_exe_path");
+        assert_eq!(results[0].display_text, "notes.txt");
+        assert_eq!(results[0].app_display_name, "Notepad");
+        assert_eq!(
+            results[0].description,
+            "C:\\Users\\bob\\Documents\\notes.txt"
+        );
+        assert_eq!(results[0].start_time, "2023-11-14T22:13:20.000Z");
+        assert_eq!(results[0].last_modified, "2023-11-14T22:15:00.000Z");
+        assert_eq!(results[0].priority, 3);
+
+        assert_eq!(results[1].activity_type, ActivityType::InFocus);
+        assert_eq!(results[1].active_duration_seconds, 42);
+        assert_eq!(results[1].end_time, "2023-11-14T22:14:02.000Z");
+        assert_eq!(results[1].user_timezone, "America/New_York");
+
+        assert_eq!(results[2].source_table, "ActivityOperation");
+        assert_eq!(results[2].operation_order, 1);
+        assert_eq!(results[2].activity_type, ActivityType::Clipboard);
+        assert_eq!(results[2].application, "Microsoft.Windows.Explorer");
+        assert_eq!(results[2].clipboard_text, vec!["copied secret text"]);
+        assert_eq!(results[2].created, "2023-11-14T22:18:20.000Z");
+        assert!(results[2].package_ids.is_empty());
+    }
+
+    #[test]
+    fn test_activity_type() {
+        assert_eq!(activity_type(16), ActivityType::CopyPaste);
+        assert_eq!(activity_type(100), ActivityType::Unknown);
+    }
+
+    #[test]
+    fn test_activity_status() {
+        assert_eq!(activity_status(3), ActivityStatus::Deleted);
+        assert_eq!(activity_status(0), ActivityStatus::Unknown);
+    }
+}
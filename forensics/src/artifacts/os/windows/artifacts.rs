@@ -1,7 +1,9 @@
 use super::{
-    accounts::parser::grab_users, amcache::parser::grab_amcache, bits::parser::grab_bits,
-    error::WinArtifactError, eventlogs::parser::grab_eventlogs, jumplists::parser::grab_jumplists,
-    mft::parser::grab_mft, ntfs::parser::ntfs_filelist, outlook::parser::grab_outlook,
+    accounts::parser::grab_users, activitiescache::parser::grab_activitiescache,
+    amcache::parser::grab_amcache, bits::parser::grab_bits, error::WinArtifactError,
+    eventlogs::parser::grab_eventlogs, hiberfil::parser::grab_hiberfil,
+    jumplists::parser::grab_jumplists, logfile::parser::grab_logfile, mft::parser::grab_mft,
+    ntfs::parser::ntfs_filelist, outlook::parser::grab_outlook, pagefile::parser::grab_pagefile,
     prefetch::parser::grab_prefetch, recyclebin::parser::grab_recycle_bin,
     registry::parser::parse_registry, search::parser::grab_search, services::parser::grab_services,
     shellbags::parser::grab_shellbags, shimcache::parser::grab_shimcache,
@@ -12,11 +14,11 @@ use super::{
 use crate::output::manager::OutputManager;
 use crate::output::record::serialize_records_to_stream;
 use crate::structs::artifacts::os::windows::{
-    AmcacheOptions, BitsOptions, EventLogsOptions, JumplistsOptions, MftOptions, OutlookOptions,
-    PrefetchOptions, RawFilesOptions, RecycleBinOptions, RegistryOptions, SearchOptions,
-    ServicesOptions, ShellbagsOptions, ShimcacheOptions, ShimdbOptions, ShortcutOptions,
-    SrumOptions, TasksOptions, UserAssistOptions, UsnJrnlOptions, WindowsUserOptions,
-    WmiPersistOptions,
+    ActivitiesCacheOptions, AmcacheOptions, BitsOptions, EventLogsOptions, HiberfilOptions,
+    JumplistsOptions, LogFileOptions, MftOptions, OutlookOptions, PagefileOptions, PrefetchOptions,
+    RawFilesOptions, RecycleBinOptions, RegistryOptions, SearchOptions, ServicesOptions,
+    ShellbagsOptions, ShimcacheOptions, ShimdbOptions, ShortcutOptions, SrumOptions, TasksOptions,
+    UserAssistOptions, UsnJrnlOptions, WindowsUserOptions, WmiPersistOptions,
 };
 use tracing::error;
 
@@ -322,6 +324,58 @@ pub(crate) fn usnjrnl(
     Ok(())
 }
 
+/// Get Windows `$LogFile` data
+pub(crate) fn logfile(
+    options: &LogFileOptions,
+    manager: &mut OutputManager,
+) -> Result<(), WinArtifactError> {
+    if let Err(err) = grab_logfile(options, manager) {
+        error!("Artemis failed to parse $LogFile data: {err:?}");
+        return Err(WinArtifactError::LogFile);
+    }
+
+    Ok(())
+}
+
+/// Get Windows `hiberfil.sys` data
+pub(crate) fn hiberfil(
+    options: &HiberfilOptions,
+    manager: &mut OutputManager,
+) -> Result<(), WinArtifactError> {
+    if let Err(err) = grab_hiberfil(options, manager) {
+        error!("Artemis failed to parse hiberfil.sys: {err:?}");
+        return Err(WinArtifactError::Hiberfil);
+    }
+
+    Ok(())
+}
+
+/// Get Windows `pagefile.sys` and `swapfile.sys` data
+pub(crate) fn pagefile(
+    options: &PagefileOptions,
+    manager: &mut OutputManager,
+) -> Result<(), WinArtifactError> {
+    if let Err(err) = grab_pagefile(options, manager) {
+        error!("Artemis failed to parse pagefile: {err:?}");
+        return Err(WinArtifactError::Pagefile);
+    }
+
+    Ok(())
+}
+
+/// Get Windows Timeline `ActivitiesCache.db` data
+pub(crate) fn activitiescache(
+    options: &ActivitiesCacheOptions,
+    manager: &mut OutputManager,
+) -> Result<(), WinArtifactError> {
+    if let Err(err) = grab_activitiescache(options, manager) {
+        error!("Artemis failed to parse ActivitiesCache: {err:?}");
+        return Err(WinArtifactError::ActivitiesCache);
+    }
+
+    Ok(())
+}
+
 /// Get Windows `Bits` data
 pub(crate) fn bits(
     options: &BitsOptions,
@@ -603,17 +657,17 @@ mod tests {
     use crate::structs::toml::{OutputConfig, OutputDestination, OutputFormat};
     use crate::{
         artifacts::os::windows::artifacts::{
-            amcache, bits, eventlogs, jumplists, mft, prefetch, raw_filelist, recycle_bin,
+            amcache, bits, eventlogs, jumplists, logfile, mft, prefetch, raw_filelist, recycle_bin,
             registry, search, services, shellbags, shimcache, shimdb, shortcuts, srum, tasks,
             userassist, users_windows, usnjrnl, wmi_persist,
         },
         output::manager::OutputManager,
         structs::artifacts::os::windows::{
-            AmcacheOptions, BitsOptions, EventLogsOptions, JumplistsOptions, MftOptions,
-            PrefetchOptions, RawFilesOptions, RecycleBinOptions, RegistryOptions, SearchOptions,
-            ServicesOptions, ShellbagsOptions, ShimcacheOptions, ShimdbOptions, ShortcutOptions,
-            SrumOptions, TasksOptions, UserAssistOptions, UsnJrnlOptions, WindowsUserOptions,
-            WmiPersistOptions,
+            AmcacheOptions, BitsOptions, EventLogsOptions, JumplistsOptions, LogFileOptions,
+            MftOptions, PrefetchOptions, RawFilesOptions, RecycleBinOptions, RegistryOptions,
+            SearchOptions, ServicesOptions, ShellbagsOptions, ShimcacheOptions, ShimdbOptions,
+            ShortcutOptions, SrumOptions, TasksOptions, UserAssistOptions, UsnJrnlOptions,
+            WindowsUserOptions, WmiPersistOptions,
         },
     };
     use std::path::PathBuf;
@@ -639,7 +693,12 @@ mod tests {
             dump_templates: false,
             include_templates: false,
             alt_template_file: None,
+            alt_template_image: None,
+            filter: None,
+            carve: None,
             only_templates: false,
+            sigma_dir: None,
+            sigma_rules: None,
         };
         let mut output = output_options("eventlogs_temp", "./tmp", true);
 
@@ -672,6 +731,8 @@ mod tests {
             system_hives: true,
             path_regex: None,
             alt_file: None,
+            recover_deleted: None,
+            recover_orphans: None,
         };
         let mut output = output_options("reg_temp", "./tmp", true);
 
@@ -748,6 +809,7 @@ mod tests {
             alt_drive: None,
             alt_file: None,
             alt_mft: None,
+            carve: None,
         };
         let mut output = output_options("usn_temp", "./tmp", false);
 
@@ -755,6 +817,19 @@ mod tests {
         assert_eq!(status, ());
     }
 
+    #[test]
+    fn test_logfile() {
+        let options = LogFileOptions {
+            alt_drive: None,
+            alt_file: None,
+            alt_mft: None,
+        };
+        let mut output = output_options("logfile_temp", "./tmp", false);
+
+        let status = logfile(&options, &mut output).unwrap();
+        assert_eq!(status, ());
+    }
+
     #[test]
     fn test_shortcuts() {
         let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -783,7 +858,11 @@ mod tests {
 
     #[test]
     fn test_srum() {
-        let options = SrumOptions { alt_file: None };
+        let options = SrumOptions {
+            alt_file: None,
+            recover_deleted: None,
+            recover_orphans: None,
+        };
         let mut output = output_options("srum_temp", "./tmp", false);
 
         let status = srum(&options, &mut output).unwrap();
@@ -793,7 +872,11 @@ mod tests {
     #[test]
     #[ignore = "Takes a long time"]
     fn test_search() {
-        let options = SearchOptions { alt_file: None };
+        let options = SearchOptions {
+            alt_file: None,
+            recover_deleted: None,
+            recover_orphans: None,
+        };
         let mut output = output_options("search_temp", "./tmp", false);
 
         let status = search(&options, &mut output).unwrap();
@@ -860,6 +943,7 @@ mod tests {
         let options = MftOptions {
             alt_drive: None,
             alt_file: None,
+            carve: None,
         };
         let mut output = output_options("mft_temp", "./tmp", false);
 
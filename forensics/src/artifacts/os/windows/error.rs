@@ -15,6 +15,10 @@ pub(crate) enum WinArtifactError {
     Amcache,
     Shortcuts,
     UsnJrnl,
+    LogFile,
+    Hiberfil,
+    Pagefile,
+    ActivitiesCache,
     Bits,
     Srum,
     Users,
@@ -46,6 +50,10 @@ impl fmt::Display for WinArtifactError {
             WinArtifactError::Amcache => write!(f, "Failed to parse Amcache"),
             WinArtifactError::Shortcuts => write!(f, "Failed to parse Shortcuts"),
             WinArtifactError::UsnJrnl => write!(f, "Failed to parse UsnJrnl"),
+            WinArtifactError::LogFile => write!(f, "Failed to parse LogFile"),
+            WinArtifactError::Hiberfil => write!(f, "Failed to parse hiberfil.sys"),
+            WinArtifactError::Pagefile => write!(f, "Failed to parse pagefile"),
+            WinArtifactError::ActivitiesCache => write!(f, "Failed to parse ActivitiesCache"),
             WinArtifactError::Bits => write!(f, "Failed to parse Bits"),
             WinArtifactError::Srum => write!(f, "Failed to parse SRUM"),
             WinArtifactError::Search => write!(f, "Failed to parse Search"),
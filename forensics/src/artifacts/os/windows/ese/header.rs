@@ -17,7 +17,7 @@ pub(crate) struct EseHeader {
     file_type: FileType,
     database_time: String,
     database_signature: DatabaseSig,
-    database_state: DatabaseState,
+    pub(crate) database_state: DatabaseState,
     consistent_position: LogPosition,
     consistent_date_time: String,
     attach_date_time: String,
@@ -25,7 +25,7 @@ pub(crate) struct EseHeader {
     detach_date_time: String,
     detach_position: LogPosition,
     dbid: u32,
-    log_signature: DatabaseSig,
+    pub(crate) log_signature: DatabaseSig,
     previous_full_backup: BackupInfo,
     previous_incremental_backup: BackupInfo,
     current_full_backup: BackupInfo,
@@ -42,7 +42,7 @@ pub(crate) struct EseHeader {
     unknown: u32,
     scrub_database_time: String,
     scrub_date_time: String,
-    required_log: u32,
+    pub(crate) required_log: u32,
     required_log2: u32,
     upgrade_exchange_format: u32,
     upgrade_free_pages: u32,
@@ -76,14 +76,14 @@ enum FileType {
 }
 
 #[derive(Debug, PartialEq)]
-struct DatabaseSig {
-    random_number: u32,
-    creation_date_time: String,
+pub(crate) struct DatabaseSig {
+    pub(crate) random_number: u32,
+    pub(crate) creation_date_time: String,
     netbios_computer_name: String,
 }
 
 #[derive(Debug, PartialEq)]
-enum DatabaseState {
+pub(crate) enum DatabaseState {
     JustCreated,
     DirtyShutdown,
     CleanShutdown,
@@ -279,7 +279,7 @@ impl EseHeader {
     }
 
     /// Parse the structure of the database signature
-    fn get_database_sig(data: &[u8]) -> nom::IResult<&[u8], DatabaseSig> {
+    pub(crate) fn get_database_sig(data: &[u8]) -> nom::IResult<&[u8], DatabaseSig> {
         let (input, random_number) = nom_unsigned_four_bytes(data, Endian::Le)?;
         let (input, creation_date_time) = EseHeader::get_log_time(input)?;
         let (input, netbios_data) = take(size_of::<u128>())(input)?;
@@ -330,7 +330,7 @@ impl EseHeader {
      * The Year starts from 1900. Ex: A year value of zero (0) is 1900
      * Last two (2) bytes are filler
      */
-    fn get_log_time(data: &[u8]) -> nom::IResult<&[u8], String> {
+    pub(crate) fn get_log_time(data: &[u8]) -> nom::IResult<&[u8], String> {
         let (input, seconds) = nom_unsigned_one_byte(data, Endian::Le)?;
         let (input, mins) = nom_unsigned_one_byte(input, Endian::Le)?;
         let (input, hours) = nom_unsigned_one_byte(input, Endian::Le)?;
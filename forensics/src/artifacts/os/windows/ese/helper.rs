@@ -18,8 +18,10 @@ use super::{
     catalog::Catalog,
     error::EseError,
     header::EseHeader,
+    logs::replay::{ReplayReader, replay_logs},
     page::{PageFlags, PageHeader},
     pages::{longvalue::parse_long_value, root::parse_root_page},
+    recover::recover_page_rows,
     tables::{ColumnInfo, TableInfo, create_table_data},
 };
 use crate::{
@@ -45,7 +47,11 @@ use crate::{
 };
 use common::windows::{ColumnType, TableDump};
 use ntfs::{Ntfs, NtfsFile};
-use std::{collections::HashMap, fs::File, io::BufReader};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{BufReader, Seek, SeekFrom},
+};
 use tracing::error;
 
 /// Get `Catalog` data from provided ESE path
@@ -57,8 +63,7 @@ pub(crate) fn get_catalog_info(path: &str) -> Result<Vec<Catalog>, EseError> {
         let reader = setup_ese_reader(path)?;
         let mut buf_reader = BufReader::new(reader);
 
-        let page_size = ese_page_size(None, &mut buf_reader)?;
-        Catalog::grab_catalog(None, &mut buf_reader, page_size)?
+        catalog_info(path, None, &mut buf_reader)?
     } else {
         // On Windows use a NTFS reader
         let ntfs_parser_result = setup_ntfs_parser(path.chars().next().unwrap_or('C'));
@@ -71,8 +76,7 @@ pub(crate) fn get_catalog_info(path: &str) -> Result<Vec<Catalog>, EseError> {
         };
         let ntfs_file = setup_ese_reader_windows(&ntfs_parser.ntfs, &mut ntfs_parser.fs, path)?;
 
-        let page_size = ese_page_size(Some(&ntfs_file), &mut ntfs_parser.fs)?;
-        Catalog::grab_catalog(Some(&ntfs_file), &mut ntfs_parser.fs, page_size)?
+        catalog_info(path, Some(&ntfs_file), &mut ntfs_parser.fs)?
     };
 
     Ok(catalog)
@@ -86,13 +90,11 @@ pub(crate) fn get_all_pages(path: &str, first_page: u32) -> Result<Vec<u32>, Ese
         let reader = setup_ese_reader(path)?;
         let mut buf_reader = BufReader::new(reader);
 
-        let page_size = ese_page_size(None, &mut buf_reader)?;
-        get_pages(first_page, None, &mut buf_reader, page_size)?
+        table_pages(path, first_page, None, &mut buf_reader)?
     } else {
         let mut ntfs_parser = setup_ntfs_parser(path.chars().next().unwrap_or('C')).unwrap();
         let ntfs_file = setup_ese_reader_windows(&ntfs_parser.ntfs, &mut ntfs_parser.fs, path)?;
-        let page_size = ese_page_size(Some(&ntfs_file), &mut ntfs_parser.fs)?;
-        get_pages(first_page, Some(&ntfs_file), &mut ntfs_parser.fs, page_size)?
+        table_pages(path, first_page, Some(&ntfs_file), &mut ntfs_parser.fs)?
     };
 
     Ok(pages)
@@ -109,48 +111,26 @@ pub(crate) fn get_page_data(
     let mut total_rows = HashMap::new();
     total_rows.insert(name.to_string(), Vec::new());
 
-    let page_size;
     let last_page = 0;
+    let pages: Vec<u32> = pages
+        .iter()
+        .filter(|page| **page != last_page)
+        .copied()
+        .collect();
     let mut rows = if plat != "Windows" {
         let reader = setup_ese_reader(path)?;
         let mut buf_reader = BufReader::new(reader);
 
-        page_size = ese_page_size(None, &mut buf_reader)?;
-        let mut rows = Vec::new();
-        for page in pages {
-            if page == &last_page {
-                continue;
-            }
-            let mut page_rows = page_data(*page, None, &mut buf_reader, page_size, info)?;
-            rows.append(&mut page_rows);
-        }
-        row_data(&mut rows, None, &mut buf_reader, page_size, info, name)?
+        table_rows(path, &pages, None, &mut buf_reader, info, name)?
     } else {
         let mut ntfs_parser = setup_ntfs_parser(path.chars().next().unwrap_or('C')).unwrap();
         let ntfs_file = setup_ese_reader_windows(&ntfs_parser.ntfs, &mut ntfs_parser.fs, path)?;
 
-        page_size = ese_page_size(Some(&ntfs_file), &mut ntfs_parser.fs)?;
-        let mut rows = Vec::new();
-
-        for page in pages {
-            if page == &last_page {
-                continue;
-            }
-            let mut page_rows = page_data(
-                *page,
-                Some(&ntfs_file),
-                &mut ntfs_parser.fs,
-                page_size,
-                info,
-            )?;
-
-            rows.append(&mut page_rows);
-        }
-        row_data(
-            &mut rows,
+        table_rows(
+            path,
+            &pages,
             Some(&ntfs_file),
             &mut ntfs_parser.fs,
-            page_size,
             info,
             name,
         )?
@@ -179,42 +159,21 @@ pub(crate) fn get_filtered_page_data(
     let mut total_rows = HashMap::new();
     total_rows.insert(name.to_string(), Vec::new());
 
-    let page_size;
     let rows = if plat != "Windows" {
         let reader = setup_ese_reader(path)?;
         let mut buf_reader = BufReader::new(reader);
 
-        page_size = ese_page_size(None, &mut buf_reader)?;
-        let mut rows = Vec::new();
-        for page in pages {
-            let mut page_rows = page_data(*page, None, &mut buf_reader, page_size, info)?;
-            rows.append(&mut page_rows);
-        }
-        row_data(&mut rows, None, &mut buf_reader, page_size, info, name)?
+        table_rows(path, pages, None, &mut buf_reader, info, name)?
     } else {
         // On Windows use a NTFS reader
         let mut ntfs_parser = setup_ntfs_parser(path.chars().next().unwrap_or('C')).unwrap();
         let ntfs_file = setup_ese_reader_windows(&ntfs_parser.ntfs, &mut ntfs_parser.fs, path)?;
 
-        page_size = ese_page_size(Some(&ntfs_file), &mut ntfs_parser.fs)?;
-        let mut rows = Vec::new();
-
-        for page in pages {
-            let mut page_rows = page_data(
-                *page,
-                Some(&ntfs_file),
-                &mut ntfs_parser.fs,
-                page_size,
-                info,
-            )?;
-
-            rows.append(&mut page_rows);
-        }
-        row_data(
-            &mut rows,
+        table_rows(
+            path,
+            pages,
             Some(&ntfs_file),
             &mut ntfs_parser.fs,
-            page_size,
             info,
             name,
         )?
@@ -259,41 +218,20 @@ pub(crate) fn dump_table_columns(
     let mut total_rows = HashMap::new();
     total_rows.insert(name.to_string(), Vec::new());
 
-    let page_size;
     let rows = if plat != "Windows" {
         let reader = setup_ese_reader(path)?;
         let mut buf_reader = BufReader::new(reader);
 
-        page_size = ese_page_size(None, &mut buf_reader)?;
-        let mut rows = Vec::new();
-        for page in pages {
-            let mut page_rows = page_data(*page, None, &mut buf_reader, page_size, info)?;
-            rows.append(&mut page_rows);
-        }
-        row_data(&mut rows, None, &mut buf_reader, page_size, info, name)?
+        table_rows(path, pages, None, &mut buf_reader, info, name)?
     } else {
         let mut ntfs_parser = setup_ntfs_parser(path.chars().next().unwrap_or('C')).unwrap();
         let ntfs_file = setup_ese_reader_windows(&ntfs_parser.ntfs, &mut ntfs_parser.fs, path)?;
 
-        page_size = ese_page_size(Some(&ntfs_file), &mut ntfs_parser.fs)?;
-        let mut rows = Vec::new();
-
-        for page in pages {
-            let mut page_rows = page_data(
-                *page,
-                Some(&ntfs_file),
-                &mut ntfs_parser.fs,
-                page_size,
-                info,
-            )?;
-
-            rows.append(&mut page_rows);
-        }
-        row_data(
-            &mut rows,
+        table_rows(
+            path,
+            pages,
             Some(&ntfs_file),
             &mut ntfs_parser.fs,
-            page_size,
             info,
             name,
         )?
@@ -319,6 +257,194 @@ pub(crate) fn dump_table_columns(
     Ok(total_rows)
 }
 
+/// Get the last transaction log generation replayed onto a dirty ESE database. Returns None if no logs were replayed
+pub(crate) fn get_log_generation(path: &str) -> Result<Option<u32>, EseError> {
+    let plat = get_platform();
+
+    let replay = if plat != "Windows" {
+        let reader = setup_ese_reader(path)?;
+        let mut buf_reader = BufReader::new(reader);
+
+        replay_logs(path, None, &mut buf_reader)
+    } else {
+        let mut ntfs_parser = setup_ntfs_parser(path.chars().next().unwrap_or('C')).unwrap();
+        let ntfs_file = setup_ese_reader_windows(&ntfs_parser.ntfs, &mut ntfs_parser.fs, path)?;
+
+        replay_logs(path, Some(&ntfs_file), &mut ntfs_parser.fs)
+    };
+
+    Ok(replay.map(|value| value.generation))
+}
+
+/// Get deleted rows from table based on array of pages. Pages should contain all of the table pages, any other page that belongs to the table is treated as an orphan page
+pub(crate) fn get_deleted_page_data(
+    path: &str,
+    pages: &[u32],
+    info: &mut TableInfo,
+    name: &str,
+) -> Result<HashMap<String, Vec<Vec<TableDump>>>, EseError> {
+    let plat = get_platform();
+
+    let last_page = 0;
+    let pages: Vec<u32> = pages
+        .iter()
+        .filter(|page| **page != last_page)
+        .copied()
+        .collect();
+    if plat != "Windows" {
+        let reader = setup_ese_reader(path)?;
+        let mut buf_reader = BufReader::new(reader);
+
+        deleted_rows(path, &pages, None, &mut buf_reader, info, name)
+    } else {
+        let mut ntfs_parser = setup_ntfs_parser(path.chars().next().unwrap_or('C')).unwrap();
+        let ntfs_file = setup_ese_reader_windows(&ntfs_parser.ntfs, &mut ntfs_parser.fs, path)?;
+
+        deleted_rows(
+            path,
+            &pages,
+            Some(&ntfs_file),
+            &mut ntfs_parser.fs,
+            info,
+            name,
+        )
+    }
+}
+
+/// Get the `Catalog` data. Dirty databases are read using the pages recovered from the transaction logs
+fn catalog_info<T: std::io::Seek + std::io::Read>(
+    path: &str,
+    ntfs_file: Option<&NtfsFile<'_>>,
+    fs: &mut BufReader<T>,
+) -> Result<Vec<Catalog>, EseError> {
+    let replay = replay_logs(path, ntfs_file, fs);
+    let mut reader = BufReader::new(ReplayReader::new(ntfs_file, fs, replay.as_deref()));
+
+    let page_size = ese_page_size(None, &mut reader)?;
+    Catalog::grab_catalog(None, &mut reader, page_size)
+}
+
+/// Get the table pages. Dirty databases are read using the pages recovered from the transaction logs
+fn table_pages<T: std::io::Seek + std::io::Read>(
+    path: &str,
+    first_page: u32,
+    ntfs_file: Option<&NtfsFile<'_>>,
+    fs: &mut BufReader<T>,
+) -> Result<Vec<u32>, EseError> {
+    let replay = replay_logs(path, ntfs_file, fs);
+    let mut reader = BufReader::new(ReplayReader::new(ntfs_file, fs, replay.as_deref()));
+
+    let page_size = ese_page_size(None, &mut reader)?;
+    get_pages(first_page, None, &mut reader, page_size)
+}
+
+/// Get the table rows from the pages. Dirty databases are read using the pages recovered from the transaction logs
+fn table_rows<T: std::io::Seek + std::io::Read>(
+    path: &str,
+    pages: &[u32],
+    ntfs_file: Option<&NtfsFile<'_>>,
+    fs: &mut BufReader<T>,
+    info: &mut TableInfo,
+    name: &str,
+) -> Result<HashMap<String, Vec<Vec<TableDump>>>, EseError> {
+    let replay = replay_logs(path, ntfs_file, fs);
+    let mut reader = BufReader::new(ReplayReader::new(ntfs_file, fs, replay.as_deref()));
+
+    let page_size = ese_page_size(None, &mut reader)?;
+    let mut rows = Vec::new();
+    for page in pages {
+        let mut page_rows = page_data(*page, None, &mut reader, page_size, info)?;
+        rows.append(&mut page_rows);
+    }
+    row_data(&mut rows, None, &mut reader, page_size, info, name)
+}
+
+/// Carve deleted rows from the table pages and from orphan pages that still belong to the table
+fn deleted_rows<T: std::io::Seek + std::io::Read>(
+    path: &str,
+    pages: &[u32],
+    ntfs_file: Option<&NtfsFile<'_>>,
+    fs: &mut BufReader<T>,
+    info: &mut TableInfo,
+    name: &str,
+) -> Result<HashMap<String, Vec<Vec<TableDump>>>, EseError> {
+    let file_size = ese_file_size(ntfs_file, fs)?;
+    let replay = replay_logs(path, ntfs_file, fs);
+    let mut reader = BufReader::new(ReplayReader::new(ntfs_file, fs, replay.as_deref()));
+
+    let page_size = ese_page_size(None, &mut reader)?;
+
+    // Remnants that are identical to a live row are old copies of the row (ex: the page was reorganized)
+    let mut seen_rows = HashSet::new();
+    for page in pages {
+        for row in page_data(*page, None, &mut reader, page_size, info)? {
+            seen_rows.insert(
+                row.into_iter()
+                    .map(|column| column.column_data)
+                    .collect::<Vec<Vec<u8>>>(),
+            );
+        }
+    }
+
+    // Need to adjust page number to account for header page
+    let adjust_page = 1;
+    let total_pages = (file_size / page_size as u64).saturating_sub(adjust_page) as u32;
+
+    let mut rows = Vec::new();
+    for page in 1..total_pages {
+        let page_number = (page + adjust_page as u32) as u64 * page_size as u64;
+        let page_result = read_bytes(page_number, page_size as u64, None, &mut reader);
+        let page_start = match page_result {
+            Ok(result) => result,
+            Err(err) => {
+                error!("Failed to read bytes for page {page}: {err:?}");
+                return Err(EseError::ParseEse);
+            }
+        };
+
+        let orphan = !pages.contains(&page);
+        for row in recover_page_rows(&page_start, info, orphan) {
+            let values = row
+                .iter()
+                .map(|column| column.column_data.clone())
+                .collect::<Vec<Vec<u8>>>();
+            if seen_rows.insert(values) {
+                rows.push(row);
+            }
+        }
+    }
+
+    row_data(&mut rows, None, &mut reader, page_size, info, name)
+}
+
+/// Get the size of the ESE database
+fn ese_file_size<T: std::io::Seek + std::io::Read>(
+    ntfs_file: Option<&NtfsFile<'_>>,
+    fs: &mut BufReader<T>,
+) -> Result<u64, EseError> {
+    let Some(file) = ntfs_file else {
+        return match fs.seek(SeekFrom::End(0)) {
+            Ok(result) => Ok(result),
+            Err(err) => {
+                error!("Could not determine ESE file size: {err:?}");
+                Err(EseError::ReadFile)
+            }
+        };
+    };
+
+    let Some(data_result) = file.data(fs, "") else {
+        error!("No data attribute for ESE file");
+        return Err(EseError::ReadFile);
+    };
+    match data_result.and_then(|data| Ok(data.to_attribute()?.value_length())) {
+        Ok(result) => Ok(result),
+        Err(err) => {
+            error!("Could not determine ESE file size: {err:?}");
+            Err(EseError::ReadFile)
+        }
+    }
+}
+
 /// Setup Windows ESE reader using NTFS parser
 fn setup_ese_reader_windows<'a>(
     ntfs_file: &'a Ntfs,
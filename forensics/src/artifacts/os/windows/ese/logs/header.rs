@@ -0,0 +1,104 @@
+use crate::{
+    artifacts::os::windows::ese::header::{DatabaseSig, EseHeader},
+    utils::nom_helper::{Endian, nom_unsigned_four_bytes, nom_unsigned_two_bytes},
+};
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct LogHeader {
+    checksum: u32,
+    pub(crate) generation: u32,
+    pub(crate) sector_size: u16,
+    pub(crate) header_sectors: u16,
+    file_sectors: u16,
+    page_size: u16,
+    creation: String,
+    previous_generation_creation: String,
+    major_version: u32,
+    minor_version: u32,
+    update_version: u32,
+    pub(crate) signature: DatabaseSig,
+}
+
+impl LogHeader {
+    /// Parse the header of an `ESE` transaction log (edb.log, edb00001.log, edbres00001.jrs)
+    pub(crate) fn parse_header(data: &[u8]) -> nom::IResult<&[u8], LogHeader> {
+        let (input, checksum) = nom_unsigned_four_bytes(data, Endian::Le)?;
+        let (input, generation) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, sector_size) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, header_sectors) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, file_sectors) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, page_size) = nom_unsigned_two_bytes(input, Endian::Le)?;
+
+        let (input, creation) = EseHeader::get_log_time(input)?;
+        let (input, previous_generation_creation) = EseHeader::get_log_time(input)?;
+        let (input, major_version) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, minor_version) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, update_version) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, signature) = EseHeader::get_database_sig(input)?;
+
+        let header = LogHeader {
+            checksum,
+            generation,
+            sector_size,
+            header_sectors,
+            file_sectors,
+            page_size,
+            creation,
+            previous_generation_creation,
+            major_version,
+            minor_version,
+            update_version,
+            signature,
+        };
+
+        Ok((input, header))
+    }
+
+    /// Offset to the start of the log records
+    pub(crate) fn records_offset(&self) -> usize {
+        self.header_sectors as usize * self.sector_size as usize
+    }
+
+    /// Check if the log belongs to the database. The log signature in the database header must match the log header signature
+    pub(crate) fn is_database_log(&self, database: &EseHeader) -> bool {
+        self.signature.random_number == database.log_signature.random_number
+            && self.signature.creation_date_time == database.log_signature.creation_date_time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LogHeader;
+    use crate::{artifacts::os::windows::ese::header::EseHeader, filesystem::files::read_file};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_parse_header() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/ese/dirty/edb00001.log");
+        let data = read_file(test_location.to_str().unwrap()).unwrap();
+
+        let (_, header) = LogHeader::parse_header(&data).unwrap();
+        assert_eq!(header.generation, 1);
+        assert_eq!(header.sector_size, 512);
+        assert_eq!(header.header_sectors, 8);
+        assert_eq!(header.records_offset(), 4096);
+        assert_eq!(header.signature.random_number, 3571977245);
+        assert_eq!(header.creation, "2024-3-1 3:30:0 UTC");
+    }
+
+    #[test]
+    fn test_is_database_log() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/ese/dirty/edb00001.log");
+        let data = read_file(test_location.to_str().unwrap()).unwrap();
+        let (_, header) = LogHeader::parse_header(&data).unwrap();
+
+        test_location.pop();
+        test_location.push("qmgr.db");
+        let data = read_file(test_location.to_str().unwrap()).unwrap();
+        let (_, db_header) = EseHeader::parse_header(&data).unwrap();
+
+        assert!(header.is_database_log(&db_header));
+    }
+}
@@ -0,0 +1,4 @@
+mod header;
+mod records;
+mod redo;
+pub(crate) mod replay;
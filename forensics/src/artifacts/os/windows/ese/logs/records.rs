@@ -0,0 +1,231 @@
+use crate::utils::nom_helper::{
+    Endian, nom_data, nom_unsigned_eight_bytes, nom_unsigned_four_bytes, nom_unsigned_one_byte,
+    nom_unsigned_two_bytes,
+};
+use nom::bytes::complete::take;
+use tracing::warn;
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct PageRecord {
+    pub(crate) operation: Operation,
+    pub(crate) page: u32,
+    pub(crate) dbtime_before: u64,
+    pub(crate) dbtime_after: u64,
+    /// Index of the page tag (node) that is modified
+    pub(crate) line: u16,
+    /// Tag flags for inserted nodes on pages smaller than 16KB
+    pub(crate) flags: u16,
+    pub(crate) data: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum Operation {
+    Insert,
+    FlagInsert,
+    FlagInsertAndReplaceData,
+    FlagDelete,
+    Replace,
+    /// Replace with delta compressed data. Cannot be replayed without the original node data
+    ReplaceDelta,
+    Delete,
+}
+
+/// Log data is stored in segments after the log header. Every segment starts with a small header
+const SEGMENT_SIZE: usize = 4096;
+const SEGMENT_HEADER_SIZE: usize = 32;
+
+/**
+ * Get the page level log records from the log data. The log header must already be removed
+ * Log files are recycled by `ESE`, segments from an older generation mark the end of the valid log data
+ */
+pub(crate) fn parse_log_records(data: &[u8], generation: u32) -> Vec<PageRecord> {
+    let mut stream = Vec::new();
+    for segment in data.chunks(SEGMENT_SIZE) {
+        if segment.len() < SEGMENT_HEADER_SIZE {
+            break;
+        }
+        let Ok((_, segment_generation)) = segment_generation(segment) else {
+            break;
+        };
+        if segment_generation != generation {
+            break;
+        }
+        stream.extend_from_slice(&segment[SEGMENT_HEADER_SIZE..]);
+    }
+
+    let mut records = Vec::new();
+    let mut input = stream.as_slice();
+    while !input.is_empty() {
+        match parse_record(input) {
+            Ok((remaining, record)) => {
+                if let Some(page_record) = record {
+                    records.push(page_record);
+                }
+                input = remaining;
+            }
+            Err(_err) => {
+                warn!(
+                    "[ese] Could not parse log record type {} in generation {generation}. Stopping log parsing",
+                    input[0]
+                );
+                break;
+            }
+        }
+    }
+
+    records
+}
+
+/// Get the log generation of the segment. Segment header: checksum (8 bytes), log position (8 bytes), log time (8 bytes), reserved (8 bytes)
+fn segment_generation(data: &[u8]) -> nom::IResult<&[u8], u32> {
+    let (input, _checksum) = nom_unsigned_eight_bytes(data, Endian::Le)?;
+    let (input, _block) = nom_unsigned_two_bytes(input, Endian::Le)?;
+    let (input, _sector) = nom_unsigned_two_bytes(input, Endian::Le)?;
+    nom_unsigned_four_bytes(input, Endian::Le)
+}
+
+/// Parse a single log record. Only records that modify database pages are returned
+fn parse_record(data: &[u8]) -> nom::IResult<&[u8], Option<PageRecord>> {
+    let (input, record_type) = nom_unsigned_one_byte(data, Endian::Le)?;
+
+    let nop = 0;
+    let begin = 8;
+    let commit = 9;
+    let refresh = 10;
+
+    let insert = 29;
+    let flag_insert = 30;
+    let flag_insert_replace = 31;
+    let flag_delete = 32;
+    let replace = 33;
+    let replace_delta = 34;
+    let delete = 35;
+
+    // Transaction records are not needed to redo page changes. Record size includes the record type
+    let skip_size: usize = if record_type == nop {
+        1
+    } else if record_type == begin {
+        9
+    } else if record_type == commit || record_type == refresh {
+        8
+    } else {
+        0
+    };
+    if skip_size != 0 {
+        let (input, _) = take(skip_size - 1)(input)?;
+        return Ok((input, None));
+    }
+
+    let operation = if record_type == insert {
+        Operation::Insert
+    } else if record_type == flag_insert {
+        Operation::FlagInsert
+    } else if record_type == flag_insert_replace {
+        Operation::FlagInsertAndReplaceData
+    } else if record_type == flag_delete {
+        Operation::FlagDelete
+    } else if record_type == replace {
+        Operation::Replace
+    } else if record_type == replace_delta {
+        Operation::ReplaceDelta
+    } else if record_type == delete {
+        Operation::Delete
+    } else {
+        return Err(nom::Err::Failure(nom::error::Error::new(
+            data,
+            nom::error::ErrorKind::Fail,
+        )));
+    };
+
+    let (input, _procid) = nom_unsigned_two_bytes(input, Endian::Le)?;
+    let (input, _dbid) = nom_unsigned_one_byte(input, Endian::Le)?;
+    let (input, page) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, _object_id) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, dbtime_before) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+    let (input, dbtime_after) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+    let (mut input, line) = nom_unsigned_two_bytes(input, Endian::Le)?;
+
+    let mut record = PageRecord {
+        operation,
+        page,
+        dbtime_before,
+        dbtime_after,
+        line,
+        flags: 0,
+        data: Vec::new(),
+    };
+
+    match record.operation {
+        Operation::Insert => {
+            let (remaining, flags) = nom_unsigned_two_bytes(input, Endian::Le)?;
+            record.flags = flags;
+            input = remaining;
+        }
+        Operation::FlagInsert | Operation::FlagDelete | Operation::Delete => {
+            return Ok((input, Some(record)));
+        }
+        _ => {}
+    }
+
+    let (input, size) = nom_unsigned_two_bytes(input, Endian::Le)?;
+    let (input, data) = nom_data(input, size as u64)?;
+    record.data = data.to_vec();
+
+    Ok((input, Some(record)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Operation, parse_log_records, parse_record};
+    use crate::filesystem::files::read_file;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_parse_log_records() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/ese/dirty/edb00001.log");
+        let data = read_file(test_location.to_str().unwrap()).unwrap();
+
+        let records = parse_log_records(&data[4096..], 1);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].operation, Operation::Replace);
+        assert_eq!(records[0].page, 32);
+        assert_eq!(records[0].dbtime_before, 0x3769);
+        assert_eq!(records[0].data.len(), 53);
+        assert_eq!(records[1].operation, Operation::Insert);
+        assert_eq!(records[1].page, 31);
+        assert_eq!(records[1].line, 2);
+    }
+
+    #[test]
+    fn test_parse_log_records_wrong_generation() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/ese/dirty/edb00001.log");
+        let data = read_file(test_location.to_str().unwrap()).unwrap();
+
+        let records = parse_log_records(&data[4096..], 5);
+        assert!(records.is_empty());
+    }
+
+    #[test]
+    fn test_parse_record() {
+        let test = [
+            32, 1, 0, 1, 10, 0, 0, 0, 2, 0, 0, 0, 5, 0, 0, 0, 0, 0, 0, 0, 6, 0, 0, 0, 0, 0, 0, 0,
+            3, 0, 0,
+        ];
+        let (remaining, record) = parse_record(&test).unwrap();
+        let record = record.unwrap();
+        assert_eq!(record.operation, Operation::FlagDelete);
+        assert_eq!(record.page, 10);
+        assert_eq!(record.dbtime_before, 5);
+        assert_eq!(record.dbtime_after, 6);
+        assert_eq!(record.line, 3);
+        assert_eq!(remaining, [0]);
+
+        let (remaining, record) = parse_record(remaining).unwrap();
+        assert!(record.is_none());
+        assert!(remaining.is_empty());
+
+        assert!(parse_record(&[200, 0, 0]).is_err());
+    }
+}
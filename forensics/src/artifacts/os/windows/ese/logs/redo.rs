@@ -0,0 +1,355 @@
+use super::records::{Operation, PageRecord};
+use crate::utils::nom_helper::{Endian, nom_unsigned_eight_bytes};
+
+/// Offsets of the page header values that are updated when redoing a log record
+const DBTIME_OFFSET: usize = 8;
+const AVAILABLE_SIZE_OFFSET: usize = 28;
+const FIRST_AVAILABLE_DATA_OFFSET: usize = 32;
+const FIRST_AVAILABLE_TAG_OFFSET: usize = 34;
+const TAG_SIZE: usize = 4;
+/// Tag flag marking the node as deleted
+const DEFUNCT: u16 = 0x4000;
+
+struct PageLayout {
+    size: usize,
+    header_size: usize,
+    large: bool,
+    tags: usize,
+}
+
+/**
+ * Redo a log record onto the page. The record is only applied if the page `dbtime` matches the record `dbtime_before`
+ * This guarantees records are applied in order and never twice. Returns true if the page was modified
+ */
+pub(crate) fn redo_page(page: &mut Vec<u8>, record: &PageRecord) -> bool {
+    let Ok((_, dbtime)) =
+        nom_unsigned_eight_bytes(page.get(DBTIME_OFFSET..).unwrap_or_default(), Endian::Le)
+    else {
+        return false;
+    };
+    if dbtime != record.dbtime_before {
+        return false;
+    }
+
+    let mut updated = page.clone();
+    let layout = page_layout(&updated);
+    if layout.header_size + layout.tags * TAG_SIZE > layout.size {
+        return false;
+    }
+    let line = record.line as usize;
+
+    let success = match record.operation {
+        Operation::Insert => insert_node(&mut updated, &layout, line, record.flags, &record.data),
+        Operation::Replace => replace_node(&mut updated, &layout, line, &record.data),
+        Operation::FlagInsert => set_defunct(&mut updated, &layout, line, false),
+        Operation::FlagInsertAndReplaceData => {
+            replace_node(&mut updated, &layout, line, &record.data)
+                && set_defunct(&mut updated, &layout, line, false)
+        }
+        Operation::FlagDelete => set_defunct(&mut updated, &layout, line, true),
+        Operation::Delete => delete_node(&mut updated, &layout, line),
+        Operation::ReplaceDelta => false,
+    };
+    if !success {
+        return false;
+    }
+
+    updated[DBTIME_OFFSET..DBTIME_OFFSET + 8].copy_from_slice(&record.dbtime_after.to_le_bytes());
+    *page = updated;
+    true
+}
+
+fn page_layout(page: &[u8]) -> PageLayout {
+    let page_16k = 16384;
+    let large = page.len() >= page_16k;
+    // Only the lower 12 bits contain the tag count
+    let tags = (read_u16(page, FIRST_AVAILABLE_TAG_OFFSET) & 0xfff) as usize;
+
+    PageLayout {
+        size: page.len(),
+        header_size: if large { 80 } else { 40 },
+        large,
+        tags,
+    }
+}
+
+fn read_u16(page: &[u8], offset: usize) -> u16 {
+    match page.get(offset..offset + 2) {
+        Some(value) => u16::from_le_bytes([value[0], value[1]]),
+        None => 0,
+    }
+}
+
+fn write_u16(page: &mut [u8], offset: usize, value: u16) {
+    page[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+}
+
+/// Tags are stored at the end of the page in reverse order
+fn tag_position(layout: &PageLayout, line: usize) -> usize {
+    layout.size - (line + 1) * TAG_SIZE
+}
+
+/// Mask for the tag size and offset values. Upper bits are flags
+fn tag_mask(layout: &PageLayout) -> u16 {
+    if layout.large { 0x7fff } else { 0x1fff }
+}
+
+/// Get the tag data offset (relative to the page header) and size
+fn read_tag(page: &[u8], layout: &PageLayout, line: usize) -> (usize, usize) {
+    let position = tag_position(layout, line);
+    let mask = tag_mask(layout);
+    let size = read_u16(page, position) & mask;
+    let offset = read_u16(page, position + 2) & mask;
+    (offset as usize, size as usize)
+}
+
+/// Update the tag count. The upper bits are preserved
+fn set_tag_count(page: &mut [u8], count: usize) {
+    let upper = read_u16(page, FIRST_AVAILABLE_TAG_OFFSET) & !0xfff;
+    write_u16(page, FIRST_AVAILABLE_TAG_OFFSET, upper | count as u16);
+}
+
+fn adjust_available(page: &mut [u8], increase: usize, decrease: usize) {
+    let available = read_u16(page, AVAILABLE_SIZE_OFFSET) as usize;
+    let value = (available + increase).saturating_sub(decrease);
+    write_u16(page, AVAILABLE_SIZE_OFFSET, value as u16);
+}
+
+/// Append data to the free space of the page. Returns the data offset relative to the page header
+fn append_data(
+    page: &mut [u8],
+    layout: &PageLayout,
+    data: &[u8],
+    new_tags: usize,
+) -> Option<usize> {
+    let free_offset = read_u16(page, FIRST_AVAILABLE_DATA_OFFSET) as usize;
+    let start = layout.header_size + free_offset;
+    let end = start + data.len();
+    if end > layout.size - new_tags * TAG_SIZE
+        || free_offset + data.len() > tag_mask(layout) as usize
+    {
+        return None;
+    }
+
+    page[start..end].copy_from_slice(data);
+    write_u16(
+        page,
+        FIRST_AVAILABLE_DATA_OFFSET,
+        (free_offset + data.len()) as u16,
+    );
+    Some(free_offset)
+}
+
+fn insert_node(page: &mut [u8], layout: &PageLayout, line: usize, flags: u16, data: &[u8]) -> bool {
+    if line > layout.tags {
+        return false;
+    }
+    let Some(offset) = append_data(page, layout, data, layout.tags + 1) else {
+        return false;
+    };
+
+    // Move the tags at and after the insert line to make room for the new tag
+    let tags_start = layout.size - layout.tags * TAG_SIZE;
+    let line_start = tag_position(layout, line) + TAG_SIZE;
+    page.copy_within(tags_start..line_start, tags_start - TAG_SIZE);
+
+    let position = tag_position(layout, line);
+    let flag_bits = if layout.large { 0 } else { flags << 13 };
+    write_u16(page, position, data.len() as u16);
+    write_u16(page, position + 2, offset as u16 | flag_bits);
+
+    set_tag_count(page, layout.tags + 1);
+    adjust_available(page, 0, data.len() + TAG_SIZE);
+    true
+}
+
+fn replace_node(page: &mut [u8], layout: &PageLayout, line: usize, data: &[u8]) -> bool {
+    if line >= layout.tags {
+        return false;
+    }
+    let (offset, size) = read_tag(page, layout, line);
+    let mask = tag_mask(layout);
+    let position = tag_position(layout, line);
+
+    let new_offset = if data.len() <= size {
+        let start = layout.header_size + offset;
+        if start + data.len() > layout.size {
+            return false;
+        }
+        page[start..start + data.len()].copy_from_slice(data);
+        offset
+    } else {
+        match append_data(page, layout, data, layout.tags) {
+            Some(result) => result,
+            None => return false,
+        }
+    };
+
+    let size_flags = read_u16(page, position) & !mask;
+    let offset_flags = read_u16(page, position + 2) & !mask;
+    write_u16(page, position, size_flags | data.len() as u16);
+    write_u16(page, position + 2, offset_flags | new_offset as u16);
+    adjust_available(page, size, data.len());
+    true
+}
+
+/// Deleted nodes are flagged as defunct. Pages 16KB or larger store the tag flags in the first two bytes of the node data
+fn set_defunct(page: &mut [u8], layout: &PageLayout, line: usize, defunct: bool) -> bool {
+    if line >= layout.tags {
+        return false;
+    }
+
+    let flag_offset = if layout.large {
+        let (offset, size) = read_tag(page, layout, line);
+        if size < 2 {
+            return false;
+        }
+        layout.header_size + offset
+    } else {
+        tag_position(layout, line) + 2
+    };
+
+    if flag_offset + 2 > layout.size {
+        return false;
+    }
+    let value = read_u16(page, flag_offset);
+    let updated = if defunct {
+        value | DEFUNCT
+    } else {
+        value & !DEFUNCT
+    };
+    write_u16(page, flag_offset, updated);
+    true
+}
+
+fn delete_node(page: &mut [u8], layout: &PageLayout, line: usize) -> bool {
+    if line >= layout.tags {
+        return false;
+    }
+    let (_, size) = read_tag(page, layout, line);
+
+    // Move the tags after the deleted line over the deleted tag
+    let tags_start = layout.size - layout.tags * TAG_SIZE;
+    let line_start = tag_position(layout, line);
+    page.copy_within(tags_start..line_start, tags_start + TAG_SIZE);
+    page[tags_start..tags_start + TAG_SIZE].fill(0);
+
+    set_tag_count(page, layout.tags - 1);
+    adjust_available(page, size + TAG_SIZE, 0);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{page_layout, read_tag, redo_page};
+    use crate::artifacts::os::windows::ese::{
+        logs::records::{Operation, PageRecord},
+        page::PageHeader,
+        tags::TagFlags,
+    };
+    use crate::filesystem::files::read_file;
+    use std::path::PathBuf;
+
+    fn record(operation: Operation, line: u16, data: &[u8]) -> PageRecord {
+        PageRecord {
+            operation,
+            page: 1,
+            dbtime_before: 10,
+            dbtime_after: 11,
+            line,
+            flags: 0,
+            data: data.to_vec(),
+        }
+    }
+
+    /// Empty 4KB page with one 4 byte node
+    fn small_page() -> Vec<u8> {
+        let mut page = vec![0; 4096];
+        page[8] = 10;
+        page[28..30].copy_from_slice(&4048u16.to_le_bytes());
+        page[32..34].copy_from_slice(&4u16.to_le_bytes());
+        page[34..36].copy_from_slice(&1u16.to_le_bytes());
+        page[36..40].copy_from_slice(&2u32.to_le_bytes());
+        page[40..44].copy_from_slice(&[1, 2, 3, 4]);
+        page[4092..4094].copy_from_slice(&4u16.to_le_bytes());
+        page
+    }
+
+    #[test]
+    fn test_redo_insert_and_delete() {
+        let mut page = small_page();
+        let mut insert = record(Operation::Insert, 0, &[9, 9]);
+        insert.flags = 1;
+        assert!(redo_page(&mut page, &insert));
+
+        let layout = page_layout(&page);
+        assert_eq!(layout.tags, 2);
+        assert_eq!(read_tag(&page, &layout, 0), (4, 2));
+        assert_eq!(read_tag(&page, &layout, 1), (0, 4));
+        assert_eq!(page[8], 11);
+
+        let (_, header) = PageHeader::parse_header(&page).unwrap();
+        assert_eq!(header.page_tags[0].flags, vec![TagFlags::Value]);
+
+        let mut delete = record(Operation::Delete, 0, &[]);
+        delete.dbtime_before = 11;
+        delete.dbtime_after = 12;
+        assert!(redo_page(&mut page, &delete));
+        let layout = page_layout(&page);
+        assert_eq!(layout.tags, 1);
+        assert_eq!(read_tag(&page, &layout, 0), (0, 4));
+    }
+
+    #[test]
+    fn test_redo_replace() {
+        let mut page = small_page();
+        assert!(redo_page(
+            &mut page,
+            &record(Operation::Replace, 0, &[5, 6, 7, 8, 9])
+        ));
+        let layout = page_layout(&page);
+        assert_eq!(read_tag(&page, &layout, 0), (4, 5));
+        assert_eq!(page[44..49], [5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn test_redo_flag_delete() {
+        let mut page = small_page();
+        assert!(redo_page(&mut page, &record(Operation::FlagDelete, 0, &[])));
+
+        let (_, header) = PageHeader::parse_header(&page).unwrap();
+        assert_eq!(header.page_tags[0].flags, vec![TagFlags::Defunct]);
+    }
+
+    #[test]
+    fn test_redo_page_dbtime_mismatch() {
+        let mut page = small_page();
+        let mut replace = record(Operation::Replace, 0, &[5]);
+        replace.dbtime_before = 9;
+        assert!(!redo_page(&mut page, &replace));
+        assert_eq!(page, small_page());
+
+        assert!(!redo_page(&mut page, &record(Operation::Replace, 4, &[5])));
+        assert!(!redo_page(
+            &mut page,
+            &record(Operation::ReplaceDelta, 0, &[5])
+        ));
+    }
+
+    #[test]
+    fn test_redo_page_large() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/ese/win10/qmgr.db");
+        let data = read_file(test_location.to_str().unwrap()).unwrap();
+        let page_size = 16384;
+        // Page 32 is the Files table
+        let mut page = data[page_size * 33..page_size * 34].to_vec();
+
+        let mut flag_delete = record(Operation::FlagDelete, 1, &[]);
+        flag_delete.dbtime_before = 0x3769;
+        assert!(redo_page(&mut page, &flag_delete));
+
+        let (_, header) = PageHeader::parse_header(&page).unwrap();
+        assert!(header.page_tags[1].flags.contains(&TagFlags::Defunct));
+    }
+}
@@ -0,0 +1,459 @@
+/**
+ * `ESE` databases write all changes to transaction logs (edb.log, edb00001.log, SRU.log, etc) before the database pages are flushed
+ * Databases copied from a live system are almost always in a dirty state and the latest changes only exist in the logs
+ *
+ * Artemis replays the page level log records (node insert, replace, delete) onto copies of the database pages
+ * A record is only applied if the page `dbtime` matches the `dbtime` before the change. Records that cannot be replayed leave the page unchanged
+ * Uncommitted transactions are not rolled back
+ *
+ * References:
+ * `https://github.com/microsoft/Extensible-Storage-Engine/tree/main/dev/ese/src/ese/_log`
+ * `https://github.com/libyal/libesedb/blob/main/documentation/Extensible%20Storage%20Engine%20(ESE)%20Database%20File%20(EDB)%20format.asciidoc`
+ * `https://techcommunity.microsoft.com/t5/ask-the-directory-services-team/ese-deep-dive-part-1-the-anatomy-of-an-ese-database/ba-p/400496`
+ *
+ * Other Parsers:
+ * `esentutl /r` (Windows)
+ */
+use super::{header::LogHeader, records::parse_log_records, redo::redo_page};
+use crate::{
+    accessor::location::loc::Location,
+    artifacts::os::windows::ese::header::{DatabaseState, EseHeader},
+    filesystem::{
+        directory::get_parent_directory,
+        files::{file_extension, list_files_any, read_any},
+        ntfs::{raw_files::raw_read_file, reader::read_bytes},
+    },
+};
+use ntfs::NtfsFile;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet, hash_map::Entry},
+    io::{BufReader, Error, ErrorKind, Read, Seek, SeekFrom},
+    rc::Rc,
+};
+use tracing::{info, warn};
+
+thread_local! {
+    /// Last database replayed on this thread. Artifacts such as SRUM and Search read the same database many times
+    static REPLAY_CACHE: RefCell<Option<ReplayCache>> = const { RefCell::new(None) };
+}
+
+/// A replayed database. The database header changes when the database or its required log generations change
+struct ReplayCache {
+    path: String,
+    header: Vec<u8>,
+    replay: Option<Rc<EseReplay>>,
+}
+
+/// Database pages recovered from the transaction logs
+#[derive(Debug)]
+pub(crate) struct EseReplay {
+    pages: HashMap<u32, Vec<u8>>,
+    page_size: u64,
+    /// Last log generation replayed
+    pub(crate) generation: u32,
+}
+
+impl EseReplay {
+    /// Get the recovered page that contains the file offset and the offset within the page
+    fn page_at(&self, offset: u64) -> Option<(&[u8], usize)> {
+        // First page is the database header
+        let index = offset / self.page_size;
+        if index == 0 {
+            return None;
+        }
+        let page = self.pages.get(&((index - 1) as u32))?;
+        Some((page, (offset % self.page_size) as usize))
+    }
+}
+
+/// Replay the transaction logs next to a dirty `ESE` database. Returns None if the database is clean or no logs were replayed
+///
+/// The logs are only replayed once per database. Later calls for the same database return the cached replay
+pub(crate) fn replay_logs<T: Read + Seek>(
+    path: &str,
+    ntfs_file: Option<&NtfsFile<'_>>,
+    fs: &mut BufReader<T>,
+) -> Option<Rc<EseReplay>> {
+    let header_size = 668;
+    let header_data = read_bytes(0, header_size, ntfs_file, fs).ok()?;
+
+    let cached = REPLAY_CACHE.with_borrow(|cache| {
+        cache
+            .as_ref()
+            .filter(|cache| cache.path == path && cache.header == header_data)
+            .map(|cache| cache.replay.clone())
+    });
+    if let Some(replay) = cached {
+        return replay;
+    }
+
+    let replay = replay_database(path, &header_data, ntfs_file, fs).map(Rc::new);
+    REPLAY_CACHE.set(Some(ReplayCache {
+        path: path.to_string(),
+        header: header_data,
+        replay: replay.clone(),
+    }));
+    replay
+}
+
+/// Replay the transaction logs onto copies of the database pages
+fn replay_database<T: Read + Seek>(
+    path: &str,
+    header_data: &[u8],
+    ntfs_file: Option<&NtfsFile<'_>>,
+    fs: &mut BufReader<T>,
+) -> Option<EseReplay> {
+    let (_, header) = EseHeader::parse_header(header_data).ok()?;
+    if header.database_state != DatabaseState::DirtyShutdown {
+        return None;
+    }
+
+    let logs = read_logs(path, &header);
+    if logs.is_empty() {
+        warn!("[ese] Database {path} is dirty but no transaction logs were found");
+        return None;
+    }
+
+    let page_size = header.page_size as u64;
+    let mut pages: HashMap<u32, Vec<u8>> = HashMap::new();
+    let mut modified = HashSet::new();
+    let mut generation = 0;
+    for (log_header, data) in logs {
+        let records = parse_log_records(
+            data.get(log_header.records_offset()..).unwrap_or_default(),
+            log_header.generation,
+        );
+        for record in records {
+            let page = match pages.entry(record.page) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    // Need to adjust page number to account for header page
+                    let offset = (record.page as u64 + 1) * page_size;
+                    match read_bytes(offset, page_size, ntfs_file, fs) {
+                        Ok(result) => entry.insert(result),
+                        Err(err) => {
+                            warn!(
+                                "[ese] Could not read page {} for log replay: {err:?}",
+                                record.page
+                            );
+                            continue;
+                        }
+                    }
+                }
+            };
+            if redo_page(page, &record) {
+                modified.insert(record.page);
+            }
+        }
+        generation = log_header.generation;
+    }
+
+    pages.retain(|page, _| modified.contains(page));
+    info!(
+        "[ese] Replayed transaction logs for {path} up to generation {generation}. Recovered {} pages",
+        pages.len()
+    );
+
+    Some(EseReplay {
+        pages,
+        page_size,
+        generation,
+    })
+}
+
+/// Read the transaction logs in the database directory that belong to the database. Sorted by generation.
+/// Logs in archives, disk images or snapshots are read with the `Accessor`
+fn read_logs(path: &str, header: &EseHeader) -> Vec<(LogHeader, Vec<u8>)> {
+    let directory = get_parent_directory(path);
+    let files = match list_files_any(&directory) {
+        Ok(result) => result,
+        Err(err) => {
+            warn!("[ese] Could not list transaction logs at {directory}: {err:?}");
+            return Vec::new();
+        }
+    };
+
+    let mut logs = Vec::new();
+    for file in files {
+        let extension = file_extension(&file).to_lowercase();
+        if extension != "log" && extension != "jrs" {
+            continue;
+        }
+
+        // Transaction logs on a live system are locked. So they are read by parsing NTFS
+        let data_result = if Location::is_container(&file) {
+            read_any(&file)
+        } else {
+            raw_read_file(&file)
+        };
+        let data = match data_result {
+            Ok(result) => result,
+            Err(err) => {
+                warn!("[ese] Could not read transaction log {file}: {err:?}");
+                continue;
+            }
+        };
+        let Ok((_, log_header)) = LogHeader::parse_header(&data) else {
+            continue;
+        };
+        // Reserve logs (.jrs) and logs for other databases have a different signature
+        if !log_header.is_database_log(header) || log_header.generation < header.required_log {
+            continue;
+        }
+        logs.push((log_header, data));
+    }
+
+    logs.sort_by_key(|(log_header, _)| log_header.generation);
+    logs.dedup_by_key(|(log_header, _)| log_header.generation);
+
+    let generations: Vec<u32> = logs
+        .iter()
+        .map(|(log_header, _)| log_header.generation)
+        .collect();
+    let count = contiguous_logs(&generations, header.required_log);
+    if count != logs.len() {
+        warn!(
+            "[ese] Transaction log generations for {path} have a gap. Only replaying {count} of {} logs",
+            logs.len()
+        );
+        logs.truncate(count);
+    }
+    logs
+}
+
+/// Count the sorted log generations that can be replayed. Replay stops at the first missing generation
+fn contiguous_logs(generations: &[u32], required_log: u32) -> usize {
+    let Some(first) = generations.first() else {
+        return 0;
+    };
+    // The first required log must exist
+    if required_log != 0 && *first != required_log {
+        return 0;
+    }
+
+    let mut count = 1;
+    for window in generations.windows(2) {
+        if window[1] != window[0] + 1 {
+            break;
+        }
+        count += 1;
+    }
+    count
+}
+
+/// Reader that returns the recovered pages from the transaction logs instead of the database pages
+pub(crate) struct ReplayReader<'a, 'b, T: Read + Seek> {
+    ntfs_file: Option<&'a NtfsFile<'b>>,
+    fs: &'a mut BufReader<T>,
+    replay: Option<&'a EseReplay>,
+    position: u64,
+}
+
+impl<'a, 'b, T: Read + Seek> ReplayReader<'a, 'b, T> {
+    pub(crate) fn new(
+        ntfs_file: Option<&'a NtfsFile<'b>>,
+        fs: &'a mut BufReader<T>,
+        replay: Option<&'a EseReplay>,
+    ) -> ReplayReader<'a, 'b, T> {
+        ReplayReader {
+            ntfs_file,
+            fs,
+            replay,
+            position: 0,
+        }
+    }
+}
+
+impl<T: Read + Seek> Read for ReplayReader<'_, '_, T> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let mut size = buf.len();
+        if let Some(replay) = self.replay {
+            if let Some((page, offset)) = replay.page_at(self.position) {
+                let count = size.min(page.len() - offset);
+                buf[..count].copy_from_slice(&page[offset..offset + count]);
+                self.position += count as u64;
+                return Ok(count);
+            }
+            // Do not read into the next page. It may have been recovered
+            size = size.min((replay.page_size - self.position % replay.page_size) as usize);
+        }
+
+        let count = if self.ntfs_file.is_none() {
+            self.fs.seek(SeekFrom::Start(self.position))?;
+            self.fs.read(&mut buf[..size])?
+        } else {
+            let data = match read_bytes(self.position, size as u64, self.ntfs_file, self.fs) {
+                Ok(result) => result,
+                Err(err) => return Err(Error::new(ErrorKind::InvalidData, err.to_string())),
+            };
+            let count = data.len().min(size);
+            buf[..count].copy_from_slice(&data[..count]);
+            count
+        };
+
+        self.position += count as u64;
+        Ok(count)
+    }
+}
+
+impl<T: Read + Seek> Seek for ReplayReader<'_, '_, T> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.position = match pos {
+            SeekFrom::Start(offset) => offset,
+            SeekFrom::Current(offset) => match self.position.checked_add_signed(offset) {
+                Some(result) => result,
+                None => return Err(Error::new(ErrorKind::InvalidInput, "Invalid seek offset")),
+            },
+            SeekFrom::End(_) => {
+                if self.ntfs_file.is_some() {
+                    return Err(Error::new(
+                        ErrorKind::Unsupported,
+                        "Cannot seek from end of NTFS file",
+                    ));
+                }
+                self.fs.seek(pos)?
+            }
+        };
+        Ok(self.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReplayReader, contiguous_logs, read_logs, replay_logs};
+    use crate::{
+        artifacts::os::windows::ese::{
+            header::EseHeader,
+            helper::{get_all_pages, get_catalog_info, get_log_generation, get_page_data},
+            tables::table_info,
+        },
+        filesystem::files::{file_reader, read_file},
+    };
+    use std::{
+        io::{BufReader, Read, Seek, SeekFrom},
+        path::PathBuf,
+        rc::Rc,
+    };
+
+    fn dirty_path() -> String {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/ese/dirty/qmgr.db");
+        test_location.display().to_string()
+    }
+
+    #[test]
+    fn test_replay_logs() {
+        let path = dirty_path();
+        let mut fs = BufReader::new(file_reader(&path).unwrap());
+
+        let replay = replay_logs(&path, None, &mut fs).unwrap();
+        assert_eq!(replay.generation, 2);
+        assert_eq!(replay.pages.len(), 2);
+        assert!(replay.pages.contains_key(&31));
+        assert!(replay.pages.contains_key(&32));
+
+        // Logs are only replayed once per database
+        let cached = replay_logs(&path, None, &mut fs).unwrap();
+        assert!(Rc::ptr_eq(&replay, &cached));
+    }
+
+    #[test]
+    fn test_replay_logs_no_logs() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/ese/win10/qmgr.db");
+        let path = test_location.display().to_string();
+        let mut fs = BufReader::new(file_reader(&path).unwrap());
+
+        assert!(replay_logs(&path, None, &mut fs).is_none());
+    }
+
+    #[test]
+    fn test_read_logs() {
+        let path = dirty_path();
+        let data = read_file(&path).unwrap();
+        let (_, header) = EseHeader::parse_header(&data).unwrap();
+
+        // The reserve log does not belong to the database
+        let logs = read_logs(&path, &header);
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].0.generation, 1);
+        assert_eq!(logs[1].0.generation, 2);
+    }
+
+    #[test]
+    fn test_contiguous_logs() {
+        assert_eq!(contiguous_logs(&[1, 2, 3], 1), 3);
+        assert_eq!(contiguous_logs(&[1, 2, 4, 5], 1), 2);
+        assert_eq!(contiguous_logs(&[2, 3], 1), 0);
+        assert_eq!(contiguous_logs(&[5, 6], 0), 2);
+        assert_eq!(contiguous_logs(&[], 1), 0);
+    }
+
+    #[test]
+    fn test_replay_reader() {
+        let path = dirty_path();
+        let original = read_file(&path).unwrap();
+        let mut fs = BufReader::new(file_reader(&path).unwrap());
+        let replay = replay_logs(&path, None, &mut fs).unwrap();
+
+        let mut reader = BufReader::new(ReplayReader::new(None, &mut fs, Some(replay.as_ref())));
+        let page_size = 16384;
+        let mut page = vec![0; page_size];
+
+        reader.seek(SeekFrom::Start(page_size as u64 * 31)).unwrap();
+        reader.read_exact(&mut page).unwrap();
+        assert_eq!(page, original[page_size * 31..page_size * 32]);
+
+        reader.read_exact(&mut page).unwrap();
+        assert_ne!(page, original[page_size * 32..page_size * 33]);
+        assert_eq!(page, replay.pages[&31]);
+
+        // Files table row now has a new Id
+        reader.seek(SeekFrom::Current(80 + 69 + 23)).unwrap();
+        let mut id = [0; 4];
+        reader.read_exact(&mut id).unwrap();
+        assert_eq!(id, [0x78, 0x56, 0x34, 0x12]);
+    }
+
+    #[test]
+    fn test_get_page_data_dirty() {
+        let path = dirty_path();
+        let catalog = get_catalog_info(&path).unwrap();
+
+        let mut info = table_info(&catalog, "Jobs");
+        let pages = get_all_pages(&path, info.table_page as u32).unwrap();
+        let rows = get_page_data(&path, &pages, &mut info, "Jobs").unwrap();
+        // Row inserted by the first log and updated by the second log
+        assert_eq!(rows["Jobs"].len(), 2);
+        assert_eq!(
+            rows["Jobs"][0][0].column_data,
+            "266504ac-d974-446c-96ad-2be13a5665b0"
+        );
+        assert_eq!(
+            rows["Jobs"][1][0].column_data,
+            "deadbeef-d974-446c-96ad-2be13a5665b0"
+        );
+
+        let mut info = table_info(&catalog, "Files");
+        let pages = get_all_pages(&path, info.table_page as u32).unwrap();
+        let rows = get_page_data(&path, &pages, &mut info, "Files").unwrap();
+        assert_eq!(rows["Files"].len(), 1);
+        assert_eq!(
+            rows["Files"][0][0].column_data,
+            "12345678-b2d3-4748-8eb1-9da0650cb892"
+        );
+    }
+
+    #[test]
+    fn test_get_log_generation() {
+        assert_eq!(get_log_generation(&dirty_path()).unwrap(), Some(2));
+
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/ese/win10/qmgr.db");
+        assert_eq!(
+            get_log_generation(&test_location.display().to_string()).unwrap(),
+            None
+        );
+    }
+}
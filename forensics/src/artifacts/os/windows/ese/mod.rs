@@ -2,7 +2,9 @@ pub(crate) mod catalog;
 mod error;
 mod header;
 pub(crate) mod helper;
+mod logs;
 mod page;
 mod pages;
+mod recover;
 pub(crate) mod tables;
 mod tags;
@@ -13,7 +13,7 @@ pub(crate) struct PageHeader {
     database_last_modified: String,
     previous_page_number: u32,
     pub(crate) next_page_number: u32,
-    father_data_page: u32,
+    pub(crate) father_data_page: u32,
     available_page_size: u16,
     available_uncommitted_data_size: u16,
     first_available_data_offset: u16,
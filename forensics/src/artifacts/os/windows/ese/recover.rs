@@ -0,0 +1,323 @@
+/**
+ * `ESE` does not immediately overwrite deleted rows. Deleted nodes are flagged as defunct, removed from the page tags, or left behind in the free space of a page when the page is reorganized
+ * Pages released from a table B-tree (ex: all rows on the page were deleted) may also still contain rows and still have the table object ID in the page header
+ *
+ * We carve row remnants from the unused regions of table leaf pages and from orphan pages that belong to the table
+ * Tagged columns are only recovered from defunct nodes, the size of other remnants is unknown
+ *
+ * References:
+ * `https://github.com/libyal/libesedb/blob/main/documentation/Extensible%20Storage%20Engine%20(ESE)%20Database%20File%20(EDB)%20format.asciidoc`
+ * `https://github.com/microsoft/Extensible-Storage-Engine`
+ */
+use super::{
+    page::{PageFlags, PageHeader},
+    tables::{
+        ColumnInfo, TableInfo, clear_column_data, nom_fixed_column, parse_fixed_data,
+        parse_variable_data,
+    },
+    tags::TagFlags,
+};
+use crate::utils::nom_helper::{Endian, nom_unsigned_one_byte, nom_unsigned_two_bytes};
+
+/// Carve deleted rows from a table leaf page. Orphan pages are not part of the table B-tree, so all of the page data is carved
+pub(crate) fn recover_page_rows(
+    page: &[u8],
+    info: &mut TableInfo,
+    orphan: bool,
+) -> Vec<Vec<ColumnInfo>> {
+    let mut rows = Vec::new();
+    let Ok((page_data, header)) = PageHeader::parse_header(page) else {
+        return rows;
+    };
+    if header.father_data_page as i32 != info.obj_id_table
+        || !header.page_flags.contains(&PageFlags::Leaf)
+        || header.page_flags.contains(&PageFlags::SpaceTree)
+        || header.page_flags.contains(&PageFlags::Index)
+        || header.page_flags.contains(&PageFlags::LongValue)
+    {
+        return rows;
+    }
+
+    // Tags are stored at the end of the page. Each tag is four (4) bytes
+    let tag_size = 4;
+    let tags_start = page_data
+        .len()
+        .saturating_sub(header.page_tags.len() * tag_size);
+    let mut used = vec![false; tags_start];
+
+    // First tag is the page key, not a row
+    for tag in header.page_tags.iter().skip(1) {
+        let start = tag.offset as usize;
+        let end = (start + tag.value_size as usize).min(tags_start);
+        if start >= end {
+            continue;
+        }
+        used[start..end].fill(true);
+
+        if !orphan && !tag.flags.contains(&TagFlags::Defunct) {
+            continue;
+        }
+        // The size of the node is known. Tagged columns can be recovered too
+        carve_rows(&page_data[start..end], info, true, &mut rows);
+    }
+
+    // Now carve the regions not used by any tag
+    let mut offset = 0;
+    while offset < tags_start {
+        if used[offset] {
+            offset += 1;
+            continue;
+        }
+        let end = used[offset..]
+            .iter()
+            .position(|value| *value)
+            .map_or(tags_start, |position| offset + position);
+        carve_rows(&page_data[offset..end], info, false, &mut rows);
+        offset = end;
+    }
+
+    rows
+}
+
+/// Scan the data for row remnants. If the data is a single node then only one row is returned
+fn carve_rows(data: &[u8], info: &mut TableInfo, is_node: bool, rows: &mut Vec<Vec<ColumnInfo>>) {
+    let mut offset = 0;
+    while offset < data.len() {
+        let size = carve_row(&data[offset..], &mut info.column_info, is_node);
+        if size.is_some() {
+            rows.push(info.column_info.clone());
+        }
+        // Now clear column data so when we go to next row we have no leftover data from previous row
+        clear_column_data(&mut info.column_info);
+
+        match size {
+            Some(_) if is_node => return,
+            Some(row_size) => offset += row_size,
+            None => offset += 1,
+        }
+    }
+}
+
+/**
+ * Try to parse a row at the start of the data. Row data starts with: last fixed column (1 byte), last variable column (1 byte), variable data offset (2 bytes)
+ * The sizes of the fixed columns are known from the table columns, which lets us validate the remnant before parsing it
+ */
+fn carve_row(data: &[u8], column_info: &mut [ColumnInfo], is_node: bool) -> Option<usize> {
+    let (input, last_fixed_data) = nom_unsigned_one_byte(data, Endian::Le).ok()?;
+    let (input, last_variable_data) = nom_unsigned_one_byte(input, Endian::Le).ok()?;
+    let (fixed_data, variable_data_offset) = nom_unsigned_two_bytes(input, Endian::Le).ok()?;
+
+    // Zeroed data or rows without fixed columns cannot be validated
+    let max_fixed = 127;
+    let no_variable = 127;
+    if last_fixed_data == 0 || last_fixed_data > max_fixed || last_variable_data < no_variable {
+        return None;
+    }
+
+    let mut remaining = fixed_data;
+    for column in 1..=last_fixed_data {
+        let entry = column_info
+            .iter()
+            .find(|entry| entry.column_id == column as i32)?;
+        let (input, _) =
+            nom_fixed_column(&entry.column_type, remaining, entry.column_space_usage).ok()?;
+        remaining = input;
+    }
+    // Fixed data is followed by a bit array of the fixed columns that are NULL
+    let fixed_size = fixed_data.len() - remaining.len() + (last_fixed_data as usize).div_ceil(8);
+    let header_size = 4;
+    if variable_data_offset as usize != fixed_size + header_size {
+        return None;
+    }
+    if fixed_data
+        .get(..fixed_size)?
+        .iter()
+        .all(|value| *value == 0)
+    {
+        return None;
+    }
+
+    let variable_start = variable_data_offset as usize;
+    let mut variable_size = 0;
+    if last_variable_data != no_variable {
+        if !column_info
+            .iter()
+            .any(|entry| entry.column_id == last_variable_data as i32)
+        {
+            return None;
+        }
+
+        // Variable column sizes are cumulative. Empty columns have the most significant bit set
+        let is_empty = 0x8000;
+        let mut sizes = data.get(variable_start..)?;
+        for _ in no_variable..last_variable_data {
+            let (input, size) = nom_unsigned_two_bytes(sizes, Endian::Le).ok()?;
+            sizes = input;
+            if (size & is_empty) > 0 {
+                continue;
+            }
+            if (size as usize) < variable_size {
+                return None;
+            }
+            variable_size = size as usize;
+        }
+        variable_size += (last_variable_data - no_variable) as usize * 2;
+    }
+
+    let row_size = variable_start + variable_size;
+    if row_size > data.len() {
+        return None;
+    }
+
+    let _ = parse_fixed_data(
+        last_fixed_data,
+        &data[header_size..variable_start],
+        column_info,
+    );
+    // Only nodes have a known size. Otherwise we do not know where the tagged data ends
+    let variable_end = if is_node { data.len() } else { row_size };
+    let _ = parse_variable_data(
+        last_variable_data,
+        &data[variable_start..variable_end],
+        column_info,
+    );
+
+    Some(row_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{carve_row, carve_rows, recover_page_rows};
+    use crate::{
+        artifacts::os::windows::ese::{
+            helper::{get_all_pages, get_catalog_info, get_deleted_page_data},
+            tables::{ColumnInfo, TableInfo, table_info},
+        },
+        filesystem::files::read_file,
+    };
+    use common::windows::ColumnType;
+    use std::path::PathBuf;
+
+    fn test_columns() -> Vec<ColumnInfo> {
+        let mut columns = Vec::new();
+        for (column_id, column_type, column_space_usage) in [
+            (1, ColumnType::Long, 4),
+            (2, ColumnType::DateTime, 8),
+            (128, ColumnType::Binary, 0),
+            (256, ColumnType::LongBinary, 0),
+        ] {
+            columns.push(ColumnInfo {
+                column_type,
+                column_name: format!("column{column_id}"),
+                column_data: Vec::new(),
+                column_id,
+                column_flags: Vec::new(),
+                column_space_usage,
+                column_tagged_flags: Vec::new(),
+            });
+        }
+        columns
+    }
+
+    #[test]
+    fn test_carve_row() {
+        let test = [
+            2, 128, 17, 0, 11, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 0, 3, 0, 65, 66, 67, 1, 0, 4, 0, 1,
+            2,
+        ];
+        let mut columns = test_columns();
+
+        let size = carve_row(&test, &mut columns, false).unwrap();
+        assert_eq!(size, 22);
+        assert_eq!(columns[0].column_data, [11, 0, 0, 0]);
+        assert_eq!(columns[1].column_data, [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(columns[2].column_data, [65, 66, 67]);
+        assert!(columns[3].column_data.is_empty());
+    }
+
+    #[test]
+    fn test_carve_row_bad_offset() {
+        let test = [
+            2, 128, 16, 0, 11, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 0, 3, 0, 65, 66, 67,
+        ];
+        let mut columns = test_columns();
+
+        assert!(carve_row(&test, &mut columns, false).is_none());
+        assert!(carve_row(&[0; 22], &mut columns, false).is_none());
+    }
+
+    #[test]
+    fn test_carve_rows() {
+        let test = [
+            0, 0, 9, 2, 128, 17, 0, 11, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 0, 3, 0, 65, 66, 67, 0, 0,
+            2, 127, 17, 0, 12, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 0,
+        ];
+        let mut info = TableInfo {
+            obj_id_table: 0,
+            table_page: 0,
+            table_name: String::from("test"),
+            column_info: test_columns(),
+            long_value_page: 0,
+        };
+        let mut rows = Vec::new();
+
+        carve_rows(&test, &mut info, false, &mut rows);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0][0].column_data, [11, 0, 0, 0]);
+        assert_eq!(rows[1][0].column_data, [12, 0, 0, 0]);
+        assert!(rows[1][2].column_data.is_empty());
+        assert!(info.column_info[0].column_data.is_empty());
+    }
+
+    #[test]
+    fn test_recover_page_rows() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/ese/win10/qmgr.db");
+        let path = test_location.to_str().unwrap();
+        let catalog = get_catalog_info(path).unwrap();
+        let mut info = table_info(&catalog, "Jobs");
+
+        let data = read_file(path).unwrap();
+        let page_size = 16384;
+        let page = 31;
+        let start = (page + 1) * page_size;
+        let rows = recover_page_rows(&data[start..start + page_size], &mut info, false);
+        assert_eq!(rows.len(), 74);
+        assert_eq!(rows[0][0].column_name, "Id");
+        assert_eq!(rows[0][0].column_data.len(), 16);
+    }
+
+    #[test]
+    fn test_get_deleted_page_data() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/ese/win10/qmgr.db");
+        let path = test_location.to_str().unwrap();
+        let catalog = get_catalog_info(path).unwrap();
+        let mut info = table_info(&catalog, "Jobs");
+        let pages = get_all_pages(path, info.table_page as u32).unwrap();
+
+        let rows = get_deleted_page_data(path, &pages, &mut info, "Jobs").unwrap();
+        let jobs = rows.get("Jobs").unwrap();
+        assert_eq!(jobs.len(), 74);
+        assert_eq!(
+            jobs[0][0].column_data,
+            "3ae64aa4-ad6d-4e70-bfb0-c92622bfd5eb"
+        );
+    }
+
+    #[test]
+    fn test_recover_page_rows_wrong_table() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/ese/win10/page.raw");
+        let data = read_file(test_location.to_str().unwrap()).unwrap();
+        let mut info = TableInfo {
+            obj_id_table: -1,
+            table_page: 0,
+            table_name: String::from("test"),
+            column_info: test_columns(),
+            long_value_page: 0,
+        };
+
+        assert!(recover_page_rows(&data, &mut info, true).is_empty());
+    }
+}
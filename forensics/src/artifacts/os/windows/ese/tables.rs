@@ -359,7 +359,7 @@ pub(crate) fn parse_row(leaf_row: PageLeaf, column_info: &mut [ColumnInfo]) {
 }
 
 /// Parse the fixed data of a column
-fn parse_fixed_data<'a>(
+pub(crate) fn parse_fixed_data<'a>(
     last_fixed_data: u8,
     fixed_data: &'a [u8],
     column_info: &mut [ColumnInfo],
@@ -382,7 +382,7 @@ fn parse_fixed_data<'a>(
 }
 
 /// Parse the variable data of a column. Follows fixed data
-fn parse_variable_data<'a>(
+pub(crate) fn parse_variable_data<'a>(
     last_variable: u8,
     variable_data: &'a [u8],
     column_info: &mut [ColumnInfo],
@@ -641,7 +641,7 @@ pub(crate) fn clear_column_data(column_info: &mut [ColumnInfo]) {
 }
 
 /// Nom the fixed column data. Columns that are fixed have static sizes (ex: GUID is 16 bytes)
-fn nom_fixed_column<'a>(
+pub(crate) fn nom_fixed_column<'a>(
     column_type: &ColumnType,
     data: &'a [u8],
     column_space_usage: i32,
@@ -0,0 +1,553 @@
+/**
+ * Carve `EventLog` chunks and records from unallocated space, memory dumps or any other binary data
+ * Cleared or deleted `EventLog` files may still exist in unallocated clusters
+ *
+ * `EventLog` chunks start with the `ElfChnk` signature and are 64KB in size. Each chunk contains the string and template tables for its records
+ * Records start with the `**` signature. Records outside of a valid chunk reference strings and templates by their offset in the original chunk
+ * We rebuild the chunk around the record, using any data that still exists before the record, so the strings and templates can be found
+ *
+ * References:
+ * `https://github.com/libyal/libevtx/blob/main/documentation/Windows%20XML%20Event%20Log%20(EVTX).asciidoc`
+ * `https://github.com/omerbenamram/EVTX`
+ */
+use super::{
+    error::EventLogsError,
+    filter::RecordFilter,
+    parser::{open_eventlog, output_logs},
+};
+use crate::{
+    filesystem::ntfs::{
+        setup::setup_ntfs_parser,
+        unallocated::{get_unallocated, scan_ranges},
+    },
+    output::{manager::OutputManager, record::serialize_records_to_stream},
+    structs::artifacts::os::windows::EventLogsOptions,
+    utils::{
+        nom_helper::{Endian, nom_unsigned_eight_bytes, nom_unsigned_four_bytes},
+        sigma::rule::{SigmaRule, evaluate_records},
+    },
+};
+use common::windows::{EventLogRecord, SigmaHit};
+use evtx::{EvtxChunkData, ParserSettings, SerializedEvtxRecord};
+use serde_json::Value;
+use std::{
+    collections::HashSet,
+    io::{Read, Seek, SeekFrom},
+    sync::Arc,
+};
+use tracing::{error, info, warn};
+
+const CHUNK_SIZE: usize = 65536;
+const CHUNK_HEADER_SIZE: usize = 512;
+const RECORD_HEADER_SIZE: usize = 24;
+const CHUNK_SIGNATURE: &[u8; 8] = b"ElfChnk\0";
+const RECORD_SIGNATURE: &[u8; 4] = b"**\0\0";
+
+/// Track carved data across the scanned data
+struct CarveState {
+    settings: Arc<ParserSettings>,
+    /**Absolute offsets of records that have already been carved */
+    records: HashSet<u64>,
+    /**Record ID, timestamp, and channel of carved records. Old data may exist multiple times */
+    seen: HashSet<String>,
+    /**Absolute offset of the last chunk signature found */
+    last_chunk: Option<u64>,
+    /**Number of records carved */
+    count: usize,
+    filter: RecordFilter,
+    evidence: String,
+}
+
+/// Carve `EventLog` records from any file. Ex: memory dump, disk image, or a corrupted evtx file
+pub(crate) fn carve_file(
+    path: &str,
+    manager: &mut OutputManager,
+    options: &EventLogsOptions,
+    rules: &[SigmaRule],
+    hits: &mut Vec<SigmaHit>,
+) -> Result<(), EventLogsError> {
+    let mut reader = open_eventlog(path)?;
+    let mut state = CarveState::new(path, options)?;
+
+    // Read 4MB at a time
+    let scan_size = 4 * 1024 * 1024;
+    let mut offset = 0;
+    loop {
+        if manager.cancel.is_cancelled() {
+            warn!("Collection cancelled. Stopping EventLog carving of {path} at offset {offset}");
+            break;
+        }
+        if let Err(err) = reader.seek(SeekFrom::Start(offset)) {
+            error!("Could not seek to offset {offset} in {path}: {err:?}");
+            return Err(EventLogsError::Parser);
+        }
+        let mut data = Vec::with_capacity(scan_size + CHUNK_SIZE);
+        if let Err(err) = reader
+            .by_ref()
+            .take((scan_size + CHUNK_SIZE) as u64)
+            .read_to_end(&mut data)
+        {
+            error!("Could not read {path} at offset {offset}: {err:?}");
+            return Err(EventLogsError::Parser);
+        }
+        if data.is_empty() {
+            break;
+        }
+
+        let records = carve_data(&data, offset, scan_size.min(data.len()), &mut state);
+        write_carved(manager, options, rules, hits, records)?;
+        if data.len() <= scan_size {
+            break;
+        }
+        offset += scan_size as u64;
+    }
+    info!("Carved {} EventLog records from {path}", state.count);
+
+    Ok(())
+}
+
+/// Carve `EventLog` records from the unallocated clusters of the provided drive
+pub(crate) fn carve_drive(
+    drive: char,
+    manager: &mut OutputManager,
+    options: &EventLogsOptions,
+    rules: &[SigmaRule],
+    hits: &mut Vec<SigmaHit>,
+) -> Result<(), EventLogsError> {
+    let mut ntfs_parser = match setup_ntfs_parser(drive) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Cannot setup NTFS parser: {err:?}");
+            return Err(EventLogsError::Parser);
+        }
+    };
+    let ranges = match get_unallocated(drive, &ntfs_parser.ntfs) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Could not determine unallocated clusters: {err:?}");
+            return Err(EventLogsError::Parser);
+        }
+    };
+
+    let mut state = CarveState::new(&format!("{drive}:"), options)?;
+    let mut result = Ok(());
+    let cancel = manager.cancel.clone();
+    scan_ranges(
+        &mut ntfs_parser.fs,
+        &ranges,
+        CHUNK_SIZE as u64,
+        &cancel,
+        |_, offset, data, scan_size| {
+            if result.is_err() {
+                return;
+            }
+            let records = carve_data(data, offset, scan_size, &mut state);
+            result = write_carved(manager, options, rules, hits, records);
+        },
+    );
+    info!("Carved {} EventLog records from {drive}", state.count);
+
+    result
+}
+
+impl CarveState {
+    fn new(evidence: &str, options: &EventLogsOptions) -> Result<CarveState, EventLogsError> {
+        Ok(CarveState {
+            settings: Arc::new(ParserSettings::default()),
+            records: HashSet::new(),
+            seen: HashSet::new(),
+            last_chunk: None,
+            count: 0,
+            filter: RecordFilter::new(&options.filter)?,
+            evidence: evidence.to_string(),
+        })
+    }
+
+    /// Track the carved record. Returns `None` if already carved or the record does not match the filter
+    fn add_record(
+        &mut self,
+        offset: u64,
+        record: SerializedEvtxRecord<Value>,
+    ) -> Option<EventLogRecord> {
+        if !self.records.insert(offset)
+            || !self.filter.matches(
+                record.event_record_id,
+                record.timestamp.as_nanosecond(),
+                &record.data,
+            )
+        {
+            return None;
+        }
+
+        let channel = record
+            .data
+            .pointer("/Event/System/Channel")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let timestamp = record.timestamp.to_string();
+        if !self
+            .seen
+            .insert(format!("{}_{timestamp}_{channel}", record.event_record_id))
+        {
+            return None;
+        }
+
+        self.count += 1;
+        Some(EventLogRecord {
+            event_record_id: record.event_record_id,
+            timestamp,
+            data: record.data,
+            evidence: self.evidence.clone(),
+            carved: true,
+            offset,
+        })
+    }
+}
+
+/// Scan the data for chunks and records. Only data that starts before `scan_size` is carved
+fn carve_data(
+    data: &[u8],
+    base: u64,
+    scan_size: usize,
+    state: &mut CarveState,
+) -> Vec<EventLogRecord> {
+    let mut carved = Vec::new();
+    // Records are 8 byte aligned. Chunks are at least sector aligned
+    let alignment = 8;
+    let sector = 512;
+    let end = scan_size.min(data.len());
+
+    let mut pos = (base.next_multiple_of(alignment) - base) as usize;
+    while pos < end {
+        let offset = base + pos as u64;
+        if offset.is_multiple_of(sector) && data[pos..].starts_with(CHUNK_SIGNATURE) {
+            state.last_chunk = Some(offset);
+            for (record_offset, record) in parse_chunk(&data[pos..], &state.settings) {
+                if let Some(entry) = state.add_record(offset + record_offset, record) {
+                    carved.push(entry);
+                }
+            }
+        } else if data[pos..].starts_with(RECORD_SIGNATURE)
+            && !state.records.contains(&offset)
+            && let Some(record) = parse_record(data, base, pos, state)
+            && let Some(entry) = state.add_record(offset, record)
+        {
+            carved.push(entry);
+        }
+        pos += alignment as usize;
+    }
+
+    carved
+}
+
+/// Parse all records in a chunk. Returns the offset of each record relative to the chunk
+fn parse_chunk(
+    data: &[u8],
+    settings: &Arc<ParserSettings>,
+) -> Vec<(u64, SerializedEvtxRecord<Value>)> {
+    let mut records = Vec::new();
+    let chunk_data = data[..CHUNK_SIZE.min(data.len())].to_vec();
+    let Ok(mut chunk_data) = EvtxChunkData::new(chunk_data, false) else {
+        return records;
+    };
+    let Ok(mut chunk) = chunk_data.parse(Arc::clone(settings)) else {
+        return records;
+    };
+
+    for record in chunk.iter().flatten() {
+        let record_offset = record.binxml_offset - RECORD_HEADER_SIZE as u64;
+        match record.into_json_value() {
+            Ok(result) => records.push((record_offset, result)),
+            Err(err) => warn!("Could not parse carved EventLog record: {err:?}"),
+        }
+    }
+    records
+}
+
+/**
+ * Parse a record that is not part of a valid chunk. The record is placed in a new chunk at its original chunk offset
+ * If we do not know where the chunk started, we try the 4KB aligned offsets before the record
+ */
+fn parse_record(
+    data: &[u8],
+    base: u64,
+    pos: usize,
+    state: &CarveState,
+) -> Option<SerializedEvtxRecord<Value>> {
+    let (input, _signature) = nom_unsigned_four_bytes(&data[pos..], Endian::Le).ok()?;
+    let (input, size) = nom_unsigned_four_bytes(input, Endian::Le).ok()?;
+    let (_, record_id) = nom_unsigned_eight_bytes(input, Endian::Le).ok()?;
+
+    let size = size as usize;
+    let min_size = RECORD_HEADER_SIZE + 4;
+    if size < min_size || size > CHUNK_SIZE - CHUNK_HEADER_SIZE || record_id == 0 {
+        return None;
+    }
+    // Record size is stored again at the end of the record
+    let (_, size_copy) = nom_unsigned_four_bytes(data.get(pos + size - 4..)?, Endian::Le).ok()?;
+    if size_copy as usize != size {
+        return None;
+    }
+
+    let offset = base + pos as u64;
+    let page = 4096;
+    let mut chunk_starts = Vec::new();
+    if let Some(chunk_start) = state.last_chunk
+        && offset - chunk_start < CHUNK_SIZE as u64
+    {
+        chunk_starts.push(chunk_start);
+    } else {
+        let mut chunk_start = offset.checked_sub(CHUNK_HEADER_SIZE as u64)? / page * page;
+        while offset + size as u64 <= chunk_start + CHUNK_SIZE as u64 {
+            chunk_starts.push(chunk_start);
+            if chunk_start < page {
+                break;
+            }
+            chunk_start -= page;
+        }
+    }
+
+    for chunk_start in chunk_starts {
+        let Some(chunk) = rebuild_chunk(data, base, chunk_start, offset, size, record_id) else {
+            continue;
+        };
+        let Ok(mut chunk_data) = EvtxChunkData::new(chunk, false) else {
+            continue;
+        };
+        let Ok(mut chunk) = chunk_data.parse(Arc::clone(&state.settings)) else {
+            continue;
+        };
+
+        let binxml_offset = offset - chunk_start + RECORD_HEADER_SIZE as u64;
+        let record = chunk.iter().flatten().find(|record| {
+            record.binxml_offset == binxml_offset && record.event_record_id == record_id
+        });
+        if let Some(record) = record
+            && let Ok(result) = record.into_json_value()
+        {
+            return Some(result);
+        }
+    }
+
+    None
+}
+
+/// Build a chunk containing the record at its original offset. Any data before the record may contain the strings and templates used by the record
+fn rebuild_chunk(
+    data: &[u8],
+    base: u64,
+    chunk_start: u64,
+    offset: u64,
+    size: usize,
+    record_id: u64,
+) -> Option<Vec<u8>> {
+    let relative = (offset - chunk_start) as usize;
+    let min_record = RECORD_HEADER_SIZE + 4;
+    let filler = relative.checked_sub(CHUNK_HEADER_SIZE)?;
+    if (filler != 0 && filler < min_record) || relative + size > CHUNK_SIZE {
+        return None;
+    }
+
+    let mut chunk = vec![0; CHUNK_SIZE];
+    // Copy the data we have before and including the record
+    let copy_start = (chunk_start + CHUNK_HEADER_SIZE as u64).max(base);
+    let copy_end = offset + size as u64;
+    let source = data.get((copy_start - base) as usize..(copy_end - base) as usize)?;
+    let target = (copy_start - chunk_start) as usize;
+    chunk[target..target + source.len()].copy_from_slice(source);
+
+    // Add a placeholder record so the parser can walk to our record
+    if filler != 0 {
+        chunk[CHUNK_HEADER_SIZE..CHUNK_HEADER_SIZE + 4].copy_from_slice(RECORD_SIGNATURE);
+        chunk[CHUNK_HEADER_SIZE + 4..CHUNK_HEADER_SIZE + 8]
+            .copy_from_slice(&(filler as u32).to_le_bytes());
+        chunk[CHUNK_HEADER_SIZE + 8..CHUNK_HEADER_SIZE + RECORD_HEADER_SIZE].fill(0);
+        chunk[relative - 4..relative].copy_from_slice(&(filler as u32).to_le_bytes());
+    }
+
+    chunk[..8].copy_from_slice(CHUNK_SIGNATURE);
+    for value_offset in [8, 16, 24, 32] {
+        chunk[value_offset..value_offset + 8].copy_from_slice(&record_id.to_le_bytes());
+    }
+    let header_size: u32 = 128;
+    chunk[40..44].copy_from_slice(&header_size.to_le_bytes());
+    chunk[44..48].copy_from_slice(&(relative as u32).to_le_bytes());
+    chunk[48..52].copy_from_slice(&((relative + size) as u32).to_le_bytes());
+    // Clear the string and template tables. Strings and templates are read directly from the chunk data
+    chunk[52..CHUNK_HEADER_SIZE].fill(0);
+
+    Some(chunk)
+}
+
+/// Output the carved records and any Sigma matches
+fn write_carved(
+    manager: &mut OutputManager,
+    options: &EventLogsOptions,
+    rules: &[SigmaRule],
+    hits: &mut Vec<SigmaHit>,
+    records: Vec<EventLogRecord>,
+) -> Result<(), EventLogsError> {
+    if records.is_empty() {
+        return Ok(());
+    }
+    hits.append(&mut evaluate_records(rules, &records));
+
+    let records = match serialize_records_to_stream(records) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Could not serialize carved logs: {err:?}");
+            return Err(EventLogsError::Serialize);
+        }
+    };
+    output_logs(manager, options, records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CHUNK_SIZE, CarveState, carve_data, carve_file, parse_chunk, rebuild_chunk};
+    use crate::{
+        filesystem::files::read_file,
+        output::manager::OutputManager,
+        structs::{
+            artifacts::os::windows::EventLogsOptions,
+            toml::{OutputConfig, OutputDestination, OutputFormat},
+        },
+        utils::sigma::rule::load_rules,
+    };
+    use std::path::PathBuf;
+
+    fn test_options() -> EventLogsOptions {
+        EventLogsOptions {
+            alt_file: None,
+            alt_dir: None,
+            include_templates: false,
+            dump_templates: false,
+            alt_template_file: None,
+            alt_template_image: None,
+            only_templates: false,
+            sigma_dir: None,
+            sigma_rules: None,
+            filter: None,
+            carve: Some(true),
+        }
+    }
+
+    /// Get the first chunk from a evtx file
+    fn test_chunk() -> Vec<u8> {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/eventlogs/System.evtx");
+        let data = read_file(test_location.to_str().unwrap()).unwrap();
+        let file_header = 4096;
+        data[file_header..file_header + CHUNK_SIZE].to_vec()
+    }
+
+    #[test]
+    fn test_parse_chunk() {
+        let state = CarveState::new("test", &test_options()).unwrap();
+        let records = parse_chunk(&test_chunk(), &state.settings);
+        assert_eq!(records.len(), 140);
+        assert_eq!(records[0].0, 512);
+        assert_eq!(records[1].0, 2136);
+        assert_eq!(records[1].1.event_record_id, 2);
+    }
+
+    #[test]
+    fn test_carve_data() {
+        // Chunk surrounded by other data
+        let mut data = vec![65; 8192];
+        data.extend(test_chunk());
+        data.extend(vec![0; 4096]);
+        let mut state = CarveState::new("test", &test_options()).unwrap();
+
+        let records = carve_data(&data, 0, data.len(), &mut state);
+        assert_eq!(records.len(), 140);
+        assert_eq!(records[0].offset, 8192 + 512);
+        assert!(records[0].carved);
+        assert_eq!(records[0].evidence, "test");
+    }
+
+    #[test]
+    fn test_carve_data_no_chunk_header() {
+        // The chunk header was overwritten. Records must be rebuilt
+        let mut chunk = test_chunk();
+        chunk[..512].fill(0);
+        let mut data = vec![0; 4096];
+        data.extend(chunk);
+        let mut state = CarveState::new("test", &test_options()).unwrap();
+
+        let records = carve_data(&data, 0, data.len(), &mut state);
+        assert_eq!(records.len(), 140);
+        assert_eq!(records[1].offset, 4096 + 2136);
+        assert_eq!(records[1].event_record_id, 2);
+        assert!(records[1].data.pointer("/Event/System/Provider").is_some());
+    }
+
+    #[test]
+    fn test_rebuild_chunk() {
+        let data = test_chunk();
+        let chunk = rebuild_chunk(&data, 0, 0, 2136, 232, 2).unwrap();
+        assert_eq!(&chunk[..8], b"ElfChnk\0");
+        assert_eq!(&chunk[512..516], b"**\0\0");
+        assert_eq!(&chunk[516..520], &1624_u32.to_le_bytes());
+        assert_eq!(&chunk[2136..2368], &data[2136..2368]);
+        assert!(rebuild_chunk(&data, 0, 0, 520, 232, 2).is_none());
+    }
+
+    #[test]
+    fn test_carve_file() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/eventlogs/Windows PowerShell.evtx");
+        let config = OutputConfig {
+            name: String::from("carve_eventlogs"),
+            directory: PathBuf::from("./tmp"),
+            format: OutputFormat::Jsonl,
+            compress: false,
+            endpoint_id: String::from("abcd"),
+            destination: OutputDestination::Local,
+            ..Default::default()
+        };
+        let mut output = OutputManager::new(config).unwrap();
+
+        carve_file(
+            test_location.to_str().unwrap(),
+            &mut output,
+            &test_options(),
+            &[],
+            &mut Vec::new(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_carve_file_sigma_hits() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/sigma");
+        let rules = load_rules(&Some(test_location.display().to_string()), &None);
+        test_location.pop();
+        test_location.push("eventlogs/Security.evtx");
+
+        let config = OutputConfig {
+            name: String::from("carve_eventlogs_sigma"),
+            directory: PathBuf::from("./tmp"),
+            format: OutputFormat::Parquet,
+            compress: false,
+            endpoint_id: String::from("abcd"),
+            destination: OutputDestination::Local,
+            ..Default::default()
+        };
+        let mut output = OutputManager::new(config).unwrap();
+        let mut hits = Vec::new();
+
+        carve_file(
+            test_location.to_str().unwrap(),
+            &mut output,
+            &test_options(),
+            &rules,
+            &mut hits,
+        )
+        .unwrap();
+        assert!(!hits.is_empty());
+        // Hits are returned to the caller. The eventlogs stream is never interrupted
+        assert!(output.artifact_runs.is_empty());
+    }
+}
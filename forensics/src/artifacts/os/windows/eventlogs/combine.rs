@@ -328,13 +328,13 @@ fn raw_data(data: &Value) -> Option<Value> {
 }
 
 #[derive(Debug)]
-struct EventId {
-    id: u64,
-    qualifier: u64,
+pub(crate) struct EventId {
+    pub(crate) id: u64,
+    pub(crate) qualifier: u64,
 }
 
 /// Get Event ID and Qualifier if available
-fn get_event_id(data: &Value) -> Option<EventId> {
+pub(crate) fn get_event_id(data: &Value) -> Option<EventId> {
     let id = data
         .as_object()?
         .get("Event")?
@@ -484,7 +484,7 @@ fn get_activity_id(data: &Value) -> Option<String> {
 }
 
 /// Get number values for various log keys
-fn get_meta_number(data: &Value, key: &str) -> Option<u64> {
+pub(crate) fn get_meta_number(data: &Value, key: &str) -> Option<u64> {
     // Sometimes key may not be included in log data
     // Seen in System Restore provider
     let default = Value::Number(Number::from(0));
@@ -505,7 +505,7 @@ fn get_meta_number(data: &Value, key: &str) -> Option<u64> {
 }
 
 /// Get strings values for various log keys
-fn get_meta_string(data: &Value, key: &str) -> Option<String> {
+pub(crate) fn get_meta_string(data: &Value, key: &str) -> Option<String> {
     // Sometimes key may not be included in log data
     // Seen in System Restore provider
     let default = Value::String(String::new());
@@ -521,7 +521,7 @@ fn get_meta_string(data: &Value, key: &str) -> Option<String> {
 }
 
 /// Get log provider
-fn get_provider(data: &Value) -> Option<&str> {
+pub(crate) fn get_provider(data: &Value) -> Option<&str> {
     let provider = &data
         .as_object()?
         .get("Event")?
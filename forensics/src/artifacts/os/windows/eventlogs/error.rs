@@ -11,6 +11,7 @@ pub enum EventLogsError {
     ReadTemplateFile,
     DeserializeTemplate,
     Output,
+    BadFilter,
 }
 
 impl std::error::Error for EventLogsError {}
@@ -27,6 +28,7 @@ impl fmt::Display for EventLogsError {
             EventLogsError::ReadTemplateFile => write!(f, "Could not read provided template file"),
             EventLogsError::DeserializeTemplate => write!(f, "Could not deserialize template data"),
             EventLogsError::Output => write!(f, "Could not write eventlog data"),
+            EventLogsError::BadFilter => write!(f, "Invalid eventlog filter"),
         }
     }
 }
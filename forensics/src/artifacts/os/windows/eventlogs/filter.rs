@@ -0,0 +1,276 @@
+use super::{
+    combine::{get_event_id, get_meta_number, get_meta_string, get_provider},
+    error::EventLogsError,
+};
+use crate::structs::artifacts::os::windows::EventLogsFilter;
+use chrono::DateTime;
+use serde_json::Value;
+use tracing::error;
+
+/// Parsed `EventLogsFilter` that is checked against every record before it is serialized
+#[derive(Debug, Default)]
+pub(crate) struct RecordFilter {
+    event_ids: Vec<(u64, u64)>,
+    providers: Vec<String>,
+    channels: Vec<String>,
+    levels: Vec<u64>,
+    start_record: Option<u64>,
+    end_record: Option<u64>,
+    /**Start date in nanoseconds since UNIX epoch */
+    start_date: Option<i128>,
+    /**End date in nanoseconds since UNIX epoch */
+    end_date: Option<i128>,
+}
+
+impl RecordFilter {
+    /// Validate and parse the filter options
+    pub(crate) fn new(filter: &Option<EventLogsFilter>) -> Result<RecordFilter, EventLogsError> {
+        let mut record_filter = RecordFilter::default();
+        let Some(filter) = filter else {
+            return Ok(record_filter);
+        };
+
+        for id in filter.event_ids.iter().flatten() {
+            record_filter.event_ids.push(parse_id_range(id)?);
+        }
+        record_filter.providers = filter
+            .providers
+            .iter()
+            .flatten()
+            .map(|provider| provider.to_lowercase())
+            .collect();
+        record_filter.channels = filter
+            .channels
+            .iter()
+            .flatten()
+            .map(|channel| channel.to_lowercase())
+            .collect();
+        record_filter.levels = filter
+            .levels
+            .iter()
+            .flatten()
+            .map(|level| *level as u64)
+            .collect();
+        record_filter.start_record = filter.start_record;
+        record_filter.end_record = filter.end_record;
+
+        if let Some(start) = &filter.start_date {
+            record_filter.start_date = Some(parse_date(start)?);
+        }
+        if let Some(end) = &filter.end_date {
+            record_filter.end_date = Some(parse_date(end)?);
+        }
+
+        Ok(record_filter)
+    }
+
+    /// Check if the record matches the filter. Timestamp is the record timestamp in nanoseconds since UNIX epoch
+    pub(crate) fn matches(&self, record_id: u64, timestamp: i128, data: &Value) -> bool {
+        if self.start_record.is_some_and(|start| record_id < start)
+            || self.end_record.is_some_and(|end| record_id > end)
+            || self.start_date.is_some_and(|start| timestamp < start)
+            || self.end_date.is_some_and(|end| timestamp > end)
+        {
+            return false;
+        }
+
+        if !self.event_ids.is_empty() {
+            let Some(event_id) = get_event_id(data) else {
+                return false;
+            };
+            if !self
+                .event_ids
+                .iter()
+                .any(|(start, end)| event_id.id >= *start && event_id.id <= *end)
+            {
+                return false;
+            }
+        }
+
+        if !self.providers.is_empty() {
+            let Some(provider) = get_provider(data) else {
+                return false;
+            };
+            if !self.providers.contains(&provider.to_lowercase()) {
+                return false;
+            }
+        }
+
+        if self.channels.is_empty() && self.levels.is_empty() {
+            return true;
+        }
+        let Some(meta) = data
+            .as_object()
+            .and_then(|event| event.get("Event"))
+            .and_then(|event| event.get("System"))
+        else {
+            return false;
+        };
+
+        if !self.channels.is_empty() {
+            let channel = get_meta_string(meta, "Channel").unwrap_or_default();
+            if !self.channels.contains(&channel.to_lowercase()) {
+                return false;
+            }
+        }
+
+        if !self.levels.is_empty() {
+            let level = get_meta_number(meta, "Level").unwrap_or_default();
+            if !self.levels.contains(&level) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Parse a single Event ID or an Event ID range. Ex: 4624 or 4624-4634
+fn parse_id_range(value: &str) -> Result<(u64, u64), EventLogsError> {
+    let range = match value.split_once('-') {
+        Some((start, end)) => (start.trim().parse(), end.trim().parse()),
+        None => (value.trim().parse(), value.trim().parse()),
+    };
+
+    match range {
+        (Ok(start), Ok(end)) if start <= end => Ok((start, end)),
+        _ => {
+            error!("[eventlogs] Invalid Event ID filter: {value}");
+            Err(EventLogsError::BadFilter)
+        }
+    }
+}
+
+/// Parse ISO 8601 date to nanoseconds
+fn parse_date(value: &str) -> Result<i128, EventLogsError> {
+    let timestamp = match DateTime::parse_from_rfc3339(value) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[eventlogs] Invalid date filter {value}: {err:?}");
+            return Err(EventLogsError::BadFilter);
+        }
+    };
+
+    let Some(nanos) = timestamp.timestamp_nanos_opt() else {
+        error!("[eventlogs] Date filter out of range: {value}");
+        return Err(EventLogsError::BadFilter);
+    };
+    Ok(nanos as i128)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RecordFilter, parse_date, parse_id_range};
+    use crate::{
+        artifacts::os::windows::eventlogs::parser::parse_eventlogs, filesystem::files::read_file,
+        structs::artifacts::os::windows::EventLogsFilter,
+    };
+    use common::windows::EventLogRecord;
+    use std::path::PathBuf;
+
+    fn test_record() -> EventLogRecord {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/eventlogs/samples/eventlog.json");
+        let data = read_file(test_location.to_str().unwrap()).unwrap();
+        serde_json::from_slice(&data).unwrap()
+    }
+
+    #[test]
+    fn test_record_filter() {
+        let record = test_record();
+        let timestamp = parse_date("2024-09-10T05:16:25.787335000Z").unwrap();
+        let filter = EventLogsFilter {
+            event_ids: Some(vec![String::from("1"), String::from("2000-2010")]),
+            providers: Some(vec![String::from("microsoft-windows-install-agent")]),
+            channels: Some(vec![String::from("Microsoft-Windows-Store/Operational")]),
+            levels: Some(vec![4]),
+            start_record: Some(25817),
+            end_record: None,
+            start_date: Some(String::from("2024-09-10T00:00:00Z")),
+            end_date: Some(String::from("2024-09-11T00:00:00Z")),
+        };
+        let record_filter = RecordFilter::new(&Some(filter)).unwrap();
+        assert!(record_filter.matches(record.event_record_id, timestamp, &record.data));
+    }
+
+    #[test]
+    fn test_record_filter_no_match() {
+        let record = test_record();
+        let timestamp = parse_date("2024-09-10T05:16:25.787335000Z").unwrap();
+
+        let mut filter = EventLogsFilter {
+            event_ids: Some(vec![String::from("4624")]),
+            ..Default::default()
+        };
+        let record_filter = RecordFilter::new(&Some(filter.clone())).unwrap();
+        assert!(!record_filter.matches(record.event_record_id, timestamp, &record.data));
+
+        filter.event_ids = None;
+        filter.levels = Some(vec![2, 3]);
+        let record_filter = RecordFilter::new(&Some(filter.clone())).unwrap();
+        assert!(!record_filter.matches(record.event_record_id, timestamp, &record.data));
+
+        filter.levels = None;
+        filter.end_date = Some(String::from("2024-09-10T05:00:00Z"));
+        let record_filter = RecordFilter::new(&Some(filter.clone())).unwrap();
+        assert!(!record_filter.matches(record.event_record_id, timestamp, &record.data));
+
+        filter.end_date = None;
+        filter.end_record = Some(100);
+        let record_filter = RecordFilter::new(&Some(filter)).unwrap();
+        assert!(!record_filter.matches(record.event_record_id, timestamp, &record.data));
+    }
+
+    #[test]
+    fn test_record_filter_empty() {
+        let record = test_record();
+        let record_filter = RecordFilter::new(&None).unwrap();
+        assert!(record_filter.matches(record.event_record_id, 0, &record.data));
+    }
+
+    #[test]
+    fn test_parse_id_range() {
+        assert_eq!(parse_id_range("4624").unwrap(), (4624, 4624));
+        assert_eq!(parse_id_range("4624 - 4634").unwrap(), (4624, 4634));
+        assert!(parse_id_range("4634-4624").is_err());
+        assert!(parse_id_range("abc").is_err());
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(parse_date("1970-01-01T00:00:01Z").unwrap(), 1_000_000_000);
+        assert!(parse_date("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_parse_eventlogs_filtered_pages() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/eventlogs/System.evtx");
+        let path = test_location.to_str().unwrap();
+        let filter = Some(EventLogsFilter {
+            levels: Some(vec![4]),
+            ..Default::default()
+        });
+
+        let (_, all) = parse_eventlogs(path, 0, usize::MAX, false, &None, &None, &filter).unwrap();
+        assert!(all.len() > 20);
+
+        let limit = 7;
+        let mut offset = 0;
+        let mut pages = Vec::new();
+        loop {
+            let (_, mut page) =
+                parse_eventlogs(path, offset, limit, false, &None, &None, &filter).unwrap();
+            if page.is_empty() {
+                break;
+            }
+            assert!(page.len() <= limit);
+            offset += page.len();
+            pages.append(&mut page);
+        }
+
+        let paged_ids: Vec<u64> = pages.iter().map(|record| record.event_record_id).collect();
+        let all_ids: Vec<u64> = all.iter().map(|record| record.event_record_id).collect();
+        assert_eq!(paged_ids, all_ids);
+    }
+}
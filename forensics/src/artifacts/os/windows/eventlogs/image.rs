@@ -0,0 +1,363 @@
+/**
+ * Build the `EventLog` message strings from a mounted or acquired Windows image instead of the live system
+ * The SYSTEM and SOFTWARE Registry files and the provider PE files (DLL, MUI, MUN) are all read from the image
+ * The image root can be any path supported by the `Accessor`. Ex: /mnt/windows, vss:C@3!/, ewf:case.E01!p2/
+ *
+ * The strings can be dumped with `dump_templates` and then provided via `alt_template_file` to render messages on any platform
+ *
+ * References:
+ * `https://learn.microsoft.com/en-us/windows/win32/eventlog/message-files`
+ * `https://learn.microsoft.com/en-us/windows/win32/intl/mui-resource-management`
+ */
+use super::{
+    error::EventLogsError,
+    resources::mui::mui_language,
+    strings::{StringResource, TemplateResource, parse_resource, registry_paths},
+};
+use crate::{
+    accessor::{
+        access::Accessor,
+        entry::handle::{DirEntry, EntryKind, ItemHandle},
+    },
+    artifacts::os::windows::{
+        pe::resources::{EventLogResource, parse_eventlog_resource},
+        registry::helper::get_registry_keys,
+    },
+    filesystem::{directory::get_parent_directory, files::get_filename},
+    utils::regex_options::create_regex,
+};
+use std::collections::HashMap;
+use tracing::error;
+
+/// Parse and extract eventlog string resources from a Windows image
+pub(crate) fn get_image_resources(root: &str) -> Result<StringResource, EventLogsError> {
+    let mut image = ImageFiles::new(root);
+
+    let Some(system) = image.resolve("C:\\Windows\\System32\\config\\SYSTEM") else {
+        error!("[eventlog] Could not find SYSTEM Registry file in image {root}");
+        return Err(EventLogsError::EventLogServices);
+    };
+    // Grab eventlog resource registry paths
+    let mut reg_paths = match get_registry_keys(
+        "ROOT",
+        &create_regex(r".*\\controlset.*\\services\\eventlog\\.*").unwrap(),
+        &system,
+    ) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[eventlog] Could not parse image registry for eventlog services: {err:?}");
+            return Err(EventLogsError::EventLogServices);
+        }
+    };
+
+    // SOFTWARE is optional. Older providers are only registered in the SYSTEM Registry file
+    let mut reg_software_paths = Vec::new();
+    if let Some(software) = image.resolve("C:\\Windows\\System32\\config\\SOFTWARE") {
+        match get_registry_keys(
+            "ROOT",
+            &create_regex(r".*\\microsoft\\windows\\currentversion\\winevt\\publishers\\.*")
+                .unwrap(),
+            &software,
+        ) {
+            Ok(result) => reg_software_paths = result,
+            Err(err) => {
+                error!(
+                    "[eventlog] Could not parse image registry for eventlog publishers: {err:?}"
+                );
+            }
+        }
+    }
+
+    let mut resources = StringResource {
+        providers: HashMap::new(),
+        templates: HashMap::new(),
+    };
+    let update_env = image_env();
+    registry_paths(&mut reg_paths, &mut resources.providers, &update_env);
+    registry_paths(
+        &mut reg_software_paths,
+        &mut resources.providers,
+        &update_env,
+    );
+
+    // Now go through and parse all PE files in the image associated with the EventLog providers
+    for provider in resources.providers.values() {
+        for file in provider
+            .message_file
+            .iter()
+            .chain(provider.parameter_file.iter())
+        {
+            update_image_resource(&mut image, &mut resources.templates, file);
+        }
+    }
+
+    Ok(resources)
+}
+
+/// We cannot use the ENV values of the host. Use the default Windows values instead
+fn image_env() -> HashMap<String, String> {
+    let mut envs = HashMap::new();
+    for (key, value) in [
+        ("systemroot", "C:\\Windows"),
+        ("windir", "C:\\Windows"),
+        ("systemdrive", "C:"),
+        ("programfiles", "C:\\Program Files"),
+        ("programfiles(x86)", "C:\\Program Files (x86)"),
+        ("programw6432", "C:\\Program Files"),
+        ("commonprogramfiles", "C:\\Program Files\\Common Files"),
+        (
+            "commonprogramfiles(x86)",
+            "C:\\Program Files (x86)\\Common Files",
+        ),
+        ("programdata", "C:\\ProgramData"),
+    ] {
+        envs.insert(key.to_string(), value.to_string());
+    }
+    envs
+}
+
+/// Parse the PE file in the image and track the eventlog strings
+fn update_image_resource(
+    image: &mut ImageFiles,
+    templates: &mut HashMap<String, TemplateResource>,
+    file: &str,
+) {
+    // Check if we already parsed this PE file
+    if templates.contains_key(file) {
+        return;
+    }
+
+    let mut real_path = file.to_string();
+    // Sometimes we do not get a full path and just a filename. Default to system32
+    if !real_path.contains('\\') {
+        real_path = format!("C:\\Windows\\System32\\{file}");
+    }
+
+    let bytes = if let Some(result) = image.read(&real_path) {
+        result
+    } else {
+        // File does not exist. It might be in a locale subdirectory
+        let parent = get_parent_directory(&real_path);
+        real_path = format!("{parent}\\en-US\\{}.mui", get_filename(&real_path));
+        // no idea where it is
+        let Some(result) = image.read(&real_path) else {
+            return;
+        };
+        result
+    };
+
+    let mut resource_data = match parse_eventlog_resource(&bytes, &real_path) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[eventlog] Could not parse PE resource {real_path}: {err:?}");
+            return;
+        }
+    };
+    add_mui_resources(image, &mut resource_data);
+
+    if resource_data.message_data.is_empty() && resource_data.wevt_data.is_empty() {
+        return;
+    }
+
+    let mut temp_info = TemplateResource {
+        path: real_path,
+        resource_data,
+        message_table: None,
+        wevt_template: None,
+    };
+    let _ = parse_resource(&mut temp_info);
+
+    // We are done parsing the resource data. Empty the bytes so we are not carrying them around
+    temp_info.resource_data.message_data = Vec::new();
+    temp_info.resource_data.wevt_data = Vec::new();
+
+    templates.insert(file.to_string(), temp_info);
+}
+
+/**
+ * The `MESSAGETABLE` and `WEVT_TEMPLATE` resources may be in the localized MUI file or in a MUN file
+ * `parse_resource` would look for these files on the host, so we merge them from the image and drop the MUI data
+ */
+fn add_mui_resources(image: &mut ImageFiles, resource: &mut EventLogResource) {
+    let mui_data = std::mem::take(&mut resource.mui_data);
+    if mui_data.is_empty() || (!resource.message_data.is_empty() && !resource.wevt_data.is_empty())
+    {
+        return;
+    }
+
+    let filename = get_filename(&resource.path);
+    if !resource.path.ends_with(".mui")
+        && let Ok((_, lang)) = mui_language(&mui_data)
+    {
+        let parent = get_parent_directory(&resource.path);
+        let mui_path = format!("{parent}\\{lang}\\{filename}.mui");
+        if let Some(bytes) = image.read(&mui_path)
+            && let Ok(mui_resource) = parse_eventlog_resource(&bytes, &mui_path)
+        {
+            if resource.message_data.is_empty() {
+                resource.message_data = mui_resource.message_data;
+            }
+            if resource.wevt_data.is_empty() {
+                resource.wevt_data = mui_resource.wevt_data;
+            }
+        }
+    }
+
+    if !resource.wevt_data.is_empty() {
+        return;
+    }
+    let mun_name = filename.trim_end_matches(".mui");
+    let mun_path = format!("C:\\Windows\\SystemResources\\{mun_name}.mun");
+    if let Some(bytes) = image.read(&mun_path)
+        && let Ok(mun_resource) = parse_eventlog_resource(&bytes, &mun_path)
+    {
+        resource.wevt_data = mun_resource.wevt_data;
+    }
+}
+
+/// Resolve Windows paths to files in the image. Image paths may not preserve the case of the Windows path
+struct ImageFiles {
+    root: String,
+    accessor: Accessor,
+    /**Cache of directory entries. Key is the image directory path */
+    directories: HashMap<String, Vec<DirEntry>>,
+}
+
+impl ImageFiles {
+    fn new(root: &str) -> Self {
+        Self {
+            root: root.trim_end_matches(['/', '\\']).to_string(),
+            accessor: Accessor::with_defaults(),
+            directories: HashMap::new(),
+        }
+    }
+
+    /// Convert a Windows path to a path in the image. Ex: C:\Windows\System32\wer.dll -> /mnt/windows/Windows/System32/wer.dll
+    fn resolve(&mut self, path: &str) -> Option<String> {
+        let path = match path.split_once(':') {
+            Some((drive, remaining)) if drive.len() == 1 => remaining,
+            _ => path,
+        };
+
+        let mut current = self.root.clone();
+        // Child directories are listed with the handle from the parent listing instead of walking the image path again
+        let mut handle = None;
+        for component in path.split(['\\', '/']).filter(|value| !value.is_empty()) {
+            let directory = if current.is_empty() || current.ends_with('!') {
+                format!("{current}/")
+            } else {
+                current.clone()
+            };
+
+            if !self.directories.contains_key(&directory) {
+                let listing = match &handle {
+                    Some(dir_handle) => self.accessor.read_dir_handle(dir_handle),
+                    None => self.accessor.read_dir(&directory),
+                };
+                let entries = match listing {
+                    Ok(result) => result
+                        .into_iter()
+                        .filter(|entry| entry.meta.kind != EntryKind::Unsupported)
+                        .collect(),
+                    Err(_err) => Vec::new(),
+                };
+                self.directories.insert(directory.clone(), entries);
+            }
+
+            let entry = self
+                .directories
+                .get(&directory)?
+                .iter()
+                .find(|entry| entry.name.eq_ignore_ascii_case(component))?;
+            handle = match &entry.handle {
+                ItemHandle::Directory(dir_handle) => Some(dir_handle.clone()),
+                _ => None,
+            };
+            current = format!("{current}/{}", entry.name);
+        }
+
+        Some(current)
+    }
+
+    /// Read a file in the image using the Windows path
+    fn read(&mut self, path: &str) -> Option<Vec<u8>> {
+        let image_path = self.resolve(path)?;
+        match self.accessor.read_file(&image_path) {
+            Ok(result) => Some(result),
+            Err(err) => {
+                error!("[eventlog] Could not read {image_path} from image: {err}");
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ImageFiles, add_mui_resources, get_image_resources, update_image_resource};
+    use crate::{
+        artifacts::os::windows::{
+            eventlogs::resources::mui::mui_language, pe::resources::parse_eventlog_resource,
+        },
+        filesystem::files::read_file,
+    };
+    use std::{collections::HashMap, path::PathBuf};
+
+    fn image_root() -> String {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/eventlogs/image");
+        test_location.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_resolve() {
+        let mut image = ImageFiles::new(&image_root());
+        let path = image
+            .resolve("C:\\WINDOWS\\system32\\EVENTLOG_PROVIDER.dll")
+            .unwrap();
+        assert!(path.ends_with("/Windows/System32/eventlog_provider.dll"));
+        assert!(
+            image
+                .resolve("C:\\Windows\\System32\\missing.dll")
+                .is_none()
+        );
+        assert!(image.read("%SystemRoot%\\System32\\wer.dll").is_none());
+    }
+
+    #[test]
+    fn test_update_image_resource() {
+        let mut image = ImageFiles::new(&image_root());
+        let mut templates = HashMap::new();
+        update_image_resource(&mut image, &mut templates, "eventlog_provider.dll");
+
+        let template = templates.get("eventlog_provider.dll").unwrap();
+        assert!(template.path.ends_with("System32\\eventlog_provider.dll"));
+        assert!(!template.message_table.as_ref().unwrap().is_empty());
+        assert!(template.resource_data.message_data.is_empty());
+
+        update_image_resource(&mut image, &mut templates, "C:\\Windows\\missing.dll");
+        assert_eq!(templates.len(), 1);
+    }
+
+    #[test]
+    fn test_add_mui_resources() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/pe/resources/winml.dll");
+        let bytes = read_file(test_location.to_str().unwrap()).unwrap();
+        let mut resource =
+            parse_eventlog_resource(&bytes, "C:\\Windows\\System32\\winml.dll").unwrap();
+
+        let (_, lang) = mui_language(&resource.mui_data).unwrap();
+        assert_eq!(lang, "en-US");
+
+        let mut image = ImageFiles::new(&image_root());
+        add_mui_resources(&mut image, &mut resource);
+        assert!(resource.mui_data.is_empty());
+        assert_eq!(resource.wevt_data.len(), 2830);
+    }
+
+    #[test]
+    fn test_get_image_resources_no_registry() {
+        assert!(get_image_resources(&image_root()).is_err());
+    }
+}
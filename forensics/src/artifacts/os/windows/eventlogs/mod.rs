@@ -1,6 +1,9 @@
+mod carve;
 mod combine;
 mod error;
+mod filter;
 mod formatters;
+mod image;
 pub(crate) mod parser;
 mod resources;
 mod strings;
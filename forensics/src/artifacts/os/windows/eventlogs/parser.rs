@@ -10,22 +10,30 @@
  *  Windows Event Viewer
  */
 use super::{
+    carve::{carve_drive, carve_file},
     combine::add_message_strings,
     error::EventLogsError,
+    filter::RecordFilter,
+    image::get_image_resources,
     strings::{StringResource, get_resources},
 };
 use crate::{
-    filesystem::files::{file_extension, list_files, read_file},
+    accessor::io::reader::AccessorReader,
+    filesystem::files::{file_extension, list_files_any, read_file, reader_any},
     output::{
         manager::OutputManager,
         record::{
             SingleRecordStream, VecRecordStream, serialize_records_to_stream, serialize_to_record,
         },
     },
-    structs::artifacts::os::windows::EventLogsOptions,
-    utils::{environment::get_systemdrive, regex_options::create_regex},
+    structs::artifacts::os::windows::{EventLogsFilter, EventLogsOptions},
+    utils::{
+        environment::get_systemdrive,
+        regex_options::create_regex,
+        sigma::rule::{SigmaRule, evaluate_records, load_rules},
+    },
 };
-use common::windows::{EventLogRecord, EventMessage};
+use common::windows::{EventLogRecord, EventMessage, SigmaHit};
 use evtx::EvtxParser;
 use tracing::{error, warn};
 
@@ -34,46 +42,64 @@ pub(crate) fn grab_eventlogs(
     options: &EventLogsOptions,
     manager: &mut OutputManager,
 ) -> Result<(), EventLogsError> {
+    let rules = load_rules(&options.sigma_dir, &options.sigma_rules);
+    let mut hits = Vec::new();
+    let result = collect_eventlogs(options, manager, &rules, &mut hits);
+    // Sigma hits are output once so they do not interrupt the eventlogs output
+    output_sigma_hits(manager, options, hits);
+    result
+}
+
+/// Parse or carve `EventLogs` and evaluate the records against any Sigma rules
+fn collect_eventlogs(
+    options: &EventLogsOptions,
+    manager: &mut OutputManager,
+    rules: &[SigmaRule],
+    hits: &mut Vec<SigmaHit>,
+) -> Result<(), EventLogsError> {
+    let carve = options.carve.unwrap_or_default();
     if let Some(file) = &options.alt_file {
-        return alt_eventlogs(file, manager, options);
+        // Carving can scan any file. Ex: a memory dump or disk image
+        if carve {
+            return carve_file(file, manager, options, rules, hits);
+        }
+        return alt_eventlogs(file, manager, options, rules, hits);
     }
 
-    default_eventlogs(manager, options)
+    let result = default_eventlogs(manager, options, rules, hits);
+    if !carve {
+        return result;
+    }
+
+    // The EventLogs may have been cleared or deleted. We still want to try carving old records
+    if let Err(err) = result {
+        error!("Could not parse eventlogs. Will still carve unallocated space: {err:?}");
+    }
+    let drive = match get_systemdrive() {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Could not determine systemdrive: {err:?}");
+            return Err(EventLogsError::DefaultDrive);
+        }
+    };
+    carve_drive(drive, manager, options, rules, hits)
 }
 
-/// Parse the `EventLog` evtx file at provided path
+/// Parse the `EventLog` evtx file at provided path. Skips the first `offset` records that match the filter and returns up to `limit` records
 pub(crate) fn parse_eventlogs(
     path: &str,
     offset: usize,
     limit: usize,
     include_templates: bool,
     template_file: &Option<String>,
+    template_image: &Option<String>,
+    filter: &Option<EventLogsFilter>,
 ) -> Result<(Vec<EventMessage>, Vec<EventLogRecord>), EventLogsError> {
-    let templates = if include_templates && template_file.is_none() {
-        Some(get_resources()?)
-    } else if template_file.is_some() {
-        let file = template_file.as_ref().unwrap();
-        let bytes = match read_file(file) {
-            Ok(result) => result,
-            Err(err) => {
-                error!("Failed to read template file: {err:?}");
-                return Err(EventLogsError::ReadTemplateFile);
-            }
-        };
-
-        match serde_json::from_slice(&bytes) {
-            Ok(result) => result,
-            Err(err) => {
-                error!("Failed to deserialize template data: {err:?}");
-                return Err(EventLogsError::DeserializeTemplate);
-            }
-        }
-    } else {
-        None
-    };
+    let templates = load_templates(include_templates, template_file, template_image)?;
+    let record_filter = RecordFilter::new(filter)?;
 
-    let evt_parser_results = EvtxParser::from_path(path);
-    let mut evt_parser = match evt_parser_results {
+    let evt_parser_results = open_eventlog(path)?;
+    let mut evt_parser = match EvtxParser::from_read_seek(evt_parser_results) {
         Ok(result) => result,
         Err(err) => {
             error!("Failed to parse event log {path}, error: {err:?}");
@@ -86,14 +112,29 @@ pub(crate) fn parse_eventlogs(
     let param_regex = create_regex(r"(%\d!.*?!)|(%\d+)").unwrap();
     let value_regex = create_regex(r"%%\d+").unwrap();
 
-    for record in evt_parser.records_json_value().skip(offset) {
+    // Offset counts records that match the filter. So the caller can page through filtered records
+    let mut matched = 0;
+    for record in evt_parser.records_json_value() {
         match record {
             Ok(data) => {
+                if !record_filter.matches(
+                    data.event_record_id,
+                    data.timestamp.as_nanosecond(),
+                    &data.data,
+                ) {
+                    continue;
+                }
+                matched += 1;
+                if matched <= offset {
+                    continue;
+                }
                 let event_record = EventLogRecord {
                     event_record_id: data.event_record_id,
                     timestamp: data.timestamp.to_string(),
                     data: data.data,
                     evidence: path.to_string(),
+                    carved: false,
+                    offset: 0,
                 };
                 eventlog_records.push(event_record);
             }
@@ -134,10 +175,52 @@ pub(crate) fn parse_eventlogs(
     Ok((messages, raw_message))
 }
 
+/**
+ * Get the `EventLog` provider strings. Priority is:
+ * 1. Previously dumped template file
+ * 2. Mounted or acquired Windows image
+ * 3. Live system
+ */
+fn load_templates(
+    include_templates: bool,
+    template_file: &Option<String>,
+    template_image: &Option<String>,
+) -> Result<Option<StringResource>, EventLogsError> {
+    if let Some(file) = template_file {
+        let bytes = match read_file(file) {
+            Ok(result) => result,
+            Err(err) => {
+                error!("Failed to read template file: {err:?}");
+                return Err(EventLogsError::ReadTemplateFile);
+            }
+        };
+
+        return match serde_json::from_slice(&bytes) {
+            Ok(result) => Ok(result),
+            Err(err) => {
+                error!("Failed to deserialize template data: {err:?}");
+                Err(EventLogsError::DeserializeTemplate)
+            }
+        };
+    }
+
+    if let Some(image) = template_image {
+        return Ok(Some(get_image_resources(image)?));
+    }
+
+    if include_templates {
+        return Ok(Some(get_resources()?));
+    }
+
+    Ok(None)
+}
+
 /// Read and parse `EventLog` files at default Windows path. Typically C:\Windows\System32\winevt
 fn default_eventlogs(
     manager: &mut OutputManager,
     options: &EventLogsOptions,
+    rules: &[SigmaRule],
+    hits: &mut Vec<SigmaHit>,
 ) -> Result<(), EventLogsError> {
     let path = if let Some(alt_dir) = &options.alt_dir {
         alt_dir
@@ -153,7 +236,7 @@ fn default_eventlogs(
         &format!("{drive}:\\Windows\\System32\\winevt\\Logs")
     };
 
-    read_directory(path, manager, options)
+    read_directory(path, manager, options, rules, hits)
 }
 
 /// Read and parse `EventLog` files with alternative path
@@ -161,29 +244,14 @@ fn alt_eventlogs(
     path: &str,
     manager: &mut OutputManager,
     options: &EventLogsOptions,
+    rules: &[SigmaRule],
+    hits: &mut Vec<SigmaHit>,
 ) -> Result<(), EventLogsError> {
-    let templates = if options.include_templates && options.alt_template_file.is_none() {
-        Some(get_resources()?)
-    } else if options.alt_template_file.is_some() {
-        let template_file = options.alt_template_file.as_ref().unwrap();
-        let bytes = match read_file(template_file) {
-            Ok(result) => result,
-            Err(err) => {
-                error!("Failed to read template file: {err:?}");
-                return Err(EventLogsError::ReadTemplateFile);
-            }
-        };
-
-        match serde_json::from_slice(&bytes) {
-            Ok(result) => result,
-            Err(err) => {
-                error!("Failed to deserialize template data: {err:?}");
-                return Err(EventLogsError::DeserializeTemplate);
-            }
-        }
-    } else {
-        None
-    };
+    let templates = load_templates(
+        options.include_templates,
+        &options.alt_template_file,
+        &options.alt_template_image,
+    )?;
 
     if let Some(template) = templates.as_ref()
         && options.dump_templates
@@ -208,7 +276,7 @@ fn alt_eventlogs(
         }
     }
 
-    read_eventlogs(path, manager, options, &templates)
+    read_eventlogs(path, manager, options, &templates, rules, hits)
 }
 
 /// Read all files at provided path
@@ -216,9 +284,11 @@ fn read_directory(
     path: &str,
     manager: &mut OutputManager,
     options: &EventLogsOptions,
+    rules: &[SigmaRule],
+    hits: &mut Vec<SigmaHit>,
 ) -> Result<(), EventLogsError> {
-    let dir_results = list_files(path);
-    let read_dir = match dir_results {
+    // EventLog directories in disk images or snapshots. Ex: vss:C@3!/Windows/System32/winevt/Logs
+    let read_dir = match list_files_any(path) {
         Ok(result) => result,
         Err(err) => {
             error!("Failed to get eventlogs files {path}, error: {err:?}");
@@ -226,28 +296,11 @@ fn read_directory(
         }
     };
 
-    let templates = if options.include_templates && options.alt_template_file.is_none() {
-        Some(get_resources()?)
-    } else if options.alt_template_file.is_some() {
-        let template_file = options.alt_template_file.as_ref().unwrap();
-        let bytes = match read_file(template_file) {
-            Ok(result) => result,
-            Err(err) => {
-                error!("Failed to read template file: {err:?}");
-                return Err(EventLogsError::ReadTemplateFile);
-            }
-        };
-
-        match serde_json::from_slice(&bytes) {
-            Ok(result) => result,
-            Err(err) => {
-                error!("Failed to deserialize template data: {err:?}");
-                return Err(EventLogsError::DeserializeTemplate);
-            }
-        }
-    } else {
-        None
-    };
+    let templates = load_templates(
+        options.include_templates,
+        &options.alt_template_file,
+        &options.alt_template_image,
+    )?;
 
     if let Some(template) = templates.as_ref()
         && options.dump_templates
@@ -277,8 +330,13 @@ fn read_directory(
         if file_extension(&evtx_file) != "evtx" {
             continue;
         }
+        if manager.cancel.is_cancelled() {
+            warn!("Collection cancelled. Skipping remaining eventlogs");
+            break;
+        }
 
-        let eventlogs_results = read_eventlogs(&evtx_file, manager, options, &templates);
+        let eventlogs_results =
+            read_eventlogs(&evtx_file, manager, options, &templates, rules, hits);
         match eventlogs_results {
             Ok(_) => (),
             Err(err) => {
@@ -290,15 +348,28 @@ fn read_directory(
     Ok(())
 }
 
+/// Open a reader for the `EventLog` file. Supports disk images and snapshots. Ex: vss:C@3!/Windows/System32/winevt/Logs/System.evtx
+pub(crate) fn open_eventlog(path: &str) -> Result<AccessorReader, EventLogsError> {
+    match reader_any(path) {
+        Ok(result) => Ok(result),
+        Err(err) => {
+            error!("Failed to open event log {path}, error: {err:?}");
+            Err(EventLogsError::Parser)
+        }
+    }
+}
+
 /// Read and parse the `EventLog` file
 fn read_eventlogs(
     path: &str,
     manager: &mut OutputManager,
     options: &EventLogsOptions,
     resources: &Option<StringResource>,
+    rules: &[SigmaRule],
+    hits: &mut Vec<SigmaHit>,
 ) -> Result<(), EventLogsError> {
-    let evt_parser_results = EvtxParser::from_path(path);
-    let mut evt_parser = match evt_parser_results {
+    let evt_parser_results = open_eventlog(path)?;
+    let mut evt_parser = match EvtxParser::from_read_seek(evt_parser_results) {
         Ok(result) => result,
         Err(err) => {
             error!("Failed to parse event log {path}, error: {err:?}");
@@ -306,19 +377,34 @@ fn read_eventlogs(
         }
     };
 
+    let record_filter = RecordFilter::new(&options.filter)?;
     let mut eventlog_records: Vec<EventLogRecord> = Vec::new();
     let limit = 1000;
     // Regex always correct
     let param_regex = create_regex(r"(%\d!.*?!)|(%\d+)").unwrap();
     let value_regex = create_regex(r"%%\d+").unwrap();
     for record in evt_parser.records_json_value() {
+        // Stop parsing if the collection has been cancelled
+        if manager.cancel.is_cancelled() {
+            warn!("Collection cancelled. Stopping eventlog parsing for {path}");
+            break;
+        }
         match record {
             Ok(data) => {
+                if !record_filter.matches(
+                    data.event_record_id,
+                    data.timestamp.as_nanosecond(),
+                    &data.data,
+                ) {
+                    continue;
+                }
                 let event_record = EventLogRecord {
                     event_record_id: data.event_record_id,
                     timestamp: data.timestamp.to_string(),
                     data: data.data,
                     evidence: path.to_string(),
+                    carved: false,
+                    offset: 0,
                 };
                 eventlog_records.push(event_record);
             }
@@ -329,6 +415,7 @@ fn read_eventlogs(
         }
 
         if eventlog_records.len() == limit {
+            hits.append(&mut evaluate_records(rules, &eventlog_records));
             let (messages, raw_output) = if let Some(resource) = resources {
                 let mut all_messages = Vec::new();
                 let mut raw_messages = Vec::new();
@@ -380,6 +467,7 @@ fn read_eventlogs(
     }
 
     if !eventlog_records.is_empty() {
+        hits.append(&mut evaluate_records(rules, &eventlog_records));
         let (messages, raw_output) = if let Some(resource) = resources {
             let mut all_messages = Vec::new();
             let mut raw_messages = Vec::new();
@@ -428,7 +516,7 @@ fn read_eventlogs(
 }
 
 /// Output log results
-fn output_logs(
+pub(crate) fn output_logs(
     manager: &mut OutputManager,
     options: &EventLogsOptions,
     mut records: VecRecordStream,
@@ -442,6 +530,25 @@ fn output_logs(
     Ok(())
 }
 
+/// Output the Sigma matches for all parsed records
+fn output_sigma_hits(manager: &mut OutputManager, options: &EventLogsOptions, hits: Vec<SigmaHit>) {
+    if hits.is_empty() {
+        return;
+    }
+
+    let mut records = match serialize_records_to_stream(hits) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Could not serialize sigma hits: {err:?}");
+            return;
+        }
+    };
+    let artifact_name = "sigma_hits";
+    if let Err(err) = manager.write_artifact(artifact_name, options, &mut records) {
+        error!("Failed to output sigma hits: {err:?}");
+    }
+}
+
 #[cfg(test)]
 #[cfg(target_os = "windows")]
 mod tests {
@@ -473,7 +580,12 @@ mod tests {
             dump_templates: false,
             alt_dir: None,
             alt_template_file: None,
+            alt_template_image: None,
+            filter: None,
+            carve: None,
             only_templates: false,
+            sigma_dir: None,
+            sigma_rules: None,
         };
         let mut output = output_options("eventlog_temp", "./tmp", true);
 
@@ -490,10 +602,15 @@ mod tests {
             dump_templates: false,
             alt_dir: None,
             alt_template_file: None,
+            alt_template_image: None,
+            filter: None,
+            carve: None,
             only_templates: false,
+            sigma_dir: None,
+            sigma_rules: None,
         };
 
-        let results = default_eventlogs(&mut output, &options).unwrap();
+        let results = default_eventlogs(&mut output, &options, &[], &mut Vec::new()).unwrap();
         assert_eq!(results, ())
     }
 
@@ -509,10 +626,15 @@ mod tests {
             dump_templates: false,
             alt_dir: None,
             alt_template_file: None,
+            alt_template_image: None,
+            filter: None,
+            carve: None,
             only_templates: false,
+            sigma_dir: None,
+            sigma_rules: None,
         };
 
-        let results = alt_eventlogs(&path, &mut output, &options).unwrap();
+        let results = alt_eventlogs(&path, &mut output, &options, &[], &mut Vec::new()).unwrap();
         assert_eq!(results, ())
     }
 
@@ -527,11 +649,22 @@ mod tests {
             dump_templates: false,
             alt_dir: None,
             alt_template_file: None,
+            alt_template_image: None,
+            filter: None,
+            carve: None,
             only_templates: false,
+            sigma_dir: None,
+            sigma_rules: None,
         };
 
-        let results =
-            read_directory(&test_location.display().to_string(), &mut output, &options).unwrap();
+        let results = read_directory(
+            &test_location.display().to_string(),
+            &mut output,
+            &options,
+            &[],
+            &mut Vec::new(),
+        )
+        .unwrap();
         assert_eq!(results, ())
     }
 
@@ -546,7 +679,12 @@ mod tests {
             dump_templates: false,
             alt_dir: None,
             alt_template_file: None,
+            alt_template_image: None,
+            filter: None,
+            carve: None,
             only_templates: false,
+            sigma_dir: None,
+            sigma_rules: None,
         };
         for file_path in read_dir {
             if file_path.as_ref().unwrap().file_type().unwrap().is_dir() {
@@ -559,6 +697,8 @@ mod tests {
                 &mut output,
                 &options,
                 &None,
+                &[],
+                &mut Vec::new(),
             )
             .unwrap();
             assert_eq!(results, ())
@@ -17,50 +17,7 @@ pub(crate) fn parse_mui<'a>(
     data: &'a [u8],
     path: &str,
 ) -> nom::IResult<&'a [u8], EventLogResource> {
-    let (input, _sig) = nom_unsigned_four_bytes(data, Endian::Le)?;
-    // Size is the entire data
-    let (input, _size) = nom_unsigned_four_bytes(input, Endian::Le)?;
-    let (input, _version) = nom_unsigned_four_bytes(input, Endian::Le)?;
-    let (input, _unknown) = nom_unsigned_four_bytes(input, Endian::Le)?;
-    let (input, _file_type) = nom_unsigned_four_bytes(input, Endian::Le)?;
-    let (input, _attributes) = nom_unsigned_four_bytes(input, Endian::Le)?;
-    let (input, _fallback_location) = nom_unsigned_four_bytes(input, Endian::Le)?;
-
-    let checksum_size: u8 = 16;
-    let (input, _service_checksum) = take(checksum_size)(input)?;
-    let (input, _checksum) = take(checksum_size)(input)?;
-
-    let unknown_size: u8 = 24;
-    let (input, _unknown2) = take(unknown_size)(input)?;
-
-    let (input, _main_name_offset) = nom_unsigned_four_bytes(input, Endian::Le)?;
-    let (input, _main_name_size) = nom_unsigned_four_bytes(input, Endian::Le)?;
-
-    let (input, _main_id_offset) = nom_unsigned_four_bytes(input, Endian::Le)?;
-    let (input, _main_id_size) = nom_unsigned_four_bytes(input, Endian::Le)?;
-
-    let (input, _main_name_type_offset) = nom_unsigned_four_bytes(input, Endian::Le)?;
-    let (input, _main_name_type_size) = nom_unsigned_four_bytes(input, Endian::Le)?;
-
-    let (input, _main_type_offset) = nom_unsigned_four_bytes(input, Endian::Le)?;
-    let (input, _main_type_size) = nom_unsigned_four_bytes(input, Endian::Le)?;
-
-    let (input, lang_offset) = nom_unsigned_four_bytes(input, Endian::Le)?;
-    let (input, lang_size) = nom_unsigned_four_bytes(input, Endian::Le)?;
-
-    let (input, fallback_lang_offset) = nom_unsigned_four_bytes(input, Endian::Le)?;
-    let (_input, fallback_lang_size) = nom_unsigned_four_bytes(input, Endian::Le)?;
-
-    let empty = 0;
-    let lang = if lang_offset == empty && lang_size == empty {
-        let (lang_start, _) = take(fallback_lang_offset)(data)?;
-        let (_, lang_data) = take(fallback_lang_size)(lang_start)?;
-        extract_utf16_string(lang_data)
-    } else {
-        let (lang_start, _) = take(lang_offset)(data)?;
-        let (_, lang_data) = take(lang_size)(lang_start)?;
-        extract_utf16_string(lang_data)
-    };
+    let (_, lang) = mui_language(data)?;
 
     let real_path = if !path.ends_with(".mui") {
         let parent = get_parent_directory(path);
@@ -116,6 +73,56 @@ pub(crate) fn parse_mui<'a>(
     Ok((&[], resource))
 }
 
+/// Get the language of the MUI resource. The localized MUI file is stored in a subdirectory named after the language. Ex: en-US
+pub(crate) fn mui_language(data: &[u8]) -> nom::IResult<&[u8], String> {
+    let (input, _sig) = nom_unsigned_four_bytes(data, Endian::Le)?;
+    // Size is the entire data
+    let (input, _size) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, _version) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, _unknown) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, _file_type) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, _attributes) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, _fallback_location) = nom_unsigned_four_bytes(input, Endian::Le)?;
+
+    let checksum_size: u8 = 16;
+    let (input, _service_checksum) = take(checksum_size)(input)?;
+    let (input, _checksum) = take(checksum_size)(input)?;
+
+    let unknown_size: u8 = 24;
+    let (input, _unknown2) = take(unknown_size)(input)?;
+
+    let (input, _main_name_offset) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, _main_name_size) = nom_unsigned_four_bytes(input, Endian::Le)?;
+
+    let (input, _main_id_offset) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, _main_id_size) = nom_unsigned_four_bytes(input, Endian::Le)?;
+
+    let (input, _main_name_type_offset) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, _main_name_type_size) = nom_unsigned_four_bytes(input, Endian::Le)?;
+
+    let (input, _main_type_offset) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, _main_type_size) = nom_unsigned_four_bytes(input, Endian::Le)?;
+
+    let (input, lang_offset) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, lang_size) = nom_unsigned_four_bytes(input, Endian::Le)?;
+
+    let (input, fallback_lang_offset) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (_input, fallback_lang_size) = nom_unsigned_four_bytes(input, Endian::Le)?;
+
+    let empty = 0;
+    let lang = if lang_offset == empty && lang_size == empty {
+        let (lang_start, _) = take(fallback_lang_offset)(data)?;
+        let (_, lang_data) = take(fallback_lang_size)(lang_start)?;
+        extract_utf16_string(lang_data)
+    } else {
+        let (lang_start, _) = take(lang_offset)(data)?;
+        let (_, lang_data) = take(lang_size)(lang_start)?;
+        extract_utf16_string(lang_data)
+    };
+
+    Ok((&[], lang))
+}
+
 #[cfg(test)]
 #[cfg(target_os = "windows")]
 mod tests {
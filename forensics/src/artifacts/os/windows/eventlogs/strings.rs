@@ -257,7 +257,7 @@ fn update_resource(templates: &mut HashMap<String, TemplateResource>, file: &str
 }
 
 /// Extract data from the Registry paths
-fn registry_paths(
+pub(crate) fn registry_paths(
     reg_paths: &mut [RegistryData],
     providers: &mut HashMap<String, ProviderInfo>,
     update_env: &HashMap<String, String>,
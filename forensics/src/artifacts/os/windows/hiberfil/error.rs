@@ -0,0 +1,30 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum HiberfilError {
+    SystemDrive,
+    Parser,
+    ReadFile,
+    BadHeader,
+    Decompress,
+    RawImage,
+    Serialize,
+    OutputData,
+}
+
+impl std::error::Error for HiberfilError {}
+
+impl fmt::Display for HiberfilError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HiberfilError::SystemDrive => write!(f, "Failed to systemdrive env variable value"),
+            HiberfilError::Parser => write!(f, "Failed to parse hiberfil.sys"),
+            HiberfilError::ReadFile => write!(f, "Failed to read hiberfil.sys"),
+            HiberfilError::BadHeader => write!(f, "No valid hibernation header"),
+            HiberfilError::Decompress => write!(f, "Failed to decompress hibernation pages"),
+            HiberfilError::RawImage => write!(f, "Failed to write raw memory image"),
+            HiberfilError::Serialize => write!(f, "Failed to serialize hiberfil.sys data"),
+            HiberfilError::OutputData => write!(f, "Failed to output hiberfil.sys data"),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::utils::nom_helper::{Endian, nom_unsigned_eight_bytes, nom_unsigned_four_bytes};
+use nom::bytes::complete::take;
+
+#[derive(Debug)]
+pub(crate) struct HiberHeader {
+    pub(crate) sig: u32,
+    pub(crate) page_size: u32,
+    /**FILETIME when the system hibernated */
+    pub(crate) system_time: u64,
+    pub(crate) first_boot_restore_page: u64,
+    pub(crate) first_kernel_restore_page: u64,
+}
+
+impl HiberHeader {
+    /// Parse the `PO_MEMORY_IMAGE` header at the start of the hibernation file. Only supports the Windows 8+ 64-bit layout
+    pub(crate) fn parse_header(data: &[u8]) -> nom::IResult<&[u8], HiberHeader> {
+        let (input, sig) = nom_unsigned_four_bytes(data, Endian::Le)?;
+
+        let page_size_offset: u8 = 0x18;
+        let (input, _) = take(page_size_offset - 4)(input)?;
+        let (input, page_size) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, _padding) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, system_time) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+
+        let restore_offset: u8 = 0x68;
+        let (restore_data, _) = take(restore_offset)(data)?;
+        let (input_restore, first_boot_restore_page) =
+            nom_unsigned_eight_bytes(restore_data, Endian::Le)?;
+        let (_, first_kernel_restore_page) = nom_unsigned_eight_bytes(input_restore, Endian::Le)?;
+
+        let header = HiberHeader {
+            sig,
+            page_size,
+            system_time,
+            first_boot_restore_page,
+            first_kernel_restore_page,
+        };
+
+        Ok((input, header))
+    }
+
+    /// Check if the header has a hibernation signature. Windows wipes the header after the system resumes
+    pub(crate) fn is_valid(&self) -> bool {
+        // hibr, HIBR, wake, WAKE, rstr, RSTR
+        let sigs = [
+            0x72626968, 0x52424948, 0x656b6177, 0x454b4157, 0x72747372, 0x52545352,
+        ];
+        let page_size = 4096;
+        sigs.contains(&self.sig) && self.page_size == page_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HiberHeader;
+
+    fn test_header() -> Vec<u8> {
+        let mut data = vec![0; 4096];
+        data[0..4].copy_from_slice(b"HIBR");
+        data[0x18..0x1c].copy_from_slice(&4096u32.to_le_bytes());
+        data[0x20..0x28].copy_from_slice(&133700000000000000u64.to_le_bytes());
+        data[0x68..0x70].copy_from_slice(&1u64.to_le_bytes());
+        data[0x70..0x78].copy_from_slice(&2u64.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse_header() {
+        let data = test_header();
+        let (_, header) = HiberHeader::parse_header(&data).unwrap();
+        assert!(header.is_valid());
+        assert_eq!(header.system_time, 133700000000000000);
+        assert_eq!(header.first_boot_restore_page, 1);
+        assert_eq!(header.first_kernel_restore_page, 2);
+    }
+
+    #[test]
+    fn test_parse_header_resumed() {
+        let mut data = test_header();
+        data[0..4].copy_from_slice(&[0, 0, 0, 0]);
+        let (_, header) = HiberHeader::parse_header(&data).unwrap();
+        assert!(!header.is_valid());
+    }
+}
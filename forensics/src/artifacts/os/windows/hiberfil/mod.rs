@@ -0,0 +1,5 @@
+mod error;
+mod header;
+pub(crate) mod parser;
+mod process;
+mod sets;
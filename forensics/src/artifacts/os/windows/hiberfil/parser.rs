@@ -0,0 +1,477 @@
+/**
+ * The Windows hibernation file (`hiberfil.sys`) contains a compressed copy of physical memory from when the system last hibernated
+ * Windows 8+ also uses the hibernation file for Fast Startup. Located at `<drive>:\hiberfil.sys`
+ * Memory pages are stored in compression sets (up to 16 page runs) compressed with Xpress or Xpress Huffman
+ * Windows wipes the header after resuming, so only files from a system that is still hibernated (or shutdown with Fast Startup) can be parsed
+ *
+ * We reconstruct a raw memory image in the output directory and scan the pages for process parameters (image path and command line)
+ *
+ * References:
+ * `https://github.com/libyal/libhibr`
+ *
+ * Other Parsers:
+ * `https://github.com/volatilityfoundation/volatility3`
+ */
+use super::{
+    error::HiberfilError,
+    header::HiberHeader,
+    process::{ProcessParameters, scan_process_parameters},
+    sets::{CompressionSet, PAGE_SIZE},
+};
+use crate::{
+    filesystem::{
+        files::file_reader,
+        ntfs::{raw_files::raw_reader, reader::read_bytes, setup::setup_ntfs_parser},
+    },
+    output::{manager::OutputManager, record::serialize_records_to_stream},
+    structs::artifacts::os::windows::HiberfilOptions,
+    utils::{cancel::CancelToken, environment::get_systemdrive, time::filetime_to_iso},
+};
+use common::windows::HiberfilProcess;
+use ntfs::NtfsFile;
+use std::{
+    fs::{File, create_dir_all},
+    io::{BufReader, Seek, SeekFrom, Write},
+    mem::take,
+};
+use tracing::{error, info, warn};
+
+/// Parse `hiberfil.sys` and output the process list
+pub(crate) fn grab_hiberfil(
+    options: &HiberfilOptions,
+    manager: &mut OutputManager,
+) -> Result<(), HiberfilError> {
+    if let Some(path) = &options.alt_file {
+        let reader = match file_reader(path) {
+            Ok(result) => result,
+            Err(err) => {
+                error!("[hiberfil] Could not open {path}: {err:?}");
+                return Err(HiberfilError::ReadFile);
+            }
+        };
+        let mut fs = BufReader::new(reader);
+        return parse_hiberfil(&mut fs, None, path, manager, options);
+    }
+
+    let systemdrive = match get_systemdrive() {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[hiberfil] Could not get systemdrive: {err:?}");
+            return Err(HiberfilError::SystemDrive);
+        }
+    };
+
+    // The hibernation file is always locked. Parse the NTFS filesystem to read it
+    let mut ntfs_parser = match setup_ntfs_parser(systemdrive) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[hiberfil] Cannot setup NTFS parser: {err:?}");
+            return Err(HiberfilError::Parser);
+        }
+    };
+    let path = format!("{systemdrive}:\\hiberfil.sys");
+    let ntfs_file = match raw_reader(&path, &ntfs_parser.ntfs, &mut ntfs_parser.fs) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[hiberfil] Could not read {path}: {err:?}");
+            return Err(HiberfilError::ReadFile);
+        }
+    };
+
+    parse_hiberfil(
+        &mut ntfs_parser.fs,
+        Some(&ntfs_file),
+        &path,
+        manager,
+        options,
+    )
+}
+
+/// Decompress all page sets in the hibernation file
+fn parse_hiberfil<T: std::io::Seek + std::io::Read>(
+    fs: &mut BufReader<T>,
+    ntfs_file: Option<&NtfsFile<'_>>,
+    path: &str,
+    manager: &mut OutputManager,
+    options: &HiberfilOptions,
+) -> Result<(), HiberfilError> {
+    let mut reader = HiberReader {
+        fs,
+        ntfs_file,
+        cache: Vec::new(),
+        cache_offset: 0,
+    };
+    let Some(header_data) = reader.read(0, PAGE_SIZE) else {
+        return Err(HiberfilError::ReadFile);
+    };
+    let header = match HiberHeader::parse_header(header_data) {
+        Ok((_, result)) => result,
+        Err(_err) => {
+            error!("[hiberfil] Could not parse header for {path}");
+            return Err(HiberfilError::BadHeader);
+        }
+    };
+    if !header.is_valid() {
+        error!(
+            "[hiberfil] {path} does not have a hibernation signature. The system may have resumed"
+        );
+        return Err(HiberfilError::BadHeader);
+    }
+
+    // Pages restored by the boot loader come first. They end where the kernel pages start
+    let (Some(boot_start), Some(kernel_start)) = (
+        header.first_boot_restore_page.checked_mul(PAGE_SIZE),
+        header.first_kernel_restore_page.checked_mul(PAGE_SIZE),
+    ) else {
+        error!("[hiberfil] Restore page numbers in {path} are too large. Header is corrupt");
+        return Err(HiberfilError::BadHeader);
+    };
+
+    let mut state = HiberState {
+        image: None,
+        image_path: String::new(),
+        processes: Vec::new(),
+        hibernation_time: filetime_to_iso(header.system_time),
+        evidence: path.to_string(),
+        pages: 0,
+    };
+    if options.skip_raw_image != Some(true) {
+        let output = manager.config.directory.join(&manager.config.name);
+        if let Err(err) = create_dir_all(&output) {
+            error!("[hiberfil] Could not create output directory: {err:?}");
+            return Err(HiberfilError::RawImage);
+        }
+        let image_path = output.join("hiberfil_memory.raw");
+        state.image = match File::create(&image_path) {
+            Ok(result) => Some(result),
+            Err(err) => {
+                error!("[hiberfil] Could not create raw memory image: {err:?}");
+                return Err(HiberfilError::RawImage);
+            }
+        };
+        state.image_path = image_path.display().to_string();
+    }
+
+    let cancel = manager.cancel.clone();
+    parse_sets(
+        &mut reader,
+        boot_start,
+        Some(kernel_start),
+        &mut state,
+        &cancel,
+    )?;
+    parse_sets(&mut reader, kernel_start, None, &mut state, &cancel)?;
+
+    info!(
+        "[hiberfil] Restored {} pages from {path}. Found {} processes",
+        state.pages,
+        state.processes.len()
+    );
+    output_hiberfil(state.build_entries(), manager, options)
+}
+
+struct HiberState {
+    image: Option<File>,
+    image_path: String,
+    processes: Vec<ProcessParameters>,
+    hibernation_time: String,
+    evidence: String,
+    /**Count of restored pages */
+    pages: u64,
+}
+
+impl HiberState {
+    /// Write the pages to the raw memory image and scan for process parameters
+    fn add_pages(&mut self, set: &CompressionSet, pages: &[u8]) -> Result<(), HiberfilError> {
+        let mut start = 0;
+        for run in &set.runs {
+            let end = start + (run.count * PAGE_SIZE) as usize;
+            let data = &pages[start..end];
+            let physical_offset = run.page * PAGE_SIZE;
+            start = end;
+
+            self.pages += run.count;
+            self.processes
+                .append(&mut scan_process_parameters(data, physical_offset));
+
+            let Some(image) = &mut self.image else {
+                continue;
+            };
+            if let Err(err) = image
+                .seek(SeekFrom::Start(physical_offset))
+                .and_then(|_| image.write_all(data))
+            {
+                error!("[hiberfil] Could not write pages to raw memory image: {err:?}");
+                return Err(HiberfilError::RawImage);
+            }
+        }
+        Ok(())
+    }
+
+    /// Convert the process parameters to output entries
+    fn build_entries(&mut self) -> Vec<HiberfilProcess> {
+        take(&mut self.processes)
+            .into_iter()
+            .map(|process| HiberfilProcess {
+                image_path: process.image_path,
+                command_line: process.command_line,
+                current_directory: process.current_directory,
+                window_title: process.window_title,
+                offset: process.offset,
+                hibernation_time: self.hibernation_time.clone(),
+                raw_image: self.image_path.clone(),
+                evidence: self.evidence.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Parse compression sets starting at offset until we reach the end offset, an invalid set, or the collection is cancelled
+fn parse_sets<T: std::io::Seek + std::io::Read>(
+    reader: &mut HiberReader<'_, '_, T>,
+    mut offset: u64,
+    end: Option<u64>,
+    state: &mut HiberState,
+    cancel: &CancelToken,
+) -> Result<(), HiberfilError> {
+    // Set header and up to 16 page descriptors
+    let max_header = 4 + 16 * 8;
+    let mut bad_sets = 0;
+    while end.is_none_or(|value| offset < value) {
+        if cancel.is_cancelled() {
+            warn!("[hiberfil] Collection cancelled. Stopping at compression set offset {offset}");
+            break;
+        }
+        let Some(set) = reader
+            .read(offset, max_header)
+            .and_then(CompressionSet::parse_set)
+        else {
+            break;
+        };
+
+        let Some(data) = reader.read(offset + set.header_size(), set.compressed_size as u64) else {
+            warn!("[hiberfil] Compression set at {offset} extends past end of file");
+            break;
+        };
+        match set.decompress(data) {
+            Ok(pages) => state.add_pages(&set, &pages)?,
+            Err(_err) => bad_sets += 1,
+        }
+        offset += set.size();
+    }
+
+    if bad_sets != 0 {
+        warn!("[hiberfil] Failed to decompress {bad_sets} compression sets");
+    }
+    Ok(())
+}
+
+/// Cache reads from the hibernation file. Compression sets are small and read sequentially
+struct HiberReader<'a, 'b, T: std::io::Seek + std::io::Read> {
+    fs: &'a mut BufReader<T>,
+    ntfs_file: Option<&'a NtfsFile<'b>>,
+    cache: Vec<u8>,
+    cache_offset: u64,
+}
+
+impl<T: std::io::Seek + std::io::Read> HiberReader<'_, '_, T> {
+    /// Read bytes at offset. Returns None if the bytes could not be read
+    fn read(&mut self, offset: u64, size: u64) -> Option<&[u8]> {
+        let cache_end = self.cache_offset + self.cache.len() as u64;
+        if offset < self.cache_offset || offset + size > cache_end {
+            // Read 4MB at a time
+            let cache_size = 4 * 1024 * 1024;
+            self.cache = match read_bytes(offset, size.max(cache_size), self.ntfs_file, self.fs) {
+                Ok(result) => result,
+                Err(err) => {
+                    error!("[hiberfil] Could not read bytes at {offset}: {err:?}");
+                    return None;
+                }
+            };
+            self.cache_offset = offset;
+        }
+
+        let start = (offset - self.cache_offset) as usize;
+        self.cache.get(start..start + size as usize)
+    }
+}
+
+/// Output hibernation processes based on `Output` structure
+fn output_hiberfil(
+    entries: Vec<HiberfilProcess>,
+    manager: &mut OutputManager,
+    options: &HiberfilOptions,
+) -> Result<(), HiberfilError> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let mut records = match serialize_records_to_stream(entries) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[hiberfil] Failed to serialize processes: {err:?}");
+            return Err(HiberfilError::Serialize);
+        }
+    };
+    let artifact_name = "hiberfil";
+    if let Err(err) = manager.write_artifact(artifact_name, options, &mut records) {
+        error!("[hiberfil] Could not output processes: {err:?}");
+        return Err(HiberfilError::OutputData);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HiberReader, HiberState, grab_hiberfil, parse_hiberfil, parse_sets};
+    use crate::artifacts::os::windows::hiberfil::error::HiberfilError;
+    use crate::{
+        artifacts::os::windows::hiberfil::sets::PAGE_SIZE,
+        filesystem::files::file_reader,
+        output::manager::OutputManager,
+        structs::{
+            artifacts::os::windows::HiberfilOptions,
+            toml::{OutputConfig, OutputDestination, OutputFormat},
+        },
+        utils::cancel::CancelToken,
+    };
+    use std::{
+        fs::read,
+        io::{BufReader, Cursor},
+        path::PathBuf,
+    };
+
+    fn output_options(name: &str, directory: &str, compress: bool) -> OutputManager {
+        let config = OutputConfig {
+            name: name.to_string(),
+            directory: PathBuf::from(directory),
+            format: OutputFormat::Jsonl,
+            compress,
+            endpoint_id: String::from("abcd"),
+            destination: OutputDestination::Local,
+            ..Default::default()
+        };
+        OutputManager::new(config).unwrap()
+    }
+
+    fn test_file() -> String {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/hiberfil/hiberfil.sys");
+        test_location.display().to_string()
+    }
+
+    #[test]
+    fn test_grab_hiberfil_alt_file() {
+        let params = HiberfilOptions {
+            alt_file: Some(test_file()),
+            skip_raw_image: None,
+        };
+        let mut output = output_options("hiberfil_temp", "./tmp", false);
+        grab_hiberfil(&params, &mut output).unwrap();
+
+        let image = read("./tmp/hiberfil_temp/hiberfil_memory.raw").unwrap();
+        assert_eq!(image.len() as u64, 0x201 * PAGE_SIZE);
+        // Boot set is Xpress Huffman compressed
+        assert!(
+            image[0x10 * PAGE_SIZE as usize..0x20 * PAGE_SIZE as usize]
+                .iter()
+                .any(|value| *value != 0)
+        );
+        assert_eq!(image[0x200 * PAGE_SIZE as usize], 0xaa);
+    }
+
+    #[test]
+    fn test_parse_sets() {
+        let reader = file_reader(&test_file()).unwrap();
+        let mut fs = BufReader::new(reader);
+        let mut reader = HiberReader {
+            fs: &mut fs,
+            ntfs_file: None,
+            cache: Vec::new(),
+            cache_offset: 0,
+        };
+        let mut state = HiberState {
+            image: None,
+            image_path: String::new(),
+            processes: Vec::new(),
+            hibernation_time: String::new(),
+            evidence: String::from("hiberfil.sys"),
+            pages: 0,
+        };
+
+        let cancel = CancelToken::new();
+        parse_sets(
+            &mut reader,
+            PAGE_SIZE,
+            Some(11 * PAGE_SIZE),
+            &mut state,
+            &cancel,
+        )
+        .unwrap();
+        assert_eq!(state.pages, 16);
+        parse_sets(&mut reader, 11 * PAGE_SIZE, None, &mut state, &cancel).unwrap();
+        assert_eq!(state.pages, 18);
+
+        let entries = state.build_entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].image_path, "C:\\Windows\\System32\\cmd.exe");
+        assert_eq!(
+            entries[0].command_line,
+            "\"C:\\Windows\\System32\\cmd.exe\" /c whoami"
+        );
+        assert_eq!(entries[0].offset, 0x100ab0);
+    }
+
+    #[test]
+    fn test_parse_sets_cancelled() {
+        let reader = file_reader(&test_file()).unwrap();
+        let mut fs = BufReader::new(reader);
+        let mut reader = HiberReader {
+            fs: &mut fs,
+            ntfs_file: None,
+            cache: Vec::new(),
+            cache_offset: 0,
+        };
+        let mut state = HiberState {
+            image: None,
+            image_path: String::new(),
+            processes: Vec::new(),
+            hibernation_time: String::new(),
+            evidence: String::from("hiberfil.sys"),
+            pages: 0,
+        };
+        let cancel = CancelToken::new();
+        cancel.cancel();
+
+        parse_sets(&mut reader, PAGE_SIZE, None, &mut state, &cancel).unwrap();
+        assert_eq!(state.pages, 0);
+    }
+
+    #[test]
+    fn test_grab_hiberfil_bad_header() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/logfile/LogFile.raw");
+        let params = HiberfilOptions {
+            alt_file: Some(test_location.display().to_string()),
+            skip_raw_image: Some(true),
+        };
+        let mut output = output_options("hiberfil_temp", "./tmp", false);
+        assert!(grab_hiberfil(&params, &mut output).is_err());
+    }
+
+    #[test]
+    fn test_parse_hiberfil_restore_page_overflow() {
+        let mut data = read(test_file()).unwrap();
+        data[0x68..0x70].copy_from_slice(&u64::MAX.to_le_bytes());
+        let mut fs = BufReader::new(Cursor::new(data));
+        let params = HiberfilOptions {
+            alt_file: None,
+            skip_raw_image: Some(true),
+        };
+        let mut output = output_options("hiberfil_temp", "./tmp", false);
+
+        let result = parse_hiberfil(&mut fs, None, "hiberfil.sys", &mut output, &params);
+        assert_eq!(result, Err(HiberfilError::BadHeader));
+    }
+}
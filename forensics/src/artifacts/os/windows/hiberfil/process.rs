@@ -0,0 +1,206 @@
+use crate::utils::{
+    nom_helper::{
+        Endian, nom_data, nom_unsigned_eight_bytes, nom_unsigned_four_bytes, nom_unsigned_two_bytes,
+    },
+    strings::extract_utf16_string,
+};
+
+/// Process details from a `RTL_USER_PROCESS_PARAMETERS` structure found in memory
+#[derive(Debug)]
+pub(crate) struct ProcessParameters {
+    pub(crate) image_path: String,
+    pub(crate) command_line: String,
+    pub(crate) current_directory: String,
+    pub(crate) window_title: String,
+    /**Physical memory offset of the structure */
+    pub(crate) offset: u64,
+}
+
+/// `UNICODE_STRING` structure. Buffer is a virtual address
+struct UnicodeString {
+    length: u16,
+    buffer: u64,
+}
+
+/// Smallest and largest known 64-bit `RTL_USER_PROCESS_PARAMETERS` sizes. The strings are copied immediately after the structure
+const MIN_STRUCT_SIZE: u64 = 0x3f0;
+const MAX_STRUCT_SIZE: u64 = 0x4b0;
+
+/**
+ * Scan physically contiguous pages for `RTL_USER_PROCESS_PARAMETERS` structures
+ * Every process has one of these in user memory. They contain the `ImagePathName` and `CommandLine` for the process
+ */
+pub(crate) fn scan_process_parameters(data: &[u8], physical_offset: u64) -> Vec<ProcessParameters> {
+    let mut processes = Vec::new();
+    // Structures are allocated on 8 byte boundaries
+    let alignment = 8;
+    let mut offset = 0;
+    while offset + MIN_STRUCT_SIZE as usize <= data.len() {
+        // Quick check. MaximumLength and Length are always equal and the normalized flag is set
+        if data[offset..offset + 4] == data[offset + 4..offset + 8]
+            && data[offset + 8] & 1 == 1
+            && let Some(process) =
+                parse_parameters(&data[offset..], physical_offset + offset as u64)
+        {
+            processes.push(process);
+        }
+        offset += alignment;
+    }
+
+    processes
+}
+
+/// Parse and validate a possible `RTL_USER_PROCESS_PARAMETERS` structure
+fn parse_parameters(data: &[u8], physical_offset: u64) -> Option<ProcessParameters> {
+    let (input, max_length) = nom_unsigned_four_bytes(data, Endian::Le).ok()?;
+    let (input, length) = nom_unsigned_four_bytes(input, Endian::Le).ok()?;
+    // Command lines can be up to 32767 characters
+    let max_size = 0x11000;
+    if max_length != length || (length as u64) < MIN_STRUCT_SIZE || length > max_size {
+        return None;
+    }
+
+    let current_dir_offset: u8 = 0x38;
+    let (input, _) = nom_data(input, (current_dir_offset - 8) as u64).ok()?;
+    let (input, current_directory) = parse_unicode(input)?;
+    let (input, _handle) = nom_unsigned_eight_bytes(input, Endian::Le).ok()?;
+    let (input, _dll_path) = parse_unicode(input)?;
+    let (input, image_path) = parse_unicode(input)?;
+    let (_, command_line) = parse_unicode(input)?;
+
+    let window_title_offset: u8 = 0xb0;
+    let (window_data, _) = nom_data(data, window_title_offset as u64).ok()?;
+    let (_, window_title) = parse_unicode(window_data)?;
+
+    if current_directory.buffer == 0 || image_path.length == 0 {
+        return None;
+    }
+
+    // The first string (CurrentDirectory) is copied immediately after the structure
+    // Virtual and physical addresses have the same page offset. So we can determine the structure size and virtual address
+    let page_mask = 0xfff;
+    let struct_size = current_directory.buffer.wrapping_sub(physical_offset) & page_mask;
+    if !(MIN_STRUCT_SIZE..=MAX_STRUCT_SIZE).contains(&struct_size) {
+        return None;
+    }
+    let base = current_directory.buffer - struct_size;
+    let allocation = &data[..data.len().min(length as usize)];
+
+    let process = ProcessParameters {
+        image_path: read_unicode(allocation, base, struct_size, &image_path)?,
+        command_line: read_unicode(allocation, base, struct_size, &command_line)?,
+        current_directory: read_unicode(allocation, base, struct_size, &current_directory)?,
+        window_title: read_unicode(allocation, base, struct_size, &window_title)?,
+        offset: physical_offset,
+    };
+    if process.image_path.is_empty() {
+        return None;
+    }
+
+    Some(process)
+}
+
+/// Parse `UNICODE_STRING`
+fn parse_unicode(data: &[u8]) -> Option<(&[u8], UnicodeString)> {
+    let (input, length) = nom_unsigned_two_bytes(data, Endian::Le).ok()?;
+    let (input, max_length) = nom_unsigned_two_bytes(input, Endian::Le).ok()?;
+    let (input, _padding) = nom_unsigned_four_bytes(input, Endian::Le).ok()?;
+    let (input, buffer) = nom_unsigned_eight_bytes(input, Endian::Le).ok()?;
+
+    // User mode address space only
+    let user_max = 0x0000_8000_0000_0000;
+    if length > max_length || length % 2 != 0 || buffer >= user_max {
+        return None;
+    }
+
+    Some((input, UnicodeString { length, buffer }))
+}
+
+/// Read the string from the structure allocation. The string must be after the structure and inside the allocation
+fn read_unicode(
+    allocation: &[u8],
+    base: u64,
+    struct_size: u64,
+    value: &UnicodeString,
+) -> Option<String> {
+    if value.length == 0 {
+        return Some(String::new());
+    }
+    let start = value.buffer.checked_sub(base)?;
+    let end = start + value.length as u64;
+    if start < struct_size || end > allocation.len() as u64 {
+        return None;
+    }
+
+    let string = extract_utf16_string(&allocation[start as usize..end as usize]);
+    if string.chars().any(char::is_control) {
+        return None;
+    }
+    Some(string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_parameters, scan_process_parameters};
+
+    /// Build a `RTL_USER_PROCESS_PARAMETERS` structure at the provided virtual address
+    fn test_parameters(base: u64) -> Vec<u8> {
+        let struct_size = 0x440;
+        let mut data = vec![0; struct_size];
+        let strings = [
+            (0x38, "C:\\Windows\\system32\\"),
+            (0x50, ""),
+            (0x60, "C:\\Windows\\System32\\cmd.exe"),
+            (0x70, "\"C:\\Windows\\System32\\cmd.exe\" /c whoami"),
+            (0xb0, "Administrator: Command Prompt"),
+        ];
+        for (offset, value) in strings {
+            let bytes: Vec<u8> = value.encode_utf16().flat_map(u16::to_le_bytes).collect();
+            let address = base + data.len() as u64;
+            data[offset..offset + 2].copy_from_slice(&(bytes.len() as u16).to_le_bytes());
+            data[offset + 2..offset + 4].copy_from_slice(&(bytes.len() as u16 + 2).to_le_bytes());
+            data[offset + 8..offset + 16].copy_from_slice(&address.to_le_bytes());
+            data.extend_from_slice(&bytes);
+            data.extend_from_slice(&[0, 0]);
+        }
+        let length = data.len() as u32;
+        data[0..4].copy_from_slice(&length.to_le_bytes());
+        data[4..8].copy_from_slice(&length.to_le_bytes());
+        // Normalized flag
+        data[8] = 1;
+        data
+    }
+
+    #[test]
+    fn test_parse_parameters() {
+        let data = test_parameters(0x1d5a8a21ab0);
+        let process = parse_parameters(&data, 0x7f000ab0).unwrap();
+        assert_eq!(process.image_path, "C:\\Windows\\System32\\cmd.exe");
+        assert_eq!(
+            process.command_line,
+            "\"C:\\Windows\\System32\\cmd.exe\" /c whoami"
+        );
+        assert_eq!(process.current_directory, "C:\\Windows\\system32\\");
+        assert_eq!(process.window_title, "Administrator: Command Prompt");
+        assert_eq!(process.offset, 0x7f000ab0);
+    }
+
+    #[test]
+    fn test_parse_parameters_bad_offset() {
+        let data = test_parameters(0x1d5a8a21ab0);
+        // Page offset does not match the virtual address
+        assert!(parse_parameters(&data, 0x7f000000).is_none());
+    }
+
+    #[test]
+    fn test_scan_process_parameters() {
+        let mut page = vec![0; 8192];
+        let data = test_parameters(0x1d5a8a21ab0);
+        page[0xab0..0xab0 + data.len()].copy_from_slice(&data);
+
+        let processes = scan_process_parameters(&page, 0x5000);
+        assert_eq!(processes.len(), 1);
+        assert_eq!(processes[0].offset, 0x5ab0);
+        assert_eq!(processes[0].image_path, "C:\\Windows\\System32\\cmd.exe");
+    }
+}
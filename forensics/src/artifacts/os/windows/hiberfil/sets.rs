@@ -0,0 +1,170 @@
+use super::error::HiberfilError;
+use crate::utils::{
+    compression::decompress::{XpressType, decompress_xpress},
+    nom_helper::{Endian, nom_unsigned_eight_bytes, nom_unsigned_four_bytes},
+};
+use tracing::warn;
+
+/// Compressed page set. Windows 8+ compresses up to 16 page runs together using Xpress or Xpress Huffman
+#[derive(Debug)]
+pub(crate) struct CompressionSet {
+    pub(crate) runs: Vec<PageRun>,
+    pub(crate) compressed_size: u32,
+    pub(crate) huffman: bool,
+}
+
+/// Run of physically contiguous memory pages
+#[derive(Debug, PartialEq)]
+pub(crate) struct PageRun {
+    pub(crate) page: u64,
+    pub(crate) count: u64,
+}
+
+pub(crate) const PAGE_SIZE: u64 = 4096;
+const MAX_DESCRIPTORS: u32 = 16;
+
+impl CompressionSet {
+    /// Parse the compression set header and page descriptors. Returns None if the data is not a valid set
+    pub(crate) fn parse_set(data: &[u8]) -> Option<CompressionSet> {
+        let (input, value) = nom_unsigned_four_bytes(data, Endian::Le).ok()?;
+        let descriptors = value & 0xff;
+        if descriptors == 0 || descriptors > MAX_DESCRIPTORS {
+            return None;
+        }
+
+        let mut set = CompressionSet {
+            runs: Vec::new(),
+            compressed_size: (value >> 8) & 0x3fffff,
+            huffman: (value >> 31) == 1,
+        };
+
+        let mut remaining = input;
+        for _ in 0..descriptors {
+            let (input, desc) = nom_unsigned_eight_bytes(remaining, Endian::Le).ok()?;
+            remaining = input;
+            let page = desc >> 4;
+            // Corrupt descriptors can have page numbers past the largest physical offset
+            page.checked_mul(PAGE_SIZE)?;
+            set.runs.push(PageRun {
+                page,
+                count: (desc & 0xf) + 1,
+            });
+        }
+
+        if set.compressed_size == 0 || set.compressed_size as u64 > set.page_count() * PAGE_SIZE {
+            return None;
+        }
+
+        Some(set)
+    }
+
+    /// Total number of pages in the set
+    pub(crate) fn page_count(&self) -> u64 {
+        self.runs.iter().map(|run| run.count).sum()
+    }
+
+    /// Size of the set header and descriptors
+    pub(crate) fn header_size(&self) -> u64 {
+        let desc_size = 8;
+        4 + self.runs.len() as u64 * desc_size
+    }
+
+    /// Size of the whole compression set in the hibernation file
+    pub(crate) fn size(&self) -> u64 {
+        self.header_size() + self.compressed_size as u64
+    }
+
+    /// Decompress the set data. The decompressed pages are in the same order as the page runs
+    pub(crate) fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, HiberfilError> {
+        let size = self.page_count() * PAGE_SIZE;
+        // Uncompressed sets just contain the raw pages
+        if self.compressed_size as u64 == size {
+            return Ok(data.to_vec());
+        }
+
+        let format = if self.huffman {
+            XpressType::XpressHuffman
+        } else {
+            XpressType::Lz77
+        };
+        let mut compressed = data.to_vec();
+        let pages = match decompress_xpress(&mut compressed, size as u32, &format) {
+            Ok(result) => result,
+            Err(err) => {
+                warn!("[hiberfil] Could not decompress page set: {err:?}");
+                return Err(HiberfilError::Decompress);
+            }
+        };
+
+        if pages.len() as u64 != size {
+            warn!(
+                "[hiberfil] Decompressed page set size {} does not match expected size {size}",
+                pages.len()
+            );
+            return Err(HiberfilError::Decompress);
+        }
+        Ok(pages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompressionSet, PAGE_SIZE, PageRun};
+    use crate::filesystem::files::read_file;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_parse_set() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&(2u32 | (8192 << 8)).to_le_bytes());
+        data.extend_from_slice(&(10u64 << 4).to_le_bytes());
+        data.extend_from_slice(&(20u64 << 4).to_le_bytes());
+
+        let set = CompressionSet::parse_set(&data).unwrap();
+        assert_eq!(
+            set.runs,
+            vec![
+                PageRun { page: 10, count: 1 },
+                PageRun { page: 20, count: 1 }
+            ]
+        );
+        assert!(!set.huffman);
+        assert_eq!(set.page_count(), 2);
+        assert_eq!(set.size(), 4 + 16 + 8192);
+
+        let pages = set.decompress(&vec![1; 8192]).unwrap();
+        assert_eq!(pages.len(), 8192);
+    }
+
+    #[test]
+    fn test_parse_set_bad() {
+        assert!(CompressionSet::parse_set(&[0, 0, 0, 0]).is_none());
+        assert!(CompressionSet::parse_set(&[17, 0, 0, 0]).is_none());
+
+        // Compressed size larger than the pages
+        let mut data = Vec::new();
+        data.extend_from_slice(&(1u32 | (8192 << 8)).to_le_bytes());
+        data.extend_from_slice(&(10u64 << 4).to_le_bytes());
+        assert!(CompressionSet::parse_set(&data).is_none());
+    }
+
+    #[test]
+    fn test_decompress_huffman() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/compression/lz_huffman.raw");
+        let compressed = read_file(&test_location.display().to_string()).unwrap();
+
+        let mut data = Vec::new();
+        data.extend_from_slice(
+            &(1u32 | ((compressed.len() as u32) << 8) | (1 << 31)).to_le_bytes(),
+        );
+        data.extend_from_slice(&((4u64 << 4) | 0xf).to_le_bytes());
+
+        let set = CompressionSet::parse_set(&data).unwrap();
+        assert!(set.huffman);
+        assert_eq!(set.page_count(), 16);
+
+        let pages = set.decompress(&compressed).unwrap();
+        assert_eq!(pages.len() as u64, 16 * PAGE_SIZE);
+    }
+}
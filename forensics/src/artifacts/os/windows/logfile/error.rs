@@ -0,0 +1,26 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum LogFileError {
+    SystemDrive,
+    Parser,
+    ReadFile,
+    NoRestartPage,
+    Serialize,
+    OutputData,
+}
+
+impl std::error::Error for LogFileError {}
+
+impl fmt::Display for LogFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogFileError::SystemDrive => write!(f, "Failed to systemdrive env variable value"),
+            LogFileError::Parser => write!(f, "Failed to parse $LogFile"),
+            LogFileError::ReadFile => write!(f, "Failed to read $LogFile"),
+            LogFileError::NoRestartPage => write!(f, "No valid $LogFile restart page"),
+            LogFileError::Serialize => write!(f, "Failed to serialize $LogFile data"),
+            LogFileError::OutputData => write!(f, "Failed to output $LogFile data"),
+        }
+    }
+}
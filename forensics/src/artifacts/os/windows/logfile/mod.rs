@@ -0,0 +1,6 @@
+mod error;
+mod operation;
+mod page;
+pub(crate) mod parser;
+mod record;
+mod restart;
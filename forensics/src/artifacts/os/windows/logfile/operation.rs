@@ -0,0 +1,378 @@
+use super::record::LogRecord;
+use crate::{
+    artifacts::os::windows::mft::{attributes::filename::Filename, header::MftHeader},
+    utils::nom_helper::{Endian, nom_unsigned_four_bytes, nom_unsigned_two_bytes},
+};
+use common::windows::LogOperation;
+use nom::bytes::complete::take;
+
+/// File information that can be recovered from a log record
+#[derive(Debug, Default)]
+pub(crate) struct FileDetails {
+    pub(crate) mft_entry: Option<u32>,
+    pub(crate) mft_sequence: u16,
+    pub(crate) filename: Option<Filename>,
+}
+
+/// Volume values used to convert a target VCN to a MFT entry
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct VolumeLayout {
+    pub(crate) cluster_size: u64,
+    pub(crate) record_size: u64,
+}
+
+impl Default for VolumeLayout {
+    fn default() -> Self {
+        Self {
+            cluster_size: 4096,
+            record_size: 1024,
+        }
+    }
+}
+
+/// Determine the redo or undo operation
+pub(crate) fn get_operation(operation: u16) -> LogOperation {
+    match operation {
+        0x0 => LogOperation::Noop,
+        0x1 => LogOperation::CompensationLogRecord,
+        0x2 => LogOperation::InitializeFileRecordSegment,
+        0x3 => LogOperation::DeallocateFileRecordSegment,
+        0x4 => LogOperation::WriteEndOfFileRecordSegment,
+        0x5 => LogOperation::CreateAttribute,
+        0x6 => LogOperation::DeleteAttribute,
+        0x7 => LogOperation::UpdateResidentValue,
+        0x8 => LogOperation::UpdateNonResidentValue,
+        0x9 => LogOperation::UpdateMappingPairs,
+        0xa => LogOperation::DeleteDirtyClusters,
+        0xb => LogOperation::SetNewAttributeSizes,
+        0xc => LogOperation::AddIndexEntryRoot,
+        0xd => LogOperation::DeleteIndexEntryRoot,
+        0xe => LogOperation::AddIndexEntryAllocation,
+        0xf => LogOperation::DeleteIndexEntryAllocation,
+        0x10 => LogOperation::WriteEndOfIndexBuffer,
+        0x11 => LogOperation::SetIndexEntryVcnRoot,
+        0x12 => LogOperation::SetIndexEntryVcnAllocation,
+        0x13 => LogOperation::UpdateFileNameRoot,
+        0x14 => LogOperation::UpdateFileNameAllocation,
+        0x15 => LogOperation::SetBitsInNonResidentBitMap,
+        0x16 => LogOperation::ClearBitsInNonResidentBitMap,
+        0x17 => LogOperation::HotFix,
+        0x18 => LogOperation::EndTopLevelAction,
+        0x19 => LogOperation::PrepareTransaction,
+        0x1a => LogOperation::CommitTransaction,
+        0x1b => LogOperation::ForgetTransaction,
+        0x1c => LogOperation::OpenNonResidentAttribute,
+        0x1d => LogOperation::OpenAttributeTableDump,
+        0x1e => LogOperation::AttributeNamesDump,
+        0x1f => LogOperation::DirtyPageTableDump,
+        0x20 => LogOperation::TransactionTableDump,
+        0x21 => LogOperation::UpdateRecordDataRoot,
+        0x22 => LogOperation::UpdateRecordDataAllocation,
+        0x23 => LogOperation::UpdateRelativeDataInIndex,
+        0x24 => LogOperation::UpdateRelativeDataInIndex2,
+        0x25 => LogOperation::ZeroEndOfFileRecord,
+        _ => LogOperation::Unknown,
+    }
+}
+
+/// Recover the MFT reference and filename associated with a log record
+pub(crate) fn file_details(record: &LogRecord, layout: &VolumeLayout) -> FileDetails {
+    let mut details = FileDetails::default();
+
+    // Index entries contain the MFT reference and the `$FILE_NAME` of the file
+    if is_index_operation(&record.redo_operation) || is_index_operation(&record.undo_operation) {
+        for data in [&record.redo_data, &record.undo_data] {
+            if let Ok((_, (entry, sequence, filename))) = parse_index_entry(data) {
+                details.mft_entry = Some(entry);
+                details.mft_sequence = sequence;
+                details.filename = Some(filename);
+                return details;
+            }
+        }
+        return details;
+    }
+
+    if !is_mft_operation(&record.redo_operation) {
+        return details;
+    }
+    details.mft_entry = Some(target_entry(record, layout));
+
+    // Initialized and deallocated FILE records contain the full MFT record
+    for data in [&record.redo_data, &record.undo_data] {
+        if let Ok((_, (header, filename))) = parse_file_record(data) {
+            details.mft_entry = Some(header.index);
+            details.mft_sequence = header.sequence;
+            details.filename = filename;
+            break;
+        }
+    }
+
+    details
+}
+
+/// Convert the target VCN and cluster block offset to a MFT entry
+fn target_entry(record: &LogRecord, layout: &VolumeLayout) -> u32 {
+    let sector_size = 512;
+    if layout.record_size == 0 {
+        return 0;
+    }
+    let offset =
+        record.target_vcn * layout.cluster_size + record.cluster_block_offset as u64 * sector_size;
+    (offset / layout.record_size) as u32
+}
+
+/// Operations that modify a MFT FILE record
+fn is_mft_operation(operation: &LogOperation) -> bool {
+    matches!(
+        operation,
+        LogOperation::InitializeFileRecordSegment
+            | LogOperation::DeallocateFileRecordSegment
+            | LogOperation::WriteEndOfFileRecordSegment
+            | LogOperation::CreateAttribute
+            | LogOperation::DeleteAttribute
+            | LogOperation::UpdateResidentValue
+            | LogOperation::UpdateMappingPairs
+            | LogOperation::SetNewAttributeSizes
+            | LogOperation::SetIndexEntryVcnRoot
+            | LogOperation::UpdateFileNameRoot
+            | LogOperation::UpdateRecordDataRoot
+            | LogOperation::ZeroEndOfFileRecord
+    )
+}
+
+/// Operations that add or remove directory index entries
+fn is_index_operation(operation: &LogOperation) -> bool {
+    matches!(
+        operation,
+        LogOperation::AddIndexEntryRoot
+            | LogOperation::DeleteIndexEntryRoot
+            | LogOperation::AddIndexEntryAllocation
+            | LogOperation::DeleteIndexEntryAllocation
+    )
+}
+
+/// Parse a directory index entry that contains a `$FILE_NAME` key
+fn parse_index_entry(data: &[u8]) -> nom::IResult<&[u8], (u32, u16, Filename)> {
+    let (input, entry) = nom_unsigned_four_bytes(data, Endian::Le)?;
+    let (input, _padding) = nom_unsigned_two_bytes(input, Endian::Le)?;
+    let (input, sequence) = nom_unsigned_two_bytes(input, Endian::Le)?;
+    let (input, _entry_size) = nom_unsigned_two_bytes(input, Endian::Le)?;
+    let (input, key_size) = nom_unsigned_two_bytes(input, Endian::Le)?;
+    let (input, _flags) = nom_unsigned_four_bytes(input, Endian::Le)?;
+
+    // Smallest `$FILE_NAME` is 66 bytes (0x42) plus a one character name
+    let min_size = 0x42;
+    if (key_size as usize) < min_size {
+        return Err(nom::Err::Failure(nom::error::Error::new(
+            data,
+            nom::error::ErrorKind::Fail,
+        )));
+    }
+    let (input, key) = take(key_size)(input)?;
+    let (_, filename) = Filename::parse_filename(key)?;
+    if filename.name.is_empty() {
+        return Err(nom::Err::Failure(nom::error::Error::new(
+            data,
+            nom::error::ErrorKind::Fail,
+        )));
+    }
+
+    Ok((input, (entry, sequence, filename)))
+}
+
+/// Parse a logged MFT FILE record and get the first non-DOS `$FILE_NAME` attribute
+fn parse_file_record(data: &[u8]) -> nom::IResult<&[u8], (MftHeader, Option<Filename>)> {
+    let (_, header) = MftHeader::parse_header(data)?;
+    let file0 = 1162627398;
+    if header.sig != file0 {
+        return Err(nom::Err::Failure(nom::error::Error::new(
+            data,
+            nom::error::ErrorKind::Fail,
+        )));
+    }
+
+    let (mut input, _) = take(header.attrib_offset)(data)?;
+    let mut filename = None;
+
+    let end = 0xffffffff;
+    let file_name = 0x30;
+    let resident = 0;
+    let min_attribute_size = 24;
+    while input.len() >= min_attribute_size {
+        let (_, attribute_type) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        if attribute_type == end {
+            break;
+        }
+        let (attribute_data, _) = take(4u8)(input)?;
+        let (_, attribute_size) = nom_unsigned_four_bytes(attribute_data, Endian::Le)?;
+        if attribute_size == 0 || attribute_size as usize > input.len() {
+            break;
+        }
+        let (remaining, attribute) = take(attribute_size)(input)?;
+        input = remaining;
+
+        if attribute_type != file_name || attribute[8] != resident {
+            continue;
+        }
+
+        let (value_data, _) = take(16u8)(attribute)?;
+        let (value_data, value_size) = nom_unsigned_four_bytes(value_data, Endian::Le)?;
+        let (_, value_offset) = nom_unsigned_two_bytes(value_data, Endian::Le)?;
+        let (value, _) = take(value_offset)(attribute)?;
+        let (_, value) = take(value_size)(value)?;
+        let (_, name) = Filename::parse_filename(value)?;
+
+        // Prefer the long filename over the DOS 8.3 filename
+        let dos = common::windows::Namespace::Dos;
+        if name.namespace != dos {
+            return Ok((input, (header, Some(name))));
+        }
+        if filename.is_none() {
+            filename = Some(name);
+        }
+    }
+
+    Ok((input, (header, filename)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        VolumeLayout, file_details, get_operation, parse_file_record, parse_index_entry,
+        target_entry,
+    };
+    use crate::artifacts::os::windows::logfile::record::LogRecord;
+    use common::windows::LogOperation;
+
+    fn filename_value(name: &str, parent: u32) -> Vec<u8> {
+        let mut value = Vec::new();
+        value.extend_from_slice(&parent.to_le_bytes());
+        value.extend_from_slice(&[0, 0, 2, 0]);
+        value.extend_from_slice(&[0; 48]);
+        value.extend_from_slice(&0x20u32.to_le_bytes());
+        value.extend_from_slice(&[0; 4]);
+        value.push(name.len() as u8);
+        value.push(1);
+        for character in name.encode_utf16() {
+            value.extend_from_slice(&character.to_le_bytes());
+        }
+        value
+    }
+
+    fn index_entry(name: &str) -> Vec<u8> {
+        let key = filename_value(name, 40);
+        let mut entry = Vec::new();
+        entry.extend_from_slice(&64u32.to_le_bytes());
+        entry.extend_from_slice(&[0, 0, 3, 0]);
+        entry.extend_from_slice(&((key.len() + 16) as u16).to_le_bytes());
+        entry.extend_from_slice(&(key.len() as u16).to_le_bytes());
+        entry.extend_from_slice(&[0; 4]);
+        entry.extend_from_slice(&key);
+        entry
+    }
+
+    fn file_record(name: &str) -> Vec<u8> {
+        let value = filename_value(name, 40);
+        let mut record = b"FILE".to_vec();
+        for value in [0x30u16, 3, 0, 0, 0, 0, 3, 1, 0x38, 1] {
+            record.extend_from_slice(&value.to_le_bytes());
+        }
+        record.extend_from_slice(&[0; 20]);
+        record.extend_from_slice(&64u32.to_le_bytes());
+        record.resize(0x38, 0);
+
+        let size = (24 + value.len()).div_ceil(8) * 8;
+        record.extend_from_slice(&0x30u32.to_le_bytes());
+        record.extend_from_slice(&(size as u32).to_le_bytes());
+        record.extend_from_slice(&[0, 0, 0x18, 0, 0, 0, 1, 0]);
+        record.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        record.extend_from_slice(&[0x18, 0, 1, 0]);
+        record.extend_from_slice(&value);
+        record.resize(0x38 + size, 0);
+        record.extend_from_slice(&0xffffffffu32.to_le_bytes());
+        record.extend_from_slice(&[0; 4]);
+        record
+    }
+
+    fn test_record(redo: LogOperation, redo_data: Vec<u8>) -> LogRecord {
+        let mut client = vec![0; 40];
+        client[0] = match redo {
+            LogOperation::InitializeFileRecordSegment => 2,
+            LogOperation::AddIndexEntryAllocation => 0xe,
+            _ => 7,
+        };
+        client[4] = 0x28;
+        client[6..8].copy_from_slice(&(redo_data.len() as u16).to_le_bytes());
+        client[20] = 2;
+        client[24] = 16;
+        client.extend_from_slice(&redo_data);
+
+        let mut data = Vec::new();
+        data.extend_from_slice(&100u64.to_le_bytes());
+        data.extend_from_slice(&[0; 16]);
+        data.extend_from_slice(&(client.len() as u32).to_le_bytes());
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&[0; 12]);
+        data.extend_from_slice(&client);
+
+        let (_, record) = LogRecord::parse_record(&data).unwrap();
+        assert_eq!(record.redo_operation, redo);
+        record
+    }
+
+    #[test]
+    fn test_get_operation() {
+        assert_eq!(get_operation(2), LogOperation::InitializeFileRecordSegment);
+        assert_eq!(get_operation(0xe), LogOperation::AddIndexEntryAllocation);
+        assert_eq!(get_operation(0x25), LogOperation::ZeroEndOfFileRecord);
+        assert_eq!(get_operation(0x99), LogOperation::Unknown);
+    }
+
+    #[test]
+    fn test_parse_index_entry() {
+        let (_, (entry, sequence, filename)) =
+            parse_index_entry(&index_entry("secret.txt")).unwrap();
+        assert_eq!(entry, 64);
+        assert_eq!(sequence, 3);
+        assert_eq!(filename.name, "secret.txt");
+        assert_eq!(filename.parent_mft, 40);
+        assert_eq!(filename.parent_sequence, 2);
+
+        assert!(parse_index_entry(&[0; 16]).is_err());
+    }
+
+    #[test]
+    fn test_parse_file_record() {
+        let (_, (header, filename)) = parse_file_record(&file_record("notes.txt")).unwrap();
+        assert_eq!(header.index, 64);
+        assert_eq!(header.sequence, 3);
+        assert_eq!(filename.unwrap().name, "notes.txt");
+
+        assert!(parse_file_record(&[0; 64]).is_err());
+    }
+
+    #[test]
+    fn test_file_details() {
+        let layout = VolumeLayout::default();
+        let record = test_record(
+            LogOperation::InitializeFileRecordSegment,
+            file_record("notes.txt"),
+        );
+        let details = file_details(&record, &layout);
+        assert_eq!(details.mft_entry, Some(64));
+        assert_eq!(details.mft_sequence, 3);
+        assert_eq!(details.filename.unwrap().name, "notes.txt");
+
+        let record = test_record(LogOperation::AddIndexEntryAllocation, index_entry("a.txt"));
+        let details = file_details(&record, &layout);
+        assert_eq!(details.mft_entry, Some(64));
+        assert_eq!(details.filename.unwrap().name, "a.txt");
+
+        let record = test_record(LogOperation::UpdateResidentValue, vec![1, 2, 3, 4]);
+        let details = file_details(&record, &layout);
+        assert_eq!(details.mft_entry, Some(65));
+        assert!(details.filename.is_none());
+        assert_eq!(target_entry(&record, &layout), 65);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{
+    artifacts::os::windows::mft::fixup::Fixup,
+    utils::nom_helper::{
+        Endian, nom_unsigned_eight_bytes, nom_unsigned_four_bytes, nom_unsigned_two_bytes,
+    },
+};
+use nom::bytes::complete::take;
+
+#[derive(Debug)]
+pub(crate) struct RecordPage {
+    pub(crate) sig: u32,
+    pub(crate) fixup_offset: u16,
+    pub(crate) fixup_count: u16,
+    /**Last LSN or file offset */
+    _last_lsn: u64,
+    _flags: u32,
+    _page_count: u16,
+    _page_position: u16,
+    _next_record_offset: u16,
+    pub(crate) last_end_lsn: u64,
+}
+
+impl RecordPage {
+    /// Parse a `$LogFile` record page header (RCRD)
+    pub(crate) fn parse_page(data: &[u8]) -> nom::IResult<&[u8], RecordPage> {
+        let (input, sig) = nom_unsigned_four_bytes(data, Endian::Le)?;
+        let (input, fixup_offset) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, fixup_count) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, last_lsn) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+        let (input, flags) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, page_count) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, page_position) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, next_record_offset) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let reserved_size: u8 = 6;
+        let (input, _reserved) = take(reserved_size)(input)?;
+        let (input, last_end_lsn) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+
+        let page = RecordPage {
+            sig,
+            fixup_offset,
+            fixup_count,
+            _last_lsn: last_lsn,
+            _flags: flags,
+            _page_count: page_count,
+            _page_position: page_position,
+            _next_record_offset: next_record_offset,
+            last_end_lsn,
+        };
+
+        Ok((input, page))
+    }
+
+    /// Apply the fixup values to a full record page
+    pub(crate) fn fixup_page(&self, page: &mut [u8]) -> bool {
+        if self.fixup_offset as usize >= page.len() {
+            return false;
+        }
+
+        // Fixup count includes the placeholder value
+        let Ok((_, fixup)) = Fixup::get_fixup(
+            &page[self.fixup_offset as usize..],
+            self.fixup_count.saturating_sub(1),
+        ) else {
+            return false;
+        };
+        Fixup::apply_sector_fixup(page, &fixup)
+    }
+
+    /// Offset to the first log record in the page. Used if the restart area does not have a valid offset
+    pub(crate) fn data_offset(&self) -> usize {
+        let fixup_size = 2;
+        let alignment = 8;
+        let end = self.fixup_offset as usize + self.fixup_count as usize * fixup_size;
+        end.div_ceil(alignment) * alignment
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RecordPage;
+    use std::{fs::read, path::PathBuf};
+
+    #[test]
+    fn test_parse_page() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/logfile/LogFile.raw");
+        let mut data = read(test_location).unwrap();
+
+        let page = &mut data[0x4000..0x5000];
+        let (_, header) = RecordPage::parse_page(page).unwrap();
+        assert_eq!(header.sig, 0x44524352);
+        assert_eq!(header.fixup_offset, 0x28);
+        assert_eq!(header.fixup_count, 9);
+        assert_eq!(header.last_end_lsn, 0x1001fd);
+        assert_eq!(header.data_offset(), 0x40);
+
+        assert!(header.fixup_page(page));
+        // Second fixup fails because the placeholder values were replaced
+        assert!(!header.fixup_page(page));
+    }
+}
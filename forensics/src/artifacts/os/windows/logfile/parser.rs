@@ -0,0 +1,596 @@
+/**
+ * The NTFS `$LogFile` is a circular transaction journal used by NTFS to recover metadata changes after a crash.
+ * Located at `<drive>:\$LogFile`
+ * Parsing the redo and undo records can show recently created, renamed, and deleted files. Including files that no longer appear in the `UsnJrnl`
+ * Records in the `$LogFile` get overwritten very quickly (usually within hours on an active system)
+ *
+ * References:
+ * `https://github.com/libyal/libfsntfs/blob/main/documentation/New%20Technologies%20File%20System%20(NTFS).asciidoc#metadata_file_logfile`
+ * `https://flatcap.github.io/linux-ntfs/ntfs/files/logfile.html`
+ *
+ * Other Parsers:
+ * `https://github.com/jschicht/LogFileParser`
+ * `https://github.com/Velocidex/velociraptor`
+ */
+use super::{
+    error::LogFileError,
+    operation::{FileDetails, VolumeLayout, file_details},
+    page::RecordPage,
+    record::{LogRecord, parse_records},
+    restart::RestartPage,
+};
+use crate::{
+    artifacts::os::windows::mft::{
+        master::{Lookups, lookup_parent},
+        reader::{setup_mft_reader, setup_mft_reader_windows},
+    },
+    filesystem::{
+        files::{file_extension, read_any},
+        ntfs::{raw_files::raw_read_file, setup::setup_ntfs_parser},
+    },
+    output::{manager::OutputManager, record::serialize_records_to_stream},
+    structs::artifacts::os::windows::LogFileOptions,
+    utils::{environment::get_systemdrive, time::filetime_to_iso},
+};
+use common::windows::LogFileEntry;
+use ntfs::NtfsFile;
+use std::{
+    collections::{HashMap, HashSet},
+    io::BufReader,
+    mem::take,
+};
+use tracing::{error, warn};
+
+/// Parse the `$LogFile` and output the log records
+pub(crate) fn grab_logfile(
+    options: &LogFileOptions,
+    manager: &mut OutputManager,
+) -> Result<(), LogFileError> {
+    if let Some(alt) = options.alt_drive {
+        return parse_logfile_drive(alt, manager, options);
+    }
+    if let Some(path) = &options.alt_file {
+        return parse_logfile_path(path, &options.alt_mft, manager, options);
+    }
+    let systemdrive_result = get_systemdrive();
+    let systemdrive = match systemdrive_result {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Could not get systemdrive: {err:?}");
+            return Err(LogFileError::SystemDrive);
+        }
+    };
+
+    parse_logfile_drive(systemdrive, manager, options)
+}
+
+/// Read the `$LogFile` and `$MFT` by parsing the NTFS filesystem
+fn parse_logfile_drive(
+    drive: char,
+    manager: &mut OutputManager,
+    options: &LogFileOptions,
+) -> Result<(), LogFileError> {
+    let path = format!("{drive}:\\$LogFile");
+    let mut data = match raw_read_file(&path) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Could not read $LogFile: {err:?}");
+            return Err(LogFileError::ReadFile);
+        }
+    };
+    let records = parse_logfile(&mut data)?;
+
+    let mut ntfs_parser = match setup_ntfs_parser(drive) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Cannot setup NTFS parser: {err:?}");
+            return Err(LogFileError::Parser);
+        }
+    };
+    let layout = VolumeLayout {
+        cluster_size: ntfs_parser.ntfs.cluster_size() as u64,
+        record_size: ntfs_parser.ntfs.file_record_size() as u64,
+    };
+
+    let ntfs_file = match setup_mft_reader_windows(
+        &ntfs_parser.ntfs,
+        &mut ntfs_parser.fs,
+        &format!("{drive}:\\$MFT"),
+    ) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Cannot read the MFT file: {err:?}");
+            return Err(LogFileError::ReadFile);
+        }
+    };
+
+    extract_entries(
+        &records,
+        Some(&mut ntfs_parser.fs),
+        Some(&ntfs_file),
+        &layout,
+        Some(manager),
+        Some(options),
+        &path,
+        &drive.to_string(),
+    )?;
+    Ok(())
+}
+
+/// Parse the `$LogFile` at provided path. An optional `$MFT` file can be provided to resolve parent directories
+fn parse_logfile_path(
+    path: &str,
+    mft_path: &Option<String>,
+    manager: &mut OutputManager,
+    options: &LogFileOptions,
+) -> Result<(), LogFileError> {
+    let mut data = read_logfile(path)?;
+    let records = parse_logfile(&mut data)?;
+
+    let mut reader = if let Some(mft) = mft_path {
+        match setup_mft_reader(mft) {
+            Ok(result) => Some(BufReader::new(result)),
+            Err(err) => {
+                error!("Could not create reader for alt MFT file: {err:?}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    extract_entries(
+        &records,
+        reader.as_mut(),
+        None,
+        &VolumeLayout::default(),
+        Some(manager),
+        Some(options),
+        path,
+        // Drive is empty because we cannot be certain what the source drive is
+        "",
+    )?;
+    Ok(())
+}
+
+/// Read the `$LogFile` at provided path. Paths in archives or disk images are read with the `Accessor`
+///
+/// Example: `ewf:case.E01!p2/$LogFile`
+fn read_logfile(path: &str) -> Result<Vec<u8>, LogFileError> {
+    match read_any(path) {
+        Ok(result) => Ok(result),
+        Err(err) => {
+            error!("Could not read $LogFile {path}: {err:?}");
+            Err(LogFileError::ReadFile)
+        }
+    }
+}
+
+/// Parse the restart and record pages in the `$LogFile`. Returns all log records sorted by LSN
+fn parse_logfile(data: &mut [u8]) -> Result<Vec<LogRecord>, LogFileError> {
+    let restart = get_restart(data)?;
+    if restart.is_clean() {
+        warn!("$LogFile was cleanly closed. Only older log records may be available");
+    }
+
+    let page_size = restart.log_page_size as usize;
+    let record_page = 0x44524352;
+
+    // Offset to the log records in each record page
+    let restart_data_offset = restart.area.page_data_offset as usize;
+
+    let mut seen = HashSet::new();
+    let mut records = Vec::new();
+    let mut run = Vec::new();
+    let mut run_data_size = 0;
+
+    // First two pages are the restart pages
+    for page in data.chunks_exact_mut(page_size).skip(2) {
+        let header = match RecordPage::parse_page(page) {
+            Ok((_, result)) if result.sig == record_page => result,
+            _ => {
+                // Unused or corrupted page. Any records spanning into this page are incomplete
+                records.append(&mut parse_records(&run, run_data_size, &mut seen));
+                run.clear();
+                continue;
+            }
+        };
+        if !header.fixup_page(page) {
+            warn!(
+                "Fixup values did not match for $LogFile page at LSN {}. Skipping page",
+                header.last_end_lsn
+            );
+            records.append(&mut parse_records(&run, run_data_size, &mut seen));
+            run.clear();
+            continue;
+        }
+
+        let data_offset = if restart_data_offset != 0 && restart_data_offset < page_size {
+            restart_data_offset
+        } else {
+            header.data_offset()
+        };
+        if data_offset >= page_size {
+            continue;
+        }
+
+        let page_data_size = page_size - data_offset;
+        if !run.is_empty() && page_data_size != run_data_size {
+            records.append(&mut parse_records(&run, run_data_size, &mut seen));
+            run.clear();
+        }
+        run_data_size = page_data_size;
+        run.extend_from_slice(&page[data_offset..]);
+    }
+    records.append(&mut parse_records(&run, run_data_size, &mut seen));
+
+    records.sort_by_key(|record| record.lsn);
+    Ok(records)
+}
+
+/// Get the first valid restart page. The second restart page is a backup of the first
+fn get_restart(data: &mut [u8]) -> Result<RestartPage, LogFileError> {
+    let restart_page = 0x52545352;
+    let min_page_size = 512;
+    let mut offset = 0;
+
+    // Check both restart pages
+    for _ in 0..2 {
+        let restart = match RestartPage::parse_restart(&data[offset.min(data.len())..]) {
+            Ok((_, result)) if result.sig == restart_page => result,
+            _ => break,
+        };
+        let page_size = restart.log_page_size as usize;
+        if page_size < min_page_size
+            || !page_size.is_power_of_two()
+            || offset + page_size > data.len()
+        {
+            break;
+        }
+
+        let page = &mut data[offset..offset + page_size];
+        if RestartPage::fixup_page(page)
+            && let Ok((_, result)) = RestartPage::parse_restart(page)
+        {
+            return Ok(result);
+        }
+        warn!("Fixup values did not match for $LogFile restart page at offset {offset}");
+        offset += page_size;
+    }
+
+    error!("Could not find a valid $LogFile restart page");
+    Err(LogFileError::NoRestartPage)
+}
+
+/// Filename information recovered from `$LogFile` records
+struct CachedName {
+    name: String,
+    parent_mft: u32,
+    parent_sequence: u16,
+}
+
+/// Loop through the log records and output the entries
+#[allow(clippy::too_many_arguments)]
+fn extract_entries<T: std::io::Seek + std::io::Read>(
+    records: &[LogRecord],
+    mut reader: Option<&mut BufReader<T>>,
+    ntfs_file: Option<&NtfsFile<'_>>,
+    layout: &VolumeLayout,
+    mut manager: Option<&mut OutputManager>,
+    options: Option<&LogFileOptions>,
+    path: &str,
+    drive: &str,
+) -> Result<Vec<LogFileEntry>, LogFileError> {
+    let details: Vec<FileDetails> = records
+        .iter()
+        .map(|record| file_details(record, layout))
+        .collect();
+
+    // Cache the names found in the $LogFile. Used if the MFT no longer has the parent directory
+    let mut name_cache = HashMap::new();
+    for detail in &details {
+        if let (Some(entry), Some(filename)) = (detail.mft_entry, &detail.filename) {
+            name_cache.insert(
+                entry,
+                CachedName {
+                    name: filename.name.clone(),
+                    parent_mft: filename.parent_mft,
+                    parent_sequence: filename.parent_sequence,
+                },
+            );
+        }
+    }
+
+    let mut mft_cache = HashMap::new();
+    let mut entries = Vec::new();
+    for (record, detail) in records.iter().zip(details) {
+        let mut entry = LogFileEntry {
+            lsn: record.lsn,
+            previous_lsn: record.previous_lsn,
+            undo_next_lsn: record.undo_next_lsn,
+            transaction_id: record.transaction_id,
+            redo_operation: record.redo_operation.clone(),
+            undo_operation: record.undo_operation.clone(),
+            target_attribute: record.target_attribute,
+            target_vcn: record.target_vcn,
+            cluster_block_offset: record.cluster_block_offset,
+            record_offset: record.record_offset,
+            attribute_offset: record.attribute_offset,
+            redo_size: record.redo_data.len() as u16,
+            undo_size: record.undo_data.len() as u16,
+            mft_entry: detail.mft_entry.unwrap_or_default(),
+            mft_sequence: detail.mft_sequence,
+            parent_mft_entry: 0,
+            parent_mft_sequence: 0,
+            filename: String::new(),
+            extension: String::new(),
+            full_path: String::new(),
+            file_attributes: Vec::new(),
+            created: String::new(),
+            modified: String::new(),
+            changed: String::new(),
+            accessed: String::new(),
+            drive: drive.to_string(),
+            evidence: path.to_string(),
+        };
+
+        if let Some(filename) = &detail.filename {
+            entry.filename = filename.name.clone();
+            entry.parent_mft_entry = filename.parent_mft;
+            entry.parent_mft_sequence = filename.parent_sequence;
+            entry.file_attributes = filename.file_attributes.clone();
+            entry.created = filetime_to_iso(filename.created);
+            entry.modified = filetime_to_iso(filename.modified);
+            entry.changed = filetime_to_iso(filename.changed);
+            entry.accessed = filetime_to_iso(filename.accessed);
+        } else if let Some(mft_entry) = detail.mft_entry
+            && let Some(cache_hit) = name_cache.get(&mft_entry)
+        {
+            // Records that update an existing FILE record do not contain the filename
+            entry.filename = cache_hit.name.clone();
+            entry.parent_mft_entry = cache_hit.parent_mft;
+            entry.parent_mft_sequence = cache_hit.parent_sequence;
+        }
+
+        if !entry.filename.is_empty() {
+            let parent = parent_path(
+                entry.parent_mft_entry,
+                entry.parent_mft_sequence,
+                &mut reader,
+                ntfs_file,
+                &mut mft_cache,
+                &name_cache,
+            );
+            entry.extension = file_extension(&entry.filename);
+            entry.full_path = format!("{parent}\\{}", entry.filename);
+        }
+
+        entries.push(entry);
+        let limit = 1000;
+        // If we are give an output structure we will dump the results
+        if let Some(out) = manager.as_deref_mut()
+            && entries.len() == limit
+            && let Some(opt) = options
+        {
+            output_logfile(take(&mut entries), out, opt)?;
+        }
+    }
+
+    if let Some(out) = manager
+        && !entries.is_empty()
+        && let Some(opt) = options
+    {
+        output_logfile(take(&mut entries), out, opt)?;
+    }
+
+    // If no output structure was provided. Return all parsed entries
+    Ok(entries)
+}
+
+/// Determine the parent directory path. Tries the `$MFT` first and then the names cached from the `$LogFile`
+fn parent_path<T: std::io::Seek + std::io::Read>(
+    parent_mft: u32,
+    parent_sequence: u16,
+    reader: &mut Option<&mut BufReader<T>>,
+    ntfs_file: Option<&NtfsFile<'_>>,
+    mft_cache: &mut HashMap<String, String>,
+    name_cache: &HashMap<u32, CachedName>,
+) -> String {
+    let root = 5;
+    if parent_mft == root {
+        return String::from(".");
+    }
+
+    if let Some(mft_reader) = reader {
+        let cache_key = format!("{parent_mft}_{parent_sequence}");
+        let path = if let Some(cache_hit) = mft_cache.get(&cache_key) {
+            cache_hit.clone()
+        } else {
+            let mut tracker = Lookups {
+                parent_index: parent_mft,
+                parent_sequence,
+                size: 0,
+                tracker: HashSet::new(),
+            };
+            lookup_parent(
+                mft_reader,
+                ntfs_file,
+                mft_cache,
+                &HashMap::new(),
+                &mut tracker,
+            )
+            .unwrap_or_default()
+        };
+        if !path.is_empty() && !path.starts_with("$OrphanFiles") {
+            return path;
+        }
+    }
+
+    let mut tracker = HashSet::new();
+    lookup_name_cache(parent_mft, name_cache, &mut tracker)
+}
+
+/// Build a directory path using only the names found in the `$LogFile`
+fn lookup_name_cache(
+    entry: u32,
+    name_cache: &HashMap<u32, CachedName>,
+    tracker: &mut HashSet<u32>,
+) -> String {
+    let Some(cache_hit) = name_cache.get(&entry) else {
+        return String::from("$OrphanFiles");
+    };
+    if !tracker.insert(entry) {
+        warn!("Got recursive parent in $LogFile names. Stopping lookups now");
+        return String::from("$OrphanFiles");
+    }
+
+    let root = 5;
+    if cache_hit.parent_mft == root {
+        return format!(".\\{}", cache_hit.name);
+    }
+    let parent = lookup_name_cache(cache_hit.parent_mft, name_cache, tracker);
+    format!("{parent}\\{}", cache_hit.name)
+}
+
+/// Output `$LogFile` entries based on `Output` structure
+fn output_logfile(
+    entries: Vec<LogFileEntry>,
+    manager: &mut OutputManager,
+    options: &LogFileOptions,
+) -> Result<(), LogFileError> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let mut records = match serialize_records_to_stream(entries) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Failed to serialize $LogFile entries: {err:?}");
+            return Err(LogFileError::Serialize);
+        }
+    };
+    let artifact_name = "logfile";
+    if let Err(err) = manager.write_artifact(artifact_name, options, &mut records) {
+        error!("Could not output $LogFile entries: {err:?}");
+        return Err(LogFileError::OutputData);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract_entries, grab_logfile, parse_logfile};
+    use crate::{
+        accessor::io::reader::AccessorReader,
+        artifacts::os::windows::logfile::operation::VolumeLayout,
+        output::manager::OutputManager,
+        structs::{
+            artifacts::os::windows::LogFileOptions,
+            toml::{OutputConfig, OutputDestination, OutputFormat},
+        },
+    };
+    use common::windows::{AttributeFlags, LogOperation};
+    use std::{fs::read, io::BufReader, path::PathBuf};
+
+    fn output_options(name: &str, directory: &str, compress: bool) -> OutputManager {
+        let config = OutputConfig {
+            name: name.to_string(),
+            directory: PathBuf::from(directory),
+            format: OutputFormat::Jsonl,
+            compress,
+            endpoint_id: String::from("abcd"),
+            destination: OutputDestination::Local,
+            ..Default::default()
+        };
+        OutputManager::new(config).unwrap()
+    }
+
+    #[test]
+    fn test_grab_logfile_alt_file() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/logfile/LogFile.raw");
+        let params = LogFileOptions {
+            alt_drive: None,
+            alt_file: Some(test_location.display().to_string()),
+            alt_mft: None,
+        };
+        let mut output = output_options("logfile_temp", "./tmp", false);
+
+        grab_logfile(&params, &mut output).unwrap();
+    }
+
+    #[test]
+    fn test_parse_logfile() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/logfile/LogFile.raw");
+        let mut data = read(test_location).unwrap();
+
+        let records = parse_logfile(&mut data).unwrap();
+        assert_eq!(records.len(), 8);
+        assert_eq!(records[0].lsn, 0x100000);
+        assert_eq!(records[7].lsn, 0x1001fd);
+
+        let entries = extract_entries::<AccessorReader>(
+            &records,
+            None::<&mut BufReader<AccessorReader>>,
+            None,
+            &VolumeLayout::default(),
+            None,
+            None,
+            "LogFile.raw",
+            "",
+        )
+        .unwrap();
+        assert_eq!(entries.len(), 8);
+
+        assert_eq!(
+            entries[0].redo_operation,
+            LogOperation::InitializeFileRecordSegment
+        );
+        assert_eq!(entries[0].full_path, ".\\Temp");
+        assert!(
+            entries[0]
+                .file_attributes
+                .contains(&AttributeFlags::Directory)
+        );
+
+        assert_eq!(entries[1].mft_entry, 64);
+        assert_eq!(entries[1].mft_sequence, 3);
+        assert_eq!(entries[1].full_path, ".\\Temp\\secret.txt");
+        assert_eq!(entries[1].extension, "txt");
+        assert_eq!(entries[1].created, "2024-03-01T12:30:00.000Z");
+
+        assert_eq!(
+            entries[3].redo_operation,
+            LogOperation::DeleteIndexEntryAllocation
+        );
+        assert_eq!(entries[3].filename, "secret.txt");
+        assert_eq!(entries[4].filename, "renamed.txt");
+        assert_eq!(entries[5].redo_operation, LogOperation::Noop);
+
+        // Record spans two pages
+        assert_eq!(entries[6].redo_operation, LogOperation::UpdateResidentValue);
+        assert_eq!(entries[6].mft_entry, 64);
+        assert_eq!(entries[6].record_offset, 0x38);
+        assert_eq!(entries[6].attribute_offset, 0x20);
+        assert_eq!(entries[6].full_path, ".\\Temp\\renamed.txt");
+
+        assert_eq!(
+            entries[7].redo_operation,
+            LogOperation::DeallocateFileRecordSegment
+        );
+        assert_eq!(
+            entries[7].undo_operation,
+            LogOperation::InitializeFileRecordSegment
+        );
+        assert_eq!(entries[7].filename, "renamed.txt");
+        assert_eq!(entries[7].evidence, "LogFile.raw");
+    }
+
+    #[test]
+    fn test_parse_logfile_bad_data() {
+        let mut data = vec![0; 8192];
+        assert!(parse_logfile(&mut data).is_err());
+    }
+}
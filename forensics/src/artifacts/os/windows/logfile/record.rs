@@ -0,0 +1,259 @@
+use super::operation::get_operation;
+use crate::utils::nom_helper::{
+    Endian, nom_unsigned_eight_bytes, nom_unsigned_four_bytes, nom_unsigned_two_bytes,
+};
+use common::windows::LogOperation;
+use nom::bytes::complete::take;
+use std::collections::HashSet;
+
+#[derive(Debug)]
+pub(crate) struct LogRecord {
+    pub(crate) lsn: u64,
+    pub(crate) previous_lsn: u64,
+    pub(crate) undo_next_lsn: u64,
+    pub(crate) client_data_size: u32,
+    _client_id: u32,
+    pub(crate) record_type: u32,
+    pub(crate) transaction_id: u32,
+    _flags: u16,
+    pub(crate) redo_operation: LogOperation,
+    pub(crate) undo_operation: LogOperation,
+    pub(crate) target_attribute: u16,
+    pub(crate) lcns_to_follow: u16,
+    pub(crate) record_offset: u16,
+    pub(crate) attribute_offset: u16,
+    pub(crate) cluster_block_offset: u16,
+    pub(crate) target_vcn: u64,
+    pub(crate) redo_data: Vec<u8>,
+    pub(crate) undo_data: Vec<u8>,
+}
+
+/// Size of the log record header before the client data
+const RECORD_HEADER_SIZE: usize = 48;
+/// Size of the redo/undo header at the start of the client data
+const CLIENT_HEADER_SIZE: usize = 32;
+
+impl LogRecord {
+    /// Parse a single log record. The client data of records that span pages must already be joined
+    pub(crate) fn parse_record(data: &[u8]) -> nom::IResult<&[u8], LogRecord> {
+        let (input, lsn) = nom_unsigned_eight_bytes(data, Endian::Le)?;
+        let (input, previous_lsn) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+        let (input, undo_next_lsn) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+        let (input, client_data_size) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, client_id) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, record_type) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, transaction_id) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, flags) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let reserved_size: u8 = 6;
+        let (input, _reserved) = take(reserved_size)(input)?;
+        let (remaining, client_data) = take(client_data_size)(input)?;
+
+        let mut record = LogRecord {
+            lsn,
+            previous_lsn,
+            undo_next_lsn,
+            client_data_size,
+            _client_id: client_id,
+            record_type,
+            transaction_id,
+            _flags: flags,
+            redo_operation: LogOperation::Noop,
+            undo_operation: LogOperation::Noop,
+            target_attribute: 0,
+            lcns_to_follow: 0,
+            record_offset: 0,
+            attribute_offset: 0,
+            cluster_block_offset: 0,
+            target_vcn: 0,
+            redo_data: Vec::new(),
+            undo_data: Vec::new(),
+        };
+
+        // Only client records have redo and undo operations
+        let client_record = 1;
+        if record_type != client_record || client_data.len() < CLIENT_HEADER_SIZE {
+            return Ok((remaining, record));
+        }
+        record.parse_client_data(client_data)?;
+
+        Ok((remaining, record))
+    }
+
+    /// Parse the redo and undo operations in the client data
+    fn parse_client_data<'a>(&mut self, data: &'a [u8]) -> nom::IResult<&'a [u8], ()> {
+        let (input, redo_operation) = nom_unsigned_two_bytes(data, Endian::Le)?;
+        let (input, undo_operation) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, redo_offset) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, redo_size) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, undo_offset) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, undo_size) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, target_attribute) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, lcns_to_follow) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, record_offset) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, attribute_offset) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, cluster_block_offset) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, _reserved) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, target_vcn) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+
+        self.redo_operation = get_operation(redo_operation);
+        self.undo_operation = get_operation(undo_operation);
+        self.target_attribute = target_attribute;
+        self.lcns_to_follow = lcns_to_follow;
+        self.record_offset = record_offset;
+        self.attribute_offset = attribute_offset;
+        self.cluster_block_offset = cluster_block_offset;
+        self.target_vcn = target_vcn;
+        self.redo_data = LogRecord::operation_data(data, redo_offset, redo_size);
+        self.undo_data = LogRecord::operation_data(data, undo_offset, undo_size);
+
+        Ok((input, ()))
+    }
+
+    /// Get the redo or undo data. Returns empty data if the offset or size is out of bounds
+    fn operation_data(data: &[u8], offset: u16, size: u16) -> Vec<u8> {
+        let start = offset as usize;
+        let end = start + size as usize;
+        if size == 0 || end > data.len() {
+            return Vec::new();
+        }
+        data[start..end].to_vec()
+    }
+}
+
+/// Parse log records from the data areas of consecutive record pages
+///
+/// `page_data_size` is the size of each page data area. Records may span into the next page data area
+pub(crate) fn parse_records(
+    data: &[u8],
+    page_data_size: usize,
+    seen: &mut HashSet<u64>,
+) -> Vec<LogRecord> {
+    let mut records = Vec::new();
+    if page_data_size < RECORD_HEADER_SIZE {
+        return records;
+    }
+
+    let alignment = 8;
+    let mut offset = 0;
+    while offset + RECORD_HEADER_SIZE <= data.len() {
+        let page_end = (offset / page_data_size + 1) * page_data_size;
+        let record = match LogRecord::parse_record(&data[offset..]) {
+            Ok((_, result)) if is_valid(&result, data.len() - offset) => result,
+            // Rest of the page is empty or contains remnant data. Move to the next page
+            _ => {
+                offset = page_end;
+                continue;
+            }
+        };
+
+        let size =
+            (RECORD_HEADER_SIZE + record.client_data_size as usize).div_ceil(alignment) * alignment;
+        offset += size;
+
+        // Record headers are never split across pages
+        let page_end = (offset / page_data_size + 1) * page_data_size;
+        if page_end - offset < RECORD_HEADER_SIZE {
+            offset = page_end;
+        }
+
+        // Buffer pages and older pages may contain the same records
+        if !seen.insert(record.lsn) {
+            continue;
+        }
+        records.push(record);
+    }
+
+    records
+}
+
+/// Basic sanity checks for log record headers
+fn is_valid(record: &LogRecord, remaining: usize) -> bool {
+    let client_record = 1;
+    let client_restart = 2;
+    record.lsn != 0
+        && (record.record_type == client_record || record.record_type == client_restart)
+        && record.client_data_size as usize + RECORD_HEADER_SIZE <= remaining
+        && record.previous_lsn <= record.lsn
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LogRecord, parse_records};
+    use common::windows::LogOperation;
+    use std::collections::HashSet;
+
+    fn test_record(lsn: u64, redo: u16, redo_data: &[u8]) -> Vec<u8> {
+        let mut client = Vec::new();
+        for value in [
+            redo,
+            0,
+            0x28,
+            redo_data.len() as u16,
+            0x28,
+            0,
+            0x18,
+            1,
+            0,
+            0,
+            2,
+            0,
+        ] {
+            client.extend_from_slice(&value.to_le_bytes());
+        }
+        client.extend_from_slice(&16u64.to_le_bytes());
+        client.extend_from_slice(&0x1234u64.to_le_bytes());
+        client.extend_from_slice(redo_data);
+        while client.len() % 8 != 0 {
+            client.push(0);
+        }
+
+        let mut record = Vec::new();
+        record.extend_from_slice(&lsn.to_le_bytes());
+        record.extend_from_slice(&(lsn - 1).to_le_bytes());
+        record.extend_from_slice(&0u64.to_le_bytes());
+        record.extend_from_slice(&(client.len() as u32).to_le_bytes());
+        record.extend_from_slice(&0u32.to_le_bytes());
+        record.extend_from_slice(&1u32.to_le_bytes());
+        record.extend_from_slice(&0x18u32.to_le_bytes());
+        record.extend_from_slice(&[0; 8]);
+        record.append(&mut client);
+        record
+    }
+
+    #[test]
+    fn test_parse_record() {
+        let data = test_record(100, 7, &[1, 2, 3, 4]);
+        let (remaining, record) = LogRecord::parse_record(&data).unwrap();
+        assert!(remaining.is_empty());
+        assert_eq!(record.lsn, 100);
+        assert_eq!(record.previous_lsn, 99);
+        assert_eq!(record.transaction_id, 0x18);
+        assert_eq!(record.redo_operation, LogOperation::UpdateResidentValue);
+        assert_eq!(record.undo_operation, LogOperation::Noop);
+        assert_eq!(record.target_vcn, 16);
+        assert_eq!(record.cluster_block_offset, 2);
+        assert_eq!(record.lcns_to_follow, 1);
+        assert_eq!(record.redo_data, [1, 2, 3, 4]);
+        assert!(record.undo_data.is_empty());
+    }
+
+    #[test]
+    fn test_parse_records() {
+        let mut data = test_record(100, 2, &[0; 16]);
+        data.append(&mut test_record(101, 3, &[]));
+        data.append(&mut test_record(100, 3, &[]));
+        // Rest of the first page is empty
+        data.resize(512, 0);
+        data.append(&mut test_record(102, 7, &[1, 2]));
+        data.resize(1024, 0);
+
+        let mut seen = HashSet::new();
+        let records = parse_records(&data, 512, &mut seen);
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[2].lsn, 102);
+        assert_eq!(
+            records[1].redo_operation,
+            LogOperation::DeallocateFileRecordSegment
+        );
+    }
+}
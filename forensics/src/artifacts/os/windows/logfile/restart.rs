@@ -0,0 +1,161 @@
+use crate::{
+    artifacts::os::windows::mft::fixup::Fixup,
+    utils::nom_helper::{
+        Endian, nom_unsigned_eight_bytes, nom_unsigned_four_bytes, nom_unsigned_two_bytes,
+    },
+};
+use nom::bytes::complete::take;
+
+#[derive(Debug)]
+pub(crate) struct RestartPage {
+    pub(crate) sig: u32,
+    fixup_offset: u16,
+    fixup_count: u16,
+    _checkdisk_lsn: u64,
+    _system_page_size: u32,
+    pub(crate) log_page_size: u32,
+    restart_area_offset: u16,
+    _minor_version: i16,
+    _major_version: i16,
+    pub(crate) area: RestartArea,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct RestartArea {
+    _current_lsn: u64,
+    _log_clients: u16,
+    _first_free_client: u16,
+    _first_in_use_client: u16,
+    pub(crate) flags: u16,
+    _sequence_number_bits: u32,
+    _restart_area_size: u16,
+    _client_array_offset: u16,
+    _file_size: u64,
+    _last_lsn_data_size: u32,
+    _record_header_size: u16,
+    pub(crate) page_data_offset: u16,
+    _log_open_count: u32,
+}
+
+impl RestartPage {
+    /// Parse a `$LogFile` restart page (RSTR). The page should be the full log page
+    pub(crate) fn parse_restart(data: &[u8]) -> nom::IResult<&[u8], RestartPage> {
+        let (input, sig) = nom_unsigned_four_bytes(data, Endian::Le)?;
+        let (input, fixup_offset) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, fixup_count) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, checkdisk_lsn) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+        let (input, system_page_size) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, log_page_size) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, restart_area_offset) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, minor_version) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (_, major_version) = nom_unsigned_two_bytes(input, Endian::Le)?;
+
+        let (area_data, _) = take(restart_area_offset)(data)?;
+        let (input, area) = RestartPage::parse_area(area_data)?;
+
+        let page = RestartPage {
+            sig,
+            fixup_offset,
+            fixup_count,
+            _checkdisk_lsn: checkdisk_lsn,
+            _system_page_size: system_page_size,
+            log_page_size,
+            restart_area_offset,
+            _minor_version: minor_version as i16,
+            _major_version: major_version as i16,
+            area,
+        };
+
+        Ok((input, page))
+    }
+
+    /// Apply the fixup values to a restart page before parsing the restart area
+    pub(crate) fn fixup_page(page: &mut [u8]) -> bool {
+        let Ok((_, header)) = RestartPage::parse_restart(page) else {
+            return false;
+        };
+        // Fixup array must be before the restart area
+        if header.fixup_offset as usize >= page.len()
+            || header.fixup_offset >= header.restart_area_offset
+        {
+            return false;
+        }
+
+        let Ok((_, fixup)) = Fixup::get_fixup(
+            &page[header.fixup_offset as usize..],
+            header.fixup_count.saturating_sub(1),
+        ) else {
+            return false;
+        };
+        Fixup::apply_sector_fixup(page, &fixup)
+    }
+
+    /// Check if the volume was cleanly dismounted
+    pub(crate) fn is_clean(&self) -> bool {
+        let clean = 0x2;
+        (self.area.flags & clean) == clean
+    }
+
+    /// Parse the restart area that follows the restart page header
+    fn parse_area(data: &[u8]) -> nom::IResult<&[u8], RestartArea> {
+        let (input, current_lsn) = nom_unsigned_eight_bytes(data, Endian::Le)?;
+        let (input, log_clients) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, first_free_client) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, first_in_use_client) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, flags) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, sequence_number_bits) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, restart_area_size) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, client_array_offset) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, file_size) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+        let (input, last_lsn_data_size) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, record_header_size) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, page_data_offset) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, log_open_count) = nom_unsigned_four_bytes(input, Endian::Le)?;
+
+        let area = RestartArea {
+            _current_lsn: current_lsn,
+            _log_clients: log_clients,
+            _first_free_client: first_free_client,
+            _first_in_use_client: first_in_use_client,
+            flags,
+            _sequence_number_bits: sequence_number_bits,
+            _restart_area_size: restart_area_size,
+            _client_array_offset: client_array_offset,
+            _file_size: file_size,
+            _last_lsn_data_size: last_lsn_data_size,
+            _record_header_size: record_header_size,
+            page_data_offset,
+            _log_open_count: log_open_count,
+        };
+
+        Ok((input, area))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RestartPage;
+    use std::{fs::read, path::PathBuf};
+
+    #[test]
+    fn test_parse_restart() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/logfile/LogFile.raw");
+        let mut data = read(test_location).unwrap();
+
+        let page = &mut data[..4096];
+        assert!(RestartPage::fixup_page(page));
+        let (_, restart) = RestartPage::parse_restart(page).unwrap();
+        assert_eq!(restart.sig, 0x52545352);
+        assert_eq!(restart.log_page_size, 4096);
+        assert_eq!(restart._system_page_size, 4096);
+        assert_eq!(restart._major_version, 1);
+        assert_eq!(restart._minor_version, 1);
+        assert_eq!(restart.area._current_lsn, 0x1001fd);
+        assert_eq!(restart.area._log_clients, 1);
+        assert_eq!(restart.area.page_data_offset, 0x40);
+        assert_eq!(restart.area._record_header_size, 0x30);
+        assert_eq!(restart.area._file_size, 24576);
+        assert!(!restart.is_clean());
+    }
+}
@@ -0,0 +1,367 @@
+use super::{
+    attributes::attribute::grab_attributes,
+    error::MftError,
+    fixup::Fixup,
+    header::{EntryFlags, MftHeader},
+    master::{Lookups, lookup_parent, output_mft},
+    reader::setup_mft_reader_windows,
+};
+use crate::{
+    filesystem::{
+        files::file_extension,
+        ntfs::{
+            setup::setup_ntfs_parser,
+            unallocated::{get_slack, get_unallocated, scan_ranges},
+        },
+    },
+    output::manager::OutputManager,
+    structs::artifacts::os::windows::MftOptions,
+    utils::time::filetime_to_iso,
+};
+use common::windows::{AttributeFlags, MftEntry};
+use ntfs::NtfsFile;
+use std::{
+    collections::{HashMap, HashSet},
+    io::BufReader,
+    mem::take,
+};
+use tracing::{error, info};
+
+/// FILE record found in unallocated or slack space. Fixup values have already been applied
+struct CarvedRecord {
+    header: MftHeader,
+    data: Vec<u8>,
+}
+
+/// Carve `MFT` FILE records from the unallocated clusters and slack space of the provided drive. Carved entries are output with the `carved` flag
+pub(crate) fn carve_mft(
+    drive: char,
+    manager: &mut OutputManager,
+    options: &MftOptions,
+) -> Result<(), MftError> {
+    let mut ntfs_parser = match setup_ntfs_parser(drive) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Could not setup NTFS parser: {err:?}");
+            return Err(MftError::Systemdrive);
+        }
+    };
+    let mut ranges = match get_unallocated(drive, &ntfs_parser.ntfs) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Could not determine unallocated clusters: {err:?}");
+            return Err(MftError::ReadFile);
+        }
+    };
+    match get_slack(&ntfs_parser.ntfs, &mut ntfs_parser.fs, &manager.cancel) {
+        Ok(mut result) => ranges.append(&mut result),
+        Err(err) => {
+            error!("Could not determine slack space, only scanning unallocated clusters: {err:?}");
+        }
+    }
+
+    let ntfs_file = setup_mft_reader_windows(
+        &ntfs_parser.ntfs,
+        &mut ntfs_parser.fs,
+        &format!("{drive}:\\$MFT"),
+    )?;
+
+    let evidence = format!("\\\\.\\{drive}:");
+    let drive_name = drive.to_string();
+    let mut cache = HashMap::new();
+    let mut entries = Vec::new();
+    let mut seen = HashSet::new();
+    let mut count = 0;
+    // Largest FILE record size
+    let overlap = 4096;
+    let cancel = manager.cancel.clone();
+    scan_ranges(
+        &mut ntfs_parser.fs,
+        &ranges,
+        overlap,
+        &cancel,
+        |fs, _, data, scan_size| {
+            for record in carve_file_records(data, scan_size) {
+                // Same FILE record may exist multiple times in unallocated space
+                if !seen.insert((
+                    record.header.index,
+                    record.header.sequence,
+                    record.header.transaction_seq,
+                )) {
+                    continue;
+                }
+                count += 1;
+                entries.append(&mut carved_entries(
+                    &record,
+                    fs,
+                    Some(&ntfs_file),
+                    &mut cache,
+                    &evidence,
+                    &drive_name,
+                ));
+            }
+
+            let limit = 1000;
+            if entries.len() >= limit {
+                let _ = output_mft(take(&mut entries), manager, options);
+            }
+            // Keep memory usage bounded on large volumes. Duplicates far apart may be output again
+            let seen_limit = 100000;
+            if seen.len() >= seen_limit {
+                seen.clear();
+            }
+            if cache.len() >= seen_limit {
+                cache.clear();
+            }
+        },
+    );
+    info!("Carved {count} MFT FILE records from {drive}");
+
+    if !entries.is_empty() {
+        let _ = output_mft(entries, manager, options);
+    }
+    Ok(())
+}
+
+/// Scan data for `MFT` FILE records. Records are always aligned to the sector size.
+/// Only records that start before `scan_size` are returned
+fn carve_file_records(data: &[u8], scan_size: usize) -> Vec<CarvedRecord> {
+    let mut records = Vec::new();
+    let sector_size = 512;
+    let file0 = b"FILE";
+
+    let mut offset = 0;
+    while offset < scan_size.min(data.len()) {
+        let remaining = &data[offset..];
+        if !remaining.starts_with(file0) {
+            offset += sector_size;
+            continue;
+        }
+
+        match validate_record(remaining) {
+            Some(record) => {
+                offset += record.header.total_size as usize;
+                records.push(record);
+            }
+            None => offset += sector_size,
+        }
+    }
+
+    records
+}
+
+/// Validate the FILE record header and apply the fixup values. Returns None if the record is not valid
+fn validate_record(data: &[u8]) -> Option<CarvedRecord> {
+    let (_, header) = MftHeader::parse_header(data).ok()?;
+
+    let sector_size = 512;
+    let record_sizes = [1024, 4096];
+    // Older NTFS versions (3.0) have the fixup values at offset 0x2a
+    let min_fixup_offset = 0x2a;
+    let fixup_size = 2;
+    let total_size = header.total_size as usize;
+    let fixup_end = header.fix_up_value_offset as usize + header.fix_up_count as usize * fixup_size;
+    if !record_sizes.contains(&total_size)
+        || total_size > data.len()
+        || header.fix_up_count as usize != total_size / sector_size + 1
+        || (header.fix_up_value_offset as usize) < min_fixup_offset
+        || fixup_end > header.attrib_offset as usize
+        || header.used_size as usize > total_size
+        || (header.attrib_offset as u32) >= header.used_size
+    {
+        return None;
+    }
+
+    let mut record = data[..total_size].to_vec();
+    let (_, fixup) = Fixup::get_fixup(
+        &record[header.fix_up_value_offset as usize..],
+        header.fix_up_count - 1,
+    )
+    .ok()?;
+    // Torn or partially overwritten records will fail the fixup
+    if !Fixup::apply_sector_fixup(&mut record, &fixup) {
+        return None;
+    }
+
+    Some(CarvedRecord {
+        header,
+        data: record,
+    })
+}
+
+/// Create `MftEntry` values for each `$FILE_NAME` attribute in the carved FILE record
+fn carved_entries<T: std::io::Seek + std::io::Read>(
+    record: &CarvedRecord,
+    reader: &mut BufReader<T>,
+    ntfs_file: Option<&NtfsFile<'_>>,
+    cache: &mut HashMap<String, String>,
+    evidence: &str,
+    drive: &str,
+) -> Vec<MftEntry> {
+    let mut entries = Vec::new();
+    let attribute_data = &record.data[record.header.attrib_offset as usize..];
+    // Non-resident data of a carved record may no longer be valid. So we do not provide the MFT file for attribute lookups
+    let attributes = match grab_attributes(
+        attribute_data,
+        reader,
+        None,
+        record.header.total_size,
+        record.header.index,
+    ) {
+        Ok((_, result)) => result,
+        Err(err) => {
+            error!("Could not parse carved mft attributes: {err:?}");
+            return entries;
+        }
+    };
+
+    for value in &attributes.filename {
+        let mut mft_entry = MftEntry {
+            deleted: !record.header.entry_flags.contains(&EntryFlags::InUse),
+            evidence: evidence.to_string(),
+            drive: drive.to_string(),
+            carved: true,
+            ..Default::default()
+        };
+
+        if let Some(standard) = attributes.standard.first() {
+            mft_entry.created = filetime_to_iso(standard.created);
+            mft_entry.modified = filetime_to_iso(standard.modified);
+            mft_entry.changed = filetime_to_iso(standard.changed);
+            mft_entry.accessed = filetime_to_iso(standard.accessed);
+            mft_entry.attributes = standard.file_attributes.clone();
+            mft_entry.usn = standard.usn;
+        }
+        if mft_entry.attributes.is_empty() {
+            mft_entry.attributes = value.file_attributes.clone();
+        }
+
+        mft_entry.filename = value.name.clone();
+        mft_entry.parent_inode = value.parent_mft;
+        mft_entry.inode = record.header.index;
+        mft_entry.namespace = value.namespace.clone();
+        mft_entry.filename_created = filetime_to_iso(value.created);
+        mft_entry.filename_modified = filetime_to_iso(value.modified);
+        mft_entry.filename_accessed = filetime_to_iso(value.accessed);
+        mft_entry.filename_changed = filetime_to_iso(value.changed);
+        mft_entry.attribute_list = attributes.attributes.clone();
+
+        if value.file_attributes.contains(&AttributeFlags::Directory) {
+            mft_entry.is_directory = true;
+        } else {
+            mft_entry.is_file = true;
+            mft_entry.size = attributes.size;
+            mft_entry.extension = file_extension(&value.name);
+        }
+
+        mft_entry.directory = if ntfs_file.is_some() {
+            carved_directory(
+                reader,
+                ntfs_file,
+                cache,
+                value.parent_mft,
+                value.parent_sequence,
+            )
+        } else if value.parent_mft == 5 {
+            String::from(".")
+        } else {
+            String::from("$OrphanFiles")
+        };
+        mft_entry.full_path = format!("{}\\{}", mft_entry.directory, value.name);
+
+        entries.push(mft_entry);
+    }
+
+    entries
+}
+
+/// Resolve the parent directory of a carved FILE record. Carved records often have a parent that was deleted or reused
+fn carved_directory<T: std::io::Seek + std::io::Read>(
+    reader: &mut BufReader<T>,
+    ntfs_file: Option<&NtfsFile<'_>>,
+    cache: &mut HashMap<String, String>,
+    parent_mft: u32,
+    parent_sequence: u16,
+) -> String {
+    let root = 5;
+    if parent_mft == root {
+        return String::from(".");
+    }
+    if let Some(cache_hit) = cache.get(&format!("{parent_mft}_{parent_sequence}")) {
+        return cache_hit.clone();
+    }
+
+    let mut tracker = Lookups {
+        parent_index: parent_mft,
+        parent_sequence,
+        size: 0,
+        tracker: HashSet::new(),
+    };
+    match lookup_parent(reader, ntfs_file, cache, &HashMap::new(), &mut tracker) {
+        Ok(path) if !path.is_empty() => path,
+        _ => String::from("$OrphanFiles"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{carve_file_records, carved_directory, carved_entries};
+    use std::{collections::HashMap, fs::read, io::BufReader, io::Cursor, path::PathBuf};
+
+    #[test]
+    fn test_carve_file_records() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/carve/unallocated.raw");
+        let data = read(test_location).unwrap();
+
+        // Torn FILE record at offset 0x3000 is skipped
+        let results = carve_file_records(&data, data.len());
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].header.index, 77);
+        assert_eq!(results[0].header.sequence, 4);
+        assert_eq!(results[0].header.transaction_seq, 0x4242);
+        assert!(results[0].data.ends_with(&[0, 0]));
+
+        let mut reader = BufReader::new(Cursor::new(Vec::new()));
+        let entries = carved_entries(
+            &results[0],
+            &mut reader,
+            None,
+            &mut HashMap::new(),
+            "unallocated.raw",
+            "",
+        );
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].filename, "carved.txt");
+        assert_eq!(entries[0].full_path, ".\\carved.txt");
+        assert_eq!(entries[0].extension, "txt");
+        assert_eq!(entries[0].inode, 77);
+        assert_eq!(entries[0].created, "2024-03-01T03:30:00.000Z");
+        assert!(entries[0].deleted);
+        assert!(entries[0].carved);
+        assert!(entries[0].is_file);
+    }
+
+    #[test]
+    fn test_carved_directory_dead_parent() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/carve/unallocated.raw");
+        let data = read(test_location).unwrap();
+        let results = carve_file_records(&data, data.len());
+
+        // Use the carved FILE record as a one entry MFT
+        let mut reader = BufReader::new(Cursor::new(results[0].data.clone()));
+        let mut cache = HashMap::new();
+
+        // Parent entry was reused
+        let reused = carved_directory(&mut reader, None, &mut cache, 0, 9);
+        assert_eq!(reused, "$OrphanFiles");
+
+        // Parent entry no longer exists in the MFT
+        let missing = carved_directory(&mut reader, None, &mut cache, 1000, 1);
+        assert_eq!(missing, "$OrphanFiles");
+
+        let root = carved_directory(&mut reader, None, &mut cache, 5, 5);
+        assert_eq!(root, ".");
+    }
+}
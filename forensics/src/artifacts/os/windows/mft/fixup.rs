@@ -63,6 +63,27 @@ impl Fixup {
             count += 1;
         }
     }
+
+    /// Apply the provided fixup values to a full record or page. Each 512 byte sector ends with the placeholder value
+    ///
+    /// Returns false if a sector does not end with the placeholder (torn write)
+    pub(crate) fn apply_sector_fixup(data: &mut [u8], fixup: &Fixup) -> bool {
+        let sector_size = 512;
+        let fixup_size = 2;
+
+        for (sector, fix) in fixup.original.iter().enumerate() {
+            let end = (sector + 1) * sector_size;
+            if end > data.len() {
+                break;
+            }
+            if data[end - fixup_size..end] != fixup.placeholder {
+                return false;
+            }
+            data[end - fixup_size..end].copy_from_slice(fix);
+        }
+
+        true
+    }
 }
 
 #[cfg(test)]
@@ -126,4 +147,20 @@ mod tests {
         Fixup::apply_fixup(&mut test, &fix);
         assert!(test.ends_with(&[0, 0]));
     }
+
+    #[test]
+    fn test_apply_sector_fixup() {
+        let mut test = vec![0; 1024];
+        test[510] = 2;
+        test[1022] = 2;
+        let fix = Fixup {
+            placeholder: vec![2, 0],
+            original: vec![vec![10, 11], vec![12, 13]],
+        };
+        assert!(Fixup::apply_sector_fixup(&mut test, &fix));
+        assert_eq!(test[510..512], [10, 11]);
+        assert_eq!(test[1022..1024], [12, 13]);
+
+        assert!(!Fixup::apply_sector_fixup(&mut test, &fix));
+    }
 }
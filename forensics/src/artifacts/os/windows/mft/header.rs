@@ -5,14 +5,14 @@ use crate::utils::nom_helper::{
 #[derive(Debug)]
 pub(crate) struct MftHeader {
     pub(crate) sig: u32,
-    pub(crate) _fix_up_value_offset: u16,
+    pub(crate) fix_up_value_offset: u16,
     pub(crate) fix_up_count: u16,
-    pub(crate) _transaction_seq: u64,
+    pub(crate) transaction_seq: u64,
     pub(crate) sequence: u16,
     pub(crate) _ref_count: u16,
-    _attrib_offset: u16,
+    pub(crate) attrib_offset: u16,
     pub(crate) entry_flags: Vec<EntryFlags>,
-    pub(crate) _used_size: u32,
+    pub(crate) used_size: u32,
     pub(crate) total_size: u32,
     pub(crate) mft_base_index: u32,
     pub(crate) mft_base_seq: u16,
@@ -53,14 +53,14 @@ impl MftHeader {
 
         let header = MftHeader {
             sig,
-            _fix_up_value_offset: fix_up_value_offset,
+            fix_up_value_offset,
             fix_up_count,
-            _transaction_seq: transaction_seq,
+            transaction_seq,
             sequence,
             _ref_count: ref_count,
-            _attrib_offset: attrib_offset,
+            attrib_offset,
             entry_flags: MftHeader::get_flags(entry_data),
-            _used_size: used_size,
+            used_size,
             total_size,
             mft_base_index,
             mft_base_seq,
@@ -105,9 +105,9 @@ mod tests {
         ];
 
         let (_, result) = MftHeader::parse_header(&test).unwrap();
-        assert_eq!(result._used_size, 592);
+        assert_eq!(result.used_size, 592);
         assert_eq!(result.total_size, 1024);
-        assert_eq!(result._transaction_seq, 29531818166);
+        assert_eq!(result.transaction_seq, 29531818166);
         assert_eq!(result.sequence, 1);
         assert_eq!(result._ref_count, 1);
         assert_eq!(result.mft_base_index, 0);
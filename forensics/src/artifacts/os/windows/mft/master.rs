@@ -5,15 +5,12 @@ use super::{
     reader::{setup_mft_reader, setup_mft_reader_windows},
 };
 use crate::{
-    artifacts::os::systeminfo::info::get_platform, filesystem::ntfs::setup::setup_ntfs_parser,
-    utils::time::filetime_to_iso,
+    accessor::location::loc::Location, artifacts::os::systeminfo::info::get_platform,
+    filesystem::ntfs::setup::setup_ntfs_parser, utils::time::filetime_to_iso,
 };
 use crate::{
     artifacts::os::windows::mft::{fixup::Fixup, header::EntryFlags},
-    filesystem::{
-        files::get_file_size,
-        ntfs::{attributes::get_raw_file_size, reader::read_bytes},
-    },
+    filesystem::ntfs::{attributes::get_raw_file_size, reader::read_bytes},
     output::{manager::OutputManager, record::serialize_records_to_stream},
     structs::artifacts::os::windows::MftOptions,
     utils::nom_helper::nom_data,
@@ -22,7 +19,7 @@ use common::windows::{AttributeFlags, MftEntry, Namespace};
 use ntfs::NtfsFile;
 use std::{
     collections::{HashMap, HashSet},
-    io::BufReader,
+    io::{BufReader, Seek, SeekFrom},
 };
 use tracing::{error, warn};
 
@@ -35,9 +32,19 @@ pub(crate) fn parse_mft(
 ) -> Result<(), MftError> {
     let plat = get_platform();
     let mut args = MftArgs { size: 0 };
-    if plat != "Windows" {
-        args.size = get_file_size(path);
-        let reader = setup_mft_reader(path)?;
+    if plat != "Windows" || Location::is_container(path) {
+        let mut reader = setup_mft_reader(path)?;
+        args.size = match reader.seek(SeekFrom::End(0)) {
+            Ok(result) => result,
+            Err(err) => {
+                error!("Failed to determine size of $MFT file {path}: {err:?}");
+                return Err(MftError::ReadFile);
+            }
+        };
+        if let Err(err) = reader.seek_from_start(0) {
+            error!("Failed to seek to start of $MFT file {path}: {err:?}");
+            return Err(MftError::ReadFile);
+        }
         let mut buf_reader = BufReader::new(reader);
 
         return read_mft(&mut buf_reader, None, manager, options, path, drive, &args);
@@ -112,6 +119,11 @@ fn read_mft<T: std::io::Seek + std::io::Read>(
             if offset > args.size {
                 break;
             }
+            // Stop reading the MFT if the collection has been cancelled
+            if manager.cancel.is_cancelled() {
+                warn!("Collection cancelled. Stopping MFT parsing at offset {offset}");
+                break;
+            }
 
             // MFT entry size is 0 bytes. Add 1024 to our offset and move on
             if header.total_size == 0 {
@@ -580,7 +592,7 @@ fn apply_fixup(data: &[u8], count: u16) -> Result<Vec<u8>, MftError> {
 }
 
 /// Output MFT data. Due to size of $MFT we will output every 10k entries we parse
-fn output_mft(
+pub(crate) fn output_mft(
     entries: Vec<MftEntry>,
     manager: &mut OutputManager,
     options: &MftOptions,
@@ -638,6 +650,7 @@ mod tests {
         let options = MftOptions {
             alt_drive: None,
             alt_file: None,
+            carve: None,
         };
 
         parse_mft(&test_location.to_str().unwrap(), &mut output, &options, "").unwrap();
@@ -663,6 +676,7 @@ mod tests {
         let options = MftOptions {
             alt_drive: None,
             alt_file: None,
+            carve: None,
         };
         read_mft(
             &mut ntfs_parser.fs,
@@ -689,7 +703,30 @@ mod tests {
         let options = MftOptions {
             alt_drive: None,
             alt_file: None,
+            carve: None,
         };
         parse_mft(&test_location.to_str().unwrap(), &mut output, &options, "").unwrap();
     }
+
+    #[test]
+    fn test_parse_mft_zip() {
+        use super::parse_mft;
+
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/mft/win11/nonresident.zip");
+
+        let mut output = output_options("mft_test", "./tmp", false);
+        let options = MftOptions {
+            alt_drive: None,
+            alt_file: None,
+            carve: None,
+        };
+        parse_mft(
+            &format!("zip:{}!nonresident.raw", test_location.display()),
+            &mut output,
+            &options,
+            "",
+        )
+        .unwrap();
+    }
 }
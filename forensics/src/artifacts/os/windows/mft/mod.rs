@@ -1,7 +1,8 @@
 pub(crate) mod attributes;
+mod carve;
 mod error;
-mod fixup;
-mod header;
+pub(crate) mod fixup;
+pub(crate) mod header;
 pub(crate) mod master;
 pub(crate) mod parser;
 pub(crate) mod reader;
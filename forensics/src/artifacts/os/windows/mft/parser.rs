@@ -6,30 +6,40 @@
  * Other parsers:
  *   `https://github.com/Velocidex/velociraptor`
  */
-use super::{error::MftError, master::parse_mft};
+use super::{carve::carve_mft, error::MftError, master::parse_mft};
 use crate::{
     output::manager::OutputManager, structs::artifacts::os::windows::MftOptions,
     utils::environment::get_systemdrive,
 };
+use tracing::{error, warn};
 
 /// Try create a filelisting from provided MFT file
 pub(crate) fn grab_mft(options: &MftOptions, manager: &mut OutputManager) -> Result<(), MftError> {
-    let mut drive = String::new();
-    let path = if let Some(file) = &options.alt_file {
-        return parse_mft(file, manager, options, &drive);
-    } else {
-        // Check if alternative drive letter provided
-        if let Some(alt_drive) = &options.alt_drive {
-            drive = alt_drive.to_string();
-            format!("{alt_drive}:\\$MFT")
-        } else {
-            // Otherwise try to get the SystemDrive
-            drive = get_systemdrive().unwrap_or('C').to_string();
-            format!("{drive}:\\$MFT")
+    if let Some(file) = &options.alt_file {
+        if options.carve.unwrap_or_default() {
+            warn!("Carving MFT FILE records is only supported when parsing a drive");
         }
-    };
+        return parse_mft(file, manager, options, "");
+    }
+
+    // Check if alternative drive letter provided. Otherwise try to get the SystemDrive
+    let drive = options
+        .alt_drive
+        .unwrap_or_else(|| get_systemdrive().unwrap_or('C'));
+    let result = parse_mft(
+        &format!("{drive}:\\$MFT"),
+        manager,
+        options,
+        &drive.to_string(),
+    );
+    if !options.carve.unwrap_or_default() {
+        return result;
+    }
 
-    parse_mft(&path, manager, options, &drive)
+    if let Err(err) = result {
+        error!("Could not parse MFT on {drive}. Will still carve unallocated space: {err:?}");
+    }
+    carve_mft(drive, manager, options)
 }
 
 #[cfg(test)]
@@ -58,6 +68,7 @@ mod tests {
         let options = MftOptions {
             alt_drive: None,
             alt_file: None,
+            carve: None,
         };
         let mut output = output_options("mft_temp", "./tmp", false);
         grab_mft(&options, &mut output).unwrap();
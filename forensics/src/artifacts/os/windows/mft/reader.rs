@@ -1,7 +1,10 @@
 use super::error::MftError;
-use crate::filesystem::{
-    files::file_reader,
-    ntfs::{raw_files::raw_reader, sector_reader::SectorReader},
+use crate::{
+    accessor::io::reader::AccessorReader,
+    filesystem::{
+        files::reader_any,
+        ntfs::{raw_files::raw_reader, sector_reader::SectorReader},
+    },
 };
 use ntfs::{Ntfs, NtfsFile};
 use std::{fs::File, io::BufReader};
@@ -25,16 +28,13 @@ pub(crate) fn setup_mft_reader_windows<'a>(
     Ok(ntfs_file)
 }
 
-/// Setup MFT using normal reader
-pub(crate) fn setup_mft_reader(path: &str) -> Result<File, MftError> {
-    let reader_result = file_reader(path);
-    let reader = match reader_result {
-        Ok(reader) => reader,
+/// Setup MFT using normal reader. Paths in archives or disk images are read with the `Accessor`. Ex: `ewf:case.E01!p2/$MFT`
+pub(crate) fn setup_mft_reader(path: &str) -> Result<AccessorReader, MftError> {
+    match reader_any(path) {
+        Ok(reader) => Ok(reader),
         Err(err) => {
-            error!("Could not setup API reader: {err:?}");
-            return Err(MftError::ReadFile);
+            error!("Could not setup reader for {path}: {err:?}");
+            Err(MftError::ReadFile)
         }
-    };
-
-    Ok(reader)
+    }
 }
@@ -1,15 +1,19 @@
 pub(crate) mod accounts;
+pub(crate) mod activitiescache;
 pub(crate) mod amcache;
 pub(crate) mod artifacts;
 pub(crate) mod bits;
 pub(crate) mod error;
 pub(crate) mod ese;
 pub(crate) mod eventlogs;
+pub(crate) mod hiberfil;
 pub(crate) mod jumplists;
+pub(crate) mod logfile;
 pub(crate) mod mft;
 mod ntfs;
 mod ole;
 pub(crate) mod outlook;
+pub(crate) mod pagefile;
 pub(crate) mod pe;
 pub(crate) mod prefetch;
 mod propertystore;
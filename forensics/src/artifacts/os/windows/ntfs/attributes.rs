@@ -9,6 +9,7 @@ use crate::{
         },
     },
     utils::{
+        cancel::CancelToken,
         nom_helper::{Endian, nom_unsigned_four_bytes},
         time::filetime_to_iso,
     },
@@ -20,12 +21,12 @@ use ntfs::{
     structured_values::{NtfsAttributeList, NtfsStandardInformation},
 };
 use serde::Serialize;
-use std::{fs::File, io::BufReader};
+use std::io::{BufReader, Read, Seek};
 use tracing::error;
 
 /// Get filename and Filename timestamps
-pub(crate) fn filename_info(
-    fs: &mut BufReader<SectorReader<File>>,
+pub(crate) fn filename_info<T: Read + Seek>(
+    fs: &mut BufReader<SectorReader<T>>,
     ntfs_file: &NtfsFile<'_>,
     file_info: &mut RawFilelist,
 ) -> Result<(), NtfsError> {
@@ -96,13 +97,14 @@ pub(crate) fn standard_info(standard: &NtfsStandardInformation, file_info: &mut
 }
 
 /// Get $DATA attribute data size and hash the data (if enabled)
-pub(crate) fn file_data(
+pub(crate) fn file_data<T: Read + Seek>(
     ntfs_file: &NtfsFile<'_>,
     //ntfs_ref: NtfsFileReference,
     file_info: &mut RawFilelist,
-    fs: &mut BufReader<SectorReader<File>>,
+    fs: &mut BufReader<SectorReader<T>>,
     ntfs: &Ntfs,
     hashes: &Hashes,
+    cancel: &CancelToken,
 ) -> Result<(), NTFSError> {
     let check_results = check_wofcompressed(ntfs_file, ntfs, fs);
     let (is_compressed, uncompressed_data, compressed_size) = match check_results {
@@ -168,7 +170,7 @@ pub(crate) fn file_data(
     };
 
     (file_info.md5, file_info.sha1, file_info.sha256) =
-        raw_hash_data(&mut data_attr_value, fs, hashes);
+        raw_hash_data(&mut data_attr_value, fs, hashes, cancel);
 
     Ok(())
 }
@@ -247,11 +249,11 @@ pub(crate) enum ReparseType {
 }
 
 /// Get the Reparse Point type
-pub(crate) fn get_reparse_type(
+pub(crate) fn get_reparse_type<T: Read + Seek>(
     //ntfs_ref: NtfsFileReference,
     ntfs_file: &NtfsFile<'_>,
     ntfs: &Ntfs,
-    fs: &mut BufReader<SectorReader<File>>,
+    fs: &mut BufReader<SectorReader<T>>,
 ) -> Result<ReparseType, NtfsError> {
     // let ntfs_file = ntfs_ref.to_file(ntfs, fs)?;
     let attr_raw = ntfs_file.attributes_raw();
@@ -383,11 +385,11 @@ pub(crate) fn get_attribute_type(attribute: &NtfsAttribute<'_, '_>) -> String {
 }
 
 /// Get all alternative data streams (ADS) for a file
-pub(crate) fn get_ads_names(
+pub(crate) fn get_ads_names<T: Read + Seek>(
     // ntfs_ref: NtfsFileReference,
     ntfs_file: &NtfsFile<'_>,
     ntfs: &Ntfs,
-    fs: &mut BufReader<SectorReader<File>>,
+    fs: &mut BufReader<SectorReader<T>>,
 ) -> Result<Vec<ADSInfo>, NtfsError> {
     //let ntfs_file = ntfs_ref.to_file(ntfs, fs)?;
     let attr_raw = ntfs_file.attributes_raw();
@@ -462,6 +464,7 @@ mod tests {
         filesystem::ntfs::{sector_reader::SectorReader, setup::setup_ntfs_parser},
         output::manager::OutputManager,
         structs::artifacts::os::windows::RawFilesOptions,
+        utils::cancel::CancelToken,
     };
     use common::files::Hashes;
     use ntfs::Ntfs;
@@ -627,6 +630,7 @@ mod tests {
                     &mut ntfs_parser.fs,
                     &ntfs_parser.ntfs,
                     &hash_data,
+                    &CancelToken::new(),
                 )
                 .unwrap();
                 assert_eq!(result, ());
@@ -13,12 +13,17 @@ use nom::{
     number::complete::le_u64,
 };
 use ntfs::{NtfsAttributes, NtfsFile, NtfsReadSeek, structured_values::NtfsFileAttributeFlags};
-use std::{ffi::OsStr, fs::File, io::BufReader, mem::size_of, path::Path};
+use std::{
+    ffi::OsStr,
+    io::{BufReader, Read, Seek},
+    mem::size_of,
+    path::Path,
+};
 use tracing::{error, info};
 
 /// Find the INDX attribute for the directory entry. We search the slack space on INDX attribute for metadata on deleted files or directories
-pub(crate) fn get_indx(
-    fs: &mut BufReader<SectorReader<File>>,
+pub(crate) fn get_indx<T: Read + Seek>(
+    fs: &mut BufReader<SectorReader<T>>,
     ntfs_file: &NtfsFile<'_>,
     directory: &str,
     depth: usize,
@@ -66,8 +71,8 @@ pub(crate) fn get_indx(
 }
 
 /// Get the raw slack space data
-fn get_slack(
-    fs: &mut BufReader<SectorReader<File>>,
+fn get_slack<T: Read + Seek>(
+    fs: &mut BufReader<SectorReader<T>>,
     attributes: &mut NtfsAttributes<'_, '_>,
     directory: &str,
     depth: usize,
@@ -21,10 +21,19 @@ use super::{
     security_ids::SecurityIDs,
 };
 use crate::{
+    accessor::{
+        entry::locator::{DiskImage, SourceId},
+        filesystem::image::data::{ImageFs, PartitionSelector, split_partition_path},
+        location::loc::Location,
+        source::factory::source_id_from_location,
+    },
     artifacts::os::windows::pe::parser::parse_pe_file,
     filesystem::{
         files::file_extension,
-        ntfs::{sector_reader::SectorReader, setup::setup_ntfs_parser},
+        ntfs::{
+            sector_reader::SectorReader,
+            setup::{NtfsParser, setup_ntfs_image_parser, setup_ntfs_parser},
+        },
     },
     output::{manager::OutputManager, record::serialize_records_to_stream},
     structs::{artifacts::os::windows::RawFilesOptions, toml::OutputFormat},
@@ -37,8 +46,12 @@ use common::files::Hashes;
 use common::windows::RawFilelist;
 use ntfs::{Ntfs, NtfsError, NtfsFile, structured_values::NtfsFileNamespace};
 use regex::Regex;
-use std::{collections::HashMap, fs::File, io::BufReader, mem::take};
-use tracing::error;
+use std::{
+    collections::HashMap,
+    io::{BufReader, Read, Seek},
+    mem::take,
+};
+use tracing::{error, warn};
 
 /// Parameters used for determining what NTFS data to return
 struct Params {
@@ -60,6 +73,23 @@ pub(crate) fn ntfs_filelist(
     options: &RawFilesOptions,
     manager: &mut OutputManager,
 ) -> Result<(), NTFSError> {
+    // Start paths inside a disk image are walked instead of a live drive. Ex: ewf:case.E01!p2\Users
+    if let Some((image, partition, path)) = image_start_path(&options.start_path) {
+        let mut ntfs_parser = match setup_ntfs_image_parser(&image, partition) {
+            Ok(result) => result,
+            Err(err) => {
+                error!(
+                    "Failed to setup NTFS parser for {}, error: {err:?}",
+                    options.start_path
+                );
+                return Err(NTFSError::Parser);
+            }
+        };
+        let drive = format!("{}!p{partition}", image.display());
+        let start_path = format!("{drive}{path}");
+        return filelist(&mut ntfs_parser, &drive, &start_path, options, manager);
+    }
+
     if options.start_path.is_empty() || !options.start_path.starts_with(options.drive_letter) {
         return Err(NTFSError::BadStart);
     }
@@ -73,6 +103,23 @@ pub(crate) fn ntfs_filelist(
         }
     };
 
+    filelist(
+        &mut ntfs_parser,
+        &format!("{}:", options.drive_letter),
+        &options.start_path,
+        options,
+        manager,
+    )
+}
+
+/// Walk the NTFS filesystem and output files under the start path
+fn filelist<T: Read + Seek>(
+    ntfs_parser: &mut NtfsParser<T>,
+    drive: &str,
+    start_path: &str,
+    options: &RawFilesOptions,
+    manager: &mut OutputManager,
+) -> Result<(), NTFSError> {
     let root_dir_result = ntfs_parser.ntfs.root_directory(&mut ntfs_parser.fs);
     let root_dir = match root_dir_result {
         Ok(result) => result,
@@ -85,26 +132,23 @@ pub(crate) fn ntfs_filelist(
     let path_regex = user_regex(options.path_regex.as_ref().unwrap_or(&String::new()))?;
     let file_regex = user_regex(options.filename_regex.as_ref().unwrap_or(&String::new()))?;
 
-    let mut start_path = options.start_path.clone();
-    start_path = start_path
-        .strip_prefix("C:")
-        .unwrap_or(&start_path)
+    let mut relative_path = start_path
+        .strip_prefix(drive)
+        .unwrap_or(start_path)
         .to_string();
 
-    if !start_path.ends_with('\\') {
-        start_path = format!("{start_path}\\");
+    if !relative_path.ends_with('\\') {
+        relative_path = format!("{relative_path}\\");
     }
 
     let mut start_path_depth = 0;
     // Adjust total depth based on starting path depth
-    for path in start_path.split('\\') {
+    for path in relative_path.split('\\') {
         if path.is_empty() {
             continue;
         }
         start_path_depth += 1;
     }
-    // restore original start path
-    start_path.clone_from(&options.start_path);
 
     // Before parsing the NTFS data, grab Windows SIDs so we can map files to User and Group SIDs
     let sids = SecurityIDs::get_security_ids(&root_dir, &mut ntfs_parser.fs, &ntfs_parser.ntfs)?;
@@ -116,13 +160,13 @@ pub(crate) fn ntfs_filelist(
     };
     let mut params = Params {
         start_path_depth,
-        start_path,
+        start_path: start_path.to_string(),
         depth: options.depth,
         path_regex,
         file_regex,
         recover_indx: options.recover_indx,
         filelist: Vec::new(),
-        directory_tracker: vec![format!("{}:", options.drive_letter)],
+        directory_tracker: vec![drive.to_string()],
         sids,
         hash: hash_data,
         metadata: options.metadata.unwrap_or(false),
@@ -142,6 +186,35 @@ pub(crate) fn ntfs_filelist(
     Ok(())
 }
 
+/// Parse a start path inside a disk image. Ex: `ewf:case.E01!p2\Users` returns the image, partition 2, and `\Users`
+fn image_start_path(start_path: &str) -> Option<(DiskImage, u32, String)> {
+    let location = Location::parse(start_path).ok()?;
+    let Ok(SourceId::Image(image)) = source_id_from_location(&location) else {
+        return None;
+    };
+    let (selector, path) = split_partition_path(&location.inner_path).ok()?;
+    // Drive letter aliases need the partition filesystems to find the system partition. Ex: ewf:case.E01!C\Users
+    let partition = match selector {
+        PartitionSelector::Number(number) => number,
+        PartitionSelector::Drive(_) => {
+            match ImageFs::open(image.clone()).and_then(|fs| fs.partition_number(&selector)) {
+                Ok(result) => result,
+                Err(err) => {
+                    error!("Could not resolve the partition for {start_path}: {err}");
+                    return None;
+                }
+            }
+        }
+    };
+    let path = path.display().replace('/', "\\");
+
+    Some((
+        image,
+        partition,
+        format!("\\{}", path.trim_start_matches('\\')),
+    ))
+}
+
 /// Create Regex based on provided input
 fn user_regex(input: &str) -> Result<Regex, NTFSError> {
     let reg_result = create_regex(input);
@@ -155,9 +228,9 @@ fn user_regex(input: &str) -> Result<Regex, NTFSError> {
 }
 
 /// Iterate through NTFS files and directories
-fn walk_ntfs(
+fn walk_ntfs<T: Read + Seek>(
     root_dir: NtfsFile<'_>,
-    fs: &mut BufReader<SectorReader<File>>,
+    fs: &mut BufReader<SectorReader<T>>,
     ntfs: &Ntfs,
     params: &mut Params,
     manager: &mut OutputManager,
@@ -166,6 +239,11 @@ fn walk_ntfs(
     let index = root_dir.directory_index(fs)?;
     let mut iter = index.entries();
     while let Some(Ok(entry_index)) = iter.next(fs) {
+        // Stop walking the NTFS if the collection has been cancelled
+        if manager.cancel.is_cancelled() {
+            warn!("Collection cancelled. Stopping raw filelisting");
+            return Ok(());
+        }
         let mut file_info = RawFilelist {
             depth: params.directory_tracker.len(),
             drive: params.directory_tracker[0].clone(),
@@ -212,7 +290,14 @@ fn walk_ntfs(
             file_info.extension = file_extension(&file_info.filename);
 
             // Grab file data for hashing
-            let _attribute_result = file_data(&ntfs_file, &mut file_info, fs, ntfs, &params.hash);
+            let _attribute_result = file_data(
+                &ntfs_file,
+                &mut file_info,
+                fs,
+                ntfs,
+                &params.hash,
+                &manager.cancel,
+            );
 
             // Grab any alternative data streams (ADS)
             file_info.ads_info = get_ads_names(&ntfs_file, ntfs, fs)?;
@@ -301,7 +386,7 @@ mod tests {
     use crate::structs::toml::{OutputConfig, OutputDestination, OutputFormat};
     use crate::{
         artifacts::os::windows::ntfs::parser::{
-            Hashes, Params, ntfs_filelist, raw_output, user_regex, walk_ntfs,
+            Hashes, Params, image_start_path, ntfs_filelist, raw_output, user_regex, walk_ntfs,
         },
         filesystem::ntfs::setup::setup_ntfs_parser,
         output::manager::OutputManager,
@@ -575,4 +660,16 @@ mod tests {
         let regex = user_regex(&reg).unwrap();
         assert_eq!(regex.as_str(), ".*");
     }
+
+    #[test]
+    fn test_image_start_path() {
+        let (image, partition, path) = image_start_path("ewf:/cases/case.E01!p2\\Users").unwrap();
+        assert_eq!(image.display(), "ewf:/cases/case.E01");
+        assert_eq!(partition, 2);
+        assert_eq!(path, "\\Users");
+
+        let (_, _, path) = image_start_path("image:disk.dd!p1").unwrap();
+        assert_eq!(path, "\\");
+        assert!(image_start_path("C:\\Users").is_none());
+    }
 }
@@ -14,7 +14,10 @@ use crate::{
 };
 use nom::bytes::complete::{take, take_until};
 use ntfs::{Ntfs, NtfsAttributes, NtfsFile, structured_values::NtfsIndexRoot};
-use std::{collections::HashMap, fs::File, io::BufReader};
+use std::{
+    collections::HashMap,
+    io::{BufReader, Read, Seek},
+};
 use tracing::error;
 
 #[derive(Debug)]
@@ -27,9 +30,9 @@ pub(crate) struct SecurityIDs {
 
 impl SecurityIDs {
     /// Get Windows SID info from $SII and $SDS attributes
-    pub(crate) fn get_security_ids(
+    pub(crate) fn get_security_ids<T: Read + Seek>(
         root_dir: &NtfsFile<'_>,
-        fs: &mut BufReader<SectorReader<File>>,
+        fs: &mut BufReader<SectorReader<T>>,
         ntfs: &Ntfs,
     ) -> Result<HashMap<u32, SecurityIDs>, NTFSError> {
         // $Secure file exists in root directory
@@ -121,9 +124,9 @@ impl SecurityIDs {
     }
 
     /// Get the $SII attribute data
-    fn get_sii(
+    fn get_sii<T: Read + Seek>(
         indx_root: &NtfsIndexRoot<'_>,
-        fs: &mut BufReader<SectorReader<File>>,
+        fs: &mut BufReader<SectorReader<T>>,
         attributes: &mut NtfsAttributes<'_, '_>,
     ) -> Result<Vec<SecurityIDs>, NTFSError> {
         let mut sids: Vec<SecurityIDs> = Vec::new();
@@ -242,8 +245,8 @@ impl SecurityIDs {
     }
 
     /// Get the $SDS attribute data
-    fn get_sds(
-        fs: &mut BufReader<SectorReader<File>>,
+    fn get_sds<T: Read + Seek>(
+        fs: &mut BufReader<SectorReader<T>>,
         attributes: &mut NtfsAttributes<'_, '_>,
         security_ids: &[SecurityIDs],
     ) -> HashMap<u32, SecurityIDs> {
@@ -0,0 +1,28 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum PagefileError {
+    SystemDrive,
+    Parser,
+    ReadFile,
+    BadExtractor,
+    YaraRule,
+    Serialize,
+    OutputData,
+}
+
+impl std::error::Error for PagefileError {}
+
+impl fmt::Display for PagefileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PagefileError::SystemDrive => write!(f, "Failed to systemdrive env variable value"),
+            PagefileError::Parser => write!(f, "Failed to parse pagefile"),
+            PagefileError::ReadFile => write!(f, "Failed to read pagefile"),
+            PagefileError::BadExtractor => write!(f, "Unknown pagefile extractor"),
+            PagefileError::YaraRule => write!(f, "Failed to compile Yara rule"),
+            PagefileError::Serialize => write!(f, "Failed to serialize pagefile data"),
+            PagefileError::OutputData => write!(f, "Failed to output pagefile data"),
+        }
+    }
+}
@@ -0,0 +1,416 @@
+use super::error::PagefileError;
+use crate::{
+    artifacts::os::windows::shortcuts::parser::parse_lnk_data,
+    utils::{
+        nom_helper::{
+            Endian, nom_unsigned_eight_bytes, nom_unsigned_four_bytes, nom_unsigned_two_bytes,
+        },
+        regex_options::create_regex,
+        time::{filetime_to_iso, unixepoch_to_iso},
+    },
+};
+use common::windows::PagefileHit;
+use regex::Regex;
+use tracing::error;
+
+/// Extractors to run against pagefile data
+pub(crate) struct Extractors {
+    url: Option<Regex>,
+    ip: Option<Regex>,
+    path: Option<Regex>,
+    command: Option<Regex>,
+    evtx: bool,
+    lnk: bool,
+    pe: bool,
+}
+
+/// Minimum length of ASCII and UTF16 strings
+const MIN_STRING: usize = 6;
+/// Max size of the context string
+const MAX_CONTEXT: usize = 256;
+
+impl Extractors {
+    /// Setup the extractors. If no names are provided all extractors are enabled
+    pub(crate) fn new(names: &Option<Vec<String>>) -> Result<Extractors, PagefileError> {
+        let all = ["url", "ip", "path", "command", "evtx", "lnk", "pe"];
+        let enabled: Vec<String> = match names {
+            Some(values) if !values.is_empty() => {
+                values.iter().map(|name| name.to_lowercase()).collect()
+            }
+            _ => all.iter().map(ToString::to_string).collect(),
+        };
+        if let Some(unknown) = enabled.iter().find(|name| !all.contains(&name.as_str())) {
+            error!("[pagefile] Unknown extractor {unknown}. Supported extractors: {all:?}");
+            return Err(PagefileError::BadExtractor);
+        }
+        let is_enabled = |name: &str| enabled.iter().any(|value| value == name);
+
+        // Regexes are always valid
+        let regex = |name: &str, value: &str| {
+            if is_enabled(name) {
+                Some(create_regex(value).unwrap())
+            } else {
+                None
+            }
+        };
+
+        Ok(Extractors {
+            url: regex("url", r#"(?i)\b(?:https?|ftp)://[^\s"'<>]{3,}"#),
+            ip: regex(
+                "ip",
+                r"\b(?:(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\.){3}(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\b",
+            ),
+            path: regex(
+                "path",
+                r#"(?i)\b[a-z]:\\(?:[^\\/:*?"<>|\r\n\t]+\\)*[^\\/:*?"<>|\r\n\t]*"#,
+            ),
+            command: regex(
+                "command",
+                r"(?i)\b(?:cmd(?:\.exe)?\s+/[ckr]|(?:powershell|pwsh)(?:\.exe)?\s+-|(?:rundll32|regsvr32|mshta|wmic|wscript|cscript)(?:\.exe)?\s+\S|certutil(?:\.exe)?\s+-|(?:schtasks|bitsadmin)(?:\.exe)?\s+/).*",
+            ),
+            evtx: is_enabled("evtx"),
+            lnk: is_enabled("lnk"),
+            pe: is_enabled("pe"),
+        })
+    }
+
+    /**
+     * Run the extractors against the provided data. `offset` is the file offset of the data
+     * Only hits that start at `start` up to `end` (relative to data) are returned. The remaining data is context
+     */
+    pub(crate) fn scan(
+        &self,
+        data: &[u8],
+        offset: u64,
+        start: usize,
+        end: usize,
+        evidence: &str,
+    ) -> Vec<PagefileHit> {
+        let mut hits = Vec::new();
+        let mut add_hit = |extractor: &str, value: String, relative: usize, context: String| {
+            if relative < start || relative >= end {
+                return;
+            }
+            hits.push(PagefileHit {
+                extractor: extractor.to_string(),
+                value,
+                offset: offset + relative as u64,
+                context,
+                evidence: evidence.to_string(),
+            });
+        };
+
+        if self.url.is_some() || self.ip.is_some() || self.path.is_some() || self.command.is_some()
+        {
+            for (string_offset, char_size, value) in extract_strings(data) {
+                let context: String = value.chars().take(MAX_CONTEXT).collect();
+                for (extractor, regex) in [
+                    ("url", &self.url),
+                    ("ip", &self.ip),
+                    ("path", &self.path),
+                    ("command", &self.command),
+                ] {
+                    let Some(regex) = regex else {
+                        continue;
+                    };
+                    for hit in regex.find_iter(&value) {
+                        if extractor == "ip" && !is_ip(&value, hit.start(), hit.end()) {
+                            continue;
+                        }
+                        add_hit(
+                            extractor,
+                            hit.as_str().to_string(),
+                            string_offset + hit.start() * char_size,
+                            context.clone(),
+                        );
+                    }
+                }
+            }
+        }
+
+        if !self.evtx && !self.lnk && !self.pe {
+            return hits;
+        }
+        // Structures are aligned to at least 8 bytes in memory
+        let alignment = 8;
+        let mut relative = start - start % alignment;
+        while relative < end.min(data.len()) {
+            let value = &data[relative..];
+            if self.evtx
+                && value.starts_with(&[42, 42, 0, 0])
+                && let Some((record, context)) = check_evtx(value)
+            {
+                add_hit("evtx", record, relative, context);
+            } else if self.lnk
+                && value.starts_with(&[76, 0, 0, 0, 1, 20, 2, 0])
+                && let Some((path, context)) = check_lnk(value)
+            {
+                add_hit("lnk", path, relative, context);
+            } else if self.pe
+                && value.starts_with(b"MZ")
+                && let Some((compiled, context)) = check_pe(value)
+            {
+                add_hit("pe", compiled, relative, context);
+            }
+            relative += alignment;
+        }
+
+        hits
+    }
+}
+
+/**
+ * Extract ASCII and UTF16 strings from the data
+ * Returns the offset of the string, the size of each character, and the string
+ */
+fn extract_strings(data: &[u8]) -> Vec<(usize, usize, String)> {
+    let mut strings = Vec::new();
+    let is_printable = |value: u8| value.is_ascii_graphic() || value == b' ' || value == b'\t';
+
+    let mut string_start = 0;
+    for (index, value) in data.iter().enumerate() {
+        if is_printable(*value) {
+            continue;
+        }
+        if index - string_start >= MIN_STRING {
+            strings.push((
+                string_start,
+                1,
+                String::from_utf8_lossy(&data[string_start..index]).to_string(),
+            ));
+        }
+        string_start = index + 1;
+    }
+    if data.len() - string_start >= MIN_STRING {
+        strings.push((
+            string_start,
+            1,
+            String::from_utf8_lossy(&data[string_start..]).to_string(),
+        ));
+    }
+
+    // UTF16 strings are always aligned to 2 bytes
+    let mut string_start = 0;
+    let mut value = String::new();
+    for (index, chars) in data.chunks_exact(2).enumerate() {
+        if is_printable(chars[0]) && chars[1] == 0 {
+            if value.is_empty() {
+                string_start = index * 2;
+            }
+            value.push(chars[0] as char);
+            continue;
+        }
+        if value.len() >= MIN_STRING {
+            strings.push((string_start, 2, value.clone()));
+        }
+        value.clear();
+    }
+    if value.len() >= MIN_STRING {
+        strings.push((string_start, 2, value));
+    }
+
+    strings
+}
+
+/// Make sure the IPv4 match is not part of a larger version string. Ex: 10.0.19041.1
+fn is_ip(value: &str, start: usize, end: usize) -> bool {
+    let bytes = value.as_bytes();
+    let before = start.checked_sub(1).map(|index| bytes[index]);
+    let after = bytes.get(end).copied();
+    before.is_none_or(|value| value != b'.')
+        && after.is_none_or(|value| value != b'.' && !value.is_ascii_digit())
+}
+
+/// Check for an `EventLog` record. Returns the record ID and record timestamp
+fn check_evtx(data: &[u8]) -> Option<(String, String)> {
+    let (input, _sig) = nom_unsigned_four_bytes(data, Endian::Le).ok()?;
+    let (input, size) = nom_unsigned_four_bytes(input, Endian::Le).ok()?;
+    let (input, record_id) = nom_unsigned_eight_bytes(input, Endian::Le).ok()?;
+    let (_, timestamp) = nom_unsigned_eight_bytes(input, Endian::Le).ok()?;
+
+    let min_size = 28;
+    let max_size = 0x10000;
+    if size < min_size || size > max_size || record_id == 0 || size as usize > data.len() {
+        return None;
+    }
+    // Size is repeated at the end of the record
+    let copy_offset = size as usize - 4;
+    let (_, size_copy) = nom_unsigned_four_bytes(&data[copy_offset..], Endian::Le).ok()?;
+    if size_copy != size {
+        return None;
+    }
+
+    Some((
+        record_id.to_string(),
+        format!(
+            "Record size: {size}. Written: {}",
+            filetime_to_iso(timestamp)
+        ),
+    ))
+}
+
+/// Check for a `Shortcut` header. Returns the target path and shortcut details
+fn check_lnk(data: &[u8]) -> Option<(String, String)> {
+    // Shortcuts are small. Ignore anything after 64KB
+    let max_size = 0x10000;
+    let lnk = parse_lnk_data(&data[..data.len().min(max_size)]).ok()?;
+
+    let path = if lnk.path.is_empty() {
+        lnk.relative_path
+    } else {
+        lnk.path
+    };
+    if path.is_empty() {
+        return None;
+    }
+    Some((
+        path,
+        format!(
+            "Created: {}. Modified: {}. Arguments: {}",
+            lnk.created, lnk.modified, lnk.command_line_args
+        ),
+    ))
+}
+
+/// Check for a `PE` header. Returns the compile timestamp and PE details
+fn check_pe(data: &[u8]) -> Option<(String, String)> {
+    let pe_offset = 0x3c;
+    let (_, nt_offset) = nom_unsigned_four_bytes(data.get(pe_offset..)?, Endian::Le).ok()?;
+    // NT header should be close to the DOS header
+    let max_offset = 0x400;
+    if nt_offset < 0x40 || nt_offset > max_offset {
+        return None;
+    }
+
+    let nt_data = data.get(nt_offset as usize..)?;
+    let (input, sig) = nom_unsigned_four_bytes(nt_data, Endian::Le).ok()?;
+    let pe_sig = 0x4550;
+    if sig != pe_sig {
+        return None;
+    }
+    let (input, machine) = nom_unsigned_two_bytes(input, Endian::Le).ok()?;
+    let (input, sections) = nom_unsigned_two_bytes(input, Endian::Le).ok()?;
+    let (input, compiled) = nom_unsigned_four_bytes(input, Endian::Le).ok()?;
+    let (input, _symbol_table) = nom_unsigned_eight_bytes(input, Endian::Le).ok()?;
+    let (input, _optional_size) = nom_unsigned_two_bytes(input, Endian::Le).ok()?;
+    let (_, characteristics) = nom_unsigned_two_bytes(input, Endian::Le).ok()?;
+
+    let dll = 0x2000;
+    let kind = if characteristics & dll == dll {
+        "DLL"
+    } else {
+        "EXE"
+    };
+    Some((
+        unixepoch_to_iso(compiled as i64),
+        format!("{kind}. Machine: {machine:#x}. Sections: {sections}"),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Extractors, check_evtx, check_pe, extract_strings, is_ip};
+    use crate::filesystem::files::read_file;
+    use std::path::PathBuf;
+
+    fn utf16(value: &str) -> Vec<u8> {
+        value.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    #[test]
+    fn test_extract_strings() {
+        let mut data = b"\0\0hello world\0ab\0\0".to_vec();
+        data.extend_from_slice(&utf16("C:\\Windows\\notepad.exe"));
+        data.extend_from_slice(&[0, 0]);
+
+        let strings = extract_strings(&data);
+        assert_eq!(strings.len(), 2);
+        assert_eq!(strings[0], (2, 1, String::from("hello world")));
+        assert_eq!(
+            strings[1],
+            (18, 2, String::from("C:\\Windows\\notepad.exe"))
+        );
+    }
+
+    #[test]
+    fn test_scan() {
+        let mut data = vec![0; 16];
+        data.extend_from_slice(b"GET https://example.com/payload.ps1 from 192.168.1.20\0\0\0");
+        data.extend_from_slice(&utf16(
+            "powershell.exe -enc SQBFAFgA C:\\Users\\bob\\AppData\\Local\\Temp\\run.ps1",
+        ));
+        data.extend_from_slice(&[0, 0, 0, 0]);
+        data.extend_from_slice(b"version 10.0.19041.1\0");
+
+        let extractors = Extractors::new(&None).unwrap();
+        let hits = extractors.scan(&data, 4096, 0, data.len(), "pagefile.sys");
+        let values: Vec<(&str, &str)> = hits
+            .iter()
+            .map(|hit| (hit.extractor.as_str(), hit.value.as_str()))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                ("url", "https://example.com/payload.ps1"),
+                ("ip", "192.168.1.20"),
+                ("path", "C:\\Users\\bob\\AppData\\Local\\Temp\\run.ps1"),
+                (
+                    "command",
+                    "powershell.exe -enc SQBFAFgA C:\\Users\\bob\\AppData\\Local\\Temp\\run.ps1"
+                ),
+            ]
+        );
+        assert_eq!(hits[0].offset, 4096 + 20);
+        assert_eq!(hits[2].offset, 4096 + 72 + 29 * 2);
+        assert_eq!(hits[3].offset, 4096 + 72);
+
+        // Hits outside of the range are ignored
+        let hits = extractors.scan(&data, 0, 30, data.len(), "pagefile.sys");
+        assert_eq!(hits.len(), 3);
+    }
+
+    #[test]
+    fn test_extractors_selection() {
+        let extractors = Extractors::new(&Some(vec![String::from("URL")])).unwrap();
+        assert!(extractors.url.is_some());
+        assert!(extractors.ip.is_none());
+        assert!(!extractors.pe);
+
+        assert!(Extractors::new(&Some(vec![String::from("email")])).is_err());
+    }
+
+    #[test]
+    fn test_is_ip() {
+        assert!(is_ip("ip 10.1.1.1 test", 3, 11));
+        assert!(!is_ip("10.0.19041.1", 0, 8));
+    }
+
+    #[test]
+    fn test_check_evtx() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/eventlogs/System.evtx");
+        let data = read_file(test_location.to_str().unwrap()).unwrap();
+
+        // First record in the first chunk
+        let (record, context) = check_evtx(&data[0x1200..]).unwrap();
+        assert_eq!(record, "1");
+        assert!(context.starts_with("Record size: "));
+        assert!(check_evtx(&data[0x1000..]).is_none());
+    }
+
+    #[test]
+    fn test_check_pe() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/pe/resources/winml.dll");
+        let data = read_file(test_location.to_str().unwrap()).unwrap();
+
+        let (_, context) = check_pe(&data).unwrap();
+        assert!(context.starts_with("DLL. Machine: 0xaa64"));
+        assert!(check_pe(&data[2..]).is_none());
+
+        let extractors = Extractors::new(&Some(vec![String::from("pe")])).unwrap();
+        let hits = extractors.scan(&data[..4096], 0, 0, 4096, "pagefile.sys");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].extractor, "pe");
+    }
+}
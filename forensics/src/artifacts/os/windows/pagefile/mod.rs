@@ -0,0 +1,3 @@
+mod error;
+mod extract;
+pub(crate) mod parser;
@@ -0,0 +1,360 @@
+/**
+ * Windows moves memory pages to `pagefile.sys` when physical memory is low. `swapfile.sys` is used to swap out suspended Windows Store (UWP) apps
+ * Located at `<drive>:\pagefile.sys` and `<drive>:\swapfile.sys`
+ * The files have no structure. They are just memory pages. So we carve strings and known structures from them
+ * Supported extractors: URLs, IPv4 addresses, file paths, command lines, `EventLog` records, `Shortcut` headers, and `PE` headers
+ * Optionally each page can also be scanned with a Yara rule
+ *
+ * References:
+ * `https://learn.microsoft.com/en-us/troubleshoot/windows-client/performance/introduction-to-the-page-file`
+ */
+use super::{error::PagefileError, extract::Extractors};
+use crate::{
+    filesystem::{
+        files::file_reader,
+        ntfs::{raw_files::raw_reader, reader::read_bytes, setup::setup_ntfs_parser},
+    },
+    output::{manager::OutputManager, record::serialize_records_to_stream},
+    structs::artifacts::os::windows::PagefileOptions,
+    utils::environment::get_systemdrive,
+};
+use common::windows::PagefileHit;
+use ntfs::NtfsFile;
+use std::io::{BufReader, Seek, SeekFrom};
+use tracing::{error, warn};
+
+#[cfg(feature = "yarax")]
+use crate::utils::yara::{compile_rules, scan_bytes_scanner};
+#[cfg(feature = "yarax")]
+use yara_x::Scanner;
+
+/// Read the `pagefile.sys` and `swapfile.sys` files and output the extracted data
+pub(crate) fn grab_pagefile(
+    options: &PagefileOptions,
+    manager: &mut OutputManager,
+) -> Result<(), PagefileError> {
+    let extractors = Extractors::new(&options.extractors)?;
+    let mut carver = PageCarver::new(extractors, options)?;
+
+    if let Some(path) = &options.alt_file {
+        let reader = match file_reader(path) {
+            Ok(result) => result,
+            Err(err) => {
+                error!("[pagefile] Could not open {path}: {err:?}");
+                return Err(PagefileError::ReadFile);
+            }
+        };
+        let mut fs = BufReader::new(reader);
+        return carver.carve_file(&mut fs, None, path, manager, options);
+    }
+
+    let systemdrive = match get_systemdrive() {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[pagefile] Could not get systemdrive: {err:?}");
+            return Err(PagefileError::SystemDrive);
+        }
+    };
+
+    // The files are always locked. Parse the NTFS filesystem to read them
+    let mut ntfs_parser = match setup_ntfs_parser(systemdrive) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[pagefile] Cannot setup NTFS parser: {err:?}");
+            return Err(PagefileError::Parser);
+        }
+    };
+    for name in ["pagefile.sys", "swapfile.sys"] {
+        let path = format!("{systemdrive}:\\{name}");
+        let ntfs_file = match raw_reader(&path, &ntfs_parser.ntfs, &mut ntfs_parser.fs) {
+            Ok(result) => result,
+            Err(err) => {
+                warn!("[pagefile] Could not read {path}: {err:?}");
+                continue;
+            }
+        };
+        if let Err(err) = carver.carve_file(
+            &mut ntfs_parser.fs,
+            Some(&ntfs_file),
+            &path,
+            manager,
+            options,
+        ) {
+            error!("[pagefile] Could not carve {path}: {err:?}");
+        }
+    }
+
+    Ok(())
+}
+
+struct PageCarver {
+    extractors: Extractors,
+    #[cfg(feature = "yarax")]
+    rules: Option<yara_x::Rules>,
+}
+
+/// Size of the pages to scan with Yara
+#[cfg(feature = "yarax")]
+const PAGE_SIZE: usize = 4096;
+
+impl PageCarver {
+    fn new(extractors: Extractors, options: &PagefileOptions) -> Result<PageCarver, PagefileError> {
+        #[cfg(feature = "yarax")]
+        let rules = match &options.yara_rule {
+            Some(rule) => match compile_rules(rule) {
+                Ok(result) => Some(result),
+                Err(err) => {
+                    error!("[pagefile] Could not compile Yara rule: {err:?}");
+                    return Err(PagefileError::YaraRule);
+                }
+            },
+            None => None,
+        };
+        #[cfg(not(feature = "yarax"))]
+        if options.yara_rule.is_some() {
+            warn!("[pagefile] Yara rule provided but artemis was not compiled with Yara support");
+        }
+
+        Ok(PageCarver {
+            extractors,
+            #[cfg(feature = "yarax")]
+            rules,
+        })
+    }
+
+    /// Read the file in chunks and run the extractors against each chunk. Stops early and outputs partial results if the collection is cancelled
+    fn carve_file<T: std::io::Seek + std::io::Read>(
+        &mut self,
+        fs: &mut BufReader<T>,
+        ntfs_file: Option<&NtfsFile<'_>>,
+        path: &str,
+        manager: &mut OutputManager,
+        options: &PagefileOptions,
+    ) -> Result<(), PagefileError> {
+        let size = file_size(ntfs_file, fs)?;
+
+        #[cfg(feature = "yarax")]
+        let mut scanner = self.rules.as_ref().map(Scanner::new);
+
+        // Read 4MB at a time. Each chunk overlaps the chunk before and after it so strings and structures are not split
+        let chunk_size = 4 * 1024 * 1024;
+        let overlap = 64 * 1024;
+        let limit = 10000;
+        let mut hits = Vec::new();
+        let mut offset = 0;
+        while offset < size {
+            if manager.cancel.is_cancelled() {
+                warn!("[pagefile] Collection cancelled. Stopping {path} scan at offset {offset}");
+                break;
+            }
+            let window_start = offset.saturating_sub(overlap);
+            let window_end = size.min(offset + chunk_size + overlap);
+            let data = match read_bytes(window_start, window_end - window_start, ntfs_file, fs) {
+                Ok(result) => result,
+                Err(err) => {
+                    error!("[pagefile] Could not read {path} at offset {offset}: {err:?}");
+                    return Err(PagefileError::ReadFile);
+                }
+            };
+
+            let start = (offset - window_start) as usize;
+            let end = start + chunk_size.min(size - offset) as usize;
+            let chunk = &data[start..end];
+            // Pagefiles are mostly empty. Skip empty chunks
+            if chunk.iter().any(|value| *value != 0) {
+                hits.append(&mut self.extractors.scan(&data, window_start, start, end, path));
+
+                #[cfg(feature = "yarax")]
+                if let Some(yara) = &mut scanner {
+                    hits.append(&mut yara_pages(chunk, offset, yara, path));
+                }
+            }
+
+            if hits.len() >= limit {
+                output_pagefile(std::mem::take(&mut hits), manager, options)?;
+            }
+            offset += chunk_size;
+        }
+
+        output_pagefile(hits, manager, options)
+    }
+}
+
+/// Scan each page with the Yara rule
+#[cfg(feature = "yarax")]
+fn yara_pages(
+    chunk: &[u8],
+    offset: u64,
+    scanner: &mut Scanner<'_>,
+    path: &str,
+) -> Vec<PagefileHit> {
+    let mut hits = Vec::new();
+    for (index, page) in chunk.chunks(PAGE_SIZE).enumerate() {
+        if page.iter().all(|value| *value == 0) {
+            continue;
+        }
+        let Ok(rules) = scan_bytes_scanner(page, scanner) else {
+            continue;
+        };
+        for rule in rules {
+            hits.push(PagefileHit {
+                extractor: String::from("yara"),
+                value: rule,
+                offset: offset + (index * PAGE_SIZE) as u64,
+                context: String::new(),
+                evidence: path.to_string(),
+            });
+        }
+    }
+    hits
+}
+
+/// Get the size of the pagefile
+fn file_size<T: std::io::Seek + std::io::Read>(
+    ntfs_file: Option<&NtfsFile<'_>>,
+    fs: &mut BufReader<T>,
+) -> Result<u64, PagefileError> {
+    let Some(file) = ntfs_file else {
+        return match fs.seek(SeekFrom::End(0)) {
+            Ok(result) => Ok(result),
+            Err(err) => {
+                error!("[pagefile] Could not determine file size: {err:?}");
+                Err(PagefileError::ReadFile)
+            }
+        };
+    };
+
+    let Some(data_result) = file.data(fs, "") else {
+        error!("[pagefile] No data attribute for pagefile");
+        return Err(PagefileError::ReadFile);
+    };
+    match data_result.and_then(|data| Ok(data.to_attribute()?.value_length())) {
+        Ok(result) => Ok(result),
+        Err(err) => {
+            error!("[pagefile] Could not determine file size: {err:?}");
+            Err(PagefileError::ReadFile)
+        }
+    }
+}
+
+/// Output pagefile hits based on `Output` structure
+fn output_pagefile(
+    entries: Vec<PagefileHit>,
+    manager: &mut OutputManager,
+    options: &PagefileOptions,
+) -> Result<(), PagefileError> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let mut records = match serialize_records_to_stream(entries) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("[pagefile] Failed to serialize hits: {err:?}");
+            return Err(PagefileError::Serialize);
+        }
+    };
+    let artifact_name = "pagefile";
+    if let Err(err) = manager.write_artifact(artifact_name, options, &mut records) {
+        error!("[pagefile] Could not output hits: {err:?}");
+        return Err(PagefileError::OutputData);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PageCarver, file_size, grab_pagefile};
+    use crate::{
+        artifacts::os::windows::pagefile::extract::Extractors,
+        filesystem::files::file_reader,
+        output::manager::OutputManager,
+        structs::{
+            artifacts::os::windows::PagefileOptions,
+            toml::{OutputConfig, OutputDestination, OutputFormat},
+        },
+    };
+    use std::{io::BufReader, path::PathBuf};
+
+    fn output_options(name: &str, directory: &str, compress: bool) -> OutputManager {
+        let config = OutputConfig {
+            name: name.to_string(),
+            directory: PathBuf::from(directory),
+            format: OutputFormat::Jsonl,
+            compress,
+            endpoint_id: String::from("abcd"),
+            destination: OutputDestination::Local,
+            ..Default::default()
+        };
+        OutputManager::new(config).unwrap()
+    }
+
+    fn test_file() -> String {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/eventlogs/System.evtx");
+        test_location.display().to_string()
+    }
+
+    #[test]
+    fn test_grab_pagefile_alt_file() {
+        let params = PagefileOptions {
+            alt_file: Some(test_file()),
+            extractors: None,
+            yara_rule: None,
+        };
+        let mut output = output_options("pagefile_temp", "./tmp", false);
+        grab_pagefile(&params, &mut output).unwrap();
+    }
+
+    #[test]
+    fn test_carve_file() {
+        let params = PagefileOptions {
+            alt_file: None,
+            extractors: Some(vec![String::from("evtx")]),
+            yara_rule: None,
+        };
+        let extractors = Extractors::new(&params.extractors).unwrap();
+        let mut carver = PageCarver::new(extractors, &params).unwrap();
+        let reader = file_reader(&test_file()).unwrap();
+        let mut fs = BufReader::new(reader);
+
+        let mut output = output_options("pagefile_temp", "./tmp", false);
+        carver
+            .carve_file(&mut fs, None, "System.evtx", &mut output, &params)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_file_size() {
+        let reader = file_reader(&test_file()).unwrap();
+        let mut fs = BufReader::new(reader);
+        assert_eq!(file_size(None, &mut fs).unwrap(), 1118208);
+    }
+
+    #[test]
+    #[cfg(feature = "yarax")]
+    fn test_yara_pages() {
+        use super::yara_pages;
+        use crate::utils::{encoding::base64_encode_standard, yara::compile_rules};
+        use yara_x::Scanner;
+
+        let rule = r#"
+        rule evtx_chunk {
+        strings:
+        $ = "ElfChnk"
+        condition:
+        all of them
+        }
+        "#;
+        let rules = compile_rules(&base64_encode_standard(rule.as_bytes())).unwrap();
+        let mut scanner = Scanner::new(&rules);
+
+        let mut data = vec![0; 8192];
+        data[4096..4103].copy_from_slice(b"ElfChnk");
+        let hits = yara_pages(&data, 4096, &mut scanner, "pagefile.sys");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].offset, 8192);
+        assert_eq!(hits[0].value, "evtx_chunk");
+    }
+}
@@ -25,7 +25,15 @@ pub(crate) fn read_eventlog_resource(path: &str) -> Result<EventLogResource, Err
         }
     };
 
-    let pe = PeFile::from_bytes(&pe_bytes)?;
+    parse_eventlog_resource(&pe_bytes, path)
+}
+
+/// Extract the eventlog resource data from PE bytes. The path is only used to track where the data came from
+pub(crate) fn parse_eventlog_resource(
+    pe_bytes: &[u8],
+    path: &str,
+) -> Result<EventLogResource, Error> {
+    let pe = PeFile::from_bytes(pe_bytes)?;
     let message_table = Name::Id(11);
     let mui = Name::Wide(&[77, 85, 73]);
     let wevt_template = Name::Wide(&[87, 69, 86, 84, 95, 84, 69, 77, 80, 76, 65, 84, 69]);
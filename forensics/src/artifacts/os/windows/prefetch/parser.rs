@@ -11,7 +11,7 @@
  */
 use crate::{
     artifacts::os::windows::prefetch::error::PrefetchError,
-    filesystem::files::{file_extension, list_files, read_file},
+    filesystem::files::{file_extension, list_files_any, read_any},
     structs::artifacts::os::windows::PrefetchOptions,
     utils::environment::get_systemdrive,
 };
@@ -45,8 +45,8 @@ pub(crate) fn custom_prefetch_path(path: &str) -> Result<Vec<Prefetch>, Prefetch
 
 /// Read all files at provided path
 fn read_directory(path: &str) -> Result<Vec<Prefetch>, PrefetchError> {
-    let dir_results = list_files(path);
-    let read_dir = match dir_results {
+    // Prefetch directories in disk images or snapshots. Ex: vss:C@3!/Windows/Prefetch
+    let read_dir = match list_files_any(path) {
         Ok(result) => result,
         Err(err) => {
             error!("Failed to get prefetch files {path}, error: {err:?}");
@@ -75,7 +75,7 @@ fn read_directory(path: &str) -> Result<Vec<Prefetch>, PrefetchError> {
 
 /// Read and parse the prefetch file
 fn read_prefetch(path: &str) -> Result<Prefetch, PrefetchError> {
-    let buffer_results = read_file(path);
+    let buffer_results = read_any(path);
     let buffer = match buffer_results {
         Ok(result) => result,
         Err(err) => {
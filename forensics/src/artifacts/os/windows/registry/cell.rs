@@ -194,18 +194,16 @@ pub(crate) fn walk_values(
 
 /// Check if a cell is allocated. Negative number = allocated, positive number = unallocated
 pub(crate) fn is_allocated(data: &[u8]) -> nom::IResult<&[u8], (bool, u32)> {
-    let (list_data, mut list_size) = nom_signed_four_bytes(data, Endian::Le)?;
+    let (list_data, list_size) = nom_signed_four_bytes(data, Endian::Le)?;
     let cell_allocated = 0;
 
     // If the size is a positive number then the cell is unallocated (deleted)
-    // We currently do not parse deleted cells
+    // Deleted cells are only parsed if recovery is enabled. See `deleted.rs`
     if list_size >= cell_allocated {
-        return Ok((list_data, (false, 0)));
+        return Ok((list_data, (false, list_size as u32)));
     }
     // Allocated cells have a negative cell size
-    let size_inverse = -1;
-    list_size *= size_inverse;
-    Ok((list_data, (true, list_size as u32)))
+    Ok((list_data, (true, list_size.unsigned_abs())))
 }
 
 #[cfg(test)]
@@ -278,6 +276,7 @@ mod tests {
             key_tracker: Vec::new(),
             offset_tracker: HashMap::new(),
             registry_path: String::from("test\\test"),
+            log_recovery: false,
         };
         let _ = walk_registry(&buffer, 216, &mut params, 4, &mut None, None).unwrap();
     }
@@ -287,7 +286,12 @@ mod tests {
         let test_data = [12, 12, 12, 12];
         let (_, (allocated, size)) = is_allocated(&test_data).unwrap();
         assert_eq!(allocated, false);
-        assert_eq!(size, 0);
+        assert_eq!(size, 202116108);
+
+        let test_data = [240, 255, 255, 255];
+        let (_, (allocated, size)) = is_allocated(&test_data).unwrap();
+        assert!(allocated);
+        assert_eq!(size, 16);
     }
 
     #[test]
@@ -0,0 +1,640 @@
+/**
+ * Deleted `Registry` keys and values are not wiped. Their cells are marked unallocated (positive cell size) and remain until overwritten
+ * Adjacent unallocated cells are merged, but the Name key (nk) and Value key (vk) records inside of them are often still intact
+ *
+ * We walk every hbin, scan the unallocated cells for nk and vk records, and rebuild what we can
+ *
+ * References:
+ *  `https://github.com/msuhanov/regf/blob/master/Windows%20registry%20file%20format%20specification.md#cells`
+ *  `https://binaryforay.blogspot.com/2015/01/registry-hive-basics-part-5-deleted.html`
+ */
+use super::{
+    cell::{is_allocated, walk_values},
+    hbin::HiveBin,
+    keys::{nk::NameKey, vk::ValueKey},
+    parser::Params,
+};
+use crate::{
+    filesystem::files::get_filename,
+    utils::{
+        nom_helper::{Endian, nom_unsigned_four_bytes, nom_unsigned_two_bytes},
+        regex_options::regex_check,
+        time::filetime_to_iso,
+    },
+};
+use common::windows::{KeyValue, RegistryData};
+use std::collections::{HashMap, HashSet};
+use tracing::warn;
+
+/// Name keys and unallocated records found when walking all hbins
+#[derive(Default)]
+struct CellScan {
+    /// All Name keys (allocated and deleted) by cell offset. Used to rebuild parent paths
+    keys: HashMap<u32, KeyPath>,
+    deleted_keys: Vec<(u32, NameKey)>,
+    deleted_values: Vec<u32>,
+    /// Start and end offsets of all unallocated cells
+    unallocated: Vec<(usize, usize)>,
+}
+
+struct KeyPath {
+    name: String,
+    parent: u32,
+    root: bool,
+    values_offset: i32,
+    number_values: u32,
+}
+
+/// Path used for deleted Value keys that could not be associated with a Name key
+const ORPHAN_PATH: &str = "<orphan>";
+
+/// Recover deleted Name keys and Value keys from the unallocated cells in all hbins
+///
+/// Deleted values are attached to their key through old value lists left in the unallocated cells.
+/// Values that cannot be attached are only returned under `<orphan>` if `include_orphans` is enabled and no path filter is set
+pub(crate) fn recover_deleted(
+    reg_data: &[u8],
+    params: &Params,
+    minor_version: u32,
+    include_orphans: bool,
+) -> Vec<RegistryData> {
+    let scan = scan_hive_bins(reg_data);
+    let mut entries = Vec::new();
+    if scan.deleted_keys.is_empty() && scan.deleted_values.is_empty() {
+        return entries;
+    }
+
+    // Deleted cells have a positive (unallocated) size. The normal parsers skip them
+    // Mark the cells we want as allocated in a copy of the Registry data so we can reuse the existing value parsing
+    let mut recovered_data = reg_data.to_vec();
+    let mut associated_values = HashMap::new();
+
+    for (key_offset, name_key) in &scan.deleted_keys {
+        let key = key_path(&scan.keys, name_key.parent);
+        let path = if key.is_empty() {
+            name_key.key_name.clone()
+        } else {
+            format!("{key}\\{}", name_key.key_name)
+        };
+
+        let mut entry = RegistryData {
+            depth: if key.is_empty() {
+                0
+            } else {
+                key.split('\\').count()
+            },
+            path,
+            key,
+            name: name_key.key_name.clone(),
+            values: Vec::new(),
+            last_modified: filetime_to_iso(name_key.last_modified),
+            security_offset: name_key.key_security_offset,
+            registry_file: get_filename(&params.registry_path),
+            evidence: params.registry_path.clone(),
+            log_recovery: params.log_recovery,
+            deleted: true,
+        };
+
+        let no_lists = -1;
+        if name_key.key_values_offset != no_lists && name_key.number_key_values != 0 {
+            let list_offset = name_key.key_values_offset as u32;
+            for value_offset in
+                mark_value_list(&mut recovered_data, list_offset, name_key.number_key_values)
+            {
+                associated_values.insert(value_offset, *key_offset);
+            }
+
+            match walk_values(
+                &recovered_data,
+                list_offset,
+                name_key.number_key_values,
+                minor_version,
+            ) {
+                Ok((_, values)) => entry.values = values,
+                Err(_err) => warn!("Could not recover values for deleted key {}", entry.path),
+            }
+        }
+
+        if path_filter(params, &entry.path) {
+            entries.push(entry);
+        }
+    }
+
+    // Remaining Value keys could not be associated with a deleted Name key
+    // Values deleted from a key may still be listed in an old value list next to values the key still has
+    let value_owners = value_owners(reg_data, &scan, &associated_values);
+    let path_filtered = !params.start_path.is_empty() || !params.path_regex.as_str().is_empty();
+    for offset in &scan.deleted_values {
+        if associated_values.contains_key(offset) {
+            continue;
+        }
+        let owner = old_value_list_owner(reg_data, &scan.unallocated, *offset, &value_owners);
+        if owner.is_none() && (!include_orphans || path_filtered) {
+            continue;
+        }
+        mark_value_data(&mut recovered_data, *offset);
+
+        let cell_header = 4;
+        let start = *offset as usize + cell_header;
+        let value_key = match ValueKey::parse_value_key(
+            &recovered_data,
+            &recovered_data[start..],
+            minor_version,
+        ) {
+            Ok((_, result)) => result,
+            Err(_err) => {
+                warn!("Could not recover deleted value at offset {offset}");
+                continue;
+            }
+        };
+
+        let (path, key, name) = match owner {
+            Some(owner) => {
+                let path = key_path(&scan.keys, owner);
+                let (key, name) = match path.rsplit_once('\\') {
+                    Some((key, name)) => (key.to_string(), name.to_string()),
+                    None => (String::new(), path.clone()),
+                };
+                (path, key, name)
+            }
+            None => (String::from(ORPHAN_PATH), String::new(), String::new()),
+        };
+
+        let entry = RegistryData {
+            depth: if key.is_empty() {
+                0
+            } else {
+                key.split('\\').count()
+            },
+            path,
+            key,
+            name,
+            values: vec![KeyValue {
+                value: value_key.value_name,
+                data: value_key.data,
+                data_type: value_key.data_type,
+            }],
+            // Value keys do not have a timestamp
+            last_modified: String::new(),
+            security_offset: 0,
+            registry_file: get_filename(&params.registry_path),
+            evidence: params.registry_path.clone(),
+            log_recovery: params.log_recovery,
+            deleted: true,
+        };
+
+        if owner.is_none() || path_filter(params, &entry.path) {
+            entries.push(entry);
+        }
+    }
+
+    entries
+}
+
+/// Map the Value keys in each Name key value list to the Name key cell offset
+fn value_owners(
+    reg_data: &[u8],
+    scan: &CellScan,
+    associated_values: &HashMap<u32, u32>,
+) -> HashMap<u32, u32> {
+    let mut owners = associated_values.clone();
+    let no_lists = -1;
+    for (key_offset, key) in &scan.keys {
+        if key.values_offset == no_lists || key.number_values == 0 {
+            continue;
+        }
+        for value_offset in list_entries(
+            reg_data,
+            key.values_offset as usize + 4,
+            key.number_values as usize,
+        ) {
+            owners.entry(value_offset).or_insert(*key_offset);
+        }
+    }
+    owners
+}
+
+/// Find the Name key for a deleted Value key by searching the unallocated cells for an old value list that contains the value
+///
+/// Old value lists have no signature. The other offsets next to the deleted value are checked against known value lists
+fn old_value_list_owner(
+    reg_data: &[u8],
+    unallocated: &[(usize, usize)],
+    value_offset: u32,
+    owners: &HashMap<u32, u32>,
+) -> Option<u32> {
+    if let Some(owner) = owners.get(&value_offset) {
+        return Some(*owner);
+    }
+
+    let entry_size = 4;
+    let needle = value_offset.to_le_bytes();
+    for (start, end) in unallocated {
+        // Skip the cell size
+        let mut offset = start + entry_size;
+        while offset + entry_size <= *end {
+            if reg_data[offset..offset + entry_size] != needle {
+                offset += entry_size;
+                continue;
+            }
+
+            // Walk the neighboring entries in both directions while they still look like cell offsets
+            let mut before = offset;
+            while before >= start + entry_size * 2 {
+                before -= entry_size;
+                let Some(neighbor) = list_entries(reg_data, before, 1).pop() else {
+                    break;
+                };
+                if let Some(owner) = owners.get(&neighbor) {
+                    return Some(*owner);
+                }
+            }
+            let mut after = offset + entry_size;
+            while after + entry_size <= *end {
+                let Some(neighbor) = list_entries(reg_data, after, 1).pop() else {
+                    break;
+                };
+                if let Some(owner) = owners.get(&neighbor) {
+                    return Some(*owner);
+                }
+                after += entry_size;
+            }
+            offset += entry_size;
+        }
+    }
+    None
+}
+
+/// Read the Value key offsets in a value list. Stops at the first entry that cannot be a cell offset
+fn list_entries(reg_data: &[u8], start: usize, count: usize) -> Vec<u32> {
+    let entry_size = 4;
+    let cell_alignment = 8;
+    let mut entries = Vec::new();
+    for index in 0..count {
+        let entry_offset = start + index * entry_size;
+        let Some(entry) = reg_data.get(entry_offset..entry_offset + entry_size) else {
+            break;
+        };
+        let Ok((_, value_offset)) = nom_unsigned_four_bytes(entry, Endian::Le) else {
+            break;
+        };
+        let empty = 0;
+        if value_offset == empty
+            || value_offset % cell_alignment != 0
+            || value_offset as usize >= reg_data.len()
+        {
+            break;
+        }
+        entries.push(value_offset);
+    }
+    entries
+}
+
+/// Walk the cells in every hbin. Track all Name keys and any nk or vk records in unallocated cells
+fn scan_hive_bins(reg_data: &[u8]) -> CellScan {
+    let mut scan = CellScan::default();
+    let hbin_header_size = 32;
+    let hbin_sig = 0x6e696268;
+    let page_size = 4096;
+    let min_cell_size = 8;
+
+    let mut hbin_offset = 0;
+    while hbin_offset + hbin_header_size <= reg_data.len() {
+        let hbin = match HiveBin::parse_hive_bin_header(&reg_data[hbin_offset..]) {
+            Ok((_, result)) => result,
+            Err(_err) => break,
+        };
+        if hbin.signature != hbin_sig || hbin.size == 0 || hbin.size % page_size != 0 {
+            break;
+        }
+
+        let hbin_end = reg_data.len().min(hbin_offset + hbin.size as usize);
+        let mut cell_offset = hbin_offset + hbin_header_size;
+        while cell_offset + min_cell_size <= hbin_end {
+            let (allocated, size) = match is_allocated(&reg_data[cell_offset..]) {
+                Ok((_, result)) => result,
+                Err(_err) => break,
+            };
+            let cell_end = cell_offset + size as usize;
+            if (size as usize) < min_cell_size || cell_end > hbin_end {
+                break;
+            }
+
+            if allocated {
+                if let Some(name_key) = get_name_key(&reg_data[..cell_end], cell_offset) {
+                    scan.keys.insert(cell_offset as u32, key_info(&name_key));
+                }
+            } else {
+                scan.unallocated.push((cell_offset, cell_end));
+                scan_unallocated(reg_data, cell_offset, cell_end, &mut scan);
+            }
+            cell_offset = cell_end;
+        }
+
+        hbin_offset += hbin.size as usize;
+    }
+
+    scan
+}
+
+/// Unallocated cells may contain several old cells. Cells are aligned to 8 bytes so check every 8 bytes for nk or vk records
+fn scan_unallocated(reg_data: &[u8], start: usize, end: usize, scan: &mut CellScan) {
+    let alignment = 8;
+    let value_sig = [0x76, 0x6b]; // vk
+    let mut offset = start;
+    while offset + alignment <= end {
+        if let Some(name_key) = get_name_key(&reg_data[..end], offset) {
+            scan.keys.insert(offset as u32, key_info(&name_key));
+            scan.deleted_keys.push((offset as u32, name_key));
+        } else if reg_data[offset + 4..offset + 6] == value_sig {
+            scan.deleted_values.push(offset as u32);
+        }
+        offset += alignment;
+    }
+}
+
+/// Parse a Name key at the provided cell offset. The provided data should end at the end of the cell
+fn get_name_key(data: &[u8], offset: usize) -> Option<NameKey> {
+    let key_sig = [0x6e, 0x6b]; // nk
+    let cell_header = 4;
+    let start = offset + cell_header;
+    if data.len() < start + key_sig.len() || data[start..start + key_sig.len()] != key_sig {
+        return None;
+    }
+
+    let (_, name_key) = NameKey::parse_key(&data[start..]).ok()?;
+    if name_key.key_name.is_empty() {
+        return None;
+    }
+    Some(name_key)
+}
+
+fn key_info(name_key: &NameKey) -> KeyPath {
+    let root_key = 0x4;
+    KeyPath {
+        name: name_key.key_name.clone(),
+        parent: name_key.parent,
+        root: name_key.flags & root_key == root_key,
+        values_offset: name_key.key_values_offset,
+        number_values: name_key.number_key_values,
+    }
+}
+
+/// Best effort attempt to rebuild the path to the parent key. Stops at the first parent we cannot find
+fn key_path(keys: &HashMap<u32, KeyPath>, parent: u32) -> String {
+    let mut names = Vec::new();
+    let mut seen = HashSet::new();
+    let mut offset = parent;
+
+    while let Some(key) = keys.get(&offset) {
+        // Prevent infinite loops from bad parent offsets
+        if !seen.insert(offset) {
+            break;
+        }
+        names.push(key.name.as_str());
+        if key.root {
+            break;
+        }
+        offset = key.parent;
+    }
+
+    names.reverse();
+    names.join("\\")
+}
+
+/// Mark the value list, and all cells for the values in the list, as allocated
+/// Returns the offsets to the Value keys in the list
+fn mark_value_list(data: &mut [u8], list_offset: u32, number_values: u32) -> Vec<u32> {
+    let mut value_offsets = Vec::new();
+    let Some(list_size) = mark_allocated(data, list_offset) else {
+        return value_offsets;
+    };
+
+    let cell_header = 4;
+    let max_values = (list_size - cell_header) / 4;
+    let list_start = list_offset as usize + cell_header as usize;
+    for index in 0..number_values.min(max_values) as usize {
+        let entry_offset = list_start + index * 4;
+        let Ok((_, value_offset)) = nom_unsigned_four_bytes(&data[entry_offset..], Endian::Le)
+        else {
+            break;
+        };
+        let empty = 0;
+        if value_offset == empty || value_offset == u32::MAX {
+            continue;
+        }
+
+        mark_allocated(data, value_offset);
+        mark_value_data(data, value_offset);
+        value_offsets.push(value_offset);
+    }
+
+    value_offsets
+}
+
+/// Mark the data cells associated with a Value key as allocated
+fn mark_value_data(data: &mut [u8], value_offset: u32) {
+    // Skip the cell size, signature, and name size
+    let data_size_offset = value_offset as usize + 8;
+    if data.len() < data_size_offset + 8 {
+        return;
+    }
+    let Ok((input, data_size)) = nom_unsigned_four_bytes(&data[data_size_offset..], Endian::Le)
+    else {
+        return;
+    };
+    let Ok((_, data_offset)) = nom_unsigned_four_bytes(input, Endian::Le) else {
+        return;
+    };
+
+    let resident_check = 0x80000000;
+    let empty = 0;
+    if data_size == empty || (data_size & resident_check) == resident_check {
+        return;
+    }
+    if mark_allocated(data, data_offset).is_none() {
+        return;
+    }
+
+    // Large values are stored in a Db cell that points to a list of data segments
+    let db_sig = [0x64, 0x62];
+    let db_start = data_offset as usize + 4;
+    if data.len() < db_start + 8 || data[db_start..db_start + 2] != db_sig {
+        return;
+    }
+    let Ok((input, segments)) = nom_unsigned_two_bytes(&data[db_start + 2..], Endian::Le) else {
+        return;
+    };
+    let Ok((_, segments_offset)) = nom_unsigned_four_bytes(input, Endian::Le) else {
+        return;
+    };
+    if mark_allocated(data, segments_offset).is_none() {
+        return;
+    }
+
+    for index in 0..segments as usize {
+        let entry_offset = segments_offset as usize + 4 + index * 4;
+        let Some(entry) = data.get(entry_offset..) else {
+            break;
+        };
+        let Ok((_, segment_offset)) = nom_unsigned_four_bytes(entry, Endian::Le) else {
+            break;
+        };
+        mark_allocated(data, segment_offset);
+    }
+}
+
+/// Flip the size of an unallocated cell so the cell is treated as allocated. Returns the cell size
+fn mark_allocated(data: &mut [u8], offset: u32) -> Option<u32> {
+    let start = offset as usize;
+    let (_, (allocated, size)) = is_allocated(data.get(start..)?).ok()?;
+    let min_cell_size = 8;
+    if size < min_cell_size || start + size as usize > data.len() {
+        return None;
+    }
+    if !allocated {
+        let allocated_size = -(size as i32);
+        data[start..start + 4].copy_from_slice(&allocated_size.to_le_bytes());
+    }
+    Some(size)
+}
+
+/// Apply the same path filtering used for allocated keys
+fn path_filter(params: &Params, path: &str) -> bool {
+    path.to_lowercase()
+        .starts_with(&params.start_path.to_lowercase())
+        && regex_check(&params.path_regex, &path.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{key_path, mark_allocated, old_value_list_owner, recover_deleted, scan_hive_bins};
+    use crate::{artifacts::os::windows::registry::parser::Params, filesystem::files::read_file};
+    use regex::Regex;
+    use std::{collections::HashMap, path::PathBuf};
+
+    fn hive_bins() -> Vec<u8> {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/registry/win10/NTUSER.DAT");
+        let buffer = read_file(&test_location.display().to_string()).unwrap();
+        let base_block_size = 4096;
+        let hive_bins_size = 196608;
+        buffer[base_block_size..base_block_size + hive_bins_size].to_vec()
+    }
+
+    #[test]
+    fn test_scan_hive_bins() {
+        let data = hive_bins();
+        let scan = scan_hive_bins(&data);
+        assert_eq!(scan.deleted_keys.len(), 2);
+        assert_eq!(scan.deleted_values.len(), 1);
+        assert_eq!(scan.deleted_keys[0].0, 153408);
+        assert_eq!(scan.deleted_keys[0].1.key_name, "Connection Manager Old");
+        assert!(scan.keys.len() > 600);
+    }
+
+    #[test]
+    fn test_recover_deleted() {
+        let data = hive_bins();
+        let params = Params {
+            start_path: String::new(),
+            path_regex: Regex::new("").unwrap(),
+            registry_list: Vec::new(),
+            key_tracker: Vec::new(),
+            offset_tracker: HashMap::new(),
+            registry_path: String::from("test\\NTUSER.DAT"),
+            log_recovery: false,
+        };
+
+        let results = recover_deleted(&data, &params, 5, true);
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|entry| entry.deleted));
+        assert_eq!(
+            results[0].path,
+            "ROOT\\SOFTWARE\\Microsoft\\Connection Manager Old"
+        );
+        assert_eq!(results[0].last_modified, "2022-10-31T04:24:10.394Z");
+        assert_eq!(results[0].depth, 3);
+        assert_eq!(
+            results[1].key,
+            "ROOT\\SOFTWARE\\Microsoft\\Connection Manager Old"
+        );
+        assert_eq!(results[1].name, "UserInfo");
+        assert_eq!(results[2].path, "<orphan>");
+        assert_eq!(results[2].values[0].value, "Default");
+        assert_eq!(
+            results[2].values[0].data,
+            "{00000000-0000-0000-0000-000000000000}"
+        );
+        assert_eq!(results[2].registry_file, "NTUSER.DAT");
+        assert!(results[2].last_modified.is_empty());
+    }
+
+    #[test]
+    fn test_recover_deleted_filter() {
+        let data = hive_bins();
+        let params = Params {
+            start_path: String::from("ROOT\\SOFTWARE"),
+            path_regex: Regex::new(r".*userinfo").unwrap(),
+            registry_list: Vec::new(),
+            key_tracker: Vec::new(),
+            offset_tracker: HashMap::new(),
+            registry_path: String::from("test\\NTUSER.DAT"),
+            log_recovery: false,
+        };
+
+        // Orphan values are skipped when a path filter is set
+        let results = recover_deleted(&data, &params, 5, true);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "UserInfo");
+    }
+
+    #[test]
+    fn test_recover_deleted_no_orphans() {
+        let data = hive_bins();
+        let params = Params {
+            start_path: String::new(),
+            path_regex: Regex::new("").unwrap(),
+            registry_list: Vec::new(),
+            key_tracker: Vec::new(),
+            offset_tracker: HashMap::new(),
+            registry_path: String::from("test\\NTUSER.DAT"),
+            log_recovery: false,
+        };
+
+        let results = recover_deleted(&data, &params, 5, false);
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|entry| entry.path != "<orphan>"));
+    }
+
+    #[test]
+    fn test_old_value_list_owner() {
+        let mut data = vec![0; 64];
+        // Old value list in an unallocated cell: a deleted value at 40 next to a live value at 48
+        data[0..4].copy_from_slice(&16i32.to_le_bytes());
+        data[4..8].copy_from_slice(&48u32.to_le_bytes());
+        data[8..12].copy_from_slice(&40u32.to_le_bytes());
+        let owners = HashMap::from([(48, 24)]);
+
+        assert_eq!(
+            old_value_list_owner(&data, &[(0, 16)], 40, &owners),
+            Some(24)
+        );
+        assert_eq!(old_value_list_owner(&data, &[(0, 16)], 32, &owners), None);
+    }
+
+    #[test]
+    fn test_key_path() {
+        let data = hive_bins();
+        let scan = scan_hive_bins(&data);
+        let path = key_path(&scan.keys, scan.deleted_keys[1].1.parent);
+        assert_eq!(path, "ROOT\\SOFTWARE\\Microsoft\\Connection Manager Old");
+    }
+
+    #[test]
+    fn test_mark_allocated() {
+        let mut test = [16, 0, 0, 0, 118, 107, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(mark_allocated(&mut test, 0).unwrap(), 16);
+        assert_eq!(test[0..4], (-16i32).to_le_bytes());
+    }
+}
@@ -14,7 +14,7 @@ use serde::Serialize;
 
 #[derive(Debug, Serialize)]
 pub(crate) struct HiveBin {
-    signature: u32,
+    pub(crate) signature: u32,
     offset: u32,
     pub(crate) size: u32,
     reserved: u64,
@@ -143,6 +143,7 @@ mod tests {
             key_tracker: Vec::new(),
             offset_tracker: HashMap::new(),
             registry_path: String::from("test\\test"),
+            log_recovery: false,
         };
 
         let (_, result) =
@@ -26,8 +26,8 @@ pub(crate) struct RegHeader {
     reserved2: Vec<u8>, // 3576 bytes
     boot_type: u32,
     boot_recover: u32,
-    is_dirty: bool,
-    valid_checksum: bool,
+    pub(crate) is_dirty: bool,
+    pub(crate) valid_checksum: bool,
 }
 
 impl RegHeader {
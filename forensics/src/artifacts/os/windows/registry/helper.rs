@@ -1,5 +1,6 @@
 use super::{
-    error::RegistryError, hbin::HiveBin, header::RegHeader, keys::sk::SecurityKey, parser::Params,
+    deleted::recover_deleted, error::RegistryError, hbin::HiveBin, header::RegHeader,
+    keys::sk::SecurityKey, parser::Params, transaction::replay_transaction_logs,
 };
 use crate::{
     filesystem::ntfs::{
@@ -17,6 +18,7 @@ use std::collections::HashMap;
 use tracing::error;
 
 /// Parse provided `Registry` file at starting Key path and apply any optional Key path regex filtering
+/// If the `Registry` file is dirty, any sibling transaction logs (.LOG1/.LOG2) are replayed first
 /// Use `get_registry_keys_by_ref` if you want to provide a `Registry` file reference
 pub(crate) fn get_registry_keys(
     start_path: &str,
@@ -30,8 +32,10 @@ pub(crate) fn get_registry_keys(
         key_tracker: Vec::new(),
         offset_tracker: HashMap::new(),
         registry_path: file_path.to_string(),
+        log_recovery: false,
     };
-    let buffer = read_registry(file_path)?;
+    let mut buffer = read_registry(file_path)?;
+    params.log_recovery = replay_transaction_logs(&mut buffer, file_path);
     let reg_entries_results = parse_raw_registry(&buffer, &mut params, &mut None, None);
     match reg_entries_results {
         Ok((_, results)) => Ok(results),
@@ -43,12 +47,13 @@ pub(crate) fn get_registry_keys(
 }
 
 /// Parse provided `Registry` file reference at starting Key path and apply any optional Key path regex filtering
+/// The `file_path` of the reference is used to find sibling transaction logs (.LOG1/.LOG2) if the `Registry` file is dirty
 /// Use `get_registry_keys` if you want to provide a `Registry` file
-/// This wont fill in the the evidence field in the `RegistryData` array because we are parsing a file reference
 pub(crate) fn get_registry_keys_by_ref(
     start_path: &str,
     regex: &Regex,
     file_ref: NtfsFileReference,
+    file_path: &str,
     ntfs_parser: &mut NtfsParser,
 ) -> Result<Vec<RegistryData>, RegistryError> {
     let mut params = Params {
@@ -57,9 +62,11 @@ pub(crate) fn get_registry_keys_by_ref(
         registry_list: Vec::new(),
         key_tracker: Vec::new(),
         offset_tracker: HashMap::new(),
-        registry_path: String::new(),
+        registry_path: file_path.to_string(),
+        log_recovery: false,
     };
-    let buffer = read_registry_ref(file_ref, ntfs_parser)?;
+    let mut buffer = read_registry_ref(file_ref, ntfs_parser)?;
+    params.log_recovery = replay_transaction_logs(&mut buffer, file_path);
     let reg_entries_results = parse_raw_registry(&buffer, &mut params, &mut None, None);
     match reg_entries_results {
         Ok((_, results)) => Ok(results),
@@ -73,6 +80,8 @@ pub(crate) fn get_registry_keys_by_ref(
 /// Parse the provided `Registry` bytes with associated parsing parameters.
 /// Provide an optional `OutputManager` structure if you want artemis to stream the Registry output to disk.
 /// Caller will need to handle any leftover `Params.registry_list` data remaining from the stream
+/// If `RegistryOptions.recover_deleted` is enabled, deleted keys and values are appended to the results.
+/// Deleted values without a key are only included if `RegistryOptions.recover_orphans` is also enabled
 pub(crate) fn parse_raw_registry<'a>(
     data: &'a [u8],
     params: &mut Params,
@@ -85,7 +94,7 @@ pub(crate) fn parse_raw_registry<'a>(
     let (_, result) = HiveBin::parse_hive_bin_header(reg_data)?;
     let (input, hbin_data) = take(result.size)(reg_data)?;
 
-    let (_, result) = HiveBin::parse_hive_cells(
+    let (_, mut result) = HiveBin::parse_hive_cells(
         reg_data,
         hbin_data,
         params,
@@ -94,11 +103,23 @@ pub(crate) fn parse_raw_registry<'a>(
         options,
     )?;
 
+    if let Some(opt) = options
+        && opt.recover_deleted.unwrap_or(false)
+    {
+        result.append(&mut recover_deleted(
+            reg_data,
+            params,
+            header.minor_version,
+            opt.recover_orphans.unwrap_or(false),
+        ));
+    }
+
     Ok((input, result))
 }
 
 /// Read the `Registry` file provided at path
 pub(crate) fn read_registry(path: &str) -> Result<Vec<u8>, RegistryError> {
+    // Hives in archives are supported. Ex: zip:hives.zip!NTUSER.DAT. Sibling transaction logs are read the same way
     let result = raw_read_file(path);
     match result {
         Ok(buffer) => Ok(buffer),
@@ -198,6 +219,7 @@ mod tests {
                 key_tracker: Vec::new(),
                 offset_tracker: HashMap::new(),
                 registry_path: String::new(),
+                log_recovery: false,
             };
             let result = parse_raw_registry(&buffer, &mut params, &mut None, None);
             if result.is_err() {
@@ -286,6 +308,7 @@ mod tests {
                 "",
                 &Regex::new("").unwrap(),
                 hive.reg_reference,
+                &hive.full_path,
                 &mut ntfs_parser,
             )
             .unwrap();
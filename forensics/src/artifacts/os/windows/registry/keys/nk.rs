@@ -24,17 +24,17 @@ use tracing::error;
 #[derive(Debug)]
 pub(crate) struct NameKey {
     _sig: u16,
-    _flags: u16,
-    _last_modified: u64,
+    pub(crate) flags: u16,
+    pub(crate) last_modified: u64,
     _accessed_bits: u32, // If Windows 8+, otherwise its Spare
-    _parent: u32,
+    pub(crate) parent: u32,
     _num_subkeys: u32,
     _num_volatile_subkeys: u32, // Not used when parsing Registry file on disk
     subkeys_list_offset: i32,
     _volatile_subkeys_list_offset: i32, // Not used when parsing Registry file on disk
-    number_key_values: u32,
-    key_values_offset: i32,
-    _key_security_offset: i32,
+    pub(crate) number_key_values: u32,
+    pub(crate) key_values_offset: i32,
+    pub(crate) key_security_offset: i32,
     _class_name_offset: i32,
     _largest_subkey_name_length: u32,
     _largest_class_name_length: u32,
@@ -43,7 +43,7 @@ pub(crate) struct NameKey {
     _workvar: u32,
     _key_name_length: u16,
     _class_name_length: u16,
-    key_name: String,
+    pub(crate) key_name: String,
 }
 
 impl NameKey {
@@ -56,71 +56,20 @@ impl NameKey {
         manager: &mut Option<&mut OutputManager>,
         options: Option<&RegistryOptions>,
     ) -> nom::IResult<&'a [u8], ()> {
-        let (input, sig) = nom_unsigned_two_bytes(name_key, Endian::Le)?;
-        let (input, flags) = nom_unsigned_two_bytes(input, Endian::Le)?;
-        let (input, last_modified) = nom_unsigned_eight_bytes(input, Endian::Le)?;
-        let (input, accessed_bits) = nom_unsigned_four_bytes(input, Endian::Le)?;
-        let (input, parent) = nom_unsigned_four_bytes(input, Endian::Le)?;
-        let (input, num_subkeys) = nom_unsigned_four_bytes(input, Endian::Le)?;
-
-        let (input, num_volatile_subkeys) = nom_unsigned_four_bytes(input, Endian::Le)?;
-        let (input, subkeys_list_offset) = nom_signed_four_bytes(input, Endian::Le)?;
-        let (input, volatile_subkeys_list_offset) = nom_signed_four_bytes(input, Endian::Le)?;
-        let (input, number_key_values) = nom_unsigned_four_bytes(input, Endian::Le)?;
-        let (input, key_values_offset) = nom_signed_four_bytes(input, Endian::Le)?;
-        let (input, key_security_offset) = nom_signed_four_bytes(input, Endian::Le)?;
-        let (input, class_name_offset) = nom_signed_four_bytes(input, Endian::Le)?;
-
-        let (input, largest_subkey_name_length) = nom_unsigned_four_bytes(input, Endian::Le)?;
-        let (input, largest_class_name_length) = nom_unsigned_four_bytes(input, Endian::Le)?;
-        let (input, largest_value_name_length) = nom_unsigned_four_bytes(input, Endian::Le)?;
-        let (input, largest_value_data_length) = nom_unsigned_four_bytes(input, Endian::Le)?;
-
-        let (input, workvar) = nom_unsigned_four_bytes(input, Endian::Le)?;
-        let (input, key_name_length) = nom_unsigned_two_bytes(input, Endian::Le)?;
-        let (input, class_name_length) = nom_unsigned_two_bytes(input, Endian::Le)?;
-        let (input, key_name_data) = take(key_name_length)(input)?;
-
-        // The string can either be ASCII or UTF16
-        let mut key_name = extract_ascii_utf16_string(key_name_data);
-        if format!("{key_name:?}").contains("\\u{") {
-            key_name = extract_utf16_string(key_name_data);
-        }
-
-        let name_key = NameKey {
-            _sig: sig,
-            _flags: flags,
-            _last_modified: last_modified,
-            _accessed_bits: accessed_bits,
-            _parent: parent,
-            _num_subkeys: num_subkeys,
-            _num_volatile_subkeys: num_volatile_subkeys,
-            subkeys_list_offset,
-            _volatile_subkeys_list_offset: volatile_subkeys_list_offset,
-            number_key_values,
-            key_values_offset,
-            _key_security_offset: key_security_offset, // Currently not parsing Security Key data
-            _class_name_offset: class_name_offset,
-            _largest_subkey_name_length: largest_subkey_name_length,
-            _largest_class_name_length: largest_class_name_length,
-            _largest_value_name_length: largest_value_name_length,
-            _largest_value_data_length: largest_value_data_length,
-            _workvar: workvar,
-            _key_name_length: key_name_length,
-            _class_name_length: class_name_length,
-            key_name,
-        };
+        let (input, name_key) = NameKey::parse_key(name_key)?;
 
         let mut registry_entry = RegistryData {
             path: String::new(),
             key: params.key_tracker.join("\\"),
             name: name_key.key_name.clone(),
             values: Vec::new(),
-            last_modified: filetime_to_iso(last_modified),
+            last_modified: filetime_to_iso(name_key.last_modified),
             depth: params.key_tracker.len(),
-            security_offset: key_security_offset,
+            security_offset: name_key.key_security_offset,
             registry_file: get_filename(&params.registry_path),
             evidence: params.registry_path.clone(),
+            log_recovery: params.log_recovery,
+            deleted: false,
         };
 
         params.key_tracker.push(name_key.key_name);
@@ -203,6 +152,66 @@ impl NameKey {
         params.key_tracker.pop();
         Ok((input, ()))
     }
+
+    /// Parse the Name key structure
+    pub(crate) fn parse_key(name_key: &[u8]) -> nom::IResult<&[u8], NameKey> {
+        let (input, sig) = nom_unsigned_two_bytes(name_key, Endian::Le)?;
+        let (input, flags) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, last_modified) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+        let (input, accessed_bits) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, parent) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, num_subkeys) = nom_unsigned_four_bytes(input, Endian::Le)?;
+
+        let (input, num_volatile_subkeys) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, subkeys_list_offset) = nom_signed_four_bytes(input, Endian::Le)?;
+        let (input, volatile_subkeys_list_offset) = nom_signed_four_bytes(input, Endian::Le)?;
+        let (input, number_key_values) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, key_values_offset) = nom_signed_four_bytes(input, Endian::Le)?;
+        let (input, key_security_offset) = nom_signed_four_bytes(input, Endian::Le)?;
+        let (input, class_name_offset) = nom_signed_four_bytes(input, Endian::Le)?;
+
+        let (input, largest_subkey_name_length) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, largest_class_name_length) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, largest_value_name_length) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, largest_value_data_length) = nom_unsigned_four_bytes(input, Endian::Le)?;
+
+        let (input, workvar) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, key_name_length) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, class_name_length) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, key_name_data) = take(key_name_length)(input)?;
+
+        // The string can either be ASCII or UTF16
+        let mut key_name = extract_ascii_utf16_string(key_name_data);
+        if format!("{key_name:?}").contains("\\u{") {
+            key_name = extract_utf16_string(key_name_data);
+        }
+
+        let name_key = NameKey {
+            _sig: sig,
+            flags,
+            last_modified,
+            _accessed_bits: accessed_bits,
+            parent,
+            _num_subkeys: num_subkeys,
+            _num_volatile_subkeys: num_volatile_subkeys,
+            subkeys_list_offset,
+            _volatile_subkeys_list_offset: volatile_subkeys_list_offset,
+            number_key_values,
+            key_values_offset,
+            key_security_offset,
+            _class_name_offset: class_name_offset,
+            _largest_subkey_name_length: largest_subkey_name_length,
+            _largest_class_name_length: largest_class_name_length,
+            _largest_value_name_length: largest_value_name_length,
+            _largest_value_data_length: largest_value_data_length,
+            _workvar: workvar,
+            _key_name_length: key_name_length,
+            _class_name_length: class_name_length,
+            key_name,
+        };
+
+        Ok((input, name_key))
+    }
 }
 
 #[cfg(test)]
@@ -236,6 +245,7 @@ mod tests {
             key_tracker: Vec::new(),
             offset_tracker: HashMap::new(),
             registry_path: String::from("test/test"),
+            log_recovery: false,
         };
 
         let (_, result) =
@@ -46,6 +46,7 @@ mod tests {
             key_tracker: Vec::new(),
             offset_tracker: HashMap::new(),
             registry_path: String::from("path/NTUSER.dat"),
+            log_recovery: false,
         };
 
         let (_, result) =
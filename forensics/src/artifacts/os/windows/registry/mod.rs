@@ -1,4 +1,5 @@
 mod cell;
+mod deleted;
 mod error;
 mod hbin;
 mod header;
@@ -6,3 +7,4 @@ pub(crate) mod helper;
 mod keys;
 mod lists;
 pub(crate) mod parser;
+mod transaction;
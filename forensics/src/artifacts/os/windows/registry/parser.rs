@@ -20,6 +20,7 @@
 use super::{
     error::RegistryError,
     helper::{parse_raw_registry, read_registry},
+    transaction::replay_transaction_logs,
 };
 use crate::{
     filesystem::ntfs::{
@@ -43,6 +44,7 @@ pub(crate) struct Params {
     pub(crate) key_tracker: Vec<String>, // Track Registry paths as we walk them
     pub(crate) offset_tracker: HashMap<u32, u32>, // Track Registry offsets to prevent infinite loops
     pub(crate) registry_path: String,
+    pub(crate) log_recovery: bool, // Track if transaction logs were replayed onto the Registry file
 }
 
 /// Parse Windows `Registry` files based on provided options
@@ -58,6 +60,7 @@ pub(crate) fn parse_registry(
         key_tracker: Vec::new(),
         offset_tracker: HashMap::new(),
         registry_path: String::new(),
+        log_recovery: false,
     };
 
     if let Some(path) = &options.alt_file {
@@ -133,7 +136,8 @@ fn parse_registry_file(
     params: &mut Params,
     options: &RegistryOptions,
 ) -> Result<(), RegistryError> {
-    let buffer = read_registry(&params.registry_path)?;
+    let mut buffer = read_registry(&params.registry_path)?;
+    params.log_recovery = replay_transaction_logs(&mut buffer, &params.registry_path);
     let reg_results = parse_raw_registry(&buffer, params, &mut Some(manager), Some(options));
     let entries = match reg_results {
         Ok((_, results)) => results,
@@ -192,7 +196,7 @@ fn parse_user_hives(
     for path in user_hives {
         let buffer_result =
             raw_read_by_file_ref(path.reg_reference, &ntfs_parser.ntfs, &mut ntfs_parser.fs);
-        let buffer = match buffer_result {
+        let mut buffer = match buffer_result {
             Ok(result) => result,
             Err(err) => {
                 error!(
@@ -203,6 +207,7 @@ fn parse_user_hives(
             }
         };
 
+        params.log_recovery = replay_transaction_logs(&mut buffer, &path.full_path);
         params.registry_path = path.full_path;
 
         let reg_results = parse_raw_registry(&buffer, params, &mut Some(manager), Some(options));
@@ -270,6 +275,8 @@ mod tests {
             system_hives: false,
             alt_file: None,
             path_regex: None,
+            recover_deleted: None,
+            recover_orphans: None,
         };
         let mut params = Params {
             start_path: String::from("ROOT"),
@@ -278,6 +285,7 @@ mod tests {
             key_tracker: Vec::new(),
             offset_tracker: HashMap::new(),
             registry_path: String::new(),
+            log_recovery: false,
         };
         parse_user_hives('C', &mut output, &mut params, &options).unwrap();
     }
@@ -290,6 +298,8 @@ mod tests {
             system_hives: true,
             alt_file: None,
             path_regex: None,
+            recover_deleted: None,
+            recover_orphans: None,
         };
         let mut params = Params {
             start_path: String::from("ROOT"),
@@ -298,6 +308,7 @@ mod tests {
             key_tracker: Vec::new(),
             offset_tracker: HashMap::new(),
             registry_path: String::new(),
+            log_recovery: false,
         };
         parse_default_system_hives('C', &mut output, &mut params, &options).unwrap();
     }
@@ -310,6 +321,8 @@ mod tests {
             system_hives: false,
             alt_file: None,
             path_regex: None,
+            recover_deleted: None,
+            recover_orphans: None,
         };
         let mut params = Params {
             start_path: String::from("ROOT\\SOFTWARE\\Microsoft\\"),
@@ -318,6 +331,7 @@ mod tests {
             key_tracker: Vec::new(),
             offset_tracker: HashMap::new(),
             registry_path: String::new(),
+            log_recovery: false,
         };
         parse_user_hives('C', &mut output, &mut params, &options).unwrap();
     }
@@ -330,6 +344,8 @@ mod tests {
             system_hives: true,
             alt_file: None,
             path_regex: None,
+            recover_deleted: None,
+            recover_orphans: None,
         };
         let mut params = Params {
             start_path: String::from("ROOT\\Microsoft\\Windows\\CurrentVersion\\Run"),
@@ -338,6 +354,7 @@ mod tests {
             key_tracker: Vec::new(),
             offset_tracker: HashMap::new(),
             registry_path: String::new(),
+            log_recovery: false,
         };
         parse_default_system_hives('C', &mut output, &mut params, &options).unwrap();
     }
@@ -351,6 +368,8 @@ mod tests {
             system_hives: false,
             alt_file: None,
             path_regex: None,
+            recover_deleted: None,
+            recover_orphans: None,
         };
         parse_registry(&reg_options, &mut output).unwrap();
     }
@@ -365,6 +384,8 @@ mod tests {
             system_hives: false,
             alt_file: None,
             path_regex: None,
+            recover_deleted: None,
+            recover_orphans: None,
         };
         let mut params = Params {
             start_path: String::from(""),
@@ -373,6 +394,7 @@ mod tests {
             key_tracker: Vec::new(),
             offset_tracker: HashMap::new(),
             registry_path: test_location.to_str().unwrap().to_string(),
+            log_recovery: false,
         };
         parse_registry_file(&mut output, &mut params, &options).unwrap();
     }
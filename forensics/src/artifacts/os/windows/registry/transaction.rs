@@ -0,0 +1,520 @@
+/**
+ * Windows `Registry` files have associated transaction logs (.LOG1 and .LOG2)
+ * Windows may not flush all changes to the primary `Registry` file right away. Instead they are written to the transaction logs first
+ * If the `Registry` file is dirty (primary and secondary sequence numbers do not match) the dirty pages in the logs can be replayed to recover the latest data
+ *
+ * Windows 8.1+ uses the new log format, which is a partial base block (512 bytes) followed by `HvLE` log entries
+ *
+ * References:
+ *  `https://github.com/msuhanov/regf/blob/master/Windows%20registry%20file%20format%20specification.md#new-format`
+ *  `https://github.com/libyal/libregf/blob/main/documentation/Windows%20NT%20Registry%20File%20(REGF)%20format.asciidoc`
+ */
+use super::{header::RegHeader, helper::read_registry};
+use crate::utils::nom_helper::{Endian, nom_unsigned_eight_bytes, nom_unsigned_four_bytes};
+use nom::bytes::complete::take;
+use tracing::{info, warn};
+
+#[derive(Debug)]
+pub(crate) struct LogEntry {
+    pub(crate) size: u32,
+    _flags: u32,
+    pub(crate) sequence_number: u32,
+    pub(crate) hive_bins_size: u32,
+    _dirty_pages_count: u32,
+    _hash1: u64,
+    _hash2: u64,
+    pub(crate) dirty_pages: Vec<DirtyPage>,
+}
+
+#[derive(Debug)]
+pub(crate) struct DirtyPage {
+    /// Offset relative to the start of the hive bins data
+    pub(crate) offset: u32,
+    pub(crate) data: Vec<u8>,
+}
+
+/// Read the sibling transaction logs for the `Registry` file at provided path and replay them onto the `Registry` bytes
+/// Returns true if any log entries were applied
+pub(crate) fn replay_transaction_logs(hive: &mut Vec<u8>, path: &str) -> bool {
+    let (_, header) = match RegHeader::parse_header(hive) {
+        Ok(result) => result,
+        Err(_err) => return false,
+    };
+    if !header.is_dirty {
+        return false;
+    }
+
+    let mut logs = Vec::new();
+    for extension in ["LOG1", "LOG2"] {
+        let log_path = format!("{path}.{extension}");
+        match read_registry(&log_path) {
+            Ok(result) if !result.is_empty() => logs.push(result),
+            _ => info!("No Registry transaction log at {log_path}"),
+        }
+    }
+
+    if logs.is_empty() {
+        warn!("Registry file {path} is dirty but no transaction logs were found");
+        return false;
+    }
+
+    let last_sequence = replay_logs(hive, &logs);
+    if let Some(sequence) = last_sequence {
+        info!("Replayed Registry transaction logs for {path} up to sequence number {sequence}");
+        return true;
+    }
+
+    false
+}
+
+/// Replay the dirty pages from the provided transaction logs onto the `Registry` bytes
+/// Returns the sequence number of the last log entry applied
+pub(crate) fn replay_logs(hive: &mut Vec<u8>, logs: &[Vec<u8>]) -> Option<u32> {
+    let (_, header) = RegHeader::parse_header(hive).ok()?;
+
+    let mut entries = Vec::new();
+    for log in logs {
+        if !valid_log_header(log) {
+            continue;
+        }
+        entries.append(&mut parse_log_entries(log));
+    }
+    // Log entries may be split across both .LOG1 and .LOG2. They must be applied in sequence order
+    entries.sort_by_key(|entry| entry.sequence_number);
+
+    // Dirty pages cannot grow the hive by more than the data in the logs
+    let max_size = hive.len() + logs.iter().map(Vec::len).sum::<usize>();
+    // The first entry to replay must continue from the secondary sequence number of the primary file
+    let mut expected_sequence = header.secondary_sequence_num;
+    let mut last_entry: Option<&LogEntry> = None;
+    for entry in &entries {
+        // Entries older than the primary file have already been flushed. Both logs can contain the same entry
+        if entry.sequence_number < expected_sequence {
+            continue;
+        }
+        if entry.sequence_number != expected_sequence {
+            warn!(
+                "Registry transaction log sequence gap. Expected {expected_sequence} got {}. Stopping replay",
+                entry.sequence_number
+            );
+            break;
+        }
+
+        if !apply_entry(hive, entry, max_size) {
+            break;
+        }
+        expected_sequence = entry.sequence_number.wrapping_add(1);
+        last_entry = Some(entry);
+    }
+
+    let entry = last_entry?;
+    update_header(
+        hive,
+        entry.sequence_number.wrapping_add(1),
+        entry.hive_bins_size,
+    );
+    Some(entry.sequence_number)
+}
+
+/// Check the partial base block of a transaction log
+fn valid_log_header(data: &[u8]) -> bool {
+    let signature = 0x66676572; // regf
+    let base_block_size = 512;
+    if data.len() < base_block_size {
+        return false;
+    }
+    let result: nom::IResult<&[u8], u32> = nom_unsigned_four_bytes(data, Endian::Le);
+    let sig = match result {
+        Ok((_, result)) => result,
+        Err(_err) => return false,
+    };
+    if sig != signature {
+        warn!("Registry transaction log has wrong signature: {sig}");
+        return false;
+    }
+
+    let checksum_offset = 508;
+    let checksum = checksum(&data[..checksum_offset]);
+    let stored = u32::from_le_bytes([
+        data[checksum_offset],
+        data[checksum_offset + 1],
+        data[checksum_offset + 2],
+        data[checksum_offset + 3],
+    ]);
+    if checksum != stored {
+        warn!("Registry transaction log has bad base block checksum");
+        return false;
+    }
+    true
+}
+
+/// Parse all valid `HvLE` entries in a transaction log. Parsing stops at the first invalid entry
+pub(crate) fn parse_log_entries(data: &[u8]) -> Vec<LogEntry> {
+    // Log entries start after the partial base block
+    let mut offset = 512;
+    let mut entries = Vec::new();
+    while offset < data.len() {
+        let entry = match LogEntry::parse_log_entry(&data[offset..]) {
+            Ok((_, result)) => result,
+            Err(_err) => break,
+        };
+        offset += entry.size as usize;
+        entries.push(entry);
+    }
+
+    entries
+}
+
+impl LogEntry {
+    /// Parse and validate a single `HvLE` log entry
+    pub(crate) fn parse_log_entry(data: &[u8]) -> nom::IResult<&[u8], LogEntry> {
+        let (input, signature) = nom_unsigned_four_bytes(data, Endian::Le)?;
+        let sig = 0x454c7648; // HvLE
+        if signature != sig {
+            return Err(nom::Err::Failure(nom::error::Error::new(
+                input,
+                nom::error::ErrorKind::Fail,
+            )));
+        }
+        let (input, size) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, flags) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, sequence_number) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, hive_bins_size) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, dirty_pages_count) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, hash1) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+        let (mut input, hash2) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+
+        let entry_alignment = 512;
+        let page_alignment = 4096;
+        let header_size = 40;
+        if size % entry_alignment != 0
+            || (size as usize) < header_size
+            || hive_bins_size % page_alignment != 0
+        {
+            warn!("Registry transaction log entry has invalid size: {size}");
+            return Err(nom::Err::Failure(nom::error::Error::new(
+                input,
+                nom::error::ErrorKind::Fail,
+            )));
+        }
+
+        let (remaining, entry_data) = take(size)(data)?;
+        let hash_header_size: usize = 32;
+        let seed = 0x82EF4D887A4E55C5;
+        if marvin32(&entry_data[..hash_header_size], seed) != hash2
+            || marvin32(&entry_data[header_size..], seed) != hash1
+        {
+            warn!("Registry transaction log entry {sequence_number} has invalid hashes");
+            return Err(nom::Err::Failure(nom::error::Error::new(
+                input,
+                nom::error::ErrorKind::Fail,
+            )));
+        }
+
+        // First get the offsets and sizes of all dirty pages. The page data follows them
+        let mut references = Vec::new();
+        for _ in 0..dirty_pages_count {
+            let (remaining, page_offset) = nom_unsigned_four_bytes(input, Endian::Le)?;
+            let (remaining, page_size) = nom_unsigned_four_bytes(remaining, Endian::Le)?;
+            input = remaining;
+            references.push((page_offset, page_size));
+        }
+
+        let mut dirty_pages = Vec::new();
+        for (page_offset, page_size) in references {
+            let (remaining, page_data) = take(page_size)(input)?;
+            input = remaining;
+            dirty_pages.push(DirtyPage {
+                offset: page_offset,
+                data: page_data.to_vec(),
+            });
+        }
+
+        let entry = LogEntry {
+            size,
+            _flags: flags,
+            sequence_number,
+            hive_bins_size,
+            _dirty_pages_count: dirty_pages_count,
+            _hash1: hash1,
+            _hash2: hash2,
+            dirty_pages,
+        };
+
+        Ok((remaining, entry))
+    }
+}
+
+/// Write the dirty pages of a log entry into the `Registry` bytes
+/// Returns false without changing the `Registry` bytes if the entry would grow the hive past `max_size` or has pages outside the hive bins
+fn apply_entry(hive: &mut Vec<u8>, entry: &LogEntry, max_size: usize) -> bool {
+    let base_block_size = 4096;
+    let hive_size = base_block_size + entry.hive_bins_size as usize;
+    if hive_size > max_size {
+        warn!(
+            "Registry transaction log entry {} has hive bins size {} larger than the hive and logs. Stopping replay",
+            entry.sequence_number, entry.hive_bins_size
+        );
+        return false;
+    }
+    if entry
+        .dirty_pages
+        .iter()
+        .any(|page| base_block_size + page.offset as usize + page.data.len() > hive_size)
+    {
+        warn!(
+            "Registry transaction log entry {} has a dirty page outside the hive bins. Stopping replay",
+            entry.sequence_number
+        );
+        return false;
+    }
+
+    if hive.len() < hive_size {
+        hive.resize(hive_size, 0);
+    }
+    for page in &entry.dirty_pages {
+        let start = base_block_size + page.offset as usize;
+        let end = start + page.data.len();
+        hive[start..end].copy_from_slice(&page.data);
+    }
+    true
+}
+
+/// Mark the `Registry` file as clean after replaying the logs
+fn update_header(hive: &mut [u8], sequence_number: u32, hive_bins_size: u32) {
+    let primary_offset = 4;
+    let secondary_offset = 8;
+    let bins_size_offset = 40;
+    let checksum_offset = 508;
+
+    hive[primary_offset..primary_offset + 4].copy_from_slice(&sequence_number.to_le_bytes());
+    hive[secondary_offset..secondary_offset + 4].copy_from_slice(&sequence_number.to_le_bytes());
+    hive[bins_size_offset..bins_size_offset + 4].copy_from_slice(&hive_bins_size.to_le_bytes());
+
+    let value = checksum(&hive[..checksum_offset]);
+    hive[checksum_offset..checksum_offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+/// XOR checksum of the base block
+fn checksum(data: &[u8]) -> u32 {
+    let mut value = 0;
+    for chunk in data.chunks_exact(4) {
+        value ^= u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    value
+}
+
+/// Marvin32 hash used to validate log entries
+pub(crate) fn marvin32(data: &[u8], seed: u64) -> u64 {
+    let mut low = seed as u32;
+    let mut high = (seed >> 32) as u32;
+
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        low = low.wrapping_add(u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]));
+        marvin_block(&mut low, &mut high);
+    }
+
+    let remaining = chunks.remainder();
+    let last = match remaining.len() {
+        1 => 0x8000 | remaining[0] as u32,
+        2 => 0x800000 | u16::from_le_bytes([remaining[0], remaining[1]]) as u32,
+        3 => {
+            0x80000000
+                | (remaining[2] as u32) << 16
+                | u16::from_le_bytes([remaining[0], remaining[1]]) as u32
+        }
+        _ => 0x80,
+    };
+
+    low = low.wrapping_add(last);
+    marvin_block(&mut low, &mut high);
+    marvin_block(&mut low, &mut high);
+
+    ((high as u64) << 32) | low as u64
+}
+
+fn marvin_block(low: &mut u32, high: &mut u32) {
+    *high ^= *low;
+    *low = low.rotate_left(20);
+    *low = low.wrapping_add(*high);
+    *high = high.rotate_left(9);
+    *high ^= *low;
+    *low = low.rotate_left(27);
+    *low = low.wrapping_add(*high);
+    *high = high.rotate_left(19);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DirtyPage, LogEntry, apply_entry, marvin32, parse_log_entries, replay_logs,
+        replay_transaction_logs,
+    };
+    use crate::{
+        artifacts::os::windows::registry::{header::RegHeader, helper::get_registry_keys},
+        filesystem::files::read_file,
+    };
+    use regex::Regex;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_marvin32() {
+        let seed = 0x004FB61A001BDBCC;
+        assert_eq!(marvin32(&[], seed), 0x30ED35C100CD3C7D);
+        assert_eq!(marvin32(&[0xaf], seed), 0x48E73FC77D75DDC1);
+        assert_eq!(marvin32(&[0xe7, 0x0f], seed), 0xB5F6E1FC485DBFF8);
+        assert_eq!(marvin32(&[0x37, 0xf4, 0x95], seed), 0xF0B07C789B8CF7E8);
+        assert_eq!(
+            marvin32(&[0x86, 0x42, 0xdc, 0x59], seed),
+            0x7008F2E87E9CF556
+        );
+    }
+
+    #[test]
+    fn test_parse_log_entries() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/registry/win10/dirty/NTUSER.DAT.LOG1");
+        let buffer = read_file(&test_location.display().to_string()).unwrap();
+
+        let entries = parse_log_entries(&buffer);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].sequence_number, 20);
+        assert_eq!(entries[0].hive_bins_size, 196608);
+        assert_eq!(entries[0].dirty_pages.len(), 1);
+        assert_eq!(entries[1].sequence_number, 21);
+    }
+
+    #[test]
+    fn test_parse_log_entry_bad_hash() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/registry/win10/dirty/NTUSER.DAT.LOG1");
+        let mut buffer = read_file(&test_location.display().to_string()).unwrap();
+        // Corrupt the first dirty page
+        buffer[512 + 60] ^= 0xff;
+
+        assert!(LogEntry::parse_log_entry(&buffer[512..]).is_err());
+    }
+
+    #[test]
+    fn test_replay_logs() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/registry/win10/dirty/NTUSER.DAT");
+        let mut hive = read_file(&test_location.display().to_string()).unwrap();
+        test_location.set_extension("DAT.LOG1");
+        let log = read_file(&test_location.display().to_string()).unwrap();
+
+        let (_, header) = RegHeader::parse_header(&hive).unwrap();
+        assert!(header.is_dirty);
+
+        let sequence = replay_logs(&mut hive, &[log]).unwrap();
+        assert_eq!(sequence, 21);
+
+        let (_, header) = RegHeader::parse_header(&hive).unwrap();
+        assert!(!header.is_dirty);
+        assert!(header.valid_checksum);
+        assert_eq!(header.primary_sequence_num, 22);
+    }
+
+    #[test]
+    fn test_replay_logs_sequence_gap() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/registry/win10/dirty/NTUSER.DAT");
+        let mut hive = read_file(&test_location.display().to_string()).unwrap();
+        test_location.set_extension("DAT.LOG1");
+        let log = read_file(&test_location.display().to_string()).unwrap();
+
+        // Primary file expects an entry that is missing from the log
+        let (_, header) = RegHeader::parse_header(&hive).unwrap();
+        let secondary = header.secondary_sequence_num - 1;
+        hive[8..12].copy_from_slice(&secondary.to_le_bytes());
+
+        assert!(replay_logs(&mut hive, &[log]).is_none());
+    }
+
+    #[test]
+    fn test_apply_entry_too_large() {
+        let mut hive = vec![0; 8192];
+        let entry = LogEntry {
+            size: 512,
+            _flags: 0,
+            sequence_number: 1,
+            hive_bins_size: 0xfffff000,
+            _dirty_pages_count: 1,
+            _hash1: 0,
+            _hash2: 0,
+            dirty_pages: vec![DirtyPage {
+                offset: 0xffffe000,
+                data: vec![1; 4096],
+            }],
+        };
+        assert!(!apply_entry(&mut hive, &entry, 16384));
+        assert_eq!(hive.len(), 8192);
+
+        let entry = LogEntry {
+            hive_bins_size: 4096,
+            ..entry
+        };
+        assert!(!apply_entry(&mut hive, &entry, 16384));
+        assert_eq!(hive.len(), 8192);
+    }
+
+    #[test]
+    fn test_replay_transaction_logs() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/registry/win10/dirty/NTUSER.DAT");
+        let path = test_location.display().to_string();
+        let mut hive = read_file(&path).unwrap();
+
+        assert!(replay_transaction_logs(&mut hive, &path));
+    }
+
+    #[test]
+    fn test_replay_transaction_logs_clean() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/registry/win10/NTUSER.DAT");
+        let path = test_location.display().to_string();
+        let mut hive = read_file(&path).unwrap();
+
+        assert!(!replay_transaction_logs(&mut hive, &path));
+    }
+
+    #[test]
+    fn test_get_registry_keys_dirty() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/registry/win10/dirty/NTUSER.DAT");
+        let start_path = "ROOT\\SOFTWARE\\Microsoft\\";
+        let regex = Regex::new(r".*\\typedurls").unwrap();
+        let result =
+            get_registry_keys(start_path, &regex, &test_location.display().to_string()).unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].name, "TypedURLs");
+        assert!(result[0].log_recovery);
+        assert_eq!(
+            result[0].values[0].data,
+            "https://artemis.example/recovered/?LinkId=00001"
+        );
+    }
+
+    #[test]
+    fn test_get_registry_keys_dirty_zip() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/registry/win10/dirty.zip");
+        let start_path = "ROOT\\SOFTWARE\\Microsoft\\";
+        let regex = Regex::new(r".*\\typedurls").unwrap();
+        let result = get_registry_keys(
+            start_path,
+            &regex,
+            &format!("zip:{}!dirty/NTUSER.DAT", test_location.display()),
+        )
+        .unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert!(result[0].log_recovery);
+        assert_eq!(
+            result[0].values[0].data,
+            "https://artemis.example/recovered/?LinkId=00001"
+        );
+    }
+}
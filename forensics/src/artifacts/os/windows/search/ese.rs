@@ -6,7 +6,10 @@ use super::{
 use crate::{
     artifacts::os::windows::ese::{
         catalog::Catalog,
-        helper::{get_all_pages, get_catalog_info, get_filtered_page_data, get_page_data},
+        helper::{
+            get_all_pages, get_catalog_info, get_deleted_page_data, get_filtered_page_data,
+            get_log_generation, get_page_data,
+        },
         tables::{TableInfo, table_info},
     },
     output::manager::OutputManager,
@@ -24,6 +27,12 @@ pub(crate) struct SearchEntry {
     pub(crate) last_modified: String,
     pub(crate) properties: HashMap<String, String>,
     pub(crate) evidence: String,
+    /// Transaction logs were replayed onto the dirty database before parsing
+    pub(crate) log_replay: bool,
+    /// Last transaction log generation replayed
+    pub(crate) log_generation: u32,
+    /// Entry was recovered from deleted rows
+    pub(crate) recovered: bool,
 }
 
 /// Parse the Windows `Search` ESE database
@@ -33,6 +42,7 @@ pub(crate) fn parse_search(
     options: &SearchOptions,
 ) -> Result<(), SearchError> {
     let catalog = search_catalog(path)?;
+    let log_generation = search_log_generation(path);
 
     let mut gather_table = table_info(&catalog, "SystemIndex_Gthr");
     let gather_pages = search_pages(gather_table.table_page as u32, path)?;
@@ -43,12 +53,12 @@ pub(crate) fn parse_search(
     let page_limit = 400;
     let mut gather_chunk = Vec::new();
     let last_page = 0;
-    for gather_page in gather_pages {
-        if gather_page == last_page {
+    for gather_page in &gather_pages {
+        if *gather_page == last_page {
             continue;
         }
 
-        gather_chunk.push(gather_page);
+        gather_chunk.push(*gather_page);
         if gather_chunk.len() != page_limit {
             continue;
         }
@@ -69,7 +79,15 @@ pub(crate) fn parse_search(
         let property_rows =
             get_properties(path, &property_pages, &mut property_table, &mut doc_ids);
 
-        let _ = process_search(&property_rows, &gather_rows, manager, options, path);
+        let _ = process_search(
+            &property_rows,
+            &gather_rows,
+            manager,
+            options,
+            path,
+            log_generation,
+            false,
+        );
         gather_chunk = Vec::new();
     }
 
@@ -90,9 +108,63 @@ pub(crate) fn parse_search(
         let property_rows =
             get_properties(path, &property_pages, &mut property_table, &mut doc_ids);
 
-        let _ = process_search(&property_rows, &gather_rows, manager, options, path);
+        let _ = process_search(
+            &property_rows,
+            &gather_rows,
+            manager,
+            options,
+            path,
+            log_generation,
+            false,
+        );
+    }
+
+    if !options.recover_deleted.unwrap_or(false) {
+        return Ok(());
     }
 
+    let gather_rows =
+        match get_deleted_page_data(path, &gather_pages, &mut gather_table, "SystemIndex_Gthr") {
+            Ok(result) => result,
+            Err(err) => {
+                error!("Failed to recover deleted SystemIndex_Gthr rows at {path}: {err:?}");
+                return Ok(());
+            }
+        };
+    let mut doc_ids = get_document_ids(gather_rows.get("SystemIndex_Gthr").unwrap_or(&Vec::new()));
+
+    // Properties of deleted entries may be deleted rows too
+    let mut property_rows =
+        get_properties(path, &property_pages, &mut property_table, &mut doc_ids);
+    match get_deleted_page_data(
+        path,
+        &property_pages,
+        &mut property_table,
+        "SystemIndex_PropertyStore",
+    ) {
+        Ok(mut result) => property_rows
+            .entry(String::from("SystemIndex_PropertyStore"))
+            .or_default()
+            .append(
+                result
+                    .get_mut("SystemIndex_PropertyStore")
+                    .unwrap_or(&mut Vec::new()),
+            ),
+        Err(err) => {
+            error!("Failed to recover deleted SystemIndex_PropertyStore rows at {path}: {err:?}");
+        }
+    }
+
+    let _ = process_search(
+        &property_rows,
+        &gather_rows,
+        manager,
+        options,
+        path,
+        log_generation,
+        true,
+    );
+
     Ok(())
 }
 
@@ -205,6 +277,8 @@ fn process_search(
     manager: &mut OutputManager,
     options: &SearchOptions,
     evidence: &str,
+    log_generation: Option<u32>,
+    recovered: bool,
 ) -> Result<(), SearchError> {
     let indexes = if let Some(values) = properties.get("SystemIndex_PropertyStore") {
         values
@@ -222,7 +296,15 @@ fn process_search(
         warn!("Could not get table SystemIndex_Gthr from ESE results. Something went very wrong");
         return Err(SearchError::ParseEse);
     };
-    let _ = parse_index_gthr(entries, &props, manager, options, evidence);
+    let _ = parse_index_gthr(
+        entries,
+        &props,
+        manager,
+        options,
+        evidence,
+        log_generation,
+        recovered,
+    );
 
     Ok(())
 }
@@ -241,6 +323,17 @@ pub(crate) fn search_catalog(path: &str) -> Result<Vec<Catalog>, SearchError> {
     Ok(catalog)
 }
 
+/// Get the last transaction log generation replayed onto a dirty Search database
+fn search_log_generation(path: &str) -> Option<u32> {
+    match get_log_generation(path) {
+        Ok(result) => result,
+        Err(err) => {
+            warn!("Could not check {path} transaction logs: {err:?}");
+            None
+        }
+    }
+}
+
 /// Get all pages for the provided table
 pub(crate) fn search_pages(table_page: u32, path: &str) -> Result<Vec<u32>, SearchError> {
     let pages_result = get_all_pages(path, table_page);
@@ -261,6 +354,7 @@ pub(crate) fn parse_search_path(
     page_limit: u32,
 ) -> Result<Vec<SearchEntry>, SearchError> {
     let catalog = search_catalog(path)?;
+    let log_generation = search_log_generation(path);
 
     let mut gather_table = table_info(&catalog, "SystemIndex_Gthr");
     let gather_pages = search_pages(gather_table.table_page as u32, path)?;
@@ -318,7 +412,7 @@ pub(crate) fn parse_search_path(
             return Err(SearchError::ParseEse);
         };
 
-        let _ = parse_index_gthr_path(entries, &props, &mut search_entries, path);
+        let _ = parse_index_gthr_path(entries, &props, &mut search_entries, path, log_generation);
     }
 
     if !gather_chunk.is_empty() {
@@ -357,7 +451,7 @@ pub(crate) fn parse_search_path(
             return Err(SearchError::ParseEse);
         };
 
-        let _ = parse_index_gthr_path(entries, &props, &mut search_entries, path);
+        let _ = parse_index_gthr_path(entries, &props, &mut search_entries, path, log_generation);
     }
 
     Ok(search_entries)
@@ -401,7 +495,10 @@ mod tests {
             return;
         }
         let mut output = output_options("search_temp", "./tmp", false);
-        let options = SearchOptions { alt_file: None };
+        let options = SearchOptions {
+            alt_file: None,
+            recover_deleted: None,
+        };
 
         parse_search(test_path, &mut output, &options).unwrap();
     }
@@ -541,7 +638,10 @@ mod tests {
         let last_page = 0;
 
         let mut output = output_options("search_temp", "./tmp", false);
-        let options = SearchOptions { alt_file: None };
+        let options = SearchOptions {
+            alt_file: None,
+            recover_deleted: None,
+        };
 
         for gather_page in gather_pages {
             if gather_page == last_page {
@@ -566,8 +666,16 @@ mod tests {
             let property_rows =
                 get_properties(path, &property_pages, &mut property_table, &mut doc_ids);
 
-            let _ =
-                process_search(&property_rows, &gather_rows, &mut output, &options, path).unwrap();
+            let _ = process_search(
+                &property_rows,
+                &gather_rows,
+                &mut output,
+                &options,
+                path,
+                None,
+                false,
+            )
+            .unwrap();
             break;
         }
     }
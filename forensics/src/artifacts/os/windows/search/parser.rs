@@ -117,7 +117,10 @@ mod tests {
     #[test]
     fn test_grab_search() {
         let mut output = output_options("search_temp", "./tmp", false);
-        let options = SearchOptions { alt_file: None };
+        let options = SearchOptions {
+            alt_file: None,
+            recover_deleted: None,
+        };
 
         let _ = grab_search(&options, &mut output);
     }
@@ -62,6 +62,9 @@ pub(crate) fn parse_search_sqlite(
                 last_modified: String::from("1970-01-01T00:00:00.000Z"),
                 properties: HashMap::new(),
                 evidence: path.to_string(),
+                log_replay: false,
+                log_generation: 0,
+                recovered: false,
             };
             // Go through each row, while the entry.document_id and sql_entry.document_id are the same each row is a property value.
             // Once the doucment_id is different we have arrived at the next entry
@@ -177,6 +180,9 @@ pub(crate) fn parse_search_sqlite_path(path: &str) -> Result<Vec<SearchEntry>, S
                 last_modified: String::new(),
                 properties: HashMap::new(),
                 evidence: path.to_string(),
+                log_replay: false,
+                log_generation: 0,
+                recovered: false,
             };
             // Go through each row, while the entry.document_id and sql_entry.document_id are the same each row is a property.
             // Once the doucment_id is different we have arrived at the next entry
@@ -237,7 +243,10 @@ mod tests {
     fn test_parse_search_sqlite() {
         let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
         test_location.push("tests/test_data/windows/search/win11/Windows.db");
-        let options = SearchOptions { alt_file: None };
+        let options = SearchOptions {
+            alt_file: None,
+            recover_deleted: None,
+        };
 
         let mut output = output_options("search_temp", "./tmp", false);
 
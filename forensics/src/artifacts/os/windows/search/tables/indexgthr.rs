@@ -19,6 +19,8 @@ pub(crate) fn parse_index_gthr(
     manager: &mut OutputManager,
     options: &SearchOptions,
     evidence: &str,
+    log_generation: Option<u32>,
+    recovered: bool,
 ) -> Result<(), SearchError> {
     let mut entries = Vec::new();
     let limit = 100000;
@@ -30,6 +32,9 @@ pub(crate) fn parse_index_gthr(
             last_modified: String::from("1970-01-01T00:00:00.000Z"),
             properties: HashMap::new(),
             evidence: evidence.to_string(),
+            log_replay: log_generation.is_some(),
+            log_generation: log_generation.unwrap_or_default(),
+            recovered,
         };
 
         for column in rows {
@@ -110,6 +115,7 @@ pub(crate) fn parse_index_gthr_path(
     lookups: &HashMap<String, HashMap<String, String>>,
     entries: &mut Vec<SearchEntry>,
     evidence: &str,
+    log_generation: Option<u32>,
 ) -> Result<(), SearchError> {
     for rows in column_rows {
         let mut entry = SearchEntry {
@@ -118,6 +124,9 @@ pub(crate) fn parse_index_gthr_path(
             last_modified: String::new(),
             properties: HashMap::new(),
             evidence: evidence.to_string(),
+            log_replay: log_generation.is_some(),
+            log_generation: log_generation.unwrap_or_default(),
+            recovered: false,
         };
 
         for column in rows {
@@ -204,7 +213,10 @@ mod tests {
         let page_limit = 5;
         let mut gather_chunk = Vec::new();
         let last_page = 0;
-        let options = SearchOptions { alt_file: None };
+        let options = SearchOptions {
+            alt_file: None,
+            recover_deleted: None,
+        };
 
         for gather_page in gather_pages {
             if gather_page == last_page {
@@ -230,6 +242,8 @@ mod tests {
                 &mut output,
                 &options,
                 test_path,
+                None,
+                false,
             )
             .unwrap();
             break;
@@ -278,6 +292,7 @@ mod tests {
                 &HashMap::new(),
                 &mut entries,
                 test_path,
+                None,
             )
             .unwrap();
             assert!(entries.len() > 20);
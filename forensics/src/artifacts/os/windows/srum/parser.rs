@@ -91,7 +91,10 @@ mod tests {
 
     #[test]
     fn test_grab_srum() {
-        let options = SrumOptions { alt_file: None };
+        let options = SrumOptions {
+            alt_file: None,
+            recover_deleted: None,
+        };
         let mut output = output_options("srum_test", "./tmp", false);
 
         grab_srum(&options, &mut output).unwrap();
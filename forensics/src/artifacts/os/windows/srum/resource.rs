@@ -10,19 +10,23 @@ use super::{
 use crate::{
     artifacts::os::windows::{
         ese::{
-            helper::{get_all_pages, get_catalog_info, get_page_data},
+            helper::{
+                get_all_pages, get_catalog_info, get_deleted_page_data, get_log_generation,
+                get_page_data,
+            },
             tables::table_info,
         },
         srum::tables::index::parse_id_lookup,
     },
     output::{
         manager::OutputManager,
-        record::{Record, RecordStream},
+        record::{Record, RecordStream, VecRecordStream},
     },
     structs::artifacts::os::windows::SrumOptions,
 };
 use common::windows::TableDump;
 use serde_json::Value;
+use std::collections::HashMap;
 use tracing::{error, warn};
 
 /// Parse and dump the provided SRUM tables
@@ -33,6 +37,7 @@ pub(crate) fn parse_srum(
 ) -> Result<(), SrumError> {
     let indexes = get_srum_ese(path, "SruDbIdMapTable")?;
     let lookups = parse_id_lookup(&indexes);
+    let log_generation = srum_log_generation(path);
 
     let tables = vec![
         "{5C8CF1C7-7257-4F13-B223-970EF5939312}",
@@ -48,36 +53,23 @@ pub(crate) fn parse_srum(
 
     for table in tables {
         let srum_data = get_srum_ese(path, table)?;
-
-        let mut records = match table {
-            "{5C8CF1C7-7257-4F13-B223-970EF5939312}" => {
-                parse_app_timeline(&srum_data, &lookups, path)?
-            }
-            "{973F5D5C-1D90-4944-BE8E-24B94231A174}" => parse_network(&srum_data, &lookups, path)?,
-            "{DD6636C4-8929-4683-974E-22C046A43763}" => {
-                parse_network_connectivity(&srum_data, &lookups, path)?
-            }
-            "{D10CA2FE-6FCF-4F6D-848E-B2E99266FA86}" => {
-                parse_notification(&srum_data, &lookups, path)?
-            }
-            "{D10CA2FE-6FCF-4F6D-848E-B2E99266FA89}" => {
-                parse_application(&srum_data, &lookups, path)?
-            }
-            "{DA73FB89-2BEA-4DDC-86B8-6E048C6DA477}" => parse_energy(&srum_data, &lookups, path)?,
-            "{FEE4E14F-02A9-4550-B5CE-5FA2DA202E37}"
-            | "{FEE4E14F-02A9-4550-B5CE-5FA2DA202E37}LT" => {
-                parse_energy_usage(&srum_data, &lookups, path)?
-            }
-            "{7ACBBAA3-D029-4BE4-9A7A-0885927F1D8F}" => {
-                parse_vfu_provider(&srum_data, &lookups, path)?
-            }
-            _ => continue,
-        };
+        let mut records =
+            parse_srum_table(table, &srum_data, &lookups, path, log_generation, false)?;
 
         let artifact_name = "srum";
         if let Err(err) = manager.write_artifact(artifact_name, options, &mut records) {
             error!("Could not output srum {table} data: {err:?}");
         }
+
+        if !options.recover_deleted.unwrap_or(false) {
+            continue;
+        }
+        let deleted_data = get_srum_deleted(path, table)?;
+        let mut records =
+            parse_srum_table(table, &deleted_data, &lookups, path, log_generation, true)?;
+        if let Err(err) = manager.write_artifact(artifact_name, options, &mut records) {
+            error!("Could not output srum {table} deleted data: {err:?}");
+        }
     }
 
     Ok(())
@@ -87,25 +79,10 @@ pub(crate) fn parse_srum(
 pub(crate) fn get_srum(path: &str, table: &str) -> Result<Value, SrumError> {
     let indexes = get_srum_ese(path, "SruDbIdMapTable")?;
     let lookups = parse_id_lookup(&indexes);
+    let log_generation = srum_log_generation(path);
     let srum_data = get_srum_ese(path, table)?;
 
-    let mut srum_data = match table {
-        "{5C8CF1C7-7257-4F13-B223-970EF5939312}" => parse_app_timeline(&srum_data, &lookups, path)?,
-        "{973F5D5C-1D90-4944-BE8E-24B94231A174}" => parse_network(&srum_data, &lookups, path)?,
-        "{DD6636C4-8929-4683-974E-22C046A43763}" => {
-            parse_network_connectivity(&srum_data, &lookups, path)?
-        }
-        "{D10CA2FE-6FCF-4F6D-848E-B2E99266FA86}" => parse_notification(&srum_data, &lookups, path)?,
-        "{D10CA2FE-6FCF-4F6D-848E-B2E99266FA89}" => parse_application(&srum_data, &lookups, path)?,
-        "{DA73FB89-2BEA-4DDC-86B8-6E048C6DA477}" => parse_energy(&srum_data, &lookups, path)?,
-        "{FEE4E14F-02A9-4550-B5CE-5FA2DA202E37}" | "{FEE4E14F-02A9-4550-B5CE-5FA2DA202E37}LT" => {
-            parse_energy_usage(&srum_data, &lookups, path)?
-        }
-        "{7ACBBAA3-D029-4BE4-9A7A-0885927F1D8F}" => parse_vfu_provider(&srum_data, &lookups, path)?,
-        _ => {
-            return Err(SrumError::NoTable);
-        }
-    };
+    let mut srum_data = parse_srum_table(table, &srum_data, &lookups, path, log_generation, false)?;
 
     let mut serde_data = Value::Array(Vec::new());
     while let Ok(Some(entries)) = srum_data.next_record() {
@@ -119,8 +96,67 @@ pub(crate) fn get_srum(path: &str, table: &str) -> Result<Value, SrumError> {
     Ok(serde_data)
 }
 
+/// Parse the rows of a SRUM table
+fn parse_srum_table(
+    table: &str,
+    srum_data: &[Vec<TableDump>],
+    lookups: &HashMap<String, String>,
+    path: &str,
+    log_generation: Option<u32>,
+    recovered: bool,
+) -> Result<VecRecordStream, SrumError> {
+    match table {
+        "{5C8CF1C7-7257-4F13-B223-970EF5939312}" => {
+            parse_app_timeline(srum_data, lookups, path, log_generation, recovered)
+        }
+        "{973F5D5C-1D90-4944-BE8E-24B94231A174}" => {
+            parse_network(srum_data, lookups, path, log_generation, recovered)
+        }
+        "{DD6636C4-8929-4683-974E-22C046A43763}" => {
+            parse_network_connectivity(srum_data, lookups, path, log_generation, recovered)
+        }
+        "{D10CA2FE-6FCF-4F6D-848E-B2E99266FA86}" => {
+            parse_notification(srum_data, lookups, path, log_generation, recovered)
+        }
+        "{D10CA2FE-6FCF-4F6D-848E-B2E99266FA89}" => {
+            parse_application(srum_data, lookups, path, log_generation, recovered)
+        }
+        "{DA73FB89-2BEA-4DDC-86B8-6E048C6DA477}" => {
+            parse_energy(srum_data, lookups, path, log_generation, recovered)
+        }
+        "{FEE4E14F-02A9-4550-B5CE-5FA2DA202E37}" | "{FEE4E14F-02A9-4550-B5CE-5FA2DA202E37}LT" => {
+            parse_energy_usage(srum_data, lookups, path, log_generation, recovered)
+        }
+        "{7ACBBAA3-D029-4BE4-9A7A-0885927F1D8F}" => {
+            parse_vfu_provider(srum_data, lookups, path, log_generation, recovered)
+        }
+        _ => Err(SrumError::NoTable),
+    }
+}
+
+/// Get the last transaction log generation replayed onto a dirty SRUM database
+fn srum_log_generation(path: &str) -> Option<u32> {
+    match get_log_generation(path) {
+        Ok(result) => result,
+        Err(err) => {
+            warn!("Could not check {path} transaction logs: {err:?}");
+            None
+        }
+    }
+}
+
 /// Extract SRUM info from ESE database
 pub(crate) fn get_srum_ese(path: &str, table: &str) -> Result<Vec<Vec<TableDump>>, SrumError> {
+    srum_rows(path, table, false)
+}
+
+/// Extract deleted SRUM rows from ESE database
+fn get_srum_deleted(path: &str, table: &str) -> Result<Vec<Vec<TableDump>>, SrumError> {
+    srum_rows(path, table, true)
+}
+
+/// Get the live or deleted rows of a SRUM table
+fn srum_rows(path: &str, table: &str, deleted: bool) -> Result<Vec<Vec<TableDump>>, SrumError> {
     let catalog_result = get_catalog_info(path);
     let catalog = match catalog_result {
         Ok(result) => result,
@@ -144,7 +180,11 @@ pub(crate) fn get_srum_ese(path: &str, table: &str) -> Result<Vec<Vec<TableDump>
         }
     };
 
-    let rows_results = get_page_data(path, &pages, &mut info, table);
+    let rows_results = if deleted {
+        get_deleted_page_data(path, &pages, &mut info, table)
+    } else {
+        get_page_data(path, &pages, &mut info, table)
+    };
     let table_rows = match rows_results {
         Ok(result) => result,
         Err(err) => {
@@ -181,7 +221,10 @@ mod tests {
     fn test_parse_srum() {
         let test_path = "C:\\Windows\\System32\\sru\\SRUDB.dat";
         let mut output = output_options("srum_temp", "./tmp", true);
-        let options = SrumOptions { alt_file: None };
+        let options = SrumOptions {
+            alt_file: None,
+            recover_deleted: None,
+        };
 
         parse_srum(test_path, &mut output, &options).unwrap();
     }
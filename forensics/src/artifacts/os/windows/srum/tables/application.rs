@@ -12,6 +12,8 @@ pub(crate) fn parse_application(
     column_rows: &[Vec<TableDump>],
     lookups: &HashMap<String, String>,
     evidence: &str,
+    log_generation: Option<u32>,
+    recovered: bool,
 ) -> Result<VecRecordStream, SrumError> {
     let mut app_vec: Vec<ApplicationInfo> = Vec::new();
     for rows in column_rows {
@@ -36,6 +38,9 @@ pub(crate) fn parse_application(
             background_num_write_operations: 0,
             background_number_of_flushes: 0,
             evidence: evidence.to_string(),
+            log_replay: log_generation.is_some(),
+            log_generation: log_generation.unwrap_or_default(),
+            recovered,
         };
 
         for column in rows {
@@ -140,6 +145,8 @@ pub(crate) fn parse_app_timeline(
     column_rows: &[Vec<TableDump>],
     lookups: &HashMap<String, String>,
     evidence: &str,
+    log_generation: Option<u32>,
+    recovered: bool,
 ) -> Result<VecRecordStream, SrumError> {
     let mut app_timeline: Vec<AppTimelineInfo> = Vec::new();
     for rows in column_rows {
@@ -189,6 +196,9 @@ pub(crate) fn parse_app_timeline(
             keyboard_input_s: 0,
             mouse_input_s: 0,
             evidence: evidence.to_string(),
+            log_replay: log_generation.is_some(),
+            log_generation: log_generation.unwrap_or_default(),
+            recovered,
         };
 
         let null_values = ["3038287259199220266", "707406378"];
@@ -369,6 +379,8 @@ pub(crate) fn parse_vfu_provider(
     column_rows: &[Vec<TableDump>],
     lookups: &HashMap<String, String>,
     evidence: &str,
+    log_generation: Option<u32>,
+    recovered: bool,
 ) -> Result<VecRecordStream, SrumError> {
     let mut app_vec: Vec<AppVfu> = Vec::new();
     for rows in column_rows {
@@ -382,6 +394,9 @@ pub(crate) fn parse_vfu_provider(
             end_time: String::new(),
             usage: String::new(),
             evidence: evidence.to_string(),
+            log_replay: log_generation.is_some(),
+            log_generation: log_generation.unwrap_or_default(),
+            recovered,
         };
 
         for column in rows {
@@ -452,7 +467,7 @@ mod tests {
         let lookups = parse_id_lookup(&indexes);
         let srum_data = get_srum_ese(test_path, "{5C8CF1C7-7257-4F13-B223-970EF5939312}").unwrap();
 
-        let mut results = parse_app_timeline(&srum_data, &lookups, test_path).unwrap();
+        let mut results = parse_app_timeline(&srum_data, &lookups, test_path, None, false).unwrap();
         assert!(results.next_record().is_ok())
     }
 
@@ -464,7 +479,7 @@ mod tests {
         let lookups = parse_id_lookup(&indexes);
         let srum_data = get_srum_ese(test_path, "{D10CA2FE-6FCF-4F6D-848E-B2E99266FA89}").unwrap();
 
-        let mut results = parse_application(&srum_data, &lookups, test_path).unwrap();
+        let mut results = parse_application(&srum_data, &lookups, test_path, None, false).unwrap();
         assert!(results.next_record().is_ok())
     }
 
@@ -476,7 +491,7 @@ mod tests {
         let lookups = parse_id_lookup(&indexes);
         let srum_data = get_srum_ese(test_path, "{7ACBBAA3-D029-4BE4-9A7A-0885927F1D8F}").unwrap();
 
-        let mut results = parse_vfu_provider(&srum_data, &lookups, test_path).unwrap();
+        let mut results = parse_vfu_provider(&srum_data, &lookups, test_path, None, false).unwrap();
         assert!(results.next_record().is_ok())
     }
 }
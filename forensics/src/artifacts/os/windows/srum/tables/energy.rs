@@ -12,6 +12,8 @@ pub(crate) fn parse_energy(
     column_rows: &[Vec<TableDump>],
     lookups: &HashMap<String, String>,
     evidence: &str,
+    log_generation: Option<u32>,
+    recovered: bool,
 ) -> Result<VecRecordStream, SrumError> {
     let mut energy_vec: Vec<EnergyInfo> = Vec::new();
     for rows in column_rows {
@@ -22,6 +24,9 @@ pub(crate) fn parse_energy(
             user_id: String::new(),
             binary_data: String::new(),
             evidence: evidence.to_string(),
+            log_replay: log_generation.is_some(),
+            log_generation: log_generation.unwrap_or_default(),
+            recovered,
         };
 
         for column in rows {
@@ -69,6 +74,8 @@ pub(crate) fn parse_energy_usage(
     column_rows: &[Vec<TableDump>],
     lookups: &HashMap<String, String>,
     evidence: &str,
+    log_generation: Option<u32>,
+    recovered: bool,
 ) -> Result<VecRecordStream, SrumError> {
     let mut energy_vec: Vec<EnergyUsage> = Vec::new();
     for rows in column_rows {
@@ -85,6 +92,9 @@ pub(crate) fn parse_energy_usage(
             cycle_count: 0,
             configuration_hash: 0,
             evidence: evidence.to_string(),
+            log_replay: log_generation.is_some(),
+            log_generation: log_generation.unwrap_or_default(),
+            recovered,
         };
 
         for column in rows {
@@ -162,7 +172,7 @@ mod tests {
             return;
         }
 
-        parse_energy(&energy_check.unwrap(), &lookups, test_path).unwrap();
+        parse_energy(&energy_check.unwrap(), &lookups, test_path, None, false).unwrap();
     }
 
     #[test]
@@ -173,7 +183,7 @@ mod tests {
         let lookups = parse_id_lookup(&indexes);
         let srum_data = get_srum_ese(test_path, "{FEE4E14F-02A9-4550-B5CE-5FA2DA202E37}").unwrap();
 
-        parse_energy_usage(&srum_data, &lookups, test_path).unwrap();
+        parse_energy_usage(&srum_data, &lookups, test_path, None, false).unwrap();
     }
 
     #[test]
@@ -185,6 +195,6 @@ mod tests {
         let srum_data =
             get_srum_ese(test_path, "{FEE4E14F-02A9-4550-B5CE-5FA2DA202E37}LT").unwrap();
 
-        parse_energy_usage(&srum_data, &lookups, test_path).unwrap();
+        parse_energy_usage(&srum_data, &lookups, test_path, None, false).unwrap();
     }
 }
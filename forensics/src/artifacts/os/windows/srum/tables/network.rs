@@ -12,6 +12,8 @@ pub(crate) fn parse_network(
     column_rows: &[Vec<TableDump>],
     lookups: &HashMap<String, String>,
     evidence: &str,
+    log_generation: Option<u32>,
+    recovered: bool,
 ) -> Result<VecRecordStream, SrumError> {
     let mut network_vec: Vec<NetworkInfo> = Vec::new();
     for rows in column_rows {
@@ -26,6 +28,9 @@ pub(crate) fn parse_network(
             bytes_sent: 0,
             bytes_recvd: 0,
             evidence: evidence.to_string(),
+            log_replay: log_generation.is_some(),
+            log_generation: log_generation.unwrap_or_default(),
+            recovered,
         };
 
         for column in rows {
@@ -88,6 +93,8 @@ pub(crate) fn parse_network_connectivity(
     column_rows: &[Vec<TableDump>],
     lookups: &HashMap<String, String>,
     evidence: &str,
+    log_generation: Option<u32>,
+    recovered: bool,
 ) -> Result<VecRecordStream, SrumError> {
     let mut network_vec: Vec<NetworkConnectivityInfo> = Vec::new();
     for rows in column_rows {
@@ -102,6 +109,9 @@ pub(crate) fn parse_network_connectivity(
             connected_time: 0,
             connect_start_time: String::new(),
             evidence: evidence.to_string(),
+            log_replay: log_generation.is_some(),
+            log_generation: log_generation.unwrap_or_default(),
+            recovered,
         };
 
         for column in rows {
@@ -176,7 +186,7 @@ mod tests {
         let lookups = parse_id_lookup(&indexes);
         let srum_data = get_srum_ese(test_path, "{973F5D5C-1D90-4944-BE8E-24B94231A174}").unwrap();
 
-        parse_network(&srum_data, &lookups, test_path).unwrap();
+        parse_network(&srum_data, &lookups, test_path, None, false).unwrap();
     }
 
     #[test]
@@ -187,6 +197,6 @@ mod tests {
         let lookups = parse_id_lookup(&indexes);
         let srum_data = get_srum_ese(test_path, "{DD6636C4-8929-4683-974E-22C046A43763}").unwrap();
 
-        parse_network_connectivity(&srum_data, &lookups, test_path).unwrap();
+        parse_network_connectivity(&srum_data, &lookups, test_path, None, false).unwrap();
     }
 }
@@ -11,6 +11,8 @@ pub(crate) fn parse_notification(
     column_rows: &[Vec<TableDump>],
     lookups: &HashMap<String, String>,
     evidence: &str,
+    log_generation: Option<u32>,
+    recovered: bool,
 ) -> Result<VecRecordStream, SrumError> {
     let mut notif_vec: Vec<NotificationInfo> = Vec::new();
     for rows in column_rows {
@@ -23,6 +25,9 @@ pub(crate) fn parse_notification(
             payload_size: 0,
             network_type: 0,
             evidence: evidence.to_string(),
+            log_replay: log_generation.is_some(),
+            log_generation: log_generation.unwrap_or_default(),
+            recovered,
         };
 
         for column in rows {
@@ -90,6 +95,6 @@ mod tests {
         let lookups = parse_id_lookup(&indexes);
         let srum_data = get_srum_ese(test_path, "{D10CA2FE-6FCF-4F6D-848E-B2E99266FA86}").unwrap();
 
-        parse_notification(&srum_data, &lookups, test_path).unwrap();
+        parse_notification(&srum_data, &lookups, test_path, None, false).unwrap();
     }
 }
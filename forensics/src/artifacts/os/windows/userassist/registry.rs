@@ -51,6 +51,7 @@ pub(crate) fn get_userassist_drive(drive: char) -> Result<Vec<UserAssistReg>, Us
             start_path,
             &assist_regex,
             hive.reg_reference,
+            &assist_entry.reg_file,
             &mut ntfs_parser,
         );
         match reg_results {
@@ -136,6 +137,7 @@ mod tests {
                 start_path,
                 &assist_regex,
                 hive.reg_reference,
+                &hive.full_path,
                 &mut ntfs_parser,
             )
             .unwrap();
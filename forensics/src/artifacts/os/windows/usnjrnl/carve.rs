@@ -0,0 +1,209 @@
+use super::{error::UsnJrnlError, journal::UsnJrnlFormat, ntfs::extract_entries};
+use crate::{
+    artifacts::os::windows::mft::{
+        master::{Lookups, lookup_parent},
+        reader::setup_mft_reader_windows,
+    },
+    filesystem::ntfs::{
+        setup::setup_ntfs_parser,
+        unallocated::{get_slack, get_unallocated, scan_ranges},
+    },
+    output::manager::OutputManager,
+    structs::artifacts::os::windows::UsnJrnlOptions,
+};
+use common::windows::AttributeFlags;
+use std::collections::{HashMap, HashSet};
+use tracing::{error, info};
+
+/// Carve `UsnJrnl` records from the unallocated clusters and slack space of the provided drive. Carved entries are output with the `carved` flag
+pub(crate) fn carve_usnjrnl(
+    drive: char,
+    manager: &mut OutputManager,
+    options: &UsnJrnlOptions,
+) -> Result<(), UsnJrnlError> {
+    let mut ntfs_parser = match setup_ntfs_parser(drive) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Cannot setup NTFS parser: {err:?}");
+            return Err(UsnJrnlError::Parser);
+        }
+    };
+    let mut ranges = match get_unallocated(drive, &ntfs_parser.ntfs) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Could not determine unallocated clusters: {err:?}");
+            return Err(UsnJrnlError::ReadFile);
+        }
+    };
+    match get_slack(&ntfs_parser.ntfs, &mut ntfs_parser.fs, &manager.cancel) {
+        Ok(mut result) => ranges.append(&mut result),
+        Err(err) => {
+            error!("Could not determine slack space, only scanning unallocated clusters: {err:?}");
+        }
+    }
+
+    let mut entries = Vec::new();
+    let mut seen = HashSet::new();
+    // Max record size. Records at the end of a chunk can be read fully
+    let overlap = 1024;
+    scan_ranges(
+        &mut ntfs_parser.fs,
+        &ranges,
+        overlap,
+        &manager.cancel,
+        |_, _, data, scan_size| {
+            for entry in carve_records(data, scan_size) {
+                // Old journal data may exist multiple times in unallocated space
+                if seen.insert(format!(
+                    "{}_{}_{}_{}",
+                    entry.update_sequence_number,
+                    entry.mft_entry,
+                    entry.mft_sequence,
+                    entry.update_time
+                )) {
+                    entries.push(entry);
+                }
+            }
+        },
+    );
+    info!("Carved {} UsnJrnl entries from {drive}", entries.len());
+
+    let ntfs_file = match setup_mft_reader_windows(
+        &ntfs_parser.ntfs,
+        &mut ntfs_parser.fs,
+        &format!("{drive}:\\$MFT"),
+    ) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Cannot read the MFT file: {err:?}");
+            return Err(UsnJrnlError::ReadFile);
+        }
+    };
+
+    // Resolve parent directories using the current MFT
+    let mut cache: HashMap<String, String> = HashMap::new();
+    let mut journal_cache = HashMap::new();
+    for entry in entries.iter_mut() {
+        let root = 5;
+        let path = if entry.parent_mft_entry == root {
+            String::from(".")
+        } else if let Some(cache_hit) = cache.get(&format!(
+            "{}_{}",
+            entry.parent_mft_entry, entry.parent_mft_sequence
+        )) {
+            cache_hit.clone()
+        } else {
+            let mut tracker = Lookups {
+                parent_index: entry.parent_mft_entry,
+                parent_sequence: entry.parent_mft_sequence,
+                size: 0,
+                tracker: HashSet::new(),
+            };
+            lookup_parent(
+                &mut ntfs_parser.fs,
+                Some(&ntfs_file),
+                &mut cache,
+                &HashMap::new(),
+                &mut tracker,
+            )
+            .unwrap_or_default()
+        };
+        entry.full_path = format!("{path}\\{}", entry.name);
+
+        if entry.file_attributes.contains(&AttributeFlags::Directory) {
+            journal_cache.insert(
+                format!("{}_{}", entry.mft_entry, entry.mft_sequence),
+                entry.clone(),
+            );
+        }
+    }
+
+    extract_entries(
+        &mut entries,
+        Some(manager),
+        Some(options),
+        &journal_cache,
+        &format!("\\\\.\\{drive}:"),
+        &drive.to_string(),
+    )?;
+    Ok(())
+}
+
+/// Scan data for `UsnJrnl` records. Records are always 8 byte aligned.
+/// Only records that start before `scan_size` are returned
+fn carve_records(data: &[u8], scan_size: usize) -> Vec<UsnJrnlFormat> {
+    let mut entries = Vec::new();
+    let alignment = 8;
+    let min_size = 64;
+
+    let mut offset = 0;
+    while offset < scan_size.min(data.len()) {
+        let remaining = &data[offset..];
+        if remaining.len() < min_size {
+            break;
+        }
+        // Quick check for the major and minor version before trying to parse a record
+        let version = &remaining[4..8];
+        if version != [2, 0, 0, 0] && version != [3, 0, 0, 0] {
+            offset += alignment;
+            continue;
+        }
+
+        match UsnJrnlFormat::parse_carved_record(remaining) {
+            Some(entry) => {
+                // Record size was validated when parsing the record
+                let size =
+                    u32::from_le_bytes([remaining[0], remaining[1], remaining[2], remaining[3]])
+                        as usize;
+                entries.push(entry);
+                offset += size;
+            }
+            None => offset += alignment,
+        }
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::carve_records;
+    use common::windows::Reason;
+    use std::{fs::read, path::PathBuf};
+
+    #[test]
+    fn test_carve_records() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/carve/unallocated.raw");
+        let data = read(test_location).unwrap();
+
+        let results = carve_records(&data, data.len());
+        assert_eq!(results.len(), 2);
+
+        assert_eq!(results[0].name, "deleted.exe");
+        assert_eq!(results[0].mft_entry, 90);
+        assert_eq!(results[0].mft_sequence, 2);
+        assert_eq!(results[0].parent_mft_entry, 5);
+        assert_eq!(results[0].update_sequence_number, 0x5000);
+        assert_eq!(results[0].update_time, "2024-03-01T03:30:00.000Z");
+        assert_eq!(results[0].update_reason, vec![Reason::FileDelete]);
+        assert!(results[0].carved);
+
+        assert_eq!(results[1].name, "evil.ps1");
+        assert_eq!(results[1].mft_entry, 91);
+        assert_eq!(results[1].mft_sequence, 1);
+        assert_eq!(results[1].parent_mft_sequence, 5);
+        assert_eq!(results[1].update_reason, vec![Reason::FileCreate]);
+    }
+
+    #[test]
+    fn test_carve_records_scan_size() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/carve/unallocated.raw");
+        let data = read(test_location).unwrap();
+
+        // Records that start in the overlap are found in the next chunk
+        let results = carve_records(&data, 0x1000);
+        assert!(results.is_empty());
+    }
+}
@@ -1,6 +1,9 @@
 use crate::{
-    artifacts::os::windows::mft::master::{Lookups, lookup_parent},
-    filesystem::{files::file_reader, ntfs::attributes::file_attribute_flags},
+    artifacts::os::windows::mft::{
+        master::{Lookups, lookup_parent},
+        reader::setup_mft_reader,
+    },
+    filesystem::ntfs::attributes::file_attribute_flags,
     utils::{
         nom_helper::{
             Endian, nom_unsigned_eight_bytes, nom_unsigned_four_bytes, nom_unsigned_two_bytes,
@@ -36,6 +39,7 @@ pub(crate) struct UsnJrnlFormat {
     _name_offset: u16,
     pub(crate) name: String,
     pub(crate) full_path: String,
+    pub(crate) carved: bool,
 }
 
 impl UsnJrnlFormat {
@@ -127,6 +131,7 @@ impl UsnJrnlFormat {
                 parent_mft_sequence: parent_mft_seq,
                 update_sequence_number,
                 full_path: format!("{path}\\{name}"),
+                carved: false,
             };
 
             if entry.file_attributes.contains(&AttributeFlags::Directory) {
@@ -149,7 +154,7 @@ impl UsnJrnlFormat {
         let mut remaining_input = data;
 
         let mut reader = if let Some(path) = mft_path {
-            match file_reader(path) {
+            match setup_mft_reader(path) {
                 Ok(result) => Some(BufReader::new(result)),
                 Err(err) => {
                     error!("Could not create reader for alt MFT file: {err:?}");
@@ -245,6 +250,7 @@ impl UsnJrnlFormat {
                 parent_mft_sequence: parent_mft_seq,
                 update_sequence_number,
                 full_path: format!("{path}\\{name}"),
+                carved: false,
             };
 
             if entry.file_attributes.contains(&AttributeFlags::Directory) {
@@ -278,6 +284,108 @@ impl UsnJrnlFormat {
         Ok((remaining_input, ()))
     }
 
+    /// Parse a single `USN_RECORD_V2` or `USN_RECORD_V3` entry found while carving. Returns None if the data is not a valid record
+    ///
+    /// Parent directories are not resolved
+    pub(crate) fn parse_carved_record(data: &[u8]) -> Option<UsnJrnlFormat> {
+        let (_, entry) = UsnJrnlFormat::nom_carved_record(data).ok()?;
+        entry
+    }
+
+    /// Parse and validate a carved `UsnJrnl` record
+    fn nom_carved_record(data: &[u8]) -> nom::IResult<&[u8], Option<UsnJrnlFormat>> {
+        let (input, record_size) = nom_unsigned_four_bytes(data, Endian::Le)?;
+        let (input, major_version) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, minor_version) = nom_unsigned_two_bytes(input, Endian::Le)?;
+
+        let version2 = 2;
+        let version3 = 3;
+        // Largest record is a 255 character name plus the V3 header
+        let max_size = 1024;
+        if (major_version != version2 && major_version != version3)
+            || minor_version != 0
+            || record_size % 8 != 0
+            || record_size > max_size
+            || record_size as usize > data.len()
+        {
+            return Ok((input, None));
+        }
+
+        let (input, mft_entry) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, _padding) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (mut input, mft_seq) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        // V3 records use 128-bit file references. NTFS only uses the lower 64-bits
+        let extra_reference_size: u8 = 8;
+        if major_version == version3 {
+            (input, _) = take(extra_reference_size)(input)?;
+        }
+        let (input, parent_mft) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, _padding) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (mut input, parent_mft_seq) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        if major_version == version3 {
+            (input, _) = take(extra_reference_size)(input)?;
+        }
+
+        let (input, update_sequence_number) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+        let (input, usn_time) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+        let (input, reason) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, source) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, security_descriptor_id) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, flags) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (input, name_size) = nom_unsigned_two_bytes(input, Endian::Le)?;
+        let (input, name_offset) = nom_unsigned_two_bytes(input, Endian::Le)?;
+
+        let offset_v2 = 60;
+        let offset_v3 = 76;
+        let expected_offset = if major_version == version2 {
+            offset_v2
+        } else {
+            offset_v3
+        };
+        // Timestamp must be between 2000-01-01 and 2100-01-01
+        let min_time = 125911584000000000;
+        let max_time = 157766880000000000;
+        let source_flags = 0xf;
+        if name_offset != expected_offset
+            || name_size == 0
+            || name_size % 2 != 0
+            || (name_offset + name_size) as u32 > record_size
+            || !(min_time..max_time).contains(&usn_time)
+            || reason == 0
+            || source & !source_flags != 0
+        {
+            return Ok((input, None));
+        }
+
+        let (input, name_data) = take(name_size)(input)?;
+        let name = extract_utf16_string(name_data);
+        if name.is_empty() || name.chars().any(|character| character.is_control()) {
+            return Ok((input, None));
+        }
+
+        let entry = UsnJrnlFormat {
+            _major_version: major_version,
+            _minor_version: minor_version,
+            mft_entry,
+            mft_sequence: mft_seq,
+            parent_mft_entry: parent_mft,
+            parent_mft_sequence: parent_mft_seq,
+            update_time: filetime_to_iso(usn_time),
+            update_reason: UsnJrnlFormat::reason_flags(reason),
+            update_source_flags: UsnJrnlFormat::source_flag(source),
+            security_descriptor_id,
+            update_sequence_number,
+            file_attributes: file_attribute_flags(flags),
+            _name_size: name_size,
+            _name_offset: name_offset,
+            name,
+            full_path: String::new(),
+            carved: true,
+        };
+
+        Ok((input, Some(entry)))
+    }
+
     /// Get `UsnJrnl` update reason flags
     fn reason_flags(flag: u32) -> Vec<Reason> {
         let mut reasons = Vec::new();
@@ -1,3 +1,4 @@
+mod carve;
 mod error;
 mod journal;
 mod ntfs;
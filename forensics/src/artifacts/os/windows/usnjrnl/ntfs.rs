@@ -4,7 +4,7 @@ use crate::{
         usnjrnl::{error::UsnJrnlError, journal::UsnJrnlFormat},
     },
     filesystem::{
-        files::{file_extension, read_file},
+        files::{file_extension, read_any},
         ntfs::{raw_files::read_attribute, setup::setup_ntfs_parser},
     },
     output::{manager::OutputManager, record::serialize_records_to_stream},
@@ -15,7 +15,7 @@ use std::{
     collections::{HashMap, HashSet},
     mem::take,
 };
-use tracing::error;
+use tracing::{error, warn};
 
 /// Grab `UsnJrnl` entries by reading the $J ADS attribute and parsing its data runs
 pub(crate) fn parse_usnjrnl_data(
@@ -119,19 +119,25 @@ pub(crate) fn get_usnjrnl_path(drive: char, mft: &str) -> Result<Vec<UsnJrnlEntr
     )
 }
 
+/// Read the `UsnJrnl` file at provided path. Paths in archives or disk images are read with the `Accessor`
+///
+/// Example: `ewf:case.E01!p2/$Extend/$UsnJrnl:$J`
+fn read_journal(path: &str) -> Result<Vec<u8>, UsnJrnlError> {
+    match read_any(path) {
+        Ok(result) => Ok(result),
+        Err(err) => {
+            error!("Could not read UsnJrnl file {path}: {err:?}");
+            Err(UsnJrnlError::ReadFile)
+        }
+    }
+}
+
 /// Parse the `UsnJrnl` file at provided path and return results
 pub(crate) fn get_usnjrnl_alt_path(
     path: &str,
     mft_path: &Option<String>,
 ) -> Result<Vec<UsnJrnlEntry>, UsnJrnlError> {
-    let data_result = read_file(path);
-    let data = match data_result {
-        Ok(result) => result,
-        Err(err) => {
-            error!("Could not read UsnJrnl file {path}: {err:?}");
-            return Err(UsnJrnlError::ReadFile);
-        }
-    };
+    let data = read_journal(path)?;
     let mut journal_cache = HashMap::new();
 
     let entries_result =
@@ -154,14 +160,7 @@ pub(crate) fn get_usnjrnl_path_stream(
     manager: &mut OutputManager,
     options: &UsnJrnlOptions,
 ) -> Result<(), UsnJrnlError> {
-    let data_result = read_file(path);
-    let data = match data_result {
-        Ok(result) => result,
-        Err(err) => {
-            error!("Could not read UsnJrnl file {path}: {err:?}");
-            return Err(UsnJrnlError::ReadFile);
-        }
-    };
+    let data = read_journal(path)?;
     let mut journal_cache = HashMap::new();
 
     let entries_result =
@@ -187,7 +186,7 @@ pub(crate) fn get_usnjrnl_path_stream(
 }
 
 /// Loop through the parsed entries
-fn extract_entries(
+pub(crate) fn extract_entries(
     data: &mut [UsnJrnlFormat],
     mut manager: Option<&mut OutputManager>,
     options: Option<&UsnJrnlOptions>,
@@ -197,6 +196,14 @@ fn extract_entries(
 ) -> Result<Vec<UsnJrnlEntry>, UsnJrnlError> {
     let mut usnjrnl_entries = Vec::new();
     for jrnl_entry in data {
+        // Stop extracting entries if the collection has been cancelled
+        if manager
+            .as_ref()
+            .is_some_and(|out| out.cancel.is_cancelled())
+        {
+            warn!("Collection cancelled. Stopping UsnJrnl parsing for {path}");
+            break;
+        }
         // Try the cached usnjrnl paths before we give up
         if jrnl_entry.full_path.starts_with("$OrphanFiles\\") {
             let mut tracker = HashSet::new();
@@ -221,6 +228,7 @@ fn extract_entries(
             filename: jrnl_entry.name.clone(),
             drive: drive.to_string(),
             evidence: path.to_string(),
+            carved: jrnl_entry.carved,
         };
         usnjrnl_entries.push(entry);
         let limit = 1000;
@@ -350,6 +358,7 @@ mod tests {
             alt_drive: None,
             alt_file: None,
             alt_mft: None,
+            carve: None,
         };
         parse_usnjrnl_data('C', "C:\\$MFT", &mut output, &params).unwrap();
     }
@@ -378,6 +387,7 @@ mod tests {
             alt_drive: None,
             alt_file: None,
             alt_mft: None,
+            carve: None,
         };
         get_usnjrnl_path_stream(test_location.to_str().unwrap(), &None, &mut out, &params).unwrap();
         let mut output_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
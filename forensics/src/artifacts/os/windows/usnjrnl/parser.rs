@@ -12,6 +12,7 @@
  * `https://github.com/Velocidex/velociraptor`
  */
 use super::{
+    carve::carve_usnjrnl,
     error::UsnJrnlError,
     ntfs::{get_usnjrnl_path, parse_usnjrnl_data},
 };
@@ -22,7 +23,7 @@ use crate::{
     utils::environment::get_systemdrive,
 };
 use common::windows::UsnJrnlEntry;
-use tracing::error;
+use tracing::{error, warn};
 
 /// Parse `UsnJrnl` data and return list of entries
 pub(crate) fn grab_usnjrnl(
@@ -30,9 +31,12 @@ pub(crate) fn grab_usnjrnl(
     manager: &mut OutputManager,
 ) -> Result<(), UsnJrnlError> {
     if let Some(alt) = options.alt_drive {
-        return parse_usnjrnl_data(alt, &format!("{alt}:\\$MFT"), manager, options);
+        return parse_drive(alt, manager, options);
     }
     if let Some(path) = &options.alt_file {
+        if options.carve.unwrap_or_default() {
+            warn!("Carving UsnJrnl records is only supported when parsing a drive");
+        }
         return get_usnjrnl_path_stream(path, &options.alt_mft, manager, options);
     }
     let systemdrive_result = get_systemdrive();
@@ -44,12 +48,29 @@ pub(crate) fn grab_usnjrnl(
         }
     };
 
-    parse_usnjrnl_data(
-        systemdrive,
-        &format!("{systemdrive}:\\$MFT"),
-        manager,
-        options,
-    )
+    parse_drive(systemdrive, manager, options)
+}
+
+/// Parse the `UsnJrnl` on a drive. If carving is enabled also carve records from unallocated space and slack space
+fn parse_drive(
+    drive: char,
+    manager: &mut OutputManager,
+    options: &UsnJrnlOptions,
+) -> Result<(), UsnJrnlError> {
+    let result = parse_usnjrnl_data(drive, &format!("{drive}:\\$MFT"), manager, options);
+    if !options.carve.unwrap_or_default() {
+        return result;
+    }
+    if manager.cancel.is_cancelled() {
+        warn!("Collection cancelled. Skipping UsnJrnl carving on {drive}");
+        return result;
+    }
+
+    // The UsnJrnl may have been deleted. We still want to try carving old records
+    if let Err(err) = result {
+        error!("Could not parse UsnJrnl on {drive}. Will still carve unallocated space: {err:?}");
+    }
+    carve_usnjrnl(drive, manager, options)
 }
 
 /// Get `UsnJrnl` data at provided path
@@ -100,6 +121,7 @@ mod tests {
             alt_drive: None,
             alt_file: None,
             alt_mft: None,
+            carve: None,
         };
         let mut output = output_options("usnjrnl_temp", "./tmp", false);
 
@@ -114,6 +136,7 @@ mod tests {
             alt_drive: None,
             alt_file: Some(test_location.display().to_string()),
             alt_mft: None,
+            carve: None,
         };
         let results = grab_usnjrnl_path(&params).unwrap();
         assert_eq!(results.len(), 1);
@@ -127,6 +150,7 @@ mod tests {
             alt_drive: None,
             alt_file: Some(test_location.display().to_string()),
             alt_mft: None,
+            carve: None,
         };
         let results = grab_usnjrnl_path(&params).unwrap();
         assert_eq!(results.len(), 133099);
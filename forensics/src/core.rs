@@ -1,11 +1,13 @@
-use crate::artifacts::collection::collect;
+use crate::artifacts::{collection::collect, error::CollectionError};
 use crate::{
-    error::TomlError,
     filesystem::files::{read_file, read_text_file},
-    structs::toml::ArtemisToml,
+    output::sink::factory::build_sink,
+    structs::toml::{ArtemisToml, OutputDestination, ResourcePolicy},
 };
 use serde_json::Value;
-use tracing::{error, info};
+use tracing::{error, info, warn};
+
+pub use crate::{error::TomlError, utils::cancel::CancelToken};
 
 #[cfg(feature = "boa")]
 use tracing::level_filters::LevelFilter;
@@ -55,6 +57,11 @@ pub fn parse_toml_file(path: &str) -> Result<(), TomlError> {
 
 /// Parse an already read TOML file
 pub fn parse_toml_data(data: &[u8]) -> Result<(), TomlError> {
+    parse_toml_data_with_cancel(data, &CancelToken::new())
+}
+
+/// Parse an already read TOML file. The collection stops early if `cancel` is cancelled
+pub fn parse_toml_data_with_cancel(data: &[u8], cancel: &CancelToken) -> Result<(), TomlError> {
     let toml_results = ArtemisToml::parse_artemis_toml(data);
     let collection = match toml_results {
         Ok(results) => results,
@@ -62,7 +69,54 @@ pub fn parse_toml_data(data: &[u8]) -> Result<(), TomlError> {
             return Err(TomlError::BadToml);
         }
     };
-    artemis_collection(collection)
+    cancellable_collection(collection, cancel)
+}
+
+/// Parse an already read TOML file and apply a `ResourcePolicy` from the host running the collection
+///
+/// If the TOML file has its own `ResourcePolicy` the stricter of each limit is used
+pub fn parse_toml_data_with_policy(
+    data: &[u8],
+    cancel: &CancelToken,
+    policy: &ResourcePolicy,
+) -> Result<(), TomlError> {
+    let toml_results = ArtemisToml::parse_artemis_toml(data);
+    let mut collection = match toml_results {
+        Ok(results) => results,
+        Err(_) => {
+            return Err(TomlError::BadToml);
+        }
+    };
+    collection.output.resources = Some(match &collection.output.resources {
+        Some(resources) => resources.restrict(policy),
+        None => policy.clone(),
+    });
+    cancellable_collection(collection, cancel)
+}
+
+/// Retry uploads left in the spool by an earlier run of a TOML collection
+///
+/// Returns the number of uploads still waiting in the spool
+pub fn flush_spool(data: &[u8]) -> Result<usize, TomlError> {
+    let collection = match ArtemisToml::parse_artemis_toml(data) {
+        Ok(results) => results,
+        Err(_) => {
+            return Err(TomlError::BadToml);
+        }
+    };
+    // Local output is never spooled
+    if collection.output.destination == OutputDestination::Local {
+        return Ok(0);
+    }
+
+    let mut sink = match build_sink(&collection.output) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Could not setup output to flush the spool: {err}");
+            return Err(TomlError::BadOutput);
+        }
+    };
+    Ok(sink.flush_spool())
 }
 
 #[cfg(feature = "boa")]
@@ -97,9 +151,18 @@ pub fn parse_js_file(path: &str) -> Result<Value, TomlError> {
 
 /// Based on target system collect data based on TOML config
 pub fn artemis_collection(collection: ArtemisToml) -> Result<(), TomlError> {
-    let result = collect(collection);
+    cancellable_collection(collection, &CancelToken::new())
+}
+
+/// Run a collection that can be stopped with a `CancelToken`
+fn cancellable_collection(collection: ArtemisToml, cancel: &CancelToken) -> Result<(), TomlError> {
+    let result = collect(collection, cancel);
     match result {
         Ok(_) => info!("Parsed TOML data"),
+        Err(CollectionError::Cancelled) => {
+            warn!("Collection cancelled before completing");
+            return Err(TomlError::Cancelled);
+        }
         Err(err) => {
             error!("Failed to parse collection: {err:?}");
             return Err(TomlError::BadToml);
@@ -110,10 +173,13 @@ pub fn artemis_collection(collection: ArtemisToml) -> Result<(), TomlError> {
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_toml_data, parse_toml_file};
-    use crate::structs::toml::{OutputConfig, OutputDestination, OutputFormat};
+    use super::{
+        CancelToken, flush_spool, parse_toml_data, parse_toml_data_with_cancel,
+        parse_toml_data_with_policy, parse_toml_file,
+    };
+    use crate::structs::toml::{OutputConfig, OutputDestination, OutputFormat, ResourcePolicy};
     use crate::{
-        core::{ArtemisToml, artemis_collection},
+        core::{ArtemisToml, TomlError, artemis_collection},
         filesystem::files::read_file,
     };
     use std::path::PathBuf;
@@ -175,6 +241,53 @@ mod tests {
         parse_toml_data(&buffer).unwrap();
     }
 
+    #[test]
+    fn test_parse_toml_data_cancelled() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/linux/processes.toml");
+
+        let buffer = read_file(&test_location.display().to_string()).unwrap();
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        assert!(matches!(
+            parse_toml_data_with_cancel(&buffer, &cancel),
+            Err(TomlError::Cancelled)
+        ));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_parse_toml_data_with_policy() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/linux/processes.toml");
+
+        let buffer = read_file(&test_location.display().to_string()).unwrap();
+        let policy = ResourcePolicy {
+            max_cpu_percent: Some(90),
+            max_read_bytes_per_second: Some(104857600),
+            ..Default::default()
+        };
+        parse_toml_data_with_policy(&buffer, &CancelToken::new(), &policy).unwrap();
+    }
+
+    #[test]
+    fn test_flush_spool() {
+        let local = br#"
+artifacts = []
+
+[output]
+name = "spool_local"
+directory = "./tmp"
+format = "jsonl"
+compress = true
+endpoint_id = "abcd"
+collection_id = 1
+destination = "local"
+"#;
+        assert_eq!(flush_spool(local).unwrap(), 0);
+        assert!(matches!(flush_spool(b"bad toml"), Err(TomlError::BadToml)));
+    }
+
     #[test]
     #[cfg(feature = "boa")]
     fn test_parse_js_file() {
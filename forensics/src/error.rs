@@ -7,6 +7,8 @@ pub enum TomlError {
     BadToml,
     BadJs,
     RemoteToml,
+    Cancelled,
+    BadOutput,
 }
 
 impl std::error::Error for TomlError {}
@@ -19,6 +21,8 @@ impl fmt::Display for TomlError {
             TomlError::BadToml => write!(f, "Failed to parse TOML data"),
             TomlError::BadJs => write!(f, "Failed run JavaScript code"),
             TomlError::RemoteToml => write!(f, "Failed to read remote TOML data"),
+            TomlError::Cancelled => write!(f, "Collection was cancelled before completing"),
+            TomlError::BadOutput => write!(f, "Failed to setup collection output"),
         }
     }
 }
@@ -3,6 +3,30 @@ use std::fmt;
 #[derive(Debug)]
 pub(crate) enum DiskError {
     Qcow,
+    QcowHeader,
+    QcowEncrypted,
+    EwfSegment,
+    EwfSection,
+    EwfVolume,
+    EwfTable,
+    EwfChunk,
+    EwfUnsupported,
+    EwfCancelled,
+    Vhd,
+    VhdFooter,
+    VhdDynamic,
+    Vhdx,
+    VhdxHeader,
+    VhdxMetadata,
+    VhdxBat,
+    Vmdk,
+    VmdkDescriptor,
+    VmdkExtent,
+    VmdkUnsupported,
+    ParentDisk,
+    Vss,
+    VssCatalog,
+    VssStore,
 }
 
 impl std::error::Error for DiskError {}
@@ -11,6 +35,32 @@ impl fmt::Display for DiskError {
     fn fmt<'a>(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             DiskError::Qcow => write!(f, "Failed to setup QCOW reader"),
+            DiskError::QcowHeader => write!(f, "Failed to parse QCOW header or level tables"),
+            DiskError::QcowEncrypted => write!(f, "Encrypted QCOW images are not supported"),
+            DiskError::EwfSegment => write!(f, "Failed to open EWF segment file"),
+            DiskError::EwfSection => write!(f, "Failed to read EWF section"),
+            DiskError::EwfVolume => write!(f, "Failed to parse EWF volume section"),
+            DiskError::EwfTable => write!(f, "Failed to parse EWF chunk table"),
+            DiskError::EwfChunk => write!(f, "Failed to read EWF chunk"),
+            DiskError::EwfUnsupported => write!(f, "EWF version 2 (Ex01) images are not supported"),
+            DiskError::EwfCancelled => write!(f, "EWF verification was cancelled"),
+            DiskError::Vhd => write!(f, "Failed to open VHD file"),
+            DiskError::VhdFooter => write!(f, "Failed to parse VHD footer"),
+            DiskError::VhdDynamic => {
+                write!(f, "Failed to parse VHD dynamic disk header or block table")
+            }
+            DiskError::Vhdx => write!(f, "Failed to open VHDX file"),
+            DiskError::VhdxHeader => write!(f, "Failed to parse VHDX header or region table"),
+            DiskError::VhdxMetadata => write!(f, "Failed to parse VHDX metadata"),
+            DiskError::VhdxBat => write!(f, "Failed to parse VHDX block allocation table"),
+            DiskError::Vmdk => write!(f, "Failed to open VMDK file"),
+            DiskError::VmdkDescriptor => write!(f, "Failed to parse VMDK descriptor"),
+            DiskError::VmdkExtent => write!(f, "Failed to parse VMDK extent"),
+            DiskError::VmdkUnsupported => write!(f, "VMDK extent type is not supported"),
+            DiskError::ParentDisk => write!(f, "Failed to open parent of differencing disk"),
+            DiskError::Vss => write!(f, "Failed to read Volume Shadow Copy data"),
+            DiskError::VssCatalog => write!(f, "Failed to parse Volume Shadow Copy catalog"),
+            DiskError::VssStore => write!(f, "Failed to open Volume Shadow Copy store"),
         }
     }
 }
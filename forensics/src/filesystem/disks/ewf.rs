@@ -0,0 +1,899 @@
+/**
+ * Expert Witness Compression Format (EWF) is a common forensic disk image format. Typically acquired by `EnCase` or `FTK Imager`
+ * An image is split into segment files (E01, E02, ... E99, EAA, ...). Media data is stored in chunks that may be zlib compressed
+ *
+ * Only EWF version 1 (E01) images are supported. EWF version 2 (Ex01) images are detected and rejected
+ *
+ * References:
+ *  `https://github.com/libyal/libewf/blob/main/documentation/Expert%20Witness%20Compression%20Format%20(EWF).asciidoc`
+ *
+ * Other Parsers:
+ *  `https://github.com/libyal/libewf`
+ */
+use crate::{
+    filesystem::disks::error::DiskError,
+    utils::{
+        cancel::CancelToken,
+        compression::decompress::decompress_zlib,
+        nom_helper::{Endian, nom_data, nom_unsigned_eight_bytes, nom_unsigned_four_bytes},
+        strings::{extract_utf8_string, extract_utf16_string},
+    },
+};
+use base16ct::lower::encode_str;
+use md5::{Digest, Md5};
+use sha1::Sha1;
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+use tracing::{error, warn};
+
+/// EWF version 1 segment file signature
+const EWF_SIGNATURE: [u8; 8] = [0x45, 0x56, 0x46, 0x09, 0x0d, 0x0a, 0xff, 0x00];
+/// EWF version 2 segment file signature
+const EWF2_SIGNATURE: [u8; 8] = [0x45, 0x56, 0x46, 0x32, 0x0d, 0x0a, 0x81, 0x00];
+/// Size of the segment file header
+const FILE_HEADER_SIZE: u64 = 13;
+/// Size of a section descriptor
+const SECTION_SIZE: u64 = 76;
+/// Max number of sections to walk in one segment. Protects against section loops
+const MAX_SECTIONS: usize = 65536;
+/// Max number of segment files. E01-E99 then EAA-ZZZ
+const MAX_SEGMENTS: u16 = 14971;
+
+/// A `Read` + `Seek` reader over the media stored in EWF segment files
+pub(crate) struct EwfDisk {
+    /// Readers for each segment file
+    segments: Vec<BufReader<File>>,
+    /// Location of every chunk in the segment files
+    chunks: Vec<EwfChunk>,
+    /// Size of a decompressed chunk in bytes
+    chunk_size: u64,
+    /// Size of the media in bytes
+    size: u64,
+    /// Position of the reader in the media
+    position: u64,
+    /// Index of the cached chunk
+    chunk_key: Option<usize>,
+    cache: Vec<u8>,
+    /// Metadata and hashes stored in the segment files
+    pub(crate) info: EwfInfo,
+}
+
+/// Location of a chunk in the segment files
+#[derive(Debug, Clone, PartialEq)]
+struct EwfChunk {
+    /// Index into the segment readers
+    segment: usize,
+    /// Offset to the chunk in the segment file
+    offset: u64,
+    /// Size of the chunk in the segment file. Includes the checksum for uncompressed chunks
+    stored_size: u64,
+    compressed: bool,
+}
+
+/// Metadata and hashes stored in the EWF segment files
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EwfInfo {
+    /// Paths to all segment files
+    pub(crate) segment_paths: Vec<PathBuf>,
+    pub(crate) case_number: String,
+    pub(crate) evidence_number: String,
+    pub(crate) description: String,
+    pub(crate) examiner: String,
+    pub(crate) notes: String,
+    /// Acquisition date as recorded by the imaging software
+    pub(crate) acquired: String,
+    /// MD5 of the media as stored in the hash or digest section
+    pub(crate) md5: Option<String>,
+    /// SHA1 of the media as stored in the digest section
+    pub(crate) sha1: Option<String>,
+}
+
+/// Result of hashing the media and comparing to the stored hashes
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct EwfVerification {
+    pub(crate) computed_md5: String,
+    pub(crate) computed_sha1: String,
+    /// True only if a stored hash exists and all stored hashes match
+    pub(crate) verified: bool,
+}
+
+/// Media geometry from the volume section
+#[derive(Debug, Default)]
+struct EwfVolume {
+    sectors_per_chunk: u32,
+    bytes_per_sector: u32,
+    sector_count: u64,
+}
+
+impl EwfVolume {
+    /// Get the chunk size and media size in bytes. Returns None if the volume section has an invalid geometry
+    fn geometry(&self) -> Option<(u64, u64)> {
+        let chunk_size =
+            (self.sectors_per_chunk as u64).checked_mul(self.bytes_per_sector as u64)?;
+        let size = self
+            .sector_count
+            .checked_mul(self.bytes_per_sector as u64)?;
+        // Chunks are typically 32KB. Every chunk is decompressed into memory
+        let max_chunk_size = 64 * 1024 * 1024;
+        if chunk_size == 0 || chunk_size > max_chunk_size {
+            return None;
+        }
+        Some((chunk_size, size))
+    }
+}
+
+/// Section descriptor found in the segment files
+#[derive(Debug)]
+struct Section {
+    section_type: String,
+    next: u64,
+    size: u64,
+}
+
+impl EwfDisk {
+    /// Open the first segment (E01) of an EWF image. Remaining segments are found next to the first segment
+    pub(crate) fn open(path: &str) -> Result<Self, DiskError> {
+        let first = Path::new(path);
+        let mut info = EwfInfo::default();
+        let mut volume = None;
+        let mut chunks = Vec::new();
+        let mut segments = Vec::new();
+
+        for number in 1..=MAX_SEGMENTS {
+            let segment_path = if number == 1 {
+                first.to_path_buf()
+            } else {
+                segment_path(first, number).ok_or(DiskError::EwfSegment)?
+            };
+            let file = match File::open(&segment_path) {
+                Ok(result) => result,
+                Err(err) => {
+                    error!(
+                        "Failed to open EWF segment {}: {err:?}",
+                        segment_path.display()
+                    );
+                    return Err(DiskError::EwfSegment);
+                }
+            };
+            let mut reader = BufReader::new(file);
+            read_file_header(&mut reader, number)?;
+
+            let done = read_sections(
+                &mut reader,
+                segments.len(),
+                &mut info,
+                &mut volume,
+                &mut chunks,
+            )?;
+            segments.push(reader);
+            info.segment_paths.push(segment_path);
+            if done {
+                break;
+            }
+        }
+
+        let volume = volume.ok_or_else(|| {
+            error!("EWF image is missing the volume section");
+            DiskError::EwfVolume
+        })?;
+        let Some((chunk_size, size)) = volume.geometry() else {
+            error!("EWF volume section has an invalid chunk or media size");
+            return Err(DiskError::EwfVolume);
+        };
+        if (chunks.len() as u64) < size.div_ceil(chunk_size) {
+            error!(
+                "EWF image has {} chunks but media size {size} requires chunk size {chunk_size}",
+                chunks.len()
+            );
+            return Err(DiskError::EwfTable);
+        }
+
+        Ok(Self {
+            segments,
+            chunks,
+            chunk_size,
+            size,
+            position: 0,
+            chunk_key: None,
+            cache: Vec::new(),
+            info,
+        })
+    }
+
+    /// Size of the media in bytes
+    pub(crate) fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Hash the entire media and compare against the hashes stored in the image
+    pub(crate) fn verify(&mut self, cancel: &CancelToken) -> Result<EwfVerification, DiskError> {
+        let mut md5 = Md5::new();
+        let mut sha1 = Sha1::new();
+
+        for index in 0..self.chunks.len() {
+            let start = index as u64 * self.chunk_size;
+            if start >= self.size {
+                break;
+            }
+            if cancel.is_cancelled() {
+                warn!("EWF verification cancelled at chunk {index}");
+                return Err(DiskError::EwfCancelled);
+            }
+            if let Err(err) = self.load_chunk(index) {
+                error!("Could not read EWF chunk {index} during verification: {err:?}");
+                return Err(DiskError::EwfChunk);
+            }
+            let end = (self.size - start).min(self.chunk_size) as usize;
+            md5.update(&self.cache[..end]);
+            sha1.update(&self.cache[..end]);
+            cancel.throttle(end);
+        }
+
+        let mut md5_buf = [0u8; 32];
+        let computed_md5 = encode_str(&md5.finalize(), &mut md5_buf)
+            .unwrap_or_default()
+            .to_string();
+        let mut sha1_buf = [0u8; 40];
+        let computed_sha1 = encode_str(&sha1.finalize(), &mut sha1_buf)
+            .unwrap_or_default()
+            .to_string();
+
+        let md5_match = self.info.md5.as_ref().map(|hash| *hash == computed_md5);
+        let sha1_match = self.info.sha1.as_ref().map(|hash| *hash == computed_sha1);
+        let verified = (md5_match.is_some() || sha1_match.is_some())
+            && md5_match.unwrap_or(true)
+            && sha1_match.unwrap_or(true);
+
+        Ok(EwfVerification {
+            computed_md5,
+            computed_sha1,
+            verified,
+        })
+    }
+
+    /// Read and decompress a chunk into the cache
+    fn load_chunk(&mut self, index: usize) -> io::Result<()> {
+        if self.chunk_key == Some(index) {
+            return Ok(());
+        }
+        self.chunk_key = None;
+
+        let chunk = self.chunks.get(index).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("EWF chunk {index} not found in chunk tables"),
+            )
+        })?;
+        // Compressed chunks should never be much larger than the chunk size
+        if chunk.stored_size > self.chunk_size * 2 + 4 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("EWF chunk {index} is too large: {}", chunk.stored_size),
+            ));
+        }
+        let reader = self.segments.get_mut(chunk.segment).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("EWF segment {} not found", chunk.segment),
+            )
+        })?;
+
+        reader.seek(SeekFrom::Start(chunk.offset))?;
+        let mut data = vec![0; chunk.stored_size as usize];
+        reader.read_exact(&mut data)?;
+
+        let mut chunk_data = if chunk.compressed {
+            decompress_zlib(&data, None, self.chunk_size as usize).map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("could not decompress EWF chunk {index}: {err:?}"),
+                )
+            })?
+        } else {
+            let checksum_size = 4;
+            if data.len() > checksum_size {
+                let (chunk_data, checksum) = data.split_at(data.len() - checksum_size);
+                let stored =
+                    u32::from_le_bytes([checksum[0], checksum[1], checksum[2], checksum[3]]);
+                if adler32(chunk_data) != stored {
+                    warn!("EWF chunk {index} checksum mismatch");
+                }
+                data.truncate(data.len() - checksum_size);
+            }
+            data
+        };
+        chunk_data.resize(self.chunk_size as usize, 0);
+
+        self.cache = chunk_data;
+        self.chunk_key = Some(index);
+        Ok(())
+    }
+}
+
+impl Read for EwfDisk {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() || self.position >= self.size {
+            return Ok(0);
+        }
+
+        let index = (self.position / self.chunk_size) as usize;
+        self.load_chunk(index)?;
+        let chunk_offset = self.position % self.chunk_size;
+        let read_len = (self.chunk_size - chunk_offset)
+            .min(self.size - self.position)
+            .min(buf.len() as u64) as usize;
+
+        let start = chunk_offset as usize;
+        buf[..read_len].copy_from_slice(&self.cache[start..start + read_len]);
+        self.position += read_len as u64;
+        Ok(read_len)
+    }
+}
+
+impl Seek for EwfDisk {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+        };
+
+        match new_pos {
+            Some(position) => {
+                self.position = position;
+                Ok(self.position)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+impl fmt::Debug for EwfDisk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EwfDisk")
+            .field("segments", &self.segments.len())
+            .field("chunks", &self.chunks.len())
+            .field("chunk_size", &self.chunk_size)
+            .field("size", &self.size)
+            .field("position", &self.position)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Build the path to a segment file. Example: `case.E01` and 2 returns `case.E02`. Segment 100 returns `case.EAA`
+fn segment_path(first: &Path, number: u16) -> Option<PathBuf> {
+    let extension = first.extension()?.to_str()?;
+    let prefix = extension.chars().next()?;
+    let upper = prefix.is_ascii_uppercase();
+
+    let extension = if number <= 99 {
+        format!("{prefix}{number:02}")
+    } else {
+        let base = if upper { b'A' } else { b'a' };
+        let index = (number - 100) as u32;
+        let first_letter = (prefix as u32) + index / (26 * 26);
+        let second = (base as u32) + (index / 26) % 26;
+        let third = (base as u32) + index % 26;
+        [first_letter, second, third]
+            .iter()
+            .map(|value| char::from_u32(*value))
+            .collect::<Option<String>>()?
+    };
+
+    Some(first.with_extension(extension))
+}
+
+/// Validate the segment file header and segment number
+fn read_file_header<T: Read + Seek>(reader: &mut T, expected: u16) -> Result<(), DiskError> {
+    let mut header = [0u8; FILE_HEADER_SIZE as usize];
+    if let Err(err) = reader.read_exact(&mut header) {
+        error!("Could not read EWF segment header: {err:?}");
+        return Err(DiskError::EwfSegment);
+    }
+
+    if header[..8] == EWF2_SIGNATURE {
+        error!("EWF version 2 (Ex01) images are not supported");
+        return Err(DiskError::EwfUnsupported);
+    }
+    if header[..8] != EWF_SIGNATURE {
+        error!(
+            "Segment file is not an EWF image. Signature: {:?}",
+            &header[..8]
+        );
+        return Err(DiskError::EwfSegment);
+    }
+
+    let segment_number = u16::from_le_bytes([header[9], header[10]]);
+    if segment_number != expected {
+        error!("Expected EWF segment {expected} but got segment {segment_number}");
+        return Err(DiskError::EwfSegment);
+    }
+
+    Ok(())
+}
+
+/// Walk the sections in a segment file. Returns true if the `done` section was found
+fn read_sections<T: Read + Seek>(
+    reader: &mut T,
+    segment: usize,
+    info: &mut EwfInfo,
+    volume: &mut Option<EwfVolume>,
+    chunks: &mut Vec<EwfChunk>,
+) -> Result<bool, DiskError> {
+    let mut offset = FILE_HEADER_SIZE;
+    // End of the last sectors section. Needed to get the size of the last chunk in a table
+    let mut sectors_end = 0;
+
+    for _ in 0..MAX_SECTIONS {
+        let descriptor = read_bytes(reader, offset, SECTION_SIZE)?;
+        let section = match parse_section(&descriptor) {
+            Ok((_, result)) => result,
+            Err(_err) => {
+                error!("Could not parse EWF section at offset {offset}");
+                return Err(DiskError::EwfSection);
+            }
+        };
+        let data_size = section.size.saturating_sub(SECTION_SIZE);
+
+        match section.section_type.as_str() {
+            "header" | "header2" if info.case_number.is_empty() => {
+                let data = read_bytes(reader, offset + SECTION_SIZE, data_size)?;
+                parse_header(&data, section.section_type == "header2", info);
+            }
+            "volume" | "disk" if volume.is_none() => {
+                let data = read_bytes(reader, offset + SECTION_SIZE, data_size)?;
+                *volume = Some(parse_volume(&data)?);
+            }
+            "sectors" => sectors_end = offset + section.size,
+            "table" => {
+                let data = read_bytes(reader, offset + SECTION_SIZE, data_size)?;
+                // The last chunk ends at the end of the sectors section. Older images store chunks in the table section
+                let end = if sectors_end > 0 {
+                    sectors_end
+                } else {
+                    offset + section.size
+                };
+                parse_table(&data, segment, end, chunks)?;
+            }
+            "hash" => {
+                let data = read_bytes(reader, offset + SECTION_SIZE, data_size)?;
+                if let Some(md5) = data.get(..16) {
+                    info.md5 = hash_string(md5);
+                }
+            }
+            "digest" => {
+                let data = read_bytes(reader, offset + SECTION_SIZE, data_size)?;
+                if let Some(md5) = data.get(..16) {
+                    info.md5 = hash_string(md5);
+                }
+                if let Some(sha1) = data.get(16..36) {
+                    info.sha1 = hash_string(sha1);
+                }
+            }
+            "done" => return Ok(true),
+            "next" => return Ok(false),
+            _ => {}
+        }
+
+        // Sections should always point forward
+        if section.next <= offset {
+            warn!(
+                "EWF section {} at offset {offset} does not point to a next section",
+                section.section_type
+            );
+            return Ok(false);
+        }
+        offset = section.next;
+    }
+
+    warn!("Reached max EWF sections for segment {}", segment + 1);
+    Ok(false)
+}
+
+/// Parse a section descriptor
+fn parse_section(data: &[u8]) -> nom::IResult<&[u8], Section> {
+    let type_size: u8 = 16;
+    let (input, type_data) = nom_data(data, type_size as u64)?;
+    let (input, next) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+    let (input, size) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+
+    let section = Section {
+        section_type: extract_utf8_string(type_data),
+        next,
+        size,
+    };
+    Ok((input, section))
+}
+
+/// Parse the media geometry from the volume or disk section
+fn parse_volume(data: &[u8]) -> Result<EwfVolume, DiskError> {
+    let result = nom_volume(data);
+    match result {
+        Ok((_, volume)) if volume.geometry().is_some() => Ok(volume),
+        _ => {
+            error!("Could not parse EWF volume section");
+            Err(DiskError::EwfVolume)
+        }
+    }
+}
+
+/// Parse the volume section. `EnCase` volumes use 8 bytes for the sector count. SMART volumes use 4 bytes
+fn nom_volume(data: &[u8]) -> nom::IResult<&[u8], EwfVolume> {
+    let (input, _media_type) = nom_unsigned_four_bytes(data, Endian::Le)?;
+    let (input, _chunk_count) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, sectors_per_chunk) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, bytes_per_sector) = nom_unsigned_four_bytes(input, Endian::Le)?;
+
+    let encase_volume_size = 1052;
+    let (input, sector_count) = if data.len() >= encase_volume_size {
+        nom_unsigned_eight_bytes(input, Endian::Le)?
+    } else {
+        let (input, count) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        (input, count as u64)
+    };
+
+    let volume = EwfVolume {
+        sectors_per_chunk,
+        bytes_per_sector,
+        sector_count,
+    };
+    Ok((input, volume))
+}
+
+/// Parse the chunk offsets in a table section
+fn parse_table(
+    data: &[u8],
+    segment: usize,
+    end: u64,
+    chunks: &mut Vec<EwfChunk>,
+) -> Result<(), DiskError> {
+    let result = nom_table(data);
+    let (entry_count, base_offset, entries) = match result {
+        Ok((_, result)) => result,
+        Err(_err) => {
+            error!("Could not parse EWF table section");
+            return Err(DiskError::EwfTable);
+        }
+    };
+
+    let compressed_flag = 0x80000000;
+    let offsets: Vec<(u64, bool)> = entries
+        .chunks_exact(4)
+        .take(entry_count as usize)
+        .map(|entry| {
+            let value = u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]);
+            (
+                base_offset + (value & !compressed_flag) as u64,
+                value & compressed_flag != 0,
+            )
+        })
+        .collect();
+
+    for (index, (offset, compressed)) in offsets.iter().enumerate() {
+        let next = offsets.get(index + 1).map_or(end, |(next, _)| *next);
+        if next <= *offset {
+            error!("EWF chunk offset {offset} is past the end of the chunk data {next}");
+            return Err(DiskError::EwfTable);
+        }
+        chunks.push(EwfChunk {
+            segment,
+            offset: *offset,
+            stored_size: next - offset,
+            compressed: *compressed,
+        });
+    }
+
+    Ok(())
+}
+
+/// Parse the table header and return the entry count, base offset, and entry bytes
+fn nom_table(data: &[u8]) -> nom::IResult<&[u8], (u32, u64, &[u8])> {
+    let (input, entry_count) = nom_unsigned_four_bytes(data, Endian::Le)?;
+    let (input, _padding) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, base_offset) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+    let (input, _padding) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, _checksum) = nom_unsigned_four_bytes(input, Endian::Le)?;
+
+    let entry_size = 4;
+    let (input, entries) = nom_data(input, entry_count as u64 * entry_size)?;
+    Ok((input, (entry_count, base_offset, entries)))
+}
+
+/// Parse the zlib compressed header section. The header2 section is UTF16
+///
+/// Header data is a tab separated table. Example:
+/// ```text
+/// 1
+/// main
+/// c   n   a   e   t   ...
+/// case    evidence    description examiner    notes   ...
+/// ```
+fn parse_header(data: &[u8], is_utf16: bool, info: &mut EwfInfo) {
+    // Header data should be small. Only a few KBs
+    let max_size = 1024 * 1024;
+    let text_data = match decompress_zlib(data, None, max_size) {
+        Ok(result) => result,
+        Err(err) => {
+            warn!("Could not decompress EWF header section: {err:?}");
+            return;
+        }
+    };
+    let text = if is_utf16 {
+        extract_utf16_string(&text_data)
+    } else {
+        extract_utf8_string(&text_data)
+    };
+
+    let mut lines = text.lines().map(|line| line.trim_start_matches('\u{feff}'));
+    let Some(keys) = lines.by_ref().find(|line| line.starts_with("c\t")) else {
+        warn!("EWF header section is missing the header values");
+        return;
+    };
+    let Some(values) = lines.next() else {
+        return;
+    };
+
+    for (key, value) in keys.split('\t').zip(values.split('\t')) {
+        let value = value.to_string();
+        match key {
+            "c" => info.case_number = value,
+            "n" => info.evidence_number = value,
+            "a" => info.description = value,
+            "e" => info.examiner = value,
+            "t" => info.notes = value,
+            "m" => info.acquired = value,
+            _ => {}
+        }
+    }
+}
+
+/// Read bytes from a segment file at the provided offset
+fn read_bytes<T: Read + Seek>(
+    reader: &mut T,
+    offset: u64,
+    size: u64,
+) -> Result<Vec<u8>, DiskError> {
+    // Section data outside of the chunk data should never be this large
+    let max_size = 64 * 1024 * 1024;
+    if size > max_size {
+        error!("EWF section at offset {offset} is too large: {size}");
+        return Err(DiskError::EwfSection);
+    }
+    if let Err(err) = reader.seek(SeekFrom::Start(offset)) {
+        error!("Could not seek to EWF offset {offset}: {err:?}");
+        return Err(DiskError::EwfSection);
+    }
+
+    let mut data = vec![0; size as usize];
+    if let Err(err) = reader.read_exact(&mut data) {
+        error!("Could not read EWF section at offset {offset}: {err:?}");
+        return Err(DiskError::EwfSection);
+    }
+    Ok(data)
+}
+
+/// Adler-32 checksum used to protect uncompressed chunks
+fn adler32(data: &[u8]) -> u32 {
+    let modulo = 65521;
+    let mut low: u32 = 1;
+    let mut high: u32 = 0;
+    // Max bytes before the sums need to be reduced to avoid overflow
+    let max_block = 5552;
+    for block in data.chunks(max_block) {
+        for value in block {
+            low += *value as u32;
+            high += low;
+        }
+        low %= modulo;
+        high %= modulo;
+    }
+    (high << 16) | low
+}
+
+/// Convert a stored hash to a hex string. Empty (all zero) hashes are ignored
+fn hash_string(data: &[u8]) -> Option<String> {
+    if data.iter().all(|value| *value == 0) {
+        return None;
+    }
+    Some(data.iter().map(|value| format!("{value:02x}")).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        EwfDisk, adler32, hash_string, nom_volume, parse_table, parse_volume, segment_path,
+    };
+    use crate::{filesystem::disks::error::DiskError, utils::cancel::CancelToken};
+    use std::{
+        fs::{copy, create_dir_all, read},
+        io::{Read, Seek, SeekFrom},
+        path::{Path, PathBuf},
+    };
+
+    fn test_location() -> PathBuf {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/ewf/gpt_ext4.E01");
+        test_location
+    }
+
+    #[test]
+    fn test_ewf_disk() {
+        let mut raw_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        raw_location.push("tests/test_data/disks/raw/gpt_ext4.raw");
+        let raw = read(raw_location).unwrap();
+
+        let mut disk = EwfDisk::open(test_location().to_str().unwrap()).unwrap();
+        assert_eq!(disk.size(), raw.len() as u64);
+        assert_eq!(disk.info.segment_paths.len(), 2);
+        assert_eq!(disk.info.case_number, "CASE-0042");
+        assert_eq!(disk.info.evidence_number, "EV-7");
+        assert_eq!(disk.info.examiner, "analyst");
+
+        let mut media = Vec::new();
+        disk.read_to_end(&mut media).unwrap();
+        assert_eq!(media, raw);
+
+        // Chunk 10 is stored uncompressed in the second segment
+        disk.seek(SeekFrom::Start(10 * 32768 + 100)).unwrap();
+        let mut buf = [0u8; 16];
+        disk.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, raw[10 * 32768 + 100..10 * 32768 + 116]);
+    }
+
+    #[test]
+    fn test_ewf_verify() {
+        let mut disk = EwfDisk::open(test_location().to_str().unwrap()).unwrap();
+        let result = disk.verify(&CancelToken::new()).unwrap();
+        assert!(result.verified);
+        assert_eq!(result.computed_md5, "c26200d79843cb1640e3b53f3cd3a3ae");
+        assert_eq!(
+            result.computed_sha1,
+            "284ebc3d1dce06441e285be9a32618a2789f16ff"
+        );
+        assert_eq!(disk.info.md5.as_deref(), Some(result.computed_md5.as_str()));
+    }
+
+    #[test]
+    fn test_ewf_verify_cancelled() {
+        let mut disk = EwfDisk::open(test_location().to_str().unwrap()).unwrap();
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let result = disk.verify(&cancel).unwrap_err();
+        assert!(matches!(result, DiskError::EwfCancelled));
+    }
+
+    #[test]
+    fn test_ewf2_unsupported() {
+        let dir = Path::new("./tmp/ewf2_unsupported");
+        create_dir_all(dir).unwrap();
+        let mut data = vec![0x45, 0x56, 0x46, 0x32, 0x0d, 0x0a, 0x81, 0x00];
+        data.resize(512, 0);
+        std::fs::write(dir.join("image.Ex01"), data).unwrap();
+
+        let result = EwfDisk::open(dir.join("image.Ex01").to_str().unwrap()).unwrap_err();
+        assert!(matches!(result, DiskError::EwfUnsupported));
+    }
+
+    #[test]
+    fn test_ewf_verify_mismatch() {
+        let dir = Path::new("./tmp/ewf_mismatch");
+        create_dir_all(dir).unwrap();
+        copy(test_location(), dir.join("gpt_ext4.E01")).unwrap();
+        let mut second = read(test_location().with_extension("E02")).unwrap();
+        // Modify the stored MD5 in the hash and digest sections
+        for section in [b"hash\0".as_slice(), b"digest\0".as_slice()] {
+            let offset = second
+                .windows(section.len())
+                .position(|data| data == section)
+                .unwrap()
+                + 76;
+            second[offset] ^= 0xff;
+        }
+        std::fs::write(dir.join("gpt_ext4.E02"), second).unwrap();
+
+        let mut disk = EwfDisk::open(dir.join("gpt_ext4.E01").to_str().unwrap()).unwrap();
+        let result = disk.verify(&CancelToken::new()).unwrap();
+        assert!(!result.verified);
+    }
+
+    #[test]
+    fn test_ewf_missing_segment() {
+        let dir = Path::new("./tmp/ewf_missing");
+        create_dir_all(dir).unwrap();
+        copy(test_location(), dir.join("gpt_ext4.E01")).unwrap();
+
+        let err = EwfDisk::open(dir.join("gpt_ext4.E01").to_str().unwrap()).unwrap_err();
+        assert!(matches!(err, DiskError::EwfSegment));
+    }
+
+    #[test]
+    fn test_segment_path() {
+        let first = Path::new("/cases/disk.E01");
+        assert_eq!(
+            segment_path(first, 2).unwrap(),
+            PathBuf::from("/cases/disk.E02")
+        );
+        assert_eq!(
+            segment_path(first, 99).unwrap(),
+            PathBuf::from("/cases/disk.E99")
+        );
+        assert_eq!(
+            segment_path(first, 100).unwrap(),
+            PathBuf::from("/cases/disk.EAA")
+        );
+        assert_eq!(
+            segment_path(first, 127).unwrap(),
+            PathBuf::from("/cases/disk.EBB")
+        );
+        assert_eq!(
+            segment_path(Path::new("disk.e01"), 776).unwrap(),
+            PathBuf::from("disk.faa")
+        );
+    }
+
+    #[test]
+    fn test_parse_table() {
+        let mut data = vec![2, 0, 0, 0, 0, 0, 0, 0];
+        data.extend_from_slice(&100u64.to_le_bytes());
+        data.extend_from_slice(&[0; 8]);
+        data.extend_from_slice(&0x80000000u32.to_le_bytes());
+        data.extend_from_slice(&50u32.to_le_bytes());
+
+        let mut chunks = Vec::new();
+        parse_table(&data, 0, 400, &mut chunks).unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].offset, 100);
+        assert_eq!(chunks[0].stored_size, 50);
+        assert!(chunks[0].compressed);
+        assert_eq!(chunks[1].offset, 150);
+        assert_eq!(chunks[1].stored_size, 250);
+        assert!(!chunks[1].compressed);
+    }
+
+    #[test]
+    fn test_nom_volume() {
+        let mut data = vec![0u8; 94];
+        data[8..12].copy_from_slice(&64u32.to_le_bytes());
+        data[12..16].copy_from_slice(&512u32.to_le_bytes());
+        data[16..20].copy_from_slice(&1185u32.to_le_bytes());
+
+        let (_, volume) = nom_volume(&data).unwrap();
+        assert_eq!(volume.sectors_per_chunk, 64);
+        assert_eq!(volume.bytes_per_sector, 512);
+        assert_eq!(volume.sector_count, 1185);
+    }
+
+    #[test]
+    fn test_parse_volume_bad_chunk_size() {
+        let mut data = vec![0u8; 94];
+        data[12..16].copy_from_slice(&512u32.to_le_bytes());
+        data[16..20].copy_from_slice(&1185u32.to_le_bytes());
+        assert!(matches!(parse_volume(&data), Err(DiskError::EwfVolume)));
+
+        // 2GB chunks
+        data[8..12].copy_from_slice(&0x400000u32.to_le_bytes());
+        assert!(matches!(parse_volume(&data), Err(DiskError::EwfVolume)));
+
+        data[8..12].copy_from_slice(&64u32.to_le_bytes());
+        assert!(parse_volume(&data).is_ok());
+    }
+
+    #[test]
+    fn test_adler32() {
+        assert_eq!(adler32(b"Wikipedia"), 0x11e60398);
+        assert_eq!(adler32(&[]), 1);
+    }
+
+    #[test]
+    fn test_hash_string() {
+        assert_eq!(hash_string(&[0; 16]), None);
+        assert_eq!(hash_string(&[0xab, 0x01]).unwrap(), "ab01");
+    }
+}
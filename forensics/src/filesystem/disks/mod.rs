@@ -1,2 +1,10 @@
 pub(crate) mod error;
+pub(crate) mod ewf;
+pub(crate) mod parent;
 pub(crate) mod qcow;
+#[cfg(test)]
+pub(crate) mod test_disks;
+pub(crate) mod vhd;
+pub(crate) mod vhdx;
+pub(crate) mod vmdk;
+pub(crate) mod vss;
@@ -0,0 +1,69 @@
+use std::path::{Path, PathBuf};
+
+/// Max number of parent images to follow. Protects against parent loops
+pub(crate) const MAX_PARENT_DEPTH: usize = 32;
+
+/// Find the parent of a differencing disk using the paths stored in the child. Example: `.\base.vhdx` or `D:\vms\base.vhdx`
+///
+/// Relative paths are resolved against the directory of the child. If no path exists, the parent file name is checked
+/// next to the child. Evidence is often copied to a different system than where the VM ran
+pub(crate) fn resolve_parent(child: &Path, hints: &[String]) -> Option<PathBuf> {
+    let directory = child.parent().unwrap_or(Path::new(""));
+    let mut names = Vec::new();
+
+    for hint in hints {
+        let hint = hint.trim_matches(char::from(0)).trim();
+        if hint.is_empty() {
+            continue;
+        }
+        let normalized = hint.replace('\\', "/");
+        let path = PathBuf::from(&normalized);
+        // Windows absolute paths (C:/vms/base.vhdx) are never absolute on other platforms
+        let is_windows_absolute = normalized.as_bytes().get(1) == Some(&b':');
+        let candidate = if path.is_absolute() || is_windows_absolute {
+            path
+        } else {
+            directory.join(path)
+        };
+        if candidate.is_file() && candidate != child {
+            return Some(candidate);
+        }
+        if let Some(name) = normalized.rsplit('/').next() {
+            names.push(name.to_string());
+        }
+    }
+
+    names
+        .iter()
+        .map(|name| directory.join(name))
+        .find(|candidate| candidate.is_file() && candidate != child)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resolve_parent;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_resolve_parent() {
+        let mut child = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        child.push("tests/test_data/disks/vhdx/gpt_ext4_child.vhdx");
+
+        let result = resolve_parent(&child, &[String::from(".\\gpt_ext4.vhdx")]).unwrap();
+        assert!(result.ends_with("vhdx/gpt_ext4.vhdx"));
+
+        // Absolute path from the original system falls back to the file name next to the child
+        let result = resolve_parent(
+            &child,
+            &[
+                String::from("D:\\missing\\gpt_ext4.vhdx"),
+                String::from("\\\\?\\Volume{1}\\vms\\gpt_ext4.vhdx"),
+            ],
+        )
+        .unwrap();
+        assert!(result.ends_with("vhdx/gpt_ext4.vhdx"));
+
+        assert!(resolve_parent(&child, &[String::from("missing.vhdx")]).is_none());
+        assert!(resolve_parent(&child, &[String::from("gpt_ext4_child.vhdx")]).is_none());
+    }
+}
@@ -1,6 +1,16 @@
 use crate::filesystem::disks::error::DiskError;
-use calf::calf::CalfReader;
-use std::{fs::File, io::BufReader};
+use calf::{
+    calf::{CalfReader, CalfReaderAction, QcowInfo},
+    format::{
+        header::{CalfHeader, Encryption},
+        level::{CalfLevel, ExtFsLevel},
+    },
+};
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufReader, Read, Seek, SeekFrom},
+};
 use tracing::error;
 
 /// Return a reader to access the OS of a QCOW disk image
@@ -18,16 +28,194 @@ pub(crate) fn qcow_reader(path: &str) -> Result<CalfReader<File>, DiskError> {
     Ok(calf)
 }
 
+/// A `Read` + `Seek` reader over the guest disk of a QCOW image
+///
+/// Unlike the calf `OsReader` this reader owns the QCOW file, so it can be shared by the accessor.
+/// Unallocated clusters are returned as zeros
+pub(crate) struct QcowDisk {
+    calf: CalfReader<File>,
+    info: QcowInfo,
+    /// Size of a QCOW cluster in bytes
+    cluster_size: u64,
+    /// Position of the reader in the guest disk
+    position: u64,
+    /// Level one index of the cached level two table
+    level2_key: Option<usize>,
+    level2_table: Vec<ExtFsLevel>,
+    /// Guest cluster number of the cached cluster
+    cluster_key: Option<u64>,
+    cluster: Vec<u8>,
+}
+
+impl QcowDisk {
+    /// Open a QCOW file and parse the header and level one table
+    pub(crate) fn open(path: &str) -> Result<Self, DiskError> {
+        let mut calf = qcow_reader(path)?;
+        let header = match calf.header() {
+            Ok(result) => result,
+            Err(err) => {
+                error!("Could not parse the QCOW header: {err:?}");
+                return Err(DiskError::QcowHeader);
+            }
+        };
+        if header.encryption_method != Encryption::None {
+            error!(
+                "QCOW image is encrypted with {:?}. This is not supported",
+                header.encryption_method
+            );
+            return Err(DiskError::QcowEncrypted);
+        }
+        let level1_table = match calf.level1_entries() {
+            Ok(result) => result,
+            Err(err) => {
+                error!("Could not parse the QCOW level one table: {err:?}");
+                return Err(DiskError::QcowHeader);
+            }
+        };
+
+        let cluster_size = 1 << header.cluster_block_bits_count;
+        Ok(Self {
+            calf,
+            info: QcowInfo {
+                header,
+                level1_table,
+            },
+            cluster_size,
+            position: 0,
+            level2_key: None,
+            level2_table: Vec::new(),
+            cluster_key: None,
+            cluster: Vec::new(),
+        })
+    }
+
+    /// Size of the guest disk in bytes
+    pub(crate) fn size(&self) -> u64 {
+        self.info.header.size
+    }
+
+    /// Load the guest cluster into the cache
+    fn load_cluster(&mut self, cluster_key: u64) -> io::Result<()> {
+        if self.cluster_key == Some(cluster_key) {
+            return Ok(());
+        }
+
+        let level2_entries = self.cluster_size / 8;
+        let level1_key = (cluster_key / level2_entries) as usize;
+        let level1_offset = self
+            .info
+            .level1_table
+            .get(level1_key)
+            .map_or(0, |entry| entry.offset);
+
+        self.cluster_key = Some(cluster_key);
+        self.cluster = vec![0; self.cluster_size as usize];
+        // An offset of zero means the level two table or cluster has never been written
+        if level1_offset == 0 {
+            return Ok(());
+        }
+
+        if self.level2_key != Some(level1_key) {
+            self.level2_table = self
+                .calf
+                .levels(level1_offset, self.cluster_size as u32)
+                .map_err(|err| {
+                    io::Error::other(format!("could not read QCOW level two table: {err:?}"))
+                })?;
+            self.level2_key = Some(level1_key);
+        }
+
+        let Some(entry) = self
+            .level2_table
+            .get((cluster_key % level2_entries) as usize)
+        else {
+            return Ok(());
+        };
+        if entry.offset == 0 {
+            return Ok(());
+        }
+        if entry.is_compressed {
+            self.cluster_key = None;
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "compressed QCOW clusters are not supported",
+            ));
+        }
+
+        self.calf.fs.seek(SeekFrom::Start(entry.offset))?;
+        if let Err(err) = self.calf.fs.read_exact(&mut self.cluster) {
+            self.cluster_key = None;
+            return Err(err);
+        }
+        Ok(())
+    }
+}
+
+impl Read for QcowDisk {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let size = self.size();
+        if buf.is_empty() || self.position >= size {
+            return Ok(0);
+        }
+
+        self.load_cluster(self.position / self.cluster_size)?;
+        let cluster_offset = self.position % self.cluster_size;
+        let read_len = (self.cluster_size - cluster_offset)
+            .min(size - self.position)
+            .min(buf.len() as u64) as usize;
+
+        let start = cluster_offset as usize;
+        buf[..read_len].copy_from_slice(&self.cluster[start..start + read_len]);
+        self.position += read_len as u64;
+        Ok(read_len)
+    }
+}
+
+impl Seek for QcowDisk {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+            SeekFrom::End(offset) => self.size().checked_add_signed(offset),
+        };
+
+        match new_pos {
+            Some(position) => {
+                self.position = position;
+                Ok(self.position)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+impl fmt::Debug for QcowDisk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QcowDisk")
+            .field("size", &self.info.header.size)
+            .field("cluster_size", &self.cluster_size)
+            .field("position", &self.position)
+            .finish_non_exhaustive()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::filesystem::disks::qcow::qcow_reader;
+    use crate::filesystem::disks::qcow::{QcowDisk, qcow_reader};
     use calf::{
         bootsector::boot::PartitionType,
         calf::{CalfReaderAction, QcowInfo},
         format::header::CalfHeader,
     };
     use ext4_fs::extfs::{Ext4Reader, Ext4ReaderAction};
-    use std::{io::BufReader, path::PathBuf};
+    use std::{
+        fs::read,
+        io::{BufReader, Read, Seek, SeekFrom},
+        path::PathBuf,
+    };
 
     #[test]
     fn test_qcow_reader() {
@@ -68,4 +256,26 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_qcow_disk() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/qcow/gpt_ext4.qcow2");
+        let mut raw_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        raw_location.push("tests/test_data/disks/raw/gpt_ext4.raw");
+
+        let mut disk = QcowDisk::open(test_location.to_str().unwrap()).unwrap();
+        let raw = read(raw_location).unwrap();
+        assert_eq!(disk.size(), raw.len() as u64);
+
+        // Unallocated clusters must read as zeros
+        let mut guest = Vec::new();
+        disk.read_to_end(&mut guest).unwrap();
+        assert_eq!(guest, raw);
+
+        disk.seek(SeekFrom::Start(512)).unwrap();
+        let mut sig = [0u8; 8];
+        disk.read_exact(&mut sig).unwrap();
+        assert_eq!(&sig, b"EFI PART");
+    }
 }
@@ -0,0 +1,15 @@
+use std::{fs::read, path::PathBuf};
+
+/// Path to a virtual disk test file. Ex: `tests/test_data/disks/vhd/gpt_ext4_fixed.vhd`
+pub(crate) fn disk_location(format: &str, name: &str) -> String {
+    let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    test_location.push("tests/test_data/disks");
+    test_location.push(format);
+    test_location.push(name);
+    test_location.to_str().unwrap().to_string()
+}
+
+/// Read the raw disk that all virtual disk test files were created from
+pub(crate) fn raw_disk() -> Vec<u8> {
+    read(disk_location("raw", "gpt_ext4.raw")).unwrap()
+}
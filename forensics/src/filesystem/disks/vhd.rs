@@ -0,0 +1,593 @@
+/**
+ * Virtual Hard Disk (VHD) is the disk format used by Virtual PC, early Hyper-V, and Azure
+ * A VHD ends with a 512 byte footer. Fixed disks store the guest disk before the footer.
+ * Dynamic and differencing disks store the guest disk in blocks tracked by a Block Allocation Table (BAT)
+ *
+ * Differencing disks only contain sectors that changed. Other sectors are read from the parent disk
+ *
+ * References:
+ *  `https://github.com/libyal/libvhdi/blob/main/documentation/Virtual%20Hard%20Disk%20(VHD)%20image%20format.asciidoc`
+ *
+ * Other Parsers:
+ *  `https://github.com/libyal/libvhdi`
+ */
+use crate::{
+    filesystem::disks::{
+        error::DiskError,
+        parent::{MAX_PARENT_DEPTH, resolve_parent},
+    },
+    utils::{
+        nom_helper::{Endian, nom_data, nom_unsigned_eight_bytes, nom_unsigned_four_bytes},
+        strings::{extract_utf8_string, extract_utf16_string},
+    },
+};
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    path::Path,
+};
+use tracing::{error, warn};
+
+/// Signature of the VHD footer
+const FOOTER_SIGNATURE: &[u8; 8] = b"conectix";
+/// Signature of the dynamic disk header
+const DYNAMIC_SIGNATURE: &[u8; 8] = b"cxsparse";
+const FOOTER_SIZE: u64 = 512;
+const DYNAMIC_HEADER_SIZE: u64 = 1024;
+const SECTOR_SIZE: u64 = 512;
+/// BAT entry for a block that has never been written
+const UNALLOCATED: u32 = 0xffffffff;
+/// Max supported block size. Hyper-V uses 2MB blocks
+const MAX_BLOCK_SIZE: u32 = 256 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum VhdType {
+    Fixed,
+    Dynamic,
+    Differencing,
+}
+
+/// A `Read` + `Seek` reader over the guest disk of a VHD file
+pub(crate) struct VhdDisk {
+    reader: BufReader<File>,
+    disk_type: VhdType,
+    /// Size of the guest disk in bytes
+    size: u64,
+    /// Size of a block in bytes. Zero for fixed disks
+    block_size: u64,
+    /// Size of the sector bitmap at the start of every block. Always a multiple of the sector size
+    bitmap_size: u64,
+    /// Sector offset to each block
+    bat: Vec<u32>,
+    /// Parent disk for differencing disks
+    parent: Option<Box<VhdDisk>>,
+    /// Position of the reader in the guest disk
+    position: u64,
+    /// Index of the cached sector bitmap
+    bitmap_key: Option<usize>,
+    bitmap: Vec<u8>,
+}
+
+/// The VHD footer. Also copied to the start of dynamic disks
+#[derive(Debug)]
+struct VhdFooter {
+    data_offset: u64,
+    current_size: u64,
+    disk_type: u32,
+}
+
+/// Header for dynamic and differencing disks
+#[derive(Debug)]
+struct DynamicHeader {
+    table_offset: u64,
+    max_table_entries: u32,
+    block_size: u32,
+    /// Parent file name in UTF16 big endian
+    parent_name: String,
+    locators: Vec<ParentLocator>,
+}
+
+/// Location of a parent path stored in a differencing disk
+#[derive(Debug)]
+struct ParentLocator {
+    platform: String,
+    length: u32,
+    offset: u64,
+}
+
+impl VhdDisk {
+    /// Open a VHD file. The parents of differencing disks are opened automatically
+    pub(crate) fn open(path: &str) -> Result<Self, DiskError> {
+        open_chain(Path::new(path), 0)
+    }
+
+    /// Load the sector bitmap for a block into the cache
+    fn load_bitmap(&mut self, block: usize, block_offset: u64) -> io::Result<()> {
+        if self.bitmap_key == Some(block) {
+            return Ok(());
+        }
+        self.bitmap_key = None;
+        self.reader.seek(SeekFrom::Start(block_offset))?;
+        self.bitmap = vec![0; self.bitmap_size as usize];
+        self.reader.read_exact(&mut self.bitmap)?;
+        self.bitmap_key = Some(block);
+        Ok(())
+    }
+
+    /// Read from the parent disk or return zeros if there is no parent
+    fn read_parent(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        if let Some(parent) = self.parent.as_mut() {
+            parent.seek(SeekFrom::Start(self.position))?;
+            return parent.read_exact(buf);
+        }
+        buf.fill(0);
+        Ok(())
+    }
+}
+
+impl Read for VhdDisk {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() || self.position >= self.size {
+            return Ok(0);
+        }
+        let remaining = (self.size - self.position).min(buf.len() as u64);
+
+        if self.disk_type == VhdType::Fixed {
+            self.reader.seek(SeekFrom::Start(self.position))?;
+            let read_len = self.reader.read(&mut buf[..remaining as usize])?;
+            self.position += read_len as u64;
+            return Ok(read_len);
+        }
+
+        let block = (self.position / self.block_size) as usize;
+        let offset = self.position % self.block_size;
+        let mut read_len = (self.block_size - offset).min(remaining) as usize;
+        let entry = self.bat.get(block).copied().unwrap_or(UNALLOCATED);
+
+        if entry == UNALLOCATED {
+            self.read_parent(&mut buf[..read_len])?;
+            self.position += read_len as u64;
+            return Ok(read_len);
+        }
+
+        let block_offset = entry as u64 * SECTOR_SIZE;
+        if self.disk_type == VhdType::Differencing {
+            self.load_bitmap(block, block_offset)?;
+            // Read the run of sectors that are all in the child or all in the parent
+            let first = offset / SECTOR_SIZE;
+            let present = sector_present(&self.bitmap, first);
+            let mut sector = first + 1;
+            while sector * SECTOR_SIZE < offset + read_len as u64
+                && sector_present(&self.bitmap, sector) == present
+            {
+                sector += 1;
+            }
+            read_len = read_len.min((sector * SECTOR_SIZE - offset) as usize);
+            if !present {
+                self.read_parent(&mut buf[..read_len])?;
+                self.position += read_len as u64;
+                return Ok(read_len);
+            }
+        }
+
+        self.reader
+            .seek(SeekFrom::Start(block_offset + self.bitmap_size + offset))?;
+        self.reader.read_exact(&mut buf[..read_len])?;
+        self.position += read_len as u64;
+        Ok(read_len)
+    }
+}
+
+impl Seek for VhdDisk {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+        };
+
+        match new_pos {
+            Some(position) => {
+                self.position = position;
+                Ok(self.position)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+impl fmt::Debug for VhdDisk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VhdDisk")
+            .field("disk_type", &self.disk_type)
+            .field("size", &self.size)
+            .field("block_size", &self.block_size)
+            .field("position", &self.position)
+            .field("parent", &self.parent)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Open a VHD file and any parent disks
+fn open_chain(path: &Path, depth: usize) -> Result<VhdDisk, DiskError> {
+    if depth > MAX_PARENT_DEPTH {
+        error!("Reached max VHD parent depth at {}", path.display());
+        return Err(DiskError::ParentDisk);
+    }
+    let file = match File::open(path) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Failed to open VHD file {}: {err:?}", path.display());
+            return Err(DiskError::Vhd);
+        }
+    };
+    let mut reader = BufReader::new(file);
+    let footer = read_footer(&mut reader)?;
+
+    let disk_type = match footer.disk_type {
+        2 => VhdType::Fixed,
+        3 => VhdType::Dynamic,
+        4 => VhdType::Differencing,
+        _ => {
+            error!("Unsupported VHD disk type: {}", footer.disk_type);
+            return Err(DiskError::VhdFooter);
+        }
+    };
+
+    let mut disk = VhdDisk {
+        reader,
+        disk_type,
+        size: footer.current_size,
+        block_size: 0,
+        bitmap_size: 0,
+        bat: Vec::new(),
+        parent: None,
+        position: 0,
+        bitmap_key: None,
+        bitmap: Vec::new(),
+    };
+    if disk_type == VhdType::Fixed {
+        return Ok(disk);
+    }
+
+    let data = read_bytes(&mut disk.reader, footer.data_offset, DYNAMIC_HEADER_SIZE)?;
+    let header = match parse_dynamic_header(&data) {
+        Ok((_, result)) => result,
+        Err(_err) => {
+            error!("Could not parse VHD dynamic disk header");
+            return Err(DiskError::VhdDynamic);
+        }
+    };
+    if header.block_size < SECTOR_SIZE as u32
+        || header.block_size > MAX_BLOCK_SIZE
+        || !header.block_size.is_power_of_two()
+    {
+        error!("Invalid VHD block size: {}", header.block_size);
+        return Err(DiskError::VhdDynamic);
+    }
+    disk.block_size = header.block_size as u64;
+    // One bit for every sector in the block
+    disk.bitmap_size = (disk.block_size / SECTOR_SIZE)
+        .div_ceil(8)
+        .next_multiple_of(SECTOR_SIZE);
+
+    let blocks = disk.size.div_ceil(disk.block_size);
+    if (header.max_table_entries as u64) < blocks {
+        error!(
+            "VHD BAT has {} entries but disk requires {blocks} blocks",
+            header.max_table_entries
+        );
+        return Err(DiskError::VhdDynamic);
+    }
+    let table = read_bytes(&mut disk.reader, header.table_offset, blocks * 4)?;
+    disk.bat = table
+        .chunks_exact(4)
+        .map(|entry| u32::from_be_bytes([entry[0], entry[1], entry[2], entry[3]]))
+        .collect();
+
+    if disk_type == VhdType::Differencing {
+        let hints = parent_hints(&mut disk.reader, &header);
+        let Some(parent_path) = resolve_parent(path, &hints) else {
+            error!(
+                "Could not find the parent of VHD {}. Parent paths: {hints:?}",
+                path.display()
+            );
+            return Err(DiskError::ParentDisk);
+        };
+        let parent = open_chain(&parent_path, depth + 1)?;
+        if parent.size != disk.size {
+            warn!(
+                "VHD parent {} size {} does not match child size {}",
+                parent_path.display(),
+                parent.size,
+                disk.size
+            );
+        }
+        disk.parent = Some(Box::new(parent));
+    }
+
+    Ok(disk)
+}
+
+/// Read the footer at the end of the file. Dynamic disks also have a copy at the start of the file
+fn read_footer<T: Read + Seek>(reader: &mut T) -> Result<VhdFooter, DiskError> {
+    let file_size = match reader.seek(SeekFrom::End(0)) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Could not get VHD file size: {err:?}");
+            return Err(DiskError::VhdFooter);
+        }
+    };
+    if file_size < FOOTER_SIZE {
+        error!("VHD file is too small: {file_size}");
+        return Err(DiskError::VhdFooter);
+    }
+
+    for offset in [file_size - FOOTER_SIZE, 0] {
+        let data = read_bytes(reader, offset, FOOTER_SIZE)?;
+        if !data.starts_with(FOOTER_SIGNATURE) {
+            continue;
+        }
+        if vhd_checksum(&data, 64) != u32::from_be_bytes([data[64], data[65], data[66], data[67]]) {
+            warn!("VHD footer at offset {offset} has an invalid checksum");
+            continue;
+        }
+        match parse_footer(&data) {
+            Ok((_, footer)) => return Ok(footer),
+            Err(_err) => {
+                warn!("Could not parse VHD footer at offset {offset}");
+            }
+        }
+    }
+
+    error!("Could not find a valid VHD footer");
+    Err(DiskError::VhdFooter)
+}
+
+/// Parse the VHD footer
+fn parse_footer(data: &[u8]) -> nom::IResult<&[u8], VhdFooter> {
+    let (input, _signature) = nom_data(data, FOOTER_SIGNATURE.len() as u64)?;
+    let (input, _features) = nom_unsigned_four_bytes(input, Endian::Be)?;
+    let (input, _version) = nom_unsigned_four_bytes(input, Endian::Be)?;
+    let (input, data_offset) = nom_unsigned_eight_bytes(input, Endian::Be)?;
+    let (input, _timestamp) = nom_unsigned_four_bytes(input, Endian::Be)?;
+    let (input, _creator_app) = nom_unsigned_four_bytes(input, Endian::Be)?;
+    let (input, _creator_version) = nom_unsigned_four_bytes(input, Endian::Be)?;
+    let (input, _creator_os) = nom_unsigned_four_bytes(input, Endian::Be)?;
+    let (input, _original_size) = nom_unsigned_eight_bytes(input, Endian::Be)?;
+    let (input, current_size) = nom_unsigned_eight_bytes(input, Endian::Be)?;
+    let (input, _geometry) = nom_unsigned_four_bytes(input, Endian::Be)?;
+    let (input, disk_type) = nom_unsigned_four_bytes(input, Endian::Be)?;
+
+    let footer = VhdFooter {
+        data_offset,
+        current_size,
+        disk_type,
+    };
+    Ok((input, footer))
+}
+
+/// Parse the dynamic disk header
+fn parse_dynamic_header(data: &[u8]) -> nom::IResult<&[u8], DynamicHeader> {
+    let (input, signature) = nom_data(data, DYNAMIC_SIGNATURE.len() as u64)?;
+    if signature != DYNAMIC_SIGNATURE {
+        return Err(nom::Err::Failure(nom::error::Error::new(
+            data,
+            nom::error::ErrorKind::Tag,
+        )));
+    }
+    let (input, _data_offset) = nom_unsigned_eight_bytes(input, Endian::Be)?;
+    let (input, table_offset) = nom_unsigned_eight_bytes(input, Endian::Be)?;
+    let (input, _version) = nom_unsigned_four_bytes(input, Endian::Be)?;
+    let (input, max_table_entries) = nom_unsigned_four_bytes(input, Endian::Be)?;
+    let (input, block_size) = nom_unsigned_four_bytes(input, Endian::Be)?;
+    let (input, _checksum) = nom_unsigned_four_bytes(input, Endian::Be)?;
+    let (input, _parent_id) = nom_data(input, 16)?;
+    let (input, _parent_timestamp) = nom_unsigned_four_bytes(input, Endian::Be)?;
+    let (input, _reserved) = nom_unsigned_four_bytes(input, Endian::Be)?;
+
+    let name_size: u16 = 512;
+    let (mut input, name_data) = nom_data(input, name_size as u64)?;
+    let name: Vec<u16> = name_data
+        .chunks_exact(2)
+        .map(|value| u16::from_be_bytes([value[0], value[1]]))
+        .take_while(|value| *value != 0)
+        .collect();
+
+    let max_locators = 8;
+    let mut locators = Vec::new();
+    for _ in 0..max_locators {
+        let (remaining, platform) = nom_data(input, 4)?;
+        let (remaining, _data_space) = nom_unsigned_four_bytes(remaining, Endian::Be)?;
+        let (remaining, length) = nom_unsigned_four_bytes(remaining, Endian::Be)?;
+        let (remaining, _reserved) = nom_unsigned_four_bytes(remaining, Endian::Be)?;
+        let (remaining, offset) = nom_unsigned_eight_bytes(remaining, Endian::Be)?;
+        input = remaining;
+
+        if platform == [0, 0, 0, 0] {
+            continue;
+        }
+        locators.push(ParentLocator {
+            platform: extract_utf8_string(platform),
+            length,
+            offset,
+        });
+    }
+
+    let header = DynamicHeader {
+        table_offset,
+        max_table_entries,
+        block_size,
+        parent_name: String::from_utf16_lossy(&name),
+        locators,
+    };
+    Ok((input, header))
+}
+
+/// Collect the parent paths from the parent locators and parent name
+fn parent_hints<T: Read + Seek>(reader: &mut T, header: &DynamicHeader) -> Vec<String> {
+    let mut hints = Vec::new();
+    // Relative paths are preferred over absolute paths
+    for platform in ["W2ru", "W2ku", "Wi2r", "Wi2k"] {
+        for locator in header
+            .locators
+            .iter()
+            .filter(|locator| locator.platform == platform)
+        {
+            let max_path = 4096;
+            if locator.length == 0 || locator.length > max_path {
+                continue;
+            }
+            let Ok(data) = read_bytes(reader, locator.offset, locator.length as u64) else {
+                continue;
+            };
+            let value = if platform.ends_with('u') {
+                extract_utf16_string(&data)
+            } else {
+                extract_utf8_string(&data)
+            };
+            hints.push(value);
+        }
+    }
+    hints.push(header.parent_name.clone());
+    hints
+}
+
+/// Check if a sector in a differencing block is stored in the child. The first sector is the most significant bit
+fn sector_present(bitmap: &[u8], sector: u64) -> bool {
+    let bits = 8;
+    bitmap
+        .get((sector / bits) as usize)
+        .is_some_and(|value| value & (0x80 >> (sector % bits)) != 0)
+}
+
+/// One's complement of the sum of all bytes, excluding the stored checksum
+fn vhd_checksum(data: &[u8], checksum_offset: usize) -> u32 {
+    let checksum_size = 4;
+    let sum = data
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !(checksum_offset..checksum_offset + checksum_size).contains(index))
+        .fold(0u32, |sum, (_, value)| sum.wrapping_add(*value as u32));
+    !sum
+}
+
+/// Read bytes from the VHD file
+fn read_bytes<T: Read + Seek>(
+    reader: &mut T,
+    offset: u64,
+    size: u64,
+) -> Result<Vec<u8>, DiskError> {
+    // Metadata should never be this large. A 2TB disk with 2MB blocks has a 4MB BAT
+    let max_size = 256 * 1024 * 1024;
+    if size > max_size {
+        error!("VHD structure at offset {offset} is too large: {size}");
+        return Err(DiskError::VhdDynamic);
+    }
+    if let Err(err) = reader.seek(SeekFrom::Start(offset)) {
+        error!("Could not seek to VHD offset {offset}: {err:?}");
+        return Err(DiskError::VhdDynamic);
+    }
+
+    let mut data = vec![0; size as usize];
+    if let Err(err) = reader.read_exact(&mut data) {
+        error!("Could not read VHD data at offset {offset}: {err:?}");
+        return Err(DiskError::VhdDynamic);
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{VhdDisk, VhdType, sector_present, vhd_checksum};
+    use crate::filesystem::disks::{
+        error::DiskError,
+        test_disks::{disk_location, raw_disk},
+    };
+    use std::{
+        fs::{copy, create_dir_all},
+        io::{Read, Seek, SeekFrom},
+        path::Path,
+    };
+
+    #[test]
+    fn test_vhd_fixed() {
+        let raw = raw_disk();
+        let mut disk = VhdDisk::open(&disk_location("vhd", "gpt_ext4_fixed.vhd")).unwrap();
+        assert_eq!(disk.disk_type, VhdType::Fixed);
+        assert_eq!(disk.size, raw.len() as u64);
+
+        let mut media = Vec::new();
+        disk.read_to_end(&mut media).unwrap();
+        assert_eq!(media, raw);
+    }
+
+    #[test]
+    fn test_vhd_dynamic() {
+        let raw = raw_disk();
+        let mut disk = VhdDisk::open(&disk_location("vhd", "gpt_ext4_dynamic.vhd")).unwrap();
+        assert_eq!(disk.disk_type, VhdType::Dynamic);
+        assert_eq!(disk.block_size, 65536);
+        assert!(disk.parent.is_none());
+
+        let mut media = Vec::new();
+        disk.read_to_end(&mut media).unwrap();
+        assert_eq!(media, raw);
+
+        // Read across a block boundary
+        disk.seek(SeekFrom::Start(65536 - 8)).unwrap();
+        let mut buf = [0u8; 16];
+        disk.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, raw[65536 - 8..65536 + 8]);
+    }
+
+    #[test]
+    fn test_vhd_differencing() {
+        let raw = raw_disk();
+        let mut disk = VhdDisk::open(&disk_location("vhd", "gpt_ext4_child.vhd")).unwrap();
+        assert_eq!(disk.disk_type, VhdType::Differencing);
+        assert!(disk.parent.is_some());
+
+        let mut media = Vec::new();
+        disk.read_to_end(&mut media).unwrap();
+        assert_eq!(media.len(), raw.len());
+
+        // Only the sector with the hostname file was changed in the child
+        let hostname = 87040;
+        assert_eq!(&media[hostname..hostname + 14], b"artemis-child\n");
+        assert_eq!(media[..hostname], raw[..hostname]);
+        assert_eq!(media[hostname + 512..], raw[hostname + 512..]);
+    }
+
+    #[test]
+    fn test_vhd_missing_parent() {
+        let dir = Path::new("./tmp/vhd_missing_parent");
+        create_dir_all(dir).unwrap();
+        let child = dir.join("gpt_ext4_child.vhd");
+        copy(disk_location("vhd", "gpt_ext4_child.vhd"), &child).unwrap();
+
+        let result = VhdDisk::open(child.to_str().unwrap());
+        assert!(matches!(result, Err(DiskError::ParentDisk)));
+    }
+
+    #[test]
+    fn test_sector_present() {
+        let bitmap = [0b1000_0001, 0b0100_0000];
+        assert!(sector_present(&bitmap, 0));
+        assert!(!sector_present(&bitmap, 1));
+        assert!(sector_present(&bitmap, 7));
+        assert!(sector_present(&bitmap, 9));
+        assert!(!sector_present(&bitmap, 100));
+    }
+
+    #[test]
+    fn test_vhd_checksum() {
+        let mut data = vec![1u8; 16];
+        data[4..8].copy_from_slice(&[0xff, 0xff, 0xff, 0xff]);
+        assert_eq!(vhd_checksum(&data, 4), !12);
+    }
+}
@@ -0,0 +1,770 @@
+/**
+ * Virtual Hard Disk v2 (VHDX) is the disk format used by Hyper-V
+ * The guest disk is stored in payload blocks tracked by a Block Allocation Table (BAT). Disk properties are stored in a metadata region
+ *
+ * Differencing disks only contain blocks or sectors that changed. Sector bitmap blocks track which sectors are in the child.
+ * Other sectors are read from the parent disk. Parents are found using the parent locator in the metadata region
+ *
+ * The VHDX log is not replayed. Disks that were not cleanly closed may be missing recent writes
+ *
+ * References:
+ *  `https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-vhdx/83e061f8-f6e2-4de1-91bd-5d518a43d477`
+ *  `https://github.com/libyal/libvhdi/blob/main/documentation/Virtual%20Hard%20Disk%20version%202%20(VHDX)%20image%20format.asciidoc`
+ *
+ * Other Parsers:
+ *  `https://github.com/libyal/libvhdi`
+ */
+use crate::{
+    filesystem::disks::{
+        error::DiskError,
+        parent::{MAX_PARENT_DEPTH, resolve_parent},
+    },
+    utils::{
+        nom_helper::{
+            Endian, nom_data, nom_unsigned_eight_bytes, nom_unsigned_four_bytes,
+            nom_unsigned_two_bytes,
+        },
+        strings::extract_utf16_string,
+        uuid::format_guid_le_bytes,
+    },
+};
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::File,
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    path::Path,
+};
+use tracing::{error, warn};
+
+const FILE_SIGNATURE: &[u8; 8] = b"vhdxfile";
+const HEADER_SIGNATURE: &[u8; 4] = b"head";
+const REGION_SIGNATURE: &[u8; 4] = b"regi";
+const METADATA_SIGNATURE: &[u8; 8] = b"metadata";
+const HEADER_SIZE: u64 = 4096;
+const REGION_TABLE_SIZE: u64 = 64 * 1024;
+const METADATA_TABLE_SIZE: u64 = 64 * 1024;
+const MB: u64 = 1024 * 1024;
+
+const BAT_REGION: &str = "2dc27766-f623-4200-9d64-115e9bfd4a08";
+const METADATA_REGION: &str = "8b7ca206-4790-4b9a-b8fe-575f050f886e";
+const FILE_PARAMETERS: &str = "caa16737-fa36-4d43-b3b6-33f0aa44e76b";
+const VIRTUAL_DISK_SIZE: &str = "2fa54224-cd1b-4876-b211-5dbed83bf4b8";
+const LOGICAL_SECTOR_SIZE: &str = "8141bf1d-a96f-4709-ba47-f233a8faab5f";
+const PARENT_LOCATOR: &str = "a8d35f2d-b30b-454d-abf7-d3d84834ab0c";
+
+/// A `Read` + `Seek` reader over the guest disk of a VHDX file
+pub(crate) struct VhdxDisk {
+    reader: BufReader<File>,
+    /// Size of the guest disk in bytes
+    size: u64,
+    /// Size of a payload block in bytes
+    block_size: u64,
+    logical_sector_size: u64,
+    /// Number of payload blocks described by one sector bitmap block
+    chunk_ratio: u64,
+    bat: Vec<u64>,
+    /// Identifies the data in this disk. Differencing children store the parent `data_write_guid`
+    data_write_guid: String,
+    /// Parent disk for differencing disks
+    parent: Option<Box<VhdxDisk>>,
+    /// Position of the reader in the guest disk
+    position: u64,
+    /// Index of the cached sector bitmap block
+    bitmap_key: Option<usize>,
+    bitmap: Vec<u8>,
+}
+
+/// State of a payload or sector bitmap block in the BAT
+#[derive(Debug, PartialEq)]
+enum BlockState {
+    NotPresent,
+    Undefined,
+    Zero,
+    Unmapped,
+    FullyPresent,
+    PartiallyPresent,
+    Unknown,
+}
+
+#[derive(Debug)]
+struct VhdxHeader {
+    sequence: u64,
+    data_write_guid: String,
+    log_guid: String,
+}
+
+#[derive(Debug)]
+struct Region {
+    guid: String,
+    offset: u64,
+    length: u32,
+    required: bool,
+}
+
+#[derive(Debug)]
+struct MetadataEntry {
+    guid: String,
+    offset: u32,
+    length: u32,
+}
+
+impl VhdxDisk {
+    /// Open a VHDX file. The parents of differencing disks are opened automatically
+    pub(crate) fn open(path: &str) -> Result<Self, DiskError> {
+        open_chain(Path::new(path), 0)
+    }
+
+    /// Load a sector bitmap block into the cache
+    fn load_bitmap(&mut self, chunk: usize) -> io::Result<()> {
+        if self.bitmap_key == Some(chunk) {
+            return Ok(());
+        }
+        self.bitmap_key = None;
+
+        let index = chunk * (self.chunk_ratio as usize + 1) + self.chunk_ratio as usize;
+        let entry = self.bat.get(index).copied().unwrap_or_default();
+        if block_state(entry) != BlockState::FullyPresent {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("VHDX sector bitmap block {chunk} is not present"),
+            ));
+        }
+        self.reader.seek(SeekFrom::Start(block_offset(entry)))?;
+        self.bitmap = vec![0; MB as usize];
+        self.reader.read_exact(&mut self.bitmap)?;
+        self.bitmap_key = Some(chunk);
+        Ok(())
+    }
+
+    /// Read from the parent disk or return zeros if there is no parent
+    fn read_parent(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        if let Some(parent) = self.parent.as_mut() {
+            parent.seek(SeekFrom::Start(self.position))?;
+            return parent.read_exact(buf);
+        }
+        buf.fill(0);
+        Ok(())
+    }
+}
+
+impl Read for VhdxDisk {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() || self.position >= self.size {
+            return Ok(0);
+        }
+
+        let block = self.position / self.block_size;
+        let offset = self.position % self.block_size;
+        let mut read_len = (self.block_size - offset)
+            .min(self.size - self.position)
+            .min(buf.len() as u64) as usize;
+        // A sector bitmap entry follows every chunk ratio payload entries
+        let entry = self
+            .bat
+            .get((block + block / self.chunk_ratio) as usize)
+            .copied()
+            .unwrap_or_default();
+
+        let in_child = match block_state(entry) {
+            BlockState::FullyPresent => true,
+            BlockState::NotPresent if self.parent.is_some() => false,
+            BlockState::NotPresent
+            | BlockState::Undefined
+            | BlockState::Zero
+            | BlockState::Unmapped => {
+                buf[..read_len].fill(0);
+                self.position += read_len as u64;
+                return Ok(read_len);
+            }
+            BlockState::PartiallyPresent => {
+                self.load_bitmap((block / self.chunk_ratio) as usize)?;
+                // Read the run of sectors that are all in the child or all in the parent
+                let chunk_offset = (block % self.chunk_ratio) * self.block_size + offset;
+                let first = chunk_offset / self.logical_sector_size;
+                let present = sector_present(&self.bitmap, first);
+                let mut sector = first + 1;
+                while sector * self.logical_sector_size < chunk_offset + read_len as u64
+                    && sector_present(&self.bitmap, sector) == present
+                {
+                    sector += 1;
+                }
+                read_len =
+                    read_len.min((sector * self.logical_sector_size - chunk_offset) as usize);
+                present
+            }
+            BlockState::Unknown => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unknown VHDX block state for block {block}: {entry:#x}"),
+                ));
+            }
+        };
+
+        if in_child {
+            self.reader
+                .seek(SeekFrom::Start(block_offset(entry) + offset))?;
+            self.reader.read_exact(&mut buf[..read_len])?;
+        } else {
+            self.read_parent(&mut buf[..read_len])?;
+        }
+        self.position += read_len as u64;
+        Ok(read_len)
+    }
+}
+
+impl Seek for VhdxDisk {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+        };
+
+        match new_pos {
+            Some(position) => {
+                self.position = position;
+                Ok(self.position)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+impl fmt::Debug for VhdxDisk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VhdxDisk")
+            .field("size", &self.size)
+            .field("block_size", &self.block_size)
+            .field("position", &self.position)
+            .field("parent", &self.parent)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Open a VHDX file and any parent disks
+fn open_chain(path: &Path, depth: usize) -> Result<VhdxDisk, DiskError> {
+    if depth > MAX_PARENT_DEPTH {
+        error!("Reached max VHDX parent depth at {}", path.display());
+        return Err(DiskError::ParentDisk);
+    }
+    let file = match File::open(path) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Failed to open VHDX file {}: {err:?}", path.display());
+            return Err(DiskError::Vhdx);
+        }
+    };
+    let mut reader = BufReader::new(file);
+
+    let signature = read_bytes(&mut reader, 0, FILE_SIGNATURE.len() as u64)?;
+    if signature != FILE_SIGNATURE {
+        error!("File is not a VHDX disk. Signature: {signature:?}");
+        return Err(DiskError::VhdxHeader);
+    }
+    let header = read_header(&mut reader)?;
+    if header.log_guid != "00000000-0000-0000-0000-000000000000" {
+        warn!(
+            "VHDX {} has a log that has not been replayed. Recent writes may be missing",
+            path.display()
+        );
+    }
+
+    let regions = read_regions(&mut reader)?;
+    let find_region = |guid: &str| {
+        regions
+            .iter()
+            .find(|region| region.guid == guid)
+            .ok_or_else(|| {
+                error!("VHDX is missing required region {guid}");
+                DiskError::VhdxHeader
+            })
+    };
+    let bat_region = find_region(BAT_REGION)?;
+    let metadata_region = find_region(METADATA_REGION)?;
+
+    let metadata = read_metadata(&mut reader, metadata_region)?;
+    let file_parameters = metadata_value(&metadata, FILE_PARAMETERS, 8)?;
+    let block_size = u32::from_le_bytes([
+        file_parameters[0],
+        file_parameters[1],
+        file_parameters[2],
+        file_parameters[3],
+    ]) as u64;
+    let has_parent = file_parameters[4] & 0x2 != 0;
+    let size_data = metadata_value(&metadata, VIRTUAL_DISK_SIZE, 8)?;
+    let mut size_bytes = [0; 8];
+    size_bytes.copy_from_slice(&size_data[..8]);
+    let size = u64::from_le_bytes(size_bytes);
+    let sector_data = metadata_value(&metadata, LOGICAL_SECTOR_SIZE, 4)?;
+    let logical_sector_size = u32::from_le_bytes([
+        sector_data[0],
+        sector_data[1],
+        sector_data[2],
+        sector_data[3],
+    ]) as u64;
+
+    // Block sizes are between 1MB and 256MB. Sectors are 512 or 4096 bytes
+    if !(MB..=256 * MB).contains(&block_size)
+        || !block_size.is_power_of_two()
+        || (logical_sector_size != 512 && logical_sector_size != 4096)
+    {
+        error!("Invalid VHDX block size {block_size} or sector size {logical_sector_size}");
+        return Err(DiskError::VhdxMetadata);
+    }
+    let chunk_ratio = (1 << 23) * logical_sector_size / block_size;
+
+    let payload_blocks = size.div_ceil(block_size);
+    let entries = if has_parent {
+        payload_blocks.div_ceil(chunk_ratio) * (chunk_ratio + 1)
+    } else {
+        payload_blocks + payload_blocks.saturating_sub(1) / chunk_ratio
+    };
+    let entry_size = 8;
+    if entries * entry_size > bat_region.length as u64 {
+        error!(
+            "VHDX BAT region is too small for {entries} entries: {}",
+            bat_region.length
+        );
+        return Err(DiskError::VhdxBat);
+    }
+    let bat = read_bytes(&mut reader, bat_region.offset, entries * entry_size)?
+        .chunks_exact(entry_size as usize)
+        .map(|entry| {
+            let mut value = [0; 8];
+            value.copy_from_slice(entry);
+            u64::from_le_bytes(value)
+        })
+        .collect();
+
+    let mut disk = VhdxDisk {
+        reader,
+        size,
+        block_size,
+        logical_sector_size,
+        chunk_ratio,
+        bat,
+        data_write_guid: header.data_write_guid,
+        parent: None,
+        position: 0,
+        bitmap_key: None,
+        bitmap: Vec::new(),
+    };
+    if !has_parent {
+        return Ok(disk);
+    }
+
+    let locator_data = metadata.get(PARENT_LOCATOR).ok_or_else(|| {
+        error!("VHDX differencing disk is missing the parent locator");
+        DiskError::VhdxMetadata
+    })?;
+    let locator = match parse_parent_locator(locator_data) {
+        Ok((_, result)) => result,
+        Err(_err) => {
+            error!("Could not parse VHDX parent locator");
+            return Err(DiskError::VhdxMetadata);
+        }
+    };
+    let hints: Vec<String> = ["relative_path", "absolute_win32_path", "volume_path"]
+        .iter()
+        .filter_map(|key| locator.get(*key).cloned())
+        .collect();
+    let Some(parent_path) = resolve_parent(path, &hints) else {
+        error!(
+            "Could not find the parent of VHDX {}. Parent paths: {hints:?}",
+            path.display()
+        );
+        return Err(DiskError::ParentDisk);
+    };
+
+    let parent = open_chain(&parent_path, depth + 1)?;
+    if let Some(linkage) = locator.get("parent_linkage") {
+        let linkage = linkage.trim_matches(['{', '}']).to_lowercase();
+        if linkage != parent.data_write_guid {
+            warn!(
+                "VHDX parent {} data write GUID {} does not match the child parent linkage {linkage}. The parent may have been modified",
+                parent_path.display(),
+                parent.data_write_guid
+            );
+        }
+    }
+    disk.parent = Some(Box::new(parent));
+
+    Ok(disk)
+}
+
+/// Read both headers and return the valid header with the highest sequence number
+fn read_header<T: Read + Seek>(reader: &mut T) -> Result<VhdxHeader, DiskError> {
+    let mut current: Option<VhdxHeader> = None;
+    for offset in [64 * 1024, 128 * 1024] {
+        let data = read_bytes(reader, offset, HEADER_SIZE)?;
+        if !data.starts_with(HEADER_SIGNATURE) || !valid_checksum(&data) {
+            warn!("VHDX header at offset {offset} is not valid");
+            continue;
+        }
+        let header = match parse_header(&data) {
+            Ok((_, result)) => result,
+            Err(_err) => {
+                warn!("Could not parse VHDX header at offset {offset}");
+                continue;
+            }
+        };
+        if current
+            .as_ref()
+            .is_none_or(|value| header.sequence > value.sequence)
+        {
+            current = Some(header);
+        }
+    }
+
+    current.ok_or_else(|| {
+        error!("VHDX does not have a valid header");
+        DiskError::VhdxHeader
+    })
+}
+
+/// Parse a VHDX header
+fn parse_header(data: &[u8]) -> nom::IResult<&[u8], VhdxHeader> {
+    let (input, _signature) = nom_unsigned_four_bytes(data, Endian::Le)?;
+    let (input, _checksum) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, sequence) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+    let guid_size: u8 = 16;
+    let (input, _file_write_guid) = nom_data(input, guid_size as u64)?;
+    let (input, data_write_guid) = nom_data(input, guid_size as u64)?;
+    let (input, log_guid) = nom_data(input, guid_size as u64)?;
+
+    let header = VhdxHeader {
+        sequence,
+        data_write_guid: format_guid_le_bytes(data_write_guid),
+        log_guid: format_guid_le_bytes(log_guid),
+    };
+    Ok((input, header))
+}
+
+/// Read the first valid region table
+fn read_regions<T: Read + Seek>(reader: &mut T) -> Result<Vec<Region>, DiskError> {
+    for offset in [192 * 1024, 256 * 1024] {
+        let data = read_bytes(reader, offset, REGION_TABLE_SIZE)?;
+        if !data.starts_with(REGION_SIGNATURE) || !valid_checksum(&data) {
+            warn!("VHDX region table at offset {offset} is not valid");
+            continue;
+        }
+        let regions = match parse_regions(&data) {
+            Ok((_, result)) => result,
+            Err(_err) => {
+                warn!("Could not parse VHDX region table at offset {offset}");
+                continue;
+            }
+        };
+        if let Some(region) = regions.iter().find(|region| {
+            region.required && region.guid != BAT_REGION && region.guid != METADATA_REGION
+        }) {
+            error!("VHDX has unsupported required region {}", region.guid);
+            return Err(DiskError::VhdxHeader);
+        }
+        return Ok(regions);
+    }
+
+    error!("VHDX does not have a valid region table");
+    Err(DiskError::VhdxHeader)
+}
+
+/// Parse the VHDX region table
+fn parse_regions(data: &[u8]) -> nom::IResult<&[u8], Vec<Region>> {
+    let (input, _signature) = nom_unsigned_four_bytes(data, Endian::Le)?;
+    let (input, _checksum) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, count) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (mut input, _reserved) = nom_unsigned_four_bytes(input, Endian::Le)?;
+
+    // Region tables support at most 2047 entries
+    let max_entries = 2047;
+    let mut regions = Vec::new();
+    for _ in 0..count.min(max_entries) {
+        let (remaining, guid) = nom_data(input, 16)?;
+        let (remaining, offset) = nom_unsigned_eight_bytes(remaining, Endian::Le)?;
+        let (remaining, length) = nom_unsigned_four_bytes(remaining, Endian::Le)?;
+        let (remaining, required) = nom_unsigned_four_bytes(remaining, Endian::Le)?;
+        input = remaining;
+
+        regions.push(Region {
+            guid: format_guid_le_bytes(guid),
+            offset,
+            length,
+            required: required & 0x1 != 0,
+        });
+    }
+    Ok((input, regions))
+}
+
+/// Read the metadata table and return the data for each metadata item
+fn read_metadata<T: Read + Seek>(
+    reader: &mut T,
+    region: &Region,
+) -> Result<HashMap<String, Vec<u8>>, DiskError> {
+    let data = read_bytes(reader, region.offset, METADATA_TABLE_SIZE)?;
+    if !data.starts_with(METADATA_SIGNATURE) {
+        error!("VHDX metadata table has an invalid signature");
+        return Err(DiskError::VhdxMetadata);
+    }
+    let entries = match parse_metadata_table(&data) {
+        Ok((_, result)) => result,
+        Err(_err) => {
+            error!("Could not parse VHDX metadata table");
+            return Err(DiskError::VhdxMetadata);
+        }
+    };
+
+    let mut metadata = HashMap::new();
+    for entry in entries {
+        if entry.offset as u64 + entry.length as u64 > region.length as u64 {
+            error!("VHDX metadata item {} is outside the region", entry.guid);
+            return Err(DiskError::VhdxMetadata);
+        }
+        let value = read_bytes(
+            reader,
+            region.offset + entry.offset as u64,
+            entry.length as u64,
+        )?;
+        metadata.insert(entry.guid, value);
+    }
+    Ok(metadata)
+}
+
+/// Parse the metadata table entries
+fn parse_metadata_table(data: &[u8]) -> nom::IResult<&[u8], Vec<MetadataEntry>> {
+    let (input, _signature) = nom_data(data, METADATA_SIGNATURE.len() as u64)?;
+    let (input, _reserved) = nom_unsigned_two_bytes(input, Endian::Le)?;
+    let (input, count) = nom_unsigned_two_bytes(input, Endian::Le)?;
+    let reserved_size: u8 = 20;
+    let (mut input, _reserved) = nom_data(input, reserved_size as u64)?;
+
+    // Metadata tables support at most 2047 entries
+    let max_entries = 2047;
+    let mut entries = Vec::new();
+    for _ in 0..count.min(max_entries) {
+        let (remaining, guid) = nom_data(input, 16)?;
+        let (remaining, offset) = nom_unsigned_four_bytes(remaining, Endian::Le)?;
+        let (remaining, length) = nom_unsigned_four_bytes(remaining, Endian::Le)?;
+        let (remaining, _flags) = nom_unsigned_four_bytes(remaining, Endian::Le)?;
+        let (remaining, _reserved) = nom_unsigned_four_bytes(remaining, Endian::Le)?;
+        input = remaining;
+
+        entries.push(MetadataEntry {
+            guid: format_guid_le_bytes(guid),
+            offset,
+            length,
+        });
+    }
+    Ok((input, entries))
+}
+
+/// Get a required metadata item with a minimum size
+fn metadata_value<'a>(
+    metadata: &'a HashMap<String, Vec<u8>>,
+    guid: &str,
+    min_size: usize,
+) -> Result<&'a [u8], DiskError> {
+    match metadata.get(guid) {
+        Some(value) if value.len() >= min_size => Ok(value),
+        _ => {
+            error!("VHDX is missing required metadata item {guid}");
+            Err(DiskError::VhdxMetadata)
+        }
+    }
+}
+
+/// Parse the parent locator key value pairs. Keys and values are UTF16
+fn parse_parent_locator(data: &[u8]) -> nom::IResult<&[u8], HashMap<String, String>> {
+    let (input, _locator_type) = nom_data(data, 16)?;
+    let (input, _reserved) = nom_unsigned_two_bytes(input, Endian::Le)?;
+    let (mut input, count) = nom_unsigned_two_bytes(input, Endian::Le)?;
+
+    let mut values = HashMap::new();
+    for _ in 0..count {
+        let (remaining, key_offset) = nom_unsigned_four_bytes(input, Endian::Le)?;
+        let (remaining, value_offset) = nom_unsigned_four_bytes(remaining, Endian::Le)?;
+        let (remaining, key_length) = nom_unsigned_two_bytes(remaining, Endian::Le)?;
+        let (remaining, value_length) = nom_unsigned_two_bytes(remaining, Endian::Le)?;
+        input = remaining;
+
+        let (key_start, _) = nom_data(data, key_offset as u64)?;
+        let (_, key) = nom_data(key_start, key_length as u64)?;
+        let (value_start, _) = nom_data(data, value_offset as u64)?;
+        let (_, value) = nom_data(value_start, value_length as u64)?;
+        values.insert(extract_utf16_string(key), extract_utf16_string(value));
+    }
+    Ok((input, values))
+}
+
+/// Get the state of a BAT entry. Stored in the lowest three bits
+fn block_state(entry: u64) -> BlockState {
+    match entry & 0x7 {
+        0 => BlockState::NotPresent,
+        1 => BlockState::Undefined,
+        2 => BlockState::Zero,
+        3 => BlockState::Unmapped,
+        6 => BlockState::FullyPresent,
+        7 => BlockState::PartiallyPresent,
+        _ => BlockState::Unknown,
+    }
+}
+
+/// Get the file offset of a BAT entry. Stored in megabytes in the upper 44 bits
+fn block_offset(entry: u64) -> u64 {
+    (entry >> 20) * MB
+}
+
+/// Check if a sector is stored in the child. The first sector is the least significant bit
+fn sector_present(bitmap: &[u8], sector: u64) -> bool {
+    let bits = 8;
+    bitmap
+        .get((sector / bits) as usize)
+        .is_some_and(|value| value & (1 << (sector % bits)) != 0)
+}
+
+/// Validate the CRC32C checksum stored at offset 4
+fn valid_checksum(data: &[u8]) -> bool {
+    let Some(stored) = data.get(4..8) else {
+        return false;
+    };
+    let stored = u32::from_le_bytes([stored[0], stored[1], stored[2], stored[3]]);
+    let mut copy = data.to_vec();
+    copy[4..8].fill(0);
+    crc32c(&copy) == stored
+}
+
+/// CRC32C (Castagnoli) checksum used by VHDX headers and region tables
+fn crc32c(data: &[u8]) -> u32 {
+    let polynomial = 0x82f63b78;
+    let mut crc = u32::MAX;
+    for value in data {
+        crc ^= *value as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ polynomial
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Read bytes from the VHDX file
+fn read_bytes<T: Read + Seek>(
+    reader: &mut T,
+    offset: u64,
+    size: u64,
+) -> Result<Vec<u8>, DiskError> {
+    // Metadata should never be this large. The BAT for a 64TB disk with 32MB blocks is 16MB
+    let max_size = 256 * MB;
+    if size > max_size {
+        error!("VHDX structure at offset {offset} is too large: {size}");
+        return Err(DiskError::VhdxHeader);
+    }
+    if let Err(err) = reader.seek(SeekFrom::Start(offset)) {
+        error!("Could not seek to VHDX offset {offset}: {err:?}");
+        return Err(DiskError::VhdxHeader);
+    }
+
+    let mut data = vec![0; size as usize];
+    if let Err(err) = reader.read_exact(&mut data) {
+        error!("Could not read VHDX data at offset {offset}: {err:?}");
+        return Err(DiskError::VhdxHeader);
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlockState, VhdxDisk, block_offset, block_state, crc32c, sector_present};
+    use crate::filesystem::disks::{
+        error::DiskError,
+        test_disks::{disk_location, raw_disk},
+    };
+    use std::{
+        fs::{copy, create_dir_all},
+        io::{Read, Seek, SeekFrom},
+        path::Path,
+    };
+
+    #[test]
+    fn test_vhdx_disk() {
+        let raw = raw_disk();
+        let mut disk = VhdxDisk::open(&disk_location("vhdx", "gpt_ext4.vhdx")).unwrap();
+        assert_eq!(disk.size, raw.len() as u64);
+        assert_eq!(disk.block_size, 1024 * 1024);
+        assert_eq!(disk.chunk_ratio, 4096);
+        assert!(disk.parent.is_none());
+        assert_eq!(disk.data_write_guid, "41111111-2222-3333-4444-555555555555");
+
+        let mut media = Vec::new();
+        disk.read_to_end(&mut media).unwrap();
+        assert_eq!(media, raw);
+
+        disk.seek(SeekFrom::Start(87040)).unwrap();
+        let mut buf = [0u8; 14];
+        disk.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"artemis-image\n");
+    }
+
+    #[test]
+    fn test_vhdx_differencing() {
+        let raw = raw_disk();
+        let mut disk = VhdxDisk::open(&disk_location("vhdx", "gpt_ext4_child.vhdx")).unwrap();
+        assert!(disk.parent.is_some());
+        assert_eq!(disk.size, raw.len() as u64);
+
+        let mut media = Vec::new();
+        disk.read_to_end(&mut media).unwrap();
+        // Only the sector with the hostname file is in the child
+        let hostname = 87040;
+        assert_eq!(&media[hostname..hostname + 14], b"artemis-child\n");
+        assert_eq!(media[..hostname], raw[..hostname]);
+        assert_eq!(media[hostname + 512..], raw[hostname + 512..]);
+    }
+
+    #[test]
+    fn test_vhdx_missing_parent() {
+        let dir = Path::new("./tmp/vhdx_missing_parent");
+        create_dir_all(dir).unwrap();
+        let child = dir.join("gpt_ext4_child.vhdx");
+        copy(disk_location("vhdx", "gpt_ext4_child.vhdx"), &child).unwrap();
+
+        let result = VhdxDisk::open(child.to_str().unwrap());
+        assert!(matches!(result, Err(DiskError::ParentDisk)));
+    }
+
+    #[test]
+    fn test_vhdx_not_vhdx() {
+        let result = VhdxDisk::open(&disk_location("raw", "gpt_ext4.raw"));
+        assert!(matches!(result, Err(DiskError::VhdxHeader)));
+    }
+
+    #[test]
+    fn test_block_state() {
+        assert_eq!(block_state(6 | (3 << 20)), BlockState::FullyPresent);
+        assert_eq!(block_offset(6 | (3 << 20)), 3 * 1024 * 1024);
+        assert_eq!(block_state(7), BlockState::PartiallyPresent);
+        assert_eq!(block_state(0), BlockState::NotPresent);
+        assert_eq!(block_state(4), BlockState::Unknown);
+    }
+
+    #[test]
+    fn test_sector_present() {
+        let bitmap = [0b1000_0001, 0b0000_0010];
+        assert!(sector_present(&bitmap, 0));
+        assert!(!sector_present(&bitmap, 1));
+        assert!(sector_present(&bitmap, 7));
+        assert!(sector_present(&bitmap, 9));
+        assert!(!sector_present(&bitmap, 100));
+    }
+
+    #[test]
+    fn test_crc32c() {
+        assert_eq!(crc32c(b"123456789"), 0xe3069283);
+    }
+}
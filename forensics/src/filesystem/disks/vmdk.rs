@@ -0,0 +1,748 @@
+/**
+ * Virtual Machine Disk (VMDK) is the disk format used by `VMware`
+ * A VMDK is described by a text descriptor that lists one or more extents. The descriptor is either a separate file
+ * or embedded in a monolithic sparse extent
+ *
+ * Supported extents:
+ *   FLAT/VMFS - Raw guest data
+ *   SPARSE - Hosted sparse extents. Grains are tracked by a grain directory and grain tables. Includes `streamOptimized` compressed grains
+ *   ZERO - Guest data that is always zero
+ *
+ * Snapshots (delta disks) only contain grains that changed. Other grains are read from the parent disk in `parentFileNameHint`
+ *
+ * References:
+ *  `https://github.com/libyal/libvmdk/blob/main/documentation/VMWare%20Virtual%20Disk%20Format%20(VMDK).asciidoc`
+ *
+ * Other Parsers:
+ *  `https://github.com/libyal/libvmdk`
+ */
+use crate::{
+    filesystem::disks::{
+        error::DiskError,
+        parent::{MAX_PARENT_DEPTH, resolve_parent},
+    },
+    utils::{
+        compression::decompress::decompress_zlib,
+        nom_helper::{
+            Endian, nom_unsigned_eight_bytes, nom_unsigned_four_bytes, nom_unsigned_one_byte,
+            nom_unsigned_two_bytes,
+        },
+        strings::extract_utf8_string,
+    },
+};
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    path::Path,
+    slice,
+};
+use tracing::{error, warn};
+
+/// Signature of a hosted sparse extent. `KDMV`
+const SPARSE_SIGNATURE: u32 = 0x564d444b;
+/// Signature of an ESX sparse extent. `COWD`
+const COWD_SIGNATURE: u32 = 0x44574f43;
+const SECTOR_SIZE: u64 = 512;
+/// Grain directory offset used by `streamOptimized` extents. The real offset is in the footer
+const GD_AT_END: u64 = 0xffffffffffffffff;
+/// Flag for extents with compressed grains
+const COMPRESSED_GRAINS: u32 = 0x10000;
+/// Max size of a descriptor file. Descriptors are small text files
+const MAX_DESCRIPTOR_SIZE: u64 = 64 * 1024;
+/// Parent CID for disks without a parent
+const NO_PARENT: &str = "ffffffff";
+
+/// A `Read` + `Seek` reader over the guest disk of a VMDK
+pub(crate) struct VmdkDisk {
+    extents: Vec<VmdkExtent>,
+    /// Size of the guest disk in bytes
+    size: u64,
+    /// Content ID of the disk. Child disks store the parent CID
+    cid: String,
+    /// Parent disk for snapshots
+    parent: Option<Box<VmdkDisk>>,
+    /// Position of the reader in the guest disk
+    position: u64,
+    /// Extent index and grain number of the cached compressed grain
+    grain_key: Option<(usize, u64)>,
+    grain: Vec<u8>,
+}
+
+/// An extent listed in the descriptor
+struct VmdkExtent {
+    /// Offset of the extent in the guest disk in bytes
+    start: u64,
+    /// Size of the extent in bytes
+    size: u64,
+    kind: ExtentKind,
+}
+
+enum ExtentKind {
+    Flat {
+        reader: BufReader<File>,
+        /// Offset to the guest data in the extent file in bytes
+        offset: u64,
+    },
+    Sparse {
+        reader: BufReader<File>,
+        sparse: SparseExtent,
+    },
+    Zero,
+}
+
+/// Grain lookup tables for a hosted sparse extent
+#[derive(Debug)]
+struct SparseExtent {
+    /// Size of a grain in bytes
+    grain_size: u64,
+    gtes_per_gt: u64,
+    /// Sector offset to each grain table
+    grain_directory: Vec<u32>,
+    compressed: bool,
+    /// Index of the cached grain table
+    table_key: Option<usize>,
+    table: Vec<u32>,
+}
+
+/// Header at the start of a hosted sparse extent
+#[derive(Debug)]
+struct SparseHeader {
+    flags: u32,
+    /// Size of the extent in sectors
+    capacity: u64,
+    /// Size of a grain in sectors
+    grain_size: u64,
+    descriptor_offset: u64,
+    descriptor_size: u64,
+    gtes_per_gt: u32,
+    gd_offset: u64,
+    compress_algorithm: u16,
+}
+
+/// Values parsed from a descriptor file
+#[derive(Debug, Default, PartialEq)]
+struct Descriptor {
+    cid: String,
+    parent_cid: String,
+    create_type: String,
+    parent_hint: String,
+    extents: Vec<ExtentEntry>,
+}
+
+/// Extent line in a descriptor. Example: `RW 4192256 SPARSE "disk-s001.vmdk"`
+#[derive(Debug, PartialEq)]
+struct ExtentEntry {
+    sectors: u64,
+    extent_type: String,
+    file: String,
+    /// Sector offset to the guest data in flat extents
+    offset: u64,
+}
+
+impl VmdkDisk {
+    /// Open a VMDK descriptor or monolithic sparse file. Extents and parent disks are opened automatically
+    pub(crate) fn open(path: &str) -> Result<Self, DiskError> {
+        open_chain(Path::new(path), 0)
+    }
+
+    /// Read from the parent disk or return zeros if there is no parent
+    fn read_parent(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        if let Some(parent) = self.parent.as_mut() {
+            parent.seek(SeekFrom::Start(self.position))?;
+            return parent.read_exact(buf);
+        }
+        buf.fill(0);
+        Ok(())
+    }
+
+    /// Read data from a sparse extent. Returns the number of bytes read
+    fn read_sparse(&mut self, index: usize, buf: &mut [u8]) -> io::Result<usize> {
+        let extent = &mut self.extents[index];
+        let relative = self.position - extent.start;
+        let ExtentKind::Sparse { reader, sparse } = &mut extent.kind else {
+            return Ok(0);
+        };
+        let grain = relative / sparse.grain_size;
+        let offset = relative % sparse.grain_size;
+        let read_len = (sparse.grain_size - offset).min(buf.len() as u64) as usize;
+
+        let entry = grain_entry(reader, sparse, grain)?;
+        match entry {
+            // Grain has been zeroed
+            1 => buf[..read_len].fill(0),
+            _ if entry != 0 && !sparse.compressed => {
+                reader.seek(SeekFrom::Start(entry as u64 * SECTOR_SIZE + offset))?;
+                reader.read_exact(&mut buf[..read_len])?;
+            }
+            _ if entry != 0 => {
+                if self.grain_key != Some((index, grain)) {
+                    self.grain_key = None;
+                    self.grain = read_compressed_grain(reader, entry, sparse.grain_size)?;
+                    self.grain_key = Some((index, grain));
+                }
+                let start = offset as usize;
+                buf[..read_len].copy_from_slice(&self.grain[start..start + read_len]);
+            }
+            // Grain has never been written. Data is in the parent
+            _ => self.read_parent(&mut buf[..read_len])?,
+        }
+        Ok(read_len)
+    }
+}
+
+impl Read for VmdkDisk {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() || self.position >= self.size {
+            return Ok(0);
+        }
+
+        let Some(index) = self.extents.iter().position(|extent| {
+            self.position >= extent.start && self.position < extent.start + extent.size
+        }) else {
+            return Ok(0);
+        };
+        let extent = &mut self.extents[index];
+        let relative = self.position - extent.start;
+        let remaining = (extent.size - relative).min(buf.len() as u64) as usize;
+
+        let read_len = match &mut extent.kind {
+            ExtentKind::Flat { reader, offset } => {
+                reader.seek(SeekFrom::Start(*offset + relative))?;
+                reader.read_exact(&mut buf[..remaining])?;
+                remaining
+            }
+            ExtentKind::Zero => {
+                buf[..remaining].fill(0);
+                remaining
+            }
+            ExtentKind::Sparse { .. } => self.read_sparse(index, &mut buf[..remaining])?,
+        };
+
+        self.position += read_len as u64;
+        Ok(read_len)
+    }
+}
+
+impl Seek for VmdkDisk {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+        };
+
+        match new_pos {
+            Some(position) => {
+                self.position = position;
+                Ok(self.position)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+impl fmt::Debug for VmdkDisk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VmdkDisk")
+            .field("extents", &self.extents.len())
+            .field("size", &self.size)
+            .field("cid", &self.cid)
+            .field("position", &self.position)
+            .field("parent", &self.parent)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Open a VMDK, its extents, and any parent disks
+fn open_chain(path: &Path, depth: usize) -> Result<VmdkDisk, DiskError> {
+    if depth > MAX_PARENT_DEPTH {
+        error!("Reached max VMDK parent depth at {}", path.display());
+        return Err(DiskError::ParentDisk);
+    }
+    let mut reader = open_file(path)?;
+    let signature = read_bytes(&mut reader, 0, 4)?;
+    let signature = u32::from_le_bytes([signature[0], signature[1], signature[2], signature[3]]);
+
+    let descriptor = if signature == SPARSE_SIGNATURE {
+        let header = read_sparse_header(&mut reader, 0)?;
+        if header.descriptor_size == 0 {
+            // A sparse extent opened directly without a descriptor
+            Descriptor {
+                parent_cid: NO_PARENT.to_string(),
+                extents: vec![ExtentEntry {
+                    sectors: header.capacity,
+                    extent_type: String::from("SPARSE"),
+                    file: path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    offset: 0,
+                }],
+                ..Default::default()
+            }
+        } else {
+            let data = read_bytes(
+                &mut reader,
+                header.descriptor_offset * SECTOR_SIZE,
+                header.descriptor_size * SECTOR_SIZE,
+            )?;
+            parse_descriptor(&extract_utf8_string(&data))?
+        }
+    } else if signature == COWD_SIGNATURE {
+        error!("ESX sparse (COWD) extents are not supported");
+        return Err(DiskError::VmdkUnsupported);
+    } else {
+        let size = reader.seek(SeekFrom::End(0)).unwrap_or_default();
+        if size > MAX_DESCRIPTOR_SIZE {
+            error!(
+                "File is not a VMDK descriptor or sparse extent: {}",
+                path.display()
+            );
+            return Err(DiskError::VmdkDescriptor);
+        }
+        let data = read_bytes(&mut reader, 0, size)?;
+        parse_descriptor(&String::from_utf8_lossy(&data))?
+    };
+
+    let directory = path.parent().unwrap_or(Path::new(""));
+    let mut extents = Vec::new();
+    let mut start = 0;
+    for entry in &descriptor.extents {
+        let size = entry.sectors * SECTOR_SIZE;
+        let kind = match entry.extent_type.as_str() {
+            "FLAT" | "VMFS" => ExtentKind::Flat {
+                reader: open_file(&directory.join(&entry.file))?,
+                offset: entry.offset * SECTOR_SIZE,
+            },
+            "SPARSE" => {
+                let mut reader = open_file(&directory.join(&entry.file))?;
+                let sparse = open_sparse(&mut reader)?;
+                ExtentKind::Sparse { reader, sparse }
+            }
+            "ZERO" => ExtentKind::Zero,
+            _ => {
+                error!("Unsupported VMDK extent type: {}", entry.extent_type);
+                return Err(DiskError::VmdkUnsupported);
+            }
+        };
+        extents.push(VmdkExtent { start, size, kind });
+        start += size;
+    }
+    if extents.is_empty() {
+        error!("VMDK {} does not have any extents", path.display());
+        return Err(DiskError::VmdkDescriptor);
+    }
+
+    let mut disk = VmdkDisk {
+        extents,
+        size: start,
+        cid: descriptor.cid,
+        parent: None,
+        position: 0,
+        grain_key: None,
+        grain: Vec::new(),
+    };
+    if descriptor.parent_cid.is_empty() || descriptor.parent_cid == NO_PARENT {
+        return Ok(disk);
+    }
+
+    let Some(parent_path) = resolve_parent(path, slice::from_ref(&descriptor.parent_hint)) else {
+        error!(
+            "Could not find the parent of VMDK {}. Parent path: {}",
+            path.display(),
+            descriptor.parent_hint
+        );
+        return Err(DiskError::ParentDisk);
+    };
+    let parent = open_chain(&parent_path, depth + 1)?;
+    if parent.cid != descriptor.parent_cid {
+        warn!(
+            "VMDK parent {} CID {} does not match the child parent CID {}. The parent may have been modified",
+            parent_path.display(),
+            parent.cid,
+            descriptor.parent_cid
+        );
+    }
+    disk.parent = Some(Box::new(parent));
+
+    Ok(disk)
+}
+
+/// Read the sparse header and grain directory of a hosted sparse extent
+fn open_sparse<T: Read + Seek>(reader: &mut T) -> Result<SparseExtent, DiskError> {
+    let mut header = read_sparse_header(reader, 0)?;
+    if header.gd_offset == GD_AT_END {
+        // streamOptimized extents store the header again in a footer before the end of stream marker
+        let footer_offset = 1024;
+        let size = reader.seek(SeekFrom::End(0)).unwrap_or_default();
+        if size < footer_offset {
+            error!("VMDK stream optimized extent is too small: {size}");
+            return Err(DiskError::VmdkExtent);
+        }
+        header = read_sparse_header(reader, size - footer_offset)?;
+    }
+
+    if header.grain_size == 0 || header.gtes_per_gt == 0 {
+        error!("Invalid VMDK grain size or grain table size");
+        return Err(DiskError::VmdkExtent);
+    }
+    let compressed = header.flags & COMPRESSED_GRAINS != 0;
+    let deflate = 1;
+    if compressed && header.compress_algorithm != deflate {
+        error!(
+            "Unsupported VMDK compression algorithm: {}",
+            header.compress_algorithm
+        );
+        return Err(DiskError::VmdkUnsupported);
+    }
+
+    let grains = header.capacity.div_ceil(header.grain_size);
+    let tables = grains.div_ceil(header.gtes_per_gt as u64);
+    let data = read_bytes(reader, header.gd_offset * SECTOR_SIZE, tables * 4)?;
+    let grain_directory = data
+        .chunks_exact(4)
+        .map(|entry| u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]))
+        .collect();
+
+    Ok(SparseExtent {
+        grain_size: header.grain_size * SECTOR_SIZE,
+        gtes_per_gt: header.gtes_per_gt as u64,
+        grain_directory,
+        compressed,
+        table_key: None,
+        table: Vec::new(),
+    })
+}
+
+/// Read the sparse header at an offset in the extent file
+fn read_sparse_header<T: Read + Seek>(
+    reader: &mut T,
+    offset: u64,
+) -> Result<SparseHeader, DiskError> {
+    let data = read_bytes(reader, offset, SECTOR_SIZE)?;
+    match parse_sparse_header(&data) {
+        Ok((_, header)) => Ok(header),
+        Err(_err) => {
+            error!("Could not parse VMDK sparse header at offset {offset}");
+            Err(DiskError::VmdkExtent)
+        }
+    }
+}
+
+/// Parse the sparse extent header
+fn parse_sparse_header(data: &[u8]) -> nom::IResult<&[u8], SparseHeader> {
+    let (input, signature) = nom_unsigned_four_bytes(data, Endian::Le)?;
+    if signature != SPARSE_SIGNATURE {
+        return Err(nom::Err::Failure(nom::error::Error::new(
+            data,
+            nom::error::ErrorKind::Tag,
+        )));
+    }
+    let (input, _version) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, flags) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, capacity) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+    let (input, grain_size) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+    let (input, descriptor_offset) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+    let (input, descriptor_size) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+    let (input, gtes_per_gt) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, _rgd_offset) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+    let (input, gd_offset) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+    let (input, _overhead) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+    let (input, _unclean_shutdown) = nom_unsigned_one_byte(input, Endian::Le)?;
+    let (input, _line_check) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, compress_algorithm) = nom_unsigned_two_bytes(input, Endian::Le)?;
+
+    let header = SparseHeader {
+        flags,
+        capacity,
+        grain_size,
+        descriptor_offset,
+        descriptor_size,
+        gtes_per_gt,
+        gd_offset,
+        compress_algorithm,
+    };
+    Ok((input, header))
+}
+
+/// Parse the text descriptor
+fn parse_descriptor(text: &str) -> Result<Descriptor, DiskError> {
+    let mut descriptor = Descriptor::default();
+    for line in text.lines() {
+        let line = line.trim_matches(char::from(0)).trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some((key, value)) = line.split_once('=')
+            && !key.contains('"')
+        {
+            let value = value.trim().trim_matches('"').to_string();
+            match key.trim() {
+                "CID" => descriptor.cid = value.to_lowercase(),
+                "parentCID" => descriptor.parent_cid = value.to_lowercase(),
+                "createType" => descriptor.create_type = value,
+                "parentFileNameHint" => descriptor.parent_hint = value,
+                _ => {}
+            }
+            continue;
+        }
+
+        let access = ["RW", "RDONLY", "NOACCESS"];
+        if access.iter().any(|value| line.starts_with(value)) {
+            descriptor.extents.push(parse_extent_line(line)?);
+        }
+    }
+
+    if descriptor.extents.is_empty() {
+        error!("VMDK descriptor does not have any extents");
+        return Err(DiskError::VmdkDescriptor);
+    }
+    Ok(descriptor)
+}
+
+/// Parse an extent line. The file name is quoted and may contain spaces. Example: `RW 2048 FLAT "disk-flat.vmdk" 0`
+fn parse_extent_line(line: &str) -> Result<ExtentEntry, DiskError> {
+    let (before, file, after) = match line.split_once('"') {
+        Some((before, rest)) => match rest.rsplit_once('"') {
+            Some((file, after)) => (before, file.to_string(), after),
+            None => (before, rest.to_string(), ""),
+        },
+        None => (line, String::new(), ""),
+    };
+
+    let mut values = before.split_whitespace();
+    let _access = values.next();
+    let sectors = values.next().and_then(|value| value.parse::<u64>().ok());
+    let extent_type = values.next().map(str::to_uppercase);
+    let (Some(sectors), Some(extent_type)) = (sectors, extent_type) else {
+        error!("Could not parse VMDK extent line: {line}");
+        return Err(DiskError::VmdkDescriptor);
+    };
+    if file.is_empty() && extent_type != "ZERO" {
+        error!("VMDK extent line is missing a file name: {line}");
+        return Err(DiskError::VmdkDescriptor);
+    }
+    let offset = after
+        .split_whitespace()
+        .next()
+        .and_then(|value| value.parse::<u64>().ok())
+        .unwrap_or_default();
+
+    Ok(ExtentEntry {
+        sectors,
+        extent_type,
+        file,
+        offset,
+    })
+}
+
+/// Lookup the grain table entry for a grain. Zero means unallocated
+fn grain_entry<T: Read + Seek>(
+    reader: &mut T,
+    sparse: &mut SparseExtent,
+    grain: u64,
+) -> io::Result<u32> {
+    let directory_index = (grain / sparse.gtes_per_gt) as usize;
+    let table_offset = sparse
+        .grain_directory
+        .get(directory_index)
+        .copied()
+        .unwrap_or_default();
+    if table_offset == 0 {
+        return Ok(0);
+    }
+
+    if sparse.table_key != Some(directory_index) {
+        sparse.table_key = None;
+        reader.seek(SeekFrom::Start(table_offset as u64 * SECTOR_SIZE))?;
+        let mut data = vec![0; sparse.gtes_per_gt as usize * 4];
+        reader.read_exact(&mut data)?;
+        sparse.table = data
+            .chunks_exact(4)
+            .map(|entry| u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]))
+            .collect();
+        sparse.table_key = Some(directory_index);
+    }
+
+    Ok(sparse
+        .table
+        .get((grain % sparse.gtes_per_gt) as usize)
+        .copied()
+        .unwrap_or_default())
+}
+
+/// Read and decompress a grain. Compressed grains start with the guest sector number and compressed size
+fn read_compressed_grain<T: Read + Seek>(
+    reader: &mut T,
+    sector: u32,
+    grain_size: u64,
+) -> io::Result<Vec<u8>> {
+    reader.seek(SeekFrom::Start(sector as u64 * SECTOR_SIZE))?;
+    let mut marker = [0u8; 12];
+    reader.read_exact(&mut marker)?;
+    let size = u32::from_le_bytes([marker[8], marker[9], marker[10], marker[11]]) as u64;
+    // Compressed grains should never be much larger than the grain
+    if size > grain_size * 2 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("VMDK compressed grain at sector {sector} is too large: {size}"),
+        ));
+    }
+
+    let mut data = vec![0; size as usize];
+    reader.read_exact(&mut data)?;
+    let mut grain = decompress_zlib(&data, None, grain_size as usize).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("could not decompress VMDK grain at sector {sector}: {err:?}"),
+        )
+    })?;
+    grain.resize(grain_size as usize, 0);
+    Ok(grain)
+}
+
+/// Open an extent or descriptor file
+fn open_file(path: &Path) -> Result<BufReader<File>, DiskError> {
+    match File::open(path) {
+        Ok(result) => Ok(BufReader::new(result)),
+        Err(err) => {
+            error!("Failed to open VMDK file {}: {err:?}", path.display());
+            Err(DiskError::Vmdk)
+        }
+    }
+}
+
+/// Read bytes from a VMDK file
+fn read_bytes<T: Read + Seek>(
+    reader: &mut T,
+    offset: u64,
+    size: u64,
+) -> Result<Vec<u8>, DiskError> {
+    // Metadata should never be this large. The grain directory for a 62TB disk is 2MB
+    let max_size = 64 * 1024 * 1024;
+    if size > max_size {
+        error!("VMDK structure at offset {offset} is too large: {size}");
+        return Err(DiskError::VmdkExtent);
+    }
+    if let Err(err) = reader.seek(SeekFrom::Start(offset)) {
+        error!("Could not seek to VMDK offset {offset}: {err:?}");
+        return Err(DiskError::VmdkExtent);
+    }
+
+    let mut data = vec![0; size as usize];
+    if let Err(err) = reader.read_exact(&mut data) {
+        error!("Could not read VMDK data at offset {offset}: {err:?}");
+        return Err(DiskError::VmdkExtent);
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExtentEntry, VmdkDisk, parse_descriptor, parse_extent_line};
+    use crate::filesystem::disks::{
+        error::DiskError,
+        test_disks::{disk_location, raw_disk},
+    };
+    use std::io::{Read, Seek, SeekFrom};
+
+    #[test]
+    fn test_vmdk_monolithic_sparse() {
+        let raw = raw_disk();
+        let mut disk = VmdkDisk::open(&disk_location("vmdk", "gpt_ext4.vmdk")).unwrap();
+        assert_eq!(disk.size, raw.len() as u64);
+        assert_eq!(disk.cid, "1a2b3c4d");
+        assert!(disk.parent.is_none());
+
+        let mut media = Vec::new();
+        disk.read_to_end(&mut media).unwrap();
+        assert_eq!(media, raw);
+    }
+
+    #[test]
+    fn test_vmdk_split_sparse() {
+        let raw = raw_disk();
+        let mut disk = VmdkDisk::open(&disk_location("vmdk", "gpt_ext4_split.vmdk")).unwrap();
+        assert_eq!(disk.extents.len(), 2);
+        assert_eq!(disk.size, raw.len() as u64);
+
+        let mut media = Vec::new();
+        disk.read_to_end(&mut media).unwrap();
+        assert_eq!(media, raw);
+
+        // Read across the extent boundary
+        let boundary = 640 * 512;
+        disk.seek(SeekFrom::Start(boundary - 8)).unwrap();
+        let mut buf = [0u8; 16];
+        disk.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, raw[boundary as usize - 8..boundary as usize + 8]);
+    }
+
+    #[test]
+    fn test_vmdk_stream_optimized() {
+        let raw = raw_disk();
+        let mut disk = VmdkDisk::open(&disk_location("vmdk", "gpt_ext4_stream.vmdk")).unwrap();
+        assert_eq!(disk.size, raw.len() as u64);
+
+        let mut media = Vec::new();
+        disk.read_to_end(&mut media).unwrap();
+        assert_eq!(media, raw);
+    }
+
+    #[test]
+    fn test_vmdk_snapshot() {
+        let raw = raw_disk();
+        let mut disk = VmdkDisk::open(&disk_location("vmdk", "gpt_ext4-000001.vmdk")).unwrap();
+        assert!(disk.parent.is_some());
+
+        let mut media = Vec::new();
+        disk.read_to_end(&mut media).unwrap();
+        let hostname = 87040;
+        assert_eq!(&media[hostname..hostname + 14], b"artemis-child\n");
+        assert_eq!(media[..hostname], raw[..hostname]);
+        assert_eq!(media[hostname + 14..], raw[hostname + 14..]);
+    }
+
+    #[test]
+    fn test_vmdk_not_vmdk() {
+        let result = VmdkDisk::open(&disk_location("raw", "gpt_ext4.raw"));
+        assert!(matches!(result, Err(DiskError::VmdkDescriptor)));
+    }
+
+    #[test]
+    fn test_parse_descriptor() {
+        let test = "# Disk DescriptorFile\nversion=1\nCID=5E6F7A8B\nparentCID=1a2b3c4d\ncreateType=\"monolithicFlat\"\nparentFileNameHint=\"base disk.vmdk\"\n\nRW 2048 FLAT \"base disk-flat.vmdk\" 0\nRW 1024 ZERO\n\nddb.adapterType = \"lsilogic\"\n";
+        let result = parse_descriptor(test).unwrap();
+        assert_eq!(result.cid, "5e6f7a8b");
+        assert_eq!(result.parent_cid, "1a2b3c4d");
+        assert_eq!(result.create_type, "monolithicFlat");
+        assert_eq!(result.parent_hint, "base disk.vmdk");
+        assert_eq!(result.extents.len(), 2);
+        assert_eq!(result.extents[1].extent_type, "ZERO");
+
+        assert!(matches!(
+            parse_descriptor("version=1\n"),
+            Err(DiskError::VmdkDescriptor)
+        ));
+    }
+
+    #[test]
+    fn test_parse_extent_line() {
+        let result = parse_extent_line("RW 4192256 VMFS \"vm 1-flat.vmdk\" 128").unwrap();
+        assert_eq!(
+            result,
+            ExtentEntry {
+                sectors: 4192256,
+                extent_type: String::from("VMFS"),
+                file: String::from("vm 1-flat.vmdk"),
+                offset: 128,
+            }
+        );
+        assert!(parse_extent_line("RW SPARSE").is_err());
+    }
+}
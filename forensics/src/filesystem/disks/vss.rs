@@ -0,0 +1,633 @@
+/**
+ * Volume Shadow Copies (VSS) are point in time snapshots of a Windows volume
+ * The volume header at offset 0x1e00 points to a catalog that lists each shadow copy store
+ *
+ * A store only contains the 16KB blocks that changed after the snapshot was created.
+ * To read a snapshot we check the store for the snapshot, then each newer store, and finally the current volume
+ *
+ * References:
+ *  `https://github.com/libyal/libvshadow/blob/main/documentation/Volume%20Shadow%20Snapshot%20(VSS)%20format.asciidoc`
+ *
+ * Other Parsers:
+ *  `https://github.com/libyal/libvshadow`
+ */
+use crate::{
+    filesystem::disks::error::DiskError,
+    utils::{
+        nom_helper::{Endian, nom_data, nom_unsigned_eight_bytes, nom_unsigned_four_bytes},
+        time::filetime_to_iso,
+        uuid::format_guid_le_bytes,
+    },
+};
+use std::{
+    collections::HashMap,
+    fmt,
+    io::{self, Read, Seek, SeekFrom},
+};
+use tracing::{error, warn};
+
+/// Identifier for all VSS structures. {3808876b-c176-4e48-b7ae-04046e6cc752}
+const VSS_IDENTIFIER: [u8; 16] = [
+    0x6b, 0x87, 0x08, 0x38, 0x76, 0xc1, 0x48, 0x4e, 0xb7, 0xae, 0x04, 0x04, 0x6e, 0x6c, 0xc7, 0x52,
+];
+const VOLUME_HEADER_OFFSET: u64 = 0x1e00;
+/// Catalog, block list, and data blocks are all 16KB
+const BLOCK_SIZE: u64 = 0x4000;
+const BLOCK_HEADER_SIZE: usize = 128;
+const CATALOG_ENTRY_SIZE: usize = 128;
+const BLOCK_DESCRIPTOR_SIZE: usize = 32;
+const SECTOR_SIZE: u64 = 512;
+/// Max number of catalog or block list blocks to follow. Protects against loops
+const MAX_BLOCK_CHAIN: usize = 64 * 1024;
+
+/// Block descriptor flags
+const FORWARDER: u32 = 0x1;
+const OVERLAY: u32 = 0x2;
+const NOT_USED: u32 = 0x4;
+
+/// A shadow copy store listed in the VSS catalog
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ShadowStore {
+    /// Store number. The oldest snapshot is store 1
+    pub(crate) number: u32,
+    /// Store GUID
+    pub(crate) store_id: String,
+    /// Snapshot creation time
+    pub(crate) created: String,
+    /// Size of the volume when the snapshot was created
+    pub(crate) volume_size: u64,
+    /// Offset to the first block list of the store
+    block_list_offset: u64,
+}
+
+/// A `Read` + `Seek` reader over the volume as it was when a snapshot was created
+pub(crate) struct VssSnapshot<T: Read + Seek> {
+    /// Reader for the current volume. Store data is also stored on the volume
+    volume: T,
+    /// Size of the snapshot volume in bytes
+    size: u64,
+    /// Block descriptors for the snapshot store and every newer store
+    stores: Vec<StoreBlocks>,
+    /// Position of the reader in the snapshot volume
+    position: u64,
+}
+
+/// Block descriptors for a single store keyed by the original volume offset
+#[derive(Debug, Default)]
+struct StoreBlocks {
+    blocks: HashMap<u64, BlockDescriptor>,
+    overlays: HashMap<u64, BlockDescriptor>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BlockDescriptor {
+    original_offset: u64,
+    relative_offset: u64,
+    store_offset: u64,
+    flags: u32,
+    /// Sectors in an overlay block that contain data. The first sector is the least significant bit
+    bitmap: u32,
+}
+
+/// Common header for catalog and store blocks
+#[derive(Debug)]
+struct BlockHeader {
+    record_type: u32,
+    next_offset: u64,
+}
+
+/// Where the data for a 16KB block of the snapshot is stored
+#[derive(Debug, PartialEq)]
+struct BlockMapping {
+    /// Offset on the volume to the block data
+    offset: u64,
+    /// Overlay block offset and its sector bitmap
+    overlay: Option<(u64, u32)>,
+}
+
+impl<T: Read + Seek> VssSnapshot<T> {
+    /// Open a snapshot on the volume by store number. Store numbers start at 1
+    pub(crate) fn open(mut volume: T, number: u32) -> Result<Self, DiskError> {
+        let stores = read_shadow_stores(&mut volume)?;
+        let Some(index) = stores.iter().position(|store| store.number == number) else {
+            error!(
+                "VSS store {number} does not exist. Volume has {} stores",
+                stores.len()
+            );
+            return Err(DiskError::VssStore);
+        };
+
+        // Changes made after the snapshot are tracked in the newer stores
+        let mut blocks = Vec::new();
+        for store in &stores[index..] {
+            blocks.push(read_store_blocks(&mut volume, store.block_list_offset)?);
+        }
+
+        Ok(Self {
+            volume,
+            size: stores[index].volume_size,
+            stores: blocks,
+            position: 0,
+        })
+    }
+
+    /// Size of the snapshot volume in bytes
+    pub(crate) fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Determine where the data for a 16KB block is stored
+    fn resolve(&self, block_offset: u64) -> BlockMapping {
+        let mut original = block_offset;
+        let mut overlay = None;
+        for store in &self.stores {
+            if overlay.is_none()
+                && let Some(entry) = store.overlays.get(&original)
+            {
+                overlay = Some((entry.store_offset, entry.bitmap));
+            }
+            let Some(entry) = store.blocks.get(&original) else {
+                continue;
+            };
+            // Forwarded blocks are stored at a different offset in a newer store or the volume
+            if entry.flags & FORWARDER != 0 {
+                original = entry.relative_offset;
+                continue;
+            }
+            return BlockMapping {
+                offset: entry.store_offset,
+                overlay,
+            };
+        }
+
+        BlockMapping {
+            offset: original,
+            overlay,
+        }
+    }
+}
+
+impl<T: Read + Seek> Read for VssSnapshot<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() || self.position >= self.size {
+            return Ok(0);
+        }
+        let remaining = (self.size - self.position).min(buf.len() as u64);
+
+        let block_offset = self.position - self.position % BLOCK_SIZE;
+        let offset = self.position - block_offset;
+        let mut read_len = (BLOCK_SIZE - offset).min(remaining);
+        let mapping = self.resolve(block_offset);
+
+        let mut data_offset = mapping.offset + offset;
+        if let Some((overlay_offset, bitmap)) = mapping.overlay {
+            // Overlay sectors replace individual sectors of the block
+            let sector = offset / SECTOR_SIZE;
+            read_len = read_len.min(SECTOR_SIZE - offset % SECTOR_SIZE);
+            if bitmap & (1 << sector) != 0 {
+                data_offset = overlay_offset + offset;
+            }
+        }
+
+        self.volume.seek(SeekFrom::Start(data_offset))?;
+        self.volume.read_exact(&mut buf[..read_len as usize])?;
+        self.position += read_len;
+        Ok(read_len as usize)
+    }
+}
+
+impl<T: Read + Seek> Seek for VssSnapshot<T> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+        };
+
+        match new_pos {
+            Some(position) => {
+                self.position = position;
+                Ok(self.position)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+impl<T: Read + Seek> fmt::Debug for VssSnapshot<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VssSnapshot")
+            .field("size", &self.size)
+            .field("stores", &self.stores.len())
+            .field("position", &self.position)
+            .finish_non_exhaustive()
+    }
+}
+
+/// List the shadow copy stores on a volume. Stores are numbered from oldest to newest
+///
+/// Returns an empty list if the volume does not have Volume Shadow Copies enabled
+pub(crate) fn read_shadow_stores<T: Read + Seek>(
+    volume: &mut T,
+) -> Result<Vec<ShadowStore>, DiskError> {
+    let header = read_bytes(volume, VOLUME_HEADER_OFFSET, 512)?;
+    if !header.starts_with(&VSS_IDENTIFIER) {
+        return Ok(Vec::new());
+    }
+    let catalog_offset = match parse_volume_header(&header) {
+        Ok((_, result)) => result,
+        Err(_err) => {
+            error!("Could not parse VSS volume header");
+            return Err(DiskError::Vss);
+        }
+    };
+    // Shadow copies are enabled but no snapshot has been created
+    if catalog_offset == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut created = HashMap::new();
+    let mut stores = Vec::new();
+    let mut offset = catalog_offset;
+    for _ in 0..MAX_BLOCK_CHAIN {
+        let data = read_bytes(volume, offset, BLOCK_SIZE)?;
+        let next_offset = match parse_block_header(&data) {
+            Ok((_, header)) if header.record_type == 2 => header.next_offset,
+            _ => {
+                error!("Invalid VSS catalog block at offset {offset}");
+                return Err(DiskError::VssCatalog);
+            }
+        };
+
+        for entry in data[BLOCK_HEADER_SIZE..].chunks_exact(CATALOG_ENTRY_SIZE) {
+            match parse_catalog_entry(entry) {
+                Ok((_, CatalogEntry::Snapshot(store_id, store))) => {
+                    created.insert(store_id, store);
+                }
+                Ok((_, CatalogEntry::Store(store_id, block_list_offset))) => {
+                    stores.push((store_id, block_list_offset));
+                }
+                Ok((_, CatalogEntry::Empty)) => {}
+                Err(_err) => {
+                    error!("Could not parse VSS catalog entry at offset {offset}");
+                    return Err(DiskError::VssCatalog);
+                }
+            }
+        }
+
+        if next_offset == 0 {
+            break;
+        }
+        offset = next_offset;
+    }
+
+    let mut shadow_stores = Vec::new();
+    for (store_id, block_list_offset) in stores {
+        let Some((filetime, volume_size)) = created.get(&store_id) else {
+            warn!("VSS store {store_id} is missing its snapshot information");
+            continue;
+        };
+        shadow_stores.push((
+            *filetime,
+            ShadowStore {
+                number: 0,
+                store_id,
+                created: filetime_to_iso(*filetime),
+                volume_size: *volume_size,
+                block_list_offset,
+            },
+        ));
+    }
+    shadow_stores.sort_by_key(|(filetime, _)| *filetime);
+
+    Ok(shadow_stores
+        .into_iter()
+        .enumerate()
+        .map(|(index, (_, mut store))| {
+            store.number = index as u32 + 1;
+            store
+        })
+        .collect())
+}
+
+/// Read all block descriptors in the block list of a store
+fn read_store_blocks<T: Read + Seek>(
+    volume: &mut T,
+    block_list_offset: u64,
+) -> Result<StoreBlocks, DiskError> {
+    let mut store = StoreBlocks::default();
+    let mut offset = block_list_offset;
+    for _ in 0..MAX_BLOCK_CHAIN {
+        let data = read_bytes(volume, offset, BLOCK_SIZE)?;
+        let next_offset = match parse_block_header(&data) {
+            Ok((_, header)) if header.record_type == 3 => header.next_offset,
+            _ => {
+                error!("Invalid VSS store block list at offset {offset}");
+                return Err(DiskError::VssStore);
+            }
+        };
+
+        for entry in data[BLOCK_HEADER_SIZE..].chunks_exact(BLOCK_DESCRIPTOR_SIZE) {
+            let Ok((_, descriptor)) = parse_block_descriptor(entry) else {
+                continue;
+            };
+            if descriptor.flags & NOT_USED != 0
+                || (descriptor.store_offset == 0 && descriptor.flags & FORWARDER == 0)
+            {
+                continue;
+            }
+
+            if descriptor.flags & OVERLAY != 0 {
+                store
+                    .overlays
+                    .insert(descriptor.original_offset, descriptor);
+            } else {
+                store.blocks.insert(descriptor.original_offset, descriptor);
+            }
+        }
+
+        if next_offset == 0 {
+            break;
+        }
+        offset = next_offset;
+    }
+
+    Ok(store)
+}
+
+/// Parse the volume header and return the catalog offset
+fn parse_volume_header(data: &[u8]) -> nom::IResult<&[u8], u64> {
+    let (input, _identifier) = nom_data(data, VSS_IDENTIFIER.len() as u64)?;
+    let (input, _version) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, _record_type) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, _current_offset) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+    let (input, _unknown) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+    let (input, _unknown2) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+    let (input, catalog_offset) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+
+    Ok((input, catalog_offset))
+}
+
+/// Parse the header shared by catalog and store blocks
+fn parse_block_header(data: &[u8]) -> nom::IResult<&[u8], BlockHeader> {
+    let (input, identifier) = nom_data(data, VSS_IDENTIFIER.len() as u64)?;
+    if identifier != VSS_IDENTIFIER {
+        return Err(nom::Err::Failure(nom::error::Error::new(
+            data,
+            nom::error::ErrorKind::Tag,
+        )));
+    }
+    let (input, _version) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, record_type) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, _relative_offset) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+    let (input, _current_offset) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+    let (input, next_offset) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+
+    Ok((
+        input,
+        BlockHeader {
+            record_type,
+            next_offset,
+        },
+    ))
+}
+
+#[derive(Debug, PartialEq)]
+enum CatalogEntry {
+    Empty,
+    /// Store GUID, creation FILETIME, and volume size
+    Snapshot(String, (u64, u64)),
+    /// Store GUID and block list offset
+    Store(String, u64),
+}
+
+/// Parse a 128 byte catalog entry
+fn parse_catalog_entry(data: &[u8]) -> nom::IResult<&[u8], CatalogEntry> {
+    let (input, entry_type) = nom_unsigned_eight_bytes(data, Endian::Le)?;
+    match entry_type {
+        2 => {
+            let (input, volume_size) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+            let (input, store_id) = nom_data(input, 16)?;
+            let (input, _sequence) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+            let (input, _flags) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+            let (input, created) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+            Ok((
+                input,
+                CatalogEntry::Snapshot(format_guid_le_bytes(store_id), (created, volume_size)),
+            ))
+        }
+        3 => {
+            let (input, block_list_offset) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+            let (input, store_id) = nom_data(input, 16)?;
+            Ok((
+                input,
+                CatalogEntry::Store(format_guid_le_bytes(store_id), block_list_offset),
+            ))
+        }
+        _ => Ok((input, CatalogEntry::Empty)),
+    }
+}
+
+/// Parse a 32 byte block descriptor from a store block list
+fn parse_block_descriptor(data: &[u8]) -> nom::IResult<&[u8], BlockDescriptor> {
+    let (input, original_offset) = nom_unsigned_eight_bytes(data, Endian::Le)?;
+    let (input, relative_offset) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+    let (input, store_offset) = nom_unsigned_eight_bytes(input, Endian::Le)?;
+    let (input, flags) = nom_unsigned_four_bytes(input, Endian::Le)?;
+    let (input, bitmap) = nom_unsigned_four_bytes(input, Endian::Le)?;
+
+    Ok((
+        input,
+        BlockDescriptor {
+            original_offset,
+            relative_offset,
+            store_offset,
+            flags,
+            bitmap,
+        },
+    ))
+}
+
+/// Read bytes from the volume
+fn read_bytes<T: Read + Seek>(
+    reader: &mut T,
+    offset: u64,
+    size: u64,
+) -> Result<Vec<u8>, DiskError> {
+    if let Err(err) = reader.seek(SeekFrom::Start(offset)) {
+        error!("Could not seek to VSS offset {offset}: {err:?}");
+        return Err(DiskError::Vss);
+    }
+
+    let mut data = vec![0; size as usize];
+    if let Err(err) = reader.read_exact(&mut data) {
+        error!("Could not read VSS data at offset {offset}: {err:?}");
+        return Err(DiskError::Vss);
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BlockDescriptor, BlockMapping, CatalogEntry, FORWARDER, OVERLAY, StoreBlocks, VssSnapshot,
+        parse_block_descriptor, parse_catalog_entry, read_shadow_stores,
+    };
+    use crate::filesystem::disks::error::DiskError;
+    use std::{
+        fs::{File, read},
+        io::{BufReader, Cursor, Read, Seek, SeekFrom},
+        path::PathBuf,
+    };
+
+    fn test_location() -> PathBuf {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/vss/ext4_vss.raw");
+        test_location
+    }
+
+    fn read_snapshot(store: u32, offset: u64, size: usize) -> Vec<u8> {
+        let volume = BufReader::new(File::open(test_location()).unwrap());
+        let mut snapshot = VssSnapshot::open(volume, store).unwrap();
+        snapshot.seek(SeekFrom::Start(offset)).unwrap();
+        let mut data = vec![0; size];
+        snapshot.read_exact(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_read_shadow_stores() {
+        let mut volume = BufReader::new(File::open(test_location()).unwrap());
+        let stores = read_shadow_stores(&mut volume).unwrap();
+        assert_eq!(stores.len(), 2);
+        assert_eq!(stores[0].number, 1);
+        assert_eq!(stores[0].store_id, "11111111-2222-3333-4444-555555555555");
+        assert_eq!(stores[0].created, "2024-01-01T00:00:00.000Z");
+        assert_eq!(stores[0].volume_size, 1048576);
+        assert_eq!(stores[1].number, 2);
+        assert_eq!(stores[1].created, "2024-02-01T00:00:00.000Z");
+    }
+
+    #[test]
+    fn test_read_shadow_stores_no_vss() {
+        let mut volume = Cursor::new(vec![0; 0x4000]);
+        assert!(read_shadow_stores(&mut volume).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_vss_snapshot() {
+        let hostname_offset = 10 * 4096;
+        let notes_offset = 16 * 4096;
+        let live = read(test_location()).unwrap();
+        assert!(live[hostname_offset..].starts_with(b"artemis-live\n"));
+
+        assert_eq!(
+            read_snapshot(1, hostname_offset as u64, 13),
+            b"artemis-vss1\n"
+        );
+        assert_eq!(
+            read_snapshot(2, hostname_offset as u64, 13),
+            b"artemis-vss2\n"
+        );
+        // Store 1 does not have the block. It changed after the second snapshot was created
+        assert_eq!(read_snapshot(1, notes_offset as u64, 11), b"snap notes\n");
+        assert_eq!(read_snapshot(2, notes_offset as u64, 11), b"snap notes\n");
+
+        // Unchanged blocks are read from the volume
+        assert_eq!(read_snapshot(1, 1024, 2048), &live[1024..3072]);
+    }
+
+    #[test]
+    fn test_vss_snapshot_missing_store() {
+        let volume = BufReader::new(File::open(test_location()).unwrap());
+        let result = VssSnapshot::open(volume, 3);
+        assert!(matches!(result, Err(DiskError::VssStore)));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let descriptor =
+            |original_offset, relative_offset, store_offset, flags, bitmap| BlockDescriptor {
+                original_offset,
+                relative_offset,
+                store_offset,
+                flags,
+                bitmap,
+            };
+        let mut first = StoreBlocks::default();
+        first
+            .blocks
+            .insert(0x4000, descriptor(0x4000, 0x8000, 0, FORWARDER, 0));
+        first
+            .overlays
+            .insert(0xc000, descriptor(0xc000, 0, 0x20000, OVERLAY, 0b10));
+        let mut second = StoreBlocks::default();
+        second
+            .blocks
+            .insert(0x8000, descriptor(0x8000, 0, 0x30000, 0, 0));
+
+        let snapshot = VssSnapshot {
+            volume: Cursor::new(Vec::new()),
+            size: 0x10000,
+            stores: vec![first, second],
+            position: 0,
+        };
+        assert_eq!(
+            snapshot.resolve(0x4000),
+            BlockMapping {
+                offset: 0x30000,
+                overlay: None
+            }
+        );
+        assert_eq!(
+            snapshot.resolve(0xc000),
+            BlockMapping {
+                offset: 0xc000,
+                overlay: Some((0x20000, 0b10))
+            }
+        );
+        assert_eq!(
+            snapshot.resolve(0),
+            BlockMapping {
+                offset: 0,
+                overlay: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_catalog_entry() {
+        let mut data = vec![0; 128];
+        data[0] = 3;
+        data[8..16].copy_from_slice(&0xc8000u64.to_le_bytes());
+        let (_, entry) = parse_catalog_entry(&data).unwrap();
+        assert_eq!(
+            entry,
+            CatalogEntry::Store(
+                String::from("00000000-0000-0000-0000-000000000000"),
+                0xc8000
+            )
+        );
+
+        data[0] = 1;
+        let (_, entry) = parse_catalog_entry(&data).unwrap();
+        assert_eq!(entry, CatalogEntry::Empty);
+    }
+
+    #[test]
+    fn test_parse_block_descriptor() {
+        let mut data = vec![0; 32];
+        data[0..8].copy_from_slice(&0x8000u64.to_le_bytes());
+        data[16..24].copy_from_slice(&0xcc000u64.to_le_bytes());
+        data[24] = 2;
+        data[28] = 0xff;
+        let (_, descriptor) = parse_block_descriptor(&data).unwrap();
+        assert_eq!(descriptor.original_offset, 0x8000);
+        assert_eq!(descriptor.store_offset, 0xcc000);
+        assert_eq!(descriptor.flags, OVERLAY);
+        assert_eq!(descriptor.bitmap, 0xff);
+    }
+}
@@ -1,4 +1,9 @@
 use super::{directory::is_directory, error::FileSystemError, metadata::get_metadata};
+use crate::{
+    accessor::{access::Accessor, io::reader::AccessorReader, location::loc::Location},
+    output::context::record_source,
+    utils::cancel::CancelToken,
+};
 use base16ct::lower::encode_str;
 use common::files::Hashes;
 use digest_io::IoWrapper;
@@ -120,6 +125,60 @@ pub(crate) fn read_file_custom(path: &str, size: u64) -> Result<Vec<u8>, FileSys
     file_read(path)
 }
 
+/// Read a file that is less than 2GB in size. Paths in archives, disk images or snapshots are read with the `Accessor`
+///
+/// Example: `ewf:case.E01!p2/$LogFile`
+pub(crate) fn read_any(path: &str) -> Result<Vec<u8>, FileSystemError> {
+    if !Location::is_container(path) {
+        return read_file(path);
+    }
+
+    record_source(path);
+    match Accessor::with_defaults().read_file(path) {
+        Ok(result) => Ok(result),
+        Err(err) => {
+            error!("Failed to read file {path} with accessor: {err}");
+            Err(FileSystemError::ReadFile)
+        }
+    }
+}
+
+/// Get a list of all files in a provided directory. Directories in archives, disk images or snapshots are read with the `Accessor`
+///
+/// Example: `vss:C@3!/Windows/Prefetch`
+pub(crate) fn list_files_any(path: &str) -> Result<Vec<String>, FileSystemError> {
+    if !Location::is_container(path) {
+        return list_files(path);
+    }
+
+    record_source(path);
+    match Accessor::with_defaults().list_files(path) {
+        Ok(result) => Ok(result),
+        Err(err) => {
+            error!("Failed to get directory contents {path} with accessor: {err}");
+            Err(FileSystemError::ReadDirectory)
+        }
+    }
+}
+
+/// Create an `AccessorReader` for a file. Files in archives, disk images or snapshots are read with the `Accessor`
+///
+/// Example: `ewf:case.E01!p2/$MFT`
+pub(crate) fn reader_any(path: &str) -> Result<AccessorReader, FileSystemError> {
+    if !Location::is_container(path) {
+        return Ok(AccessorReader::Host(file_reader(path)?));
+    }
+
+    record_source(path);
+    match Accessor::with_defaults().open_reader(path) {
+        Ok(result) => Ok(result),
+        Err(err) => {
+            error!("Failed to open file {path} with accessor: {err}");
+            Err(FileSystemError::OpenFile)
+        }
+    }
+}
+
 /// Read a file into memory
 fn file_read(path: &str) -> Result<Vec<u8>, FileSystemError> {
     // Verify provided path is a file
@@ -187,7 +246,12 @@ pub(crate) fn hash_file_data(hashes: &Hashes, data: &[u8]) -> (String, String, S
 }
 
 /// Read a file in chunks and hash its contents. Returns MD5, SHA1, and/or SHA256 hashes
-pub(crate) fn hash_file(hashes: &Hashes, path: &str) -> (String, String, String) {
+/// Returns empty hashes if `cancel` is cancelled before the whole file is read
+pub(crate) fn hash_file(
+    hashes: &Hashes,
+    path: &str,
+    cancel: &CancelToken,
+) -> (String, String, String) {
     let mut md5_string = String::new();
     let mut sha1_string = String::new();
     let mut sha256_string = String::new();
@@ -225,6 +289,11 @@ pub(crate) fn hash_file(hashes: &Hashes, path: &str) -> (String, String, String)
         if bytes == finished {
             break;
         }
+        cancel.throttle(bytes);
+        if cancel.is_cancelled() {
+            warn!("Collection cancelled. Stopped hashing {path}");
+            return (md5_string, sha1_string, sha256_string);
+        }
 
         // Make sure our temp buff does not have any extra zeros from the initialization
         if bytes < temp_buff_size {
@@ -335,8 +404,10 @@ mod tests {
     use crate::filesystem::files::{
         file_extension, file_lines, file_read_text, file_reader, file_too_large,
         file_too_large_custom, get_file_size, get_filename, hash_file, hash_file_data, is_file,
-        list_files, list_files_directories, read_file, read_file_custom, read_text_file,
+        list_files, list_files_any, list_files_directories, read_any, read_file, read_file_custom,
+        read_text_file, reader_any,
     };
+    use crate::utils::cancel::CancelToken;
     use common::files::Hashes;
     use std::path::PathBuf;
 
@@ -384,7 +455,11 @@ mod tests {
             sha256: true,
         };
 
-        let (md5, sha1, sha256) = hash_file(&hashes, &test_location.display().to_string());
+        let (md5, sha1, sha256) = hash_file(
+            &hashes,
+            &test_location.display().to_string(),
+            &CancelToken::new(),
+        );
 
         assert_eq!(md5, "220c0b91fd1d000ad08441675dab02c8");
         assert_eq!(sha1, "9962ed200cdca61a0daad6a045c920e09ffdea50");
@@ -394,6 +469,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_hash_file_cancelled() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/system/files/test.txt");
+        let hashes = Hashes {
+            md5: true,
+            sha1: true,
+            sha256: true,
+        };
+        let cancel = CancelToken::new();
+        cancel.cancel();
+
+        let (md5, sha1, sha256) = hash_file(&hashes, &test_location.display().to_string(), &cancel);
+        assert!(md5.is_empty());
+        assert!(sha1.is_empty());
+        assert!(sha256.is_empty());
+    }
+
     #[test]
     fn test_file_extension() {
         let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -414,6 +507,45 @@ mod tests {
         assert_eq!(result.len(), 23);
     }
 
+    #[test]
+    fn test_read_any() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/system/files/test.txt");
+        let result = read_any(&test_location.display().to_string()).unwrap();
+        assert_eq!(result.len(), 23);
+
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/ewf/gpt_ext4.E01");
+        let result = read_any(&format!("ewf:{}!p2/etc/hostname", test_location.display())).unwrap();
+        assert_eq!(result, b"artemis-image\n");
+
+        assert!(read_any(&format!("ewf:{}!p2/etc/missing", test_location.display())).is_err());
+    }
+
+    #[test]
+    fn test_list_files_any() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/ewf/gpt_ext4.E01");
+        let result = list_files_any(&format!("ewf:{}!p2/etc", test_location.display())).unwrap();
+        assert!(result.iter().any(|entry| entry.ends_with("/etc/hostname")));
+
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests");
+        let result = list_files_any(&test_location.display().to_string()).unwrap();
+        assert!(result.iter().any(|entry| entry.ends_with("ntfs_tester.rs")));
+    }
+
+    #[test]
+    fn test_reader_any() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/ewf/gpt_ext4.E01");
+        let mut reader =
+            reader_any(&format!("ewf:{}!p2/etc/hostname", test_location.display())).unwrap();
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, b"artemis-image\n");
+    }
+
     #[test]
     fn test_read_text_file() {
         let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
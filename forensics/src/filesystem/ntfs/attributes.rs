@@ -6,10 +6,7 @@ use ntfs::{
     attribute_value::NtfsAttributeValue,
     structured_values::{NtfsAttributeList, NtfsFileName},
 };
-use std::{
-    fs::File,
-    io::{BufReader, Error, ErrorKind},
-};
+use std::io::{BufReader, Error, ErrorKind, Read, Seek};
 use tracing::{error, warn};
 
 /// Return FILENAME attribute data
@@ -26,11 +23,11 @@ pub(crate) fn get_filename_attribute(
 }
 
 /// Get attribute data by walking the attribute list until we find our attribute or reading the attribute directly. Returns a vec data from the data runs
-pub(crate) fn get_attribute_data(
+pub(crate) fn get_attribute_data<T: Read + Seek>(
     //ntfs_ref: NtfsFileReference,
     ntfs_file: &NtfsFile<'_>,
     ntfs: &Ntfs,
-    fs: &mut BufReader<SectorReader<File>>,
+    fs: &mut BufReader<SectorReader<T>>,
     attribute: &str,
 ) -> Result<Vec<u8>, NtfsError> {
     let attr_raw = ntfs_file.attributes_raw();
@@ -83,9 +80,9 @@ pub(crate) fn get_attribute_data(
 }
 
 /// Get the size of a file by parsing the NTFS filesystem
-pub(crate) fn get_raw_file_size(
+pub(crate) fn get_raw_file_size<T: Read + Seek>(
     ntfs: &NtfsFile<'_>,
-    fs: &mut BufReader<SectorReader<File>>,
+    fs: &mut BufReader<SectorReader<T>>,
 ) -> Result<u64, NtfsError> {
     let attrib = match ntfs.data(fs, "") {
         Some(result) => result,
@@ -100,9 +97,9 @@ pub(crate) fn get_raw_file_size(
 }
 
 /// Read the attribute data. Handles both resident and non-resident data.
-pub(crate) fn read_attribute_data(
+pub(crate) fn read_attribute_data<T: Read + Seek>(
     value: &mut NtfsAttributeValue<'_, '_>,
-    fs: &mut BufReader<SectorReader<File>>,
+    fs: &mut BufReader<SectorReader<T>>,
     entry_attr: &NtfsAttribute<'_, '_>,
 ) -> Result<Vec<u8>, NtfsError> {
     // If attribute data is resident, just read the all the data. Resident data is very small
@@ -11,7 +11,7 @@ use crate::{
 use nom::bytes::complete::take;
 use ntfs::NtfsFile;
 use ntfs::{Ntfs, NtfsAttributeType, NtfsError, structured_values::NtfsAttributeList};
-use std::{fs::File, io::BufReader};
+use std::io::{BufReader, Read, Seek};
 use tracing::{error, warn};
 
 /**
@@ -20,10 +20,10 @@ use tracing::{error, warn};
  * The actual file data is compressed in the Alternative Data Stream (ADS) `WofCompressedData`
  * We need to decompress the data in order to get the actual file contents
  */
-pub(crate) fn check_wofcompressed(
+pub(crate) fn check_wofcompressed<T: Read + Seek>(
     ntfs_file: &NtfsFile<'_>,
     ntfs: &Ntfs,
-    fs: &mut BufReader<SectorReader<File>>,
+    fs: &mut BufReader<SectorReader<T>>,
 ) -> Result<(bool, Vec<u8>, u64), NtfsError> {
     let ads = "WofCompressedData";
     let compressed_data = get_attribute_data(ntfs_file, ntfs, fs, ads)?;
@@ -96,10 +96,10 @@ pub(crate) fn check_wofcompressed(
 }
 
 /// Get the compressed data and determine compression unit
-fn grab_reparsepoint(
+fn grab_reparsepoint<T: Read + Seek>(
     ntfs_file: &NtfsFile<'_>,
     ntfs: &Ntfs,
-    fs: &mut BufReader<SectorReader<File>>,
+    fs: &mut BufReader<SectorReader<T>>,
 ) -> Result<u32, NtfsError> {
     //let ntfs_file = ntfs_ref.to_file(ntfs, fs)?;
 
@@ -4,3 +4,4 @@ pub(crate) mod raw_files;
 pub(crate) mod reader;
 pub(crate) mod sector_reader;
 pub(crate) mod setup;
+pub(crate) mod unallocated;
@@ -5,9 +5,14 @@ use super::{
     setup::setup_ntfs_parser,
 };
 use crate::{
+    accessor::location::loc::Location,
     artifacts::os::systeminfo::info::get_platform,
-    filesystem::{error::FileSystemError, files::read_file_custom},
+    filesystem::{
+        error::FileSystemError,
+        files::{read_any, read_file_custom},
+    },
     utils::{
+        cancel::CancelToken,
         regex_options::{create_regex, regex_check},
         strings::strings_contains,
     },
@@ -22,16 +27,13 @@ use ntfs::{
 use regex::Regex;
 use sha1::Sha1;
 use sha2::Sha256;
-use std::{
-    fs::File,
-    io::{BufReader, copy},
-};
+use std::io::{BufReader, Read, Seek, copy};
 use tracing::{error, warn};
 
 /// Read the whole attribute data. This can be used to read a whole file
-pub(crate) fn raw_read_data(
+pub(crate) fn raw_read_data<T: Read + Seek>(
     data_attr_value: &mut NtfsAttributeValue<'_, '_>,
-    fs: &mut BufReader<SectorReader<File>>,
+    fs: &mut BufReader<SectorReader<T>>,
 ) -> Result<Vec<u8>, NtfsError> {
     let mut buff_data: Vec<u8> = Vec::new();
     loop {
@@ -54,10 +56,10 @@ pub(crate) fn raw_read_data(
 }
 
 /// Return the file reference number for a file. Can be used to create reader to stream the file
-pub(crate) fn raw_reader<'a>(
+pub(crate) fn raw_reader<'a, T: Read + Seek>(
     path: &str,
     ntfs: &'a Ntfs,
-    fs: &mut BufReader<SectorReader<File>>,
+    fs: &mut BufReader<SectorReader<T>>,
 ) -> Result<NtfsFile<'a>, FileSystemError> {
     let min_path_len = 4;
     if path.len() < min_path_len || !path.contains(':') {
@@ -110,11 +112,12 @@ pub(crate) fn raw_reader<'a>(
     Err(FileSystemError::OpenFile)
 }
 
-/// Given a file $DATA attribute, read and hash the data
-pub(crate) fn raw_hash_data(
+/// Given a file $DATA attribute, read and hash the data. Returns empty hashes if `cancel` is cancelled before all data is read
+pub(crate) fn raw_hash_data<T: Read + Seek>(
     data_attr_value: &mut NtfsAttributeValue<'_, '_>,
-    fs: &mut BufReader<SectorReader<File>>,
+    fs: &mut BufReader<SectorReader<T>>,
     hash_data: &Hashes,
+    cancel: &CancelToken,
 ) -> (String, String, String) {
     let mut md5 = IoWrapper(Md5::new());
     let mut sha1 = IoWrapper(Sha1::new());
@@ -134,6 +137,10 @@ pub(crate) fn raw_hash_data(
         if bytes == finished {
             break;
         }
+        if cancel.is_cancelled() {
+            warn!("Collection cancelled. Stopped hashing raw file data");
+            return (String::new(), String::new(), String::new());
+        }
 
         // Make sure our temp buff does not have any extra zeros from the initialization
         if bytes < temp_buff_size {
@@ -174,8 +181,12 @@ pub(crate) fn raw_hash_data(
     (md5_string, sha1_string, sha256_string)
 }
 
-/// Read a single file by parsing the NTFS system
+/// Read a single file by parsing the NTFS system. Files in archives, disk images or snapshots are read with the `Accessor`
 pub(crate) fn raw_read_file(path: &str) -> Result<Vec<u8>, FileSystemError> {
+    if Location::is_container(path) {
+        return read_any(path);
+    }
+
     // Raw file access only works on Windows. For all other platforms redirect to normal file access
     let platform = get_platform();
     if platform != "Windows" {
@@ -249,10 +260,10 @@ pub(crate) fn raw_read_file(path: &str) -> Result<Vec<u8>, FileSystemError> {
 * `WofCompression` can be detected by checking for the alternative data stream (ADS) attribute `WofCompressedData`
 * `raw_read_by_file_ref` can decompress the data and return a hash of the uncompressed data
 */
-pub(crate) fn raw_read_by_file_ref(
+pub(crate) fn raw_read_by_file_ref<T: Read + Seek>(
     ntfs_ref: NtfsFileReference,
     ntfs: &Ntfs,
-    fs: &mut BufReader<SectorReader<File>>,
+    fs: &mut BufReader<SectorReader<T>>,
 ) -> Result<Vec<u8>, FileSystemError> {
     let ntfs_file = match ntfs_ref.to_file(ntfs, fs) {
         Ok(result) => result,
@@ -471,9 +482,9 @@ pub(crate) struct NtfsEntry {
 }
 
 /// Iterate through the NTFS system and return entries based on provided start path and any regexes. Can be used to search for a file(s)
-pub(crate) fn iterate_ntfs(
+pub(crate) fn iterate_ntfs<T: Read + Seek>(
     root_dir: NtfsFile<'_>,
-    fs: &mut BufReader<SectorReader<File>>,
+    fs: &mut BufReader<SectorReader<T>>,
     ntfs: &Ntfs,
     params: &mut NtfsOptions,
 ) -> Result<(), FileSystemError> {
@@ -575,7 +586,7 @@ mod tests {
             sector_reader::SectorReader,
             setup::setup_ntfs_parser,
         },
-        utils::regex_options::create_regex,
+        utils::{cancel::CancelToken, regex_options::create_regex},
     };
     use common::files::Hashes;
     use ntfs::Ntfs;
@@ -612,7 +623,8 @@ mod tests {
                 let ntfs_data = ntfs_file.data(&mut fs, "").unwrap().unwrap();
                 let ntfs_attribute = ntfs_data.to_attribute().unwrap();
                 let mut data_attr_value = ntfs_attribute.value(&mut fs).unwrap();
-                let (md5, sha1, sha256) = raw_hash_data(&mut data_attr_value, &mut fs, &hashes);
+                let (md5, sha1, sha256) =
+                    raw_hash_data(&mut data_attr_value, &mut fs, &hashes, &CancelToken::new());
 
                 assert_eq!(md5.is_empty(), false);
                 assert_eq!(sha1.is_empty(), false);
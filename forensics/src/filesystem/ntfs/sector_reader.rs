@@ -1,5 +1,6 @@
 // Full credit to: https://github.com/ColinFinck/ntfs/blob/master/examples/ntfs-shell/sector_reader.rs - MIT/Apache License - 2022-11-07
 
+use crate::utils::governor::throttle;
 use std::io;
 use std::io::{Read, Seek, SeekFrom};
 
@@ -71,6 +72,7 @@ where
         // Perform the sector-sized read and copy the actually requested bytes into the given buffer.
         self.temp_buf.resize(aligned_bytes_to_read, 0);
         self.inner.read_exact(&mut self.temp_buf)?;
+        throttle(self.temp_buf.len());
         buf.copy_from_slice(&self.temp_buf[start..end]);
 
         // We are done.
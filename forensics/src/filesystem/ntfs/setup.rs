@@ -1,12 +1,21 @@
 use super::sector_reader::SectorReader;
-use crate::filesystem::error::FileSystemError;
+use crate::{
+    accessor::{
+        entry::locator::DiskImage,
+        filesystem::image::{data::open_partition_reader, volume::PartitionReader},
+    },
+    filesystem::error::FileSystemError,
+};
 use ntfs::Ntfs;
-use std::{fs::File, io::BufReader};
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek},
+};
 use tracing::error;
 
-pub(crate) struct NtfsParser {
+pub(crate) struct NtfsParser<T: Read + Seek = File> {
     pub(crate) ntfs: Ntfs,
-    pub(crate) fs: BufReader<SectorReader<File>>,
+    pub(crate) fs: BufReader<SectorReader<T>>,
 }
 
 /// Setup NTFS parser by opening drive letter and creating Sector Reader
@@ -41,8 +50,40 @@ pub(crate) fn setup_ntfs_parser(drive_letter: char) -> Result<NtfsParser, FileSy
     Ok(ntfs_parser)
 }
 
+/// Setup NTFS parser for a partition in a disk image. Example: partition 2 of `ewf:case.E01`
+pub(crate) fn setup_ntfs_image_parser(
+    image: &DiskImage,
+    partition: u32,
+) -> Result<NtfsParser<PartitionReader>, FileSystemError> {
+    let reader = match open_partition_reader(image, partition) {
+        Ok(result) => result,
+        Err(err) => {
+            error!(
+                "Failed to open partition {partition} in {}, error: {err}",
+                image.display()
+            );
+            return Err(FileSystemError::OpenFile);
+        }
+    };
+
+    // Size used for reader setup
+    let reader_size = 4096;
+    let sector_reader = match SectorReader::new(reader, reader_size) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Failed to setup sector reader, error: {err:?}");
+            return Err(FileSystemError::NtfsSectorReader);
+        }
+    };
+
+    let mut fs = BufReader::new(sector_reader);
+    let ntfs = get_ntfs(&mut fs)?;
+
+    Ok(NtfsParser { ntfs, fs })
+}
+
 /// Create NTFS object
-fn get_ntfs(fs: &mut BufReader<SectorReader<File>>) -> Result<Ntfs, FileSystemError> {
+fn get_ntfs<T: Read + Seek>(fs: &mut BufReader<SectorReader<T>>) -> Result<Ntfs, FileSystemError> {
     let ntfs_result = Ntfs::new(fs);
     match ntfs_result {
         Ok(result) => Ok(result),
@@ -0,0 +1,338 @@
+use super::{attributes::get_raw_file_size, raw_files::raw_read_file, sector_reader::SectorReader};
+use crate::{filesystem::error::FileSystemError, utils::cancel::CancelToken};
+use ntfs::{Ntfs, NtfsAttributeType, NtfsFile, NtfsFileFlags, attribute_value::NtfsAttributeValue};
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use tracing::{error, warn};
+
+/// Byte range on the volume that is not allocated to any file or is slack space
+#[derive(Debug, PartialEq)]
+pub(crate) struct VolumeRange {
+    pub(crate) offset: u64,
+    pub(crate) size: u64,
+}
+
+/// Get unallocated ranges for a volume by reading the `$Bitmap` file
+pub(crate) fn get_unallocated(
+    drive: char,
+    ntfs: &Ntfs,
+) -> Result<Vec<VolumeRange>, FileSystemError> {
+    let bitmap = raw_read_file(&format!("{drive}:\\$Bitmap"))?;
+    let cluster_size = ntfs.cluster_size() as u64;
+    if cluster_size == 0 {
+        error!("NTFS cluster size is zero. Cannot determine unallocated space");
+        return Err(FileSystemError::NoAttribute);
+    }
+
+    let clusters = ntfs.size() / cluster_size;
+    Ok(unallocated_ranges(&bitmap, cluster_size, clusters))
+}
+
+/// Convert the `$Bitmap` data into byte ranges of unallocated clusters. Each bit represents one cluster
+pub(crate) fn unallocated_ranges(
+    bitmap: &[u8],
+    cluster_size: u64,
+    clusters: u64,
+) -> Vec<VolumeRange> {
+    let mut ranges: Vec<VolumeRange> = Vec::new();
+    let bits = 8;
+
+    for cluster in 0..clusters.min(bitmap.len() as u64 * bits) {
+        let allocated = bitmap[(cluster / bits) as usize] & (1 << (cluster % bits)) != 0;
+        if allocated {
+            continue;
+        }
+
+        let offset = cluster * cluster_size;
+        // Merge consecutive unallocated clusters
+        if let Some(last) = ranges.last_mut()
+            && last.offset + last.size == offset
+        {
+            last.size += cluster_size;
+            continue;
+        }
+        ranges.push(VolumeRange {
+            offset,
+            size: cluster_size,
+        });
+    }
+
+    ranges
+}
+
+/// Get file slack and `MFT` record slack ranges for a volume by walking every FILE record in the `MFT`
+pub(crate) fn get_slack<T: Read + Seek>(
+    ntfs: &Ntfs,
+    fs: &mut BufReader<SectorReader<T>>,
+    cancel: &CancelToken,
+) -> Result<Vec<VolumeRange>, FileSystemError> {
+    let mft_size = match ntfs.file(fs, 0).and_then(|mft| get_raw_file_size(&mft, fs)) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Could not determine MFT size for slack space: {err:?}");
+            return Err(FileSystemError::ReadFile);
+        }
+    };
+
+    let record_size = ntfs.file_record_size() as u64;
+    if record_size == 0 {
+        error!("NTFS file record size is zero. Cannot determine slack space");
+        return Err(FileSystemError::NoAttribute);
+    }
+    let sector_size = ntfs.sector_size() as u64;
+
+    let mut ranges = Vec::new();
+    for index in 0..mft_size / record_size {
+        if cancel.is_cancelled() {
+            warn!("Collection cancelled. Stopping slack scan at MFT entry {index}");
+            break;
+        }
+        // Unused or corrupt FILE records cannot be parsed
+        let Ok(file) = ntfs.file(fs, index) else {
+            continue;
+        };
+        let Some(position) = file.position().value() else {
+            continue;
+        };
+
+        // Bytes after the end of the FILE record attributes
+        let record_end = position.get() + file.allocated_size() as u64;
+        let used_end = position.get() + file.data_size() as u64;
+        if record_end > used_end {
+            ranges.push(VolumeRange {
+                offset: used_end,
+                size: record_end - used_end,
+            });
+        }
+
+        // Clusters of deleted files may already belong to another file
+        if !file.flags().contains(NtfsFileFlags::IN_USE) || is_extension_record(fs, position.get())
+        {
+            continue;
+        }
+        ranges.append(&mut file_slack(&file, fs, sector_size));
+    }
+
+    Ok(ranges)
+}
+
+/// Check if the FILE record at the provided volume offset is an extension of another FILE record
+fn is_extension_record<T: Read + Seek>(fs: &mut BufReader<SectorReader<T>>, offset: u64) -> bool {
+    // Base FILE record reference is at offset 0x20 of the header
+    let base_offset = 0x20;
+    match read_volume(fs, offset + base_offset, 8) {
+        Ok(base) => base.iter().any(|value| *value != 0),
+        Err(_) => true,
+    }
+}
+
+/// Get the slack space of the non-resident `$DATA` attributes of a FILE record
+fn file_slack<T: Read + Seek>(
+    file: &NtfsFile<'_>,
+    fs: &mut BufReader<SectorReader<T>>,
+    sector_size: u64,
+) -> Vec<VolumeRange> {
+    let mut ranges = Vec::new();
+    for attr_result in file.attributes_raw() {
+        let Ok(attr) = attr_result else {
+            continue;
+        };
+        if attr.is_resident() || !matches!(attr.ty(), Ok(NtfsAttributeType::Data)) {
+            continue;
+        }
+        // Files with an AttributeList are skipped. The data runs may be spread across multiple FILE records
+        let Ok(NtfsAttributeValue::NonResident(value)) = attr.value(fs) else {
+            continue;
+        };
+
+        let mut runs = Vec::new();
+        for run_result in value.data_runs() {
+            let Ok(run) = run_result else {
+                break;
+            };
+            runs.push((
+                run.data_position().value().map(|position| position.get()),
+                run.allocated_size(),
+            ));
+        }
+        ranges.append(&mut slack_ranges(&runs, attr.value_length(), sector_size));
+    }
+
+    ranges
+}
+
+/// Map the bytes between the data size and the allocated size of an attribute to volume ranges.
+/// Each run is the volume offset (`None` if sparse) and the allocated size of the run.
+/// Slack starts at the next sector because the rest of the last sector is zeroed by Windows
+pub(crate) fn slack_ranges(
+    runs: &[(Option<u64>, u64)],
+    data_size: u64,
+    sector_size: u64,
+) -> Vec<VolumeRange> {
+    let mut ranges = Vec::new();
+    let slack_start = if sector_size == 0 {
+        data_size
+    } else {
+        data_size.div_ceil(sector_size) * sector_size
+    };
+
+    let mut logical = 0;
+    for (position, size) in runs {
+        let run_end = logical + size;
+        if run_end > slack_start
+            && let Some(offset) = position
+        {
+            let skip = slack_start.saturating_sub(logical);
+            ranges.push(VolumeRange {
+                offset: offset + skip,
+                size: size - skip,
+            });
+        }
+        logical = run_end;
+    }
+
+    ranges
+}
+
+/// Read the provided volume ranges in chunks and pass each chunk and the volume reader to the `scan` function.
+/// Each chunk includes `overlap` extra bytes so records at the end of a chunk are not split.
+/// The `scan` function should only report records that start before `chunk.len() - overlap`
+pub(crate) fn scan_ranges<
+    T: Read + Seek,
+    F: FnMut(&mut BufReader<SectorReader<T>>, u64, &[u8], usize),
+>(
+    fs: &mut BufReader<SectorReader<T>>,
+    ranges: &[VolumeRange],
+    overlap: u64,
+    cancel: &CancelToken,
+    mut scan: F,
+) {
+    // Read 4MB at a time
+    let chunk_size = 4 * 1024 * 1024;
+
+    for range in ranges {
+        let mut offset = range.offset;
+        let end = range.offset + range.size;
+        while offset < end {
+            if cancel.is_cancelled() {
+                warn!("Collection cancelled. Stopping unallocated scan at offset {offset}");
+                return;
+            }
+            let scan_size = chunk_size.min(end - offset);
+            let read_size = (scan_size + overlap).min(end - offset);
+
+            let data = match read_volume(fs, offset, read_size) {
+                Ok(result) => result,
+                Err(err) => {
+                    warn!("Could not read volume at offset {offset}: {err:?}");
+                    offset += scan_size;
+                    continue;
+                }
+            };
+            scan(fs, offset, &data, scan_size as usize);
+            offset += scan_size;
+        }
+    }
+}
+
+/// Read bytes from the raw volume
+fn read_volume<T: Read + Seek>(
+    fs: &mut BufReader<SectorReader<T>>,
+    offset: u64,
+    size: u64,
+) -> Result<Vec<u8>, FileSystemError> {
+    if let Err(err) = fs.seek(SeekFrom::Start(offset)) {
+        error!("Could not seek to volume offset {offset}: {err:?}");
+        return Err(FileSystemError::ReadFile);
+    }
+
+    let mut data = vec![0; size as usize];
+    if let Err(err) = fs.read_exact(&mut data) {
+        error!("Could not read volume bytes at {offset}: {err:?}");
+        return Err(FileSystemError::ReadFile);
+    }
+
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{VolumeRange, scan_ranges, slack_ranges, unallocated_ranges};
+    use crate::filesystem::ntfs::sector_reader::SectorReader;
+    use crate::utils::cancel::CancelToken;
+    use std::io::{BufReader, Cursor};
+
+    #[test]
+    fn test_unallocated_ranges() {
+        // Clusters 0-1 allocated, 2-4 free, 5 allocated, 6-7 free, 8-9 free
+        let bitmap = [0b0010_0011, 0b0000_0000];
+        let ranges = unallocated_ranges(&bitmap, 4096, 10);
+        assert_eq!(
+            ranges,
+            vec![
+                VolumeRange {
+                    offset: 8192,
+                    size: 12288
+                },
+                VolumeRange {
+                    offset: 24576,
+                    size: 16384
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_slack_ranges() {
+        // 10000 bytes of data in two runs with a sparse run between them
+        let runs = [(Some(40960), 4096), (None, 4096), (Some(81920), 8192)];
+        let ranges = slack_ranges(&runs, 10000, 512);
+        assert_eq!(
+            ranges,
+            vec![VolumeRange {
+                offset: 83968,
+                size: 6144
+            }]
+        );
+
+        // Data fills all allocated clusters
+        assert!(slack_ranges(&[(Some(4096), 4096)], 4096, 512).is_empty());
+    }
+
+    #[test]
+    fn test_scan_ranges() {
+        let volume = vec![1u8; 16384];
+        let mut fs = BufReader::new(SectorReader::new(Cursor::new(volume), 512).unwrap());
+        let ranges = vec![VolumeRange {
+            offset: 1024,
+            size: 4096,
+        }];
+
+        let mut scanned = Vec::new();
+        scan_ranges(
+            &mut fs,
+            &ranges,
+            512,
+            &CancelToken::new(),
+            |_, offset, data, scan_size| {
+                scanned.push((offset, data.len(), scan_size));
+            },
+        );
+        assert_eq!(scanned, vec![(1024, 4096, 4096)]);
+    }
+
+    #[test]
+    fn test_scan_ranges_cancelled() {
+        let volume = vec![1u8; 16384];
+        let mut fs = BufReader::new(SectorReader::new(Cursor::new(volume), 512).unwrap());
+        let ranges = vec![VolumeRange {
+            offset: 1024,
+            size: 4096,
+        }];
+        let cancel = CancelToken::new();
+        cancel.cancel();
+
+        let mut scanned = 0;
+        scan_ranges(&mut fs, &ranges, 512, &cancel, |_, _, _, _| scanned += 1);
+        assert_eq!(scanned, 0);
+    }
+}
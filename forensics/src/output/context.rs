@@ -1,4 +1,10 @@
 use crate::{
+    accessor::{
+        entry::locator::{ShadowCopy, SourceId},
+        filesystem::vss::shadow_copy_store,
+        location::loc::Location,
+        source::factory::source_id_from_location,
+    },
     artifacts::os::systeminfo::info::get_info_metadata,
     structs::toml::OutputConfig,
     utils::{
@@ -8,7 +14,13 @@ use crate::{
 };
 use common::system::SystemInfoMetadata;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{cell::RefCell, path::PathBuf};
+use tracing::warn;
+
+thread_local! {
+    /// Volume Shadow Copy the running artifact read its data from. Artifacts run on the collection thread
+    static ARTIFACT_SNAPSHOT: RefCell<Option<ShadowCopy>> = const { RefCell::new(None) };
+}
 
 /// Context shared across an entire Artemis collection
 ///
@@ -59,6 +71,21 @@ pub(crate) struct ArtifactContext {
     pub(crate) end_time_filter: Option<String>,
     /// Metadata associated with the target system
     pub(crate) system: SystemInfoMetadata,
+    /// Volume Shadow Copy the artifact was parsed from
+    pub(crate) snapshot: Option<SnapshotContext>,
+}
+
+/// Volume Shadow Copy snapshot metadata for artifacts parsed from a `vss:` path
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub(crate) struct SnapshotContext {
+    /// Snapshot source. Example: `vss:C@3`
+    pub(crate) source: String,
+    /// Shadow copy store number
+    pub(crate) store: u32,
+    /// Snapshot GUID
+    pub(crate) store_id: String,
+    /// Snapshot creation time
+    pub(crate) created: String,
 }
 
 impl CollectionContext {
@@ -96,13 +123,58 @@ impl CollectionContext {
             system: self.system.clone(),
             start_time_filter: start_time_filter.clone(),
             end_time_filter: end_time_filter.clone(),
+            snapshot: None,
+        }
+    }
+}
+
+/// Track the Volume Shadow Copy of a container path opened by the running artifact. Example: `vss:C@3!/Windows/Prefetch`
+pub(crate) fn record_source(path: &str) {
+    let Ok(location) = Location::parse(path) else {
+        return;
+    };
+    if let Ok(SourceId::Vss(snapshot)) = source_id_from_location(&location) {
+        ARTIFACT_SNAPSHOT.set(Some(snapshot));
+    }
+}
+
+/// Clear the tracked Volume Shadow Copy before the next artifact runs
+pub(crate) fn clear_source() {
+    ARTIFACT_SNAPSHOT.set(None);
+}
+
+/// Get the snapshot metadata for the Volume Shadow Copy the running artifact read its data from
+///
+/// The `cached` snapshot metadata is returned if it is for the same snapshot, so the snapshot store is only read once
+pub(crate) fn snapshot_context(cached: &Option<SnapshotContext>) -> Option<SnapshotContext> {
+    let snapshot = ARTIFACT_SNAPSHOT.with_borrow(Clone::clone)?;
+    let source = snapshot.display();
+    if let Some(cached) = cached
+        && cached.source == source
+    {
+        return Some(cached.clone());
+    }
+
+    match shadow_copy_store(&snapshot) {
+        Ok(store) => Some(SnapshotContext {
+            source,
+            store: store.number,
+            store_id: store.store_id,
+            created: store.created,
+        }),
+        Err(err) => {
+            warn!("[output] Could not read snapshot metadata for {source}: {err:?}");
+            None
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{output::context::CollectionContext, structs::toml::OutputConfig};
+    use crate::{
+        output::context::{CollectionContext, clear_source, record_source, snapshot_context},
+        structs::toml::OutputConfig,
+    };
     use std::path::PathBuf;
 
     #[test]
@@ -117,4 +189,30 @@ mod tests {
         assert_eq!(artifact.collection_name, "");
         assert_eq!(artifact.artifact_name, "processes");
     }
+
+    #[test]
+    fn test_snapshot_context() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/vss/ext4_vss.raw");
+
+        clear_source();
+        assert!(snapshot_context(&None).is_none());
+
+        // Host paths do not change the tracked snapshot
+        record_source(&format!("vss:{}@2!/etc/hostname", test_location.display()));
+        record_source("/etc/hostname");
+        let snapshot = snapshot_context(&None).unwrap();
+        assert_eq!(snapshot.store, 2);
+        assert_eq!(snapshot.store_id, "66666666-7777-8888-9999-aaaaaaaaaaaa");
+        assert_eq!(snapshot.created, "2024-02-01T00:00:00.000Z");
+        assert!(snapshot.source.ends_with("ext4_vss.raw@2"));
+
+        // Metadata for the same snapshot is only read once
+        let mut cached = snapshot.clone();
+        cached.created = String::from("cached");
+        assert_eq!(snapshot_context(&Some(cached.clone())), Some(cached));
+
+        clear_source();
+        assert!(snapshot_context(&Some(snapshot)).is_none());
+    }
 }
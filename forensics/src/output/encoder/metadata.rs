@@ -6,9 +6,7 @@ use serde_json::{Value, json};
 /// This only appended to JSON objects. Scalar output types do not get metadata
 pub(crate) fn append_metadata(record: &mut Value, context: &ArtifactContext) {
     if let Value::Object(fields) = record {
-        fields.insert(
-            String::from("collection_metadata"),
-            json!({
+        let mut metadata = json!({
                 "endpoint_id": context.endpoint_id,
                 "id": context.collection_id,
                 "collection_name": context.collection_name,
@@ -25,7 +23,11 @@ pub(crate) fn append_metadata(record: &mut Value, context: &ArtifactContext) {
                 "rust_version": context.system.rust_version,
                 "build_date": context.system.build_date,
                 "interfaces": context.system.interfaces,
-            }),
-        );
+        });
+        // Artifacts parsed from a Volume Shadow Copy include the snapshot creation time
+        if let Some(snapshot) = &context.snapshot {
+            metadata["snapshot"] = json!(snapshot);
+        }
+        fields.insert(String::from("collection_metadata"), metadata);
     }
 }
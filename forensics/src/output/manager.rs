@@ -1,19 +1,20 @@
 use crate::{
     output::{
-        context::{ArtifactContext, CollectionContext},
+        context::{ArtifactContext, CollectionContext, SnapshotContext, snapshot_context},
         encoder::{
             artifact_encoder::{Encoder, EncoderMode, StreamWriter},
             factory::build_encoder,
         },
         error::OutputResult,
-        record::RecordStream,
-        report::{ArtifactRunReport, CollectionReport, hash_artifact_options},
+        record::{CancelRecordStream, RecordStream},
+        report::{ArtifactRunReport, CollectionReport, hash_artifact_options, verify_evidence},
         sink::{
             factory::{Sink, build_sink},
             output_handle::OutputHandle,
         },
     },
     structs::toml::OutputConfig,
+    utils::{cancel::CancelToken, governor::Governor},
 };
 use serde::Serialize;
 use serde_json::Value;
@@ -38,6 +39,13 @@ pub(crate) struct OutputManager {
     /// Array of artifacts collected from the Artemis execution
     pub(crate) artifact_runs: Vec<ArtifactRunReport>,
     pub(crate) filter: bool,
+    /// Cancellation token for the Artemis collection. Checked between records.
+    /// Also enforces the `ResourcePolicy` for the collection
+    pub(crate) cancel: CancelToken,
+    /// Set if the collection was cancelled before all artifacts were collected
+    pub(crate) cut_short: bool,
+    /// Snapshot metadata for the Volume Shadow Copy the last artifact output was read from
+    snapshot: Option<SnapshotContext>,
     active_stream: Option<ActiveStream>,
 }
 
@@ -48,6 +56,7 @@ struct ActiveStream {
     output_file: String,
     record_count: usize,
     writer: StreamWriter,
+    timeout: bool,
 }
 
 impl OutputManager {
@@ -82,6 +91,9 @@ impl OutputManager {
             artifacts: Vec::new(),
             artifact_runs: Vec::new(),
             filter: false,
+            cancel: CancelToken::default(),
+            cut_short: false,
+            snapshot: None,
             active_stream: None,
         })
     }
@@ -93,9 +105,16 @@ impl OutputManager {
         artifact_options: &T,
         records: &mut dyn RecordStream,
     ) -> OutputResult<()> {
+        let cancel = self.cancel.clone();
+        if cancel.is_cancelled() {
+            self.record_timeout_artifact(artifact_name, artifact_options);
+            return Ok(());
+        }
+        let mut records = CancelRecordStream::new(records, &cancel);
+
         match self.encoder.encoder_mode() {
             EncoderMode::Chunked => {
-                let handle = self.write(artifact_name, records)?;
+                let handle = self.write(artifact_name, &mut records)?;
 
                 if !self.artifacts.iter().any(|name| name == artifact_name) {
                     self.artifacts.push(artifact_name.to_string());
@@ -106,11 +125,16 @@ impl OutputManager {
                     handle.location_string(),
                     handle.record_count,
                 );
-
-                Ok(())
             }
-            EncoderMode::Streamed => self.write_stream(artifact_name, artifact_options, records),
+            EncoderMode::Streamed => {
+                self.write_stream(artifact_name, artifact_options, &mut records)?;
+            }
+        }
+
+        if records.cancelled {
+            self.record_timeout_artifact(artifact_name, artifact_options);
         }
+        Ok(())
     }
 
     /// Write a failed artifact run
@@ -135,12 +159,21 @@ impl OutputManager {
     pub(crate) fn finalize(mut self) -> OutputResult<()> {
         // Complete any active writer stream
         self.finish_stream()?;
-        let report = CollectionReport::new(
+        // Only report a timeout if an artifact was actually cut short by the cancellation
+        let cut_short = self.was_cut_short();
+        let mut report = CollectionReport::new(
             &self.config,
             &self.context,
             self.artifacts,
             self.artifact_runs,
         );
+        if cut_short {
+            report.status = String::from("timeout");
+        }
+        if let Some(paths) = &self.config.verify_evidence {
+            report.evidence = verify_evidence(paths, &self.cancel);
+        }
+        report.resources = self.cancel.governor().map(Governor::report);
         self.sink.write_report(&report)?;
         self.sink.finalize()
     }
@@ -175,17 +208,53 @@ impl OutputManager {
         ));
     }
 
+    /// Check if the cancellation stopped any artifact before it was fully collected
+    pub(crate) fn was_cut_short(&self) -> bool {
+        self.cut_short || self.artifact_runs.iter().any(|run| run.status == "timeout")
+    }
+
+    /// Mark an artifact run as `timeout` if the collection was cancelled before all records were written
+    fn record_timeout_artifact<T: Serialize>(&mut self, artifact_name: &str, artifact_options: &T) {
+        if !self.artifacts.iter().any(|name| name == artifact_name) {
+            self.artifacts.push(artifact_name.to_string());
+        }
+        let hash = hash_artifact_options(&artifact_options).unwrap_or_default();
+        if let Some(active) = self.active_stream.as_mut().filter(|active| {
+            active.artifact_name == artifact_name && active.artifact_options_hash == hash
+        }) {
+            active.timeout = true;
+            return;
+        }
+        if let Some(run) = self
+            .artifact_runs
+            .iter_mut()
+            .find(|run| run.name == artifact_name && run.artifact_options_hash == hash)
+        {
+            run.status = String::from("timeout");
+            return;
+        }
+        self.artifact_runs.push(ArtifactRunReport::new(
+            artifact_name,
+            artifact_options,
+            Vec::new(),
+            0,
+            "timeout",
+        ));
+    }
+
     /// Write artifact records to our configured destination `Sink`
     fn write(
         &mut self,
         artifact_name: &str,
         records: &mut dyn RecordStream,
     ) -> OutputResult<OutputHandle> {
-        let artifact_context = self.context.artifact(
+        let mut artifact_context = self.context.artifact(
             artifact_name,
             &self.config.start_time_filter,
             &self.config.end_time_filter,
         );
+        self.snapshot = snapshot_context(&self.snapshot);
+        artifact_context.snapshot = self.snapshot.clone();
         // If boa is enabled and we have a filter script
         // Filter records before writing them to Sink
         #[cfg(feature = "boa")]
@@ -237,11 +306,13 @@ impl OutputManager {
         artifact_options: &T,
         records: &mut dyn RecordStream,
     ) -> OutputResult<()> {
-        let artifact_context = self.context.artifact(
+        let mut artifact_context = self.context.artifact(
             artifact_name,
             &self.config.start_time_filter,
             &self.config.end_time_filter,
         );
+        self.snapshot = snapshot_context(&self.snapshot);
+        artifact_context.snapshot = self.snapshot.clone();
 
         // If boa is enabled and we have a filter script
         // Filter records before writing them to Sink
@@ -314,6 +385,7 @@ impl OutputManager {
             output_file,
             record_count: open.record_count,
             writer: open.writer,
+            timeout: false,
         });
         if !self.artifacts.iter().any(|name| name == artifact_name) {
             self.artifacts.push(artifact_name.to_string());
@@ -333,6 +405,7 @@ impl OutputManager {
             output_file,
             record_count,
             writer,
+            timeout,
         } = output;
 
         writer.finish()?;
@@ -342,6 +415,7 @@ impl OutputManager {
             artifact_options,
             output_file,
             record_count,
+            timeout,
         );
         Ok(())
     }
@@ -354,11 +428,16 @@ impl OutputManager {
         artifact_options: Value,
         output_file: String,
         record_count: usize,
+        timeout: bool,
     ) {
+        let status = if timeout { "timeout" } else { "completed" };
         if let Some(run) = self.artifact_runs.iter_mut().find(|run| {
             run.name == artifact_name && run.artifact_options_hash == artifact_option_hash
         }) {
             run.add_output_file(output_file, record_count);
+            if timeout {
+                run.status = status.to_string();
+            }
             return;
         }
 
@@ -367,7 +446,7 @@ impl OutputManager {
             &artifact_options,
             vec![output_file],
             record_count,
-            "completed",
+            status,
         );
         run.artifact_options_hash = artifact_option_hash;
         self.artifact_runs.push(run);
@@ -391,14 +470,15 @@ mod tests {
         manager::OutputManager,
         record::{JsonRecord, Record, ScalarRecord, VecRecordStream},
     };
-    use crate::structs::toml::{OutputConfig, OutputDestination, OutputFormat};
+    use crate::structs::toml::{OutputConfig, OutputDestination, OutputFormat, ResourcePolicy};
+    use crate::utils::governor::Governor;
     use httpmock::{
         Method::{POST, PUT},
         MockServer,
     };
     use serde_json::{Map, json};
     use std::{
-        fs::{read_dir, read_to_string},
+        fs::{read_dir, read_to_string, remove_dir_all},
         path::PathBuf,
     };
 
@@ -490,6 +570,123 @@ mod tests {
         assert_eq!(report["artifact_runs"][0]["status"], "completed");
     }
 
+    #[test]
+    fn test_output_manager_cancelled() {
+        let config = OutputConfig {
+            name: String::from("manager_cancel_collection"),
+            endpoint_id: String::from("test"),
+            directory: PathBuf::from("./tmp"),
+            destination: OutputDestination::Local,
+            format: OutputFormat::Jsonl,
+            ..Default::default()
+        };
+
+        let mut manage = OutputManager::new(config).unwrap();
+        let mut first = Map::new();
+        first.insert("path".to_string(), "/tmp/one.txt".into());
+        let mut records = VecRecordStream::new(vec![Record::Json(JsonRecord::new(first))]);
+        manage
+            .write_artifact("files", &String::from("first"), &mut records)
+            .unwrap();
+
+        manage.cancel.cancel();
+        let mut second = Map::new();
+        second.insert("path".to_string(), "/tmp/two.txt".into());
+        let mut records = VecRecordStream::new(vec![Record::Json(JsonRecord::new(second))]);
+        manage
+            .write_artifact("files", &String::from("second"), &mut records)
+            .unwrap();
+        assert_eq!(manage.artifact_runs[0].status, "completed");
+        assert_eq!(manage.artifact_runs[1].status, "timeout");
+        assert_eq!(manage.artifact_runs[1].record_count, 0);
+
+        manage.finalize().unwrap();
+
+        let output_dir = PathBuf::from("./tmp").join("manager_cancel_collection");
+        let report_file = read_dir(&output_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.to_string_lossy().contains("report_"))
+            .unwrap();
+        let report: serde_json::Value =
+            serde_json::from_str(&read_to_string(report_file).unwrap()).unwrap();
+        assert_eq!(report["status"], "timeout");
+        assert_eq!(report["total_output_files"], 1);
+    }
+
+    #[test]
+    fn test_output_manager_cancelled_after_artifacts() {
+        let config = OutputConfig {
+            name: String::from("manager_cancel_complete_collection"),
+            endpoint_id: String::from("test"),
+            directory: PathBuf::from("./tmp"),
+            destination: OutputDestination::Local,
+            format: OutputFormat::Jsonl,
+            ..Default::default()
+        };
+
+        let mut manage = OutputManager::new(config).unwrap();
+        let mut first = Map::new();
+        first.insert("path".to_string(), "/tmp/one.txt".into());
+        let mut records = VecRecordStream::new(vec![Record::Json(JsonRecord::new(first))]);
+        manage
+            .write_artifact("files", &String::from("first"), &mut records)
+            .unwrap();
+
+        // Deadline passed after every artifact completed
+        manage.cancel.cancel();
+        manage.finalize().unwrap();
+
+        let output_dir = PathBuf::from("./tmp").join("manager_cancel_complete_collection");
+        let report_file = read_dir(&output_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.to_string_lossy().contains("report_"))
+            .unwrap();
+        let report: serde_json::Value =
+            serde_json::from_str(&read_to_string(report_file).unwrap()).unwrap();
+        assert_eq!(report["status"], "completed");
+    }
+
+    #[test]
+    fn test_output_manager_resources() {
+        let config = OutputConfig {
+            name: String::from("manager_resources_collection"),
+            endpoint_id: String::from("test"),
+            directory: PathBuf::from("./tmp"),
+            destination: OutputDestination::Local,
+            format: OutputFormat::Jsonl,
+            resources: Some(ResourcePolicy {
+                max_memory_bytes: Some(1),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let output_dir = PathBuf::from("./tmp").join("manager_resources_collection");
+        let _ = remove_dir_all(&output_dir);
+        let mut manage = OutputManager::new(config).unwrap();
+        manage.cancel = manage
+            .cancel
+            .with_governor(Governor::new(manage.config.resources.clone().unwrap()));
+        manage.cancel.throttle(4096);
+        manage.finalize().unwrap();
+
+        let report_file = read_dir(&output_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.to_string_lossy().contains("report_"))
+            .unwrap();
+        let report: serde_json::Value =
+            serde_json::from_str(&read_to_string(report_file).unwrap()).unwrap();
+        // Memory over the limit pauses the collection instead of stopping it
+        assert_eq!(report["status"], "completed");
+        assert_eq!(report["resources"]["bytes_read"], 4096);
+        assert_eq!(report["resources"]["memory_limit_reached"], true);
+        assert!(report["resources"]["memory_throttled_ms"].as_u64().unwrap() > 0);
+        assert_eq!(report["resources"]["policy"]["max_memory_bytes"], 1);
+    }
+
     #[test]
     #[cfg(feature = "gcp")]
     fn test_output_manager_gcp() {
@@ -686,11 +883,11 @@ mod tests {
         manage.write_failed_artifact("madeup", &String::from("nothing matters"));
         manage.finalize().unwrap();
 
-        // 3 uploads:
+        // 3 uploads, each is one block and one block list:
         // Dummy artifact
         // Failed artifact
         // log file
-        mock_me.assert_calls(3);
+        mock_me.assert_calls(6);
     }
 
     #[test]
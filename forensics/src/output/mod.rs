@@ -1,4 +1,4 @@
-mod context;
+pub(crate) mod context;
 mod encoder;
 pub(crate) mod error;
 #[cfg(feature = "boa")]
@@ -6,5 +6,5 @@ mod filter;
 pub(crate) mod manager;
 pub(crate) mod marker;
 pub(crate) mod record;
-mod report;
-mod sink;
+pub(crate) mod report;
+pub(crate) mod sink;
@@ -1,4 +1,7 @@
-use crate::output::error::{OutputError, OutputResult};
+use crate::{
+    output::error::{OutputError, OutputResult},
+    utils::cancel::CancelToken,
+};
 use serde::Serialize;
 use serde_json::{Map, Number, Value};
 use std::vec::IntoIter;
@@ -251,6 +254,43 @@ impl RecordStream for SingleRecordStream {
     }
 }
 
+/// A `RecordStream` that stops returning records once the collection is cancelled
+///
+/// Records already returned are still encoded, so partial output is flushed to the `Sink`
+pub(crate) struct CancelRecordStream<'a> {
+    /// Records to output
+    records: &'a mut dyn RecordStream,
+    /// Collection cancellation token
+    cancel: &'a CancelToken,
+    /// Set if the stream ended early due to cancellation
+    pub(crate) cancelled: bool,
+}
+
+impl<'a> CancelRecordStream<'a> {
+    /// Wrap a stream so it is checked for cancellation between records
+    pub(crate) fn new(records: &'a mut dyn RecordStream, cancel: &'a CancelToken) -> Self {
+        Self {
+            records,
+            cancel,
+            cancelled: false,
+        }
+    }
+}
+
+impl RecordStream for CancelRecordStream<'_> {
+    fn next_record(&mut self) -> OutputResult<Option<Record>> {
+        if self.cancel.is_cancelled() {
+            self.cancelled = true;
+            return Ok(None);
+        }
+        self.records.next_record()
+    }
+
+    fn stream_kind(&self) -> RecordStreamKind {
+        self.records.stream_kind()
+    }
+}
+
 /// Serializes an artifact entry into a JSON backed `Record`.
 ///
 /// The serialized value must be a JSON object so it can be represented as a
@@ -284,12 +324,15 @@ pub(crate) fn serialize_records_to_stream<T: Serialize>(
 
 #[cfg(test)]
 mod tests {
-    use crate::output::{
-        error::OutputError,
-        record::{
-            JsonRecord, Record, RecordStream, VecRecordStream, serialize_records_to_stream,
-            serialize_to_record,
+    use crate::{
+        output::{
+            error::OutputError,
+            record::{
+                CancelRecordStream, JsonRecord, Record, RecordStream, VecRecordStream,
+                serialize_records_to_stream, serialize_to_record,
+            },
         },
+        utils::cancel::CancelToken,
     };
     use serde_json::{Map, Value, json};
 
@@ -347,6 +390,19 @@ mod tests {
         assert!(record.is_none());
     }
 
+    #[test]
+    fn test_cancel_record_stream() {
+        let test = json!([{"test": "value"}, {"test2": "value2"}]);
+        let mut records = serialize_records_to_stream(test.as_array().unwrap().clone()).unwrap();
+        let cancel = CancelToken::new();
+        let mut stream = CancelRecordStream::new(&mut records, &cancel);
+
+        assert!(stream.next_record().unwrap().is_some());
+        cancel.cancel();
+        assert!(stream.next_record().unwrap().is_none());
+        assert!(stream.cancelled);
+    }
+
     #[test]
     fn test_serialize_to_record_bad_value() {
         let test = "test";
@@ -1,13 +1,17 @@
 use crate::{
     artifacts::os::systeminfo::info::get_info,
-    filesystem::files::hash_file_data,
+    filesystem::{disks::ewf::EwfDisk, files::hash_file_data},
     output::{context::CollectionContext, error::OutputResult},
-    structs::toml::{OutputConfig, OutputDestination, OutputFormat},
-    utils::time::{time_now, unixepoch_to_iso},
+    structs::toml::{OutputConfig, OutputDestination, OutputFormat, ResourcePolicy},
+    utils::{
+        cancel::CancelToken,
+        time::{time_now, unixepoch_to_iso},
+    },
 };
 use common::{files::Hashes, system::SystemInfo};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use tracing::{error, warn};
 
 /// Report metadata for a single artifact run
 ///
@@ -30,7 +34,7 @@ pub(crate) struct ArtifactRunReport {
     pub(crate) record_count: usize,
     /// Output files created from this artifact run
     pub(crate) output_files: Vec<String>,
-    /// Artifact run status: `completed` or `failed`
+    /// Artifact run status: `completed`, `failed`, or `timeout`
     pub(crate) status: String,
 }
 
@@ -85,6 +89,8 @@ pub(crate) struct CollectionReport {
     pub(crate) start_time: String,
     /// When the Artemis execution completed
     pub(crate) end_time: String,
+    /// Collection status: `completed` or `timeout` if the collection was cancelled
+    pub(crate) status: String,
     /// Total number of files created from the Artemis collection
     pub(crate) total_output_files: usize,
     /// Artifacts collected from the Artemis collection
@@ -100,6 +106,70 @@ pub(crate) struct CollectionReport {
     pub(crate) system: SystemInfo,
     /// Run reports for each artifact collected
     pub(crate) artifact_runs: Vec<ArtifactRunReport>,
+    /// Integrity checks for evidence containers listed in `verify_evidence`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) evidence: Vec<EvidenceReport>,
+    /// Resource limits applied to the collection and when they were enforced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) resources: Option<ResourceReport>,
+}
+
+/// Resource usage for a collection that ran with a `ResourcePolicy`
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub(crate) struct ResourceReport {
+    /// Resource limits applied to the collection
+    pub(crate) policy: ResourcePolicy,
+    /// Whether the collection thread ran with low CPU priority
+    pub(crate) low_cpu_priority: bool,
+    /// Whether the collection thread ran with low I/O priority
+    pub(crate) low_io_priority: bool,
+    /// Whether the thread priority was restored after the collection.
+    /// Unprivileged Linux threads cannot raise their CPU priority again
+    pub(crate) priority_restored: bool,
+    /// Total bytes read through the throttled read path
+    pub(crate) bytes_read: u64,
+    /// Milliseconds spent waiting to stay under the read limit
+    pub(crate) read_throttled_ms: u64,
+    /// Milliseconds spent waiting to stay under the CPU limit
+    pub(crate) cpu_throttled_ms: u64,
+    /// Highest resident memory in bytes observed during the collection
+    pub(crate) peak_memory: u64,
+    /// Whether memory went over the limit and the collection was paused
+    pub(crate) memory_limit_reached: bool,
+    /// Milliseconds spent paused waiting for memory to drop under the limit
+    pub(crate) memory_throttled_ms: u64,
+}
+
+/// Integrity check for an evidence container
+///
+/// `EvidenceReport` compares the hashes stored in the container at
+/// acquisition time against hashes of the media calculated during the collection
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub(crate) struct EvidenceReport {
+    /// Path to the first segment of the evidence container
+    pub(crate) path: String,
+    /// Case number recorded at acquisition
+    pub(crate) case_number: String,
+    /// Evidence number recorded at acquisition
+    pub(crate) evidence_number: String,
+    /// Examiner recorded at acquisition
+    pub(crate) examiner: String,
+    /// Number of segment files in the evidence container
+    pub(crate) segment_count: usize,
+    /// Size of the acquired media in bytes
+    pub(crate) media_size: u64,
+    /// MD5 stored in the evidence container
+    pub(crate) stored_md5: String,
+    /// SHA1 stored in the evidence container
+    pub(crate) stored_sha1: String,
+    /// MD5 of the media calculated during the collection
+    pub(crate) computed_md5: String,
+    /// SHA1 of the media calculated during the collection
+    pub(crate) computed_sha1: String,
+    /// Whether the stored hashes match the calculated hashes
+    pub(crate) verified: bool,
+    /// Error if the evidence container could not be read
+    pub(crate) error: Option<String>,
 }
 
 impl CollectionReport {
@@ -116,6 +186,7 @@ impl CollectionReport {
             endpoint_id: context.endpoint_id.clone(),
             start_time: unixepoch_to_iso(context.start_time_epoch as i64),
             end_time: unixepoch_to_iso(time_now() as i64),
+            status: String::from("completed"),
             total_output_files,
             artifacts,
             log_file: context.log_file.display().to_string(),
@@ -123,10 +194,67 @@ impl CollectionReport {
             destination: config.destination,
             system: get_info(),
             artifact_runs,
+            evidence: Vec::new(),
+            resources: None,
         }
     }
 }
 
+/// Verify the stored hashes for each EWF evidence container. Paths may include the `ewf:` scheme
+///
+/// Hashing every chunk can take a long time for large images. Verification stops if the collection is cancelled
+pub(crate) fn verify_evidence(paths: &[String], cancel: &CancelToken) -> Vec<EvidenceReport> {
+    let mut reports = Vec::new();
+    for entry in paths {
+        let source = entry
+            .split_once('!')
+            .map_or(entry.as_str(), |(source, _)| source);
+        let path = source.strip_prefix("ewf:").unwrap_or(source);
+
+        let mut report = EvidenceReport {
+            path: path.to_string(),
+            ..Default::default()
+        };
+        let mut disk = match EwfDisk::open(path) {
+            Ok(result) => result,
+            Err(err) => {
+                error!("Could not open evidence container {path}: {err}");
+                report.error = Some(err.to_string());
+                reports.push(report);
+                continue;
+            }
+        };
+
+        report.case_number.clone_from(&disk.info.case_number);
+        report
+            .evidence_number
+            .clone_from(&disk.info.evidence_number);
+        report.examiner.clone_from(&disk.info.examiner);
+        report.segment_count = disk.info.segment_paths.len();
+        report.media_size = disk.size();
+        report.stored_md5 = disk.info.md5.clone().unwrap_or_default();
+        report.stored_sha1 = disk.info.sha1.clone().unwrap_or_default();
+
+        match disk.verify(cancel) {
+            Ok(result) => {
+                if !result.verified {
+                    warn!("Evidence container {path} failed hash verification");
+                }
+                report.computed_md5 = result.computed_md5;
+                report.computed_sha1 = result.computed_sha1;
+                report.verified = result.verified;
+            }
+            Err(err) => {
+                error!("Could not verify evidence container {path}: {err}");
+                report.error = Some(err.to_string());
+            }
+        }
+        reports.push(report);
+    }
+
+    reports
+}
+
 /// MD5 hash serialized artifact options
 pub(crate) fn hash_artifact_options<T: Serialize>(options: &T) -> OutputResult<String> {
     let bytes = serde_json::to_vec(options)?;
@@ -143,9 +271,10 @@ pub(crate) fn hash_artifact_options<T: Serialize>(options: &T) -> OutputResult<S
 mod tests {
     use crate::output::{
         context::CollectionContext,
-        report::{ArtifactRunReport, CollectionReport, hash_artifact_options},
+        report::{ArtifactRunReport, CollectionReport, hash_artifact_options, verify_evidence},
     };
     use crate::structs::toml::{OutputConfig, OutputFormat};
+    use crate::utils::cancel::CancelToken;
     use serde_json::Value;
     use std::path::PathBuf;
 
@@ -155,6 +284,7 @@ mod tests {
         let context = CollectionContext::new(&config, PathBuf::from("./tmp/file.log"));
         let result = CollectionReport::new(&config, &context, Vec::new(), Vec::new());
         assert_eq!(result.output_format, OutputFormat::Jsonl);
+        assert_eq!(result.status, "completed");
         assert!(!result.system.artemis_version.is_empty())
     }
 
@@ -170,4 +300,43 @@ mod tests {
         let result = hash_artifact_options(&Value::String("test".into())).unwrap();
         assert_eq!(result, "303b5c8988601647873b4ffd247d83cb");
     }
+
+    #[test]
+    fn test_verify_evidence() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/ewf/gpt_ext4.E01");
+
+        let result = verify_evidence(
+            &[
+                format!("ewf:{}", test_location.display()),
+                String::from("./tmp/missing.E01"),
+            ],
+            &CancelToken::new(),
+        );
+        assert_eq!(result.len(), 2);
+        assert!(result[0].verified);
+        assert_eq!(result[0].case_number, "CASE-0042");
+        assert_eq!(result[0].segment_count, 2);
+        assert_eq!(result[0].stored_md5, result[0].computed_md5);
+        assert_eq!(result[0].stored_sha1, result[0].computed_sha1);
+        assert!(!result[1].verified);
+        assert!(result[1].error.is_some());
+    }
+
+    #[test]
+    fn test_verify_evidence_cancelled() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/disks/ewf/gpt_ext4.E01");
+        let cancel = CancelToken::new();
+        cancel.cancel();
+
+        let result = verify_evidence(&[test_location.display().to_string()], &cancel);
+        assert_eq!(result.len(), 1);
+        assert!(!result[0].verified);
+        assert_eq!(result[0].case_number, "CASE-0042");
+        assert_eq!(
+            result[0].error.as_deref(),
+            Some("EWF verification was cancelled")
+        );
+    }
 }
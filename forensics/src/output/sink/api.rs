@@ -2,8 +2,9 @@ use crate::{
     output::{
         error::{OutputError, OutputResult},
         sink::{
-            output_handle::{OutputHandle, OutputLocation},
+            output_handle::OutputHandle,
             output_sink::{LogOutput, OutputSink},
+            spool::{Spool, SpoolEntry, SpoolUpload},
         },
     },
     structs::toml::OutputConfig,
@@ -17,10 +18,8 @@ use reqwest::{
 use std::{
     fs::{File, create_dir_all, read, remove_file},
     path::PathBuf,
-    thread::sleep,
-    time::Duration,
 };
-use tracing::{error, warn};
+use tracing::warn;
 
 /// A data Sink representing the API pipeline flow
 #[derive(Debug)]
@@ -31,10 +30,10 @@ pub(crate) struct ApiSink {
     log_file: PathBuf,
     /// Collection ID for the Artemis execution
     collection_id: u64,
-    /// Collection name
-    name: String,
     /// Unique ID for the endpoint
     endpoint_id: String,
+    /// Local spool that holds uploads until the server acknowledges them
+    spool: Spool,
 }
 
 impl ApiSink {
@@ -48,69 +47,61 @@ impl ApiSink {
         // Local directory to store log file. Artemis logs issues locally. Once artifact and report uploads are done
         // The log file is then uploaded. The log file is uploaded last
         let log_file = config.directory.join(&config.name);
+        let spool = Spool::new(config, &format!("api:{url}"))?;
         Ok(Self {
             url: url.clone(),
             collection_id: config.collection_id,
             endpoint_id: config.endpoint_id.clone(),
-            name: config.name.clone(),
             log_file,
+            spool,
         })
     }
 
-    /// Start the upload process to API server
+    /// Upload data to the API server. Makes a single attempt, the spool retries failed uploads
     fn upload_bytes(
         &self,
-        data: Vec<u8>,
+        data: &[u8],
         mime_type: &str,
         compress: bool,
         filename: &str,
+        collection_id: u64,
+        collection_name: &str,
     ) -> OutputResult<()> {
         let client = Client::new();
-        let max_attempts = 15;
-        let pause = 8;
-
-        for attempt in 0..max_attempts {
-            let mut builder = client
-                .post(&self.url)
-                .header("x-artemis-endpoint_id", &self.endpoint_id)
-                .header("x-artemis-collection_id", self.collection_id)
-                .header("x-artemis-collection_name", &self.name)
-                .header("accept", "application/json")
-                .header("Content-Type", mime_type);
-
-            let mut part = multipart::Part::bytes(data.clone());
-            part = part.file_name(filename.to_string());
-            if compress {
-                builder = builder.header("Content-Encoding", "gzip");
-            }
-            if filename.ends_with(".jsonl.gz") || filename.ends_with(".jsonl") {
-                // Should be safe to unwrap?
-                part = part.mime_str("application/jsonl").unwrap();
-            } else {
-                // Should be safe to unwrap?
-                part = part.mime_str("application/json").unwrap();
-            }
-
-            let form = multipart::Form::new().part("artemis-upload", part);
-            builder = builder.multipart(form);
-            let result = builder.send();
-
-            match result {
-                Ok(response) if response.status() == StatusCode::OK => return Ok(()),
-                Ok(response) => warn!("Non-OK response from server: {:?}", response.status()),
-                Err(err) => {
-                    error!("Failed to upload data to API. Error: {err:?}");
-                }
-            }
-            let jitter = fastrand::usize(..11);
-
-            let backoff = pause * attempt + jitter;
-            // Pause between each attempt
-            sleep(Duration::from_secs(backoff as u64));
+        let mut builder = client
+            .post(&self.url)
+            .header("x-artemis-endpoint_id", &self.endpoint_id)
+            .header("x-artemis-collection_id", collection_id)
+            .header("x-artemis-collection_name", collection_name)
+            .header("accept", "application/json")
+            .header("Content-Type", mime_type);
+
+        let mut part = multipart::Part::bytes(data.to_vec());
+        part = part.file_name(filename.to_string());
+        if compress {
+            builder = builder.header("Content-Encoding", "gzip");
+        }
+        if filename.ends_with(".jsonl.gz") || filename.ends_with(".jsonl") {
+            // Should be safe to unwrap?
+            part = part.mime_str("application/jsonl").unwrap();
+        } else {
+            // Should be safe to unwrap?
+            part = part.mime_str("application/json").unwrap();
+        }
+
+        let form = multipart::Form::new().part("artemis-upload", part);
+        builder = builder.multipart(form);
+
+        match builder.send() {
+            Ok(response) if response.status() == StatusCode::OK => Ok(()),
+            Ok(response) => Err(OutputError::Sink(format!(
+                "non-OK response from API server: {:?}",
+                response.status()
+            ))),
+            Err(err) => Err(OutputError::Sink(format!(
+                "failed to upload data to API: {err:?}"
+            ))),
         }
-        Err(OutputError::Sink(String::from(
-            "max attempts reached for API upload",
-        )))
     }
 
     /// Return the log file we are logging to
@@ -119,6 +110,25 @@ impl ApiSink {
     }
 }
 
+impl SpoolUpload for ApiSink {
+    fn upload_entry(
+        &self,
+        _spool: &Spool,
+        entry: &mut SpoolEntry,
+        data: &[u8],
+    ) -> OutputResult<()> {
+        // The API server does not support resumable uploads. The whole chunk is sent again
+        self.upload_bytes(
+            data,
+            &entry.mime_type,
+            entry.compressed,
+            &entry.object_name,
+            entry.collection_id,
+            &entry.collection_name,
+        )
+    }
+}
+
 impl OutputSink for ApiSink {
     fn write_artifact(
         &mut self,
@@ -136,12 +146,10 @@ impl OutputSink for ApiSink {
 
         // Only JSONL and compress API uploads are supported right now
         let filename = format!("{artifact_name}_{uuid}.jsonl.gz");
-        self.upload_bytes(data, mime_type, true, &filename)?;
+        let entry = self.spool.add(&filename, mime_type, true, &data)?;
+        let location = self.spool.deliver(entry, self, self.url.clone())?;
 
-        Ok(OutputHandle::artifact(
-            OutputLocation::Remote(self.url.clone()),
-            record_count,
-        ))
+        Ok(OutputHandle::artifact(location, record_count))
     }
 
     fn write_report(
@@ -151,10 +159,11 @@ impl OutputSink for ApiSink {
         let data = serde_json::to_vec(report)?;
         let filename = format!("report_{}.json", generate_uuid());
 
-        self.upload_bytes(data, "application/json", false, &filename)?;
-        Ok(OutputHandle::report(OutputLocation::Remote(
-            self.url.clone(),
-        )))
+        let entry = self
+            .spool
+            .add(&filename, "application/json", false, &data)?;
+        let location = self.spool.deliver(entry, self, self.url.clone())?;
+        Ok(OutputHandle::report(location))
     }
 
     fn create_log_file(
@@ -173,22 +182,41 @@ impl OutputSink for ApiSink {
             .log_file
             .file_name()
             .and_then(|name| name.to_str())
-            .ok_or_else(|| OutputError::Finalize(String::from("log file path has no filename")))?;
+            .ok_or_else(|| OutputError::Finalize(String::from("log file path has no filename")))?
+            .to_string();
+        // Retry any uploads left from earlier failures before uploading the log file
+        let remaining = self.flush_spool();
+        if remaining != 0 {
+            warn!("{remaining} uploads are still waiting in the spool");
+        }
+
         let data = read(&self.log_file).map_err(|err| OutputError::io_path(&self.log_file, err))?;
-        self.upload_bytes(data, "application/jsonl", false, filename)?;
+        let entry = self
+            .spool
+            .add(&filename, "application/jsonl", false, &data)?;
         let _ = remove_file(&self.log_file);
 
+        self.spool.deliver(entry, self, self.url.clone())?;
         Ok(())
     }
+
+    fn flush_spool(&mut self) -> usize {
+        self.spool.flush(self)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::output::{error::OutputError, sink::api::ApiSink};
+    use crate::output::{
+        error::OutputError,
+        sink::{
+            api::ApiSink, output_handle::OutputLocation, output_sink::OutputSink, spool::Spool,
+        },
+    };
     use crate::structs::toml::{OutputConfig, OutputDestination, OutputFormat};
     use httpmock::{Method::POST, MockServer};
     use serde_json::json;
-    use std::path::PathBuf;
+    use std::{path::PathBuf, time::Duration};
 
     fn api_config(port: u16) -> OutputConfig {
         OutputConfig {
@@ -199,6 +227,7 @@ mod tests {
             destination: OutputDestination::Api,
             format: OutputFormat::Jsonl,
             url: Some(format!("http://127.0.0.1:{port}")),
+            spool_directory: Some(PathBuf::from("./tmp/spool_api")),
             ..Default::default()
         }
     }
@@ -220,11 +249,90 @@ mod tests {
                 .json_body(json!({ "message": "ok" }));
         });
 
-        sink.upload_bytes(vec![0, 0, 0, 0, 0], "applicstion/jsonl", true, "test")
-            .unwrap();
+        sink.upload_bytes(
+            &[0, 0, 0, 0, 0],
+            "applicstion/jsonl",
+            true,
+            "test",
+            0,
+            "test",
+        )
+        .unwrap();
         mock_me.assert();
     }
 
+    #[test]
+    fn test_api_sink_resume_spool() {
+        let server = MockServer::start();
+        let port = server.port();
+        let mut config = api_config(port);
+
+        // Upload left in the spool by an earlier collection that lost network access
+        config.collection_id = 9;
+        config.name = String::from("earlier");
+        let spool = Spool::new(&config, &format!("api:http://127.0.0.1:{port}")).unwrap();
+        spool
+            .add("processes.jsonl.gz", "application/jsonl", true, &[1, 2, 3])
+            .unwrap();
+
+        config.collection_id = 10;
+        config.name = String::from("test");
+        let mut sink = ApiSink::new(&config).unwrap();
+        let mock_earlier = server.mock(|when, then| {
+            when.method(POST)
+                .header("x-artemis-collection_id", "9")
+                .header("x-artemis-collection_name", "earlier");
+            then.status(200);
+        });
+        let mock_log = server.mock(|when, then| {
+            when.method(POST).header("x-artemis-collection_id", "10");
+            then.status(200);
+        });
+
+        sink.create_log_file().unwrap();
+        sink.finalize().unwrap();
+        mock_earlier.assert();
+        mock_log.assert();
+        assert!(spool.pending().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_api_sink_spooled() {
+        let server = MockServer::start();
+        let port = server.port();
+        let mut config = api_config(port);
+        config.spool_directory = Some(PathBuf::from("./tmp/spool_api_pending"));
+        let _ = std::fs::remove_dir_all("./tmp/spool_api_pending");
+
+        let mut sink = ApiSink::new(&config).unwrap();
+        sink.spool = Spool::new(&config, &format!("api:http://127.0.0.1:{port}"))
+            .unwrap()
+            .with_retry(1, Duration::ZERO);
+        let mut mock_down = server.mock(|when, then| {
+            when.method(POST);
+            then.status(503);
+        });
+
+        // Server is down. The artifact is kept in the spool instead of failing
+        let handle = sink
+            .write_artifact("processes", "jsonl", "application/jsonl", &mut |writer| {
+                writer.write_all(b"{}")?;
+                Ok(1)
+            })
+            .unwrap();
+        mock_down.assert();
+        assert!(matches!(handle.location, OutputLocation::Spooled(_)));
+        assert!(handle.location_string().starts_with("spooled:"));
+        mock_down.delete();
+
+        let mock_up = server.mock(|when, then| {
+            when.method(POST);
+            then.status(200);
+        });
+        assert_eq!(sink.flush_spool(), 0);
+        mock_up.assert();
+    }
+
     #[test]
     fn test_api_sink_bad_url() {
         let server = MockServer::start();
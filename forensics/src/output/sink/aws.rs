@@ -3,8 +3,9 @@ use crate::{
         error::{OutputError, OutputResult},
         report::CollectionReport,
         sink::{
-            output_handle::{OutputHandle, OutputLocation},
+            output_handle::OutputHandle,
             output_sink::{LogOutput, OutputSink},
+            spool::{Spool, SpoolEntry, SpoolUpload},
         },
     },
     structs::toml::OutputConfig,
@@ -14,9 +15,7 @@ use flate2::{Compression, write::GzEncoder};
 use reqwest::{StatusCode, blocking::Client, header::ETAG};
 use rusty_s3::{
     Bucket, Credentials, S3Action, UrlStyle,
-    actions::{
-        CompleteMultipartUpload, CreateMultipartUpload, CreateMultipartUploadResponse, UploadPart,
-    },
+    actions::{CompleteMultipartUpload, CreateMultipartUpload, UploadPart},
 };
 use serde::Deserialize;
 use std::{
@@ -25,7 +24,7 @@ use std::{
     path::PathBuf,
     time::Duration,
 };
-use tracing::{error, warn};
+use tracing::warn;
 use url::Url;
 
 #[derive(Deserialize)]
@@ -36,11 +35,8 @@ struct AwsInfo {
     region: String,
 }
 
-struct AwsSetup {
-    bucket: Bucket,
-    creds: Credentials,
-    session: CreateMultipartUploadResponse,
-}
+/// Size of each multipart upload part. AWS requires every part except the last to be at least 5MB
+const PART_SIZE: usize = 8 * 1024 * 1024;
 
 /// A data Sink representing the AWS pipeline flow
 pub(crate) struct AwsSink {
@@ -57,6 +53,8 @@ pub(crate) struct AwsSink {
     /// Whether to compress the results with gzip
     compress: bool,
     url_style: UrlStyle,
+    /// Local spool that holds uploads until AWS acknowledges them
+    spool: Spool,
 }
 
 impl AwsSink {
@@ -82,6 +80,7 @@ impl AwsSink {
         let url = url
             .parse::<Url>()
             .map_err(|err| OutputError::Sink(format!("failed to parse AWS URL: {err:?}")))?;
+        let spool = Spool::new(config, &format!("aws:{url}"))?;
         Ok(Self {
             url,
             object_prefix,
@@ -90,96 +89,113 @@ impl AwsSink {
             compress: config.compress,
             log_file,
             url_style: UrlStyle::VirtualHost,
+            spool,
         })
     }
 
-    /// Start the upload process to AWS
-    fn upload_bytes(&self, object_name: &str, data: Vec<u8>, mime_type: &str) -> OutputResult<()> {
+    /// Upload a spooled chunk as a multipart upload. Acknowledged parts are saved to the spool so we can resume
+    fn upload_parts(&self, spool: &Spool, entry: &mut SpoolEntry, data: &[u8]) -> OutputResult<()> {
         let info = self.decode_creds()?;
-        let session = self.create_upload_session(info, object_name)?;
-        // Valid for one (1) hour
-        let duration = Duration::from_secs(3600);
-
-        let part_upload = UploadPart::new(
-            &session.bucket,
-            Some(&session.creds),
-            object_name,
-            1,
-            session.session.upload_id(),
-        );
+        let (bucket, creds) = self.bucket(info)?;
+        let upload_id = if let Some(result) = &entry.session {
+            result.clone()
+        } else {
+            let upload_id = AwsSink::create_upload_session(&bucket, &creds, &entry.object_name)?;
+            entry.session = Some(upload_id.clone());
+            entry.parts.clear();
+            spool.save(entry)?;
+            upload_id
+        };
 
-        let max_attempts = 15;
-        let client = Client::new();
-        let mut etag = Vec::new();
-        for attempt in 0..max_attempts {
-            let signed_url = part_upload.sign(duration);
-            let result = client
-                .put(signed_url)
-                .header("Content-Type", mime_type)
-                .header("Content-Length", data.len())
-                .body(data.clone())
-                .send();
-            match result {
-                Ok(response) if response.status() == StatusCode::OK => {
-                    if let Some(etag_header) = response.headers().get(ETAG) {
-                        etag.push(etag_header.to_str().unwrap_or_default().to_string());
-                        break;
-                    }
-                }
-                Ok(response) => {
-                    tracing::error!(
-                        "Non-OK response for upload on attempt {attempt}: {:?}",
-                        response.text()
-                    );
-                }
-                Err(err) => {
-                    tracing::error!("Failed to upload on attempt {attempt}: {err:?}");
-                }
-            }
-        }
-        if etag.is_empty() {
-            return Err(OutputError::Sink(String::from(
-                "Could not start AWS upload. Zero etags",
-            )));
+        // Empty uploads still need one part
+        let parts: Vec<&[u8]> = if data.is_empty() {
+            vec![data]
+        } else {
+            data.chunks(PART_SIZE).collect()
+        };
+        for (index, part) in parts.iter().enumerate().skip(entry.parts.len()) {
+            let part_upload = UploadPart::new(
+                &bucket,
+                Some(&creds),
+                &entry.object_name,
+                index as u16 + 1,
+                &upload_id,
+            );
+            let etag = AwsSink::upload_bytes(&part_upload, part, &entry.mime_type)?;
+            entry.parts.push(etag);
+            spool.save(entry)?;
         }
-        let etags: Vec<&str> = etag.iter().map(|tag| tag as &str).collect();
 
+        let etags: Vec<&str> = entry.parts.iter().map(|tag| tag as &str).collect();
         let action = CompleteMultipartUpload::new(
-            &session.bucket,
-            Some(&session.creds),
-            object_name,
-            session.session.upload_id(),
+            &bucket,
+            Some(&creds),
+            &entry.object_name,
+            &upload_id,
             etags.into_iter(),
         );
-        let url = action.sign(duration);
-
-        for attempt in 0..max_attempts {
-            let result = client.post(url.as_str()).body(action.clone().body()).send();
-            match result {
-                Ok(response) if response.status() == StatusCode::OK => {
-                    if response
-                        .text()
-                        .unwrap_or_default()
-                        .contains("Internal Error")
-                    {
-                        error!(
-                            "OK response on final upload but the response contained an error for attempt {attempt}"
-                        );
-                        continue;
-                    }
-                    return Ok(());
-                }
-                Ok(response) => {
-                    warn!("Non-OK response on attempt {attempt} for final upload : {response:?}");
+        // Valid for one (1) hour
+        let url = action.sign(Duration::from_secs(3600));
+
+        let result = Client::new()
+            .post(url.as_str())
+            .body(action.clone().body())
+            .send();
+        match result {
+            Ok(response) if response.status() == StatusCode::OK => {
+                if response
+                    .text()
+                    .unwrap_or_default()
+                    .contains("Internal Error")
+                {
+                    return Err(OutputError::Sink(String::from(
+                        "OK response on final AWS upload but the response contained an error",
+                    )));
                 }
-                Err(err) => {
-                    error!("Final upload failed on attempt {attempt}: {err:?}");
+                Ok(())
+            }
+            Ok(response) => Err(OutputError::Sink(format!(
+                "non-OK response for final AWS upload: {:?}",
+                response.status()
+            ))),
+            Err(err) => Err(OutputError::Sink(format!(
+                "final AWS upload failed: {err:?}"
+            ))),
+        }
+    }
+
+    /// Upload one part of a multipart upload to AWS. Returns the `ETag` of the part
+    ///
+    /// Makes a single attempt, the spool retries failed uploads
+    fn upload_bytes(
+        part_upload: &UploadPart<'_>,
+        data: &[u8],
+        mime_type: &str,
+    ) -> OutputResult<String> {
+        // Valid for one (1) hour
+        let duration = Duration::from_secs(3600);
+        let signed_url = part_upload.sign(duration);
+        let result = Client::new()
+            .put(signed_url)
+            .header("Content-Type", mime_type)
+            .header("Content-Length", data.len())
+            .body(data.to_vec())
+            .send();
+        match result {
+            Ok(response) if response.status() == StatusCode::OK => {
+                match response.headers().get(ETAG) {
+                    Some(etag_header) => Ok(etag_header.to_str().unwrap_or_default().to_string()),
+                    None => Err(OutputError::Sink(String::from(
+                        "AWS upload response did not contain an etag",
+                    ))),
                 }
             }
+            Ok(response) => Err(OutputError::Sink(format!(
+                "non-OK response for AWS upload: {:?}",
+                response.text()
+            ))),
+            Err(err) => Err(OutputError::Sink(format!("failed AWS upload: {err:?}"))),
         }
-        Err(OutputError::Sink(String::from(
-            "max attempts reached for AWS upload",
-        )))
     }
 
     /// Construct the full path for our upload
@@ -214,54 +230,67 @@ impl AwsSink {
         Ok(aws_key)
     }
 
-    /// Setup the AWS upload session using our credentials
-    fn create_upload_session(&self, info: AwsInfo, object_name: &str) -> OutputResult<AwsSetup> {
+    /// Create the AWS bucket and credentials used to sign requests
+    fn bucket(&self, info: AwsInfo) -> OutputResult<(Bucket, Credentials)> {
         let bucket = Bucket::new(self.url.clone(), self.url_style, info.bucket, info.region)
             .map_err(|err| {
                 OutputError::Sink(format!("failed to initialize AWS bucket: {err:?}"))
             })?;
 
-        let creds = Credentials::new(info.key, info.secret);
+        Ok((bucket, Credentials::new(info.key, info.secret)))
+    }
+
+    /// Setup the AWS upload session using our credentials. Returns the multipart upload ID
+    fn create_upload_session(
+        bucket: &Bucket,
+        creds: &Credentials,
+        object_name: &str,
+    ) -> OutputResult<String> {
         // Valid for one hour
         let duration = Duration::from_secs(3600);
-        let action = CreateMultipartUpload::new(&bucket, Some(&creds), object_name);
+        let action = CreateMultipartUpload::new(bucket, Some(creds), object_name);
         let url = action.sign(duration);
 
-        let client = Client::new();
-        let max_attempts = 15;
-
-        for attempt in 0..max_attempts {
-            let response = client
-                .post(url.as_str())
-                .send()
-                .map_err(|err| OutputError::Sink(format!("failed to start AWS upload: {err:?}")))?;
-
-            if response.status() != StatusCode::OK {
-                warn!(
-                    "Non-200 AWS response on upload start. Attempt {attempt}: {:?}",
-                    response.status()
-                );
-                continue;
-            }
-            let xml_session = response.text().map_err(|err| {
-                OutputError::Sink(format!("failed to get AWS XML start: {err:?}"))
-            })?;
+        let response = Client::new()
+            .post(url.as_str())
+            .send()
+            .map_err(|err| OutputError::Sink(format!("failed to start AWS upload: {err:?}")))?;
 
-            let session = CreateMultipartUpload::parse_response(&xml_session).map_err(|err| {
-                OutputError::Sink(format!("failed to parse AWS XML session: {err:?}"))
-            })?;
+        if response.status() != StatusCode::OK {
+            return Err(OutputError::Sink(format!(
+                "non-200 AWS response on upload start: {:?}",
+                response.status()
+            )));
+        }
+        let xml_session = response
+            .text()
+            .map_err(|err| OutputError::Sink(format!("failed to get AWS XML start: {err:?}")))?;
 
-            let setup = AwsSetup {
-                bucket,
-                creds,
-                session,
-            };
+        let session = CreateMultipartUpload::parse_response(&xml_session).map_err(|err| {
+            OutputError::Sink(format!("failed to parse AWS XML session: {err:?}"))
+        })?;
 
-            return Ok(setup);
+        Ok(session.upload_id().to_string())
+    }
+}
+
+impl SpoolUpload for AwsSink {
+    fn upload_entry(&self, spool: &Spool, entry: &mut SpoolEntry, data: &[u8]) -> OutputResult<()> {
+        if entry.session.is_some() {
+            match self.upload_parts(spool, entry, data) {
+                Ok(()) => return Ok(()),
+                Err(err) => {
+                    // Multipart uploads can be aborted or expire. Start over with a new upload
+                    warn!(
+                        "Could not resume AWS upload for {}: {err}. Starting new upload",
+                        entry.object_name
+                    );
+                    entry.session = None;
+                    entry.parts.clear();
+                }
+            }
         }
-        Err(OutputError::Sink(String::from(
-            "max attempts reached for AWS setup",
-        )))
+        self.upload_parts(spool, entry, data)
     }
 }
 
@@ -285,12 +314,12 @@ impl OutputSink for AwsSink {
             encode(&mut data)?
         };
 
-        self.upload_bytes(&upload_filename, data, mime_type)?;
+        let entry = self
+            .spool
+            .add(&upload_filename, mime_type, self.compress, &data)?;
+        let location = self.spool.deliver(entry, self, upload_filename)?;
 
-        Ok(OutputHandle::artifact(
-            OutputLocation::Remote(upload_filename),
-            record_count,
-        ))
+        Ok(OutputHandle::artifact(location, record_count))
     }
 
     fn write_report(&mut self, report: &CollectionReport) -> OutputResult<OutputHandle> {
@@ -298,8 +327,11 @@ impl OutputSink for AwsSink {
         let upload_report = self.object_path(&filename);
         let data = serde_json::to_vec(report)?;
 
-        self.upload_bytes(&upload_report, data, "application/json")?;
-        Ok(OutputHandle::report(OutputLocation::Remote(upload_report)))
+        let entry = self
+            .spool
+            .add(&upload_report, "application/json", false, &data)?;
+        let location = self.spool.deliver(entry, self, upload_report)?;
+        Ok(OutputHandle::report(location))
     }
 
     fn create_log_file(&mut self) -> OutputResult<LogOutput> {
@@ -319,12 +351,25 @@ impl OutputSink for AwsSink {
             .ok_or_else(|| OutputError::Finalize(String::from("log file path has no filename")))?;
         let object_log = self.object_path(filename);
 
+        // Retry any uploads left from earlier failures before uploading the log file
+        let remaining = self.flush_spool();
+        if remaining != 0 {
+            warn!("{remaining} uploads are still waiting in the spool");
+        }
+
         let data = read(&self.log_file).map_err(|err| OutputError::io_path(&self.log_file, err))?;
-        self.upload_bytes(&object_log, data, "application/jsonl")?;
+        let entry = self
+            .spool
+            .add(&object_log, "application/jsonl", false, &data)?;
         let _ = remove_file(&self.log_file);
 
+        self.spool.deliver(entry, self, object_log)?;
         Ok(())
     }
+
+    fn flush_spool(&mut self) -> usize {
+        self.spool.flush(self)
+    }
 }
 
 #[cfg(test)]
@@ -341,7 +386,7 @@ mod tests {
         context::CollectionContext,
         error::OutputError,
         report::CollectionReport,
-        sink::{aws::AwsSink, output_sink::OutputSink},
+        sink::{aws::AwsSink, output_handle::OutputLocation, output_sink::OutputSink},
     };
     use crate::structs::toml::{OutputConfig, OutputDestination, OutputFormat};
     use httpmock::{
@@ -349,6 +394,7 @@ mod tests {
         MockServer,
     };
     use rusty_s3::UrlStyle;
+    use std::time::Duration;
     use std::{io::Write, path::PathBuf};
 
     fn aws_config(port: u16) -> OutputConfig {
@@ -364,6 +410,7 @@ mod tests {
                 "ewogICAgImJ1Y2tldCI6ICJibGFoIiwKICAgICJzZWNyZXQiOiAicGtsNkFpQWFrL2JQcEdPenlGVW9DTC96SW1hSEoyTzVtR3ZzVWxSTCIsCiAgICAia2V5IjogIkFLSUEyT0dZQkFINlRPSUFVSk1SIiwKICAgICJyZWdpb24iOiAidXMtZWFzdC0yIgp9",
             )),
             url: Some(format!("http://127.0.0.1:{port}")),
+            spool_directory: Some(PathBuf::from("./tmp/spool_aws")),
             ..Default::default()
         }
     }
@@ -426,9 +473,11 @@ mod tests {
             when.method(POST);
             then.status(200).body(mock_response);
         });
-        let creds = sink.decode_creds().unwrap();
-        let session = sink.create_upload_session(creds, "test").unwrap();
-        assert!(session.bucket.base_url().as_str().starts_with("http://127"));
+        let info = sink.decode_creds().unwrap();
+        let (bucket, creds) = sink.bucket(info).unwrap();
+        let upload_id = AwsSink::create_upload_session(&bucket, &creds, "test").unwrap();
+        assert!(bucket.base_url().as_str().starts_with("http://127"));
+        assert_eq!(upload_id, "whatever");
         mock_me.assert_calls(1);
     }
 
@@ -441,12 +490,12 @@ mod tests {
             .unwrap()
             .with_url_style(UrlStyle::Path);
 
-        let err = sink
-            .upload_bytes("test", vec![0, 0, 0], "test")
-            .unwrap_err();
+        let info = sink.decode_creds().unwrap();
+        let (bucket, creds) = sink.bucket(info).unwrap();
+        let err = AwsSink::create_upload_session(&bucket, &creds, "test").unwrap_err();
 
         assert!(
-            matches!(err, OutputError::Sink(value) if value == "max attempts reached for AWS setup")
+            matches!(err, OutputError::Sink(value) if value.starts_with("non-200 AWS response on upload start"))
         );
     }
 
@@ -468,10 +517,97 @@ mod tests {
             when.method(PUT);
             then.status(200).header("ETAG", "whatever");
         });
-        sink.upload_bytes("test", vec![0, 0, 0, 0], "application/jsonl")
+        let entry = sink
+            .spool
+            .add("test", "application/jsonl", false, &[0, 0, 0, 0])
             .unwrap();
+        sink.spool.upload(entry, &sink).unwrap();
 
         mock_me.assert_calls(2);
         mock_me_put.assert_calls(1);
     }
+
+    #[test]
+    fn test_aws_spool_failed_upload() {
+        let server = MockServer::start();
+        let port = server.port();
+        let config = aws_config(port);
+        let mut sink = AwsSink::new(&config)
+            .unwrap()
+            .with_url_style(UrlStyle::Path);
+        sink.spool = sink.spool.with_retry(1, Duration::ZERO);
+
+        let mut mock_me = server.mock(|when, then| {
+            when.method(POST);
+            then.status(503);
+        });
+        let mut encode = |writer: &mut dyn Write| {
+            writer.write_all(br#"{"pid":1}"#)?;
+            Ok(1)
+        };
+        let handle = sink
+            .write_artifact("processes", "jsonl", "application/jsonl", &mut encode)
+            .unwrap();
+        assert!(matches!(handle.location, OutputLocation::Spooled(_)));
+        mock_me.assert_calls(1);
+
+        // Upload is kept until AWS acknowledges it
+        let pending = sink.spool.pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].attempts, 1);
+        assert!(pending[0].object_name.starts_with("./tmp/test/processes_"));
+
+        mock_me.delete();
+        let mock_response = "<?xml version=\"1.0\" encoding=\"UTF-8\"?><InitiateMultipartUploadResult xmlns=\"http://s3.amazonaws.com/doc/2006-03-01/\"><Bucket>mybucket</Bucket><Key>mykey</Key><UploadId>whatever</UploadId></InitiateMultipartUploadResult>";
+        let mock_me = server.mock(|when, then| {
+            when.method(POST);
+            then.status(200).body(mock_response);
+        });
+        let mock_me_put = server.mock(|when, then| {
+            when.method(PUT);
+            then.status(200).header("ETAG", "whatever");
+        });
+        assert_eq!(sink.spool.flush(&sink), 0);
+        assert!(sink.spool.pending().unwrap().is_empty());
+        mock_me.assert_calls(2);
+        mock_me_put.assert_calls(1);
+    }
+
+    #[test]
+    fn test_aws_resume_spool() {
+        let server = MockServer::start();
+        let port = server.port();
+        let config = aws_config(port);
+        let sink = AwsSink::new(&config)
+            .unwrap()
+            .with_url_style(UrlStyle::Path);
+
+        // All parts were uploaded before the network was lost. Only the upload needs to be completed
+        let mut entry = sink
+            .spool
+            .add(
+                "./tmp/test/report.json",
+                "application/json",
+                false,
+                &[1, 2, 3],
+            )
+            .unwrap();
+        entry.session = Some(String::from("resume-id"));
+        entry.parts.push(String::from("etag1"));
+        sink.spool.save(&entry).unwrap();
+
+        let mock_complete = server.mock(|when, then| {
+            when.method(POST).query_param("uploadId", "resume-id");
+            then.status(200);
+        });
+        let mock_me_put = server.mock(|when, then| {
+            when.method(PUT);
+            then.status(200).header("ETAG", "whatever");
+        });
+
+        assert_eq!(sink.spool.flush(&sink), 0);
+        mock_complete.assert_calls(1);
+        mock_me_put.assert_calls(0);
+        assert!(sink.spool.pending().unwrap().is_empty());
+    }
 }
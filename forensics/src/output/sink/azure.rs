@@ -2,12 +2,13 @@ use crate::{
     output::{
         error::{OutputError, OutputResult},
         sink::{
-            output_handle::{OutputHandle, OutputLocation},
+            output_handle::OutputHandle,
             output_sink::{LogOutput, OutputSink},
+            spool::{Spool, SpoolEntry, SpoolUpload},
         },
     },
     structs::toml::OutputConfig,
-    utils::uuid::generate_uuid,
+    utils::{encoding::base64_encode_standard, uuid::generate_uuid},
 };
 use flate2::{Compression, write::GzEncoder};
 use reqwest::{StatusCode, blocking::Client};
@@ -16,7 +17,10 @@ use std::{
     io::Write,
     path::PathBuf,
 };
-use tracing::error;
+use tracing::{error, warn};
+
+/// Size of each block we upload. Blocks are committed with a block list once all are uploaded
+const BLOCK_SIZE: usize = 4 * 1024 * 1024;
 
 /// A data Sink representing the Azure pipeline flow
 pub(crate) struct AzureSink {
@@ -30,6 +34,8 @@ pub(crate) struct AzureSink {
     collection_id: u64,
     /// Whether to compress the results with gzip
     compress: bool,
+    /// Local spool that holds uploads until Azure acknowledges them
+    spool: Spool,
 }
 
 impl AzureSink {
@@ -47,52 +53,93 @@ impl AzureSink {
         // Local directory to store log file. Artemis logs issues locally. Once artifact and report uploads are done
         // The log file is then uploaded. The log file is uploaded last
         let log_file = config.directory.join(&config.name);
+        // Do not include the SAS token in the spool manifests
+        let base = url.split_once('?').map_or(url.as_str(), |(base, _)| base);
+        let spool = Spool::new(config, &format!("azure:{base}"))?;
         Ok(Self {
             url: url.clone(),
             object_prefix,
             collection_id: config.collection_id,
             compress: config.compress,
             log_file,
+            spool,
         })
     }
 
-    /// Start the upload process to Azure
-    fn upload_bytes(&self, object_name: &str, data: Vec<u8>, mime_type: &str) -> OutputResult<()> {
-        let client = Client::new();
-        let max_attempts = 15;
-        let azure_url = self.compose_url(object_name)?;
-
-        for attempt in 0..max_attempts {
-            let mut builder = client
-                .put(&azure_url)
-                .header("Content-Type", mime_type)
-                .header("Content-Length", data.len())
-                .header("x-ms-version", "2019-12-12");
-
-            if !azure_url.contains("&comp=") {
-                builder = builder.header("x-ms-blob-type", "BlockBlob");
-            }
+    /// Upload a spooled chunk as blocks. Acknowledged blocks are saved to the spool so we can resume
+    fn upload_blocks(
+        &self,
+        spool: &Spool,
+        entry: &mut SpoolEntry,
+        data: &[u8],
+    ) -> OutputResult<()> {
+        let azure_url = self.compose_url(&entry.object_name)?;
+        // Empty uploads still need one block
+        let blocks: Vec<&[u8]> = if data.is_empty() {
+            vec![data]
+        } else {
+            data.chunks(BLOCK_SIZE).collect()
+        };
 
-            let result = builder.body(data.clone()).send();
+        for (index, block) in blocks.iter().enumerate().skip(entry.parts.len()) {
+            // Block IDs must be base64 and the same length for every block in the blob
+            let block_id = base64_encode_standard(format!("{index:08}").as_bytes());
+            let block_url = format!(
+                "{azure_url}&comp=block&blockid={}",
+                AzureSink::encode_block_id(&block_id)
+            );
+            AzureSink::upload_bytes(&block_url, block, &entry.mime_type)?;
+            entry.parts.push(block_id);
+            spool.save(entry)?;
+        }
 
-            match result {
-                Ok(response)
-                    if response.status() == StatusCode::OK
-                        || response.status() == StatusCode::CREATED =>
-                {
-                    return Ok(());
-                }
-                Ok(response) => error!(
-                    "Non-OK response from Azure blob storage on {attempt}: {:?}",
-                    response.status()
-                ),
-                Err(err) => error!("Failed to upload to Azure on {attempt}: {err:?}"),
-            }
+        let mut block_list = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?><BlockList>");
+        for block_id in &entry.parts {
+            block_list.push_str(&format!("<Latest>{block_id}</Latest>"));
         }
+        block_list.push_str("</BlockList>");
 
-        Err(OutputError::Sink(String::from(
-            "max attempts reached for Azure upload",
-        )))
+        AzureSink::upload_bytes(
+            &format!("{azure_url}&comp=blocklist"),
+            block_list.as_bytes(),
+            &entry.mime_type,
+        )
+    }
+
+    /// Upload data to Azure. Makes a single attempt, the spool retries failed uploads
+    fn upload_bytes(azure_url: &str, data: &[u8], mime_type: &str) -> OutputResult<()> {
+        let client = Client::new();
+        let mut builder = client
+            .put(azure_url)
+            .header("Content-Length", data.len())
+            .header("x-ms-version", "2019-12-12");
+
+        if azure_url.contains("&comp=blocklist") {
+            builder = builder
+                .header("Content-Type", "application/xml")
+                .header("x-ms-blob-content-type", mime_type);
+        } else {
+            builder = builder.header("Content-Type", mime_type);
+        }
+        if !azure_url.contains("&comp=") {
+            builder = builder.header("x-ms-blob-type", "BlockBlob");
+        }
+
+        match builder.body(data.to_vec()).send() {
+            Ok(response)
+                if response.status() == StatusCode::OK
+                    || response.status() == StatusCode::CREATED =>
+            {
+                Ok(())
+            }
+            Ok(response) => Err(OutputError::Sink(format!(
+                "non-OK response from Azure blob storage: {:?}",
+                response.status()
+            ))),
+            Err(err) => Err(OutputError::Sink(format!(
+                "failed to upload to Azure: {err:?}"
+            ))),
+        }
     }
 
     /// Compose the final URL to upload data to Azure
@@ -105,6 +152,14 @@ impl AzureSink {
         Ok(format!("{base}/{full_path}?{query}"))
     }
 
+    /// URL encode base64 block IDs
+    fn encode_block_id(block_id: &str) -> String {
+        block_id
+            .replace('+', "%2B")
+            .replace('/', "%2F")
+            .replace('=', "%3D")
+    }
+
     /// URL encode upload paths to "%2F"
     fn encode_path(path: &str) -> String {
         path.trim_matches('/').replace('/', "%2F")
@@ -141,6 +196,25 @@ impl AzureSink {
     }
 }
 
+impl SpoolUpload for AzureSink {
+    fn upload_entry(&self, spool: &Spool, entry: &mut SpoolEntry, data: &[u8]) -> OutputResult<()> {
+        if !entry.parts.is_empty() {
+            match self.upload_blocks(spool, entry, data) {
+                Ok(()) => return Ok(()),
+                Err(err) => {
+                    // Uncommitted blocks expire after a week. Start over with new blocks
+                    warn!(
+                        "Could not resume Azure upload for {}: {err}. Uploading all blocks",
+                        entry.object_name
+                    );
+                    entry.parts.clear();
+                }
+            }
+        }
+        self.upload_blocks(spool, entry, data)
+    }
+}
+
 impl OutputSink for AzureSink {
     fn write_artifact(
         &mut self,
@@ -161,12 +235,14 @@ impl OutputSink for AzureSink {
             encode(&mut data)?
         };
 
-        self.upload_bytes(&upload_filename, data, mime_type)?;
+        let entry = self
+            .spool
+            .add(&upload_filename, mime_type, self.compress, &data)?;
+        let location =
+            self.spool
+                .deliver(entry, self, AzureSink::remote_location(&upload_filename))?;
 
-        Ok(OutputHandle::artifact(
-            OutputLocation::Remote(AzureSink::remote_location(&upload_filename)),
-            record_count,
-        ))
+        Ok(OutputHandle::artifact(location, record_count))
     }
 
     fn write_report(
@@ -177,10 +253,13 @@ impl OutputSink for AzureSink {
         let upload_report = self.object_path(&filename);
         let data = serde_json::to_vec(report)?;
 
-        self.upload_bytes(&upload_report, data, "application/json")?;
-        Ok(OutputHandle::report(OutputLocation::Remote(
-            AzureSink::remote_location(&upload_report),
-        )))
+        let entry = self
+            .spool
+            .add(&upload_report, "application/json", false, &data)?;
+        let location =
+            self.spool
+                .deliver(entry, self, AzureSink::remote_location(&upload_report))?;
+        Ok(OutputHandle::report(location))
     }
 
     fn create_log_file(&mut self) -> OutputResult<super::output_sink::LogOutput> {
@@ -200,19 +279,34 @@ impl OutputSink for AzureSink {
             .ok_or_else(|| OutputError::Finalize(String::from("log file path has no filename")))?;
         let object_log = self.object_path(filename);
 
+        // Retry any uploads left from earlier failures before uploading the log file
+        let remaining = self.flush_spool();
+        if remaining != 0 {
+            warn!("{remaining} uploads are still waiting in the spool");
+        }
+
         let data = read(&self.log_file).map_err(|err| OutputError::io_path(&self.log_file, err))?;
-        self.upload_bytes(&object_log, data, "application/jsonl")?;
+        let entry = self
+            .spool
+            .add(&object_log, "application/jsonl", false, &data)?;
         let _ = remove_file(&self.log_file);
 
+        self.spool
+            .deliver(entry, self, AzureSink::remote_location(&object_log))?;
         Ok(())
     }
+
+    fn flush_spool(&mut self) -> usize {
+        self.spool.flush(self)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::output::sink::azure::AzureSink;
+    use crate::output::sink::{azure::AzureSink, output_sink::OutputSink};
     use crate::structs::toml::{OutputConfig, OutputDestination, OutputFormat};
     use httpmock::{Method::PUT, MockServer};
+    use std::io::Write;
     use std::path::PathBuf;
 
     fn azure_config(port: u16) -> OutputConfig {
@@ -226,6 +320,7 @@ mod tests {
             url: Some(format!(
                 "http://127.0.0.1:{port}/mycontainername?sp=rcw&st=2023-06-14T03:00:40Z&se=2023-06-14T11:00:40Z&skoid=asdfasdfas-asdfasdfsadf-asdfsfd-sadf"
             )),
+            spool_directory: Some(PathBuf::from("./tmp/spool_azure")),
             ..Default::default()
         }
     }
@@ -254,13 +349,70 @@ mod tests {
                 .header("Content-MD5", "sQqNsWTgdUEFt6mb5y4/5Q==");
         });
 
-        sink.upload_bytes(
-            &sink.construct_filename("test", "jsonl"),
-            vec![0, 0, 0, 0],
-            "application/csv",
-        )
-        .unwrap();
+        let entry = sink
+            .spool
+            .add(
+                &sink.construct_filename("test", "jsonl"),
+                "application/csv",
+                false,
+                &[0, 0, 0, 0],
+            )
+            .unwrap();
+        sink.spool.upload(entry, &sink).unwrap();
+
+        // One block and the block list
+        mock_me.assert_calls(2);
+    }
 
-        mock_me.assert();
+    #[test]
+    fn test_azure_resume_spool() {
+        let server = MockServer::start();
+        let port = server.port();
+        let config = azure_config(port);
+        let mut sink = AzureSink::new(&config).unwrap();
+
+        // First block was uploaded before the network was lost
+        let mut entry = sink
+            .spool
+            .add(
+                "test%2Fprocesses.jsonl",
+                "application/jsonl",
+                false,
+                &[1, 2, 3],
+            )
+            .unwrap();
+        entry.parts.push(String::from("MDAwMDAwMDA="));
+        sink.spool.save(&entry).unwrap();
+
+        let mock_block = server.mock(|when, then| {
+            when.method(PUT).query_param("comp", "block");
+            then.status(201);
+        });
+        let mock_block_list = server.mock(|when, then| {
+            when.method(PUT)
+                .path("/mycontainername/test%2Fprocesses.jsonl")
+                .query_param("comp", "blocklist")
+                .header("x-ms-blob-content-type", "application/jsonl")
+                .body("<?xml version=\"1.0\" encoding=\"utf-8\"?><BlockList><Latest>MDAwMDAwMDA=</Latest></BlockList>");
+            then.status(201);
+        });
+        let mock_log = server.mock(|when, then| {
+            when.method(PUT).query_param("comp", "blocklist");
+            then.status(201);
+        });
+
+        assert_eq!(sink.spool.flush(&sink), 0);
+        mock_block.assert_calls(0);
+        mock_block_list.assert_calls(1);
+        assert!(sink.spool.pending().unwrap().is_empty());
+
+        let mut encode = |writer: &mut dyn Write| {
+            writer.write_all(b"test")?;
+            Ok(1)
+        };
+        sink.write_artifact("processes", "jsonl", "application/jsonl", &mut encode)
+            .unwrap();
+        mock_block.assert_calls(1);
+        mock_log.assert_calls(1);
     }
 }
@@ -104,6 +104,21 @@ impl Sink {
         }
     }
 
+    /// Upload output left in the spool by earlier collections. Returns the number of uploads still pending
+    pub(crate) fn flush_spool(&mut self) -> usize {
+        match self {
+            Self::Local(sink) => sink.flush_spool(),
+            #[cfg(feature = "gcp")]
+            Self::Gcp(sink) => sink.flush_spool(),
+            #[cfg(feature = "aws")]
+            Self::Aws(sink) => sink.flush_spool(),
+            #[cfg(feature = "azure")]
+            Self::Azure(sink) => sink.flush_spool(),
+            #[cfg(feature = "api")]
+            Self::Api(sink) => sink.flush_spool(),
+        }
+    }
+
     /// Initialize the log file
     pub(crate) fn create_log_file(&mut self) -> OutputResult<LogOutput> {
         match self {
@@ -10,3 +10,5 @@ mod gcp;
 mod local;
 pub(crate) mod output_handle;
 mod output_sink;
+#[cfg(any(feature = "gcp", feature = "aws", feature = "azure", feature = "api"))]
+mod spool;
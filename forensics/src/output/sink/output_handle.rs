@@ -17,6 +17,9 @@ pub(crate) enum OutputLocation {
     Local(PathBuf),
     /// Output sent to remote system
     Remote(String),
+    /// Output saved to the local spool. It is uploaded to the remote system once it is reachable
+    #[cfg(any(feature = "gcp", feature = "aws", feature = "azure", feature = "api"))]
+    Spooled(PathBuf),
 }
 
 impl OutputHandle {
@@ -41,6 +44,8 @@ impl OutputHandle {
         match &self.location {
             OutputLocation::Local(path) => path.display().to_string(),
             OutputLocation::Remote(location) => location.clone(),
+            #[cfg(any(feature = "gcp", feature = "aws", feature = "azure", feature = "api"))]
+            OutputLocation::Spooled(path) => format!("spooled:{}", path.display()),
         }
     }
 }
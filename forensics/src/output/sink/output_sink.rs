@@ -32,4 +32,9 @@ pub(crate) trait OutputSink {
     fn finalize(&mut self) -> OutputResult<()> {
         Ok(())
     }
+
+    /// Upload any output left in the spool by earlier collections. Returns the number of uploads still pending
+    fn flush_spool(&mut self) -> usize {
+        0
+    }
 }
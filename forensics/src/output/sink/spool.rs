@@ -0,0 +1,458 @@
+use crate::{
+    output::{
+        error::{OutputError, OutputResult},
+        sink::output_handle::OutputLocation,
+    },
+    structs::toml::OutputConfig,
+    utils::{time::time_now, uuid::generate_uuid},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{File, create_dir_all, read, read_dir, remove_file, rename},
+    io::Write,
+    path::{Path, PathBuf},
+    thread::sleep,
+    time::Duration,
+};
+use tracing::{error, warn};
+
+/// Upload state for a spooled chunk. Saved next to the chunk so uploads can resume after a restart or network loss
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct SpoolEntry {
+    /// Unique ID for the spooled chunk
+    pub(crate) id: String,
+    /// Remote destination the chunk belongs to
+    pub(crate) target: String,
+    /// Remote object name or filename for the chunk
+    pub(crate) object_name: String,
+    /// Mime type of the chunk
+    pub(crate) mime_type: String,
+    /// Whether the chunk is gzip compressed
+    pub(crate) compressed: bool,
+    /// Collection ID that produced the chunk
+    pub(crate) collection_id: u64,
+    /// Collection name that produced the chunk
+    pub(crate) collection_name: String,
+    /// Size of the chunk in bytes
+    pub(crate) size: u64,
+    /// Time the chunk was spooled
+    pub(crate) created: u64,
+    /// Number of upload attempts
+    pub(crate) attempts: u32,
+    /// Resumable upload session. GCP session URI or AWS multipart upload ID
+    pub(crate) session: Option<String>,
+    /// Parts acknowledged by the destination. AWS `ETags` or Azure block IDs
+    pub(crate) parts: Vec<String>,
+}
+
+/// Remote sinks that can upload spooled chunks
+pub(crate) trait SpoolUpload {
+    /// Make a single upload attempt for a spooled chunk. The spool handles retries and backoff
+    ///
+    /// Progress should be saved to the spool so interrupted uploads can be resumed
+    fn upload_entry(&self, spool: &Spool, entry: &mut SpoolEntry, data: &[u8]) -> OutputResult<()>;
+}
+
+/// Durable local queue for remote uploads.
+///
+/// Encoded chunks are written to the spool directory with a JSON manifest before uploading.
+/// They are only removed after the destination acknowledges the upload
+#[derive(Debug)]
+pub(crate) struct Spool {
+    /// Local directory containing spooled chunks and manifests
+    directory: PathBuf,
+    /// Remote destination. Only chunks for this destination are uploaded
+    target: String,
+    /// Collection ID for the Artemis execution
+    collection_id: u64,
+    /// Collection name
+    collection_name: String,
+    /// Upload attempts before leaving a chunk in the spool
+    max_attempts: u32,
+    /// Initial pause between upload attempts. Doubled after each attempt
+    backoff: Duration,
+}
+
+impl Spool {
+    /// Open the spool directory for a remote destination
+    pub(crate) fn new(config: &OutputConfig, target: &str) -> OutputResult<Self> {
+        let directory = config
+            .spool_directory
+            .clone()
+            .unwrap_or_else(|| config.directory.join("spool"));
+        create_dir_all(&directory).map_err(|err| OutputError::io_path(&directory, err))?;
+
+        Ok(Self {
+            directory,
+            target: target.to_string(),
+            collection_id: config.collection_id,
+            collection_name: config.name.clone(),
+            max_attempts: 5,
+            backoff: Duration::from_secs(5),
+        })
+    }
+
+    /// Write a chunk and its manifest to the spool
+    pub(crate) fn add(
+        &self,
+        object_name: &str,
+        mime_type: &str,
+        compressed: bool,
+        data: &[u8],
+    ) -> OutputResult<SpoolEntry> {
+        let entry = SpoolEntry {
+            id: generate_uuid(),
+            target: self.target.clone(),
+            object_name: object_name.to_string(),
+            mime_type: mime_type.to_string(),
+            compressed,
+            collection_id: self.collection_id,
+            collection_name: self.collection_name.clone(),
+            size: data.len() as u64,
+            created: time_now(),
+            attempts: 0,
+            session: None,
+            parts: Vec::new(),
+        };
+
+        // Chunk is written first. A manifest always points to a complete chunk
+        write_durable(&self.chunk_path(&entry), data)?;
+        self.save(&entry)?;
+        Ok(entry)
+    }
+
+    /// Save upload progress to the entry manifest
+    pub(crate) fn save(&self, entry: &SpoolEntry) -> OutputResult<()> {
+        let data = serde_json::to_vec(entry)?;
+        write_durable(&self.manifest_path(entry), &data)
+    }
+
+    /// Get all chunks for our destination that are waiting to be uploaded. Oldest first
+    pub(crate) fn pending(&self) -> OutputResult<Vec<SpoolEntry>> {
+        let dir =
+            read_dir(&self.directory).map_err(|err| OutputError::io_path(&self.directory, err))?;
+
+        let mut entries = Vec::new();
+        for file in dir.flatten() {
+            let path = file.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let data = match read(&path) {
+                Ok(result) => result,
+                Err(err) => {
+                    error!("Could not read spool manifest {}: {err:?}", path.display());
+                    continue;
+                }
+            };
+            let entry: SpoolEntry = match serde_json::from_slice(&data) {
+                Ok(result) => result,
+                Err(err) => {
+                    error!("Could not parse spool manifest {}: {err:?}", path.display());
+                    continue;
+                }
+            };
+            if entry.target != self.target {
+                continue;
+            }
+            if !self.chunk_path(&entry).is_file() {
+                warn!(
+                    "Spooled chunk for {} is missing. Removing manifest",
+                    entry.object_name
+                );
+                let _ = remove_file(&path);
+                continue;
+            }
+            entries.push(entry);
+        }
+
+        entries.sort_by(|first, second| {
+            first
+                .created
+                .cmp(&second.created)
+                .then_with(|| first.id.cmp(&second.id))
+        });
+        Ok(entries)
+    }
+
+    /// Upload a spooled chunk with retry and backoff. The chunk is removed once the destination acknowledges it
+    pub(crate) fn upload(&self, entry: SpoolEntry, uploader: &dyn SpoolUpload) -> OutputResult<()> {
+        let object_name = entry.object_name.clone();
+        match self.try_upload(entry, uploader, self.max_attempts)? {
+            None => Ok(()),
+            Some(last_error) => Err(OutputError::Sink(format!(
+                "upload of {object_name} left in spool {}: {last_error}",
+                self.directory.display()
+            ))),
+        }
+    }
+
+    /// Upload a spooled chunk and return where the output is.
+    ///
+    /// Only one attempt is made so an offline destination does not block the collection.
+    /// If the destination does not acknowledge the upload the chunk stays in the spool and is reported as `Spooled`.
+    /// It is uploaded with retries by a later flush
+    pub(crate) fn deliver(
+        &self,
+        entry: SpoolEntry,
+        uploader: &dyn SpoolUpload,
+        remote: String,
+    ) -> OutputResult<OutputLocation> {
+        let chunk = self.chunk_path(&entry);
+        let object_name = entry.object_name.clone();
+        match self.try_upload(entry, uploader, 1)? {
+            None => Ok(OutputLocation::Remote(remote)),
+            Some(last_error) => {
+                warn!("Upload of {object_name} is pending in the spool: {last_error}");
+                Ok(OutputLocation::Spooled(chunk))
+            }
+        }
+    }
+
+    /// Upload a spooled chunk with retry and backoff. Returns the last upload error if the chunk was left in the spool
+    fn try_upload(
+        &self,
+        mut entry: SpoolEntry,
+        uploader: &dyn SpoolUpload,
+        max_attempts: u32,
+    ) -> OutputResult<Option<String>> {
+        let chunk = self.chunk_path(&entry);
+        let data = read(&chunk).map_err(|err| OutputError::io_path(&chunk, err))?;
+
+        let mut last_error = String::new();
+        for attempt in 0..max_attempts {
+            if attempt != 0 {
+                sleep(self.backoff(attempt));
+            }
+            entry.attempts += 1;
+            self.save(&entry)?;
+
+            match uploader.upload_entry(self, &mut entry, &data) {
+                Ok(()) => return self.acknowledge(&entry).map(|_| None),
+                Err(err) => {
+                    warn!(
+                        "Failed to upload spooled chunk {} on attempt {attempt}: {err}",
+                        entry.object_name
+                    );
+                    last_error = err.to_string();
+                }
+            }
+        }
+
+        // Keep any resumable progress for the next attempt
+        self.save(&entry)?;
+        Ok(Some(last_error))
+    }
+
+    /// Try to upload all pending chunks for our destination. Returns the number of chunks still in the spool
+    pub(crate) fn flush(&self, uploader: &dyn SpoolUpload) -> usize {
+        let entries = match self.pending() {
+            Ok(result) => result,
+            Err(err) => {
+                error!("Could not read spool directory: {err}");
+                return 0;
+            }
+        };
+
+        let mut remaining = 0;
+        for entry in entries {
+            if let Err(err) = self.upload(entry, uploader) {
+                error!("Spooled upload failed: {err}");
+                remaining += 1;
+            }
+        }
+        remaining
+    }
+
+    /// Remove an acknowledged chunk and its manifest from the spool
+    fn acknowledge(&self, entry: &SpoolEntry) -> OutputResult<()> {
+        let chunk = self.chunk_path(entry);
+        remove_file(&chunk).map_err(|err| OutputError::io_path(&chunk, err))?;
+        let manifest = self.manifest_path(entry);
+        remove_file(&manifest).map_err(|err| OutputError::io_path(&manifest, err))
+    }
+
+    /// Exponential backoff with jitter between upload attempts
+    fn backoff(&self, attempt: u32) -> Duration {
+        if self.backoff.is_zero() {
+            return self.backoff;
+        }
+        let jitter = Duration::from_millis(fastrand::u64(..1000));
+        self.backoff
+            .saturating_mul(2u32.saturating_pow(attempt - 1))
+            + jitter
+    }
+
+    fn chunk_path(&self, entry: &SpoolEntry) -> PathBuf {
+        self.directory.join(format!("{}.chunk", entry.id))
+    }
+
+    fn manifest_path(&self, entry: &SpoolEntry) -> PathBuf {
+        self.directory.join(format!("{}.json", entry.id))
+    }
+}
+
+#[cfg(test)]
+impl Spool {
+    pub(crate) fn with_retry(mut self, max_attempts: u32, backoff: Duration) -> Self {
+        self.max_attempts = max_attempts;
+        self.backoff = backoff;
+        self
+    }
+}
+
+/// Write data to a temporary file, sync it to disk, then move it into place
+fn write_durable(path: &Path, data: &[u8]) -> OutputResult<()> {
+    let temp = path.with_extension("tmp");
+    let mut file = File::create(&temp).map_err(|err| OutputError::io_path(&temp, err))?;
+    file.write_all(data)
+        .map_err(|err| OutputError::io_path(&temp, err))?;
+    file.sync_all()
+        .map_err(|err| OutputError::io_path(&temp, err))?;
+    rename(&temp, path).map_err(|err| OutputError::io_path(path, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Spool, SpoolEntry, SpoolUpload};
+    use crate::{
+        output::{
+            error::{OutputError, OutputResult},
+            sink::output_handle::OutputLocation,
+        },
+        structs::toml::OutputConfig,
+    };
+    use std::{cell::RefCell, path::PathBuf, time::Duration};
+
+    /// Fake destination that fails a set number of uploads
+    struct FakeUpload {
+        failures: RefCell<u32>,
+        uploaded: RefCell<Vec<Vec<u8>>>,
+    }
+
+    impl SpoolUpload for FakeUpload {
+        fn upload_entry(
+            &self,
+            spool: &Spool,
+            entry: &mut SpoolEntry,
+            data: &[u8],
+        ) -> OutputResult<()> {
+            entry.parts.push(String::from("part"));
+            spool.save(entry)?;
+            if *self.failures.borrow() != 0 {
+                *self.failures.borrow_mut() -= 1;
+                return Err(OutputError::Sink(String::from("offline")));
+            }
+            self.uploaded.borrow_mut().push(data.to_vec());
+            Ok(())
+        }
+    }
+
+    fn test_spool(name: &str) -> Spool {
+        let config = OutputConfig {
+            name: String::from("test"),
+            spool_directory: Some(PathBuf::from(format!("./tmp/spool_{name}"))),
+            ..Default::default()
+        };
+        let _ = std::fs::remove_dir_all(config.spool_directory.as_ref().unwrap());
+        Spool::new(&config, "test:target")
+            .unwrap()
+            .with_retry(2, Duration::ZERO)
+    }
+
+    #[test]
+    fn test_spool_upload() {
+        let spool = test_spool("upload");
+        let entry = spool
+            .add("test.jsonl.gz", "application/jsonl", true, b"data")
+            .unwrap();
+        assert_eq!(spool.pending().unwrap(), vec![entry.clone()]);
+
+        let upload = FakeUpload {
+            failures: RefCell::new(1),
+            uploaded: RefCell::new(Vec::new()),
+        };
+        spool.upload(entry, &upload).unwrap();
+        assert_eq!(upload.uploaded.borrow().as_slice(), &[b"data".to_vec()]);
+        assert!(spool.pending().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_spool_keeps_failed_upload() {
+        let spool = test_spool("failed");
+        let entry = spool
+            .add("test.json", "application/json", false, b"report")
+            .unwrap();
+        let upload = FakeUpload {
+            failures: RefCell::new(3),
+            uploaded: RefCell::new(Vec::new()),
+        };
+
+        let err = spool.upload(entry, &upload).unwrap_err();
+        assert!(matches!(err, OutputError::Sink(value) if value.contains("left in spool")));
+
+        let pending = spool.pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].attempts, 2);
+        assert_eq!(pending[0].parts.len(), 2);
+
+        // Network is back. Pending chunk is uploaded and removed
+        assert_eq!(spool.flush(&upload), 0);
+        assert_eq!(upload.uploaded.borrow().as_slice(), &[b"report".to_vec()]);
+        assert!(spool.pending().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_spool_deliver_pending() {
+        let spool = test_spool("deliver");
+        let entry = spool
+            .add("test.jsonl.gz", "application/jsonl", true, b"data")
+            .unwrap();
+        let upload = FakeUpload {
+            failures: RefCell::new(1),
+            uploaded: RefCell::new(Vec::new()),
+        };
+
+        // Destination is offline. Output is kept in the spool and reported as pending without waiting on retries
+        let spool = spool.with_retry(5, Duration::from_secs(60));
+        let location = spool
+            .deliver(entry.clone(), &upload, String::from("remote"))
+            .unwrap();
+        assert!(
+            matches!(location, OutputLocation::Spooled(path) if path.ends_with(format!("{}.chunk", entry.id)))
+        );
+        assert_eq!(spool.pending().unwrap().len(), 1);
+        assert_eq!(spool.pending().unwrap()[0].attempts, 1);
+
+        let entry = spool.pending().unwrap().remove(0);
+        let location = spool
+            .deliver(entry, &upload, String::from("remote"))
+            .unwrap();
+        assert!(matches!(location, OutputLocation::Remote(value) if value == "remote"));
+        assert!(spool.pending().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_spool_pending_other_target() {
+        let spool = test_spool("target");
+        spool
+            .add("test.json", "application/json", false, b"report")
+            .unwrap();
+
+        let config = OutputConfig {
+            spool_directory: Some(PathBuf::from("./tmp/spool_target")),
+            ..Default::default()
+        };
+        let other = Spool::new(&config, "other:target").unwrap();
+        assert!(other.pending().unwrap().is_empty());
+        assert_eq!(spool.pending().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_spool_backoff() {
+        let spool = test_spool("backoff").with_retry(3, Duration::from_secs(2));
+        assert!(spool.backoff(1) >= Duration::from_secs(2));
+        assert!(spool.backoff(3) >= Duration::from_secs(8));
+        assert!(spool.backoff(3) < Duration::from_secs(9));
+    }
+}
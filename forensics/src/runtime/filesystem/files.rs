@@ -5,6 +5,7 @@ use crate::{
         metadata::{get_metadata, get_timestamps, glob_paths},
     },
     runtime::helper::{boolean_arg, number_arg, string_arg},
+    utils::cancel::CancelToken,
 };
 use boa_engine::{
     Context, JsError, JsResult, JsString, JsValue, js_string, object::builtins::JsUint8Array,
@@ -113,7 +114,7 @@ pub(crate) fn js_hash_file(
         sha1: boolean_arg(args, 2)?,
         sha256: boolean_arg(args, 3)?,
     };
-    let (md5_value, sha1_value, sha256_value) = hash_file(&hashes, &path);
+    let (md5_value, sha1_value, sha256_value) = hash_file(&hashes, &path, &CancelToken::new());
     let info = HashInfo {
         md5: md5_value,
         sha1: sha1_value,
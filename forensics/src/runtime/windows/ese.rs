@@ -1,14 +1,14 @@
 use crate::{
     artifacts::os::windows::ese::{
         helper::{
-            dump_table_columns, get_all_pages, get_catalog_info, get_filtered_page_data,
-            get_page_data,
+            dump_table_columns, get_all_pages, get_catalog_info, get_deleted_page_data,
+            get_filtered_page_data, get_page_data,
         },
         tables::TableInfo,
     },
-    runtime::helper::{number_arg, string_arg, value_arg},
+    runtime::helper::{boolean_arg, number_arg, string_arg, value_arg},
 };
-use boa_engine::{Context, JsError, JsResult, JsValue, js_string};
+use boa_engine::{Context, JsArgs, JsError, JsResult, JsValue, js_string};
 use std::collections::HashMap;
 
 pub(crate) fn js_get_catalog(
@@ -76,14 +76,32 @@ pub(crate) fn js_page_data(
     };
 
     let name = string_arg(args, 3)?;
+    let recover_deleted = if args.get_or_undefined(4).is_undefined() {
+        false
+    } else {
+        boolean_arg(args, 4)?
+    };
 
-    let ese = match get_page_data(&path, &pages, &mut info, &name) {
+    let mut ese = match get_page_data(&path, &pages, &mut info, &name) {
         Ok(result) => result,
         Err(err) => {
             let issue = format!("Failed to get page data for {path}: {err:?}");
             return Err(JsError::from_opaque(js_string!(issue).into()));
         }
     };
+    // Deleted rows are returned separately from the live rows
+    if recover_deleted {
+        let deleted = match get_deleted_page_data(&path, &pages, &mut info, &name) {
+            Ok(result) => result,
+            Err(err) => {
+                let issue = format!("Failed to get deleted page data for {path}: {err:?}");
+                return Err(JsError::from_opaque(js_string!(issue).into()));
+            }
+        };
+        for (table, rows) in deleted {
+            ese.insert(format!("{table}_deleted"), rows);
+        }
+    }
     let results = serde_json::to_value(&ese).unwrap_or_default();
     let value = JsValue::from_json(&results, context)?;
 
@@ -1,6 +1,6 @@
 use crate::{
     artifacts::os::windows::eventlogs::parser::parse_eventlogs,
-    runtime::helper::{boolean_arg, number_arg, string_arg},
+    runtime::helper::{boolean_arg, number_arg, string_arg, value_arg},
 };
 use boa_engine::{Context, JsArgs, JsError, JsResult, JsValue, js_string};
 
@@ -20,6 +20,23 @@ pub(crate) fn js_eventlogs(
     } else {
         Some(string_arg(args, 4)?)
     };
+    let image_option = if args.get_or_undefined(5).is_undefined() {
+        None
+    } else {
+        Some(string_arg(args, 5)?)
+    };
+    let filter = if args.get_or_undefined(6).is_undefined() {
+        None
+    } else {
+        let filter_value = value_arg(args, 6, context)?;
+        match serde_json::from_value(filter_value) {
+            Ok(result) => Some(result),
+            Err(err) => {
+                let issue = format!("Failed to deserialize eventlog filter: {err:?}");
+                return Err(JsError::from_opaque(js_string!(issue).into()));
+            }
+        }
+    };
 
     let logs = match parse_eventlogs(
         &path,
@@ -27,6 +44,8 @@ pub(crate) fn js_eventlogs(
         limit as usize,
         include_templates,
         &temp_option,
+        &image_option,
+        &filter,
     ) {
         Ok(result) => result,
         Err(err) => {
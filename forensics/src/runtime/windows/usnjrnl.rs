@@ -31,6 +31,7 @@ pub(crate) fn js_usnjrnl(
         alt_drive: drive,
         alt_file: path,
         alt_mft: mft_path,
+        carve: None,
     };
     let jrnl = match grab_usnjrnl_path(&options) {
         Ok(result) => result,
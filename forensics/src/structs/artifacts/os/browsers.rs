@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BrowserOptions {
+    /**Alternative profile directory to parse instead of discovering user profiles */
+    pub alt_path: Option<String>,
+    /**Records to collect. Default is all. Supported: history, downloads, cookies, logins, topsites, cache, sessions */
+    pub records: Option<Vec<String>>,
+}
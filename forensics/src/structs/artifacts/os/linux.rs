@@ -15,6 +15,12 @@ pub struct LogonOptions {
     pub alt_file: Option<String>,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UserActivityOptions {
+    /**Alternative root directory to read `/etc/passwd` and home directories from. Ex: a mounted disk image */
+    pub alt_dir: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Ext4Options {
     pub start_path: String,
@@ -12,7 +12,37 @@ pub struct EventLogsOptions {
     pub include_templates: bool,
     pub dump_templates: bool,
     pub alt_template_file: Option<String>,
+    /**Mounted or acquired Windows image used to build the template strings. Ex: /mnt/windows or vss:C@3!/ */
+    pub alt_template_image: Option<String>,
     pub only_templates: bool,
+    /**Directory containing Sigma YAML rules to evaluate against each record */
+    pub sigma_dir: Option<String>,
+    /**Base64 encoded Sigma YAML rules to evaluate against each record */
+    pub sigma_rules: Option<Vec<String>>,
+    /**Only include records that match the filter */
+    pub filter: Option<EventLogsFilter>,
+    /**Carve records from `alt_file` (any file, ex: memory dump) or from the unallocated space of the system drive */
+    pub carve: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
+pub struct EventLogsFilter {
+    /**Event IDs or Event ID ranges to include. Ex: 4624 or 4624-4634 */
+    pub event_ids: Option<Vec<String>>,
+    /**Provider names to include. Case insensitive */
+    pub providers: Option<Vec<String>>,
+    /**Channels to include. Case insensitive. Ex: Security */
+    pub channels: Option<Vec<String>>,
+    /**Levels to include. Ex: 2 (Error) or 3 (Warning) */
+    pub levels: Option<Vec<u8>>,
+    /**Include records with a record ID greater than or equal to the start */
+    pub start_record: Option<u64>,
+    /**Include records with a record ID less than or equal to the end */
+    pub end_record: Option<u64>,
+    /**Include records after the start date. Format needs to be ISO 8601. Ex: YYYY-MM-ddTHH:mm:ss.000Z */
+    pub start_date: Option<String>,
+    /**Include records before the end date. Format needs to be ISO 8601. Ex: YYYY-MM-ddTHH:mm:ss.000Z */
+    pub end_date: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -41,6 +71,10 @@ pub struct RegistryOptions {
     pub system_hives: bool,
     pub path_regex: Option<String>,
     pub alt_file: Option<String>,
+    /**Recover deleted keys and values from unallocated cells */
+    pub recover_deleted: Option<bool>,
+    /**Include deleted values that cannot be associated with a key. Ignored if `path_regex` is set */
+    pub recover_orphans: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -76,6 +110,36 @@ pub struct UsnJrnlOptions {
     pub alt_drive: Option<char>,
     pub alt_file: Option<String>,
     pub alt_mft: Option<String>,
+    /**Carve `UsnJrnl` records from unallocated space and slack space. Only supported when parsing a drive */
+    pub carve: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LogFileOptions {
+    pub alt_drive: Option<char>,
+    pub alt_file: Option<String>,
+    pub alt_mft: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct HiberfilOptions {
+    pub alt_file: Option<String>,
+    /**Do not write the decompressed memory pages to a raw image in the output directory */
+    pub skip_raw_image: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PagefileOptions {
+    pub alt_file: Option<String>,
+    /**Extractors to run. Default is all. Supported: url, ip, path, command, evtx, lnk, pe */
+    pub extractors: Option<Vec<String>>,
+    /**Base64 encoded Yara rule (or URL to a rule) to scan each page with. Requires the yarax feature */
+    pub yara_rule: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ActivitiesCacheOptions {
+    pub alt_file: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -87,6 +151,8 @@ pub struct BitsOptions {
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SrumOptions {
     pub alt_file: Option<String>,
+    /**Recover deleted rows from the free space of the ESE pages */
+    pub recover_deleted: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -97,6 +163,8 @@ pub struct WindowsUserOptions {
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SearchOptions {
     pub alt_file: Option<String>,
+    /**Recover deleted rows from the free space of the ESE pages */
+    pub recover_deleted: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -138,4 +206,6 @@ pub struct WmiPersistOptions {
 pub struct MftOptions {
     pub alt_file: Option<String>,
     pub alt_drive: Option<char>,
+    /**Carve `MFT` FILE records from unallocated space and slack space. Only supported when parsing a drive */
+    pub carve: Option<bool>,
 }
@@ -1,9 +1,14 @@
-use super::artifacts::os::linux::{JournalOptions, LinuxSudoOptions, LogonOptions};
+use super::artifacts::os::linux::{
+    JournalOptions, LinuxSudoOptions, LogonOptions, UserActivityOptions,
+};
 use super::artifacts::os::macos::{
     EmondOptions, FseventsOptions, LaunchdOptions, LoginitemsOptions, MacosGroupsOptions,
     MacosSudoOptions, MacosUsersOptions, SpotlightOptions, UnifiedLogsOptions,
 };
-use super::artifacts::os::windows::{MftOptions, OutlookOptions};
+use super::artifacts::os::windows::{
+    ActivitiesCacheOptions, HiberfilOptions, LogFileOptions, MftOptions, OutlookOptions,
+    PagefileOptions,
+};
 use crate::output::marker::MarkerTracker;
 use crate::structs::artifacts::os::linux::Ext4Options;
 use crate::structs::artifacts::os::windows::{
@@ -14,7 +19,7 @@ use crate::structs::artifacts::os::windows::{
 };
 use crate::structs::artifacts::triage::TriageOptions;
 use crate::structs::artifacts::{
-    os::{files::FileOptions, processes::ProcessOptions},
+    os::{browsers::BrowserOptions, files::FileOptions, processes::ProcessOptions},
     runtime::script::JSScript,
 };
 use serde::{Deserialize, Serialize};
@@ -61,6 +66,51 @@ pub struct OutputConfig {
     pub api_key: Option<String>,
     /// Set logging setting. Default is `warn`. Options include: error, warn, info, debug
     pub logging: Option<String>,
+    /// EWF evidence containers to verify against their stored MD5/SHA1 hashes. Results are added to the collection report
+    pub verify_evidence: Option<Vec<String>>,
+    /// Local directory used to spool remote uploads until they are acknowledged. Default is `directory`/spool
+    pub spool_directory: Option<PathBuf>,
+    /// Limit the CPU, I/O, and memory used by the collection
+    pub resources: Option<ResourcePolicy>,
+}
+
+/// Resource limits for a collection
+///
+/// Reads through the accessor and file hashing are throttled to stay under the limits
+#[derive(Debug, Deserialize, Serialize, Default, PartialEq, Clone)]
+pub struct ResourcePolicy {
+    /// Max CPU usage as a percent of a single core
+    pub max_cpu_percent: Option<u8>,
+    /// Max bytes read from disk per second
+    pub max_read_bytes_per_second: Option<u64>,
+    /// Max resident memory in bytes. The collection is paused while memory is over the limit
+    pub max_memory_bytes: Option<u64>,
+    /// Run the collection with low CPU and I/O priority
+    #[serde(default)]
+    pub low_priority: bool,
+}
+
+impl ResourcePolicy {
+    /// Combine with another `ResourcePolicy`. The stricter of each limit is used
+    pub fn restrict(&self, other: &ResourcePolicy) -> ResourcePolicy {
+        ResourcePolicy {
+            max_cpu_percent: stricter(self.max_cpu_percent, other.max_cpu_percent),
+            max_read_bytes_per_second: stricter(
+                self.max_read_bytes_per_second,
+                other.max_read_bytes_per_second,
+            ),
+            max_memory_bytes: stricter(self.max_memory_bytes, other.max_memory_bytes),
+            low_priority: self.low_priority || other.low_priority,
+        }
+    }
+}
+
+/// Get the lowest limit that is set
+fn stricter<T: Ord>(first: Option<T>, second: Option<T>) -> Option<T> {
+    match (first, second) {
+        (Some(first), Some(second)) => Some(first.min(second)),
+        (first, second) => first.or(second),
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Default, PartialEq, Copy, Clone)]
@@ -106,6 +156,8 @@ pub struct Artifacts {
     pub filter: Option<bool>,
     pub processes: Option<ProcessOptions>,
     pub files: Option<FileOptions>,
+    pub chromium: Option<BrowserOptions>,
+    pub firefox: Option<BrowserOptions>,
     pub unifiedlogs: Option<UnifiedLogsOptions>,
     pub users_macos: Option<MacosUsersOptions>,
     pub groups_macos: Option<MacosGroupsOptions>,
@@ -118,6 +170,7 @@ pub struct Artifacts {
     pub journal: Option<JournalOptions>,
     pub sudologs_linux: Option<LinuxSudoOptions>,
     pub logons: Option<LogonOptions>,
+    pub useractivity: Option<UserActivityOptions>,
     pub rawfiles_ext4: Option<Ext4Options>,
     pub eventlogs: Option<EventLogsOptions>,
     pub prefetch: Option<PrefetchOptions>,
@@ -130,6 +183,10 @@ pub struct Artifacts {
     pub amcache: Option<AmcacheOptions>,
     pub shortcuts: Option<ShortcutOptions>,
     pub usnjrnl: Option<UsnJrnlOptions>,
+    pub logfile: Option<LogFileOptions>,
+    pub hiberfil: Option<HiberfilOptions>,
+    pub pagefile: Option<PagefileOptions>,
+    pub activitiescache: Option<ActivitiesCacheOptions>,
     pub bits: Option<BitsOptions>,
     pub srum: Option<SrumOptions>,
     pub users_windows: Option<WindowsUserOptions>,
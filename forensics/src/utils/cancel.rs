@@ -0,0 +1,104 @@
+use super::governor::Governor;
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+/// Cooperative cancellation for a running Artemis collection
+///
+/// Clones share the same state. Cancelling any clone cancels the collection.
+/// Artifacts check the token between records and stop writing once it is cancelled
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    /// Set when the collection has been cancelled or the deadline has passed
+    cancelled: Arc<AtomicBool>,
+    /// Optional hard deadline for the collection
+    deadline: Option<Instant>,
+    /// Resource limits for the collection
+    governor: Option<Governor>,
+}
+
+impl CancelToken {
+    /// Create a token that is only cancelled by calling `cancel`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a token that is automatically cancelled once `timeout` has elapsed
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            deadline: Instant::now().checked_add(timeout),
+            governor: None,
+        }
+    }
+
+    /// Get a token for the same collection that also enforces a `ResourcePolicy` with `governor`
+    pub(crate) fn with_governor(&self, governor: Governor) -> Self {
+        Self {
+            cancelled: self.cancelled.clone(),
+            deadline: self.deadline,
+            governor: Some(governor),
+        }
+    }
+
+    /// Get the `Governor` for the collection if it has a `ResourcePolicy`
+    pub(crate) fn governor(&self) -> Option<&Governor> {
+        self.governor.as_ref()
+    }
+
+    /// Account for `bytes` read by the collection and wait if it is over its resource limits
+    pub(crate) fn throttle(&self, bytes: usize) {
+        if let Some(governor) = &self.governor {
+            governor.throttle(bytes, self);
+        }
+    }
+
+    /// Cancel the collection
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Check if the collection has been cancelled or the deadline has passed
+    pub fn is_cancelled(&self) -> bool {
+        if self.cancelled.load(Ordering::SeqCst) {
+            return true;
+        }
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            self.cancel();
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CancelToken;
+    use std::time::Duration;
+
+    #[test]
+    fn test_cancel_token() {
+        let token = CancelToken::new();
+        let clone = token.clone();
+        assert!(!token.is_cancelled());
+
+        clone.cancel();
+        assert!(token.is_cancelled());
+    }
+
+    #[test]
+    fn test_cancel_token_with_timeout() {
+        let token = CancelToken::with_timeout(Duration::from_secs(0));
+        assert!(token.is_cancelled());
+
+        let token = CancelToken::with_timeout(Duration::from_secs(300));
+        assert!(!token.is_cancelled());
+    }
+}
@@ -0,0 +1,643 @@
+use crate::{
+    output::report::ResourceReport, structs::toml::ResourcePolicy, utils::cancel::CancelToken,
+};
+use std::{
+    cell::RefCell,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, get_current_pid};
+use tracing::warn;
+
+thread_local! {
+    /// Collection running on this thread. Each collection has its own `Governor`, even if collections run at the same time
+    static CURRENT: RefCell<Option<CancelToken>> = const { RefCell::new(None) };
+}
+
+/// How often CPU time and memory are sampled
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+/// Waits are split into slices so cancellation is noticed quickly
+const WAIT_SLICE: Duration = Duration::from_millis(100);
+/// Longest CPU wait for a single sample
+const MAX_CPU_WAIT: Duration = Duration::from_secs(5);
+/// Longest pause waiting for memory to drop under the limit for a single sample
+const MAX_MEMORY_WAIT: Duration = Duration::from_secs(2);
+
+/// Enforces a `ResourcePolicy` for a collection. The `Governor` is part of the collection `CancelToken`
+///
+/// Clones share the same state. Reads are limited with a token bucket, CPU usage is limited by
+/// pausing the collection, and memory over the limit pauses the collection until usage drops
+#[derive(Debug, Clone)]
+pub(crate) struct Governor {
+    policy: ResourcePolicy,
+    state: Arc<Mutex<GovernorState>>,
+}
+
+#[derive(Debug)]
+struct GovernorState {
+    /// Bytes that can be read before waiting. Negative when reads are ahead of the limit
+    read_budget: f64,
+    last_refill: Instant,
+    /// Time of the last CPU and memory sample. None until the first sample is taken
+    last_sample: Option<Instant>,
+    /// Process CPU time in milliseconds at the last sample
+    last_cpu_time: u64,
+    system: System,
+    pid: Option<Pid>,
+    report: ResourceReport,
+}
+
+/// Stops throttling reads on the collection thread and restores the thread priority
+pub(crate) struct GovernorGuard {
+    governor: Governor,
+    previous: Option<CancelToken>,
+    priority: Option<LoweredPriority>,
+}
+
+impl Governor {
+    /// Create a `Governor` for a collection
+    pub(crate) fn new(policy: ResourcePolicy) -> Self {
+        let pid = match get_current_pid() {
+            Ok(result) => Some(result),
+            Err(err) => {
+                warn!("Could not get current PID. CPU and memory limits are disabled: {err}");
+                None
+            }
+        };
+        let state = GovernorState {
+            read_budget: policy.max_read_bytes_per_second.unwrap_or_default() as f64,
+            last_refill: Instant::now(),
+            last_sample: None,
+            last_cpu_time: 0,
+            system: System::new(),
+            pid,
+            report: ResourceReport {
+                policy: policy.clone(),
+                ..Default::default()
+            },
+        };
+
+        Self {
+            policy,
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// Throttle reads made on this thread with the `Governor` from `cancel` until the guard is dropped
+    ///
+    /// Lowers the priority of this thread if the `ResourcePolicy` asks for it
+    pub(crate) fn install(cancel: &CancelToken) -> Option<GovernorGuard> {
+        let governor = cancel.governor()?.clone();
+        let priority = governor.policy.low_priority.then(lower_priority);
+        if let Some(lowered) = &priority {
+            let mut state = governor.lock();
+            state.report.low_cpu_priority = lowered.cpu;
+            state.report.low_io_priority = lowered.io;
+        }
+
+        let previous = CURRENT.with(|current| current.replace(Some(cancel.clone())));
+        Some(GovernorGuard {
+            governor,
+            previous,
+            priority,
+        })
+    }
+
+    /// Get resource usage for the collection report
+    pub(crate) fn report(&self) -> ResourceReport {
+        self.lock().report.clone()
+    }
+
+    /// Account for `bytes` read and wait if the collection is over its read, CPU or memory limit
+    ///
+    /// Waits stop early if `cancel` is cancelled
+    pub(crate) fn throttle(&self, bytes: usize, cancel: &CancelToken) {
+        let (read_wait, usage) = {
+            let mut state = self.lock();
+            state.report.bytes_read += bytes as u64;
+
+            let read_wait = match self.policy.max_read_bytes_per_second {
+                Some(limit) if limit != 0 => state.read_wait(limit, bytes),
+                _ => Duration::ZERO,
+            };
+            let usage = if self.limits_usage()
+                && state
+                    .last_sample
+                    .is_none_or(|sample| sample.elapsed() >= SAMPLE_INTERVAL)
+            {
+                self.check_usage(&mut state)
+            } else {
+                Usage::default()
+            };
+
+            (read_wait, usage)
+        };
+        if read_wait.is_zero() && usage.cpu_wait.is_zero() && !usage.over_memory {
+            return;
+        }
+
+        let read_waited = wait(read_wait, cancel);
+        let cpu_waited = wait(usage.cpu_wait, cancel);
+        let memory_waited = if usage.over_memory {
+            self.wait_for_memory(cancel)
+        } else {
+            Duration::ZERO
+        };
+        let mut state = self.lock();
+        state.report.read_throttled_ms += read_waited.as_millis() as u64;
+        state.report.cpu_throttled_ms += cpu_waited.as_millis() as u64;
+        state.report.memory_throttled_ms += memory_waited.as_millis() as u64;
+    }
+
+    /// Check if CPU or memory needs to be sampled
+    fn limits_usage(&self) -> bool {
+        self.policy.max_cpu_percent.is_some_and(|limit| limit != 0)
+            || self.policy.max_memory_bytes.is_some()
+    }
+
+    /// Sample CPU time and memory. Returns how long to wait to get back under the CPU limit and if memory is over the limit
+    fn check_usage(&self, state: &mut GovernorState) -> Usage {
+        let Some((cpu_time, memory)) = state.sample() else {
+            return Usage::default();
+        };
+        let now = Instant::now();
+        let previous = state.last_sample.replace(now);
+        let used = cpu_time.saturating_sub(state.last_cpu_time);
+        state.last_cpu_time = cpu_time;
+        state.report.peak_memory = state.report.peak_memory.max(memory);
+
+        let over_memory = self
+            .policy
+            .max_memory_bytes
+            .is_some_and(|limit| memory > limit);
+        if over_memory && !state.report.memory_limit_reached {
+            warn!(
+                "Collection memory usage {memory} exceeded limit of {} bytes. Pausing collection",
+                self.policy.max_memory_bytes.unwrap_or_default()
+            );
+            state.report.memory_limit_reached = true;
+        }
+
+        // First sample only sets the baseline
+        let (Some(previous), Some(limit)) = (previous, self.policy.max_cpu_percent) else {
+            return Usage {
+                cpu_wait: Duration::ZERO,
+                over_memory,
+            };
+        };
+        if limit == 0 {
+            return Usage {
+                cpu_wait: Duration::ZERO,
+                over_memory,
+            };
+        }
+        let elapsed = now.duration_since(previous).as_millis() as u64;
+        // Wait until CPU time is `limit` percent of the wall clock time since the last sample
+        let target = used * 100 / u64::from(limit);
+        Usage {
+            cpu_wait: Duration::from_millis(target.saturating_sub(elapsed)).min(MAX_CPU_WAIT),
+            over_memory,
+        }
+    }
+
+    /// Pause until memory drops under the limit. The collection continues after `MAX_MEMORY_WAIT` even if memory is still over the limit
+    fn wait_for_memory(&self, cancel: &CancelToken) -> Duration {
+        let start = Instant::now();
+        let limit = self.policy.max_memory_bytes.unwrap_or(u64::MAX);
+        while !cancel.is_cancelled() && start.elapsed() < MAX_MEMORY_WAIT {
+            wait(SAMPLE_INTERVAL, cancel);
+            let memory = match self.lock().sample() {
+                Some((_, memory)) => memory,
+                None => break,
+            };
+            if memory <= limit {
+                break;
+            }
+        }
+        start.elapsed()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, GovernorState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Result of sampling CPU and memory usage
+#[derive(Debug, Default)]
+struct Usage {
+    /// How long to wait to get back under the CPU limit
+    cpu_wait: Duration,
+    /// Memory usage is over the limit
+    over_memory: bool,
+}
+
+impl GovernorState {
+    /// Take `bytes` from the read budget. Returns how long to wait until the budget is no longer negative
+    fn read_wait(&mut self, limit: u64, bytes: usize) -> Duration {
+        let rate = limit as f64;
+        let now = Instant::now();
+        // Allow bursts of up to one second of reads
+        self.read_budget = (self.read_budget
+            + now.duration_since(self.last_refill).as_secs_f64() * rate)
+            .min(rate);
+        self.last_refill = now;
+        self.read_budget -= bytes as f64;
+
+        if self.read_budget >= 0.0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(-self.read_budget / rate)
+    }
+
+    /// Get the CPU time in milliseconds and resident memory in bytes for our process
+    fn sample(&mut self) -> Option<(u64, u64)> {
+        let pid = self.pid?;
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[pid]),
+            true,
+            ProcessRefreshKind::nothing().with_cpu().with_memory(),
+        );
+        let process = self.system.process(pid)?;
+        Some((process.accumulated_cpu_time(), process.memory()))
+    }
+}
+
+impl Drop for GovernorGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+
+        if let Some(lowered) = self.priority.take() {
+            let restored = restore_priority(&lowered);
+            self.governor.lock().report.priority_restored = restored;
+        }
+    }
+}
+
+/// Wait for `duration`. Stops early if the collection is cancelled. Returns how long we waited
+fn wait(duration: Duration, cancel: &CancelToken) -> Duration {
+    let start = Instant::now();
+    let end = start + duration;
+    while !cancel.is_cancelled() {
+        let remaining = end.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        sleep(remaining.min(WAIT_SLICE));
+    }
+    start.elapsed()
+}
+
+/// Throttle the collection running on this thread after reading `bytes`
+///
+/// Does nothing if the collection has no `ResourcePolicy`. Code with access to the collection `CancelToken` should use `CancelToken::throttle`
+pub(crate) fn throttle(bytes: usize) {
+    let cancel = CURRENT.with(|current| current.borrow().clone());
+    if let Some(cancel) = cancel {
+        cancel.throttle(bytes);
+    }
+}
+
+/// Priority changes made for the collection thread
+#[derive(Debug, Default)]
+struct LoweredPriority {
+    /// CPU priority was lowered
+    cpu: bool,
+    /// I/O priority was lowered
+    io: bool,
+    /// Nice value before the collection
+    #[cfg(target_os = "linux")]
+    nice: i32,
+    /// I/O priority before the collection
+    #[cfg(target_os = "linux")]
+    ioprio: libc::c_long,
+    /// QoS class before the collection
+    #[cfg(target_os = "macos")]
+    qos: Option<(libc::qos_class_t, i32)>,
+}
+
+#[cfg(target_os = "linux")]
+/// `IOPRIO_WHO_PROCESS`. With an ID of 0 this is the calling thread
+const IOPRIO_WHO_PROCESS: i32 = 1;
+
+#[cfg(target_os = "linux")]
+/// Lower the CPU and I/O priority of the thread running the collection. Linux applies both per thread
+fn lower_priority() -> LoweredPriority {
+    let mut lowered = LoweredPriority::default();
+
+    // getpriority can return -1 as a valid value. Clear errno to check for errors
+    #[allow(unsafe_code)]
+    let nice = unsafe {
+        *libc::__errno_location() = 0;
+        libc::getpriority(libc::PRIO_PROCESS, 0)
+    };
+    if std::io::Error::last_os_error().raw_os_error() == Some(0) {
+        lowered.nice = nice;
+        #[allow(unsafe_code)]
+        let status = unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, 19) };
+        lowered.cpu = status == 0;
+    }
+    if !lowered.cpu {
+        warn!(
+            "Could not lower CPU priority: {}",
+            std::io::Error::last_os_error()
+        );
+    }
+
+    #[allow(unsafe_code)]
+    let ioprio = unsafe { libc::syscall(libc::SYS_ioprio_get, IOPRIO_WHO_PROCESS, 0) };
+    if ioprio >= 0 {
+        lowered.ioprio = ioprio;
+        // Lowest level of the best-effort class
+        let lowest = (2 << 13) | 7;
+        #[allow(unsafe_code)]
+        let status = unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, lowest) };
+        lowered.io = status == 0;
+    }
+    if !lowered.io {
+        warn!(
+            "Could not lower I/O priority: {}",
+            std::io::Error::last_os_error()
+        );
+    }
+    lowered
+}
+
+#[cfg(target_os = "linux")]
+/// Restore the thread priority. Unprivileged threads cannot raise their CPU priority again and keep the lower priority
+fn restore_priority(lowered: &LoweredPriority) -> bool {
+    let mut restored = true;
+    if lowered.cpu {
+        #[allow(unsafe_code)]
+        let status = unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, lowered.nice) };
+        if status != 0 {
+            warn!(
+                "Could not restore CPU priority. The collection thread keeps the lower priority: {}",
+                std::io::Error::last_os_error()
+            );
+            restored = false;
+        }
+    }
+    if lowered.io {
+        #[allow(unsafe_code)]
+        let status =
+            unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, lowered.ioprio) };
+        if status != 0 {
+            warn!(
+                "Could not restore I/O priority. The collection thread keeps the lower priority: {}",
+                std::io::Error::last_os_error()
+            );
+            restored = false;
+        }
+    }
+    restored
+}
+
+#[cfg(target_os = "macos")]
+/// Run the collection thread with the background QoS class. Lowers both CPU and I/O priority for the thread only
+fn lower_priority() -> LoweredPriority {
+    let mut class = libc::qos_class_t::QOS_CLASS_UNSPECIFIED;
+    let mut relative = 0;
+    #[allow(unsafe_code)]
+    let status =
+        unsafe { libc::pthread_get_qos_class_np(libc::pthread_self(), &mut class, &mut relative) };
+    let previous = (status == 0).then_some((class, relative));
+
+    #[allow(unsafe_code)]
+    let status =
+        unsafe { libc::pthread_set_qos_class_self_np(libc::qos_class_t::QOS_CLASS_BACKGROUND, 0) };
+    if status != 0 {
+        warn!(
+            "Could not set background QoS class: {}",
+            std::io::Error::from_raw_os_error(status)
+        );
+        return LoweredPriority::default();
+    }
+    LoweredPriority {
+        cpu: true,
+        io: true,
+        qos: previous,
+    }
+}
+
+#[cfg(target_os = "macos")]
+/// Restore the QoS class the thread had before the collection
+fn restore_priority(lowered: &LoweredPriority) -> bool {
+    let (class, relative) = lowered
+        .qos
+        .unwrap_or((libc::qos_class_t::QOS_CLASS_DEFAULT, 0));
+    #[allow(unsafe_code)]
+    let status = unsafe { libc::pthread_set_qos_class_self_np(class, relative) };
+    if status != 0 {
+        warn!(
+            "Could not restore QoS class: {}",
+            std::io::Error::from_raw_os_error(status)
+        );
+        return false;
+    }
+    true
+}
+
+#[cfg(target_os = "windows")]
+/// Run the collection thread in background mode. Lowers both CPU and I/O priority
+fn lower_priority() -> LoweredPriority {
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentThread, SetThreadPriority, THREAD_MODE_BACKGROUND_BEGIN,
+    };
+
+    #[allow(unsafe_code)]
+    let status = unsafe { SetThreadPriority(GetCurrentThread(), THREAD_MODE_BACKGROUND_BEGIN) };
+    if status == 0 {
+        warn!(
+            "Could not enable background mode: {}",
+            std::io::Error::last_os_error()
+        );
+        return LoweredPriority::default();
+    }
+    LoweredPriority {
+        cpu: true,
+        io: true,
+    }
+}
+
+#[cfg(target_os = "windows")]
+/// Leave background mode once the collection completes
+fn restore_priority(_lowered: &LoweredPriority) -> bool {
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentThread, SetThreadPriority, THREAD_MODE_BACKGROUND_END,
+    };
+
+    #[allow(unsafe_code)]
+    let status = unsafe { SetThreadPriority(GetCurrentThread(), THREAD_MODE_BACKGROUND_END) };
+    if status == 0 {
+        warn!(
+            "Could not leave background mode: {}",
+            std::io::Error::last_os_error()
+        );
+        return false;
+    }
+    true
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+/// Other platforms only support lowering priority for the whole process. The priority is left unchanged
+fn lower_priority() -> LoweredPriority {
+    warn!("Low priority collections are not supported on this platform");
+    LoweredPriority::default()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn restore_priority(_lowered: &LoweredPriority) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Governor, throttle};
+    use crate::{structs::toml::ResourcePolicy, utils::cancel::CancelToken};
+    use std::thread::spawn;
+
+    #[test]
+    fn test_governor_read_limit() {
+        let policy = ResourcePolicy {
+            max_read_bytes_per_second: Some(1000),
+            ..Default::default()
+        };
+        let governor = Governor::new(policy);
+        let cancel = CancelToken::new();
+
+        // One second of reads is allowed right away
+        governor.throttle(1000, &cancel);
+        assert_eq!(governor.report().read_throttled_ms, 0);
+
+        governor.throttle(500, &cancel);
+        let report = governor.report();
+        assert!(report.read_throttled_ms >= 400);
+        assert_eq!(report.bytes_read, 1500);
+    }
+
+    #[test]
+    fn test_governor_read_limit_cancelled() {
+        let policy = ResourcePolicy {
+            max_read_bytes_per_second: Some(1),
+            ..Default::default()
+        };
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let governor = Governor::new(policy);
+
+        // Cancelled collections do not wait
+        governor.throttle(1_000_000, &cancel);
+        assert_eq!(governor.report().read_throttled_ms, 0);
+    }
+
+    #[test]
+    fn test_governor_memory_limit() {
+        let policy = ResourcePolicy {
+            max_memory_bytes: Some(1),
+            ..Default::default()
+        };
+        let cancel = CancelToken::new();
+        let governor = Governor::new(policy);
+
+        governor.throttle(0, &cancel);
+        let report = governor.report();
+        assert!(report.memory_limit_reached);
+        assert!(report.peak_memory > 1);
+        // The collection is paused instead of cancelled
+        assert!(report.memory_throttled_ms > 0);
+        assert!(!cancel.is_cancelled());
+    }
+
+    #[test]
+    fn test_governor_install() {
+        let cancel = CancelToken::new().with_governor(Governor::new(ResourcePolicy::default()));
+        let governor = cancel.governor().unwrap().clone();
+        {
+            let _guard = Governor::install(&cancel).unwrap();
+            throttle(10);
+        }
+        assert_eq!(governor.report().bytes_read, 10);
+
+        throttle(10);
+        assert_eq!(governor.report().bytes_read, 10);
+        assert!(Governor::install(&CancelToken::new()).is_none());
+    }
+
+    #[test]
+    fn test_governor_per_collection() {
+        let first = CancelToken::new().with_governor(Governor::new(ResourcePolicy::default()));
+        let second = CancelToken::new().with_governor(Governor::new(ResourcePolicy::default()));
+
+        // Collections running at the same time keep their own governor
+        let handles: Vec<_> = [(first.clone(), 10), (second.clone(), 20)]
+            .into_iter()
+            .map(|(cancel, bytes)| {
+                spawn(move || {
+                    let _guard = Governor::install(&cancel);
+                    throttle(bytes);
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(first.governor().unwrap().report().bytes_read, 10);
+        assert_eq!(second.governor().unwrap().report().bytes_read, 20);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_governor_low_priority() {
+        let policy = ResourcePolicy {
+            low_priority: true,
+            ..Default::default()
+        };
+        let cancel = CancelToken::new().with_governor(Governor::new(policy));
+        let governor = cancel.governor().unwrap().clone();
+
+        // Run on another thread. Unprivileged threads cannot raise their priority again
+        spawn(move || {
+            let guard = Governor::install(&cancel);
+            drop(guard);
+        })
+        .join()
+        .unwrap();
+
+        let report = governor.report();
+        assert!(report.low_cpu_priority);
+        assert!(report.low_io_priority);
+    }
+
+    #[test]
+    fn test_resource_policy() {
+        let policy: ResourcePolicy =
+            toml::from_str("max_cpu_percent = 50\nmax_read_bytes_per_second = 10485760").unwrap();
+        assert_eq!(policy.max_cpu_percent, Some(50));
+        assert_eq!(policy.max_read_bytes_per_second, Some(10485760));
+        assert_eq!(policy.max_memory_bytes, None);
+        assert!(!policy.low_priority);
+    }
+
+    #[test]
+    fn test_resource_policy_restrict() {
+        let collection = ResourcePolicy {
+            max_cpu_percent: Some(50),
+            max_read_bytes_per_second: Some(1000),
+            ..Default::default()
+        };
+        let host = ResourcePolicy {
+            max_cpu_percent: Some(25),
+            max_memory_bytes: Some(1024),
+            low_priority: true,
+            ..Default::default()
+        };
+
+        let policy = collection.restrict(&host);
+        assert_eq!(policy.max_cpu_percent, Some(25));
+        assert_eq!(policy.max_read_bytes_per_second, Some(1000));
+        assert_eq!(policy.max_memory_bytes, Some(1024));
+        assert!(policy.low_priority);
+    }
+}
@@ -1,11 +1,15 @@
 pub(crate) mod artemis_toml;
+pub(crate) mod cancel;
 pub(crate) mod compression;
 pub(crate) mod decryption;
 pub(crate) mod encoding;
 pub(crate) mod environment;
 mod error;
+pub(crate) mod governor;
 pub(crate) mod nom_helper;
 pub(crate) mod regex_options;
+pub(crate) mod sigma;
+pub(crate) mod sqlite;
 pub(crate) mod strings;
 pub(crate) mod time;
 pub(crate) mod uuid;
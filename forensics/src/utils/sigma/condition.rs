@@ -0,0 +1,330 @@
+use super::error::SigmaError;
+use tracing::error;
+
+/// Compiled Sigma condition expression
+#[derive(Debug, PartialEq)]
+pub(crate) enum Condition {
+    Search(String),
+    Not(Box<Condition>),
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+    /// Ex: `1 of selection*`, `all of them`
+    Of {
+        count: OfCount,
+        pattern: String,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum OfCount {
+    All,
+    Count(usize),
+}
+
+impl Condition {
+    /// Evaluate the condition. The `search` function returns the result of a named search
+    pub(crate) fn evaluate<F: Fn(&str) -> bool>(&self, names: &[String], search: &F) -> bool {
+        match self {
+            Condition::Search(name) => search(name),
+            Condition::Not(value) => !value.evaluate(names, search),
+            Condition::And(values) => values.iter().all(|value| value.evaluate(names, search)),
+            Condition::Or(values) => values.iter().any(|value| value.evaluate(names, search)),
+            Condition::Of { count, pattern } => {
+                let targets = matching_names(names, pattern);
+                match count {
+                    OfCount::All => !targets.is_empty() && targets.iter().all(|name| search(name)),
+                    OfCount::Count(min) => {
+                        targets.iter().filter(|name| search(name)).count() >= *min
+                    }
+                }
+            }
+        }
+    }
+
+    /// Verify all searches referenced by the condition exist
+    pub(crate) fn validate(&self, names: &[String]) -> Result<(), SigmaError> {
+        match self {
+            Condition::Search(name) => {
+                if !names.contains(name) {
+                    error!("[sigma] Condition references unknown search {name}");
+                    return Err(SigmaError::Condition);
+                }
+            }
+            Condition::Not(value) => value.validate(names)?,
+            Condition::And(values) | Condition::Or(values) => {
+                for value in values {
+                    value.validate(names)?;
+                }
+            }
+            Condition::Of { pattern, .. } => {
+                if matching_names(names, pattern).is_empty() {
+                    error!("[sigma] Condition pattern {pattern} does not match any search");
+                    return Err(SigmaError::Condition);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse a Sigma condition string. Ex: `selection and not 1 of filter_*`
+pub(crate) fn parse_condition(condition: &str) -> Result<Condition, SigmaError> {
+    // Aggregations were removed from the Sigma specification and are not supported
+    if condition.contains('|') {
+        error!("[sigma] Condition aggregations are not supported: {condition}");
+        return Err(SigmaError::Condition);
+    }
+
+    let tokens = tokenize(condition);
+    let mut parser = ConditionParser {
+        tokens: &tokens,
+        position: 0,
+    };
+    let result = parser.or_expression()?;
+    if parser.position != tokens.len() {
+        error!("[sigma] Unexpected tokens in condition: {condition}");
+        return Err(SigmaError::Condition);
+    }
+    Ok(result)
+}
+
+/// Split the condition into words and parentheses
+fn tokenize(condition: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    for value in condition.chars() {
+        if value.is_whitespace() || value == '(' || value == ')' {
+            if !word.is_empty() {
+                tokens.push(word.clone());
+                word.clear();
+            }
+            if !value.is_whitespace() {
+                tokens.push(value.to_string());
+            }
+            continue;
+        }
+        word.push(value);
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    tokens
+}
+
+struct ConditionParser<'a> {
+    tokens: &'a [String],
+    position: usize,
+}
+
+impl ConditionParser<'_> {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.position).map(String::as_str)
+    }
+
+    fn next(&mut self) -> Result<&str, SigmaError> {
+        let Some(token) = self.tokens.get(self.position) else {
+            error!("[sigma] Condition ended unexpectedly");
+            return Err(SigmaError::Condition);
+        };
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        self.peek()
+            .is_some_and(|token| token.eq_ignore_ascii_case(keyword))
+    }
+
+    /// Lowest precedence. `a or b`
+    fn or_expression(&mut self) -> Result<Condition, SigmaError> {
+        let mut values = vec![self.and_expression()?];
+        while self.is_keyword("or") {
+            self.position += 1;
+            values.push(self.and_expression()?);
+        }
+
+        if values.len() == 1 {
+            return Ok(values.remove(0));
+        }
+        Ok(Condition::Or(values))
+    }
+
+    /// `a and b`
+    fn and_expression(&mut self) -> Result<Condition, SigmaError> {
+        let mut values = vec![self.not_expression()?];
+        while self.is_keyword("and") {
+            self.position += 1;
+            values.push(self.not_expression()?);
+        }
+
+        if values.len() == 1 {
+            return Ok(values.remove(0));
+        }
+        Ok(Condition::And(values))
+    }
+
+    /// Highest precedence. `not a`
+    fn not_expression(&mut self) -> Result<Condition, SigmaError> {
+        if self.is_keyword("not") {
+            self.position += 1;
+            return Ok(Condition::Not(Box::new(self.not_expression()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Condition, SigmaError> {
+        let token = self.next()?.to_string();
+        if token == "(" {
+            let value = self.or_expression()?;
+            if self.next()? != ")" {
+                error!("[sigma] Condition missing closing parenthesis");
+                return Err(SigmaError::Condition);
+            }
+            return Ok(value);
+        }
+
+        let count = if token.eq_ignore_ascii_case("all") {
+            Some(OfCount::All)
+        } else {
+            token.parse::<usize>().ok().map(OfCount::Count)
+        };
+        if let Some(count) = count {
+            if !self.is_keyword("of") {
+                error!("[sigma] Expected 'of' after {token}");
+                return Err(SigmaError::Condition);
+            }
+            self.position += 1;
+            let pattern = self.next()?.to_string();
+            return Ok(Condition::Of { count, pattern });
+        }
+
+        if ["and", "or", "not", "of", ")"]
+            .iter()
+            .any(|keyword| token.eq_ignore_ascii_case(keyword))
+        {
+            error!("[sigma] Unexpected token {token} in condition");
+            return Err(SigmaError::Condition);
+        }
+        Ok(Condition::Search(token))
+    }
+}
+
+/// Get the search names that match a pattern. `them` matches all searches except those starting with an underscore
+fn matching_names<'a>(names: &'a [String], pattern: &str) -> Vec<&'a String> {
+    if pattern == "them" {
+        return names.iter().filter(|name| !name.starts_with('_')).collect();
+    }
+
+    names
+        .iter()
+        .filter(|name| wildcard_match(pattern, name))
+        .collect()
+}
+
+/// Simple `*` wildcard match used for search name patterns
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == value;
+    }
+
+    let mut remaining = value;
+    for (index, part) in parts.iter().enumerate() {
+        if index == 0 {
+            match remaining.strip_prefix(part) {
+                Some(result) => remaining = result,
+                None => return false,
+            }
+        } else if index == parts.len() - 1 {
+            return remaining.ends_with(part);
+        } else {
+            match remaining.find(part) {
+                Some(position) => remaining = &remaining[position + part.len()..],
+                None => return false,
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Condition, OfCount, parse_condition, wildcard_match};
+
+    #[test]
+    fn test_parse_condition() {
+        let result = parse_condition("selection and not (filter1 or filter2)").unwrap();
+        assert_eq!(
+            result,
+            Condition::And(vec![
+                Condition::Search(String::from("selection")),
+                Condition::Not(Box::new(Condition::Or(vec![
+                    Condition::Search(String::from("filter1")),
+                    Condition::Search(String::from("filter2"))
+                ])))
+            ])
+        );
+
+        let result = parse_condition("1 of selection* and all of them").unwrap();
+        assert_eq!(
+            result,
+            Condition::And(vec![
+                Condition::Of {
+                    count: OfCount::Count(1),
+                    pattern: String::from("selection*")
+                },
+                Condition::Of {
+                    count: OfCount::All,
+                    pattern: String::from("them")
+                }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_condition_bad() {
+        assert!(parse_condition("selection and").is_err());
+        assert!(parse_condition("(selection").is_err());
+        assert!(parse_condition("selection | count() > 5").is_err());
+        assert!(parse_condition("1 selection").is_err());
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let names = vec![
+            String::from("selection_a"),
+            String::from("selection_b"),
+            String::from("filter"),
+            String::from("_internal"),
+        ];
+        let search = |name: &str| name == "selection_b" || name == "_internal";
+
+        let condition = parse_condition("1 of selection_* and not filter").unwrap();
+        assert!(condition.evaluate(&names, &search));
+
+        let condition = parse_condition("all of selection_*").unwrap();
+        assert!(!condition.evaluate(&names, &search));
+
+        let condition = parse_condition("1 of them").unwrap();
+        assert!(condition.evaluate(&names, &search));
+
+        let condition = parse_condition("all of them").unwrap();
+        assert!(!condition.evaluate(&names, &search));
+
+        let condition = parse_condition("not selection_a or filter").unwrap();
+        assert!(condition.evaluate(&names, &search));
+        assert!(condition.validate(&names).is_ok());
+
+        let condition = parse_condition("selection_c").unwrap();
+        assert!(condition.validate(&names).is_err());
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("selection*", "selection_img"));
+        assert!(wildcard_match("*_img", "selection_img"));
+        assert!(wildcard_match("sel*_*g", "selection_img"));
+        assert!(!wildcard_match("filter*", "selection_img"));
+        assert!(wildcard_match("selection", "selection"));
+    }
+}
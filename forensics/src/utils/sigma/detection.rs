@@ -0,0 +1,610 @@
+use super::error::SigmaError;
+use crate::utils::{encoding::base64_encode_standard, regex_options::create_regex};
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use tracing::error;
+
+/// Named search in the Sigma `detection` section
+#[derive(Debug)]
+pub(crate) enum Search {
+    /// List of keywords. Any keyword can match any value in the record
+    Keywords(Vec<Matcher>),
+    /// List of field maps. Any map can match. All fields in a map must match
+    Selections(Vec<Vec<FieldMatch>>),
+}
+
+#[derive(Debug)]
+pub(crate) struct FieldMatch {
+    field: String,
+    /// Each Sigma value may expand into multiple matchers. Ex: `base64offset`
+    values: Vec<Vec<Matcher>>,
+    /// All values must match instead of any value
+    all: bool,
+}
+
+#[derive(Debug)]
+pub(crate) enum Matcher {
+    Text {
+        value: String,
+        position: Position,
+        cased: bool,
+    },
+    Regex(Regex),
+    Null,
+    Exists(bool),
+    Compare {
+        operator: Operator,
+        value: f64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Position {
+    Exact,
+    Contains,
+    StartsWith,
+    EndsWith,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Operator {
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
+}
+
+/// Flattened `EventLog` record. Field names map to all values found for that field
+pub(crate) type Fields = HashMap<String, Vec<String>>;
+
+/// Compile a single named search from the `detection` section
+pub(crate) fn compile_search(name: &str, search: &Value) -> Result<Search, SigmaError> {
+    match search {
+        Value::Object(map) => Ok(Search::Selections(vec![compile_map(map)?])),
+        Value::Array(values) => {
+            if values.iter().all(Value::is_object) {
+                let mut selections = Vec::new();
+                for value in values {
+                    if let Value::Object(map) = value {
+                        selections.push(compile_map(map)?);
+                    }
+                }
+                return Ok(Search::Selections(selections));
+            }
+            let mut keywords = Vec::new();
+            for value in values {
+                keywords.append(&mut compile_value(value, &[], "")?);
+            }
+            Ok(Search::Keywords(keywords))
+        }
+        Value::String(_) | Value::Number(_) => {
+            Ok(Search::Keywords(compile_value(search, &[], "")?))
+        }
+        _ => {
+            error!("[sigma] Unsupported search {name}: {search:?}");
+            Err(SigmaError::Detection)
+        }
+    }
+}
+
+/// Compile a map of field names and values. Ex: `Image|endswith: '\cmd.exe'`
+fn compile_map(map: &Map<String, Value>) -> Result<Vec<FieldMatch>, SigmaError> {
+    let mut fields = Vec::new();
+    for (key, value) in map {
+        let mut parts = key.split('|');
+        let field = parts.next().unwrap_or_default().to_string();
+        let modifiers: Vec<&str> = parts.collect();
+
+        let values = match value {
+            Value::Array(entries) => {
+                let mut values = Vec::new();
+                for entry in entries {
+                    values.push(compile_value(entry, &modifiers, &field)?);
+                }
+                values
+            }
+            _ => vec![compile_value(value, &modifiers, &field)?],
+        };
+
+        fields.push(FieldMatch {
+            field,
+            values,
+            all: modifiers.contains(&"all"),
+        });
+    }
+    Ok(fields)
+}
+
+/// Apply the field modifiers to a Sigma value. A value may expand into multiple matchers. Any of them can match
+fn compile_value(
+    value: &Value,
+    modifiers: &[&str],
+    field: &str,
+) -> Result<Vec<Matcher>, SigmaError> {
+    let text = match value {
+        Value::String(result) => result.clone(),
+        Value::Number(result) => result.to_string(),
+        Value::Bool(result) => result.to_string(),
+        Value::Null => return Ok(vec![Matcher::Null]),
+        _ => {
+            error!("[sigma] Unsupported value for field {field}: {value:?}");
+            return Err(SigmaError::Detection);
+        }
+    };
+
+    let mut variants = vec![text.into_bytes()];
+    let mut position = Position::Exact;
+    let mut cased = false;
+    let mut is_regex = false;
+    // Keywords are full text searches
+    if field.is_empty() {
+        position = Position::Contains;
+    }
+
+    for modifier in modifiers {
+        match *modifier {
+            "contains" => position = Position::Contains,
+            "startswith" => position = Position::StartsWith,
+            "endswith" => position = Position::EndsWith,
+            "cased" => cased = true,
+            "re" => is_regex = true,
+            // Regex flags are applied when the regex is compiled
+            "all" | "i" | "m" | "s" => {}
+            "wide" | "utf16le" => {
+                variants = variants
+                    .iter()
+                    .map(|variant| {
+                        String::from_utf8_lossy(variant)
+                            .encode_utf16()
+                            .flat_map(u16::to_le_bytes)
+                            .collect()
+                    })
+                    .collect();
+            }
+            "utf16be" => {
+                variants = variants
+                    .iter()
+                    .map(|variant| {
+                        String::from_utf8_lossy(variant)
+                            .encode_utf16()
+                            .flat_map(u16::to_be_bytes)
+                            .collect()
+                    })
+                    .collect();
+            }
+            "base64" => {
+                variants = variants
+                    .iter()
+                    .map(|variant| base64_encode_standard(variant).into_bytes())
+                    .collect();
+            }
+            "base64offset" => {
+                variants = variants
+                    .iter()
+                    .flat_map(|variant| base64_offsets(variant))
+                    .map(String::into_bytes)
+                    .collect();
+            }
+            "windash" => {
+                variants = variants
+                    .iter()
+                    .flat_map(|variant| windash(&String::from_utf8_lossy(variant)))
+                    .map(String::into_bytes)
+                    .collect();
+            }
+            "exists" => {
+                let exists = value.as_bool().unwrap_or(true);
+                return Ok(vec![Matcher::Exists(exists)]);
+            }
+            "gt" | "gte" | "lt" | "lte" => {
+                let Some(number) = value
+                    .as_f64()
+                    .or(value.as_str().and_then(|v| v.parse().ok()))
+                else {
+                    error!("[sigma] Comparison value for {field} is not a number");
+                    return Err(SigmaError::Detection);
+                };
+                let operator = match *modifier {
+                    "gt" => Operator::Greater,
+                    "gte" => Operator::GreaterEqual,
+                    "lt" => Operator::Less,
+                    _ => Operator::LessEqual,
+                };
+                return Ok(vec![Matcher::Compare {
+                    operator,
+                    value: number,
+                }]);
+            }
+            _ => {
+                error!("[sigma] Unsupported modifier {modifier} for field {field}");
+                return Err(SigmaError::Modifier);
+            }
+        }
+    }
+
+    let mut matchers = Vec::new();
+    for variant in variants {
+        let text = String::from_utf8_lossy(&variant).to_string();
+        if is_regex {
+            let flags = regex_flags(modifiers);
+            let regex = match create_regex(&format!("{flags}{text}")) {
+                Ok(result) => result,
+                Err(err) => {
+                    error!("[sigma] Bad regex for field {field}: {err:?}");
+                    return Err(SigmaError::Regex);
+                }
+            };
+            matchers.push(Matcher::Regex(regex));
+            continue;
+        }
+        matchers.push(text_matcher(&text, position, cased)?);
+    }
+
+    Ok(matchers)
+}
+
+/// Get regex flags from the `i`, `m`, and `s` modifiers
+fn regex_flags(modifiers: &[&str]) -> String {
+    let flags: String = modifiers
+        .iter()
+        .filter(|modifier| ["i", "m", "s"].contains(modifier))
+        .copied()
+        .collect();
+    if flags.is_empty() {
+        return flags;
+    }
+    format!("(?{flags})")
+}
+
+/// Create a text matcher. Values with unescaped `*` or `?` wildcards are converted to a regex
+fn text_matcher(value: &str, position: Position, cased: bool) -> Result<Matcher, SigmaError> {
+    let mut literal = String::new();
+    let mut pattern = String::new();
+    let mut has_wildcard = false;
+
+    let mut chars = value.chars().peekable();
+    while let Some(entry) = chars.next() {
+        match entry {
+            '\\' if chars
+                .peek()
+                .is_some_and(|next| ['*', '?', '\\'].contains(next)) =>
+            {
+                // Escape is always followed by a char
+                let escaped = chars.next().unwrap_or_default();
+                literal.push(escaped);
+                pattern.push_str(&regex::escape(&escaped.to_string()));
+            }
+            '*' => {
+                has_wildcard = true;
+                pattern.push_str(".*");
+            }
+            '?' => {
+                has_wildcard = true;
+                pattern.push('.');
+            }
+            _ => {
+                literal.push(entry);
+                pattern.push_str(&regex::escape(&entry.to_string()));
+            }
+        }
+    }
+
+    if !has_wildcard {
+        let value = if cased {
+            literal
+        } else {
+            literal.to_lowercase()
+        };
+        return Ok(Matcher::Text {
+            value,
+            position,
+            cased,
+        });
+    }
+
+    let (start, end) = match position {
+        Position::Exact => ("^", "$"),
+        Position::Contains => ("", ""),
+        Position::StartsWith => ("^", ""),
+        Position::EndsWith => ("", "$"),
+    };
+    let flags = if cased { "(?s)" } else { "(?is)" };
+    match create_regex(&format!("{flags}{start}{pattern}{end}")) {
+        Ok(result) => Ok(Matcher::Regex(result)),
+        Err(err) => {
+            error!("[sigma] Could not convert wildcard value {value}: {err:?}");
+            Err(SigmaError::Regex)
+        }
+    }
+}
+
+/// Base64 encode the value at the three possible offsets. Only the bytes not affected by surrounding data are kept
+fn base64_offsets(value: &[u8]) -> Vec<String> {
+    let start_offsets = [0, 2, 3];
+    let end_offsets = [0, 3, 2];
+
+    let mut variants = Vec::new();
+    for (offset, start) in start_offsets.iter().enumerate() {
+        let mut data = vec![b' '; offset];
+        data.extend_from_slice(value);
+        let encoded = base64_encode_standard(&data);
+
+        let end = encoded.len() - end_offsets[(value.len() + offset) % 3];
+        if *start >= end {
+            continue;
+        }
+        variants.push(encoded[*start..end].to_string());
+    }
+    variants
+}
+
+/// Windows command line flags may start with `-` or `/` or other dash characters
+fn windash(value: &str) -> Vec<String> {
+    let dashes = ['-', '/', '\u{2013}', '\u{2014}', '\u{2015}'];
+    let mut variants = Vec::new();
+    for dash in dashes {
+        let variant: String = value
+            .chars()
+            .map(|entry| if entry == '-' { dash } else { entry })
+            .collect();
+        if !variants.contains(&variant) {
+            variants.push(variant);
+        }
+    }
+    variants
+}
+
+impl Search {
+    /// Check if the search matches the record fields
+    pub(crate) fn matches(&self, fields: &Fields) -> bool {
+        match self {
+            Search::Keywords(keywords) => keywords.iter().any(|keyword| {
+                fields
+                    .values()
+                    .flatten()
+                    .any(|value| keyword.matches(Some(value)))
+            }),
+            Search::Selections(selections) => selections
+                .iter()
+                .any(|selection| selection.iter().all(|field| field.matches(fields))),
+        }
+    }
+}
+
+impl FieldMatch {
+    fn matches(&self, fields: &Fields) -> bool {
+        let values = fields.get(&self.field);
+        let group_match = |group: &Vec<Matcher>| {
+            group.iter().any(|matcher| match (matcher, values) {
+                (Matcher::Null | Matcher::Exists(false), None) => true,
+                (Matcher::Exists(exists), Some(_)) => *exists,
+                (Matcher::Null, Some(entries)) => entries.iter().all(String::is_empty),
+                (_, Some(entries)) => entries.iter().any(|entry| matcher.matches(Some(entry))),
+                (_, None) => false,
+            })
+        };
+
+        if self.all {
+            return self.values.iter().all(group_match);
+        }
+        self.values.iter().any(group_match)
+    }
+}
+
+impl Matcher {
+    fn matches(&self, value: Option<&String>) -> bool {
+        let value = match value {
+            Some(result) => result,
+            None => return false,
+        };
+
+        match self {
+            Matcher::Text {
+                value: expected,
+                position,
+                cased,
+            } => {
+                let lower;
+                let target = if *cased {
+                    value
+                } else {
+                    lower = value.to_lowercase();
+                    &lower
+                };
+                match position {
+                    Position::Exact => target == expected,
+                    Position::Contains => target.contains(expected.as_str()),
+                    Position::StartsWith => target.starts_with(expected.as_str()),
+                    Position::EndsWith => target.ends_with(expected.as_str()),
+                }
+            }
+            Matcher::Regex(regex) => regex.is_match(value),
+            Matcher::Null => value.is_empty(),
+            Matcher::Exists(exists) => *exists,
+            Matcher::Compare {
+                operator,
+                value: expected,
+            } => {
+                let number = match value.parse::<f64>() {
+                    Ok(result) => result,
+                    Err(_) => return false,
+                };
+                match operator {
+                    Operator::Greater => number > *expected,
+                    Operator::GreaterEqual => number >= *expected,
+                    Operator::Less => number < *expected,
+                    Operator::LessEqual => number <= *expected,
+                }
+            }
+        }
+    }
+}
+
+/// Flatten the `EventLog` JSON data into field names and values.
+/// Attributes are named `<element>_<attribute>`. Ex: `Provider_Name`
+pub(crate) fn flatten_record(data: &Value) -> Fields {
+    let mut fields = HashMap::new();
+    flatten_value("", data, &mut fields);
+    fields
+}
+
+fn flatten_value(key: &str, data: &Value, fields: &mut Fields) {
+    match data {
+        Value::Object(map) => {
+            for (name, value) in map {
+                if name == "#attributes" {
+                    if let Value::Object(attributes) = value {
+                        for (attribute, attribute_value) in attributes {
+                            flatten_value(&format!("{key}_{attribute}"), attribute_value, fields);
+                        }
+                    }
+                    continue;
+                }
+                if name == "#text" {
+                    flatten_value(key, value, fields);
+                    continue;
+                }
+                flatten_value(name, value, fields);
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                flatten_value(key, value, fields);
+            }
+        }
+        Value::String(value) => push_field(key, value.clone(), fields),
+        Value::Number(value) => push_field(key, value.to_string(), fields),
+        Value::Bool(value) => push_field(key, value.to_string(), fields),
+        Value::Null => push_field(key, String::new(), fields),
+    }
+}
+
+fn push_field(key: &str, value: String, fields: &mut Fields) {
+    if key.is_empty() {
+        return;
+    }
+    fields.entry(key.to_string()).or_default().push(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Matcher, Position, Search, base64_offsets, compile_search, flatten_record, text_matcher,
+        windash,
+    };
+    use serde_json::json;
+
+    fn record() -> serde_json::Value {
+        json!({"Event":{"System":{"Provider":{"#attributes":{"Name":"Microsoft-Windows-Security-Auditing"}},"EventID":4688,"Channel":"Security","Correlation":null},"EventData":{"NewProcessName":"C:\\Windows\\System32\\WindowsPowerShell\\v1.0\\powershell.exe","CommandLine":"powershell.exe -enc SQBFAFgAIAAoAE4AZQB3AC0ATwBiAGoAZQBjAHQA","TokenElevationType":"%%1936"}}})
+    }
+
+    #[test]
+    fn test_flatten_record() {
+        let fields = flatten_record(&record());
+        assert_eq!(fields["EventID"], vec!["4688"]);
+        assert_eq!(
+            fields["Provider_Name"],
+            vec!["Microsoft-Windows-Security-Auditing"]
+        );
+        assert_eq!(fields["Correlation"], vec![""]);
+        assert_eq!(fields["Channel"], vec!["Security"]);
+    }
+
+    #[test]
+    fn test_compile_search() {
+        let fields = flatten_record(&record());
+        let search = compile_search(
+            "selection",
+            &json!({"EventID": 4688, "NewProcessName|endswith": ["\\cmd.exe", "\\POWERSHELL.exe"]}),
+        )
+        .unwrap();
+        assert!(search.matches(&fields));
+
+        let search = compile_search(
+            "selection",
+            &json!({"CommandLine|contains|all": [" -enc ", "SQBFAFgA"]}),
+        )
+        .unwrap();
+        assert!(search.matches(&fields));
+
+        let search = compile_search(
+            "selection",
+            &json!({"CommandLine|contains|all": [" -enc ", "mimikatz"]}),
+        )
+        .unwrap();
+        assert!(!search.matches(&fields));
+
+        let search =
+            compile_search("selection", &json!({"CommandLine|re": "-[eE][nN][cC] "})).unwrap();
+        assert!(search.matches(&fields));
+
+        let search = compile_search(
+            "selection",
+            &json!({"CommandLine|wide|base64offset|contains": "IEX"}),
+        )
+        .unwrap();
+        assert!(search.matches(&fields));
+
+        let search = compile_search(
+            "selection",
+            &json!({"NewProcessName": "C:\\Windows\\\\*\\powershell.exe"}),
+        )
+        .unwrap();
+        assert!(search.matches(&fields));
+
+        let search = compile_search(
+            "selection",
+            &json!([{"EventID": 1}, {"Correlation": null, "Missing": null}]),
+        )
+        .unwrap();
+        assert!(search.matches(&fields));
+
+        let search = compile_search(
+            "selection",
+            &json!({"CommandLine|exists": true, "Missing|exists": false, "EventID|gte": 4600}),
+        )
+        .unwrap();
+        assert!(search.matches(&fields));
+
+        let search = compile_search("keywords", &json!(["mimikatz", "*SQBFAFgA*"])).unwrap();
+        assert!(matches!(search, Search::Keywords(_)));
+        assert!(search.matches(&fields));
+    }
+
+    #[test]
+    fn test_compile_search_bad_modifier() {
+        assert!(compile_search("selection", &json!({"Image|expand": "%test%"})).is_err());
+        assert!(compile_search("selection", &json!({"Image|re": "(unclosed"})).is_err());
+    }
+
+    #[test]
+    fn test_text_matcher() {
+        let result = text_matcher("C:\\Windows\\", Position::StartsWith, false).unwrap();
+        assert!(matches!(result, Matcher::Text { .. }));
+        assert!(result.matches(Some(&String::from("c:\\windows\\system32"))));
+
+        let result = text_matcher("what\\?", Position::Exact, true).unwrap();
+        assert!(result.matches(Some(&String::from("what?"))));
+        assert!(!result.matches(Some(&String::from("WHAT?"))));
+
+        let result = text_matcher("a?c", Position::Exact, false).unwrap();
+        assert!(result.matches(Some(&String::from("ABC"))));
+    }
+
+    #[test]
+    fn test_base64_offsets() {
+        assert_eq!(
+            base64_offsets(b"/bin/sh"),
+            vec!["L2Jpbi9za", "9iaW4vc2", "vYmluL3No"]
+        );
+    }
+
+    #[test]
+    fn test_windash() {
+        assert_eq!(
+            windash("-enc"),
+            vec!["-enc", "/enc", "\u{2013}enc", "\u{2014}enc", "\u{2015}enc"]
+        );
+    }
+}
@@ -0,0 +1,24 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum SigmaError {
+    ReadDirectory,
+    Detection,
+    Modifier,
+    Regex,
+    Condition,
+}
+
+impl std::error::Error for SigmaError {}
+
+impl fmt::Display for SigmaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SigmaError::ReadDirectory => write!(f, "Failed to read Sigma rule directory"),
+            SigmaError::Detection => write!(f, "Unsupported Sigma detection"),
+            SigmaError::Modifier => write!(f, "Unsupported Sigma field modifier"),
+            SigmaError::Regex => write!(f, "Invalid Sigma regex"),
+            SigmaError::Condition => write!(f, "Invalid Sigma condition"),
+        }
+    }
+}
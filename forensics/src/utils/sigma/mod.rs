@@ -0,0 +1,4 @@
+mod condition;
+mod detection;
+mod error;
+pub(crate) mod rule;
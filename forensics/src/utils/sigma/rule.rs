@@ -0,0 +1,376 @@
+/**
+ * Sigma is a generic signature format for describing detections in log data
+ * Rules are YAML documents with a `detection` section made of named searches and a `condition` that combines the searches
+ * Artemis compiles the rules once and evaluates them against `EventLog` records during collection
+ *
+ * References:
+ * `https://github.com/SigmaHQ/sigma-specification`
+ * `https://sigmahq.io/docs/basics/modifiers.html`
+ *
+ * Other Parsers:
+ * `https://github.com/SigmaHQ/pySigma`
+ * `https://github.com/WithSecureLabs/chainsaw`
+ * `https://github.com/Yamato-Security/hayabusa`
+ */
+use super::{
+    condition::{Condition, parse_condition},
+    detection::{Fields, Search, compile_search, flatten_record},
+    error::SigmaError,
+};
+use crate::{
+    filesystem::files::{file_extension, read_text_file},
+    utils::encoding::base64_decode_standard,
+};
+use common::windows::{EventLogRecord, SigmaHit};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use tracing::{error, warn};
+use walkdir::WalkDir;
+
+#[derive(Debug, Deserialize)]
+struct RawRule {
+    title: String,
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    level: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    logsource: LogSource,
+    detection: Value,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct LogSource {
+    product: Option<String>,
+    service: Option<String>,
+}
+
+/// Compiled Sigma rule
+#[derive(Debug)]
+pub(crate) struct SigmaRule {
+    pub(crate) title: String,
+    pub(crate) id: String,
+    pub(crate) level: String,
+    pub(crate) description: String,
+    pub(crate) tags: Vec<String>,
+    /// `EventLog` channel based on the `logsource` service
+    channel: Option<String>,
+    names: Vec<String>,
+    searches: HashMap<String, Search>,
+    conditions: Vec<Condition>,
+}
+
+/// Load Sigma rules from a directory and base64 encoded strings. Rules that cannot be compiled are skipped
+pub(crate) fn load_rules(
+    directory: &Option<String>,
+    encoded: &Option<Vec<String>>,
+) -> Vec<SigmaRule> {
+    let mut rules = Vec::new();
+    if let Some(path) = directory {
+        match read_rule_directory(path) {
+            Ok(mut result) => rules.append(&mut result),
+            Err(err) => error!("[sigma] Could not read rules at {path}: {err:?}"),
+        }
+    }
+
+    if let Some(values) = encoded {
+        for value in values {
+            let bytes = match base64_decode_standard(value) {
+                Ok(result) => result,
+                Err(err) => {
+                    error!("[sigma] Could not base64 decode rule: {err:?}");
+                    continue;
+                }
+            };
+            rules.append(&mut parse_rules(
+                &String::from_utf8_lossy(&bytes),
+                "base64 rule",
+            ));
+        }
+    }
+
+    rules
+}
+
+/// Read all YAML rules in the directory and any subdirectories
+fn read_rule_directory(path: &str) -> Result<Vec<SigmaRule>, SigmaError> {
+    let mut rules = Vec::new();
+    for entry in WalkDir::new(path) {
+        let entry = match entry {
+            Ok(result) => result,
+            Err(err) => {
+                error!("[sigma] Could not walk rule directory {path}: {err:?}");
+                return Err(SigmaError::ReadDirectory);
+            }
+        };
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let rule_path = entry.path().display().to_string();
+        let extension = file_extension(&rule_path);
+        if extension != "yml" && extension != "yaml" {
+            continue;
+        }
+
+        let data = match read_text_file(&rule_path) {
+            Ok(result) => result,
+            Err(err) => {
+                warn!("[sigma] Could not read rule {rule_path}: {err:?}");
+                continue;
+            }
+        };
+        rules.append(&mut parse_rules(&data, &rule_path));
+    }
+
+    Ok(rules)
+}
+
+/// Parse and compile all Sigma rules in YAML data. A file may contain multiple YAML documents
+pub(crate) fn parse_rules(data: &str, source: &str) -> Vec<SigmaRule> {
+    let mut rules = Vec::new();
+    for document in serde_yaml_ng::Deserializer::from_str(data) {
+        let raw = match RawRule::deserialize(document) {
+            Ok(result) => result,
+            Err(err) => {
+                warn!("[sigma] Could not deserialize rule from {source}: {err:?}");
+                continue;
+            }
+        };
+
+        // Only Windows rules apply to EventLogs
+        if raw
+            .logsource
+            .product
+            .as_ref()
+            .is_some_and(|product| product != "windows")
+        {
+            continue;
+        }
+
+        let title = raw.title.clone();
+        match compile_rule(raw) {
+            Ok(result) => rules.push(result),
+            Err(err) => warn!("[sigma] Skipping rule '{title}' from {source}: {err:?}"),
+        }
+    }
+
+    rules
+}
+
+/// Compile the detection section of a rule
+fn compile_rule(raw: RawRule) -> Result<SigmaRule, SigmaError> {
+    let Value::Object(detection) = raw.detection else {
+        error!("[sigma] Rule detection is not a map");
+        return Err(SigmaError::Detection);
+    };
+
+    let mut searches = HashMap::new();
+    let mut names = Vec::new();
+    let mut conditions = Vec::new();
+    for (name, value) in detection {
+        if name == "condition" {
+            match value {
+                Value::String(condition) => conditions.push(parse_condition(&condition)?),
+                Value::Array(values) => {
+                    for condition in values {
+                        match condition.as_str() {
+                            Some(result) => conditions.push(parse_condition(result)?),
+                            None => return Err(SigmaError::Condition),
+                        }
+                    }
+                }
+                _ => return Err(SigmaError::Condition),
+            }
+            continue;
+        }
+        // Deprecated Sigma option
+        if name == "timeframe" {
+            return Err(SigmaError::Detection);
+        }
+
+        searches.insert(name.clone(), compile_search(&name, &value)?);
+        names.push(name);
+    }
+
+    if conditions.is_empty() {
+        error!("[sigma] Rule is missing condition");
+        return Err(SigmaError::Condition);
+    }
+    for condition in &conditions {
+        condition.validate(&names)?;
+    }
+
+    Ok(SigmaRule {
+        title: raw.title,
+        id: raw.id,
+        level: raw.level,
+        description: raw.description,
+        tags: raw.tags,
+        channel: raw.logsource.service.as_deref().and_then(service_channel),
+        names,
+        searches,
+        conditions,
+    })
+}
+
+/// Map common Sigma `logsource` services to `EventLog` channels
+fn service_channel(service: &str) -> Option<String> {
+    let channel = match service {
+        "security" => "Security",
+        "system" => "System",
+        "application" => "Application",
+        "sysmon" => "Microsoft-Windows-Sysmon/Operational",
+        "powershell" => "Microsoft-Windows-PowerShell/Operational",
+        "powershell-classic" => "Windows PowerShell",
+        "taskscheduler" => "Microsoft-Windows-TaskScheduler/Operational",
+        "windefend" => "Microsoft-Windows-Windows Defender/Operational",
+        "bits-client" => "Microsoft-Windows-Bits-Client/Operational",
+        "wmi" => "Microsoft-Windows-WMI-Activity/Operational",
+        "terminalservices-localsessionmanager" => {
+            "Microsoft-Windows-TerminalServices-LocalSessionManager/Operational"
+        }
+        _ => return None,
+    };
+    Some(channel.to_string())
+}
+
+impl SigmaRule {
+    /// Check if the flattened record matches the rule
+    pub(crate) fn matches(&self, fields: &Fields) -> bool {
+        if let Some(channel) = &self.channel
+            && !fields.get("Channel").is_some_and(|values| {
+                values
+                    .iter()
+                    .any(|value| value.eq_ignore_ascii_case(channel))
+            })
+        {
+            return false;
+        }
+
+        let search = |name: &str| {
+            self.searches
+                .get(name)
+                .is_some_and(|search| search.matches(fields))
+        };
+        self.conditions
+            .iter()
+            .any(|condition| condition.evaluate(&self.names, &search))
+    }
+}
+
+/// Evaluate `EventLog` records against the Sigma rules
+pub(crate) fn evaluate_records(rules: &[SigmaRule], records: &[EventLogRecord]) -> Vec<SigmaHit> {
+    let mut hits = Vec::new();
+    if rules.is_empty() {
+        return hits;
+    }
+
+    for record in records {
+        let fields = flatten_record(&record.data);
+        for rule in rules {
+            if !rule.matches(&fields) {
+                continue;
+            }
+            hits.push(SigmaHit {
+                rule_id: rule.id.clone(),
+                title: rule.title.clone(),
+                level: rule.level.clone(),
+                description: rule.description.clone(),
+                tags: rule.tags.clone(),
+                event_record_id: record.event_record_id,
+                timestamp: record.timestamp.clone(),
+                record: record.data.clone(),
+                evidence: record.evidence.clone(),
+            });
+        }
+    }
+
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{evaluate_records, load_rules, parse_rules};
+    use crate::utils::encoding::base64_encode_standard;
+    use common::windows::EventLogRecord;
+    use evtx::EvtxParser;
+    use std::path::PathBuf;
+
+    fn security_records() -> Vec<EventLogRecord> {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/eventlogs/Security.evtx");
+        let mut parser = EvtxParser::from_path(&test_location).unwrap();
+
+        let mut records = Vec::new();
+        for record in parser.records_json_value() {
+            let data = record.unwrap();
+            records.push(EventLogRecord {
+                event_record_id: data.event_record_id,
+                timestamp: data.timestamp.to_string(),
+                data: data.data,
+                evidence: test_location.display().to_string(),
+                carved: false,
+                offset: 0,
+            });
+        }
+        records
+    }
+
+    #[test]
+    fn test_load_rules() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/sigma");
+        let encoded = base64_encode_standard(
+            b"title: Special Logon\nid: 1234\nlevel: low\nlogsource:\n  product: windows\n  service: security\ndetection:\n  selection:\n    EventID: 4672\n  condition: selection\n",
+        );
+
+        let rules = load_rules(
+            &Some(test_location.display().to_string()),
+            &Some(vec![encoded]),
+        );
+        assert_eq!(rules.len(), 4);
+        assert!(rules.iter().any(|rule| rule.title == "Special Logon"));
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        let data = "title: Linux\nlogsource:\n  product: linux\ndetection:\n  selection:\n    Image: test\n  condition: selection\n---\ntitle: Bad Modifier\ndetection:\n  selection:\n    Image|expand: test\n  condition: selection\n---\ntitle: Missing Search\ndetection:\n  selection:\n    Image: test\n  condition: selection and filter\n---\ntitle: Good\ndetection:\n  selection:\n    Image: test\n  condition: selection\n";
+        let rules = parse_rules(data, "test");
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].title, "Good");
+    }
+
+    #[test]
+    fn test_evaluate_records() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/sigma");
+        let rules = load_rules(&Some(test_location.display().to_string()), &None);
+        let records = security_records();
+
+        let hits = evaluate_records(&rules, &records);
+        let lsass: Vec<_> = hits
+            .iter()
+            .filter(|hit| hit.rule_id == "5b0e8a42-6a51-4f61-9c7e-2c5cf3a9c8f1")
+            .collect();
+        assert_eq!(lsass.len(), 2);
+        assert_eq!(lsass[0].level, "informational");
+        assert_eq!(lsass[0].record["Event"]["System"]["EventID"], 4688);
+
+        // Rule only applies to the System channel
+        assert!(
+            !hits
+                .iter()
+                .any(|hit| hit.rule_id == "0c0f7a5e-5d55-4b1f-8e61-1f3f0d0a7f3d")
+        );
+        assert!(
+            hits.iter()
+                .any(|hit| hit.rule_id == "c6a0c1f4-8d5e-4f59-9b0b-ec6f7d9f5a22")
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use rusqlite::{Connection, Error, OpenFlags, Row, types::ValueRef};
+
+/// Open a SQLITE file read only. The file is opened as immutable to bypass any SQLITE file locks
+pub(crate) fn open_sqlite(path: &str) -> Result<Connection, Error> {
+    let sqlite_file = format!("file:{path}?immutable=1");
+    Connection::open_with_flags(
+        sqlite_file,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_URI,
+    )
+}
+
+/// Get a text or blob column as a string. Returns an empty string if the column does not exist
+pub(crate) fn column_text(row: &Row<'_>, name: &str) -> String {
+    match row.get_ref(name) {
+        Ok(ValueRef::Text(value) | ValueRef::Blob(value)) => {
+            String::from_utf8_lossy(value).to_string()
+        }
+        Ok(ValueRef::Integer(value)) => value.to_string(),
+        Ok(ValueRef::Real(value)) => value.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Get an integer column. Returns 0 if the column does not exist
+pub(crate) fn column_int(row: &Row<'_>, name: &str) -> i64 {
+    match row.get_ref(name) {
+        Ok(ValueRef::Integer(value)) => value,
+        Ok(ValueRef::Real(value)) => value as i64,
+        Ok(ValueRef::Text(value)) => String::from_utf8_lossy(value).parse().unwrap_or_default(),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{column_int, column_text, open_sqlite};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_open_sqlite() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/activitiescache/ActivitiesCache.db");
+        let conn = open_sqlite(test_location.to_str().unwrap()).unwrap();
+        assert!(conn.prepare("SELECT * FROM Activity").is_ok());
+
+        assert!(open_sqlite("./does/not/exist.db").is_err());
+    }
+
+    #[test]
+    fn test_columns() {
+        let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        test_location.push("tests/test_data/windows/activitiescache/ActivitiesCache.db");
+        let conn = open_sqlite(test_location.to_str().unwrap()).unwrap();
+        let mut stmt = conn
+            .prepare("SELECT Platform, ExpirationTime FROM Activity_PackageId")
+            .unwrap();
+        let mut rows = stmt.query([]).unwrap();
+        let row = rows.next().unwrap().unwrap();
+
+        assert_eq!(column_text(row, "Platform"), "windows_win32");
+        assert_eq!(column_int(row, "ExpirationTime"), 1702592100);
+        assert_eq!(column_text(row, "Missing"), "");
+        assert_eq!(column_int(row, "Missing"), 0);
+    }
+}
@@ -177,6 +177,15 @@ pub(crate) fn unixepoch_microseconds_to_iso(timestamp: i64) -> String {
     }
 }
 
+/// Convert `WebKit` (Chromium) microseconds since 1601 to ISO8601 format. Zero values are treated as unset
+pub(crate) fn webkit_time_to_iso(timestamp: i64) -> String {
+    if timestamp <= 0 {
+        return String::from("1970-01-01T00:00:00.000Z");
+    }
+    let to_filetime = 10;
+    filetime_to_iso(timestamp as u64 * to_filetime)
+}
+
 /// Check if `time1` is greater than `time2`. Timestamps must be in RFC 3339 format. (YYYY-MM-DDTHH:mm:ss.000Z)
 pub(crate) fn compare_timestamps(time1: &str, time2: &str) -> Result<bool, ArtemisError> {
     let time1_result = DateTime::parse_from_rfc3339(time1);
@@ -217,7 +226,7 @@ mod tests {
     use crate::utils::time::{
         cocoatime_to_iso, compare_timestamps, fattime_utc_to_iso, filetime_to_iso, get_fat_bits,
         ole_automationtime_to_iso, time_now, unixepoch_microseconds_to_iso, unixepoch_to_iso,
-        unixepoch_to_iso_float, unixepoch_to_iso_with_nano,
+        unixepoch_to_iso_float, unixepoch_to_iso_with_nano, webkit_time_to_iso,
     };
 
     #[test]
@@ -251,6 +260,15 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_webkit_time_to_iso() {
+        assert_eq!(
+            webkit_time_to_iso(13345678901234567),
+            "2023-11-28T21:01:41.234Z"
+        );
+        assert_eq!(webkit_time_to_iso(0), "1970-01-01T00:00:00.000Z");
+    }
+
     #[test]
     fn test_get_fat_bits() {
         let test_data = [123, 79, 195, 14];
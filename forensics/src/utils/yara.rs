@@ -2,7 +2,7 @@ use super::{encoding::base64_decode_standard, error::ArtemisError, strings::extr
 use reqwest::blocking::Client;
 use tracing::error;
 #[cfg(feature = "yarax")]
-use yara_x::{Compiler, Scanner};
+use yara_x::{Compiler, Rules, Scanner};
 
 /// Decode the provided Yara rule
 pub(crate) fn extract_rule(encoded_rule: &str) -> Result<String, ArtemisError> {
@@ -61,6 +61,32 @@ pub(crate) fn scan_bytes(data: &[u8], encoded_rule: &str) -> Result<Vec<String>,
     Ok(matches)
 }
 
+/// Compile the provided Yara rule once. Use when scanning many small buffers with the same rule
+pub(crate) fn compile_rules(encoded_rule: &str) -> Result<Rules, ArtemisError> {
+    let rule = extract_rule(encoded_rule)?;
+    let compile = compile_rule(&rule)?;
+    Ok(compile.build())
+}
+
+/// Scan bytes using a scanner created from compiled Yara-X rules
+pub(crate) fn scan_bytes_scanner(
+    data: &[u8],
+    scanner: &mut Scanner<'_>,
+) -> Result<Vec<String>, ArtemisError> {
+    let hits = match scanner.scan(data) {
+        Ok(result) => result,
+        Err(err) => {
+            error!("Failed to scan bytes: {err:?}",);
+            return Err(ArtemisError::YaraScan);
+        }
+    };
+    let mut matches = Vec::new();
+    for hit in hits.matching_rules() {
+        matches.push(hit.identifier().to_string());
+    }
+    Ok(matches)
+}
+
 /// Scan base64 encoded bytes using Yara-X
 pub(crate) fn scan_base64_bytes(
     encoded_bytes: &str,
@@ -184,6 +210,30 @@ mod tests {
         assert_eq!(result[0], "hello_world");
     }
 
+    #[test]
+    fn test_scan_bytes_scanner() {
+        use super::{compile_rules, scan_bytes_scanner};
+        use yara_x::Scanner;
+
+        let rule = r#"
+        rule hello_world {
+        strings:
+        $ = "hello, world! Its Rust!"
+        condition:
+        all of them
+        }
+        "#;
+
+        let rules = compile_rules(&base64_encode_standard(rule.as_bytes())).unwrap();
+        let mut scanner = Scanner::new(&rules);
+        let result =
+            scan_bytes_scanner(b"test hello, world! Its Rust! test", &mut scanner).unwrap();
+        assert_eq!(result[0], "hello_world");
+
+        let result = scan_bytes_scanner(b"nothing here", &mut scanner).unwrap();
+        assert!(result.is_empty());
+    }
+
     #[test]
     fn test_extract_rule() {
         let rule = r#"
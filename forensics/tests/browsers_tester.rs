@@ -0,0 +1,21 @@
+#[test]
+fn test_chromium_parser() {
+    use forensics::core::parse_toml_file;
+    use std::path::PathBuf;
+
+    let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    test_location.push("tests/test_data/browser/chromium.toml");
+
+    parse_toml_file(&test_location.display().to_string()).unwrap();
+}
+
+#[test]
+fn test_firefox_parser() {
+    use forensics::core::parse_toml_file;
+    use std::path::PathBuf;
+
+    let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    test_location.push("tests/test_data/browser/firefox.toml");
+
+    parse_toml_file(&test_location.display().to_string()).unwrap();
+}
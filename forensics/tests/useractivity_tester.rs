@@ -0,0 +1,10 @@
+#[test]
+fn test_useractivity_parser() {
+    use forensics::core::parse_toml_file;
+    use std::path::PathBuf;
+
+    let mut test_location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    test_location.push("tests/test_data/linux/useractivity.toml");
+
+    parse_toml_file(&test_location.display().to_string()).unwrap();
+}
@@ -0,0 +1,125 @@
+use crate::artifacts::filter::filter_data;
+use serde_json::Value;
+
+/// Timeline Chromium and Firefox browser records
+pub(crate) fn browsers(
+    data: &mut Value,
+    artifact: &str,
+    start: &Option<String>,
+    end: &Option<String>,
+) -> bool {
+    if !data.is_object() {
+        return false;
+    }
+    let record_type = data["record_type"]
+        .as_str()
+        .unwrap_or("Unknown")
+        .to_lowercase();
+    let data_type = format!("browser:{}:{record_type}", artifact.to_lowercase());
+    let url = data["url"].as_str().unwrap_or_default();
+    let title = data["title"].as_str().unwrap_or_default();
+    let message = if title.is_empty() {
+        url.to_string()
+    } else {
+        format!("{url} - {title}")
+    };
+    data["artifact"] = artifact.into();
+    data["data_type"] = Value::String(data_type);
+    data["message"] = Value::String(message);
+
+    let mut times = Vec::new();
+    let primary_desc = data["timestamp_desc"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    for (key, desc) in [
+        ("timestamp", primary_desc.as_str()),
+        ("last_accessed", "Last Accessed"),
+        ("end_time", "End Time"),
+        ("expires", "Expires"),
+    ] {
+        let Some(value) = data[key].as_str() else {
+            continue;
+        };
+        // History records have an entry for each visit. The last visit time is already one of the visits
+        if value.starts_with("1970") || (key == "last_accessed" && record_type == "history") {
+            continue;
+        }
+        times.push((value.to_string(), desc.to_string()));
+    }
+
+    // Some records do not have timestamps (ex: Top Sites). We can still include them
+    if times.is_empty() {
+        if start.is_some() || end.is_some() {
+            return false;
+        }
+        data["datetime"] = "1970-01-01T00:00:00.000Z".into();
+        data["timestamp_desc"] = "N/A".into();
+        return true;
+    }
+
+    let mut entries = Vec::new();
+    for (time, desc) in times {
+        if filter_data(&time, start, end) {
+            continue;
+        }
+        data["datetime"] = Value::String(time);
+        data["timestamp_desc"] = Value::String(desc);
+        entries.push(data.clone());
+    }
+
+    if entries.is_empty() {
+        return false;
+    }
+    *data = Value::Array(entries);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::browsers;
+    use serde_json::json;
+
+    #[test]
+    fn test_browsers() {
+        let mut test = json!({
+            "browser": "Chrome",
+            "record_type": "Download",
+            "url": "https://github.com/artemis.zip",
+            "title": "",
+            "timestamp": "2023-11-14T22:18:20.000Z",
+            "timestamp_desc": "Download Started",
+            "last_accessed": "1970-01-01T00:00:00.000Z",
+            "end_time": "2023-11-14T22:18:25.000Z",
+            "expires": "1970-01-01T00:00:00.000Z",
+        });
+
+        assert!(browsers(&mut test, "Chromium", &None, &None));
+        let entries = test.as_array().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["timestamp_desc"], "Download Started");
+        assert_eq!(entries[0]["data_type"], "browser:chromium:download");
+        assert_eq!(entries[0]["message"], "https://github.com/artemis.zip");
+        assert_eq!(entries[1]["datetime"], "2023-11-14T22:18:25.000Z");
+        assert_eq!(entries[1]["timestamp_desc"], "End Time");
+    }
+
+    #[test]
+    fn test_browsers_no_times() {
+        let mut test = json!({
+            "browser": "Chrome",
+            "record_type": "TopSite",
+            "url": "https://github.com/",
+            "title": "GitHub",
+            "timestamp": "1970-01-01T00:00:00.000Z",
+            "timestamp_desc": "",
+        });
+
+        assert!(browsers(&mut test, "Chromium", &None, &None));
+        assert_eq!(test["message"], "https://github.com/ - GitHub");
+        assert_eq!(test["timestamp_desc"], "N/A");
+
+        let start = Some(String::from("2024-01-01T00:00:00.000Z"));
+        assert!(!browsers(&mut test, "Chromium", &start, &None));
+    }
+}
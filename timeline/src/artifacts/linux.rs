@@ -66,6 +66,44 @@ pub(crate) fn logons(data: &mut Value, start: &Option<String>, end: &Option<Stri
     true
 }
 
+/// Timeline Linux user activity. Many history files do not track timestamps
+pub(crate) fn user_activity(
+    data: &mut Value,
+    start: &Option<String>,
+    end: &Option<String>,
+) -> bool {
+    if !data.is_object() {
+        return false;
+    }
+    let Some(timestamp) = data["timestamp"].as_str() else {
+        return false;
+    };
+    let has_time = !timestamp.starts_with("1970");
+    if (start.is_some() || end.is_some()) && !has_time {
+        return false;
+    }
+    if filter_data(timestamp, start, end) {
+        return false;
+    }
+
+    data["datetime"] = timestamp.into();
+    data["artifact"] = "User Activity Linux".into();
+    data["data_type"] = "linux:useractivity:entry".into();
+    data["timestamp_desc"] = if has_time {
+        "Activity Time".into()
+    } else {
+        "N/A".into()
+    };
+    data["message"] = Value::String(format!(
+        "User: {} - {}: {}",
+        data["username"].as_str().unwrap_or_default(),
+        data["activity_type"].as_str().unwrap_or_default(),
+        data["value"].as_str().unwrap_or_default()
+    ));
+
+    true
+}
+
 pub(crate) fn ext4_filelisting(
     data: &mut Value,
     start: &Option<String>,
@@ -105,7 +143,7 @@ pub(crate) fn ext4_filelisting(
 #[cfg(test)]
 mod tests {
     use super::{journal, sudo_linux};
-    use crate::artifacts::linux::{ext4_filelisting, logons};
+    use crate::artifacts::linux::{ext4_filelisting, logons, user_activity};
     use serde_json::json;
 
     #[test]
@@ -136,6 +174,38 @@ mod tests {
         assert_eq!(test["data1"], "anything i want");
     }
 
+    #[test]
+    fn test_user_activity() {
+        let mut test = json!({
+            "username": "bob",
+            "activity_type": "BashHistory",
+            "value": "sudo cat /etc/shadow",
+            "timestamp": "2023-11-14T22:15:00.000Z",
+        });
+
+        assert!(user_activity(&mut test, &None, &None));
+        assert_eq!(test["datetime"], "2023-11-14T22:15:00.000Z");
+        assert_eq!(test["artifact"], "User Activity Linux");
+        assert_eq!(
+            test["message"],
+            "User: bob - BashHistory: sudo cat /etc/shadow"
+        );
+
+        let mut test = json!({
+            "username": "bob",
+            "activity_type": "KnownHost",
+            "value": "github.com",
+            "timestamp": "1970-01-01T00:00:00.000Z",
+        });
+        assert!(!user_activity(
+            &mut test,
+            &Some(String::from("2023-01-01T00:00:00.000Z")),
+            &None
+        ));
+        assert!(user_activity(&mut test, &None, &None));
+        assert_eq!(test["timestamp_desc"], "N/A");
+    }
+
     #[test]
     fn test_logons() {
         let mut test = json!({
@@ -1,3 +1,4 @@
+pub(crate) mod browsers;
 pub(crate) mod files;
 pub(crate) mod filter;
 pub(crate) mod linux;
@@ -746,6 +746,74 @@ pub(crate) fn wmi(data: &mut Value) -> bool {
     true
 }
 
+/// Timeline Windows Timeline activities
+pub(crate) fn activitiescache(
+    data: &mut Value,
+    start: &Option<String>,
+    end: &Option<String>,
+) -> bool {
+    if !data.is_object() {
+        return false;
+    }
+    let mut entries = Vec::new();
+    data["artifact"] = "ActivitiesCache".into();
+    data["data_type"] = "windows:sqlite:activitiescache:entry".into();
+
+    let application = data["application"].as_str().unwrap_or_default();
+    let details = [
+        data["display_text"].as_str().unwrap_or_default(),
+        data["description"].as_str().unwrap_or_default(),
+        data["clipboard_text"][0].as_str().unwrap_or_default(),
+    ];
+    data["message"] = match details.iter().find(|value| !value.is_empty()) {
+        Some(value) => Value::String(format!("{application} - {value}")),
+        None => Value::String(application.into()),
+    };
+
+    // Timesketch cannot handle large amounts of nested JSON keys. Common payload values are already extracted
+    data.as_object_mut().unwrap().remove("payload");
+
+    let temp = data.clone();
+    let times = extract_activity_times(&temp).unwrap_or_default();
+    for (key, value) in times {
+        if filter_data(key, start, end) {
+            continue;
+        }
+        data["datetime"] = Value::String(key.into());
+        data["timestamp_desc"] = Value::String(value);
+        entries.push(data.clone());
+    }
+
+    if entries.is_empty() {
+        return false;
+    }
+    *data = Value::Array(entries);
+    true
+}
+
+/// Extract the Windows Timeline activity timestamps. Skips empty timestamps
+fn extract_activity_times(data: &Value) -> Option<HashMap<&str, String>> {
+    let mut times: HashMap<&str, String> = HashMap::new();
+    for (key, desc) in [
+        ("start_time", "Activity Start"),
+        ("end_time", "Activity End"),
+        ("last_modified", "Activity Last Modified"),
+        ("created", "Activity Operation Created"),
+    ] {
+        let value = data[key].as_str()?;
+        if value.starts_with("1970") {
+            continue;
+        }
+        if let Some(existing) = times.get(value) {
+            times.insert(value, format!("{existing} {desc}"));
+        } else {
+            times.insert(value, String::from(desc));
+        }
+    }
+
+    Some(times)
+}
+
 pub(crate) fn mft(data: &mut Value, start: &Option<String>, end: &Option<String>) -> bool {
     if !data.is_object() {
         return false;
@@ -787,8 +855,8 @@ pub(crate) fn mft(data: &mut Value, start: &Option<String>, end: &Option<String>
 #[cfg(test)]
 mod tests {
     use crate::artifacts::windows::{
-        amcache, bits, eventlogs, jumplists, mft, outlook, prefetch, raw_files, recycle_bin,
-        registry, users,
+        activitiescache, amcache, bits, eventlogs, jumplists, mft, outlook, prefetch, raw_files,
+        recycle_bin, registry, users,
     };
     use serde_json::json;
 
@@ -961,6 +1029,34 @@ mod tests {
         assert_eq!(test[0]["message"], "/usr/bin/ls");
     }
 
+    #[test]
+    fn test_activitiescache() {
+        let mut test = json!({
+            "application": "%windir%\\System32\\notepad.exe",
+            "display_text": "notes.txt",
+            "description": "",
+            "clipboard_text": [],
+            "payload": {"displayText": "notes.txt"},
+            "start_time": "2024-01-01T00:00:00.000Z",
+            "end_time": "1970-01-01T00:00:00.000Z",
+            "last_modified": "2024-01-01T00:00:00.000Z",
+            "created": "1970-01-01T00:00:00.000Z",
+        });
+
+        assert!(activitiescache(&mut test, &None, &None));
+        assert_eq!(test.as_array().unwrap().len(), 1);
+        assert_eq!(
+            test[0]["timestamp_desc"],
+            "Activity Start Activity Last Modified"
+        );
+        assert_eq!(test[0]["artifact"], "ActivitiesCache");
+        assert_eq!(
+            test[0]["message"],
+            "%windir%\\System32\\notepad.exe - notes.txt"
+        );
+        assert!(test[0].get("payload").is_none());
+    }
+
     #[test]
     fn test_outlook() {
         let mut test = json!({
@@ -1,15 +1,16 @@
 use crate::artifacts::{
+    browsers::browsers,
     files::files,
-    linux::{ext4_filelisting, journal, logons, sudo_linux},
+    linux::{ext4_filelisting, journal, logons, sudo_linux, user_activity},
     macos::{
         emond, fsevents, groups_macos, launchd, loginitems, spotlight, sudo_macos, unifiedlogs,
         users_macos,
     },
     processes::{network, processes},
     windows::{
-        amcache, bits, eventlogs, jumplists, mft, outlook, prefetch, raw_files, recycle_bin,
-        registry, search, services, shellbags, shimcache, shimdb, shortcuts, srum, tasks,
-        userassist, users, usnjrnl, wmi,
+        activitiescache, amcache, bits, eventlogs, jumplists, mft, outlook, prefetch, raw_files,
+        recycle_bin, registry, search, services, shellbags, shimcache, shimdb, shortcuts, srum,
+        tasks, userassist, users, usnjrnl, wmi,
     },
 };
 use serde_json::Value;
@@ -24,6 +25,7 @@ pub fn timeline_artifact(
     end: &Option<String>,
 ) -> bool {
     match artifact.to_ascii_lowercase().as_str() {
+        "activitiescache" => activitiescache(data, start, end),
         "amcache" => amcache(data, start, end),
         "bits" => bits(data, start, end),
         "files" => files(data, start, end),
@@ -59,8 +61,11 @@ pub fn timeline_artifact(
         "spotlight" => spotlight(data, start, end),
         "logons" => logons(data, start, end),
         "sudologs-linux" => sudo_linux(data, start, end),
+        "useractivity" => user_activity(data, start, end),
         "users-windows" => users(data, start, end),
         "connections" => network(data),
+        "chromium" => browsers(data, "Chromium", start, end),
+        "firefox" => browsers(data, "Firefox", start, end),
         "ext4files" => ext4_filelisting(data, start, end),
         _ => {
             warn!("Got unknown artifact: {artifact}");